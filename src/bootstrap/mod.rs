@@ -14,9 +14,15 @@
 //!   blockchain
 //! - `create_trigger_handler`: Creates a trigger handler function that processes trigger events
 //!   from the block processing pipeline
+//!
+//! # Settings
+//! - `RuntimeSettings`: Runtime options resolved from the command line and the environment
+
+mod settings;
 
+use chrono::Utc;
 use futures::future::BoxFuture;
-use std::{collections::HashMap, error::Error, sync::Arc};
+use std::{collections::HashMap, error::Error, path::Path, sync::Arc, time::Instant};
 use tokio::sync::{watch, Mutex};
 
 use crate::{
@@ -29,17 +35,31 @@ use crate::{
 		TriggerRepositoryTrait, TriggerService,
 	},
 	services::{
-		blockchain::{BlockChainClient, BlockFilterFactory, ClientPoolTrait},
-		filter::{evm_helpers, handle_match, stellar_helpers, FilterService},
+		blockchain::{client_key, BlockChainClient, BlockFilterFactory, ClientPoolTrait},
+		blockwatcher::{spawn_network_task, BlockFailures},
+		filter::{
+			applicable_monitors, evaluate_match_expression, evm_helpers, handle_batch,
+			handle_match, match_block, rpc_groups, stellar_helpers, FilterContext, FilterService,
+			WindowStore,
+		},
 		notification::NotificationService,
 		trigger::{
+			group_block_matches, is_batched, is_observed, record_block_matches, record_observation,
+			JournalEntry, LatencyTracker, LatencyTriggerExecution, MatchPriority, MatchSampler,
+			MonitorStatsTriggerExecution, NotificationOrdering, NotificationSequencer,
+			PendingNotifications, PriorityDispatchQueue, SampleDecision, SampledTriggerExecution,
 			ScriptError, ScriptExecutorFactory, TriggerError, TriggerExecutionService,
-			TriggerExecutionServiceTrait,
+			TriggerExecutionServiceTrait, MATCH_JOURNAL,
 		},
 	},
-	utils::normalize_string,
+	utils::{
+		control::CONTROL, crash::network_scope, metrics::stats,
+		monitor::activation::check_deployments_at, normalize_string, RetryBudgets,
+	},
 };
 
+pub use settings::RuntimeSettings;
+
 /// Type alias for handling ServiceResult
 pub type Result<T> = std::result::Result<T, Box<dyn Error>>;
 
@@ -55,6 +75,14 @@ type ServiceResult<M, N, T> = Result<(
 
 /// Initializes all required services for the blockchain monitor.
 ///
+/// # Arguments
+/// * `config_dir` - Root of the `monitors`, `networks` and `triggers` configuration
+///   directories, from which the services not provided are loaded
+/// * `monitor_service` - Monitor service to use instead of loading one
+/// * `network_service` - Network service to use instead of loading one
+/// * `trigger_service` - Trigger service to use instead of loading one
+/// * `retry_budgets` - Retry budgets shared by the notification clients
+///
 /// # Returns
 /// Returns a tuple containing:
 /// - FilterService: Handles filtering of blockchain data
@@ -67,9 +95,11 @@ type ServiceResult<M, N, T> = Result<(
 /// # Errors
 /// Returns an error if any service initialization fails
 pub async fn initialize_services<M, N, T>(
+	config_dir: &Path,
 	monitor_service: Option<MonitorService<M, N, T>>,
 	network_service: Option<NetworkService<N>>,
 	trigger_service: Option<TriggerService<T>>,
+	retry_budgets: RetryBudgets,
 ) -> ServiceResult<M, N, T>
 where
 	M: MonitorRepositoryTrait<N, T> + Send + Sync + 'static,
@@ -79,7 +109,7 @@ where
 	let network_service = match network_service {
 		Some(service) => service,
 		None => {
-			let repository = N::new(None).await?;
+			let repository = N::new(Some(&config_dir.join("networks"))).await?;
 			NetworkService::<N>::new_with_repository(repository)?
 		}
 	};
@@ -87,7 +117,7 @@ where
 	let trigger_service = match trigger_service {
 		Some(service) => service,
 		None => {
-			let repository = T::new(None).await?;
+			let repository = T::new(Some(&config_dir.join("triggers"))).await?;
 			TriggerService::<T>::new_with_repository(repository)?
 		}
 	};
@@ -96,7 +126,7 @@ where
 		Some(service) => service,
 		None => {
 			let repository = M::new(
-				None,
+				Some(&config_dir.join("monitors")),
 				Some(network_service.clone()),
 				Some(trigger_service.clone()),
 			)
@@ -105,7 +135,7 @@ where
 		}
 	};
 
-	let notification_service = NotificationService::new();
+	let notification_service = NotificationService::with_retry_budgets(retry_budgets);
 
 	let filter_service = Arc::new(FilterService::new());
 	let trigger_execution_service = Arc::new(TriggerExecutionService::new(
@@ -135,6 +165,10 @@ where
 /// * `filter_service` - Service for filtering blockchain data
 /// * `active_monitors` - List of active monitors
 /// * `client_pools` - Client pools for accessing blockchain clients
+/// * `contract_specs` - Contract specs of the monitors
+/// * `failures` - Failures of the blocks, in which the blocks whose filtering failed are
+///   recorded
+/// * `latency` - Stage times of the blocks, in which the detection of matches is recorded
 ///
 /// # Returns
 /// Returns a function that handles incoming blocks
@@ -144,19 +178,38 @@ pub fn create_block_handler<P: ClientPoolTrait + 'static>(
 	active_monitors: Vec<Monitor>,
 	client_pools: Arc<P>,
 	contract_specs: Vec<(String, ContractSpec)>,
+	failures: Arc<BlockFailures>,
+	latency: Arc<LatencyTracker>,
 ) -> Arc<impl Fn(BlockType, Network) -> BoxFuture<'static, ProcessedBlock> + Send + Sync> {
 	Arc::new(
 		move |block: BlockType, network: Network| -> BoxFuture<'static, ProcessedBlock> {
 			let filter_service = filter_service.clone();
+			let failures = failures.clone();
+			let latency = latency.clone();
 			let active_monitors = active_monitors.clone();
 			let client_pools = client_pools.clone();
 			let shutdown_tx = shutdown_tx.clone();
 			let contract_specs = contract_specs.clone();
 			Box::pin(async move {
-				let applicable_monitors = filter_network_monitors(&active_monitors, &network.slug);
+				let block_number = block.number().unwrap_or(0);
+				// Contracts deployed since the latest check activate their monitors from the
+				// block following it, including this one
+				check_deployments_at(
+					client_pools.as_ref(),
+					&network,
+					&active_monitors,
+					block_number,
+				)
+				.await;
+				let applicable_monitors =
+					applicable_monitors(&active_monitors, &network.slug, block_number);
+				// A block filtered again does not see the monitor states it staged before
+				filter_service
+					.context()
+					.discard_block(&network.slug, block_number);
 
 				let mut processed_block = ProcessedBlock {
-					block_number: block.number().unwrap_or(0),
+					block_number,
 					network_slug: network.slug.clone(),
 					processing_results: Vec::new(),
 				};
@@ -164,79 +217,69 @@ pub fn create_block_handler<P: ClientPoolTrait + 'static>(
 				if !applicable_monitors.is_empty() {
 					let mut shutdown_rx = shutdown_tx.subscribe();
 
-					let matches = match network.network_type {
-						BlockChainType::EVM => match client_pools.get_evm_client(&network).await {
-							Ok(client) => {
-								process_block(
-									client.as_ref(),
+					// Monitors pinning their own RPC endpoints are evaluated with the client of
+					// their endpoints, which also fetches the logs and receipts they need
+					let mut processing_results = Vec::new();
+					for (rpc_network, monitors) in rpc_groups(&network, &applicable_monitors) {
+						let mut matches = filter_block_with_client(
+							client_pools.as_ref(),
+							&rpc_network,
+							&block,
+							&monitors,
+							&contract_specs,
+							&filter_service,
+							&failures,
+							&mut shutdown_rx,
+						)
+						.await;
+						if let Err(e) = &matches {
+							if client_key(&rpc_network) != client_key(&network) {
+								// The matches of the group are not dropped with the block
+								tracing::error!(
+									network = %network.slug,
+									error = %e,
+									"Failed to get the client of the RPC overrides of {} monitors, \
+									 filtering them with the endpoints of the network",
+									monitors.len()
+								);
+								matches = filter_block_with_client(
+									client_pools.as_ref(),
 									&network,
 									&block,
-									&applicable_monitors,
-									Some(&contract_specs),
+									&monitors,
+									&contract_specs,
 									&filter_service,
+									&failures,
 									&mut shutdown_rx,
 								)
-								.await
-							}
-							Err(_) => None,
-						},
-						BlockChainType::Stellar => {
-							match client_pools.get_stellar_client(&network).await {
-								Ok(client) => {
-									process_block(
-										client.as_ref(),
-										&network,
-										&block,
-										&applicable_monitors,
-										Some(&contract_specs),
-										&filter_service,
-										&mut shutdown_rx,
-									)
-									.await
-								}
-								Err(_) => None,
-							}
-						}
-						BlockChainType::Midnight => {
-							match client_pools.get_midnight_client(&network).await {
-								Ok(client) => {
-									process_block(
-										client.as_ref(),
-										&network,
-										&block,
-										&applicable_monitors,
-										Some(&contract_specs),
-										&filter_service,
-										&mut shutdown_rx,
-									)
-									.await
-								}
-								Err(_) => None,
+								.await;
 							}
 						}
-						BlockChainType::Solana => {
-							match client_pools.get_solana_client(&network).await {
-								Ok(client) => {
-									process_block(
-										client.as_ref(),
-										&network,
-										&block,
-										&applicable_monitors,
-										Some(&contract_specs),
-										&filter_service,
-										&mut shutdown_rx,
-									)
-									.await
-								}
-								Err(e) => {
-									tracing::error!(error = %e, "Failed to get Solana client");
-									None
-								}
+						match matches {
+							Ok(matches) => processing_results.extend(matches.unwrap_or_default()),
+							Err(e) => {
+								tracing::error!(
+									network = %network.slug,
+									error = %e,
+									"Failed to get the client of the network"
+								);
+								failures.record_filter_failure(
+									&network.slug,
+									block_number,
+									e.to_string(),
+								);
 							}
 						}
-					};
+					}
 
-					processed_block.processing_results = matches.unwrap_or_default();
+					if !processing_results.is_empty() {
+						latency.record_detected(
+							&network.slug,
+							block_number,
+							Utc::now().timestamp_millis(),
+						);
+					}
+					processed_block.processing_results = processing_results;
 				}
 
 				processed_block
@@ -245,15 +288,106 @@ pub fn create_block_handler<P: ClientPoolTrait + 'static>(
 	)
 }
 
+/// Filters a block for a group of monitors with the client of a network.
+///
+/// # Arguments
+/// * `client_pools` - Client pools for accessing blockchain clients
+/// * `network` - The network, with the endpoints of the group of monitors
+/// * `block` - The block to process
+/// * `monitors` - The group of monitors
+/// * `contract_specs` - Contract specs of the monitors
+/// * `filter_service` - Service for filtering blockchain data
+/// * `failures` - Failures of the blocks, in which a failed filtering is recorded
+/// * `shutdown_rx` - Receiver for shutdown signals
+///
+/// # Returns
+/// The matches, `None` if the filtering failed or was interrupted, or the error of the creation
+/// of the client
+#[allow(clippy::too_many_arguments)]
+async fn filter_block_with_client<P: ClientPoolTrait>(
+	client_pools: &P,
+	network: &Network,
+	block: &BlockType,
+	monitors: &[Monitor],
+	contract_specs: &[(String, ContractSpec)],
+	filter_service: &FilterService,
+	failures: &BlockFailures,
+	shutdown_rx: &mut watch::Receiver<bool>,
+) -> std::result::Result<Option<Vec<MonitorMatch>>, anyhow::Error> {
+	let contract_specs = Some(contract_specs);
+	Ok(match network.network_type {
+		BlockChainType::EVM => {
+			let client = client_pools.get_evm_client(network).await?;
+			process_block(
+				client.as_ref(),
+				network,
+				block,
+				monitors,
+				contract_specs,
+				filter_service,
+				failures,
+				shutdown_rx,
+			)
+			.await
+		}
+		BlockChainType::Stellar => {
+			let client = client_pools.get_stellar_client(network).await?;
+			process_block(
+				client.as_ref(),
+				network,
+				block,
+				monitors,
+				contract_specs,
+				filter_service,
+				failures,
+				shutdown_rx,
+			)
+			.await
+		}
+		BlockChainType::Midnight => {
+			let client = client_pools.get_midnight_client(network).await?;
+			process_block(
+				client.as_ref(),
+				network,
+				block,
+				monitors,
+				contract_specs,
+				filter_service,
+				failures,
+				shutdown_rx,
+			)
+			.await
+		}
+		BlockChainType::Solana => {
+			let client = client_pools.get_solana_client(network).await?;
+			process_block(
+				client.as_ref(),
+				network,
+				block,
+				monitors,
+				contract_specs,
+				filter_service,
+				failures,
+				shutdown_rx,
+			)
+			.await
+		}
+	})
+}
+
 /// Processes a single block for all applicable monitors.
 ///
+/// The block is filtered with [`match_block`], which processes oversized blocks in chunks.
+///
 /// # Arguments
 /// * `client` - The client to use to process the block
 /// * `network` - The network the block belongs to
 /// * `block` - The block to process
 /// * `applicable_monitors` - List of monitors that apply to this network
 /// * `filter_service` - Service for filtering blockchain data
+/// * `failures` - Failures of the blocks, in which a failed filtering is recorded
 /// * `shutdown_rx` - Receiver for shutdown signals
+#[allow(clippy::too_many_arguments)]
 pub async fn process_block<T>(
 	client: &T,
 	network: &Network,
@@ -261,14 +395,30 @@ pub async fn process_block<T>(
 	applicable_monitors: &[Monitor],
 	contract_specs: Option<&[(String, ContractSpec)]>,
 	filter_service: &FilterService,
+	failures: &BlockFailures,
 	shutdown_rx: &mut watch::Receiver<bool>,
 ) -> Option<Vec<MonitorMatch>>
 where
 	T: BlockChainClient + BlockFilterFactory<T>,
 {
 	tokio::select! {
-		result = filter_service.filter_block(client, network, block, applicable_monitors, contract_specs) => {
-			result.ok()
+		result = match_block(client, network, block, applicable_monitors, contract_specs, filter_service) => {
+			match result {
+				Ok(matches) => Some(matches),
+				Err(e) => {
+					// The block must not be checkpointed without its matches
+					let block_number = block.number().unwrap_or(0);
+					tracing::error!(
+						network = %network.slug,
+						block = block_number,
+						error = %e,
+						"Failed to filter block {}",
+						block_number
+					);
+					failures.record_filter_failure(&network.slug, block_number, e.to_string());
+					None
+				}
+			}
 		}
 		_ = shutdown_rx.changed() => {
 			tracing::info!("Shutting down block processing task");
@@ -400,32 +550,119 @@ pub async fn get_contract_specs<P: ClientPoolTrait + 'static>(
 /// Creates a trigger handler function that processes trigger events from the block processing
 /// pipeline.
 ///
+/// Notifications are dispatched in block order per network or per monitor when configured
+/// with `ordering`. Every match is appended to the match journal, and the matches of monitors
+/// in observe mode (or of all monitors with `observe_all`) are not notified.
+/// Monitors with a `sampling` section only notify their sampled matches, and monitors with a
+/// `batch` section notify all of their matches in a block at once. The matches of every
+/// monitor are counted in the window store, and notifications carry the counts of their
+/// monitor (`monitor.stats.*`) and the latency of their block (`latency.*`). The matches
+/// waiting for their turn or a dispatch slot are tracked in `pending_notifications`, flushed
+/// by the panic hook. As blocks are handed over in order, the monitor states staged by the
+/// filtering of a block are committed to `filter_context`.
+///
 /// # Arguments
 /// * `shutdown_tx` - Watch channel for shutdown signals
 /// * `trigger_service` - Service for executing triggers
+/// * `active_monitors_trigger_scripts` - Trigger scripts of the active monitors
+/// * `ordering` - Ordering of the notifications
+/// * `observe_all` - Whether all monitors are in observe mode
+/// * `latency` - Stage times of the blocks, exposed to the notifications of their matches
+/// * `dispatch_queue` - Queue bounding the concurrent notification dispatches
+/// * `window_store` - Window store counting the matches of the monitors
+/// * `pending_notifications` - Matches waiting to be dispatched
+/// * `filter_context` - State of the filters, holding the monitor states staged by the blocks
 ///
 /// # Returns
 /// Returns a function that handles trigger execution for matching monitors
+#[allow(clippy::too_many_arguments)]
 pub fn create_trigger_handler<S: TriggerExecutionServiceTrait + Send + Sync + 'static>(
 	shutdown_tx: watch::Sender<bool>,
 	trigger_service: Arc<S>,
 	active_monitors_trigger_scripts: HashMap<String, (ScriptLanguage, String)>,
+	ordering: NotificationOrdering,
+	observe_all: bool,
+	latency: Arc<LatencyTracker>,
+	dispatch_queue: Arc<PriorityDispatchQueue>,
+	window_store: Arc<dyn WindowStore>,
+	pending_notifications: Arc<PendingNotifications>,
+	filter_context: FilterContext,
 ) -> Arc<impl Fn(&ProcessedBlock) -> tokio::task::JoinHandle<()> + Send + Sync> {
+	let sequencer = NotificationSequencer::new(ordering);
+	let sampler = Arc::new(MatchSampler::default());
 	Arc::new(move |block: &ProcessedBlock| {
 		let mut shutdown_rx = shutdown_tx.subscribe();
 		let trigger_service = trigger_service.clone();
+		let sampler = sampler.clone();
+		let latency = latency.clone();
+		let dispatch_queue = dispatch_queue.clone();
+		let window_store = window_store.clone();
 		let trigger_scripts = active_monitors_trigger_scripts.clone();
+		// Blocks are handed over in order, so turns are reserved and monitor states committed
+		// before spawning
+		filter_context.commit_block(&block.network_slug, block.block_number);
+		let mut dispatch_turns = sequencer.reserve(block);
+		let handed_over = pending_notifications.hand_over(&block.network_slug, block.block_number);
+		let pending_notifications = pending_notifications.clone();
 		let block = block.clone();
+		let in_flight = CONTROL.dispatch_started();
+		let detected_at = Instant::now();
+		let network_slug = block.network_slug.clone();
 
-		tokio::spawn(async move {
+		let dispatch = async move {
+			let _in_flight = in_flight;
 			tokio::select! {
 				_ = async {
 					if block.processing_results.is_empty() {
 						return;
 					}
-					let filtered_matches = run_trigger_filters(&block.processing_results, &block.network_slug, &trigger_scripts).await;
+					let mut filtered_matches = run_trigger_filters(&block.processing_results, &block.network_slug, &trigger_scripts).await;
+					// The sort is stable, so the matches of a monitor keep their order
+					filtered_matches.sort_by_key(|monitor_match| std::cmp::Reverse(monitor_match.monitor().severity));
 					for monitor_match in &filtered_matches {
-						if let Err(e) = handle_match(monitor_match.clone(), &*trigger_service, &trigger_scripts).await {
+						stats::record_match(&monitor_match.monitor().name, &block.network_slug);
+					}
+					let journal_entries: Vec<_> = filtered_matches
+						.iter()
+						.map(|monitor_match| JournalEntry::new(monitor_match, &block.network_slug, is_observed(monitor_match, observe_all)))
+						.collect();
+					MATCH_JOURNAL.append(&journal_entries).await;
+					// Notifications carry the statistics of their monitor, including the matches of the block
+					let monitor_stats = record_block_matches(&*window_store, &filtered_matches).await;
+					let stats_service = MonitorStatsTriggerExecution::new(&*trigger_service, monitor_stats);
+					let mut notified_matches = Vec::new();
+					for monitor_match in filtered_matches {
+						if is_observed(&monitor_match, observe_all) {
+							record_observation(&monitor_match, &block.network_slug);
+						} else {
+							notified_matches.push(monitor_match);
+						}
+					}
+					// Batched monitors are never sampled, so each group is sampled on its first match
+					let groups = group_block_matches(notified_matches);
+					let decisions = sampler.sample(&block.network_slug, groups.iter().map(|group| &group[0]));
+					let queued: Vec<_> = groups
+						.iter()
+						.zip(decisions)
+						.filter(|(_, decision)| *decision != SampleDecision::Skipped)
+						.map(|(group, decision)| (group, decision, pending_notifications.queue(&block.network_slug, group)))
+						.collect();
+					// The matches of the block are flushed on a panic from now on
+					drop(handed_over);
+					for (group, decision, _queued) in queued {
+						let monitor_match = &group[0];
+						dispatch_turns.wait(monitor_match).await;
+						let _permit = dispatch_queue.acquire(MatchPriority::of(monitor_match, detected_at)).await;
+						let latency_service = LatencyTriggerExecution::new(&stats_service, &latency, &block.network_slug, block.block_number);
+						let result = match decision {
+							SampleDecision::Sampled(counts) => {
+								let sampled_service = SampledTriggerExecution::new(&latency_service, counts);
+								handle_match(monitor_match.clone(), &sampled_service, &trigger_scripts).await
+							}
+							_ if is_batched(monitor_match) => handle_batch(group, &latency_service, &trigger_scripts).await,
+							_ => handle_match(monitor_match.clone(), &latency_service, &trigger_scripts).await,
+						};
+						if let Err(e) = result {
 							TriggerError::execution_error(e.to_string(), Some(e.into()), None);
 						}
 					}
@@ -434,7 +671,12 @@ pub fn create_trigger_handler<S: TriggerExecutionServiceTrait + Send + Sync + 's
 					tracing::info!("Shutting down trigger handling task");
 				}
 			}
-		})
+		};
+		spawn_network_task(
+			&network_slug,
+			"trigger",
+			network_scope(&network_slug, dispatch),
+		)
 	})
 }
 
@@ -452,34 +694,38 @@ pub fn has_active_monitors(monitors: &[Monitor], network_slug: &String) -> bool
 		.any(|m| m.networks.contains(network_slug) && !m.paused)
 }
 
-/// Filters out paused monitors from the provided collection.
+/// Returns the block a network starts from when no block has been processed yet.
+///
+/// The earliest of the network's `start_block` and the `start_block` of its active
+/// monitors is used.
 ///
 /// # Arguments
-/// * `monitors` - HashMap of monitors to filter
+/// * `network` - The network to resolve the start block for
+/// * `monitors` - List of monitors to check
 ///
 /// # Returns
-/// Returns a vector containing only active (non-paused) monitors
-fn filter_active_monitors(monitors: HashMap<String, Monitor>) -> Vec<Monitor> {
+/// Returns the start block, or `None` to start from the latest confirmed block
+pub fn resolve_start_block(network: &Network, monitors: &[Monitor]) -> Option<u64> {
 	monitors
-		.into_values()
-		.filter(|m| !m.paused)
-		.collect::<Vec<_>>()
+		.iter()
+		.filter(|m| m.networks.contains(&network.slug) && !m.paused)
+		.filter_map(|m| m.start_block)
+		.chain(network.start_block)
+		.min()
 }
 
-/// Filters monitors that are applicable to a specific network.
+/// Filters out paused monitors from the provided collection.
 ///
 /// # Arguments
-/// * `monitors` - List of monitors to filter
-/// * `network_slug` - Network identifier to filter by
+/// * `monitors` - HashMap of monitors to filter
 ///
 /// # Returns
-/// Returns a vector of monitors that are configured for the specified network
-fn filter_network_monitors(monitors: &[Monitor], network_slug: &String) -> Vec<Monitor> {
+/// Returns a vector containing only active (non-paused) monitors
+fn filter_active_monitors(monitors: HashMap<String, Monitor>) -> Vec<Monitor> {
 	monitors
-		.iter()
-		.filter(|m| m.networks.contains(network_slug))
-		.cloned()
-		.collect()
+		.into_values()
+		.filter(|m| !m.paused)
+		.collect::<Vec<_>>()
 }
 
 async fn execute_trigger_condition(
@@ -508,7 +754,16 @@ async fn execute_trigger_condition(
 	}
 }
 
-async fn run_trigger_filters(
+/// Evaluates the trigger conditions of matches
+///
+/// # Arguments
+/// * `matches` - The matches to evaluate
+/// * `_network` - Slug of the network of the matches
+/// * `trigger_scripts` - The trigger condition scripts of the monitors
+///
+/// # Returns
+/// The matches that are not filtered out by a trigger condition
+pub async fn run_trigger_filters(
 	matches: &[MonitorMatch],
 	_network: &str,
 	trigger_scripts: &HashMap<String, (ScriptLanguage, String)>,
@@ -525,6 +780,21 @@ async fn run_trigger_filters(
 		};
 
 		for trigger_condition in trigger_conditions {
+			// Inline expressions are evaluated in-process, no script is spawned
+			if let Some(expression) = &trigger_condition.expression {
+				match evaluate_match_expression(expression, monitor_match) {
+					Ok(true) => {
+						is_filtered = true;
+						break;
+					}
+					Ok(false) => {}
+					Err(e) => {
+						tracing::error!("Failed to evaluate trigger condition expression: {}", e);
+					}
+				}
+				continue;
+			}
+
 			let monitor_name = match monitor_match {
 				MonitorMatch::EVM(evm_match) => evm_match.monitor.name.clone(),
 				MonitorMatch::Stellar(stellar_match) => stellar_match.monitor.name.clone(),
@@ -567,7 +837,11 @@ mod tests {
 			SolanaTransaction, SolanaTransactionInfo, StellarBlock, StellarMonitorMatch,
 			StellarTransaction, StellarTransactionInfo, TriggerConditions,
 		},
-		utils::tests::{builders::evm::monitor::MonitorBuilder, evm::receipt::ReceiptBuilder},
+		services::filter::filter_network_monitors,
+		utils::tests::{
+			builders::{evm::monitor::MonitorBuilder, network::NetworkBuilder},
+			evm::receipt::ReceiptBuilder,
+		},
 	};
 	use alloy::{
 		consensus::{transaction::Recovered, Signed, TxEnvelope},
@@ -679,7 +953,10 @@ mod tests {
 					events: vec![],
 					transactions: vec![],
 				},
+				matched_on_audit: Vec::new(),
 				matched_on_args: None,
+				block_timestamp: None,
+				trace: None,
 			})),
 			BlockChainType::Stellar => MonitorMatch::Stellar(Box::new(StellarMonitorMatch {
 				monitor: create_test_monitor("test", vec![], false, script_path),
@@ -692,6 +969,7 @@ mod tests {
 					transactions: vec![],
 				},
 				matched_on_args: None,
+				missing_data: Vec::new(),
 			})),
 			BlockChainType::Solana => MonitorMatch::Solana(Box::new(SolanaMonitorMatch {
 				monitor: create_test_monitor("test", vec![], false, script_path),
@@ -725,7 +1003,10 @@ mod tests {
 					events: vec![],
 					transactions: vec![],
 				},
+				matched_on_audit: Vec::new(),
 				matched_on_args: None,
+				block_timestamp: None,
+				trace: None,
 			})),
 			BlockChainType::Stellar => MonitorMatch::Stellar(Box::new(StellarMonitorMatch {
 				monitor,
@@ -738,6 +1019,7 @@ mod tests {
 					transactions: vec![],
 				},
 				matched_on_args: None,
+				missing_data: Vec::new(),
 			})),
 			BlockChainType::Solana => MonitorMatch::Solana(Box::new(SolanaMonitorMatch {
 				monitor,
@@ -798,6 +1080,34 @@ mod tests {
 		));
 	}
 
+	#[test]
+	fn test_resolve_start_block() {
+		let network = NetworkBuilder::new().slug("ethereum_mainnet").build();
+		let monitor = |start_block: Option<u64>, paused: bool| Monitor {
+			start_block,
+			..create_test_monitor("monitor", vec!["ethereum_mainnet"], paused, None)
+		};
+
+		assert_eq!(resolve_start_block(&network, &[monitor(None, false)]), None);
+		assert_eq!(
+			resolve_start_block(
+				&network,
+				&[monitor(Some(500), false), monitor(Some(100), true)]
+			),
+			Some(500)
+		);
+
+		// The earliest of the network and monitor start blocks is used
+		let network = NetworkBuilder::new()
+			.slug("ethereum_mainnet")
+			.start_block(300)
+			.build();
+		assert_eq!(
+			resolve_start_block(&network, &[monitor(Some(500), false)]),
+			Some(300)
+		);
+	}
+
 	#[test]
 	fn test_filter_active_monitors() {
 		let mut monitors = HashMap::new();
@@ -848,6 +1158,38 @@ mod tests {
 		assert!(midnight_monitors.is_empty());
 	}
 
+	#[test]
+	fn test_filter_network_monitors_resolves_network_addresses() {
+		let monitor = MonitorBuilder::new()
+			.name("USDC")
+			.networks(vec![
+				"ethereum_mainnet".to_string(),
+				"base_mainnet".to_string(),
+			])
+			.address("0x0000000000000000000000000000000000000001")
+			.network_address(
+				"ethereum_mainnet",
+				"0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48",
+			)
+			.network_address("base_mainnet", "0x833589fcd6edb6e08f4c7c32d4f71b54bda02913")
+			.build();
+
+		let base_monitors = filter_network_monitors(&[monitor], &"base_mainnet".to_string());
+		let addresses: Vec<_> = base_monitors[0]
+			.addresses
+			.iter()
+			.map(|a| a.address.as_str())
+			.collect();
+		assert_eq!(
+			addresses,
+			vec![
+				"0x0000000000000000000000000000000000000001",
+				"0x833589fcd6edb6e08f4c7c32d4f71b54bda02913",
+			]
+		);
+		assert!(base_monitors[0].network_addresses.is_empty());
+	}
+
 	#[tokio::test]
 	async fn test_run_trigger_filters_empty_matches() {
 		// Create empty matches vector
@@ -937,6 +1279,39 @@ print(result)
 		assert_eq!(filtered.len(), 1);
 	}
 
+	#[tokio::test]
+	async fn test_run_trigger_filters_expression_condition() {
+		let with_expression = |expression: &str| {
+			let mut match_item = create_mock_monitor_match_from_path(BlockChainType::EVM, None);
+			if let MonitorMatch::EVM(evm_match) = &mut match_item {
+				evm_match.monitor.trigger_conditions = vec![TriggerConditions {
+					script_path: String::new(),
+					arguments: None,
+					language: ScriptLanguage::Python,
+					timeout_ms: 0,
+					expression: Some(expression.to_string()),
+				}];
+			}
+			match_item
+		};
+		let trigger_scripts = HashMap::new();
+
+		// A true expression filters the match out
+		let matches = vec![with_expression("network_slug == 'ethereum_mainnet'")];
+		let filtered = run_trigger_filters(&matches, "ethereum_mainnet", &trigger_scripts).await;
+		assert!(filtered.is_empty());
+
+		// A false expression keeps the match
+		let matches = vec![with_expression("transaction.value > 0")];
+		let filtered = run_trigger_filters(&matches, "ethereum_mainnet", &trigger_scripts).await;
+		assert_eq!(filtered.len(), 1);
+
+		// An expression that cannot be evaluated keeps the match
+		let matches = vec![with_expression("unknown_field == 1")];
+		let filtered = run_trigger_filters(&matches, "ethereum_mainnet", &trigger_scripts).await;
+		assert_eq!(filtered.len(), 1);
+	}
+
 	#[tokio::test]
 	async fn test_execute_trigger_condition_returns_false() {
 		let script_content = r#"print(False)  # Script returns false"#;
@@ -946,6 +1321,7 @@ print(result)
 			script_path: temp_file.path().to_str().unwrap().to_string(),
 			timeout_ms: 1000,
 			arguments: None,
+			expression: None,
 		};
 		let match_item = create_mock_monitor_match_from_path(
 			BlockChainType::EVM,
@@ -967,6 +1343,7 @@ print(result)
 			script_path: temp_file.path().to_str().unwrap().to_string(),
 			timeout_ms: 1000,
 			arguments: None,
+			expression: None,
 		};
 		let match_item = create_mock_monitor_match_from_path(
 			BlockChainType::EVM,
@@ -986,6 +1363,7 @@ print(result)
 			script_path: "non_existent_script.py".to_string(),
 			timeout_ms: 1000,
 			arguments: None,
+			expression: None,
 		};
 		let match_item = create_mock_monitor_match_from_path(
 			BlockChainType::EVM,
@@ -0,0 +1,924 @@
+//! Runtime settings of the service.
+//!
+//! Every command line option has an environment variable counterpart, so that container
+//! deployments can configure the service without wrapper scripts. [`RuntimeSettings`] holds
+//! the options given on the command line and [`RuntimeSettings::resolve`] fills the missing
+//! ones from the environment (after loading `.env`). A command line option always overrides
+//! its environment variable.
+//!
+//! | Option | Environment variable |
+//! | --- | --- |
+//! | `--config-dir` | `CONFIG_DIR` |
+//! | `--data-dir` | `DATA_DIR` |
+//! | `--log-file` | `LOG_MODE=file` |
+//! | `--log-level` | `LOG_LEVEL` (or `RUST_LOG`) |
+//! | `--log-path` | `LOG_DATA_DIR` |
+//! | `--log-max-size` | `LOG_MAX_SIZE` |
+//! | `--metrics-address` | `METRICS_ADDRESS` (or `METRICS_PORT`) |
+//! | `--metrics` | `METRICS_ENABLED` |
+//! | `--notification-ordering` | `NOTIFICATION_ORDERING` |
+//! | `--observe` | `OBSERVE_MODE` |
+//! | `--match-journal` | `MATCH_JOURNAL_PATH` |
+//! | `--match-journal-max-size` | `MATCH_JOURNAL_MAX_SIZE` |
+//! | `--match-journal-max-age` | `MATCH_JOURNAL_MAX_AGE` |
+//! | `--panic-mode` | `PANIC_MODE` |
+//! | `--rpc-usage-interval` | `RPC_USAGE_SUMMARY_INTERVAL` |
+//! | `--control-socket` | `CONTROL_SOCKET_PATH` |
+//! | `--monitor-path` | `MONITOR_PATH` |
+//! | `--network` | `MONITOR_NETWORK` |
+//! | `--block` | `MONITOR_BLOCK` |
+//! | `--expect` | `EXPECT_PATH` |
+//! | `--check` | `CHECK_CONFIG` |
+//! | `--check-templates` | `CHECK_TEMPLATES` |
+//! | `--retry-skipped-blocks` | `RETRY_SKIPPED_BLOCKS` |
+//! | `--replay-matches` | `REPLAY_MATCHES` |
+//! | `--replay-since` | `REPLAY_SINCE` |
+//! | `--replay-until` | `REPLAY_UNTIL` |
+//! | `--replay-trigger` | `REPLAY_TRIGGER` |
+//! | `--export-state` | `EXPORT_STATE_PATH` |
+//! | `--import-state` | `IMPORT_STATE_PATH` |
+//! | `--import-alerts` | `IMPORT_ALERTS_PATH` |
+//! | `--import-format` | `IMPORT_ALERTS_FORMAT` |
+//!
+//! The settings of the internals of the service (caches, stores, retry budgets, third-party
+//! APIs) have no command line option and are only read from the environment.
+
+use clap::Args;
+use dotenvy::dotenv_override;
+use std::{env, path::PathBuf, str::FromStr, sync::Arc, time::Duration};
+
+use crate::{
+	bootstrap::Result,
+	models::{SecretString, DEFAULT_CONFIG_DIR},
+	services::{
+		blockchain::{
+			ContractCallCache, RpcCache, DEFAULT_CALL_CACHE_MAX_ENTRIES, DEFAULT_CALL_CACHE_TTL,
+			DEFAULT_RPC_CACHE_DIR,
+		},
+		blockwatcher::DEFAULT_DATA_DIR,
+		filter::{
+			state::{create_window_store, DEFAULT_WINDOW_STORE_SNAPSHOT_PATH},
+			WindowStore,
+		},
+		trigger::{
+			JournalRotation, NotificationOrdering, PriorityDispatchQueue, DEFAULT_JOURNAL_MAX_AGE,
+			DEFAULT_JOURNAL_MAX_SIZE, DEFAULT_MAX_CONCURRENCY,
+		},
+	},
+	utils::{
+		control::DEFAULT_STATE_DUMP_DIR,
+		crash::PanicMode,
+		logging::LoggingConfig,
+		metrics::usage,
+		monitor::abi_drift::{EtherscanConfig, DEFAULT_ETHERSCAN_API_URL},
+		parse_string_to_bytes_size, RetryBudgets,
+	},
+};
+
+/// Default port of the metrics server
+const DEFAULT_METRICS_PORT: u16 = 8081;
+
+/// Runtime options of the service, given on the command line or in the environment
+#[derive(Args, Debug, Clone, Default, PartialEq)]
+pub struct RuntimeSettings {
+	/// Root directory of the monitors, networks and triggers configuration (default: config/)
+	/// [env: CONFIG_DIR]
+	#[arg(long, value_name = "PATH")]
+	pub config_dir: Option<String>,
+
+	/// Directory of the state of the service: processed blocks, statistics, caches and dumps
+	/// (default: data/) [env: DATA_DIR]
+	#[arg(long, value_name = "PATH")]
+	pub data_dir: Option<String>,
+
+	/// Write logs to file instead of stdout [env: LOG_MODE=file]
+	#[arg(long)]
+	pub log_file: bool,
+
+	/// Set log level (trace, debug, info, warn, error) [env: LOG_LEVEL]
+	#[arg(long, value_name = "LEVEL")]
+	pub log_level: Option<String>,
+
+	/// Path to store log files (default: logs/) [env: LOG_DATA_DIR]
+	#[arg(long, value_name = "PATH")]
+	pub log_path: Option<String>,
+
+	/// Maximum log file size before rolling (e.g., "1GB", "500MB", "1024KB") [env: LOG_MAX_SIZE]
+	#[arg(long, value_name = "SIZE", value_parser = parse_string_to_bytes_size)]
+	pub log_max_size: Option<u64>,
+
+	/// Address to start the metrics server on (default: 127.0.0.1:8081) [env: METRICS_ADDRESS]
+	#[arg(long, value_name = "HOST:PORT")]
+	pub metrics_address: Option<String>,
+
+	/// Enable metrics server [env: METRICS_ENABLED]
+	#[arg(long)]
+	pub metrics: bool,
+
+	/// Dispatch notifications in block order per network or per monitor (none, network,
+	/// monitor) [env: NOTIFICATION_ORDERING]
+	#[arg(long, value_name = "MODE")]
+	pub notification_ordering: Option<String>,
+
+	/// Record the matches of all monitors in the match journal without sending notifications
+	/// [env: OBSERVE_MODE]
+	#[arg(long)]
+	pub observe: bool,
+
+	/// Append every match to this JSON lines file (disabled by default)
+	/// [env: MATCH_JOURNAL_PATH]
+	#[arg(long, value_name = "PATH")]
+	pub match_journal: Option<String>,
+
+	/// Size of the match journal before it is rotated (default: 100MB)
+	/// [env: MATCH_JOURNAL_MAX_SIZE]
+	#[arg(long, value_name = "SIZE", value_parser = parse_string_to_bytes_size)]
+	pub match_journal_max_size: Option<u64>,
+
+	/// Days before the rotated match journals are deleted (default: 30)
+	/// [env: MATCH_JOURNAL_MAX_AGE]
+	#[arg(long, value_name = "DAYS")]
+	pub match_journal_max_age: Option<u64>,
+
+	/// Handling of panics: flush the state and abort, or fail the run of the network watcher
+	/// that panicked and suspend the network (abort, isolate) [env: PANIC_MODE]
+	#[arg(long, value_name = "MODE")]
+	pub panic_mode: Option<String>,
+
+	/// Seconds between two logged summaries of the RPC usage, 0 to disable (default: 3600)
+	/// [env: RPC_USAGE_SUMMARY_INTERVAL]
+	#[arg(long, value_name = "SECONDS")]
+	pub rpc_usage_interval: Option<u64>,
+
+	/// Path of the Unix domain socket accepting control commands (reload, pause-monitor, ...)
+	/// [env: CONTROL_SOCKET_PATH]
+	#[arg(long, value_name = "PATH")]
+	pub control_socket: Option<String>,
+
+	/// Path to the monitor to execute [env: MONITOR_PATH]
+	#[arg(long, value_name = "MONITOR_PATH")]
+	pub monitor_path: Option<String>,
+
+	/// Network to execute the monitor for [env: MONITOR_NETWORK]
+	#[arg(long, value_name = "NETWORK_SLUG")]
+	pub network: Option<String>,
+
+	/// Block number to execute the monitor for [env: MONITOR_BLOCK]
+	#[arg(long, value_name = "BLOCK_NUMBER")]
+	pub block: Option<u64>,
+
+	/// Verify that blocks match exactly the monitors listed in a JSON file of expected matches
+	/// [env: EXPECT_PATH]
+	#[arg(long, value_name = "PATH")]
+	pub expect: Option<String>,
+
+	/// Validate configuration files without starting the service [env: CHECK_CONFIG]
+	#[arg(long)]
+	pub check: bool,
+
+	/// Render the notification templates of the triggers with fixture matches of each chain
+	/// type and report unknown variables and malformed placeholders [env: CHECK_TEMPLATES]
+	#[arg(long)]
+	pub check_templates: bool,
+
+	/// Re-attempt the skipped blocks of all networks (or of --network) and exit
+	/// [env: RETRY_SKIPPED_BLOCKS]
+	#[arg(long)]
+	pub retry_skipped_blocks: bool,
+
+	/// Send the journaled matches of all networks (or of --network) through their triggers
+	/// again and exit [env: REPLAY_MATCHES]
+	#[arg(long)]
+	pub replay_matches: bool,
+
+	/// Replay the matches journaled from this time (RFC 3339 or YYYY-MM-DD)
+	/// [env: REPLAY_SINCE]
+	#[arg(long, value_name = "TIME")]
+	pub replay_since: Option<String>,
+
+	/// Replay the matches journaled until this time (RFC 3339 or YYYY-MM-DD)
+	/// [env: REPLAY_UNTIL]
+	#[arg(long, value_name = "TIME")]
+	pub replay_until: Option<String>,
+
+	/// Send the replayed matches to this trigger instead of their own triggers
+	/// [env: REPLAY_TRIGGER]
+	#[arg(long, value_name = "TRIGGER")]
+	pub replay_trigger: Option<String>,
+
+	/// Write the processed-block, recovery and aggregation state to an archive and exit
+	/// [env: EXPORT_STATE_PATH]
+	#[arg(long, value_name = "PATH", conflicts_with = "import_state")]
+	pub export_state: Option<String>,
+
+	/// Restore the state of an archive written by --export-state and exit
+	/// [env: IMPORT_STATE_PATH]
+	#[arg(long, value_name = "PATH")]
+	pub import_state: Option<String>,
+
+	/// Convert exported Defender Sentinels or Tenderly alerts into monitors and triggers in the
+	/// configuration directory and exit [env: IMPORT_ALERTS_PATH]
+	#[arg(long, value_name = "PATH")]
+	pub import_alerts: Option<String>,
+
+	/// Format of the imported alerts ("defender" or "tenderly"), detected per alert if unset
+	/// [env: IMPORT_ALERTS_FORMAT]
+	#[arg(long, value_name = "FORMAT")]
+	pub import_format: Option<String>,
+
+	/// Seconds the result of a contract read is reused (`CONTRACT_CALL_CACHE_TTL_SECS`)
+	#[arg(skip)]
+	pub contract_call_cache_ttl: Option<u64>,
+
+	/// Maximum number of cached contract reads, 0 to disable the cache
+	/// (`CONTRACT_CALL_CACHE_MAX_ENTRIES`)
+	#[arg(skip)]
+	pub contract_call_cache_max_entries: Option<usize>,
+
+	/// Whether the service runs in a container (`IN_DOCKER`)
+	#[arg(skip)]
+	pub in_docker: bool,
+
+	/// Port of the metrics server when no address is set (`METRICS_PORT`)
+	#[arg(skip)]
+	pub metrics_port: Option<u16>,
+
+	/// Additional regex whose matches are redacted from the logs (`LOG_REDACT_PATTERN`)
+	#[arg(skip)]
+	pub log_redact_pattern: Option<String>,
+
+	/// JSON file of the API tokens of the metrics server (`API_TOKENS_FILE`)
+	#[arg(skip)]
+	pub api_tokens_file: Option<String>,
+
+	/// Maximum number of notifications dispatched at once, 0 for no limit
+	/// (`NOTIFICATION_MAX_CONCURRENCY`)
+	#[arg(skip)]
+	pub notification_max_concurrency: Option<usize>,
+
+	/// Maximum number of retries per minute of each component, 0 for no limit
+	/// (`RETRY_BUDGET_PER_MINUTE`)
+	#[arg(skip)]
+	pub retry_budget_per_minute: Option<u32>,
+
+	/// Record/replay mode of the RPC response cache: record, replay or off (`RPC_CACHE_MODE`)
+	#[arg(skip)]
+	pub rpc_cache_mode: Option<String>,
+
+	/// Directory of the cached RPC responses (`RPC_CACHE_DIR`)
+	#[arg(skip)]
+	pub rpc_cache_dir: Option<String>,
+
+	/// URL of the Redis server of the window store (`WINDOW_STORE_URL`)
+	#[arg(skip)]
+	pub window_store_url: Option<SecretString>,
+
+	/// Snapshot of the in-memory window store (`WINDOW_STORE_SNAPSHOT_PATH`)
+	#[arg(skip)]
+	pub window_store_snapshot_path: Option<String>,
+
+	/// Directory of the state dumps (`STATE_DUMP_DIR`)
+	#[arg(skip)]
+	pub state_dump_dir: Option<String>,
+
+	/// Key signing and verifying the state archives (`STATE_SIGNING_KEY`)
+	#[arg(skip)]
+	pub state_signing_key: Option<SecretString>,
+
+	/// Key of the Etherscan API, used by the ABI drift checks (`ETHERSCAN_API_KEY`)
+	#[arg(skip)]
+	pub etherscan_api_key: Option<SecretString>,
+
+	/// Endpoint of the Etherscan API (`ETHERSCAN_API_URL`)
+	#[arg(skip)]
+	pub etherscan_api_url: Option<String>,
+}
+
+impl RuntimeSettings {
+	/// Loads the `.env` file and fills the options missing from the command line from the
+	/// environment
+	///
+	/// # Errors
+	/// Returns an error if an environment variable holds an invalid value
+	pub fn resolve(self) -> Result<Self> {
+		// Values of the .env file override the existing environment variables
+		dotenv_override().ok();
+		self.resolve_with(|name| env::var(name).ok().filter(|value| !value.is_empty()))
+	}
+
+	/// Fills the options missing from the command line from a source of variables
+	///
+	/// # Arguments
+	/// * `lookup` - Returns the value of a variable, `None` if unset
+	///
+	/// # Errors
+	/// Returns an error if a variable holds an invalid value
+	pub fn resolve_with<F>(self, lookup: F) -> Result<Self>
+	where
+		F: Fn(&str) -> Option<String>,
+	{
+		let flag = |name: &str| -> Result<bool> {
+			match lookup(name) {
+				Some(value) => parse_flag(name, &value),
+				None => Ok(false),
+			}
+		};
+		let log_max_size = match (self.log_max_size, lookup("LOG_MAX_SIZE")) {
+			(Some(size), _) => Some(size),
+			(None, Some(size)) => Some(
+				parse_string_to_bytes_size(&size).map_err(|e| format!("LOG_MAX_SIZE: {}", e))?,
+			),
+			(None, None) => None,
+		};
+		let match_journal_max_size = match (
+			self.match_journal_max_size,
+			lookup("MATCH_JOURNAL_MAX_SIZE"),
+		) {
+			(Some(size), _) => Some(size),
+			(None, Some(size)) => Some(
+				parse_string_to_bytes_size(&size)
+					.map_err(|e| format!("MATCH_JOURNAL_MAX_SIZE: {}", e))?,
+			),
+			(None, None) => None,
+		};
+		let match_journal_max_age =
+			match (self.match_journal_max_age, lookup("MATCH_JOURNAL_MAX_AGE")) {
+				(Some(days), _) => Some(days),
+				(None, Some(days)) => Some(days.parse().map_err(|_| {
+					format!(
+						"MATCH_JOURNAL_MAX_AGE must be a number of days, got '{}'",
+						days
+					)
+				})?),
+				(None, None) => None,
+			};
+		let block =
+			match (self.block, lookup("MONITOR_BLOCK")) {
+				(Some(block), _) => Some(block),
+				(None, Some(block)) => Some(block.parse().map_err(|_| {
+					format!("MONITOR_BLOCK must be a block number, got '{}'", block)
+				})?),
+				(None, None) => None,
+			};
+		let rpc_usage_interval = match (
+			self.rpc_usage_interval,
+			lookup("RPC_USAGE_SUMMARY_INTERVAL"),
+		) {
+			(Some(interval), _) => Some(interval),
+			(None, Some(interval)) => Some(interval.parse().map_err(|_| {
+				format!(
+					"RPC_USAGE_SUMMARY_INTERVAL must be a number of seconds, got '{}'",
+					interval
+				)
+			})?),
+			(None, None) => None,
+		};
+		let contract_call_cache_ttl = parse_number(
+			"CONTRACT_CALL_CACHE_TTL_SECS",
+			lookup("CONTRACT_CALL_CACHE_TTL_SECS"),
+		)?;
+		let contract_call_cache_max_entries = parse_number(
+			"CONTRACT_CALL_CACHE_MAX_ENTRIES",
+			lookup("CONTRACT_CALL_CACHE_MAX_ENTRIES"),
+		)?;
+		let notification_max_concurrency = parse_number(
+			"NOTIFICATION_MAX_CONCURRENCY",
+			lookup("NOTIFICATION_MAX_CONCURRENCY"),
+		)?;
+		let retry_budget_per_minute =
+			parse_number("RETRY_BUDGET_PER_MINUTE", lookup("RETRY_BUDGET_PER_MINUTE"))?;
+		let metrics_port = match lookup("METRICS_PORT") {
+			Some(port) => Some(
+				port.parse()
+					.map_err(|_| format!("METRICS_PORT must be a TCP port, got '{}'", port))?,
+			),
+			None => None,
+		};
+
+		Ok(Self {
+			config_dir: self.config_dir.or_else(|| lookup("CONFIG_DIR")),
+			data_dir: self.data_dir.or_else(|| lookup("DATA_DIR")),
+			log_file: self.log_file
+				|| lookup("LOG_MODE").is_some_and(|mode| mode.eq_ignore_ascii_case("file")),
+			log_level: self
+				.log_level
+				.or_else(|| lookup("RUST_LOG"))
+				.or_else(|| lookup("LOG_LEVEL")),
+			log_path: self.log_path.or_else(|| lookup("LOG_DATA_DIR")),
+			log_max_size,
+			metrics_address: self.metrics_address.or_else(|| lookup("METRICS_ADDRESS")),
+			metrics: self.metrics || flag("METRICS_ENABLED")?,
+			notification_ordering: self
+				.notification_ordering
+				.or_else(|| lookup("NOTIFICATION_ORDERING")),
+			observe: self.observe || flag("OBSERVE_MODE")?,
+			match_journal: self.match_journal.or_else(|| lookup("MATCH_JOURNAL_PATH")),
+			match_journal_max_size,
+			match_journal_max_age,
+			panic_mode: self.panic_mode.or_else(|| lookup("PANIC_MODE")),
+			rpc_usage_interval,
+			control_socket: self
+				.control_socket
+				.or_else(|| lookup("CONTROL_SOCKET_PATH")),
+			monitor_path: self.monitor_path.or_else(|| lookup("MONITOR_PATH")),
+			network: self.network.or_else(|| lookup("MONITOR_NETWORK")),
+			block,
+			expect: self.expect.or_else(|| lookup("EXPECT_PATH")),
+			check: self.check || flag("CHECK_CONFIG")?,
+			check_templates: self.check_templates || flag("CHECK_TEMPLATES")?,
+			retry_skipped_blocks: self.retry_skipped_blocks || flag("RETRY_SKIPPED_BLOCKS")?,
+			replay_matches: self.replay_matches || flag("REPLAY_MATCHES")?,
+			replay_since: self.replay_since.or_else(|| lookup("REPLAY_SINCE")),
+			replay_until: self.replay_until.or_else(|| lookup("REPLAY_UNTIL")),
+			replay_trigger: self.replay_trigger.or_else(|| lookup("REPLAY_TRIGGER")),
+			export_state: self.export_state.or_else(|| lookup("EXPORT_STATE_PATH")),
+			import_state: self.import_state.or_else(|| lookup("IMPORT_STATE_PATH")),
+			import_alerts: self.import_alerts.or_else(|| lookup("IMPORT_ALERTS_PATH")),
+			import_format: self
+				.import_format
+				.or_else(|| lookup("IMPORT_ALERTS_FORMAT")),
+			contract_call_cache_ttl,
+			contract_call_cache_max_entries,
+			in_docker: lookup("IN_DOCKER").is_some_and(|value| value == "true"),
+			metrics_port,
+			log_redact_pattern: lookup("LOG_REDACT_PATTERN"),
+			api_tokens_file: lookup("API_TOKENS_FILE"),
+			notification_max_concurrency,
+			retry_budget_per_minute,
+			rpc_cache_mode: lookup("RPC_CACHE_MODE"),
+			rpc_cache_dir: lookup("RPC_CACHE_DIR"),
+			window_store_url: lookup("WINDOW_STORE_URL").map(SecretString::new),
+			window_store_snapshot_path: lookup("WINDOW_STORE_SNAPSHOT_PATH"),
+			state_dump_dir: lookup("STATE_DUMP_DIR"),
+			state_signing_key: lookup("STATE_SIGNING_KEY").map(SecretString::new),
+			etherscan_api_key: lookup("ETHERSCAN_API_KEY").map(SecretString::new),
+			etherscan_api_url: lookup("ETHERSCAN_API_URL"),
+		})
+	}
+
+	/// Returns the root of the `monitors`, `networks` and `triggers` configuration directories
+	pub fn config_dir(&self) -> PathBuf {
+		PathBuf::from(
+			self.config_dir
+				.as_deref()
+				.filter(|dir| !dir.is_empty())
+				.unwrap_or(DEFAULT_CONFIG_DIR),
+		)
+	}
+
+	/// Returns the directory of the state of the service
+	pub fn data_dir(&self) -> PathBuf {
+		PathBuf::from(
+			self.data_dir
+				.as_deref()
+				.filter(|dir| !dir.is_empty())
+				.unwrap_or(DEFAULT_DATA_DIR),
+		)
+	}
+
+	/// Returns the configuration of the logging
+	///
+	/// Logs are written to `logs/` in a container, whatever `--log-path`.
+	pub fn logging_config(&self) -> LoggingConfig {
+		let defaults = LoggingConfig::default();
+		LoggingConfig {
+			to_file: self.log_file,
+			level: self.log_level.clone().unwrap_or(defaults.level),
+			data_dir: match &self.log_path {
+				Some(path) if !self.in_docker => path.clone(),
+				_ => defaults.data_dir,
+			},
+			max_size: self.log_max_size.unwrap_or(defaults.max_size),
+			redact_pattern: self.log_redact_pattern.clone(),
+		}
+	}
+
+	/// Returns the ordering of the notifications, `None` if unset or invalid
+	pub fn notification_ordering(&self) -> NotificationOrdering {
+		parse_or_default(self.notification_ordering.as_deref())
+	}
+
+	/// Returns the handling of panics, `Abort` if unset or invalid
+	pub fn panic_mode(&self) -> PanicMode {
+		parse_or_default(self.panic_mode.as_deref())
+	}
+
+	/// Returns the address of the metrics server
+	///
+	/// Defaults to port `METRICS_PORT` (8081) of the loopback interface, or of all interfaces
+	/// in a container.
+	pub fn metrics_address(&self) -> String {
+		match &self.metrics_address {
+			Some(address) => address.clone(),
+			None => format!(
+				"{}:{}",
+				if self.in_docker {
+					"0.0.0.0"
+				} else {
+					"127.0.0.1"
+				},
+				self.metrics_port.unwrap_or(DEFAULT_METRICS_PORT)
+			),
+		}
+	}
+
+	/// Returns the time between two summaries of the RPC usage, `None` if disabled
+	pub fn rpc_usage_interval(&self) -> Option<Duration> {
+		match self.rpc_usage_interval {
+			Some(0) => None,
+			Some(secs) => Some(Duration::from_secs(secs)),
+			None => Some(usage::DEFAULT_SUMMARY_INTERVAL),
+		}
+	}
+
+	/// Returns the file and the rotation of the match journal, `None` if disabled
+	pub fn match_journal(&self) -> Option<(PathBuf, JournalRotation)> {
+		let path = self
+			.match_journal
+			.as_ref()
+			.filter(|path| !path.is_empty())?;
+		Some((
+			PathBuf::from(path),
+			JournalRotation {
+				max_size: self
+					.match_journal_max_size
+					.unwrap_or(DEFAULT_JOURNAL_MAX_SIZE),
+				max_age: self
+					.match_journal_max_age
+					.map(|days| Duration::from_secs(days * 24 * 3600))
+					.unwrap_or(DEFAULT_JOURNAL_MAX_AGE),
+			},
+		))
+	}
+
+	/// Returns the cache of the contract reads of the EVM clients
+	pub fn contract_call_cache(&self) -> ContractCallCache {
+		ContractCallCache::new(
+			self.contract_call_cache_ttl
+				.map(Duration::from_secs)
+				.unwrap_or(DEFAULT_CALL_CACHE_TTL),
+			self.contract_call_cache_max_entries
+				.unwrap_or(DEFAULT_CALL_CACHE_MAX_ENTRIES),
+		)
+	}
+
+	/// Returns the path of the control socket, if enabled
+	pub fn control_socket_path(&self) -> Option<PathBuf> {
+		self.control_socket
+			.as_ref()
+			.filter(|path| !path.is_empty())
+			.map(PathBuf::from)
+	}
+
+	/// Returns the JSON file of the API tokens, if any
+	pub fn api_tokens_file(&self) -> Option<PathBuf> {
+		self.api_tokens_file.as_ref().map(PathBuf::from)
+	}
+
+	/// Returns the queue of the notification dispatches
+	pub fn dispatch_queue(&self) -> PriorityDispatchQueue {
+		PriorityDispatchQueue::new(
+			self.notification_max_concurrency
+				.unwrap_or(DEFAULT_MAX_CONCURRENCY),
+		)
+	}
+
+	/// Returns new retry budgets, to be owned by the service whose clients share them
+	pub fn retry_budgets(&self) -> RetryBudgets {
+		RetryBudgets::new(self.retry_budget_per_minute.unwrap_or(0))
+	}
+
+	/// Returns the record/replay cache of the RPC responses, `None` if disabled
+	pub fn rpc_cache(&self) -> Option<RpcCache> {
+		RpcCache::configured(
+			self.rpc_cache_mode.as_deref()?,
+			self.rpc_cache_dir
+				.as_ref()
+				.map(PathBuf::from)
+				.unwrap_or_else(|| self.data_dir().join(DEFAULT_RPC_CACHE_DIR)),
+		)
+	}
+
+	/// Returns the window store, backed by Redis if a URL is set and by memory otherwise
+	pub fn window_store(&self) -> Arc<dyn WindowStore> {
+		create_window_store(
+			self.window_store_url.as_ref().map(SecretString::as_str),
+			self.window_store_snapshot_path
+				.as_ref()
+				.map(PathBuf::from)
+				.unwrap_or_else(|| self.data_dir().join(DEFAULT_WINDOW_STORE_SNAPSHOT_PATH)),
+			self.retry_budgets().get("window_store", None),
+		)
+	}
+
+	/// Returns the directory of the state dumps
+	pub fn state_dump_dir(&self) -> PathBuf {
+		self.state_dump_dir
+			.as_ref()
+			.map(PathBuf::from)
+			.unwrap_or_else(|| self.data_dir().join(DEFAULT_STATE_DUMP_DIR))
+	}
+
+	/// Returns the key signing and verifying the state archives, if set
+	pub fn state_signing_key(&self) -> Option<&str> {
+		self.state_signing_key.as_ref().map(SecretString::as_str)
+	}
+
+	/// Returns the access to the Etherscan API, `None` if no API key is set
+	pub fn etherscan(&self) -> Option<EtherscanConfig> {
+		Some(EtherscanConfig {
+			url: self
+				.etherscan_api_url
+				.clone()
+				.unwrap_or_else(|| DEFAULT_ETHERSCAN_API_URL.to_string()),
+			api_key: self.etherscan_api_key.as_ref()?.as_str().to_string(),
+		})
+	}
+}
+
+/// Parses an option, falling back to its default with a warning if it is invalid
+fn parse_or_default<T: FromStr<Err = String> + Default>(value: Option<&str>) -> T {
+	value
+		.map(|value| {
+			value.parse().unwrap_or_else(|e| {
+				tracing::warn!("{}", e);
+				T::default()
+			})
+		})
+		.unwrap_or_default()
+}
+
+/// Parses a numeric environment variable, `None` if unset
+fn parse_number<T: FromStr>(name: &str, value: Option<String>) -> Result<Option<T>> {
+	value
+		.map(|value| {
+			value
+				.parse()
+				.map_err(|_| format!("{} must be a number, got '{}'", name, value).into())
+		})
+		.transpose()
+}
+
+/// Parses a boolean environment variable
+fn parse_flag(name: &str, value: &str) -> Result<bool> {
+	match value.to_lowercase().as_str() {
+		"true" | "1" | "yes" => Ok(true),
+		"false" | "0" | "no" => Ok(false),
+		_ => Err(format!("{} must be true or false, got '{}'", name, value).into()),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::services::blockchain::RpcCacheMode;
+	use std::collections::HashMap;
+
+	fn resolve(cli: RuntimeSettings, vars: &[(&str, &str)]) -> Result<RuntimeSettings> {
+		let vars: HashMap<String, String> = vars
+			.iter()
+			.map(|(name, value)| (name.to_string(), value.to_string()))
+			.collect();
+		cli.resolve_with(|name| vars.get(name).cloned())
+	}
+
+	#[test]
+	fn test_resolve_from_env() {
+		let settings = resolve(
+			RuntimeSettings::default(),
+			&[
+				("CONFIG_DIR", "/etc/monitor"),
+				("DATA_DIR", "/var/lib/monitor"),
+				("LOG_MODE", "file"),
+				("LOG_LEVEL", "debug"),
+				("LOG_MAX_SIZE", "1KB"),
+				("METRICS_ENABLED", "true"),
+				("METRICS_ADDRESS", "0.0.0.0:9000"),
+				("MONITOR_PATH", "config/monitors/usdc.json"),
+				("MONITOR_NETWORK", "ethereum_mainnet"),
+				("MONITOR_BLOCK", "100"),
+				("CHECK_CONFIG", "1"),
+				("CHECK_TEMPLATES", "true"),
+				("RETRY_SKIPPED_BLOCKS", "false"),
+				("RPC_USAGE_SUMMARY_INTERVAL", "0"),
+				("EXPORT_STATE_PATH", "state.json"),
+				("PANIC_MODE", "isolate"),
+				("REPLAY_MATCHES", "true"),
+				("REPLAY_SINCE", "2024-05-01T12:00:00Z"),
+				("REPLAY_TRIGGER", "slack_incidents"),
+				("IMPORT_ALERTS_PATH", "sentinels.json"),
+				("MATCH_JOURNAL_PATH", "data/match_journal.jsonl"),
+				("MATCH_JOURNAL_MAX_SIZE", "1MB"),
+				("CONTRACT_CALL_CACHE_MAX_ENTRIES", "100"),
+				("NOTIFICATION_MAX_CONCURRENCY", "4"),
+				("RPC_CACHE_MODE", "replay"),
+				("STATE_DUMP_DIR", "/tmp/dumps"),
+				("STATE_SIGNING_KEY", "archive-key"),
+				("ETHERSCAN_API_KEY", "etherscan-key"),
+			],
+		)
+		.unwrap();
+
+		assert_eq!(settings.config_dir(), PathBuf::from("/etc/monitor"));
+		assert_eq!(settings.data_dir(), PathBuf::from("/var/lib/monitor"));
+		assert!(settings.log_file);
+		assert_eq!(settings.log_level.as_deref(), Some("debug"));
+		assert_eq!(settings.log_max_size, Some(1000));
+		assert!(settings.metrics);
+		assert_eq!(settings.metrics_address(), "0.0.0.0:9000");
+		assert_eq!(
+			settings.monitor_path.as_deref(),
+			Some("config/monitors/usdc.json")
+		);
+		assert_eq!(settings.network.as_deref(), Some("ethereum_mainnet"));
+		assert_eq!(settings.block, Some(100));
+		assert!(settings.check);
+		assert!(settings.check_templates);
+		assert!(!settings.retry_skipped_blocks);
+		assert_eq!(settings.rpc_usage_interval(), None);
+		assert_eq!(settings.export_state.as_deref(), Some("state.json"));
+		assert_eq!(settings.panic_mode(), PanicMode::Isolate);
+		assert!(settings.replay_matches);
+		assert_eq!(
+			settings.replay_since.as_deref(),
+			Some("2024-05-01T12:00:00Z")
+		);
+		assert_eq!(settings.replay_until, None);
+		assert_eq!(settings.replay_trigger.as_deref(), Some("slack_incidents"));
+		assert_eq!(settings.import_alerts.as_deref(), Some("sentinels.json"));
+		assert_eq!(settings.import_format, None);
+		assert_eq!(settings.contract_call_cache_ttl, None);
+		assert_eq!(settings.contract_call_cache_max_entries, Some(100));
+		assert_eq!(settings.notification_max_concurrency, Some(4));
+		assert_eq!(
+			settings.rpc_cache().map(|cache| cache.mode()),
+			Some(RpcCacheMode::Replay)
+		);
+		assert_eq!(settings.state_dump_dir(), PathBuf::from("/tmp/dumps"));
+		assert_eq!(settings.state_signing_key(), Some("archive-key"));
+		assert_eq!(
+			settings.etherscan(),
+			Some(EtherscanConfig {
+				url: DEFAULT_ETHERSCAN_API_URL.to_string(),
+				api_key: "etherscan-key".to_string(),
+			})
+		);
+		assert_eq!(
+			settings.match_journal(),
+			Some((
+				PathBuf::from("data/match_journal.jsonl"),
+				JournalRotation {
+					max_size: 1_000_000,
+					max_age: DEFAULT_JOURNAL_MAX_AGE,
+				}
+			))
+		);
+	}
+
+	#[test]
+	fn test_cli_overrides_env() {
+		let cli = RuntimeSettings {
+			log_level: Some("warn".to_string()),
+			block: Some(7),
+			control_socket: Some("cli.sock".to_string()),
+			..Default::default()
+		};
+		let settings = resolve(
+			cli,
+			&[
+				("RUST_LOG", "trace"),
+				("MONITOR_BLOCK", "100"),
+				("CONTROL_SOCKET_PATH", "env.sock"),
+			],
+		)
+		.unwrap();
+
+		assert_eq!(settings.log_level.as_deref(), Some("warn"));
+		assert_eq!(settings.block, Some(7));
+		assert_eq!(
+			settings.control_socket_path(),
+			Some(PathBuf::from("cli.sock"))
+		);
+	}
+
+	#[test]
+	fn test_logging_config() {
+		let settings = resolve(
+			RuntimeSettings::default(),
+			&[
+				("LOG_MODE", "file"),
+				("LOG_LEVEL", "debug"),
+				("LOG_DATA_DIR", "/var/log/monitor"),
+				("LOG_MAX_SIZE", "1KB"),
+				("LOG_REDACT_PATTERN", "acct-[0-9]+"),
+			],
+		)
+		.unwrap();
+		assert_eq!(
+			settings.logging_config(),
+			LoggingConfig {
+				to_file: true,
+				level: "debug".to_string(),
+				data_dir: "/var/log/monitor".to_string(),
+				max_size: 1000,
+				redact_pattern: Some("acct-[0-9]+".to_string()),
+			}
+		);
+
+		// Containers always log to logs/
+		let settings = resolve(
+			RuntimeSettings::default(),
+			&[("LOG_DATA_DIR", "/var/log/monitor"), ("IN_DOCKER", "true")],
+		)
+		.unwrap();
+		assert_eq!(settings.logging_config().data_dir, "logs/");
+	}
+
+	#[test]
+	fn test_metrics_address_defaults() {
+		let settings = resolve(RuntimeSettings::default(), &[]).unwrap();
+		assert_eq!(settings.metrics_address(), "127.0.0.1:8081");
+		assert_eq!(settings.config_dir(), PathBuf::from(DEFAULT_CONFIG_DIR));
+		assert_eq!(settings.data_dir(), PathBuf::from(DEFAULT_DATA_DIR));
+		assert_eq!(settings.match_journal(), None);
+		assert_eq!(settings.logging_config(), LoggingConfig::default());
+		assert_eq!(settings.notification_ordering(), NotificationOrdering::None);
+		assert_eq!(settings.panic_mode(), PanicMode::Abort);
+		assert_eq!(
+			settings.rpc_usage_interval(),
+			Some(usage::DEFAULT_SUMMARY_INTERVAL)
+		);
+		assert!(settings.rpc_cache().is_none());
+		assert_eq!(settings.etherscan(), None);
+		assert_eq!(settings.api_tokens_file(), None);
+		assert_eq!(settings.state_dump_dir(), PathBuf::from("data/state_dumps"));
+
+		let settings = resolve(
+			RuntimeSettings::default(),
+			&[("IN_DOCKER", "true"), ("METRICS_PORT", "9090")],
+		)
+		.unwrap();
+		assert_eq!(settings.metrics_address(), "0.0.0.0:9090");
+	}
+
+	#[test]
+	fn test_data_dir() {
+		let settings = resolve(
+			RuntimeSettings::default(),
+			&[
+				("DATA_DIR", "/var/lib/monitor"),
+				("RPC_CACHE_DIR", "/tmp/rpc"),
+			],
+		)
+		.unwrap();
+		assert_eq!(
+			settings.state_dump_dir(),
+			PathBuf::from("/var/lib/monitor/state_dumps")
+		);
+		assert_eq!(settings.rpc_cache_dir.as_deref(), Some("/tmp/rpc"));
+
+		let cli = RuntimeSettings {
+			data_dir: Some("cli-data".to_string()),
+			..Default::default()
+		};
+		let settings = resolve(cli, &[("DATA_DIR", "/var/lib/monitor")]).unwrap();
+		assert_eq!(settings.data_dir(), PathBuf::from("cli-data"));
+		assert_eq!(
+			settings.state_dump_dir(),
+			PathBuf::from("cli-data/state_dumps")
+		);
+	}
+
+	#[test]
+	fn test_invalid_env_values() {
+		assert!(resolve(RuntimeSettings::default(), &[("MONITOR_BLOCK", "latest")]).is_err());
+		assert!(resolve(RuntimeSettings::default(), &[("METRICS_ENABLED", "on")]).is_err());
+		assert!(resolve(RuntimeSettings::default(), &[("LOG_MAX_SIZE", "big")]).is_err());
+		assert!(resolve(
+			RuntimeSettings::default(),
+			&[("MATCH_JOURNAL_MAX_AGE", "30d")]
+		)
+		.is_err());
+		assert!(resolve(RuntimeSettings::default(), &[("METRICS_PORT", "http")]).is_err());
+		assert!(resolve(
+			RuntimeSettings::default(),
+			&[("CONTRACT_CALL_CACHE_TTL_SECS", "30s")]
+		)
+		.is_err());
+		assert!(resolve(
+			RuntimeSettings::default(),
+			&[("RPC_USAGE_SUMMARY_INTERVAL", "1h")]
+		)
+		.is_err());
+		assert!(resolve(
+			RuntimeSettings::default(),
+			&[("NOTIFICATION_MAX_CONCURRENCY", "-1")]
+		)
+		.is_err());
+		assert!(resolve(
+			RuntimeSettings::default(),
+			&[("RETRY_BUDGET_PER_MINUTE", "unlimited")]
+		)
+		.is_err());
+	}
+}
@@ -15,9 +15,17 @@
 //! - `repositories`: Configuration storage and management
 //! - `services`: Core business logic and blockchain interaction
 //! - `utils`: Common utilities and helper functions
+//!
+//! # Embedding
+//!
+//! The matching engine can be used without the block watcher: [`FilterPipeline`] filters a
+//! stream of blocks from any source and yields the processed blocks with their matches, e.g.
+//! `blocks.filter_matches(FilterPipeline::new(client, network, monitors))`.
 
 pub mod bootstrap;
 pub mod models;
 pub mod repositories;
 pub mod services;
 pub mod utils;
+
+pub use services::filter::{BlockStreamExt, FilterPipeline};
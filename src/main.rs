@@ -27,39 +27,75 @@ pub mod utils;
 use crate::{
 	bootstrap::{
 		create_block_handler, create_trigger_handler, get_contract_specs, has_active_monitors,
-		initialize_services, Result,
+		initialize_services, resolve_start_block, Result, RuntimeSettings,
 	},
-	models::{BlockChainType, Network, ScriptLanguage},
+	models::{BlockChainType, ConfigLoader, Monitor, Network, ScriptLanguage},
 	repositories::{
 		MonitorRepository, MonitorService, NetworkRepository, NetworkService, TriggerRepository,
 	},
 	services::{
-		blockchain::{ClientPool, ClientPoolTrait},
-		blockwatcher::{BlockTracker, BlockTrackerTrait, BlockWatcherService, FileBlockStorage},
-		filter::FilterService,
-		trigger::{TriggerExecutionService, TriggerExecutionServiceTrait},
+		blockchain::{ClientPool, ClientPoolTrait, RpcCacheMode},
+		blockwatcher::{
+			fetch_block, retry_skipped_blocks, spawn_capability_probes, BlockFailures,
+			BlockTracker, BlockTrackerTrait, BlockWatcherService, FileBlockStorage, SkipNotifier,
+		},
+		filter::{
+			start_monitor_state_snapshots, state::start_window_store_snapshots, FilterService,
+		},
+		notification::{check_templates, NotificationService},
+		trigger::{
+			parse_replay_time, read_replay_matches, replay_matches, resume_pending_notifications,
+			LatencyTracker, PendingNotifications, ReplayOptions, TriggerExecutionService,
+			TriggerExecutionServiceTrait, MATCH_JOURNAL, PENDING_NOTIFICATIONS_FILE,
+		},
 	},
 	utils::{
+		alert_import::{convert_alerts, write_imported_config, AlertFormat},
 		constants::DOCUMENTATION_URL,
+		control::{
+			applied_config_path, apply_config, write_state_dump, ConfigSnapshot, ControlContext,
+			CONTROL,
+		},
+		crash::{install_panic_hook, PanicContext},
 		logging::setup_logging,
-		metrics::server::create_metrics_server,
+		metrics::{
+			auth::ApiTokens,
+			server::create_metrics_server,
+			stats::{start_match_stats_snapshots, MATCH_STATS},
+			usage::{start_usage_summary, RPC_USAGE},
+		},
 		monitor::{
+			abi_drift::start_abi_drift_checks,
+			activation::start_deployment_watchers,
+			canary::start_canaries,
 			execution::{execute_monitor, MonitorExecutionConfig},
+			expectations::{format_diff, load_expectations, verify_expectations},
 			MonitorExecutionError,
 		},
-		parse_string_to_bytes_size,
+		state_archive::{export_state_to_file, import_state_from_file},
+		systemd, RetryBudgets,
 	},
 };
 
+#[cfg(unix)]
+use crate::utils::control::{remove_control_socket, start_control_socket};
 use clap::Parser;
-use dotenvy::dotenv_override;
 use std::collections::HashMap;
-use std::env::{set_var, var};
+use std::path::Path;
 use std::sync::Arc;
 use tokio::sync::{watch, Mutex};
 use tokio_cron_scheduler::JobScheduler;
 use tracing::{error, info, instrument};
 
+/// Time between two snapshots of the in-memory window store
+const WINDOW_STORE_SNAPSHOT_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// Time between two writes of the match statistics
+const MATCH_STATS_SNAPSHOT_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// Time between two writes of the monitor states of the filters
+const MONITOR_STATE_SNAPSHOT_INTERVAL: std::time::Duration = std::time::Duration::from_secs(10);
+
 type MonitorServiceType = MonitorService<
 	MonitorRepository<NetworkRepository, TriggerRepository>,
 	NetworkRepository,
@@ -97,93 +133,8 @@ struct MonitorExecutionTestConfig {
 	version
 )]
 struct Cli {
-	/// Write logs to file instead of stdout
-	#[arg(long)]
-	log_file: bool,
-
-	/// Set log level (trace, debug, info, warn, error)
-	#[arg(long, value_name = "LEVEL")]
-	log_level: Option<String>,
-
-	/// Path to store log files (default: logs/)
-	#[arg(long, value_name = "PATH")]
-	log_path: Option<String>,
-
-	/// Maximum log file size before rolling (e.g., "1GB", "500MB", "1024KB")
-	#[arg(long, value_name = "SIZE", value_parser = parse_string_to_bytes_size)]
-	log_max_size: Option<u64>,
-
-	/// Address to start the metrics server on (default: 127.0.0.1:8081)
-	#[arg(long, value_name = "HOST:PORT")]
-	metrics_address: Option<String>,
-
-	/// Enable metrics server
-	#[arg(long)]
-	metrics: bool,
-
-	/// Path to the monitor to execute
-	#[arg(long, value_name = "MONITOR_PATH")]
-	monitor_path: Option<String>,
-
-	/// Network to execute the monitor for
-	#[arg(long, value_name = "NETWORK_SLUG")]
-	network: Option<String>,
-
-	/// Block number to execute the monitor for
-	#[arg(long, value_name = "BLOCK_NUMBER")]
-	block: Option<u64>,
-
-	/// Validate configuration files without starting the service
-	#[arg(long)]
-	check: bool,
-}
-
-impl Cli {
-	/// Apply CLI options to environment variables, overriding any existing values
-	fn apply_to_env(&self) {
-		// Reload environment variables from .env file
-		// Override any existing environment variables
-		dotenv_override().ok();
-
-		// Log file mode - override if CLI flag is set
-		if self.log_file {
-			set_var("LOG_MODE", "file");
-		}
-
-		// Set log level from RUST_LOG if it exists
-		if let Ok(level) = var("RUST_LOG") {
-			set_var("LOG_LEVEL", level);
-		}
-
-		// Log level - override if CLI flag is set
-		if let Some(level) = &self.log_level {
-			set_var("LOG_LEVEL", level);
-			set_var("RUST_LOG", level);
-		}
-
-		// Log path - override if CLI flag is set
-		if let Some(path) = &self.log_path {
-			set_var("LOG_DATA_DIR", path);
-		}
-
-		// Log max size - override if CLI flag is set
-		if let Some(max_size) = &self.log_max_size {
-			set_var("LOG_MAX_SIZE", max_size.to_string());
-		}
-
-		// Metrics server - override if CLI flag is set
-		if self.metrics {
-			set_var("METRICS_ENABLED", "true");
-		}
-
-		// Metrics address - override if CLI flag is set
-		if let Some(address) = &self.metrics_address {
-			// Extract port from address if it's in HOST:PORT format
-			if let Some(port) = address.split(':').nth(1) {
-				set_var("METRICS_PORT", port);
-			}
-		}
-	}
+	#[command(flatten)]
+	settings: RuntimeSettings,
 }
 
 /// Main entry point for the blockchain monitoring service.
@@ -192,19 +143,78 @@ impl Cli {
 /// Returns an error if service initialization fails or if there's an error during shutdown.
 #[tokio::main]
 async fn main() -> Result<()> {
-	let cli = Cli::parse();
-
-	// Apply CLI options to environment
-	cli.apply_to_env();
+	// Resolve the options missing from the command line from the environment
+	let settings = Cli::parse().settings.resolve()?;
+	let config_dir = settings.config_dir();
+	let data_dir = settings.data_dir();
 
 	// Setup logging to stdout
-	setup_logging().unwrap_or_else(|e| {
+	setup_logging(&settings.logging_config()).unwrap_or_else(|e| {
 		error!("Failed to setup logging: {}", e);
 	});
 
 	// If --check flag is provided, only validate configuration and exit
-	if cli.check {
-		validate_configuration().await;
+	if settings.check {
+		validate_configuration(&config_dir).await;
+		return Ok(());
+	}
+
+	// If --check-templates flag is provided, only render the notification templates and exit
+	if settings.check_templates {
+		return check_notification_templates(&config_dir).await;
+	}
+
+	// Move the state of a deployment to or from an archive and exit
+	if let Some(path) = &settings.export_state {
+		let exported =
+			export_state_to_file(&data_dir, Path::new(path), settings.state_signing_key()).await?;
+		info!("Exported {} state files to {}", exported, path);
+		return Ok(());
+	}
+	if let Some(path) = &settings.import_state {
+		let imported =
+			import_state_from_file(Path::new(path), &data_dir, settings.state_signing_key())
+				.await?;
+		info!("Imported {} state files from {}", imported, path);
+		return Ok(());
+	}
+
+	// Convert third-party alert definitions into monitors and triggers and exit
+	if let Some(path) = &settings.import_alerts {
+		let format = settings
+			.import_format
+			.as_deref()
+			.map(str::parse::<AlertFormat>)
+			.transpose()?;
+		let networks: HashMap<String, Network> =
+			Network::load_all(Some(&config_dir.join("networks"))).await?;
+		let content = tokio::fs::read_to_string(path).await?;
+		let imported = convert_alerts(&content, format, &networks)?;
+		for warning in &imported.warnings {
+			tracing::warn!("{}", warning);
+		}
+		let written = write_imported_config(&imported, &config_dir, Path::new(path))?;
+		for file in &written {
+			info!("Wrote {}", file.display());
+		}
+		if !imported.secret_variables.is_empty() {
+			info!(
+				"Set the secrets of the imported triggers in the environment variables: {}",
+				imported
+					.secret_variables
+					.iter()
+					.cloned()
+					.collect::<Vec<_>>()
+					.join(", ")
+			);
+		}
+		info!(
+			"Imported {} monitors and {} triggers from {} ({} warnings)",
+			imported.monitors.len(),
+			imported.triggers.len(),
+			path,
+			imported.warnings.len()
+		);
 		return Ok(());
 	}
 
@@ -220,22 +230,92 @@ async fn main() -> Result<()> {
 		MonitorRepository<NetworkRepository, TriggerRepository>,
 		NetworkRepository,
 		TriggerRepository,
-	>(None, None, None)
+	>(&config_dir, None, None, None, settings.retry_budgets())
 	.await
 	.map_err(|e| anyhow::anyhow!("Failed to initialize services: {}. Please refer to the documentation quickstart ({}) on how to configure the service.", e, DOCUMENTATION_URL))?;
 
+	// If --replay-matches flag is provided, send the journaled matches again and exit
+	if settings.replay_matches {
+		if let Some(trigger) = &settings.replay_trigger {
+			if trigger_service.lock().await.get(trigger).is_none() {
+				return Err(format!("Unknown replay trigger '{}'", trigger).into());
+			}
+		}
+		let options = ReplayOptions {
+			since: settings
+				.replay_since
+				.as_deref()
+				.map(parse_replay_time)
+				.transpose()?,
+			until: settings
+				.replay_until
+				.as_deref()
+				.map(parse_replay_time)
+				.transpose()?,
+			network: settings.network.clone(),
+			trigger: settings.replay_trigger.clone(),
+		};
+		let Some((journal_path, _)) = settings.match_journal() else {
+			return Err("--replay-matches requires the match journal (--match-journal)".into());
+		};
+		let matches = read_replay_matches(&journal_path, &options).await?;
+		let result = replay_matches(matches, &*trigger_execution_service).await?;
+		info!(
+			"Replayed {} journaled matches, {} with failed triggers",
+			result.replayed, result.failed
+		);
+		return Ok(());
+	}
+
 	// Pre-load all trigger scripts into memory at startup to reduce file I/O operations.
 	// This prevents repeated file descriptor usage during script execution and improves performance
 	// by keeping scripts readily available in memory.
 	let active_monitors_trigger_scripts = trigger_execution_service
 		.load_scripts(&active_monitors)
 		.await?;
-	// Read CLI arguments to determine if we should test monitor execution
-	let monitor_path = cli.monitor_path.clone();
-	let network_slug = cli.network.clone();
-	let block_number = cli.block;
+	// Read the settings to determine if we should test monitor execution
+	let monitor_path = settings.monitor_path.clone();
+	let network_slug = settings.network.clone();
+	let block_number = settings.block;
+
+	// The clients and the filters share the capabilities detected on the endpoints
+	let rpc_cache = settings.rpc_cache();
+	let replaying = rpc_cache
+		.as_ref()
+		.is_some_and(|cache| cache.mode() == RpcCacheMode::Replay);
+	let client_pool = Arc::new(
+		ClientPool::new()
+			.with_call_cache(settings.contract_call_cache())
+			.with_capabilities(filter_service.context().capabilities.clone())
+			.with_retry_budgets(settings.retry_budgets())
+			.with_rpc_cache(rpc_cache),
+	);
 
-	let client_pool = Arc::new(ClientPool::new());
+	// If an expectations file is provided, verify the expected matches and exit
+	if let Some(expectations_path) = settings.expect.clone() {
+		let monitors = match &monitor_path {
+			Some(path) => vec![
+				monitor_service
+					.lock()
+					.await
+					.load_from_path(
+						Some(Path::new(path)),
+						Some(network_service.lock().await.clone()),
+						Some(trigger_service.lock().await.clone()),
+					)
+					.await?,
+			],
+			None => active_monitors,
+		};
+		return verify_expected_matches(
+			Path::new(&expectations_path),
+			&monitors,
+			&networks,
+			&client_pool,
+			&filter_service,
+		)
+		.await;
+	}
 
 	let should_test_monitor_execution = monitor_path.is_some();
 	// If monitor path is provided, test monitor execution else start the service
@@ -258,33 +338,24 @@ async fn main() -> Result<()> {
 		.await;
 	}
 
-	// Check if metrics should be enabled from either CLI flag or env var
-	let metrics_enabled =
-		cli.metrics || var("METRICS_ENABLED").map(|v| v == "true").unwrap_or(false);
-
-	// Extract metrics address as a String to avoid borrowing issues
-	let metrics_address = if var("IN_DOCKER").unwrap_or_default() == "true" {
-		// For Docker, use METRICS_PORT env var if available
-		var("METRICS_PORT")
-			.map(|port| format!("0.0.0.0:{}", port))
-			.unwrap_or_else(|_| "0.0.0.0:8081".to_string())
-	} else {
-		// For CLI, use the command line arg or default
-		cli.metrics_address
-			.map(|s| s.to_string())
-			.unwrap_or_else(|| "127.0.0.1:8081".to_string())
-	};
+	let metrics_address = settings.metrics_address();
 
 	// Start the metrics server if successful
-	let metrics_server = if metrics_enabled {
+	let metrics_server = if settings.metrics {
 		info!("Metrics server enabled, starting on {}", metrics_address);
 
+		let api_tokens = ApiTokens::load(settings.api_tokens_file().as_deref()).await?;
+		if api_tokens.is_enabled() {
+			info!("API token authentication enabled for the metrics server");
+		}
+
 		// Create the metrics server future
 		match create_metrics_server(
 			metrics_address,
 			monitor_service.clone(),
 			network_service.clone(),
 			trigger_service.clone(),
+			api_tokens,
 		) {
 			Ok(server) => Some(server),
 			Err(e) => {
@@ -300,7 +371,10 @@ async fn main() -> Result<()> {
 	let networks_with_monitors: Vec<Network> = networks
 		.values()
 		.filter(|network| has_active_monitors(&active_monitors.clone(), &network.slug))
-		.cloned()
+		.map(|network| Network {
+			start_block: resolve_start_block(network, &active_monitors),
+			..network.clone()
+		})
 		.collect();
 
 	if networks_with_monitors.is_empty() {
@@ -308,6 +382,22 @@ async fn main() -> Result<()> {
 		return Ok(());
 	}
 
+	// Detect the optional capabilities of the endpoints in the background, unless replaying
+	// recorded responses
+	if !replaying {
+		spawn_capability_probes(
+			networks_with_monitors.clone(),
+			client_pool.transport_context().capabilities.clone(),
+		);
+	}
+
+	CONTROL.register_networks(
+		&networks_with_monitors
+			.iter()
+			.map(|network| network.slug.clone())
+			.collect::<Vec<_>>(),
+	);
+
 	// Create a vector of networks with their associated monitors
 	let network_monitors = networks_with_monitors
 		.iter()
@@ -317,7 +407,7 @@ async fn main() -> Result<()> {
 				active_monitors
 					.iter()
 					.filter(|m| m.networks.contains(&network.slug))
-					.cloned()
+					.map(|m| m.for_network(&network.slug))
 					.collect::<Vec<_>>(),
 			)
 		})
@@ -326,28 +416,176 @@ async fn main() -> Result<()> {
 	// Fetch all contract specs for all active monitors
 	let contract_specs = get_contract_specs(&client_pool, &network_monitors).await;
 
+	// Monitors waiting for the deployment of a contract are activated once it has code
+	let deployment_watchers =
+		start_deployment_watchers(client_pool.clone(), &networks, &active_monitors);
+
+	// Configured contract specs are periodically compared with the deployed contracts
+	let triggers = trigger_service.lock().await.get_all();
+	// Receipts are fetched for the matches of monitors whose templates render receipt fields
+	filter_service
+		.context()
+		.receipt_templates
+		.register(&triggers, &active_monitors);
+	let abi_drift_checks = start_abi_drift_checks(
+		client_pool.clone(),
+		Arc::new(NotificationService::with_retry_budgets(
+			settings.retry_budgets(),
+		)),
+		&triggers,
+		&networks,
+		&active_monitors,
+		settings.etherscan(),
+	);
+	let config_snapshot = ConfigSnapshot::new(&active_monitors, &networks, &triggers);
+
+	// Monitors checked end-to-end against a known block, once the service is started
+	let canary_monitors: Vec<Monitor> = active_monitors
+		.iter()
+		.filter(|monitor| monitor.canary.is_some())
+		.cloned()
+		.collect();
+	let canary_filter_service = filter_service.clone();
+	let canary_trigger_service = trigger_execution_service.clone();
+	let canary_trigger_scripts = active_monitors_trigger_scripts.clone();
+
 	let (shutdown_tx, _) = watch::channel(false);
+	// Blocks whose filtering failed are recorded by the block handler for the block watcher,
+	// and the blocks it skips are notified to the `on_block_skipped` triggers
+	let skip_notifier = SkipNotifier::new(
+		Arc::new(NotificationService::with_retry_budgets(
+			settings.retry_budgets(),
+		)),
+		&triggers,
+		&networks,
+	);
+	let block_failures = Arc::new(BlockFailures::default().with_skip_notifier(skip_notifier));
+	let latency_tracker = Arc::new(LatencyTracker::default());
+	let dispatch_queue = Arc::new(settings.dispatch_queue());
+	let window_store = settings.window_store();
+	let pending_notifications = Arc::new(PendingNotifications::with_storage(
+		data_dir.join(PENDING_NOTIFICATIONS_FILE),
+	));
+	let pending_trigger_service = trigger_execution_service.clone();
+	// Monitor states are staged by the block handler and committed by the trigger handler
+	let filter_context = filter_service.context().clone();
 	let block_handler = create_block_handler(
 		shutdown_tx.clone(),
 		filter_service,
 		active_monitors,
 		client_pool.clone(),
 		contract_specs,
+		block_failures.clone(),
+		latency_tracker.clone(),
 	);
 	let trigger_handler = create_trigger_handler(
 		shutdown_tx.clone(),
 		trigger_execution_service,
 		active_monitors_trigger_scripts,
+		settings.notification_ordering(),
+		settings.observe,
+		latency_tracker.clone(),
+		dispatch_queue.clone(),
+		window_store.clone(),
+		pending_notifications.clone(),
+		filter_context.clone(),
 	);
 
-	let file_block_storage = Arc::new(FileBlockStorage::default());
+	let file_block_storage = Arc::new(FileBlockStorage::new(data_dir.clone()));
+
+	// If --retry-skipped-blocks flag is provided, only process the skip lists and exit
+	if settings.retry_skipped_blocks {
+		let networks_to_retry = networks_with_monitors.iter().filter(|network| {
+			settings
+				.network
+				.as_ref()
+				.is_none_or(|slug| *slug == network.slug)
+		});
+		for network in networks_to_retry {
+			let result = retry_skipped_blocks(
+				network,
+				|block_number| fetch_block(&*client_pool, network, block_number),
+				file_block_storage.clone(),
+				block_handler.clone(),
+				trigger_handler.clone(),
+				block_failures.clone(),
+			)
+			.await;
+			match result {
+				Ok(result) => info!(
+					network = %network.slug,
+					"Retried {} skipped blocks: {} processed, {} still failing",
+					result.attempted,
+					result.recovered,
+					result.failed
+				),
+				Err(e) => error!(
+					"Failed to retry skipped blocks for network {}: {}",
+					network.slug, e
+				),
+			}
+		}
+		return Ok(());
+	}
+
+	// Log the differences with the configuration of the previous start
+	apply_config(config_snapshot, &applied_config_path(&data_dir)).await;
+
+	filter_context.configure_storage(&data_dir);
+	MATCH_STATS.configure_storage(data_dir.join("match_stats.json"));
+	match settings.match_journal() {
+		Some((path, rotation)) => MATCH_JOURNAL.configure_storage(path, rotation),
+		None if settings.observe => {
+			tracing::warn!(
+				"Observe mode without --match-journal: observed matches are only counted"
+			)
+		}
+		None => {}
+	}
+	// Panics flush the state configured above before aborting
+	install_panic_hook(
+		settings.panic_mode(),
+		PanicContext {
+			window_store: window_store.clone(),
+			pending_notifications,
+			block_storage: file_block_storage.clone(),
+			filter_context: filter_context.clone(),
+		},
+	);
+	// Notifications left pending by a panic are sent before the new blocks
+	let pending_path = data_dir.join(PENDING_NOTIFICATIONS_FILE);
+	match resume_pending_notifications(&pending_path, &*pending_trigger_service).await {
+		Ok(Some(result)) => info!(
+			"Dispatched {} notifications left pending by the previous run, {} failed",
+			result.replayed, result.failed
+		),
+		Ok(None) => {}
+		Err(e) => error!(
+			"Failed to dispatch the notifications left pending in {}: {}",
+			pending_path.display(),
+			e
+		),
+	}
+	let window_store_snapshots =
+		start_window_store_snapshots(window_store.clone(), WINDOW_STORE_SNAPSHOT_INTERVAL);
+	let match_stats_snapshots = start_match_stats_snapshots(MATCH_STATS_SNAPSHOT_INTERVAL);
+	let monitor_state_snapshots =
+		start_monitor_state_snapshots(filter_context.clone(), MONITOR_STATE_SNAPSHOT_INTERVAL);
+	for network in networks.values() {
+		if let Some(cost) = &network.rpc_cost {
+			RPC_USAGE.set_costs(&network.slug, cost.clone());
+		}
+	}
+	let usage_summary = settings.rpc_usage_interval().map(start_usage_summary);
 	let block_watcher = BlockWatcherService::<FileBlockStorage, _, _, JobScheduler>::new(
 		file_block_storage.clone(),
 		block_handler,
 		trigger_handler,
 		Arc::new(BlockTracker::new(1000)),
 	)
-	.await?;
+	.await?
+	.with_failures(block_failures)
+	.with_latency_tracker(latency_tracker);
 
 	for network in networks_with_monitors {
 		match network.network_type {
@@ -431,31 +669,110 @@ async fn main() -> Result<()> {
 		}
 	}
 
+	let canaries = start_canaries(
+		client_pool.clone(),
+		canary_filter_service,
+		canary_trigger_service,
+		canary_trigger_scripts,
+		&networks,
+		&canary_monitors,
+	);
+
+	let control_context = ControlContext {
+		dispatch_queue: dispatch_queue.clone(),
+		window_store: window_store.clone(),
+		state_dump_dir: settings.state_dump_dir(),
+	};
+
+	// Start the control socket if configured
+	#[cfg(unix)]
+	let control_socket_path = match settings.control_socket_path() {
+		Some(path) => match start_control_socket(&path, control_context.clone()) {
+			Ok(_) => {
+				info!("Control socket listening on {}", path.display());
+				Some(path)
+			}
+			Err(e) => {
+				error!("Failed to start control socket {}: {}", path.display(), e);
+				None
+			}
+		},
+		None => None,
+	};
+
+	// Write a state dump whenever SIGUSR1 is received
+	#[cfg(unix)]
+	{
+		use tokio::signal::unix::{signal, SignalKind};
+		match signal(SignalKind::user_defined1()) {
+			Ok(mut sigusr1) => {
+				let context = control_context.clone();
+				tokio::spawn(async move {
+					while sigusr1.recv().await.is_some() {
+						match write_state_dump(&context.state_dump_dir, &context.dispatch_queue)
+							.await
+						{
+							Ok(path) => info!("State dumped to {}", path.display()),
+							Err(e) => error!("Failed to dump state: {}", e),
+						}
+					}
+				});
+			}
+			Err(e) => error!("Failed to listen for SIGUSR1: {}", e),
+		}
+	}
+
 	info!("Service started. Press Ctrl+C to shutdown");
+	systemd::notify_or_log("READY=1");
+	let watchdog_pings = systemd::start_watchdog_pings();
 
 	let ctrl_c = tokio::signal::ctrl_c();
+	tokio::pin!(ctrl_c);
+	let metrics_stopped = async {
+		match metrics_server {
+			Some(metrics_future) => metrics_future.await,
+			None => std::future::pending().await,
+		}
+	};
+	tokio::pin!(metrics_stopped);
+	let mut reload_requested = false;
 
-	if let Some(metrics_future) = metrics_server {
+	loop {
 		tokio::select! {
-				result = ctrl_c => {
-					if let Err(e) = result {
-			  error!("Error waiting for Ctrl+C: {}", e);
+			result = &mut ctrl_c => {
+				if let Err(e) = result {
+					error!("Error waiting for Ctrl+C: {}", e);
+				}
+				info!("Shutdown signal received, stopping services...");
 			}
-			info!("Shutdown signal received, stopping services...");
-		  }
-		  result = metrics_future => {
-			if let Err(e) = result {
-			  error!("Metrics server error: {}", e);
+			result = &mut metrics_stopped => {
+				if let Err(e) = result {
+					error!("Metrics server error: {}", e);
+				}
+				info!("Metrics server stopped, shutting down services...");
+			}
+			_ = CONTROL.reload_requested() => {
+				// The new configuration is loaded before anything is stopped, so that a
+				// configuration that cannot be loaded never replaces the running one
+				if !validate_reload(&config_dir).await {
+					continue;
+				}
+				info!("Reload requested, restarting services...");
+				reload_requested = true;
 			}
-			info!("Metrics server stopped, shutting down services...");
-		  }
 		}
-	} else {
-		let _ = ctrl_c.await;
-		info!("Shutdown signal received, stopping services...");
+		break;
 	}
 
 	// Common shutdown logic
+	systemd::notify_or_log(if reload_requested {
+		"RELOADING=1"
+	} else {
+		"STOPPING=1"
+	});
+	if let Some(watchdog_pings) = watchdog_pings {
+		watchdog_pings.abort();
+	}
 	let _ = shutdown_tx.send(true);
 
 	// Future for all network shutdown operations
@@ -471,10 +788,100 @@ async fn main() -> Result<()> {
 
 	tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
 
+	window_store_snapshots.abort();
+	match_stats_snapshots.abort();
+	monitor_state_snapshots.abort();
+	if let Some(usage_summary) = usage_summary {
+		usage_summary.abort();
+	}
+	for canary in canaries {
+		canary.abort();
+	}
+	for watcher in deployment_watchers {
+		watcher.abort();
+	}
+	for check in abi_drift_checks {
+		check.abort();
+	}
+	if let Err(e) = window_store.persist().await {
+		error!("Failed to persist the window store: {}", e);
+	}
+	MATCH_STATS.persist().await;
+	filter_context.persist().await;
+
+	#[cfg(unix)]
+	if let Some(path) = &control_socket_path {
+		remove_control_socket(path);
+	}
+
+	if reload_requested {
+		// Replace the process with a fresh instance, reloading all configuration
+		#[cfg(unix)]
+		{
+			use std::os::unix::process::CommandExt;
+			let error = std::process::Command::new(std::env::current_exe()?)
+				.args(std::env::args_os().skip(1))
+				.exec();
+			error!("Failed to restart the service: {}", error);
+			return Err(Box::new(error));
+		}
+	}
+
 	info!("Shutdown complete");
 	Ok(())
 }
 
+/// Verifies that blocks match exactly the expected monitors
+///
+/// # Arguments
+/// * `expectations_path` - Path of the JSON file of expected matches
+/// * `monitors` - Monitors to evaluate against the blocks
+/// * `networks` - Configured networks, keyed by slug
+/// * `client_pool` - Client pool of blockchain clients
+/// * `filter_service` - Service handling filter operations
+///
+/// # Errors
+/// Returns an error listing the differences if a block did not match the expected monitors
+async fn verify_expected_matches(
+	expectations_path: &Path,
+	monitors: &[Monitor],
+	networks: &HashMap<String, Network>,
+	client_pool: &Arc<ClientPool>,
+	filter_service: &FilterService,
+) -> Result<()> {
+	let expectations = load_expectations(expectations_path).await?;
+	info!(
+		message = "Verifying expected matches",
+		path = %expectations_path.display(),
+		blocks = expectations.len(),
+	);
+
+	let results = verify_expectations(
+		&expectations,
+		monitors,
+		networks,
+		client_pool,
+		filter_service,
+	)
+	.await?;
+	let failed = results.iter().filter(|result| !result.is_success()).count();
+	if failed == 0 {
+		info!("All {} blocks matched the expected monitors", results.len());
+		return Ok(());
+	}
+
+	Err(Box::new(MonitorExecutionError::execution_error(
+		format!(
+			"{} of {} blocks did not match the expected monitors:\n{}",
+			failed,
+			results.len(),
+			format_diff(&results)
+		),
+		None,
+		None,
+	)))
+}
+
 /// Tests the execution of a blockchain monitor configuration file.
 ///
 /// This function loads and executes a monitor configuration from the specified path,
@@ -710,6 +1117,49 @@ async fn test_monitor_execution(config: MonitorExecutionTestConfig) -> Result<()
 											}
 										}
 									}
+
+									// Explain why the conditions matched
+									if let Some(audit) =
+										details.get("matched_on_audit").and_then(|a| a.as_array())
+									{
+										info!("Condition Audit:");
+										for entry in audit {
+											let field = |name: &str| {
+												entry
+													.get(name)
+													.and_then(|v| v.as_str())
+													.unwrap_or_default()
+											};
+											info!(
+												"  - {} {} {}",
+												field("kind"),
+												field("signature"),
+												field("address")
+											);
+											if let Some(operands) =
+												entry.get("operands").and_then(|o| o.as_array())
+											{
+												for operand in operands {
+													let field = |name: &str| {
+														operand.get(name).map(|v| {
+															match v.as_str() {
+																Some(s) => s.to_string(),
+																None => v.to_string(),
+															}
+														})
+													};
+													info!(
+														"      {} = {} ({} {} -> {})",
+														field("variable").unwrap_or_default(),
+														field("value").unwrap_or_default(),
+														field("operator").unwrap_or_default(),
+														field("literal").unwrap_or_default(),
+														field("result").unwrap_or_default()
+													);
+												}
+											}
+										}
+									}
 								}
 								info!("-------------\n");
 							}
@@ -753,8 +1203,39 @@ async fn test_monitor_execution(config: MonitorExecutionTestConfig) -> Result<()
 	}
 }
 
+/// Checks that the configuration a reload restarts the service with can be loaded
+///
+/// The differences with the applied configuration are logged once the service restarted.
+///
+/// # Arguments
+/// * `config_dir` - Root of the configuration directories
+///
+/// # Returns
+/// `true` if the configuration can be loaded, `false` if the reload is refused
+async fn validate_reload(config_dir: &Path) -> bool {
+	match initialize_services::<
+		MonitorRepository<NetworkRepository, TriggerRepository>,
+		NetworkRepository,
+		TriggerRepository,
+	>(config_dir, None, None, None, RetryBudgets::default())
+	.await
+	{
+		Ok(_) => true,
+		Err(e) => {
+			error!(
+				"Reload refused, the new configuration cannot be loaded: {}",
+				e
+			);
+			false
+		}
+	}
+}
+
 /// Validates configuration files and their structure
-async fn validate_configuration() {
+///
+/// # Arguments
+/// * `config_dir` - Root of the configuration directories
+async fn validate_configuration(config_dir: &Path) {
 	info!("Validating configuration files...");
 
 	// Initialize services in validation mode to check configurations
@@ -762,7 +1243,7 @@ async fn validate_configuration() {
 		MonitorRepository<NetworkRepository, TriggerRepository>,
 		NetworkRepository,
 		TriggerRepository,
-	>(None, None, None)
+	>(config_dir, None, None, None, RetryBudgets::default())
 	.await
 	{
 		Ok((_, _, active_monitors, networks, _, _, _)) => {
@@ -798,9 +1279,66 @@ async fn validate_configuration() {
 	}
 }
 
+/// Renders the notification templates of the triggers with fixture matches, failing if any
+/// template has unknown variables or malformed placeholders
+///
+/// # Arguments
+/// * `config_dir` - Root of the configuration directories
+async fn check_notification_templates(config_dir: &Path) -> Result<()> {
+	info!("Checking notification templates...");
+
+	let (_, _, active_monitors, networks, _, _, trigger_service) =
+		initialize_services::<
+			MonitorRepository<NetworkRepository, TriggerRepository>,
+			NetworkRepository,
+			TriggerRepository,
+		>(config_dir, None, None, None, RetryBudgets::default())
+		.await?;
+	let triggers = trigger_service.lock().await.get_all();
+
+	let checks = check_templates(&triggers, &active_monitors, &networks);
+	let mut failed = 0;
+	for check in &checks {
+		let chain = match &check.chain {
+			_ if check.abi_drift => "ABI drift".to_string(),
+			Some(chain) => chain.to_string(),
+			None => "any chain".to_string(),
+		};
+		tracing::debug!(
+			"{} {} ({}) renders as: {}",
+			check.trigger,
+			check.field,
+			chain,
+			check.rendered
+		);
+		if check.passed() {
+			continue;
+		}
+		failed += 1;
+		for issue in &check.issues {
+			error!("✗ {} {} ({}): {}", check.trigger, check.field, chain, issue);
+		}
+	}
+
+	if failed > 0 {
+		return Err(format!(
+			"{} of {} notification template(s) failed the check",
+			failed,
+			checks.len()
+		)
+		.into());
+	}
+	info!(
+		"✓ {} notification template(s) rendered successfully",
+		checks.len()
+	);
+	Ok(())
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use crate::models::DEFAULT_CONFIG_DIR;
 
 	#[tokio::test]
 	async fn test_monitor_execution_without_network_slug_with_block_number() {
@@ -813,7 +1351,13 @@ mod tests {
 				MonitorRepository<NetworkRepository, TriggerRepository>,
 				NetworkRepository,
 				TriggerRepository,
-			>(None, None, None)
+			>(
+				Path::new(DEFAULT_CONFIG_DIR),
+				None,
+				None,
+				None,
+				RetryBudgets::default(),
+			)
 			.await
 			.unwrap();
 
@@ -855,7 +1399,13 @@ mod tests {
 				MonitorRepository<NetworkRepository, TriggerRepository>,
 				NetworkRepository,
 				TriggerRepository,
-			>(None, None, None)
+			>(
+				Path::new(DEFAULT_CONFIG_DIR),
+				None,
+				None,
+				None,
+				RetryBudgets::default(),
+			)
 			.await
 			.unwrap();
 
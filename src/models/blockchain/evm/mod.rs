@@ -10,9 +10,13 @@ mod transaction;
 
 pub use block::Block as EVMBlock;
 pub use monitor::{
-	ContractSpec as EVMContractSpec, MatchArguments as EVMMatchArguments,
+	AddressPoisoningConfig as EVMAddressPoisoningConfig, ContractSpec as EVMContractSpec,
+	FirstInteractionConfig as EVMFirstInteractionConfig, HeuristicsConfig as EVMHeuristicsConfig,
+	InternalTransfer as EVMInternalTransfer, MatchArguments as EVMMatchArguments,
 	MatchParamEntry as EVMMatchParamEntry, MatchParamsMap as EVMMatchParamsMap,
 	MonitorConfig as EVMMonitorConfig, MonitorMatch as EVMMonitorMatch,
+	NonceAnomalyConfig as EVMNonceAnomalyConfig, TraceStatus as EVMTraceStatus,
+	TransactionTrace as EVMTransactionTrace,
 };
 pub use receipt::{
 	BaseLog as EVMReceiptLog, BaseReceipt as EVMBaseReceipt,
@@ -1,6 +1,7 @@
 use crate::models::{
-	EVMReceiptLog, EVMTransaction, EVMTransactionReceipt, MatchConditions, Monitor,
+	ConditionAudit, EVMReceiptLog, EVMTransaction, EVMTransactionReceipt, MatchConditions, Monitor,
 };
+use alloy::primitives::U256;
 use serde::{Deserialize, Serialize};
 
 /// Result of a successful monitor match on an EVM chain
@@ -26,6 +27,75 @@ pub struct MonitorMatch {
 
 	/// Decoded arguments from the matched conditions
 	pub matched_on_args: Option<MatchArguments>,
+
+	/// Audit of the matched conditions: evaluated expressions, operand values and the
+	/// addresses that triggered them
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub matched_on_audit: Vec<ConditionAudit>,
+
+	/// Timestamp of the block of the transaction (unix seconds), when known
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub block_timestamp: Option<u64>,
+
+	/// Trace of the transaction, for the monitors inspecting internal calls
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub trace: Option<TransactionTrace>,
+}
+
+/// Outcome of the tracing of a matched transaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TraceStatus {
+	/// The transaction was traced
+	Traced,
+	/// The transaction was not sampled by the trace budget of the network
+	SampledOut,
+	/// The trace budget of the network was exhausted
+	OverBudget,
+	/// No endpoint of the network serves the tracing API
+	Unsupported,
+	/// The trace request failed
+	Failed,
+}
+
+impl TraceStatus {
+	/// Returns the name of the status, used in templates and as metrics label
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			Self::Traced => "traced",
+			Self::SampledOut => "sampled_out",
+			Self::OverBudget => "over_budget",
+			Self::Unsupported => "unsupported",
+			Self::Failed => "failed",
+		}
+	}
+}
+
+/// Trace data of a matched transaction
+///
+/// A match whose transaction was not traced (any status but `traced`) was produced without
+/// trace data: its internal transfers are unknown, not absent.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct TransactionTrace {
+	/// Outcome of the tracing
+	pub status: TraceStatus,
+
+	/// Native value moved by the internal calls of the transaction
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub internal_transfers: Vec<InternalTransfer>,
+}
+
+/// Native value moved by an internal call
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct InternalTransfer {
+	/// Caller of the internal call
+	pub from: String,
+
+	/// Callee of the internal call
+	pub to: String,
+
+	/// Value moved, in wei
+	pub value: U256,
 }
 
 /// Collection of decoded parameters from matched conditions
@@ -130,7 +200,116 @@ impl std::ops::Deref for ContractSpec {
 /// This configuration is used to for additional fields in the monitor configuration
 /// that are specific to EVM.
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Default)]
-pub struct MonitorConfig {}
+pub struct MonitorConfig {
+	/// Optional per-block analysis flagging flashloan and sandwich patterns
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub heuristics: Option<HeuristicsConfig>,
+
+	/// Compute net native and token flows per address for matched transactions
+	#[serde(default)]
+	pub funds_flow: bool,
+
+	/// Trace matched transactions to include the native value moved by their internal calls
+	/// in the funds flow, within the `trace_budget` of the network
+	#[serde(default)]
+	pub internal_transfers: bool,
+
+	/// Track senders interacting with monitored contracts for the first time
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub first_interaction: Option<FirstInteractionConfig>,
+
+	/// Flag zero-value transfers and look-alike addresses involving monitored addresses
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub address_poisoning: Option<AddressPoisoningConfig>,
+
+	/// Flag nonce gaps and dormant keys in the transactions sent by monitored addresses
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub nonce_anomaly: Option<NonceAnomalyConfig>,
+}
+
+/// Configuration of the address-poisoning detection
+///
+/// When enabled, the `is_zero_value_transfer`, `is_lookalike_address` and `lookalike_of`
+/// transaction fields describe token transfers involving the monitored addresses.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct AddressPoisoningConfig {
+	/// Number of leading hex characters an address must share to be a look-alike
+	#[serde(default = "default_lookalike_chars")]
+	pub prefix_length: usize,
+
+	/// Number of trailing hex characters an address must share to be a look-alike
+	#[serde(default = "default_lookalike_chars")]
+	pub suffix_length: usize,
+
+	/// Additional trusted addresses (e.g. usual counterparties) that attackers may imitate
+	#[serde(default)]
+	pub known_addresses: Vec<String>,
+}
+
+impl Default for AddressPoisoningConfig {
+	fn default() -> Self {
+		Self {
+			prefix_length: default_lookalike_chars(),
+			suffix_length: default_lookalike_chars(),
+			known_addresses: Vec::new(),
+		}
+	}
+}
+
+fn default_lookalike_chars() -> usize {
+	4
+}
+
+/// Configuration of the nonce anomaly detection
+///
+/// When enabled, the nonce of each transaction sent by a monitored address is compared with
+/// the nonce of its previous transaction, and the `is_nonce_gap`, `nonce_gap`,
+/// `expected_nonce`, `is_dormant_sender` and `idle_blocks` transaction fields describe the
+/// deviations.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Default)]
+#[serde(deny_unknown_fields)]
+pub struct NonceAnomalyConfig {
+	/// Number of blocks without a transaction after which the key of an address is dormant
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub dormant_after_blocks: Option<u64>,
+
+	/// Monitored addresses whose keys are not expected to send transactions (e.g. cold wallets)
+	#[serde(default)]
+	pub dormant_addresses: Vec<String>,
+}
+
+/// Configuration of the first-time interaction tracking
+///
+/// When enabled, the `is_first_interaction` transaction field is `true` when the sender
+/// has not called the monitored contract within the lookback window.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Default)]
+#[serde(deny_unknown_fields)]
+pub struct FirstInteractionConfig {
+	/// Number of blocks after which a previous interaction is forgotten
+	pub lookback_blocks: u64,
+}
+
+/// Configuration of the per-block heuristics analysis
+///
+/// When enabled, the flags computed for each transaction are exposed as additional
+/// transaction fields (`is_flashloan`, `flashloan_amount`, `is_sandwich`, `sandwich_role`)
+/// that can be used in transaction condition expressions.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Default)]
+#[serde(deny_unknown_fields)]
+pub struct HeuristicsConfig {
+	/// Flag transactions that borrow and repay the same token within the transaction
+	#[serde(default)]
+	pub flashloan: bool,
+
+	/// Minimum borrowed amount (raw token units) for a transaction to be flagged as a flashloan
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub flashloan_min_amount: Option<String>,
+
+	/// Flag same-block opposite swaps by one sender around another sender's swap
+	#[serde(default)]
+	pub sandwich: bool,
+}
 
 #[cfg(test)]
 mod tests {
@@ -203,10 +382,12 @@ mod tests {
 				events: vec![],
 				transactions: vec![],
 			},
+			matched_on_audit: Vec::new(),
 			matched_on_args: Some(MatchArguments {
 				functions: Some(vec![match_params]),
 				events: None,
 			}),
+			block_timestamp: None,
 		};
 
 		assert_eq!(monitor_match.monitor.name, "TestMonitor");
@@ -179,6 +179,16 @@ impl From<AlloyTransactionReceipt> for TransactionReceipt {
 	}
 }
 
+impl TransactionReceipt {
+	/// Returns the fee paid by the transaction (`gas_used * effective_gas_price`) in wei
+	///
+	/// # Returns
+	/// The fee, or `None` if the client did not return the gas used or the effective gas price
+	pub fn fee(&self) -> Option<U256> {
+		self.gas_used?.checked_mul(self.effective_gas_price?)
+	}
+}
+
 impl Deref for TransactionReceipt {
 	type Target = BaseReceipt;
 
@@ -98,6 +98,87 @@ impl BlockType {
 			BlockType::Solana(b) => b.number(),
 		}
 	}
+
+	/// Returns the timestamp of the block on chain
+	///
+	/// # Returns
+	/// The unix timestamp (seconds) of the block, or `None` when the block data has no
+	/// timestamp (Midnight) or it cannot be parsed
+	pub fn timestamp(&self) -> Option<u64> {
+		match self {
+			BlockType::EVM(b) => Some(b.timestamp.saturating_to::<u64>()),
+			BlockType::Stellar(b) => {
+				// Ledger close times are unix seconds, or RFC 3339 for some providers
+				let close_time = b.ledger_close_time.as_str();
+				close_time.parse::<u64>().ok().or_else(|| {
+					chrono::DateTime::parse_from_rfc3339(close_time)
+						.ok()
+						.and_then(|time| u64::try_from(time.timestamp()).ok())
+				})
+			}
+			BlockType::Solana(b) => b.block_time().and_then(|time| u64::try_from(time).ok()),
+			BlockType::Midnight(_) => None,
+		}
+	}
+
+	/// Returns the number of transactions included in the block data
+	///
+	/// # Returns
+	/// The number of transactions, or `None` for blocks whose transactions are fetched
+	/// separately (Stellar, Midnight)
+	pub fn transaction_count(&self) -> Option<usize> {
+		match self {
+			BlockType::EVM(b) => Some(b.transactions.len()),
+			BlockType::Solana(b) => Some(b.transactions.len()),
+			BlockType::Stellar(_) | BlockType::Midnight(_) => None,
+		}
+	}
+
+	/// Splits the block into blocks of at most `chunk_size` transactions each
+	///
+	/// Every chunk keeps the header of the block, so that it can be filtered like the
+	/// original block.
+	///
+	/// # Arguments
+	/// * `chunk_size` - Maximum number of transactions per chunk
+	///
+	/// # Returns
+	/// The chunks in transaction order, or `None` for blocks whose transactions are fetched
+	/// separately (Stellar, Midnight)
+	pub fn transaction_chunks(&self, chunk_size: usize) -> Option<Vec<BlockType>> {
+		let chunk_size = chunk_size.max(1);
+		match self {
+			BlockType::EVM(b) => {
+				let mut header = (**b).clone();
+				let transactions = std::mem::take(&mut header.0.transactions);
+				Some(
+					transactions
+						.chunks(chunk_size)
+						.map(|chunk| {
+							let mut block = header.clone();
+							block.0.transactions = chunk.to_vec();
+							BlockType::EVM(Box::new(block))
+						})
+						.collect(),
+				)
+			}
+			BlockType::Solana(b) => {
+				let mut header = (**b).clone();
+				let transactions = std::mem::take(&mut header.0.transactions);
+				Some(
+					transactions
+						.chunks(chunk_size)
+						.map(|chunk| {
+							let mut block = header.clone();
+							block.0.transactions = chunk.to_vec();
+							BlockType::Solana(Box::new(block))
+						})
+						.collect(),
+				)
+			}
+			BlockType::Stellar(_) | BlockType::Midnight(_) => None,
+		}
+	}
 }
 
 /// Transaction data from different blockchain platforms
@@ -153,6 +234,80 @@ pub enum MonitorMatch {
 	Solana(Box<solana::SolanaMonitorMatch>),
 }
 
+impl MonitorMatch {
+	/// Returns the monitor that triggered the match
+	pub fn monitor(&self) -> &crate::models::Monitor {
+		match self {
+			MonitorMatch::EVM(m) => &m.monitor,
+			MonitorMatch::Stellar(m) => &m.monitor,
+			MonitorMatch::Midnight(m) => &m.monitor,
+			MonitorMatch::Solana(m) => &m.monitor,
+		}
+	}
+
+	/// Returns a mutable reference to the monitor that triggered the match
+	pub fn monitor_mut(&mut self) -> &mut crate::models::Monitor {
+		match self {
+			MonitorMatch::EVM(m) => &mut m.monitor,
+			MonitorMatch::Stellar(m) => &mut m.monitor,
+			MonitorMatch::Midnight(m) => &mut m.monitor,
+			MonitorMatch::Solana(m) => &mut m.monitor,
+		}
+	}
+
+	/// Returns the slug of the network the match was found on
+	pub fn network_slug(&self) -> &str {
+		match self {
+			MonitorMatch::EVM(m) => &m.network_slug,
+			MonitorMatch::Stellar(m) => &m.network_slug,
+			MonitorMatch::Midnight(m) => &m.network_slug,
+			MonitorMatch::Solana(m) => &m.network_slug,
+		}
+	}
+}
+
+/// Audit of a matched condition, recording why a monitor fired
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct ConditionAudit {
+	/// Kind of the condition: `function`, `event` or `transaction`
+	pub kind: String,
+
+	/// Signature of the matched function or event
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub signature: Option<String>,
+
+	/// Expression of the condition
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub expression: Option<String>,
+
+	/// Comparisons evaluated for the expression, in evaluation order
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub operands: Vec<OperandAudit>,
+
+	/// Monitored address that triggered the condition
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub address: Option<String>,
+}
+
+/// A comparison evaluated within a condition expression
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct OperandAudit {
+	/// Variable on the left side of the comparison (e.g. `value` or `args[0].amount`)
+	pub variable: String,
+
+	/// Actual value of the variable
+	pub value: String,
+
+	/// Comparison operator (e.g. `>=` or `contains`)
+	pub operator: String,
+
+	/// Literal on the right side of the comparison
+	pub literal: String,
+
+	/// Result of the comparison
+	pub result: bool,
+}
+
 /// Chain-specific configuration
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Default)]
 pub struct ChainConfiguration {
@@ -206,6 +361,54 @@ mod tests {
 		assert_eq!(block_type.number(), Some(12345));
 	}
 
+	#[test]
+	fn test_block_type_timestamp() {
+		let mut evm_block = evm::EVMBlock::default();
+		evm_block.0.timestamp = alloy::primitives::U256::from(1_710_928_800u64);
+		assert_eq!(
+			BlockType::EVM(Box::new(evm_block)).timestamp(),
+			Some(1_710_928_800)
+		);
+
+		let mut stellar_block = stellar::StellarBlock::default();
+		stellar_block.0.ledger_close_time = "2024-03-20T10:00:00Z".to_string();
+		assert_eq!(
+			BlockType::Stellar(Box::new(stellar_block.clone())).timestamp(),
+			Some(1_710_928_800)
+		);
+		stellar_block.0.ledger_close_time = "1710928800".to_string();
+		assert_eq!(
+			BlockType::Stellar(Box::new(stellar_block.clone())).timestamp(),
+			Some(1_710_928_800)
+		);
+		stellar_block.0.ledger_close_time = String::new();
+		assert_eq!(
+			BlockType::Stellar(Box::new(stellar_block)).timestamp(),
+			None
+		);
+	}
+
+	#[test]
+	fn test_block_type_transaction_chunks() {
+		let mut evm_block = evm::EVMBlock::default();
+		evm_block.0.number = Some(alloy::primitives::U64::from(7));
+		evm_block.0.transactions = vec![evm::EVMTransaction::default(); 5];
+		let block_type = BlockType::EVM(Box::new(evm_block));
+		assert_eq!(block_type.transaction_count(), Some(5));
+
+		let chunks = block_type.transaction_chunks(2).unwrap();
+		let sizes: Vec<_> = chunks
+			.iter()
+			.map(|chunk| chunk.transaction_count().unwrap())
+			.collect();
+		assert_eq!(sizes, vec![2, 2, 1]);
+		assert!(chunks.iter().all(|chunk| chunk.number() == Some(7)));
+
+		let stellar_block = BlockType::Stellar(Box::default());
+		assert_eq!(stellar_block.transaction_count(), None);
+		assert!(stellar_block.transaction_chunks(2).is_none());
+	}
+
 	#[test]
 	fn test_block_type_number_solana() {
 		let solana_block = solana::SolanaBlock::from(solana::SolanaConfirmedBlock {
@@ -295,4 +498,29 @@ mod tests {
 		assert_eq!(format!("{}", BlockChainType::Midnight), "Midnight");
 		assert_eq!(format!("{}", BlockChainType::Solana), "Solana");
 	}
+
+	#[test]
+	fn test_monitor_match_accessors() {
+		use crate::utils::tests::builders::evm::{
+			monitor::MonitorBuilder, transaction::TransactionBuilder,
+		};
+
+		let mut monitor_match = MonitorMatch::EVM(Box::new(evm::EVMMonitorMatch {
+			monitor: MonitorBuilder::new().name("transfers").build(),
+			transaction: TransactionBuilder::new().build(),
+			receipt: None,
+			logs: None,
+			network_slug: "ethereum_mainnet".to_string(),
+			matched_on: crate::models::MatchConditions::default(),
+			matched_on_audit: Vec::new(),
+			matched_on_args: None,
+			block_timestamp: None,
+			trace: None,
+		}));
+		assert_eq!(monitor_match.monitor().name, "transfers");
+		assert_eq!(monitor_match.network_slug(), "ethereum_mainnet");
+
+		monitor_match.monitor_mut().name = "renamed".to_string();
+		assert_eq!(monitor_match.monitor().name, "renamed");
+	}
 }
@@ -32,6 +32,11 @@ pub struct MonitorMatch {
 
 	/// Decoded arguments from the matched conditions
 	pub matched_on_args: Option<MatchArguments>,
+
+	/// Data of the ledger that could not be fetched (e.g. `events`), the match being found
+	/// without it
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub missing_data: Vec<String>,
 }
 
 /// Collection of decoded parameters from matched conditions
@@ -557,6 +562,7 @@ mod tests {
 				functions: Some(vec![match_params]),
 				events: None,
 			}),
+			missing_data: Vec::new(),
 		};
 
 		assert_eq!(monitor_match.monitor.name, "TestMonitor");
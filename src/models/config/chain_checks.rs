@@ -0,0 +1,328 @@
+//! Chain-specific sanity checks of monitor conditions.
+//!
+//! A condition written for another chain is valid configuration but never matches, e.g. an
+//! EVM event signature on a Stellar network or a `gas_price` transaction condition on Solana.
+//! The checks compare the conditions and addresses of a monitor with the type of each of its
+//! networks and return a warning for every condition that cannot apply there.
+
+use std::collections::HashMap;
+
+use crate::{
+	models::{BlockChainType, Monitor, Network},
+	services::filter::{
+		expression_variables, solana_helpers, stellar_helpers, transaction_variables,
+	},
+};
+
+/// Soroban types (lowercase), not used in EVM signatures
+const STELLAR_TYPES: &[&str] = &[
+	"i32",
+	"i64",
+	"i128",
+	"i256",
+	"u32",
+	"u64",
+	"u128",
+	"u256",
+	"symbol",
+	"vec",
+	"map",
+	"bytesn",
+	"muxedaddress",
+	"timepoint",
+	"duration",
+	"option",
+	"void",
+];
+
+/// Returns the warnings of the conditions of a monitor that cannot apply to its networks
+///
+/// # Arguments
+/// * `monitor` - The monitor
+/// * `networks` - The networks, keyed by slug
+///
+/// # Returns
+/// One message per condition or address that does not make sense for a network of the monitor
+pub fn chain_condition_warnings(
+	monitor: &Monitor,
+	networks: &HashMap<String, Network>,
+) -> Vec<String> {
+	let mut warnings = Vec::new();
+	for network_slug in &monitor.networks {
+		let Some(network) = networks.get(network_slug) else {
+			continue; // Network reference errors are handled separately
+		};
+		let chain = &network.network_type;
+		let mut warn = |message: String| {
+			warnings.push(format!(
+				"Monitor '{}' on {} network '{}': {}",
+				monitor.name, chain, network_slug, message
+			))
+		};
+
+		let signatures = monitor
+			.match_conditions
+			.functions
+			.iter()
+			.map(|function| ("function", &function.signature))
+			.chain(
+				monitor
+					.match_conditions
+					.events
+					.iter()
+					.map(|event| ("event", &event.signature)),
+			);
+		for (kind, signature) in signatures {
+			if let Some(foreign) = foreign_signature_chain(signature, chain) {
+				warn(format!(
+					"{} signature '{}' uses {} types and will never match",
+					kind, signature, foreign
+				));
+			}
+		}
+
+		for condition in &monitor.match_conditions.transactions {
+			let Some(expression) = condition.expression.as_deref() else {
+				continue;
+			};
+			for variable in expression_variables(expression).unwrap_or_default() {
+				if let Some(foreign) = foreign_transaction_variable(&variable, chain) {
+					warn(format!(
+						"transaction condition '{}' uses the {} transaction field '{}', which \
+						 is not available",
+						expression, foreign, variable
+					));
+				}
+			}
+		}
+
+		if *chain == BlockChainType::Midnight {
+			if !monitor.match_conditions.events.is_empty() {
+				warn("event conditions are not supported and will never match".to_string());
+			}
+			let has_expressions = monitor
+				.match_conditions
+				.functions
+				.iter()
+				.map(|function| &function.expression)
+				.chain(
+					monitor
+						.match_conditions
+						.transactions
+						.iter()
+						.map(|transaction| &transaction.expression),
+				)
+				.any(Option::is_some);
+			if has_expressions {
+				warn("condition expressions are not evaluated".to_string());
+			}
+		}
+
+		for address in monitor.addresses_for_network(network_slug) {
+			if !is_chain_address(&address.address, chain) {
+				warn(format!(
+					"address '{}' is not a {} address and will never match",
+					address.address, chain
+				));
+			}
+		}
+	}
+	warnings
+}
+
+/// Returns the chain whose types a signature uses, if incompatible with the network
+fn foreign_signature_chain(signature: &str, chain: &BlockChainType) -> Option<BlockChainType> {
+	let (Some(open), Some(close)) = (signature.find('('), signature.rfind(')')) else {
+		return None;
+	};
+	if open >= close {
+		return None;
+	}
+	let mut types = signature[open + 1..close]
+		.split(|c: char| !c.is_ascii_alphanumeric())
+		.filter(|token| !token.is_empty() && !token.chars().all(|c| c.is_ascii_digit()))
+		.map(str::to_lowercase);
+	match chain {
+		BlockChainType::EVM => types
+			.any(|token| STELLAR_TYPES.contains(&token.as_str()))
+			.then_some(BlockChainType::Stellar),
+		BlockChainType::Stellar => types
+			.any(|token| is_evm_type(&token))
+			.then_some(BlockChainType::EVM),
+		BlockChainType::Midnight | BlockChainType::Solana => None,
+	}
+}
+
+/// Returns whether a (lowercase) type only exists in EVM signatures
+fn is_evm_type(token: &str) -> bool {
+	let sized = |prefix: &str, allow_unsized: bool| {
+		token.strip_prefix(prefix).is_some_and(|size| {
+			(allow_unsized && size.is_empty())
+				|| (!size.is_empty() && size.chars().all(|c| c.is_ascii_digit()))
+		})
+	};
+	sized("uint", true) || sized("int", true) || sized("bytes", false)
+}
+
+/// Returns the chain a transaction variable belongs to, if unavailable on the network
+fn foreign_transaction_variable(variable: &str, chain: &BlockChainType) -> Option<BlockChainType> {
+	// Midnight does not evaluate transaction expressions, reported separately
+	let own = transaction_variables(chain)?;
+	if own.contains(&variable) {
+		return None;
+	}
+	[
+		BlockChainType::EVM,
+		BlockChainType::Stellar,
+		BlockChainType::Solana,
+	]
+	.into_iter()
+	.find(|other| {
+		transaction_variables(other).is_some_and(|variables| variables.contains(&variable))
+	})
+}
+
+/// Returns whether an address has the format of the addresses of a chain
+fn is_chain_address(address: &str, chain: &BlockChainType) -> bool {
+	match chain {
+		BlockChainType::EVM => address.parse::<alloy::primitives::Address>().is_ok(),
+		BlockChainType::Stellar => stellar_helpers::is_address(address),
+		BlockChainType::Solana => solana_helpers::is_valid_pubkey(address),
+		BlockChainType::Midnight => true,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		models::{EventCondition, FunctionCondition, TransactionCondition, TransactionStatus},
+		utils::tests::builders::{evm::monitor::MonitorBuilder, network::NetworkBuilder},
+	};
+
+	const EVM_ADDRESS: &str = "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48";
+	const STELLAR_ADDRESS: &str = "CA6PUJLBYKZKUEKLZJMKBZLEKP2OTHANDEOWSFF44FTSYLKQPIICCJBE";
+
+	fn networks() -> HashMap<String, Network> {
+		[
+			NetworkBuilder::new()
+				.slug("ethereum_mainnet")
+				.network_type(BlockChainType::EVM)
+				.build(),
+			NetworkBuilder::new()
+				.slug("stellar_mainnet")
+				.network_type(BlockChainType::Stellar)
+				.build(),
+			NetworkBuilder::new()
+				.slug("solana_mainnet")
+				.network_type(BlockChainType::Solana)
+				.build(),
+			NetworkBuilder::new()
+				.slug("midnight_testnet")
+				.network_type(BlockChainType::Midnight)
+				.build(),
+		]
+		.into_iter()
+		.map(|network| (network.slug.clone(), network))
+		.collect()
+	}
+
+	fn event(signature: &str) -> EventCondition {
+		EventCondition {
+			signature: signature.to_string(),
+			expression: None,
+		}
+	}
+
+	fn transaction(expression: &str) -> TransactionCondition {
+		TransactionCondition {
+			status: TransactionStatus::Any,
+			expression: Some(expression.to_string()),
+		}
+	}
+
+	#[test]
+	fn test_matching_conditions_have_no_warnings() {
+		let monitor = MonitorBuilder::new()
+			.networks(vec!["ethereum_mainnet".to_string()])
+			.address(EVM_ADDRESS)
+			.event("Transfer(address,address,uint256)", None)
+			.transaction(
+				TransactionStatus::Success,
+				Some("gas_price > 100".to_string()),
+			)
+			.build();
+		assert!(chain_condition_warnings(&monitor, &networks()).is_empty());
+
+		let monitor = MonitorBuilder::new()
+			.networks(vec!["stellar_mainnet".to_string()])
+			.address(STELLAR_ADDRESS)
+			.function("swap(Address,U32,U32,U128,U128)", None)
+			.transaction(TransactionStatus::Any, Some("ledger > 100".to_string()))
+			.build();
+		assert!(chain_condition_warnings(&monitor, &networks()).is_empty());
+	}
+
+	#[test]
+	fn test_evm_conditions_on_stellar() {
+		let mut monitor = MonitorBuilder::new()
+			.networks(vec!["stellar_mainnet".to_string()])
+			.address(EVM_ADDRESS)
+			.build();
+		monitor.match_conditions.events = vec![event("Transfer(address,address,uint256)")];
+		monitor.match_conditions.transactions = vec![transaction("gas_price > 100")];
+
+		let warnings = chain_condition_warnings(&monitor, &networks());
+		assert_eq!(warnings.len(), 3);
+		assert!(warnings[0].contains("event signature 'Transfer(address,address,uint256)'"));
+		assert!(warnings[1].contains("EVM transaction field 'gas_price'"));
+		assert!(warnings[2].contains("is not a Stellar address"));
+	}
+
+	#[test]
+	fn test_stellar_conditions_on_evm() {
+		let mut monitor = MonitorBuilder::new()
+			.networks(vec!["ethereum_mainnet".to_string()])
+			.build();
+		monitor.match_conditions.functions = vec![FunctionCondition {
+			signature: "swap(Address,U32,Vec<Address>)".to_string(),
+			expression: None,
+		}];
+		monitor.match_conditions.transactions = vec![transaction("ledger > 5 && value > 0")];
+
+		let warnings = chain_condition_warnings(&monitor, &networks());
+		assert_eq!(warnings.len(), 2);
+		assert!(warnings[0].contains("uses Stellar types"));
+		assert!(warnings[1].contains("Stellar transaction field 'ledger'"));
+	}
+
+	#[test]
+	fn test_solana_and_midnight_conditions() {
+		let mut monitor = MonitorBuilder::new()
+			.networks(vec![
+				"solana_mainnet".to_string(),
+				"midnight_testnet".to_string(),
+			])
+			.addresses(vec![])
+			.build();
+		monitor.match_conditions.events = vec![event("Deposit")];
+		monitor.match_conditions.transactions = vec![transaction("gas_used > 0")];
+
+		let warnings = chain_condition_warnings(&monitor, &networks());
+		assert_eq!(warnings.len(), 3);
+		assert!(warnings[0].contains("'solana_mainnet'"));
+		assert!(warnings[0].contains("EVM transaction field 'gas_used'"));
+		assert!(warnings[1].contains("event conditions are not supported"));
+		assert!(warnings[2].contains("expressions are not evaluated"));
+	}
+
+	#[test]
+	fn test_is_evm_type() {
+		assert!(is_evm_type("uint256"));
+		assert!(is_evm_type("int"));
+		assert!(is_evm_type("bytes32"));
+		assert!(!is_evm_type("bytes"));
+		assert!(!is_evm_type("address"));
+		assert!(!is_evm_type("u128"));
+	}
+}
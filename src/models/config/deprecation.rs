@@ -0,0 +1,278 @@
+//! Migration of renamed configuration fields.
+//!
+//! A renamed field keeps being accepted under its old name for at least one release: the
+//! loaders rewrite the old name to the new one before deserializing, and log a warning naming
+//! the file, the location of the field and the release removing the old name. Configurations
+//! without renamed fields are deserialized from their text, so that parse errors keep their
+//! line and column.
+
+use serde::de::{DeserializeOwned, Error as _};
+use serde_json::Value as JsonValue;
+use std::path::Path;
+
+/// A field renamed in a configuration file
+#[derive(Debug, Clone, Copy)]
+pub struct FieldRename {
+	/// Dotted path of the object holding the field, relative to the configuration. `*`
+	/// matches any key or array index, and an empty path is the configuration itself
+	pub object: &'static str,
+	/// Field of the configuration and the value it must have for the rename to apply, for
+	/// fields renamed in a single variant (e.g. a trigger type)
+	pub when: Option<(&'static str, &'static str)>,
+	/// Previous name of the field
+	pub old: &'static str,
+	/// Current name of the field
+	pub new: &'static str,
+	/// Release no longer accepting the previous name
+	pub removed_in: &'static str,
+}
+
+/// Renamed fields of monitor configurations
+pub const MONITOR_RENAMES: &[FieldRename] = &[];
+
+/// Renamed fields of network configurations
+pub const NETWORK_RENAMES: &[FieldRename] = &[];
+
+/// Renamed fields of trigger configurations
+pub const TRIGGER_RENAMES: &[FieldRename] = &[
+	FieldRename {
+		object: "config",
+		when: Some(("trigger_type", "slack")),
+		old: "webhook_url",
+		new: "slack_url",
+		removed_in: "1.8.0",
+	},
+	FieldRename {
+		object: "config",
+		when: Some(("trigger_type", "discord")),
+		old: "webhook_url",
+		new: "discord_url",
+		removed_in: "1.8.0",
+	},
+];
+
+/// Parses a configuration file, migrating its renamed fields
+///
+/// # Arguments
+/// * `content` - Content of the file
+/// * `renames` - Renamed fields of the configurations of the file
+/// * `root` - Dotted path of the configurations in the file, `*` for a file keyed by name
+/// * `path` - Path of the file, for the warnings
+///
+/// # Errors
+/// Returns an error if the content cannot be deserialized, or if a configuration sets both
+/// the previous and the current name of a field
+pub fn parse_config<T: DeserializeOwned>(
+	content: &str,
+	renames: &[FieldRename],
+	root: &str,
+	path: &Path,
+) -> Result<T, serde_json::Error> {
+	if renames.is_empty() {
+		return serde_json::from_str(content);
+	}
+	let mut value: JsonValue = serde_json::from_str(content)?;
+	if migrate_renamed_fields(&mut value, renames, root, path)? == 0 {
+		// Parsed again from the text to keep the line and column of errors
+		return serde_json::from_str(content);
+	}
+	serde_json::from_value(value)
+}
+
+/// Rewrites the previous names of renamed fields to their current names
+///
+/// # Arguments
+/// * `value` - Content of the file
+/// * `renames` - Renamed fields of the configurations of the file
+/// * `root` - Dotted path of the configurations in the file, `*` for a file keyed by name
+/// * `path` - Path of the file, for the warnings
+///
+/// # Returns
+/// The number of fields migrated
+pub fn migrate_renamed_fields(
+	value: &mut JsonValue,
+	renames: &[FieldRename],
+	root: &str,
+	path: &Path,
+) -> Result<usize, serde_json::Error> {
+	let mut migrated = 0;
+	let mut configs = Vec::new();
+	select(value, &segments(root), String::new(), &mut configs);
+	for (location, config) in configs {
+		for rename in renames {
+			if let Some((field, expected)) = rename.when {
+				if config.get(field).and_then(JsonValue::as_str) != Some(expected) {
+					continue;
+				}
+			}
+			let mut objects = Vec::new();
+			select(
+				config,
+				&segments(rename.object),
+				location.clone(),
+				&mut objects,
+			);
+			for (object_location, object) in objects {
+				let Some(object) = object.as_object_mut() else {
+					continue;
+				};
+				let Some(old_value) = object.remove(rename.old) else {
+					continue;
+				};
+				let old = join(&object_location, rename.old);
+				let new = join(&object_location, rename.new);
+				if object.contains_key(rename.new) {
+					return Err(serde_json::Error::custom(format!(
+						"both the deprecated field `{}` and its replacement `{}` are set",
+						old, new
+					)));
+				}
+				tracing::warn!(
+					path = %path.display(),
+					field = %old,
+					replacement = %new,
+					removed_in = rename.removed_in,
+					"Configuration field `{}` is deprecated and will no longer be accepted in {}, \
+					 rename it to `{}`",
+					old,
+					rename.removed_in,
+					new
+				);
+				object.insert(rename.new.to_string(), old_value);
+				migrated += 1;
+			}
+		}
+	}
+	Ok(migrated)
+}
+
+fn segments(path: &str) -> Vec<&str> {
+	path.split('.')
+		.filter(|segment| !segment.is_empty())
+		.collect()
+}
+
+fn join(location: &str, segment: &str) -> String {
+	if location.is_empty() {
+		segment.to_string()
+	} else {
+		format!("{}.{}", location, segment)
+	}
+}
+
+/// Collects the values at a path, with their locations
+fn select<'a>(
+	value: &'a mut JsonValue,
+	segments: &[&str],
+	location: String,
+	selected: &mut Vec<(String, &'a mut JsonValue)>,
+) {
+	let Some((segment, rest)) = segments.split_first() else {
+		selected.push((location, value));
+		return;
+	};
+	match (value, *segment) {
+		(JsonValue::Object(object), "*") => {
+			for (key, child) in object.iter_mut() {
+				select(child, rest, join(&location, key), selected);
+			}
+		}
+		(JsonValue::Array(items), "*") => {
+			for (index, child) in items.iter_mut().enumerate() {
+				select(child, rest, join(&location, &index.to_string()), selected);
+			}
+		}
+		(JsonValue::Object(object), key) => {
+			if let Some(child) = object.get_mut(key) {
+				select(child, rest, join(&location, key), selected);
+			}
+		}
+		_ => {}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use serde_json::json;
+
+	const RENAMES: &[FieldRename] = &[
+		FieldRename {
+			object: "rpc_urls.*",
+			when: None,
+			old: "type",
+			new: "type_",
+			removed_in: "1.8.0",
+		},
+		FieldRename {
+			object: "",
+			when: Some(("network_type", "EVM")),
+			old: "chain",
+			new: "chain_id",
+			removed_in: "1.8.0",
+		},
+	];
+
+	#[test]
+	fn test_migrate_renamed_fields() {
+		let mut config = json!({
+			"network_type": "EVM",
+			"chain": 1,
+			"rpc_urls": [{"type": "rpc"}, {"type_": "rpc"}],
+		});
+		let migrated =
+			migrate_renamed_fields(&mut config, RENAMES, "", Path::new("network.json")).unwrap();
+		assert_eq!(migrated, 2);
+		assert_eq!(
+			config,
+			json!({
+				"network_type": "EVM",
+				"chain_id": 1,
+				"rpc_urls": [{"type_": "rpc"}, {"type_": "rpc"}],
+			})
+		);
+
+		// Renames of a variant only apply to it
+		let mut config = json!({"network_type": "Stellar", "chain": 1});
+		assert_eq!(
+			migrate_renamed_fields(&mut config, RENAMES, "", Path::new("network.json")).unwrap(),
+			0
+		);
+		assert_eq!(config["chain"], 1);
+	}
+
+	#[test]
+	fn test_migrate_renamed_fields_conflict() {
+		let mut config = json!({"rpc_urls": [{"type": "rpc", "type_": "rpc"}]});
+		let error = migrate_renamed_fields(&mut config, RENAMES, "", Path::new("network.json"))
+			.unwrap_err();
+		assert!(error.to_string().contains("rpc_urls.0.type"));
+	}
+
+	#[test]
+	fn test_parse_config_trigger_file() {
+		let content = r#"{
+			"slack_alerts": {
+				"trigger_type": "slack",
+				"config": {"webhook_url": "https://hooks.slack.com/services/A/B/C"}
+			},
+			"discord_alerts": {
+				"trigger_type": "discord",
+				"config": {"webhook_url": "https://discord.com/api/webhooks/1/abc"}
+			}
+		}"#;
+		let config: JsonValue =
+			parse_config(content, TRIGGER_RENAMES, "*", Path::new("triggers.json")).unwrap();
+		assert_eq!(
+			config["slack_alerts"]["config"]["slack_url"],
+			"https://hooks.slack.com/services/A/B/C"
+		);
+		assert_eq!(
+			config["discord_alerts"]["config"]["discord_url"],
+			"https://discord.com/api/webhooks/1/abc"
+		);
+		assert!(config["slack_alerts"]["config"]
+			.get("webhook_url")
+			.is_none());
+	}
+}
@@ -1,20 +1,27 @@
 //! Configuration loading and validation.
 //!
 //! This module provides traits and implementations for loading and validating
-//! configuration files for networks, monitors, and triggers.
+//! configuration files for networks, monitors, and triggers. Fields renamed in a recent
+//! release are migrated to their current names when the files are loaded.
 
 #![allow(clippy::result_large_err)]
 
 use async_trait::async_trait;
 use std::path::Path;
 
+mod chain_checks;
+mod deprecation;
 mod error;
 mod monitor_config;
 mod network_config;
 mod trigger_config;
 
+pub use chain_checks::chain_condition_warnings;
 pub use error::ConfigError;
 
+/// Default root of the `monitors`, `networks` and `triggers` configuration directories
+pub const DEFAULT_CONFIG_DIR: &str = "config";
+
 /// Common interface for loading configuration files
 #[async_trait]
 pub trait ConfigLoader: Sized {
@@ -4,8 +4,18 @@
 //! allowing monitors to be loaded from JSON files.
 
 use crate::{
-	models::{config::error::ConfigError, ConfigLoader, Monitor, SecretValue},
-	services::trigger::validate_script_config,
+	models::{
+		config::{
+			deprecation::{parse_config, MONITOR_RENAMES},
+			error::ConfigError,
+			DEFAULT_CONFIG_DIR,
+		},
+		ConfigLoader, Monitor, SamplingConfig, SecretValue,
+	},
+	services::{
+		filter::{evm_helpers::string_to_u256, validate_match_expression},
+		trigger::validate_script_config,
+	},
 	utils::normalize_string,
 };
 use async_trait::async_trait;
@@ -39,6 +49,17 @@ impl ConfigLoader for Monitor {
 					.await?;
 			}
 		}
+
+		for rpc_url in monitor.rpc_overrides.values_mut().flatten() {
+			let resolved_url = rpc_url.url.resolve().await.map_err(|e| {
+				ConfigError::parse_error(
+					format!("failed to resolve RPC override URL: {}", e),
+					Some(Box::new(e)),
+					None,
+				)
+			})?;
+			rpc_url.url = SecretValue::Plain(resolved_url);
+		}
 		Ok(monitor)
 	}
 
@@ -50,7 +71,8 @@ impl ConfigLoader for Monitor {
 	where
 		T: FromIterator<(String, Self)>,
 	{
-		let monitor_dir = path.unwrap_or(Path::new("config/monitors"));
+		let default_dir = Path::new(DEFAULT_CONFIG_DIR).join("monitors");
+		let monitor_dir = path.unwrap_or(&default_dir);
 		let mut pairs = Vec::new();
 
 		if !monitor_dir.exists() {
@@ -113,7 +135,7 @@ impl ConfigLoader for Monitor {
 	///
 	/// Reads and parses a single JSON file as a monitor configuration.
 	async fn load_from_path(path: &Path) -> Result<Self, ConfigError> {
-		let file = std::fs::File::open(path).map_err(|e| {
+		let content = fs::read_to_string(path).map_err(|e| {
 			ConfigError::file_error(
 				format!("failed to open monitor config file: {}", e),
 				Some(Box::new(e)),
@@ -123,16 +145,17 @@ impl ConfigLoader for Monitor {
 				)])),
 			)
 		})?;
-		let mut config: Monitor = serde_json::from_reader(file).map_err(|e| {
-			ConfigError::parse_error(
-				format!("failed to parse monitor config: {}", e),
-				Some(Box::new(e)),
-				Some(HashMap::from([(
-					"path".to_string(),
-					path.display().to_string(),
-				)])),
-			)
-		})?;
+		let mut config: Monitor =
+			parse_config(&content, MONITOR_RENAMES, "", path).map_err(|e| {
+				ConfigError::parse_error(
+					format!("failed to parse monitor config: {}", e),
+					Some(Box::new(e)),
+					Some(HashMap::from([(
+						"path".to_string(),
+						path.display().to_string(),
+					)])),
+				)
+			})?;
 
 		// Resolve secrets before validating
 		config = config.resolve_secrets().await?;
@@ -172,8 +195,313 @@ impl ConfigLoader for Monitor {
 			));
 		}
 
+		// Validate EVM heuristics configuration
+		for heuristics in self
+			.chain_configurations
+			.iter()
+			.filter_map(|config| config.evm.as_ref())
+			.filter_map(|evm| evm.heuristics.as_ref())
+		{
+			if let Some(min_amount) = &heuristics.flashloan_min_amount {
+				if string_to_u256(min_amount).is_err() {
+					return Err(ConfigError::validation_error(
+						format!("Invalid flashloan_min_amount: {}", min_amount),
+						None,
+						None,
+					));
+				}
+			}
+		}
+
+		// Validate first-time interaction tracking
+		if self
+			.chain_configurations
+			.iter()
+			.filter_map(|config| config.evm.as_ref())
+			.filter_map(|evm| evm.first_interaction.as_ref())
+			.any(|config| config.lookback_blocks == 0)
+		{
+			return Err(ConfigError::validation_error(
+				"first_interaction lookback_blocks must be greater than 0",
+				None,
+				None,
+			));
+		}
+
+		// Validate address-poisoning detection
+		for poisoning in self
+			.chain_configurations
+			.iter()
+			.filter_map(|config| config.evm.as_ref())
+			.filter_map(|evm| evm.address_poisoning.as_ref())
+		{
+			if poisoning.prefix_length + poisoning.suffix_length == 0
+				|| poisoning.prefix_length + poisoning.suffix_length >= 40
+			{
+				return Err(ConfigError::validation_error(
+					"address_poisoning prefix_length and suffix_length must add up to between 1 \
+					 and 39 hex characters",
+					None,
+					None,
+				));
+			}
+			if let Some(address) = poisoning
+				.known_addresses
+				.iter()
+				.find(|address| address.parse::<alloy::primitives::Address>().is_err())
+			{
+				return Err(ConfigError::validation_error(
+					format!("Invalid address_poisoning known address: {}", address),
+					None,
+					None,
+				));
+			}
+		}
+
+		// Validate nonce anomaly detection
+		for nonce_anomaly in self
+			.chain_configurations
+			.iter()
+			.filter_map(|config| config.evm.as_ref())
+			.filter_map(|evm| evm.nonce_anomaly.as_ref())
+		{
+			if nonce_anomaly.dormant_after_blocks == Some(0) {
+				return Err(ConfigError::validation_error(
+					"nonce_anomaly dormant_after_blocks must be greater than 0",
+					None,
+					None,
+				));
+			}
+			if let Some(address) = nonce_anomaly
+				.dormant_addresses
+				.iter()
+				.find(|address| address.parse::<alloy::primitives::Address>().is_err())
+			{
+				return Err(ConfigError::validation_error(
+					format!("Invalid nonce_anomaly dormant address: {}", address),
+					None,
+					None,
+				));
+			}
+		}
+
+		// Internal transfers are only reported in the funds flow
+		if self
+			.chain_configurations
+			.iter()
+			.filter_map(|config| config.evm.as_ref())
+			.any(|evm| evm.internal_transfers && !evm.funds_flow)
+		{
+			return Err(ConfigError::validation_error(
+				"internal_transfers requires funds_flow",
+				None,
+				None,
+			));
+		}
+
+		// Block numbers are chain-specific, so a start block applies to a single network
+		if self.start_block.is_some() && self.networks.len() != 1 {
+			return Err(ConfigError::validation_error(
+				"start_block requires the monitor to watch exactly one network",
+				None,
+				None,
+			));
+		}
+
+		// The canary block must be on a watched network
+		if let Some(canary) = &self.canary {
+			if !self.networks.contains(&canary.network) {
+				return Err(ConfigError::validation_error(
+					format!(
+						"canary network '{}' is not watched by the monitor",
+						canary.network
+					),
+					None,
+					None,
+				));
+			}
+			if canary.interval_secs == 0 {
+				return Err(ConfigError::validation_error(
+					"canary interval_secs must be greater than 0",
+					None,
+					None,
+				));
+			}
+		}
+
+		// The contract the monitor depends on must be on a watched network
+		if let Some(dependency) = &self.activate_on_deployment {
+			if !self.networks.contains(&dependency.network) {
+				return Err(ConfigError::validation_error(
+					format!(
+						"activate_on_deployment network '{}' is not watched by the monitor",
+						dependency.network
+					),
+					None,
+					None,
+				));
+			}
+			if dependency.address.trim().is_empty() {
+				return Err(ConfigError::validation_error(
+					"activate_on_deployment address must not be empty",
+					None,
+					None,
+				));
+			}
+			if dependency.interval_secs == 0 {
+				return Err(ConfigError::validation_error(
+					"activate_on_deployment interval_secs must be greater than 0",
+					None,
+					None,
+				));
+			}
+		}
+
+		if self
+			.abi_drift
+			.as_ref()
+			.is_some_and(|drift| drift.interval_secs == 0)
+		{
+			return Err(ConfigError::validation_error(
+				"abi_drift interval_secs must be greater than 0",
+				None,
+				None,
+			));
+		}
+
+		if matches!(self.sampling, Some(SamplingConfig::OneIn { n: 0 })) {
+			return Err(ConfigError::validation_error(
+				"sampling n must be greater than 0",
+				None,
+				None,
+			));
+		}
+
+		if let Some(batch) = &self.batch {
+			if self.sampling.is_some() {
+				return Err(ConfigError::validation_error(
+					"batch cannot be combined with sampling",
+					None,
+					None,
+				));
+			}
+			if batch
+				.aggregate_arg
+				.as_ref()
+				.is_some_and(|arg| arg.trim().is_empty())
+			{
+				return Err(ConfigError::validation_error(
+					"batch aggregate_arg cannot be empty",
+					None,
+					None,
+				));
+			}
+		}
+
+		for owner in &self.owners {
+			if owner.name.trim().is_empty() {
+				return Err(ConfigError::validation_error(
+					"owner name cannot be empty",
+					None,
+					None,
+				));
+			}
+			if owner.addresses.is_empty() {
+				return Err(ConfigError::validation_error(
+					format!("owner '{}' must have at least one address", owner.name),
+					None,
+					None,
+				));
+			}
+		}
+
+		// Validate per-network addresses: every watched network needs a mapping, and every
+		// mapping must refer to a watched network
+		if !self.network_addresses.is_empty() {
+			if let Some(network) = self
+				.networks
+				.iter()
+				.find(|network| !self.network_addresses.contains_key(*network))
+			{
+				return Err(ConfigError::validation_error(
+					format!("Missing network_addresses entry for network '{}'", network),
+					None,
+					None,
+				));
+			}
+			if let Some(network) = self
+				.network_addresses
+				.keys()
+				.find(|network| !self.networks.contains(network))
+			{
+				return Err(ConfigError::validation_error(
+					format!(
+						"network_addresses entry for network '{}' which is not in networks",
+						network
+					),
+					None,
+					None,
+				));
+			}
+		}
+
+		// Validate RPC overrides: each must refer to a watched network and list valid endpoints
+		for (network, rpc_urls) in &self.rpc_overrides {
+			if !self.networks.contains(network) {
+				return Err(ConfigError::validation_error(
+					format!(
+						"rpc_overrides entry for network '{}' which is not in networks",
+						network
+					),
+					None,
+					None,
+				));
+			}
+			if rpc_urls.is_empty() {
+				return Err(ConfigError::validation_error(
+					format!("rpc_overrides entry for network '{}' is empty", network),
+					None,
+					None,
+				));
+			}
+			for rpc_url in rpc_urls {
+				let type_valid = ["rpc", "ws_rpc"].contains(&rpc_url.type_.as_str());
+				let protocol_valid = ["http://", "https://", "wss://", "ws://"]
+					.iter()
+					.any(|protocol| rpc_url.url.starts_with(protocol));
+				if !type_valid || !protocol_valid || rpc_url.weight > 100 {
+					return Err(ConfigError::validation_error(
+						format!(
+							"Invalid rpc_overrides entry for network '{}': type must be rpc or \
+							 ws_rpc, URL must be http(s) or ws(s) and weight must be <= 100",
+							network
+						),
+						None,
+						None,
+					));
+				}
+			}
+		}
+
 		// Validate trigger conditions (focus on script path, timeout, and language)
 		for trigger_condition in &self.trigger_conditions {
+			if let Some(expression) = &trigger_condition.expression {
+				if !trigger_condition.script_path.is_empty() {
+					return Err(ConfigError::validation_error(
+						"Trigger condition cannot have both an expression and a script_path",
+						None,
+						None,
+					));
+				}
+				if let Err(e) = validate_match_expression(expression) {
+					return Err(ConfigError::validation_error(
+						format!("Invalid trigger condition expression: {}", e),
+						None,
+						None,
+					));
+				}
+				continue;
+			}
 			validate_script_config(
 				&trigger_condition.script_path,
 				&trigger_condition.language,
@@ -181,6 +509,14 @@ impl ConfigLoader for Monitor {
 			)?;
 		}
 
+		if let Some(post_processing) = &self.post_processing {
+			validate_script_config(
+				&post_processing.script_path,
+				&post_processing.language,
+				&post_processing.timeout_ms,
+			)?;
+		}
+
 		// Log a warning if the monitor uses an insecure protocol
 		self.validate_protocol();
 
@@ -193,7 +529,11 @@ impl ConfigLoader for Monitor {
 	fn validate_protocol(&self) {
 		// Check script file permissions on Unix systems
 		#[cfg(unix)]
-		for condition in &self.trigger_conditions {
+		for condition in self
+			.trigger_conditions
+			.iter()
+			.filter(|c| !c.is_expression())
+		{
 			use std::os::unix::fs::PermissionsExt;
 			if let Ok(metadata) = std::fs::metadata(&condition.script_path) {
 				let permissions = metadata.permissions();
@@ -239,7 +579,11 @@ impl ConfigLoader for Monitor {
 mod tests {
 	use super::*;
 	use crate::{
-		models::core::{ScriptLanguage, TransactionStatus},
+		models::{
+			core::{ScriptLanguage, TransactionStatus},
+			ChainConfiguration, EVMAddressPoisoningConfig, EVMFirstInteractionConfig,
+			EVMHeuristicsConfig, EVMMonitorConfig, EVMNonceAnomalyConfig,
+		},
 		utils::tests::builders::evm::monitor::MonitorBuilder,
 	};
 	use std::collections::HashMap;
@@ -402,6 +746,376 @@ mod tests {
 		assert!(invalid_monitor.validate().is_err());
 	}
 
+	#[test]
+	fn test_validate_monitor_heuristics_config() {
+		let monitor_with = |min_amount: &str| {
+			MonitorBuilder::new()
+				.name("TestMonitor")
+				.chain_configurations(vec![ChainConfiguration {
+					evm: Some(EVMMonitorConfig {
+						heuristics: Some(EVMHeuristicsConfig {
+							flashloan: true,
+							flashloan_min_amount: Some(min_amount.to_string()),
+							sandwich: false,
+						}),
+						..Default::default()
+					}),
+					..Default::default()
+				}])
+				.build()
+		};
+
+		assert!(monitor_with("1000000000000000000").validate().is_ok());
+		assert!(monitor_with("0x0de0b6b3a7640000").validate().is_ok());
+		assert!(monitor_with("one million").validate().is_err());
+	}
+
+	#[test]
+	fn test_validate_monitor_first_interaction_config() {
+		let monitor_with = |lookback_blocks: u64| {
+			MonitorBuilder::new()
+				.name("TestMonitor")
+				.chain_configurations(vec![ChainConfiguration {
+					evm: Some(EVMMonitorConfig {
+						first_interaction: Some(EVMFirstInteractionConfig { lookback_blocks }),
+						..Default::default()
+					}),
+					..Default::default()
+				}])
+				.build()
+		};
+
+		assert!(monitor_with(1000).validate().is_ok());
+		assert!(monitor_with(0).validate().is_err());
+	}
+
+	#[test]
+	fn test_validate_monitor_internal_transfers() {
+		let monitor_with = |funds_flow: bool| {
+			MonitorBuilder::new()
+				.name("TestMonitor")
+				.chain_configurations(vec![ChainConfiguration {
+					evm: Some(EVMMonitorConfig {
+						funds_flow,
+						internal_transfers: true,
+						..Default::default()
+					}),
+					..Default::default()
+				}])
+				.build()
+		};
+
+		assert!(monitor_with(true).validate().is_ok());
+		assert!(monitor_with(false).validate().is_err());
+	}
+
+	#[test]
+	fn test_validate_monitor_start_block() {
+		let monitor = MonitorBuilder::new()
+			.name("TestMonitor")
+			.networks(vec!["ethereum_mainnet".to_string()])
+			.start_block(19_000_000)
+			.build();
+		assert!(monitor.validate().is_ok());
+
+		let monitor = MonitorBuilder::new()
+			.name("TestMonitor")
+			.networks(vec![
+				"ethereum_mainnet".to_string(),
+				"polygon_mainnet".to_string(),
+			])
+			.start_block(19_000_000)
+			.build();
+		assert!(monitor.validate().is_err());
+	}
+
+	#[test]
+	fn test_validate_monitor_canary() {
+		let monitor = MonitorBuilder::new()
+			.name("TestMonitor")
+			.networks(vec!["ethereum_mainnet".to_string()])
+			.canary("ethereum_mainnet", 19_000_000, 3600)
+			.build();
+		assert!(monitor.validate().is_ok());
+
+		let monitor = MonitorBuilder::new()
+			.name("TestMonitor")
+			.networks(vec!["ethereum_mainnet".to_string()])
+			.canary("polygon_mainnet", 19_000_000, 3600)
+			.build();
+		assert!(monitor.validate().is_err());
+
+		let monitor = MonitorBuilder::new()
+			.name("TestMonitor")
+			.networks(vec!["ethereum_mainnet".to_string()])
+			.canary("ethereum_mainnet", 19_000_000, 0)
+			.build();
+		assert!(monitor.validate().is_err());
+	}
+
+	#[test]
+	fn test_validate_monitor_activate_on_deployment() {
+		let address = "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48";
+		let monitor = MonitorBuilder::new()
+			.name("TestMonitor")
+			.networks(vec!["ethereum_mainnet".to_string()])
+			.activate_on_deployment("ethereum_mainnet", address)
+			.build();
+		assert!(monitor.validate().is_ok());
+
+		let monitor = MonitorBuilder::new()
+			.name("TestMonitor")
+			.networks(vec!["ethereum_mainnet".to_string()])
+			.activate_on_deployment("polygon_mainnet", address)
+			.build();
+		assert!(monitor.validate().is_err());
+
+		let monitor = MonitorBuilder::new()
+			.name("TestMonitor")
+			.networks(vec!["ethereum_mainnet".to_string()])
+			.activate_on_deployment("ethereum_mainnet", " ")
+			.build();
+		assert!(monitor.validate().is_err());
+	}
+
+	#[test]
+	fn test_validate_monitor_abi_drift() {
+		let monitor = MonitorBuilder::new()
+			.name("TestMonitor")
+			.abi_drift(3600, vec![])
+			.build();
+		assert!(monitor.validate().is_ok());
+
+		let monitor = MonitorBuilder::new()
+			.name("TestMonitor")
+			.abi_drift(0, vec![])
+			.build();
+		assert!(monitor.validate().is_err());
+	}
+
+	#[test]
+	fn test_validate_monitor_sampling() {
+		for sampling in [
+			SamplingConfig::OneIn { n: 100 },
+			SamplingConfig::FirstPerBlock,
+		] {
+			let monitor = MonitorBuilder::new()
+				.name("TestMonitor")
+				.sampling(sampling)
+				.build();
+			assert!(monitor.validate().is_ok());
+		}
+
+		let monitor = MonitorBuilder::new()
+			.name("TestMonitor")
+			.sampling(SamplingConfig::OneIn { n: 0 })
+			.build();
+		assert!(monitor.validate().is_err());
+	}
+
+	#[test]
+	fn test_validate_monitor_batch() {
+		let monitor = MonitorBuilder::new()
+			.name("TestMonitor")
+			.batch(Some("value"))
+			.build();
+		assert!(monitor.validate().is_ok());
+
+		let monitor = MonitorBuilder::new()
+			.name("TestMonitor")
+			.batch(Some(" "))
+			.build();
+		assert!(monitor.validate().is_err());
+
+		let monitor = MonitorBuilder::new()
+			.name("TestMonitor")
+			.batch(None)
+			.sampling(SamplingConfig::FirstPerBlock)
+			.build();
+		assert!(monitor.validate().is_err());
+	}
+
+	#[test]
+	fn test_validate_monitor_owners() {
+		let monitor = MonitorBuilder::new()
+			.name("TestMonitor")
+			.owner("DeFi", vec!["0x1"], Some("@defi"))
+			.build();
+		assert!(monitor.validate().is_ok());
+
+		let monitor = MonitorBuilder::new()
+			.name("TestMonitor")
+			.owner(" ", vec!["0x1"], None)
+			.build();
+		assert!(monitor.validate().is_err());
+
+		let monitor = MonitorBuilder::new()
+			.name("TestMonitor")
+			.owner("DeFi", vec![], None)
+			.build();
+		assert!(monitor.validate().is_err());
+	}
+
+	#[test]
+	fn test_validate_monitor_network_addresses() {
+		let networks = vec!["ethereum_mainnet".to_string(), "base_mainnet".to_string()];
+		let monitor = MonitorBuilder::new()
+			.name("TestMonitor")
+			.networks(networks.clone())
+			.network_address(
+				"ethereum_mainnet",
+				"0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48",
+			)
+			.network_address("base_mainnet", "0x833589fcd6edb6e08f4c7c32d4f71b54bda02913")
+			.build();
+		assert!(monitor.validate().is_ok());
+
+		// A watched network without mapping
+		let monitor = MonitorBuilder::new()
+			.name("TestMonitor")
+			.networks(networks.clone())
+			.network_address(
+				"ethereum_mainnet",
+				"0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48",
+			)
+			.build();
+		assert!(monitor.validate().is_err());
+
+		// A mapping for a network that is not watched
+		let monitor = MonitorBuilder::new()
+			.name("TestMonitor")
+			.networks(vec!["ethereum_mainnet".to_string()])
+			.network_address(
+				"ethereum_mainnet",
+				"0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48",
+			)
+			.network_address("base_mainnet", "0x833589fcd6edb6e08f4c7c32d4f71b54bda02913")
+			.build();
+		assert!(monitor.validate().is_err());
+	}
+
+	#[test]
+	fn test_validate_monitor_rpc_overrides() {
+		let monitor = MonitorBuilder::new()
+			.name("TestMonitor")
+			.rpc_override("ethereum_mainnet", "https://trace.example.com")
+			.build();
+		assert!(monitor.validate().is_ok());
+
+		let monitor = MonitorBuilder::new()
+			.name("TestMonitor")
+			.rpc_override("base_mainnet", "https://trace.example.com")
+			.build();
+		assert!(monitor
+			.validate()
+			.unwrap_err()
+			.to_string()
+			.contains("rpc_overrides entry for network 'base_mainnet' which is not in networks"));
+
+		let monitor = MonitorBuilder::new()
+			.name("TestMonitor")
+			.rpc_override("ethereum_mainnet", "ftp://trace.example.com")
+			.build();
+		assert!(monitor.validate().is_err());
+
+		let mut monitor = MonitorBuilder::new().name("TestMonitor").build();
+		monitor
+			.rpc_overrides
+			.insert("ethereum_mainnet".to_string(), Vec::new());
+		assert!(monitor.validate().is_err());
+	}
+
+	#[test]
+	fn test_validate_monitor_trigger_expression() {
+		let monitor = MonitorBuilder::new()
+			.name("TestMonitor")
+			.trigger_expression("transaction.value > 1000000")
+			.build();
+		assert!(monitor.validate().is_ok());
+
+		let monitor = MonitorBuilder::new()
+			.name("TestMonitor")
+			.trigger_expression("transaction.value >")
+			.build();
+		assert!(monitor.validate().is_err());
+
+		// An expression condition cannot also reference a script
+		let mut monitor = MonitorBuilder::new()
+			.name("TestMonitor")
+			.trigger_expression("transaction.value > 1000000")
+			.build();
+		monitor.trigger_conditions[0].script_path = "test_script.py".to_string();
+		assert!(monitor.validate().is_err());
+	}
+
+	#[test]
+	fn test_validate_monitor_address_poisoning_config() {
+		let monitor_with = |config: EVMAddressPoisoningConfig| {
+			MonitorBuilder::new()
+				.name("TestMonitor")
+				.chain_configurations(vec![ChainConfiguration {
+					evm: Some(EVMMonitorConfig {
+						address_poisoning: Some(config),
+						..Default::default()
+					}),
+					..Default::default()
+				}])
+				.build()
+		};
+
+		assert!(monitor_with(EVMAddressPoisoningConfig::default())
+			.validate()
+			.is_ok());
+		assert!(monitor_with(EVMAddressPoisoningConfig {
+			prefix_length: 0,
+			suffix_length: 0,
+			..Default::default()
+		})
+		.validate()
+		.is_err());
+		assert!(monitor_with(EVMAddressPoisoningConfig {
+			known_addresses: vec!["0xnot-an-address".to_string()],
+			..Default::default()
+		})
+		.validate()
+		.is_err());
+	}
+
+	#[test]
+	fn test_validate_monitor_nonce_anomaly_config() {
+		let monitor_with = |config: EVMNonceAnomalyConfig| {
+			MonitorBuilder::new()
+				.name("TestMonitor")
+				.chain_configurations(vec![ChainConfiguration {
+					evm: Some(EVMMonitorConfig {
+						nonce_anomaly: Some(config),
+						..Default::default()
+					}),
+					..Default::default()
+				}])
+				.build()
+		};
+
+		assert!(monitor_with(EVMNonceAnomalyConfig {
+			dormant_after_blocks: Some(100_000),
+			dormant_addresses: vec!["0x0000000000000000000000000000000000000001".to_string()],
+		})
+		.validate()
+		.is_ok());
+		assert!(monitor_with(EVMNonceAnomalyConfig {
+			dormant_after_blocks: Some(0),
+			..Default::default()
+		})
+		.validate()
+		.is_err());
+		assert!(monitor_with(EVMNonceAnomalyConfig {
+			dormant_addresses: vec!["0xnot-an-address".to_string()],
+			..Default::default()
+		})
+		.validate()
+		.is_err());
+	}
+
 	#[test]
 	fn test_validate_monitor_with_trigger_conditions() {
 		// Create a temporary directory and script file
@@ -430,6 +1144,33 @@ mod tests {
 		std::env::set_current_dir(original_dir).unwrap();
 	}
 
+	#[test]
+	fn test_validate_monitor_post_processing() {
+		let temp_dir = TempDir::new().unwrap();
+		let script_path = temp_dir.path().join("enrich.py");
+		fs::write(&script_path, "print('{}')").unwrap();
+		let script_path = script_path.to_str().unwrap();
+
+		let monitor = MonitorBuilder::new()
+			.name("TestMonitor")
+			.post_processing(script_path, ScriptLanguage::Python, None)
+			.build();
+		assert!(monitor.validate().is_ok());
+
+		// The language must match the extension of the script
+		let monitor = MonitorBuilder::new()
+			.name("TestMonitor")
+			.post_processing(script_path, ScriptLanguage::Bash, None)
+			.build();
+		assert!(monitor.validate().is_err());
+
+		let monitor = MonitorBuilder::new()
+			.name("TestMonitor")
+			.post_processing("non_existent_script.py", ScriptLanguage::Python, None)
+			.build();
+		assert!(monitor.validate().is_err());
+	}
+
 	#[test]
 	fn test_validate_monitor_with_invalid_script_path() {
 		let invalid_monitor = MonitorBuilder::new()
@@ -8,8 +8,12 @@ use std::{collections::HashMap, path::Path, str::FromStr};
 
 use crate::{
 	models::{
-		config::error::ConfigError, BlockChainType, ConfigLoader, MaxPastBlocks, Network,
-		SecretValue,
+		config::{
+			deprecation::{parse_config, NETWORK_RENAMES},
+			error::ConfigError,
+			DEFAULT_CONFIG_DIR,
+		},
+		BlockChainType, ConfigLoader, MaxPastBlocks, Network, SecretValue,
 	},
 	utils::{get_cron_interval_ms, normalize_string},
 };
@@ -65,7 +69,8 @@ impl ConfigLoader for Network {
 	where
 		T: FromIterator<(String, Self)>,
 	{
-		let network_dir = path.unwrap_or(Path::new("config/networks"));
+		let default_dir = Path::new(DEFAULT_CONFIG_DIR).join("networks");
+		let network_dir = path.unwrap_or(&default_dir);
 		let mut pairs = Vec::new();
 
 		if !network_dir.exists() {
@@ -128,7 +133,7 @@ impl ConfigLoader for Network {
 	///
 	/// Reads and parses a single JSON file as a network configuration.
 	async fn load_from_path(path: &std::path::Path) -> Result<Self, ConfigError> {
-		let file = std::fs::File::open(path).map_err(|e| {
+		let content = std::fs::read_to_string(path).map_err(|e| {
 			ConfigError::file_error(
 				format!("failed to open network config file: {}", e),
 				Some(Box::new(e)),
@@ -138,16 +143,17 @@ impl ConfigLoader for Network {
 				)])),
 			)
 		})?;
-		let mut config: Network = serde_json::from_reader(file).map_err(|e| {
-			ConfigError::parse_error(
-				format!("failed to parse network config: {}", e),
-				Some(Box::new(e)),
-				Some(HashMap::from([(
-					"path".to_string(),
-					path.display().to_string(),
-				)])),
-			)
-		})?;
+		let mut config: Network =
+			parse_config(&content, NETWORK_RENAMES, "", path).map_err(|e| {
+				ConfigError::parse_error(
+					format!("failed to parse network config: {}", e),
+					Some(Box::new(e)),
+					Some(HashMap::from([(
+						"path".to_string(),
+						path.display().to_string(),
+					)])),
+				)
+			})?;
 
 		// Resolve secrets before validating
 		config = config.resolve_secrets().await?;
@@ -234,6 +240,17 @@ impl ConfigLoader for Network {
 					None,
 				));
 			}
+
+			if let Some(provider) = &rpc_url.provider {
+				if provider.name.trim().is_empty() || provider.max_requests_per_second == Some(0) {
+					return Err(ConfigError::validation_error(
+						"RPC provider must have a name and a max_requests_per_second greater \
+						 than 0",
+						None,
+						None,
+					));
+				}
+			}
 		}
 
 		// Validate block time
@@ -302,6 +319,129 @@ impl ConfigLoader for Network {
 			_ => {}
 		}
 
+		// Validate the RPC consistency check job
+		if let Some(config) = self.rpc_consistency_check.as_ref().filter(|c| c.enabled) {
+			if self.network_type != BlockChainType::EVM {
+				return Err(ConfigError::validation_error(
+					"rpc_consistency_check is only supported on EVM networks",
+					None,
+					None,
+				));
+			}
+			if let Err(e) = cron::Schedule::from_str(&config.cron_schedule) {
+				return Err(ConfigError::validation_error(
+					format!("Invalid rpc_consistency_check cron_schedule: {}", e),
+					None,
+					None,
+				));
+			}
+		}
+
+		// Validate the block processing watchdog
+		if let Some(config) = self.watchdog.as_ref().filter(|c| c.enabled) {
+			if config.stall_timeout_ms == 0 {
+				return Err(ConfigError::validation_error(
+					"watchdog stall_timeout_ms must be greater than 0",
+					None,
+					None,
+				));
+			}
+			if config.stall_timeout_ms < self.block_time_ms {
+				tracing::warn!(
+					"Network '{}' watchdog stall_timeout_ms ({}) is shorter than block_time_ms ({}), \
+					 healthy runs may be restarted",
+					self.slug,
+					config.stall_timeout_ms,
+					self.block_time_ms
+				);
+			}
+		}
+
+		// Validate the poison block policy
+		if let Some(policy) = self.poison_block_policy.as_ref().filter(|p| p.enabled) {
+			if policy.max_attempts == 0 {
+				return Err(ConfigError::validation_error(
+					"poison_block_policy max_attempts must be greater than 0",
+					None,
+					None,
+				));
+			}
+		}
+
+		// Validate the block guardrails
+		if let Some(guardrails) = &self.block_guardrails {
+			if guardrails.max_block_transactions == 0 || guardrails.chunk_size == 0 {
+				return Err(ConfigError::validation_error(
+					"block_guardrails max_block_transactions and chunk_size must be greater than 0",
+					None,
+					None,
+				));
+			}
+		}
+
+		// Validate the restart policy
+		if let Some(policy) = &self.restart_policy {
+			if policy.max_consecutive_failures == 0 || policy.backoff_ms == 0 {
+				return Err(ConfigError::validation_error(
+					"restart_policy max_consecutive_failures and backoff_ms must be greater than 0",
+					None,
+					None,
+				));
+			}
+			if policy.max_backoff_ms < policy.backoff_ms {
+				return Err(ConfigError::validation_error(
+					"restart_policy max_backoff_ms must not be less than backoff_ms",
+					None,
+					None,
+				));
+			}
+		}
+
+		// Validate the trace budget
+		if let Some(budget) = &self.trace_budget {
+			if self.network_type != BlockChainType::EVM {
+				return Err(ConfigError::validation_error(
+					"trace_budget is only supported on EVM networks",
+					None,
+					None,
+				));
+			}
+			if budget.max_per_block == Some(0)
+				|| budget.max_per_minute == Some(0)
+				|| budget.sample_one_in == Some(0)
+			{
+				return Err(ConfigError::validation_error(
+					"trace_budget max_per_block, max_per_minute and sample_one_in must be greater \
+					 than 0",
+					None,
+					None,
+				));
+			}
+		}
+
+		// Validate the RPC costs
+		if let Some(rpc_cost) = &self.rpc_cost {
+			let valid = |cost: &f64| cost.is_finite() && *cost >= 0.0;
+			if !valid(&rpc_cost.default) || !rpc_cost.methods.values().all(valid) {
+				return Err(ConfigError::validation_error(
+					"rpc_cost costs must be non-negative numbers",
+					None,
+					None,
+				));
+			}
+		}
+
+		// Validate the eth_getLogs limits
+		if let Some(limits) = &self.get_logs_limits {
+			if limits.max_block_range == Some(0) {
+				return Err(ConfigError::validation_error(
+					"get_logs_limits max_block_range must be greater than 0",
+					None,
+					None,
+				));
+			}
+		}
+
 		// Log a warning if the network uses an insecure protocol
 		self.validate_protocol();
 
@@ -367,8 +507,14 @@ impl ConfigLoader for Network {
 #[cfg(test)]
 mod tests {
 	use super::*;
-	use crate::{models::SecretString, utils::tests::builders::network::NetworkBuilder};
-	use std::fs;
+	use crate::{
+		models::{
+			BlockGuardrails, GetLogsLimits, PoisonBlockPolicy, RestartPolicy, RpcConsistencyConfig,
+			RpcCostConfig, SecretString, TraceBudget, WatchdogConfig,
+		},
+		utils::tests::builders::network::NetworkBuilder,
+	};
+	use std::{collections::BTreeMap, fs};
 	use tempfile::TempDir;
 	use tracing_test::traced_test;
 
@@ -501,6 +647,246 @@ mod tests {
 		));
 	}
 
+	#[test]
+	fn test_validate_rpc_consistency_check() {
+		let config = RpcConsistencyConfig {
+			enabled: true,
+			cron_schedule: "0 */1 * * * *".to_string(),
+			max_block_lag: 5,
+		};
+
+		let network = NetworkBuilder::new()
+			.rpc_consistency_check(config.clone())
+			.build();
+		assert!(network.validate().is_ok());
+
+		let network = NetworkBuilder::new()
+			.rpc_consistency_check(RpcConsistencyConfig {
+				cron_schedule: "invalid cron".to_string(),
+				..config.clone()
+			})
+			.build();
+		assert!(matches!(
+			network.validate(),
+			Err(ConfigError::ValidationError(_))
+		));
+
+		let network = NetworkBuilder::new()
+			.network_type(BlockChainType::Stellar)
+			.rpc_consistency_check(config)
+			.build();
+		assert!(matches!(
+			network.validate(),
+			Err(ConfigError::ValidationError(_))
+		));
+	}
+
+	#[test]
+	fn test_validate_watchdog() {
+		let network = NetworkBuilder::new()
+			.watchdog(WatchdogConfig {
+				enabled: true,
+				stall_timeout_ms: 300_000,
+			})
+			.build();
+		assert!(network.validate().is_ok());
+
+		let network = NetworkBuilder::new()
+			.watchdog(WatchdogConfig {
+				enabled: true,
+				stall_timeout_ms: 0,
+			})
+			.build();
+		assert!(matches!(
+			network.validate(),
+			Err(ConfigError::ValidationError(_))
+		));
+
+		// Disabled watchdogs are not validated
+		let network = NetworkBuilder::new()
+			.watchdog(WatchdogConfig {
+				enabled: false,
+				stall_timeout_ms: 0,
+			})
+			.build();
+		assert!(network.validate().is_ok());
+	}
+
+	#[test]
+	fn test_validate_poison_block_policy() {
+		let network = NetworkBuilder::new()
+			.poison_block_policy(PoisonBlockPolicy {
+				enabled: true,
+				max_attempts: 3,
+				on_block_skipped: Vec::new(),
+			})
+			.build();
+		assert!(network.validate().is_ok());
+
+		let network = NetworkBuilder::new()
+			.poison_block_policy(PoisonBlockPolicy {
+				enabled: true,
+				max_attempts: 0,
+				on_block_skipped: Vec::new(),
+			})
+			.build();
+		assert!(matches!(
+			network.validate(),
+			Err(ConfigError::ValidationError(_))
+		));
+	}
+
+	#[test]
+	fn test_validate_block_guardrails() {
+		let network = NetworkBuilder::new()
+			.block_guardrails(BlockGuardrails {
+				max_block_transactions: 1000,
+				chunk_size: 100,
+			})
+			.build();
+		assert!(network.validate().is_ok());
+
+		let network = NetworkBuilder::new()
+			.block_guardrails(BlockGuardrails {
+				max_block_transactions: 1000,
+				chunk_size: 0,
+			})
+			.build();
+		assert!(matches!(
+			network.validate(),
+			Err(ConfigError::ValidationError(_))
+		));
+	}
+
+	#[test]
+	fn test_validate_trace_budget() {
+		let budget = TraceBudget {
+			max_per_block: Some(5),
+			max_per_minute: Some(100),
+			sample_one_in: Some(10),
+		};
+		let network = NetworkBuilder::new().trace_budget(budget.clone()).build();
+		assert!(network.validate().is_ok());
+
+		let network = NetworkBuilder::new()
+			.trace_budget(TraceBudget {
+				sample_one_in: Some(0),
+				..budget.clone()
+			})
+			.build();
+		assert!(matches!(
+			network.validate(),
+			Err(ConfigError::ValidationError(_))
+		));
+
+		let network = NetworkBuilder::new()
+			.network_type(BlockChainType::Stellar)
+			.trace_budget(budget)
+			.build();
+		assert!(matches!(
+			network.validate(),
+			Err(ConfigError::ValidationError(_))
+		));
+	}
+
+	#[test]
+	fn test_validate_restart_policy() {
+		let policy = RestartPolicy {
+			max_consecutive_failures: 3,
+			backoff_ms: 10_000,
+			max_backoff_ms: 600_000,
+		};
+		let network = NetworkBuilder::new().restart_policy(policy.clone()).build();
+		assert!(network.validate().is_ok());
+
+		let network = NetworkBuilder::new()
+			.restart_policy(RestartPolicy {
+				max_consecutive_failures: 0,
+				..policy.clone()
+			})
+			.build();
+		assert!(matches!(
+			network.validate(),
+			Err(ConfigError::ValidationError(_))
+		));
+
+		let network = NetworkBuilder::new()
+			.restart_policy(RestartPolicy {
+				max_backoff_ms: 1_000,
+				..policy
+			})
+			.build();
+		assert!(matches!(
+			network.validate(),
+			Err(ConfigError::ValidationError(_))
+		));
+	}
+
+	#[test]
+	fn test_validate_rpc_cost() {
+		let network = NetworkBuilder::new()
+			.rpc_cost(RpcCostConfig {
+				default: 1.0,
+				methods: BTreeMap::from([("eth_getLogs".to_string(), 75.0)]),
+				unit: Some("CU".to_string()),
+			})
+			.build();
+		assert!(network.validate().is_ok());
+
+		let network = NetworkBuilder::new()
+			.rpc_cost(RpcCostConfig {
+				default: -1.0,
+				..Default::default()
+			})
+			.build();
+		assert!(matches!(
+			network.validate(),
+			Err(ConfigError::ValidationError(_))
+		));
+	}
+
+	#[test]
+	fn test_validate_get_logs_limits() {
+		let network = NetworkBuilder::new()
+			.get_logs_limits(GetLogsLimits {
+				max_block_range: Some(2000),
+				error_patterns: vec!["exceeds the block limit".to_string()],
+			})
+			.build();
+		assert!(network.validate().is_ok());
+
+		let network = NetworkBuilder::new()
+			.get_logs_limits(GetLogsLimits {
+				max_block_range: Some(0),
+				..Default::default()
+			})
+			.build();
+		assert!(matches!(
+			network.validate(),
+			Err(ConfigError::ValidationError(_))
+		));
+	}
+
+	#[test]
+	fn test_validate_rpc_provider() {
+		let network = NetworkBuilder::new()
+			.rpc_url("https://eth-mainnet.example.com")
+			.rpc_provider("alchemy", Some(25))
+			.build();
+		assert!(network.validate().is_ok());
+
+		for (name, max_requests_per_second) in [("", None), ("alchemy", Some(0))] {
+			let network = NetworkBuilder::new()
+				.rpc_url("https://eth-mainnet.example.com")
+				.rpc_provider(name, max_requests_per_second)
+				.build();
+			assert!(matches!(
+				network.validate(),
+				Err(ConfigError::ValidationError(_))
+			));
+		}
+	}
+
 	#[test]
 	fn test_validate_zero_max_past_blocks() {
 		let network = NetworkBuilder::new().max_past_blocks(0).build();
@@ -10,8 +10,12 @@ use std::{collections::HashMap, fs, path::Path};
 
 use crate::{
 	models::{
-		config::error::ConfigError, ConfigLoader, SecretValue, Trigger, TriggerType,
-		TriggerTypeConfig, WebhookPayloadMode,
+		config::{
+			deprecation::{parse_config, TRIGGER_RENAMES},
+			error::ConfigError,
+			DEFAULT_CONFIG_DIR,
+		},
+		ConfigLoader, SecretValue, Trigger, TriggerType, TriggerTypeConfig, WebhookPayloadMode,
 	},
 	services::trigger::validate_script_config,
 	utils::normalize_string,
@@ -111,6 +115,17 @@ impl ConfigLoader for Trigger {
 			_ => {}
 		}
 
+		if let Some(delivery_report) = &mut trigger.delivery_report {
+			let resolved_url = delivery_report.url.resolve().await.map_err(|e| {
+				ConfigError::parse_error(
+					format!("failed to resolve delivery report URL: {}", e),
+					Some(Box::new(e)),
+					None,
+				)
+			})?;
+			delivery_report.url = SecretValue::Plain(resolved_url);
+		}
+
 		Ok(trigger)
 	}
 
@@ -122,7 +137,8 @@ impl ConfigLoader for Trigger {
 	where
 		T: FromIterator<(String, Self)>,
 	{
-		let config_dir = path.unwrap_or(Path::new("config/triggers"));
+		let default_dir = Path::new(DEFAULT_CONFIG_DIR).join("triggers");
+		let config_dir = path.unwrap_or(&default_dir);
 
 		if !config_dir.exists() {
 			return Err(ConfigError::file_error(
@@ -171,7 +187,7 @@ impl ConfigLoader for Trigger {
 					)
 				})?;
 				let file_triggers: TriggerConfigFile =
-					serde_json::from_str(&content).map_err(|e| {
+					parse_config(&content, TRIGGER_RENAMES, "*", &file_path).map_err(|e| {
 						ConfigError::parse_error(
 							format!("failed to parse trigger config: {}", e),
 							Some(Box::new(e)),
@@ -220,9 +236,9 @@ impl ConfigLoader for Trigger {
 	///
 	/// Reads and parses a single JSON file as a trigger configuration.
 	async fn load_from_path(path: &Path) -> Result<Self, ConfigError> {
-		let file = std::fs::File::open(path)
+		let content = fs::read_to_string(path)
 			.map_err(|e| ConfigError::file_error(e.to_string(), None, None))?;
-		let mut config: Trigger = serde_json::from_reader(file)
+		let mut config: Trigger = parse_config(&content, TRIGGER_RENAMES, "", path)
 			.map_err(|e| ConfigError::parse_error(e.to_string(), None, None))?;
 
 		// Resolve secrets before validating
@@ -258,6 +274,7 @@ impl ConfigLoader for Trigger {
 					slack_url,
 					message,
 					retry_policy: _,
+					http_client: _,
 				} = &self.config
 				{
 					// Validate webhook URL
@@ -608,6 +625,84 @@ impl ConfigLoader for Trigger {
 			}
 		}
 
+		// Validate the chain-specific variants of the message like the message itself
+		if let Some(message) = self.config.get_message() {
+			let max_body_length = match self.trigger_type {
+				TriggerType::Telegram => Some(TELEGRAM_MAX_BODY_LENGTH),
+				TriggerType::Discord => Some(DISCORD_MAX_BODY_LENGTH),
+				_ => None,
+			};
+			let chains = &message.chains;
+			for variant in [
+				&chains.evm,
+				&chains.stellar,
+				&chains.midnight,
+				&chains.solana,
+			]
+			.into_iter()
+			.flatten()
+			{
+				if variant
+					.title
+					.as_ref()
+					.is_some_and(|title| title.trim().is_empty())
+				{
+					return Err(ConfigError::validation_error(
+						"Title cannot be empty",
+						None,
+						None,
+					));
+				}
+				if variant.body.trim().is_empty() {
+					return Err(ConfigError::validation_error(
+						"Body cannot be empty",
+						None,
+						None,
+					));
+				}
+				if let Some(max_body_length) =
+					max_body_length.filter(|max| variant.body.len() > *max)
+				{
+					return Err(ConfigError::validation_error(
+						format!(
+							"Message body should not exceed {} characters",
+							max_body_length
+						),
+						None,
+						None,
+					));
+				}
+			}
+		}
+
+		// Validate the delivery report URL
+		if let Some(delivery_report) = &self.delivery_report {
+			if !delivery_report.url.starts_with("http://")
+				&& !delivery_report.url.starts_with("https://")
+			{
+				return Err(ConfigError::validation_error(
+					"Invalid delivery report URL format",
+					None,
+					None,
+				));
+			}
+		}
+
+		// Validate the HTTP client settings of webhook-based triggers
+		if let Some(http_client) = self.config.get_http_client_config() {
+			if http_client.timeout_ms == Some(0) || http_client.connect_timeout_ms == Some(0) {
+				return Err(ConfigError::validation_error(
+					"HTTP client timeouts must be greater than 0",
+					None,
+					None,
+				));
+			}
+			http_client
+				.apply(reqwest::Client::builder())
+				.and_then(|builder| Ok(builder.build()?))
+				.map_err(|e| ConfigError::validation_error(e.to_string(), None, None))?;
+		}
+
 		// Log a warning if the trigger uses an insecure protocol
 		self.validate_protocol();
 
@@ -674,6 +769,14 @@ impl ConfigLoader for Trigger {
 				}
 			}
 		};
+
+		if self
+			.config
+			.get_http_client_config()
+			.is_some_and(|http_client| http_client.accept_invalid_certs)
+		{
+			tracing::warn!("Trigger '{}' accepts invalid TLS certificates", self.name);
+		}
 	}
 
 	fn validate_uniqueness(
@@ -705,10 +808,10 @@ impl ConfigLoader for Trigger {
 #[cfg(test)]
 mod tests {
 	use super::*;
-	use crate::models::NotificationMessage;
 	use crate::models::{core::Trigger, ScriptLanguage, SecretString};
+	use crate::models::{BlockChainType, ChainMessages, NotificationMessage};
 	use crate::utils::tests::builders::trigger::TriggerBuilder;
-	use crate::utils::RetryConfig;
+	use crate::utils::{HttpClientConfig, RetryConfig};
 	use std::{fs::File, io::Write, os::unix::fs::PermissionsExt};
 	use tempfile::TempDir;
 	use tracing_test::traced_test;
@@ -975,6 +1078,130 @@ mod tests {
 		assert!(valid_raw_with_message.validate().is_ok());
 	}
 
+	#[test]
+	fn test_webhook_http_client_validation() {
+		let slow_endpoint = HttpClientConfig {
+			timeout_ms: Some(60_000),
+			connect_timeout_ms: Some(30_000),
+			proxy: Some("http://proxy.internal:3128".to_string()),
+			..Default::default()
+		};
+		let trigger = TriggerBuilder::new()
+			.name("internal_webhook")
+			.webhook("https://internal.example.com/alerts")
+			.http_client(slow_endpoint)
+			.build();
+		assert!(trigger.validate().is_ok());
+
+		let zero_timeout = TriggerBuilder::new()
+			.name("internal_webhook")
+			.webhook("https://internal.example.com/alerts")
+			.http_client(HttpClientConfig {
+				timeout_ms: Some(0),
+				..Default::default()
+			})
+			.build();
+		assert!(zero_timeout.validate().is_err());
+
+		let missing_ca = TriggerBuilder::new()
+			.name("internal_slack")
+			.slack("https://hooks.slack.com/services/A/B/C")
+			.http_client(HttpClientConfig {
+				ca_cert_path: Some("/nonexistent/ca.pem".to_string()),
+				..Default::default()
+			})
+			.build();
+		assert!(missing_ca.validate().is_err());
+
+		let config: TriggerTypeConfig = serde_json::from_value(serde_json::json!({
+			"url": {"type": "plain", "value": "https://internal.example.com/alerts"},
+			"method": "POST",
+			"message": {"title": "Alert", "body": "Body"},
+			"http_client": {"timeout_ms": 30000, "accept_invalid_certs": true}
+		}))
+		.unwrap();
+		let http_client = config.get_http_client_config().unwrap();
+		assert_eq!(http_client.timeout_ms, Some(30000));
+		assert!(http_client.accept_invalid_certs);
+	}
+
+	#[test]
+	fn test_delivery_report_validation() {
+		let trigger = TriggerBuilder::new()
+			.name("slack_alerts")
+			.slack("https://hooks.slack.com/services/A/B/C")
+			.delivery_report("https://slo.example.com/deliveries", true)
+			.build();
+		assert!(trigger.validate().is_ok());
+
+		let invalid_url = TriggerBuilder::new()
+			.name("slack_alerts")
+			.slack("https://hooks.slack.com/services/A/B/C")
+			.delivery_report("slo.example.com/deliveries", false)
+			.build();
+		assert!(invalid_url.validate().is_err());
+
+		let trigger: Trigger = serde_json::from_value(serde_json::json!({
+			"name": "Slack Alerts",
+			"trigger_type": "slack",
+			"config": {
+				"slack_url": {"type": "plain", "value": "https://hooks.slack.com/services/A/B/C"},
+				"message": {"title": "Alert", "body": "Body"}
+			},
+			"delivery_report": {"url": {"type": "plain", "value": "https://slo.example.com"}}
+		}))
+		.unwrap();
+		assert!(!trigger.delivery_report.unwrap().failures_only);
+	}
+
+	#[test]
+	fn test_chain_message_validation() {
+		let config: TriggerTypeConfig = serde_json::from_value(serde_json::json!({
+			"discord_url": {"type": "plain", "value": "https://discord.com/api/webhooks/1/a"},
+			"message": {
+				"title": "Alert",
+				"body": "${transaction.hash}",
+				"chains": {
+					"evm": {"body": "${transaction.hash} from ${transaction.from}"},
+					"solana": {"title": "Solana alert", "body": "${transaction.signature}"}
+				}
+			}
+		}))
+		.unwrap();
+		let message = config.get_message().unwrap();
+		assert!(message.chains.stellar.is_none());
+		assert_eq!(
+			message.chains.solana.as_ref().unwrap().title.as_deref(),
+			Some("Solana alert")
+		);
+		let trigger = Trigger {
+			name: "discord_alerts".to_string(),
+			trigger_type: TriggerType::Discord,
+			config,
+			parameters: Default::default(),
+			delivery_report: None,
+		};
+		assert!(trigger.validate().is_ok());
+
+		let empty_variant = TriggerBuilder::new()
+			.name("slack_alerts")
+			.slack("https://hooks.slack.com/services/A/B/C")
+			.chain_message(BlockChainType::Stellar, None, " ")
+			.build();
+		assert!(empty_variant.validate().is_err());
+
+		let long_variant = TriggerBuilder::new()
+			.name("discord_alerts")
+			.discord("https://discord.com/api/webhooks/1/a")
+			.chain_message(
+				BlockChainType::EVM,
+				Some("Alert"),
+				&"z".repeat(DISCORD_MAX_BODY_LENGTH + 1),
+			)
+			.build();
+		assert!(long_variant.validate().is_err());
+	}
+
 	#[test]
 	fn test_webhook_payload_mode_serialization() {
 		use crate::models::WebhookPayloadMode;
@@ -996,6 +1223,10 @@ mod tests {
 
 		assert_eq!(deserialized_template, WebhookPayloadMode::Template);
 		assert_eq!(deserialized_raw, WebhookPayloadMode::Raw);
+		assert_eq!(
+			serde_json::from_str::<WebhookPayloadMode>("\"normalized\"").unwrap(),
+			WebhookPayloadMode::Normalized
+		);
 
 		// Test default
 		assert_eq!(WebhookPayloadMode::default(), WebhookPayloadMode::Template);
@@ -1035,6 +1266,56 @@ mod tests {
 		assert!(invalid_body.validate().is_err());
 	}
 
+	#[test]
+	fn test_trigger_with_parameters() {
+		use crate::utils::tests::builders::evm::monitor::MonitorBuilder;
+
+		let trigger = TriggerBuilder::new()
+			.name("team_webhook")
+			.webhook("https://alerts.example.com/${params.team}")
+			.webhook_headers(HashMap::from([(
+				"X-Team".to_string(),
+				"${params.team}".to_string(),
+			)]))
+			.parameter("team", "security")
+			.build();
+		let destination = |trigger: &Trigger| match &trigger.config {
+			TriggerTypeConfig::Webhook { url, headers, .. } => (
+				url.as_ref().to_string(),
+				headers.as_ref().unwrap()["X-Team"].clone(),
+			),
+			_ => unreachable!(),
+		};
+
+		// Monitors not setting the parameter get the default of the trigger
+		let monitor = MonitorBuilder::new().build();
+		assert_eq!(
+			destination(&trigger.with_parameters(&monitor)),
+			(
+				"https://alerts.example.com/security".to_string(),
+				"security".to_string()
+			)
+		);
+
+		let monitor = MonitorBuilder::new()
+			.trigger_param("team_webhook", "team", "defi")
+			.build();
+		let parameterized = trigger.with_parameters(&monitor);
+		assert_eq!(
+			destination(&parameterized),
+			(
+				"https://alerts.example.com/defi".to_string(),
+				"defi".to_string()
+			)
+		);
+		assert!(parameterized.validate().is_ok());
+		// The shared trigger is unchanged
+		assert_eq!(
+			destination(&trigger).0,
+			"https://alerts.example.com/${params.team}"
+		);
+	}
+
 	#[test]
 	fn test_telegram_trigger_validation() {
 		let valid_trigger = TriggerBuilder::new()
@@ -1507,9 +1788,13 @@ mod tests {
 				message: NotificationMessage {
 					title: "Test".to_string(),
 					body: "x".repeat(TELEGRAM_MAX_BODY_LENGTH + 1), // Exceeds max length
+					chains: ChainMessages::default(),
 				},
 				retry_policy: RetryConfig::default(),
+				http_client: HttpClientConfig::default(),
 			},
+			parameters: Default::default(),
+			delivery_report: None,
 		};
 		assert!(max_body_length.validate().is_err());
 	}
@@ -1526,9 +1811,13 @@ mod tests {
 				message: NotificationMessage {
 					title: "Test".to_string(),
 					body: "z".repeat(DISCORD_MAX_BODY_LENGTH + 1), // Exceeds max length
+					chains: ChainMessages::default(),
 				},
 				retry_policy: RetryConfig::default(),
+				http_client: HttpClientConfig::default(),
 			},
+			parameters: Default::default(),
+			delivery_report: None,
 		};
 		assert!(max_body_length.validate().is_err());
 	}
@@ -1584,4 +1873,50 @@ mod tests {
 			assert!(err.message.contains("Duplicate trigger name found"));
 		}
 	}
+
+	#[tokio::test]
+	async fn test_load_all_deprecated_webhook_url() {
+		let temp_dir = TempDir::new().unwrap();
+		let trigger_config = r#"{
+			"legacy_slack": {
+				"name": "LegacySlack",
+				"trigger_type": "slack",
+				"config": {
+					"webhook_url": {
+						"type": "plain",
+						"value": "https://hooks.slack.com/services/xxx"
+					},
+					"message": {
+						"title": "Test",
+						"body": "Test"
+					}
+				}
+			}
+		}"#;
+		fs::write(temp_dir.path().join("legacy.json"), trigger_config).unwrap();
+
+		let triggers: HashMap<String, Trigger> =
+			Trigger::load_all(Some(temp_dir.path())).await.unwrap();
+		match &triggers["legacy_slack"].config {
+			TriggerTypeConfig::Slack { slack_url, .. } => {
+				assert_eq!(slack_url.as_ref(), "https://hooks.slack.com/services/xxx")
+			}
+			config => panic!("Expected a Slack config, got {:?}", config),
+		}
+
+		// The previous and current names cannot be set together
+		let conflicting = trigger_config.replace(
+			r#""message""#,
+			r#""slack_url": {"type": "plain", "value": "https://hooks.slack.com/services/yyy"}, "message""#,
+		);
+		fs::write(temp_dir.path().join("legacy.json"), conflicting).unwrap();
+		let result: Result<HashMap<String, Trigger>, ConfigError> =
+			Trigger::load_all(Some(temp_dir.path())).await;
+		match result {
+			Err(ConfigError::ParseError(err)) => {
+				assert!(err.message.contains("legacy_slack.config.webhook_url"))
+			}
+			result => panic!("Expected a parse error, got {:?}", result),
+		}
+	}
 }
@@ -4,16 +4,29 @@
 //! - Monitors: Configuration for watching blockchain activity
 //! - Networks: Blockchain network definitions and connection details
 //! - Triggers: Actions to take when monitored conditions are met
+//! - Normalized matches: Chain-agnostic representation of matches for downstream consumers
 
 mod monitor;
 mod network;
+mod normalized_match;
 mod trigger;
 
 pub use monitor::{
-	AddressWithSpec, EventCondition, FunctionCondition, MatchConditions, Monitor, ScriptLanguage,
-	TransactionCondition, TransactionStatus, TriggerConditions, SCRIPT_LANGUAGE_EXTENSIONS,
+	AbiDriftConfig, AddressOwner, AddressWithSpec, BatchConfig, CanaryConfig, DeploymentDependency,
+	EventCondition, FunctionCondition, MatchConditions, Monitor, MonitorSeverity,
+	PostProcessingScript, SamplingConfig, ScriptLanguage, TransactionCondition, TransactionStatus,
+	TriggerConditions, SCRIPT_LANGUAGE_EXTENSIONS,
+};
+pub use network::{
+	BlockGuardrails, BlockRecoveryConfig, GetLogsLimits, MaxPastBlocks, Network, PoisonBlockPolicy,
+	RestartPolicy, RpcConsistencyConfig, RpcCostConfig, RpcProvider, RpcUrl, TraceBudget,
+	WatchdogConfig,
+};
+pub use normalized_match::{
+	NormalizedCall, NormalizedMatch, NormalizedMonitor, NormalizedTransaction,
+	NORMALIZED_MATCH_EXTENSIONS, NORMALIZED_MATCH_SCHEMA_VERSION,
 };
-pub use network::{BlockRecoveryConfig, MaxPastBlocks, Network, RpcUrl};
 pub use trigger::{
-	NotificationMessage, Trigger, TriggerType, TriggerTypeConfig, WebhookPayloadMode,
+	ChainMessage, ChainMessages, DeliveryReportConfig, NotificationMessage, Trigger, TriggerType,
+	TriggerTypeConfig, WebhookPayloadMode,
 };
@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
-use crate::models::{blockchain::ContractSpec, ChainConfiguration};
+use crate::models::{blockchain::ContractSpec, ChainConfiguration, Network, RpcUrl};
 
 /// Configuration for monitoring specific blockchain activity.
 ///
@@ -26,6 +27,11 @@ pub struct Monitor {
 	/// Contract addresses to monitor, optionally with their contract specs
 	pub addresses: Vec<AddressWithSpec>,
 
+	/// Contract addresses to monitor on specific networks, keyed by network slug, in
+	/// addition to `addresses`
+	#[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+	pub network_addresses: BTreeMap<String, Vec<AddressWithSpec>>,
+
 	/// Conditions that should trigger this monitor
 	pub match_conditions: MatchConditions,
 
@@ -38,6 +44,138 @@ pub struct Monitor {
 	/// Chain-specific configurations
 	#[serde(default)]
 	pub chain_configurations: Vec<ChainConfiguration>,
+
+	/// Block from which the monitor is applied, also used as the network's starting block
+	/// when no block has been processed yet
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub start_block: Option<u64>,
+
+	/// Severity of the monitor, used to prioritize its notifications when they queue up
+	#[serde(default, skip_serializing_if = "MonitorSeverity::is_default")]
+	pub severity: MonitorSeverity,
+
+	/// Whether matches are only recorded in the match journal and metrics, without sending
+	/// notifications
+	#[serde(default, skip_serializing_if = "std::ops::Not::not")]
+	pub observe: bool,
+
+	/// Known block the monitor is periodically checked against, proving that the pipeline
+	/// still matches and notifies end-to-end
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub canary: Option<CanaryConfig>,
+
+	/// Contract whose deployment activates the monitor; until code is found at its address,
+	/// blocks are not filtered for the monitor
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub activate_on_deployment: Option<DeploymentDependency>,
+
+	/// Periodic comparison of the configured contract specs with the verified interfaces of
+	/// the deployed contracts
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub abi_drift: Option<AbiDriftConfig>,
+
+	/// Sampling of the notifications of high-volume monitors; every match is still journaled
+	/// and counted, but only sampled matches are notified
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub sampling: Option<SamplingConfig>,
+
+	/// Aggregation of the matches of the monitor in a block into a single notification
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub batch: Option<BatchConfig>,
+
+	/// Values of the parameters of the triggers, keyed by trigger name and parameter name
+	#[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+	pub trigger_params: BTreeMap<String, BTreeMap<String, String>>,
+
+	/// Owners of the monitored addresses, mentioned in and routed the matches on their addresses
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub owners: Vec<AddressOwner>,
+
+	/// Script run on each match before notifying, whose output is merged into the template
+	/// variables under `enrichment.*`
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub post_processing: Option<PostProcessingScript>,
+
+	/// RPC endpoints used instead of those of the network to evaluate the monitor, keyed by
+	/// network slug, e.g. a private node with the trace support the shared endpoints lack
+	#[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+	pub rpc_overrides: BTreeMap<String, Vec<RpcUrl>>,
+}
+
+impl Monitor {
+	/// Returns the contract addresses monitored on a network
+	///
+	/// # Arguments
+	/// * `network_slug` - The network
+	///
+	/// # Returns
+	/// The addresses shared by all networks followed by the addresses of the network
+	pub fn addresses_for_network(&self, network_slug: &str) -> Vec<AddressWithSpec> {
+		self.addresses
+			.iter()
+			.chain(
+				self.network_addresses
+					.get(network_slug)
+					.into_iter()
+					.flatten(),
+			)
+			.cloned()
+			.collect()
+	}
+
+	/// Returns the owner of a monitored address
+	///
+	/// # Arguments
+	/// * `address` - The address, compared case-insensitively
+	pub fn owner_of(&self, address: &str) -> Option<&AddressOwner> {
+		self.owners.iter().find(|owner| {
+			owner
+				.addresses
+				.iter()
+				.any(|owned| owned.eq_ignore_ascii_case(address))
+		})
+	}
+
+	/// Returns the triggers the matches of the monitor can be sent to: the triggers of the
+	/// monitor and those of the owners of its addresses
+	pub fn routed_triggers(&self) -> impl Iterator<Item = &String> {
+		self.triggers
+			.iter()
+			.chain(self.owners.iter().flat_map(|owner| &owner.triggers))
+	}
+
+	/// Returns the network as used to evaluate the monitor, with its RPC endpoints replaced
+	/// by those pinned by the monitor
+	///
+	/// # Arguments
+	/// * `network` - The network
+	///
+	/// # Returns
+	/// The network with the RPC overrides of the monitor, or `None` if the monitor uses the
+	/// endpoints of the network
+	pub fn rpc_network(&self, network: &Network) -> Option<Network> {
+		self.rpc_overrides
+			.get(&network.slug)
+			.map(|rpc_urls| Network {
+				rpc_urls: rpc_urls.clone(),
+				..network.clone()
+			})
+	}
+
+	/// Returns the monitor as applied to a network, with its addresses resolved
+	///
+	/// # Arguments
+	/// * `network_slug` - The network
+	pub fn for_network(&self, network_slug: &str) -> Monitor {
+		if self.network_addresses.is_empty() {
+			return self.clone();
+		}
+		Monitor {
+			addresses: self.addresses_for_network(network_slug),
+			network_addresses: BTreeMap::new(),
+			..self.clone()
+		}
+	}
 }
 
 /// Contract address with optional ABI for decoding transactions and events
@@ -98,6 +236,179 @@ pub struct TransactionCondition {
 	pub expression: Option<String>,
 }
 
+/// Canary check of a monitor
+///
+/// The block is filtered again every `interval_secs` seconds: it must produce a match, whose
+/// notifications must be sent to the triggers of the canary. Without triggers, the check is a
+/// dry run: the notifications are prepared but not sent.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct CanaryConfig {
+	/// Slug of the network of the block
+	pub network: String,
+
+	/// Block the monitor is known to match
+	pub block: u64,
+
+	/// Time between two checks, in seconds
+	#[serde(default = "CanaryConfig::default_interval_secs")]
+	pub interval_secs: u64,
+
+	/// Triggers notified of the canary matches, never the triggers of the monitor
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub triggers: Vec<String>,
+}
+
+impl CanaryConfig {
+	/// Default time between two checks, in seconds
+	pub const DEFAULT_INTERVAL_SECS: u64 = 3600;
+
+	fn default_interval_secs() -> u64 {
+		Self::DEFAULT_INTERVAL_SECS
+	}
+}
+
+/// Contract deployment a monitor waits for before becoming active
+///
+/// The address is checked for code every `interval_secs` seconds (`eth_getCode` on EVM
+/// networks, the contract instance ledger entry on Stellar networks), and before filtering the
+/// blocks of its network past the latest check. Once the contract is deployed, the monitor
+/// applies to the blocks of that network following the latest check that found no code.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct DeploymentDependency {
+	/// Slug of the network the contract is deployed on
+	pub network: String,
+
+	/// Address of the contract
+	pub address: String,
+
+	/// Time between two checks of the address, in seconds
+	#[serde(default = "DeploymentDependency::default_interval_secs")]
+	pub interval_secs: u64,
+}
+
+impl DeploymentDependency {
+	/// Default time between two checks of the address, in seconds
+	pub const DEFAULT_INTERVAL_SECS: u64 = 60;
+
+	fn default_interval_secs() -> u64 {
+		Self::DEFAULT_INTERVAL_SECS
+	}
+}
+
+/// ABI drift detection of a monitor
+///
+/// Every `interval_secs` seconds, the contract spec configured for each address is compared
+/// with the interface of the deployed contract (the verified ABI from Etherscan on EVM
+/// networks, the contract spec on Stellar networks). Functions and events that no longer match
+/// are logged, exposed by the `abi_drift_signatures` metric and notified to `triggers`.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct AbiDriftConfig {
+	/// Time between two comparisons, in seconds
+	#[serde(default = "AbiDriftConfig::default_interval_secs")]
+	pub interval_secs: u64,
+
+	/// IDs of triggers notified when a drift is detected
+	#[serde(default)]
+	pub triggers: Vec<String>,
+}
+
+impl AbiDriftConfig {
+	/// Default time between two comparisons, in seconds
+	pub const DEFAULT_INTERVAL_SECS: u64 = 86400;
+
+	fn default_interval_secs() -> u64 {
+		Self::DEFAULT_INTERVAL_SECS
+	}
+}
+
+/// Sampling of the notifications of a monitor
+///
+/// Matches that are not sampled are journaled but not notified. The notification of a sampled
+/// match carries the number of matches it stands for in the `sampling.count` variable.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(tag = "mode", rename_all = "snake_case", deny_unknown_fields)]
+pub enum SamplingConfig {
+	/// Notifies one match out of every `n` matches of a network, starting with the first
+	OneIn {
+		/// Number of matches per notification
+		n: u64,
+	},
+	/// Notifies the first match of each block
+	FirstPerBlock,
+}
+
+/// Batching of the notifications of a monitor
+///
+/// All the matches of the monitor in a block are sent as one notification, whose `functions`
+/// and `events` are those of all the matches. The notification carries the number of matched
+/// events, and the minimum, maximum and sum of the `aggregate_arg` event argument.
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct BatchConfig {
+	/// Name of a numeric event argument aggregated over the events of the block
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub aggregate_arg: Option<String>,
+}
+
+/// Owner of monitored addresses
+///
+/// The matches on the addresses carry the `owners.*` variables, and are sent to the triggers
+/// of the owner instead of those of the monitor when the owner has any.
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct AddressOwner {
+	/// Name of the owner (e.g. a team)
+	pub name: String,
+
+	/// Addresses owned
+	pub addresses: Vec<String>,
+
+	/// Mention of the owner in notifications (e.g. `<!subteam^S012AB3CD>` on Slack)
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub mention: Option<String>,
+
+	/// Triggers notified of the matches on the addresses, instead of the triggers of the monitor
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub triggers: Vec<String>,
+}
+
+/// Severity of a monitor
+#[derive(
+	Debug, Copy, Clone, Default, Deserialize, Serialize, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum MonitorSeverity {
+	Low,
+	#[default]
+	Medium,
+	High,
+	Critical,
+}
+
+impl MonitorSeverity {
+	/// Returns the level of the severity, from 0 (low) to 3 (critical)
+	pub fn level(&self) -> u8 {
+		*self as u8
+	}
+
+	/// Returns the name of the severity as used in configuration files
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			Self::Low => "low",
+			Self::Medium => "medium",
+			Self::High => "high",
+			Self::Critical => "critical",
+		}
+	}
+
+	fn is_default(&self) -> bool {
+		*self == Self::default()
+	}
+}
+
 /// Possible transaction execution states
 #[derive(Debug, Copy, Clone, Deserialize, Serialize, PartialEq)]
 #[serde(deny_unknown_fields)]
@@ -115,6 +426,7 @@ pub enum TransactionStatus {
 #[serde(deny_unknown_fields)]
 pub struct TriggerConditions {
 	/// The path to the script
+	#[serde(default)]
 	pub script_path: String,
 
 	/// The arguments of the script
@@ -122,11 +434,54 @@ pub struct TriggerConditions {
 	pub arguments: Option<Vec<String>>,
 
 	/// The language of the script
+	#[serde(default = "default_script_language")]
 	pub language: ScriptLanguage,
 
 	/// The timeout of the script
+	#[serde(default)]
 	pub timeout_ms: u32,
+
+	/// Optional inline expression evaluated against the match instead of a script
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub expression: Option<String>,
+}
+
+impl TriggerConditions {
+	/// Returns whether the condition is an inline expression rather than a script
+	pub fn is_expression(&self) -> bool {
+		self.expression.is_some()
+	}
 }
+
+/// Script enriching the matches of a monitor before they are notified
+///
+/// The script receives the same input as a trigger condition script (`monitor_match` and
+/// `args` on stdin) and prints a JSON object on its last line of output. The object is
+/// flattened into the template variables under `enrichment.*` (e.g. `${enrichment.owner}`).
+/// A match whose script fails is notified without enrichment.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct PostProcessingScript {
+	/// The path to the script
+	pub script_path: String,
+
+	/// The arguments of the script
+	#[serde(default)]
+	pub arguments: Option<Vec<String>>,
+
+	/// The language of the script
+	#[serde(default = "default_script_language")]
+	pub language: ScriptLanguage,
+
+	/// The timeout of the script
+	#[serde(default)]
+	pub timeout_ms: u32,
+}
+
+fn default_script_language() -> ScriptLanguage {
+	ScriptLanguage::Python
+}
+
 /// The possible languages of the script
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Hash, Eq)]
 pub enum ScriptLanguage {
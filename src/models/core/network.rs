@@ -3,6 +3,8 @@ use serde::{
 	Deserialize, Deserializer, Serialize, Serializer,
 };
 
+use std::collections::BTreeMap;
+
 use crate::models::{BlockChainType, SecretValue};
 
 /// Maximum number of past blocks to process for a network.
@@ -111,6 +113,162 @@ pub struct BlockRecoveryConfig {
 	pub retry_delay_ms: u64,
 }
 
+/// Configuration for the RPC consistency check job.
+///
+/// Defines parameters for the background job that compares the block hash returned by
+/// every configured RPC endpoint to detect forked or lagging providers.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct RpcConsistencyConfig {
+	/// Whether the consistency check job is enabled
+	pub enabled: bool,
+
+	/// Cron schedule for the consistency check job (e.g., "0 */1 * * * *" for every minute)
+	pub cron_schedule: String,
+
+	/// Number of blocks an endpoint may lag behind the most advanced one before a warning
+	/// is logged
+	pub max_block_lag: u64,
+}
+
+/// Configuration for the block processing watchdog.
+///
+/// Defines when a block processing run is considered stuck and cancelled so that the
+/// next scheduled run resumes from the last checkpoint.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct WatchdogConfig {
+	/// Whether the watchdog is enabled
+	pub enabled: bool,
+
+	/// Maximum duration of a block processing run in milliseconds before it is cancelled
+	pub stall_timeout_ms: u64,
+}
+
+/// Policy for blocks that repeatedly fail to be fetched or processed.
+///
+/// After `max_attempts` failed attempts of a batch, its blocks are processed one by one and
+/// those that still fail are recorded in the skip list so that the watcher can move on. Every
+/// skipped block is notified to the `on_block_skipped` triggers.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct PoisonBlockPolicy {
+	/// Whether failing blocks are skipped
+	pub enabled: bool,
+
+	/// Number of failed attempts of a batch before its failing blocks are skipped
+	pub max_attempts: u32,
+
+	/// IDs of triggers notified when a block is skipped
+	#[serde(default)]
+	pub on_block_skipped: Vec<String>,
+}
+
+/// Cost of the RPC calls made to the endpoints of a network.
+///
+/// Used to estimate the provider spend (e.g. compute units or credits) driven by the service.
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct RpcCostConfig {
+	/// Cost of a call to a method missing from `methods`
+	#[serde(default)]
+	pub default: f64,
+
+	/// Cost of a call, keyed by method name
+	#[serde(default)]
+	pub methods: BTreeMap<String, f64>,
+
+	/// Unit of the costs, for display (e.g. "CU", "credits", "USD")
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub unit: Option<String>,
+}
+
+impl RpcCostConfig {
+	/// Returns the cost of a call to a method
+	pub fn cost_of(&self, method: &str) -> f64 {
+		self.methods.get(method).copied().unwrap_or(self.default)
+	}
+}
+
+/// Limits of the `eth_getLogs` queries accepted by the providers of a network.
+///
+/// Queries spanning more than `max_block_range` blocks are split before being sent. Queries
+/// rejected for exceeding a provider limit (block range or number of results) are split in
+/// halves until accepted, whether or not `max_block_range` is set.
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct GetLogsLimits {
+	/// Maximum number of blocks of a query (e.g. 2000)
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub max_block_range: Option<u64>,
+
+	/// Additional messages (case-insensitive substrings) of the errors returned by the
+	/// providers when a query exceeds their limits
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub error_patterns: Vec<String>,
+}
+
+/// Guardrails for abnormally large blocks.
+///
+/// A block with more than `max_block_transactions` transactions is processed in chunks of
+/// `chunk_size` transactions, one chunk at a time, with its progress logged and reported to
+/// the watchdog so that the run is not cancelled while it still makes progress.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct BlockGuardrails {
+	/// Number of transactions above which a block is considered oversized
+	pub max_block_transactions: usize,
+
+	/// Number of transactions of an oversized block processed per chunk
+	#[serde(default = "default_guardrails_chunk_size")]
+	pub chunk_size: usize,
+}
+
+fn default_guardrails_chunk_size() -> usize {
+	100
+}
+
+/// Restart policy of the watcher of a network.
+///
+/// After `max_consecutive_failures` failed block processing runs in a row, the runs of the
+/// network are suspended for `backoff_ms`, doubled after every further failure up to
+/// `max_backoff_ms`, so that a network with a broken RPC or pathological blocks does not keep
+/// retrying at the expense of the other networks.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct RestartPolicy {
+	/// Number of failed runs in a row before the runs are suspended
+	pub max_consecutive_failures: u32,
+
+	/// Suspension after `max_consecutive_failures` failed runs, in milliseconds
+	pub backoff_ms: u64,
+
+	/// Maximum suspension, in milliseconds
+	pub max_backoff_ms: u64,
+}
+
+/// Budget of the transactions traced on a network
+///
+/// Tracing a transaction (`debug_traceTransaction`) is one of the most expensive RPC calls.
+/// The matched transactions of the monitors inspecting internal calls are sampled, one out of
+/// every `sample_one_in`, and the sampled ones are traced until `max_per_block` or
+/// `max_per_minute` is reached. The matches whose transaction is not traced are marked as such.
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct TraceBudget {
+	/// Maximum number of transactions traced per block
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub max_per_block: Option<u32>,
+
+	/// Maximum number of transactions traced per minute
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub max_per_minute: Option<u32>,
+
+	/// Traces one transaction out of every `sample_one_in` candidates, starting with the first
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub sample_one_in: Option<u64>,
+}
+
 /// Configuration for connecting to and interacting with a blockchain network.
 ///
 /// Defines connection details and operational parameters for a specific blockchain network.
@@ -147,11 +305,39 @@ pub struct Network {
 	/// Maximum number of past blocks to process (a number or "unlimited")
 	pub max_past_blocks: Option<MaxPastBlocks>,
 
+	/// Block to start from when no block has been processed yet, instead of the latest
+	/// confirmed block
+	pub start_block: Option<u64>,
+
 	/// Whether to store processed blocks
 	pub store_blocks: Option<bool>,
 
 	/// Configuration for missed block recovery job
 	pub recovery_config: Option<BlockRecoveryConfig>,
+
+	/// Configuration for the RPC consistency check job
+	pub rpc_consistency_check: Option<RpcConsistencyConfig>,
+
+	/// Configuration for the block processing watchdog
+	pub watchdog: Option<WatchdogConfig>,
+
+	/// Policy for skipping blocks that repeatedly fail
+	pub poison_block_policy: Option<PoisonBlockPolicy>,
+
+	/// Cost of the RPC calls, used to estimate the provider spend
+	pub rpc_cost: Option<RpcCostConfig>,
+
+	/// Limits of the `eth_getLogs` queries accepted by the providers (EVM only)
+	pub get_logs_limits: Option<GetLogsLimits>,
+
+	/// Guardrails for blocks with an abnormally large number of transactions
+	pub block_guardrails: Option<BlockGuardrails>,
+
+	/// Suspension of the watcher after repeated failures
+	pub restart_policy: Option<RestartPolicy>,
+
+	/// Budget of the transactions traced for the monitors inspecting internal calls (EVM only)
+	pub trace_budget: Option<TraceBudget>,
 }
 
 /// RPC endpoint configuration with load balancing weight
@@ -166,4 +352,24 @@ pub struct RpcUrl {
 
 	/// Weight for load balancing (0-100)
 	pub weight: u32,
+
+	/// Provider serving the endpoint, whose rate limit is shared with the endpoints of the
+	/// other networks naming the same provider
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub provider: Option<RpcProvider>,
+}
+
+/// Provider of RPC endpoints, such as a provider account used for several networks.
+///
+/// The endpoints naming the same provider share its rate limit and its rate limit backoff,
+/// whatever their network.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct RpcProvider {
+	/// Name of the provider, identifying the endpoints sharing its limits (e.g. "alchemy")
+	pub name: String,
+
+	/// Maximum number of requests per second sent to the provider across all networks
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub max_requests_per_second: Option<u32>,
 }
@@ -0,0 +1,97 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value as JsonValue};
+
+use crate::models::MonitorSeverity;
+
+/// Version of the normalized match schema
+///
+/// The version is increased whenever the shape of the schema changes, including the fields of
+/// the transaction and the keys of [`NORMALIZED_MATCH_EXTENSIONS`].
+pub const NORMALIZED_MATCH_SCHEMA_VERSION: u32 = 2;
+
+/// Keys of the chain-specific data exposed as extensions of a normalized match
+///
+/// - `block`: timestamp and age of the block (EVM)
+/// - `funds_flow`: net flows per address, when enabled for the monitor (EVM)
+/// - `trace`: status of the trace the match was produced with (EVM)
+/// - `audit`: conditions evaluated to produce the match (EVM)
+/// - `data`: completeness of the data of the ledger (Stellar)
+/// - `owners`: owner of the matched address, when configured
+pub const NORMALIZED_MATCH_EXTENSIONS: [&str; 6] =
+	["block", "funds_flow", "trace", "audit", "data", "owners"];
+
+/// Chain-agnostic representation of a match for downstream consumers
+///
+/// Unlike `MonitorMatch`, which mirrors the internal models of each chain, the normalized
+/// match has a stable shape: a common envelope, with the data specific to a chain under
+/// `extensions`.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct NormalizedMatch {
+	/// Version of the schema of the match
+	pub schema_version: u32,
+
+	/// Chain type of the network (`evm`, `stellar`, `midnight` or `solana`)
+	pub chain: String,
+
+	/// Slug of the network
+	pub network: String,
+
+	/// Monitor that matched
+	pub monitor: NormalizedMonitor,
+
+	/// Matched transaction
+	pub transaction: NormalizedTransaction,
+
+	/// Matched functions
+	pub functions: Vec<NormalizedCall>,
+
+	/// Matched events
+	pub events: Vec<NormalizedCall>,
+
+	/// Chain-specific data of the match, keyed by [`NORMALIZED_MATCH_EXTENSIONS`]
+	#[serde(default, skip_serializing_if = "Map::is_empty")]
+	pub extensions: Map<String, JsonValue>,
+}
+
+/// Monitor of a normalized match
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct NormalizedMonitor {
+	/// Name of the monitor
+	pub name: String,
+
+	/// Severity of the monitor
+	pub severity: MonitorSeverity,
+}
+
+/// Transaction of a normalized match
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct NormalizedTransaction {
+	/// Transaction hash, or signature on Solana
+	pub id: String,
+
+	/// Sender of the transaction, when known
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub from: Option<String>,
+
+	/// Recipient of the transaction, when known
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub to: Option<String>,
+
+	/// Native value transferred by the transaction, when known
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub value: Option<String>,
+
+	/// Fee paid by the transaction, when its receipt was fetched
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub fee: Option<String>,
+}
+
+/// Matched function or event of a normalized match
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct NormalizedCall {
+	/// Signature of the function or event
+	pub signature: String,
+
+	/// Decoded arguments, keyed by name (or by position when names are unknown)
+	pub args: Map<String, JsonValue>,
+}
@@ -1,9 +1,10 @@
 use crate::{
-	models::{core::ScriptLanguage, SecretValue},
-	utils::RetryConfig,
+	models::{core::ScriptLanguage, BlockChainType, Monitor, SecretString, SecretValue},
+	utils::{HttpClientConfig, RetryConfig},
 };
 use email_address::EmailAddress;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 
 /// Configuration for actions to take when monitored conditions are met.
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
@@ -17,6 +18,103 @@ pub struct Trigger {
 
 	/// Configuration specific to the trigger type
 	pub config: TriggerTypeConfig,
+
+	/// Parameters accepted by the trigger with their default values, available to the message
+	/// templates and the destination of the trigger as `params.<name>` and set per monitor
+	/// through `trigger_params`
+	#[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+	pub parameters: BTreeMap<String, String>,
+
+	/// Callback receiving a report of each notification attempt of the trigger
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub delivery_report: Option<DeliveryReportConfig>,
+}
+
+/// Callback reporting the outcome of the notification attempts of a trigger to external
+/// tooling, e.g. to track the delivery reliability of the alerts
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct DeliveryReportConfig {
+	/// URL the reports are posted to
+	pub url: SecretValue,
+
+	/// Whether only the failed attempts are reported
+	#[serde(default)]
+	pub failures_only: bool,
+}
+
+impl Trigger {
+	/// Returns the template variables of the parameters of the trigger for a monitor
+	///
+	/// # Arguments
+	/// * `monitor` - The monitor notifying the trigger
+	///
+	/// # Returns
+	/// The `params.<name>` variables, with the values set by the monitor taking precedence
+	/// over the defaults of the trigger
+	pub fn parameter_variables(&self, monitor: &Monitor) -> HashMap<String, String> {
+		let mut parameters = self.parameters.clone();
+		if let Some(values) = monitor.trigger_params.get(&self.name) {
+			parameters.extend(values.clone());
+		}
+		parameters
+			.into_iter()
+			.map(|(name, value)| (format!("params.{}", name), value))
+			.collect()
+	}
+
+	/// Returns the trigger with the parameters of a monitor applied to its destination
+	///
+	/// The `${params.<name>}` placeholders of the webhook URLs and headers, of the Telegram chat
+	/// ID and of the script arguments are replaced by the values of the parameters, so that a
+	/// shared trigger can e.g. notify a different channel for each monitor.
+	///
+	/// # Arguments
+	/// * `monitor` - The monitor notifying the trigger
+	pub fn with_parameters(&self, monitor: &Monitor) -> Trigger {
+		let variables = self.parameter_variables(monitor);
+		let mut trigger = self.clone();
+		if variables.is_empty() {
+			return trigger;
+		}
+		let substitute = |value: &str| {
+			variables
+				.iter()
+				.fold(value.to_string(), |value, (name, param)| {
+					value.replace(&format!("${{{}}}", name), param)
+				})
+		};
+		let substitute_secret = |value: &SecretValue| match value {
+			SecretValue::Plain(secret) => {
+				SecretValue::Plain(SecretString::new(substitute(secret.as_str())))
+			}
+			SecretValue::Environment(name) => SecretValue::Environment(substitute(name)),
+			SecretValue::HashicorpCloudVault(name) => {
+				SecretValue::HashicorpCloudVault(substitute(name))
+			}
+		};
+
+		match &mut trigger.config {
+			TriggerTypeConfig::Slack { slack_url: url, .. }
+			| TriggerTypeConfig::Discord {
+				discord_url: url, ..
+			} => *url = substitute_secret(url),
+			TriggerTypeConfig::Webhook { url, headers, .. } => {
+				*url = substitute_secret(url);
+				for value in headers.iter_mut().flat_map(|headers| headers.values_mut()) {
+					*value = substitute(value);
+				}
+			}
+			TriggerTypeConfig::Telegram { chat_id, .. } => *chat_id = substitute(chat_id),
+			TriggerTypeConfig::Script { arguments, .. } => {
+				for argument in arguments.iter_mut().flatten() {
+					*argument = substitute(argument);
+				}
+			}
+			TriggerTypeConfig::Email { .. } => {}
+		}
+		trigger
+	}
 }
 
 /// Supported trigger action types
@@ -46,6 +144,81 @@ pub struct NotificationMessage {
 	pub title: String,
 	/// Message template
 	pub body: String,
+	/// Chain-specific variants, sent instead of the title and body for the matches of a
+	/// chain type
+	#[serde(default, skip_serializing_if = "ChainMessages::is_empty")]
+	pub chains: ChainMessages,
+}
+
+impl NotificationMessage {
+	/// Returns the message sent for the matches of a chain type
+	///
+	/// # Arguments
+	/// * `chain` - The chain type of the match
+	///
+	/// # Returns
+	/// The variant of the chain type if any, with the default title if it has none, or the
+	/// default title and body otherwise
+	pub fn for_chain(&self, chain: &BlockChainType) -> NotificationMessage {
+		let (title, body) = match self.chains.get(chain) {
+			Some(variant) => (variant.title.as_ref().unwrap_or(&self.title), &variant.body),
+			None => (&self.title, &self.body),
+		};
+		NotificationMessage {
+			title: title.clone(),
+			body: body.clone(),
+			chains: ChainMessages::default(),
+		}
+	}
+}
+
+/// Chain-specific variants of a notification message
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Default)]
+#[serde(deny_unknown_fields)]
+pub struct ChainMessages {
+	/// Variant for EVM matches
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub evm: Option<ChainMessage>,
+	/// Variant for Stellar matches
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub stellar: Option<ChainMessage>,
+	/// Variant for Midnight matches
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub midnight: Option<ChainMessage>,
+	/// Variant for Solana matches
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub solana: Option<ChainMessage>,
+}
+
+impl ChainMessages {
+	/// Returns true if no chain type has a variant
+	pub fn is_empty(&self) -> bool {
+		self.evm.is_none()
+			&& self.stellar.is_none()
+			&& self.midnight.is_none()
+			&& self.solana.is_none()
+	}
+
+	/// Returns the variant of a chain type, if any
+	pub fn get(&self, chain: &BlockChainType) -> Option<&ChainMessage> {
+		match chain {
+			BlockChainType::EVM => self.evm.as_ref(),
+			BlockChainType::Stellar => self.stellar.as_ref(),
+			BlockChainType::Midnight => self.midnight.as_ref(),
+			BlockChainType::Solana => self.solana.as_ref(),
+		}
+	}
+}
+
+/// Notification message for the matches of a chain type
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ChainMessage {
+	/// Notification title or subject, defaults to the title of the message
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub title: Option<String>,
+	/// Message template
+	pub body: String,
 }
 
 /// Payload mode for webhook triggers
@@ -57,6 +230,8 @@ pub enum WebhookPayloadMode {
 	Template,
 	/// Send the raw MonitorMatch as the JSON payload
 	Raw,
+	/// Send the match in the versioned normalized schema as the JSON payload
+	Normalized,
 }
 
 /// Type-specific configuration for triggers
@@ -73,6 +248,9 @@ pub enum TriggerTypeConfig {
 		/// Retry policy for HTTP requests
 		#[serde(default)]
 		retry_policy: RetryConfig,
+		/// HTTP client settings
+		#[serde(default, skip_serializing_if = "HttpClientConfig::is_default")]
+		http_client: HttpClientConfig,
 	},
 	/// Email notification configuration
 	Email {
@@ -104,15 +282,18 @@ pub enum TriggerTypeConfig {
 		secret: Option<SecretValue>,
 		/// Optional HTTP headers
 		headers: Option<std::collections::HashMap<String, String>>,
-		/// Notification message (required for template mode, optional for raw mode)
+		/// Notification message (required for template mode, unused in the other modes)
 		#[serde(default)]
 		message: NotificationMessage,
-		/// Payload mode: "template" (default) or "raw"
+		/// Payload mode: "template" (default), "raw" or "normalized"
 		#[serde(default)]
 		payload_mode: WebhookPayloadMode,
 		/// Retry policy for HTTP requests
 		#[serde(default)]
 		retry_policy: RetryConfig,
+		/// HTTP client settings
+		#[serde(default, skip_serializing_if = "HttpClientConfig::is_default")]
+		http_client: HttpClientConfig,
 	},
 	/// Telegram notification configuration
 	Telegram {
@@ -127,6 +308,9 @@ pub enum TriggerTypeConfig {
 		/// Retry policy for HTTP requests
 		#[serde(default)]
 		retry_policy: RetryConfig,
+		/// HTTP client settings
+		#[serde(default, skip_serializing_if = "HttpClientConfig::is_default")]
+		http_client: HttpClientConfig,
 	},
 	/// Discord notification configuration
 	Discord {
@@ -137,6 +321,9 @@ pub enum TriggerTypeConfig {
 		/// Retry policy for HTTP requests
 		#[serde(default)]
 		retry_policy: RetryConfig,
+		/// HTTP client settings
+		#[serde(default, skip_serializing_if = "HttpClientConfig::is_default")]
+		http_client: HttpClientConfig,
 	},
 	/// Script execution configuration
 	Script {
@@ -163,4 +350,40 @@ impl TriggerTypeConfig {
 			_ => None,
 		}
 	}
+
+	/// Get the notification message for the trigger type, if applicable.
+	pub fn get_message(&self) -> Option<&NotificationMessage> {
+		match self {
+			Self::Slack { message, .. }
+			| Self::Email { message, .. }
+			| Self::Webhook { message, .. }
+			| Self::Telegram { message, .. }
+			| Self::Discord { message, .. } => Some(message),
+			Self::Script { .. } => None,
+		}
+	}
+
+	/// Get a mutable reference to the notification message for the trigger type, if
+	/// applicable.
+	pub fn get_message_mut(&mut self) -> Option<&mut NotificationMessage> {
+		match self {
+			Self::Slack { message, .. }
+			| Self::Email { message, .. }
+			| Self::Webhook { message, .. }
+			| Self::Telegram { message, .. }
+			| Self::Discord { message, .. } => Some(message),
+			Self::Script { .. } => None,
+		}
+	}
+
+	/// Get the HTTP client settings for the trigger type, if applicable.
+	pub fn get_http_client_config(&self) -> Option<HttpClientConfig> {
+		match self {
+			Self::Slack { http_client, .. }
+			| Self::Discord { http_client, .. }
+			| Self::Webhook { http_client, .. }
+			| Self::Telegram { http_client, .. } => Some(http_client.clone()),
+			_ => None,
+		}
+	}
 }
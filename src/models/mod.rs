@@ -14,14 +14,16 @@ mod security;
 
 // Re-export blockchain types
 pub use blockchain::{
-	BlockChainType, BlockType, ChainConfiguration, ContractSpec, MonitorMatch, ProcessedBlock,
-	TransactionType,
+	BlockChainType, BlockType, ChainConfiguration, ConditionAudit, ContractSpec, MonitorMatch,
+	OperandAudit, ProcessedBlock, TransactionType,
 };
 
 pub use blockchain::evm::{
-	EVMBaseReceipt, EVMBaseTransaction, EVMBlock, EVMContractSpec, EVMMatchArguments,
-	EVMMatchParamEntry, EVMMatchParamsMap, EVMMonitorConfig, EVMMonitorMatch, EVMReceiptLog,
-	EVMTransaction, EVMTransactionReceipt,
+	EVMAddressPoisoningConfig, EVMBaseReceipt, EVMBaseTransaction, EVMBlock, EVMContractSpec,
+	EVMFirstInteractionConfig, EVMHeuristicsConfig, EVMInternalTransfer, EVMMatchArguments,
+	EVMMatchParamEntry, EVMMatchParamsMap, EVMMonitorConfig, EVMMonitorMatch,
+	EVMNonceAnomalyConfig, EVMReceiptLog, EVMTraceStatus, EVMTransaction, EVMTransactionReceipt,
+	EVMTransactionTrace,
 };
 
 pub use blockchain::stellar::{
@@ -87,14 +89,19 @@ pub use blockchain::solana::{
 
 // Re-export core types
 pub use core::{
-	AddressWithSpec, BlockRecoveryConfig, EventCondition, FunctionCondition, MatchConditions,
-	MaxPastBlocks, Monitor, Network, NotificationMessage, RpcUrl, ScriptLanguage,
-	TransactionCondition, TransactionStatus, Trigger, TriggerConditions, TriggerType,
-	TriggerTypeConfig, WebhookPayloadMode, SCRIPT_LANGUAGE_EXTENSIONS,
+	AbiDriftConfig, AddressOwner, AddressWithSpec, BatchConfig, BlockGuardrails,
+	BlockRecoveryConfig, CanaryConfig, ChainMessage, ChainMessages, DeliveryReportConfig,
+	DeploymentDependency, EventCondition, FunctionCondition, GetLogsLimits, MatchConditions,
+	MaxPastBlocks, Monitor, MonitorSeverity, Network, NormalizedCall, NormalizedMatch,
+	NormalizedMonitor, NormalizedTransaction, NotificationMessage, PoisonBlockPolicy,
+	PostProcessingScript, RestartPolicy, RpcConsistencyConfig, RpcCostConfig, RpcProvider, RpcUrl,
+	SamplingConfig, ScriptLanguage, TraceBudget, TransactionCondition, TransactionStatus, Trigger,
+	TriggerConditions, TriggerType, TriggerTypeConfig, WatchdogConfig, WebhookPayloadMode,
+	NORMALIZED_MATCH_EXTENSIONS, NORMALIZED_MATCH_SCHEMA_VERSION, SCRIPT_LANGUAGE_EXTENSIONS,
 };
 
 // Re-export config types
-pub use config::{ConfigError, ConfigLoader};
+pub use config::{chain_condition_warnings, ConfigError, ConfigLoader, DEFAULT_CONFIG_DIR};
 
 // Re-export security types
 pub use security::{SecretString, SecretValue, SecurityError};
@@ -22,6 +22,7 @@ use crate::{
 		error::{SecurityError, SecurityResult},
 		get_env_var,
 	},
+	utils::logging::redaction::register_secret,
 };
 
 /// Trait for vault clients that can retrieve secrets
@@ -173,6 +174,8 @@ impl SecretValue {
 	/// - For `Environment`, reads the environment variable
 	/// - For `HashicorpCloudVault`, fetches the secret from the vault
 	///
+	/// The resolved value is registered for redaction from logs and error contexts.
+	///
 	/// # Errors
 	///
 	/// Returns a `SecurityError` if:
@@ -180,7 +183,7 @@ impl SecretValue {
 	/// - Vault access fails
 	/// - Any other security-related error occurs
 	pub async fn resolve(&self) -> SecurityResult<SecretString> {
-		match self {
+		let secret = match self {
 			SecretValue::Plain(secret) => Ok(secret.clone()),
 			SecretValue::Environment(env_var) => {
 				env::var(env_var).map(SecretString::new).map_err(|e| {
@@ -201,7 +204,9 @@ impl SecretValue {
 					))
 				})
 			}
-		}
+		}?;
+		register_secret(secret.as_str());
+		Ok(secret)
 	}
 
 	/// Checks if the secret value starts with a given prefix
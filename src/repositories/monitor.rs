@@ -11,7 +11,10 @@ use std::{collections::HashMap, marker::PhantomData, path::Path};
 use async_trait::async_trait;
 
 use crate::{
-	models::{ConfigLoader, Monitor, Network, Trigger, SCRIPT_LANGUAGE_EXTENSIONS},
+	models::{
+		chain_condition_warnings, BlockChainType, ConfigLoader, Monitor, Network, Trigger,
+		SCRIPT_LANGUAGE_EXTENSIONS,
+	},
 	repositories::{
 		error::RepositoryError,
 		network::{NetworkRepository, NetworkRepositoryTrait, NetworkService},
@@ -108,7 +111,8 @@ impl<
 		}
 	}
 
-	/// Returns an error if any monitor references a non-existent network or trigger.
+	/// Returns an error if any monitor references a non-existent network or trigger, or any
+	/// network a non-existent trigger.
 	pub fn validate_monitor_references(
 		monitors: &HashMap<String, Monitor>,
 		triggers: &HashMap<String, Trigger>,
@@ -132,6 +136,92 @@ impl<
 				}
 			}
 
+			// Validate the triggers notified of ABI drifts
+			for trigger_id in monitor.abi_drift.iter().flat_map(|drift| &drift.triggers) {
+				if !triggers.contains_key(trigger_id) {
+					validation_errors.push(format!(
+						"Monitor '{}' references non-existent ABI drift trigger '{}'",
+						monitor_name, trigger_id
+					));
+					metadata.insert(
+						format!("monitor_{}_invalid_trigger", monitor_name),
+						trigger_id.clone(),
+					);
+				}
+			}
+
+			// Validate the triggers notified of the canary matches
+			for trigger_id in monitor.canary.iter().flat_map(|canary| &canary.triggers) {
+				if !triggers.contains_key(trigger_id) {
+					validation_errors.push(format!(
+						"Monitor '{}' references non-existent canary trigger '{}'",
+						monitor_name, trigger_id
+					));
+					metadata.insert(
+						format!("monitor_{}_invalid_trigger", monitor_name),
+						trigger_id.clone(),
+					);
+				}
+			}
+
+			// Validate the triggers of the owners of the addresses
+			for trigger_id in monitor.owners.iter().flat_map(|owner| &owner.triggers) {
+				if !triggers.contains_key(trigger_id) {
+					validation_errors.push(format!(
+						"Monitor '{}' routes matches to non-existent trigger '{}'",
+						monitor_name, trigger_id
+					));
+					metadata.insert(
+						format!("monitor_{}_invalid_trigger", monitor_name),
+						trigger_id.clone(),
+					);
+				}
+			}
+
+			// Validate the parameters set for the triggers
+			for (trigger_id, params) in &monitor.trigger_params {
+				let notified = monitor
+					.routed_triggers()
+					.any(|trigger| trigger == trigger_id)
+					|| monitor
+						.abi_drift
+						.as_ref()
+						.is_some_and(|drift| drift.triggers.contains(trigger_id))
+					|| monitor
+						.canary
+						.as_ref()
+						.is_some_and(|canary| canary.triggers.contains(trigger_id));
+				if !notified {
+					validation_errors.push(format!(
+						"Monitor '{}' sets parameters for trigger '{}' it does not reference",
+						monitor_name, trigger_id
+					));
+					continue;
+				}
+				let Some(trigger) = triggers.get(trigger_id) else {
+					continue;
+				};
+				for param in params.keys() {
+					if !trigger.parameters.contains_key(param) {
+						validation_errors.push(format!(
+							"Monitor '{}' sets parameter '{}' not declared by trigger '{}'",
+							monitor_name, param, trigger_id
+						));
+						metadata.insert(
+							format!("monitor_{}_invalid_trigger_param", monitor_name),
+							param.clone(),
+						);
+					}
+				}
+				// The parameters may be used in the destination of the trigger
+				if let Err(e) = trigger.with_parameters(monitor).validate() {
+					validation_errors.push(format!(
+						"Trigger '{}' is invalid with the parameters of monitor '{}': {}",
+						trigger_id, monitor_name, e
+					));
+				}
+			}
+
 			// Validate network references
 			for network_slug in &monitor.networks {
 				if !networks.contains_key(network_slug) {
@@ -146,6 +236,22 @@ impl<
 				}
 			}
 
+			// Deployments can only be checked on EVM and Stellar networks
+			if let Some(dependency) = &monitor.activate_on_deployment {
+				if let Some(network) = networks.get(&dependency.network) {
+					if !matches!(
+						network.network_type,
+						BlockChainType::EVM | BlockChainType::Stellar
+					) {
+						validation_errors.push(format!(
+							"Monitor '{}' activates on a deployment on {} network '{}', only EVM \
+							 and Stellar networks are supported",
+							monitor_name, network.network_type, dependency.network
+						));
+					}
+				}
+			}
+
 			// Validate signatures based on network type
 			Self::validate_monitor_signatures(
 				monitor_name,
@@ -154,8 +260,16 @@ impl<
 				&mut validation_errors,
 			);
 
+			// Conditions written for another chain are valid but never match
+			for warning in chain_condition_warnings(monitor, networks) {
+				tracing::warn!("{}", warning);
+			}
+
 			// Validate custom trigger conditions
 			for condition in &monitor.trigger_conditions {
+				if condition.is_expression() {
+					continue;
+				}
 				let script_path = Path::new(&condition.script_path);
 				if !script_path.exists() {
 					validation_errors.push(format!(
@@ -198,6 +312,26 @@ impl<
 			}
 		}
 
+		// Validate the triggers notified of the skipped blocks
+		for (network_slug, network) in networks {
+			let skip_triggers = network
+				.poison_block_policy
+				.iter()
+				.flat_map(|policy| &policy.on_block_skipped);
+			for trigger_id in skip_triggers {
+				if !triggers.contains_key(trigger_id) {
+					validation_errors.push(format!(
+						"Network '{}' references non-existent block skip trigger '{}'",
+						network_slug, trigger_id
+					));
+					metadata.insert(
+						format!("network_{}_invalid_trigger", network_slug),
+						trigger_id.clone(),
+					);
+				}
+			}
+		}
+
 		if !validation_errors.is_empty() {
 			return Err(RepositoryError::validation_error(
 				format!(
@@ -649,6 +783,156 @@ mod tests {
 		assert!(err.to_string().contains("references non-existent trigger"));
 	}
 
+	#[test]
+	fn test_block_skip_trigger_validation_error() {
+		use crate::{models::PoisonBlockPolicy, utils::tests::network::NetworkBuilder};
+
+		let network = NetworkBuilder::new()
+			.slug("ethereum_mainnet")
+			.poison_block_policy(PoisonBlockPolicy {
+				enabled: true,
+				max_attempts: 3,
+				on_block_skipped: vec!["skipped_blocks".to_string()],
+			})
+			.build();
+		let networks = HashMap::from([("ethereum_mainnet".to_string(), network)]);
+
+		let result =
+			MonitorRepository::<NetworkRepository, TriggerRepository>::validate_monitor_references(
+				&HashMap::new(),
+				&HashMap::new(),
+				&networks,
+			);
+
+		assert!(result.unwrap_err().to_string().contains(
+			"Network 'ethereum_mainnet' references non-existent block skip trigger 'skipped_blocks'"
+		));
+	}
+
+	#[test]
+	fn test_trigger_params_validation() {
+		use crate::utils::tests::builders::trigger::TriggerBuilder;
+
+		let triggers = HashMap::from([
+			(
+				"team_slack".to_string(),
+				TriggerBuilder::new()
+					.name("team_slack")
+					.parameter("channel", "#alerts")
+					.build(),
+			),
+			(
+				"team_telegram".to_string(),
+				TriggerBuilder::new()
+					.name("team_telegram")
+					.telegram(
+						"1234567890:ABCdefGHIjklMNOpqrSTUvwxYZ123456789", // noboost
+						"${params.chat}",
+						true,
+					)
+					.parameter("chat", "1730223038")
+					.build(),
+			),
+		]);
+		let validate = |monitor: Monitor| {
+			let monitors = HashMap::from([("test_monitor".to_string(), monitor)]);
+			MonitorRepository::<NetworkRepository, TriggerRepository>::validate_monitor_references(
+				&monitors,
+				&triggers,
+				&HashMap::new(),
+			)
+		};
+
+		let monitor = MonitorBuilder::new()
+			.name("test_monitor")
+			.networks(vec![])
+			.triggers(vec!["team_slack".to_string()])
+			.trigger_param("team_slack", "channel", "#defi")
+			.build();
+		assert!(validate(monitor).is_ok());
+
+		let monitor = MonitorBuilder::new()
+			.name("test_monitor")
+			.networks(vec![])
+			.triggers(vec!["team_slack".to_string()])
+			.trigger_param("team_slack", "mention", "@defi")
+			.build();
+		let err = validate(monitor).unwrap_err();
+		assert!(err
+			.to_string()
+			.contains("sets parameter 'mention' not declared by trigger 'team_slack'"));
+
+		let monitor = MonitorBuilder::new()
+			.name("test_monitor")
+			.networks(vec![])
+			.trigger_param("team_slack", "channel", "#defi")
+			.build();
+		let err = validate(monitor).unwrap_err();
+		assert!(err.to_string().contains("it does not reference"));
+
+		// Owners can route matches to existing triggers only, whose parameters can be set
+		let monitor = MonitorBuilder::new()
+			.name("test_monitor")
+			.networks(vec![])
+			.owner("DeFi", vec!["0x1"], None)
+			.owner_triggers(vec!["team_slack".to_string()])
+			.trigger_param("team_slack", "channel", "#defi")
+			.build();
+		assert!(validate(monitor).is_ok());
+
+		let monitor = MonitorBuilder::new()
+			.name("test_monitor")
+			.networks(vec![])
+			.owner("DeFi", vec!["0x1"], None)
+			.owner_triggers(vec!["defi_slack".to_string()])
+			.build();
+		let err = validate(monitor).unwrap_err();
+		assert!(err
+			.to_string()
+			.contains("routes matches to non-existent trigger 'defi_slack'"));
+
+		// Canaries notify existing triggers only, whose parameters can be set
+		let monitor = MonitorBuilder::new()
+			.name("test_monitor")
+			.networks(vec![])
+			.canary("ethereum_mainnet", 19_000_000, 3600)
+			.canary_triggers(vec!["team_slack".to_string()])
+			.trigger_param("team_slack", "channel", "#canary")
+			.build();
+		assert!(validate(monitor).is_ok());
+
+		let monitor = MonitorBuilder::new()
+			.name("test_monitor")
+			.networks(vec![])
+			.canary("ethereum_mainnet", 19_000_000, 3600)
+			.canary_triggers(vec!["canary_slack".to_string()])
+			.build();
+		let err = validate(monitor).unwrap_err();
+		assert!(err
+			.to_string()
+			.contains("references non-existent canary trigger 'canary_slack'"));
+
+		// The trigger must stay valid once the parameters are applied to its destination
+		let monitor = MonitorBuilder::new()
+			.name("test_monitor")
+			.networks(vec![])
+			.triggers(vec!["team_telegram".to_string()])
+			.trigger_param("team_telegram", "chat", "1730223039")
+			.build();
+		assert!(validate(monitor).is_ok());
+
+		let monitor = MonitorBuilder::new()
+			.name("test_monitor")
+			.networks(vec![])
+			.triggers(vec!["team_telegram".to_string()])
+			.trigger_param("team_telegram", "chat", " ")
+			.build();
+		let err = validate(monitor).unwrap_err();
+		assert!(err.to_string().contains(
+			"Trigger 'team_telegram' is invalid with the parameters of monitor 'test_monitor'"
+		));
+	}
+
 	#[tokio::test]
 	async fn test_load_from_path_error_handling() {
 		// Create a temporary directory for testing
@@ -681,6 +965,44 @@ mod tests {
 		}
 	}
 
+	#[test]
+	fn test_activate_on_deployment_network_type() {
+		use crate::utils::tests::builders::network::NetworkBuilder;
+
+		let networks = HashMap::from([
+			(
+				"ethereum_mainnet".to_string(),
+				NetworkBuilder::new()
+					.slug("ethereum_mainnet")
+					.network_type(BlockChainType::EVM)
+					.build(),
+			),
+			(
+				"solana_mainnet".to_string(),
+				NetworkBuilder::new()
+					.slug("solana_mainnet")
+					.network_type(BlockChainType::Solana)
+					.build(),
+			),
+		]);
+		let validate = |network: &str| {
+			let monitor = MonitorBuilder::new()
+				.name("dependent")
+				.networks(vec![network.to_string()])
+				.activate_on_deployment(network, "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48")
+				.build();
+			MonitorRepository::<NetworkRepository, TriggerRepository>::validate_monitor_references(
+				&HashMap::from([("dependent".to_string(), monitor)]),
+				&HashMap::new(),
+				&networks,
+			)
+		};
+
+		assert!(validate("ethereum_mainnet").is_ok());
+		let err = validate("solana_mainnet").unwrap_err();
+		assert!(err.to_string().contains("only EVM and Stellar networks"));
+	}
+
 	#[test]
 	fn test_signature_validation_with_network_types() {
 		use crate::models::{BlockChainType, EventCondition, FunctionCondition, MatchConditions};
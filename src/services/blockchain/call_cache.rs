@@ -0,0 +1,212 @@
+//! In-memory cache of contract reads.
+//!
+//! Enrichment of matches (token decimals, labels, invariants) repeatedly reads the same view
+//! functions of the same contracts. The results of `eth_call` requests are cached for a short
+//! time, keyed by network, address and calldata. The cache is owned by the client pool and
+//! shared by all its EVM clients, so that the monitors of a network reuse each other's reads.
+//! The cache is bounded: the oldest entries are evicted first once it holds
+//! `CONTRACT_CALL_CACHE_MAX_ENTRIES` results. Lookups are counted by the
+//! `contract_call_cache_requests_total` metric, from which the hit rate is derived.
+
+use std::{
+	collections::{HashMap, VecDeque},
+	sync::Mutex,
+	time::{Duration, Instant},
+};
+
+use crate::utils::metrics::{record_contract_call_cache_lookup, set_contract_call_cache_entries};
+
+/// Default lifetime of a cached result
+pub const DEFAULT_CALL_CACHE_TTL: Duration = Duration::from_secs(30);
+
+/// Default maximum number of cached results
+pub const DEFAULT_CALL_CACHE_MAX_ENTRIES: usize = 10_000;
+
+/// Key of a cached contract read: network slug, contract address and calldata
+type CallKey = (String, String, String);
+
+/// Cached result of a contract read
+struct CachedCall {
+	/// Insertion number, telling the latest insertion of a key from previous ones
+	sequence: u64,
+	inserted_at: Instant,
+	result: String,
+}
+
+/// Cached results and their insertion order
+#[derive(Default)]
+struct CallEntries {
+	results: HashMap<CallKey, CachedCall>,
+	order: VecDeque<(CallKey, u64)>,
+	next_sequence: u64,
+}
+
+/// TTL cache of the results of contract reads
+pub struct ContractCallCache {
+	ttl: Duration,
+	max_entries: usize,
+	entries: Mutex<CallEntries>,
+}
+
+impl ContractCallCache {
+	/// Creates a new cache
+	///
+	/// # Arguments
+	/// * `ttl` - Lifetime of a cached result
+	/// * `max_entries` - Maximum number of cached results, `0` disables the cache
+	pub fn new(ttl: Duration, max_entries: usize) -> Self {
+		Self {
+			ttl,
+			max_entries,
+			entries: Mutex::new(CallEntries::default()),
+		}
+	}
+
+	/// Returns the cached result of a contract read, if it has not expired
+	///
+	/// # Arguments
+	/// * `network_slug` - Network of the contract
+	/// * `address` - Address of the contract
+	/// * `calldata` - Hex-encoded calldata of the read
+	pub fn get(&self, network_slug: &str, address: &str, calldata: &str) -> Option<String> {
+		let key = call_key(network_slug, address, calldata);
+		let result = {
+			let entries = self.lock();
+			entries
+				.results
+				.get(&key)
+				.filter(|cached| cached.inserted_at.elapsed() < self.ttl)
+				.map(|cached| cached.result.clone())
+		};
+		record_contract_call_cache_lookup(network_slug, result.is_some());
+		result
+	}
+
+	/// Caches the result of a contract read
+	///
+	/// Expired results are dropped, then the oldest results are evicted to keep the cache
+	/// within its size bound.
+	///
+	/// # Arguments
+	/// * `network_slug` - Network of the contract
+	/// * `address` - Address of the contract
+	/// * `calldata` - Hex-encoded calldata of the read
+	/// * `result` - Hex-encoded result of the read
+	pub fn insert(&self, network_slug: &str, address: &str, calldata: &str, result: String) {
+		if self.max_entries == 0 {
+			return;
+		}
+		let key = call_key(network_slug, address, calldata);
+		let mut entries = self.lock();
+		let sequence = entries.next_sequence;
+		entries.next_sequence += 1;
+		entries.results.insert(
+			key.clone(),
+			CachedCall {
+				sequence,
+				inserted_at: Instant::now(),
+				result,
+			},
+		);
+		entries.order.push_back((key, sequence));
+
+		while let Some((key, sequence)) = entries.order.front().cloned() {
+			// The key may have been refreshed since, in which case the newer result is kept
+			let evict = entries
+				.results
+				.get(&key)
+				.filter(|cached| cached.sequence == sequence)
+				.map(|cached| {
+					entries.results.len() > self.max_entries
+						|| cached.inserted_at.elapsed() >= self.ttl
+				});
+			match evict {
+				Some(false) => break,
+				Some(true) => {
+					entries.results.remove(&key);
+				}
+				None => {}
+			}
+			entries.order.pop_front();
+		}
+		set_contract_call_cache_entries(entries.results.len());
+	}
+
+	/// Returns the number of cached results, including expired ones not yet dropped
+	pub fn len(&self) -> usize {
+		self.lock().results.len()
+	}
+
+	/// Returns true if no result is cached
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	fn lock(&self) -> std::sync::MutexGuard<'_, CallEntries> {
+		match self.entries.lock() {
+			Ok(entries) => entries,
+			Err(poisoned) => poisoned.into_inner(),
+		}
+	}
+}
+
+impl Default for ContractCallCache {
+	fn default() -> Self {
+		Self::new(DEFAULT_CALL_CACHE_TTL, DEFAULT_CALL_CACHE_MAX_ENTRIES)
+	}
+}
+
+/// Builds the key of a read, addresses and calldata being case-insensitive hex
+fn call_key(network_slug: &str, address: &str, calldata: &str) -> CallKey {
+	(
+		network_slug.to_string(),
+		address.to_lowercase(),
+		calldata.to_lowercase(),
+	)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const TOKEN: &str = "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48";
+	const DECIMALS: &str = "0x313ce567";
+
+	#[test]
+	fn test_get_and_expiry() {
+		let cache = ContractCallCache::new(Duration::from_millis(50), 10);
+		assert_eq!(cache.get("ethereum_mainnet", TOKEN, DECIMALS), None);
+
+		cache.insert("ethereum_mainnet", TOKEN, DECIMALS, "0x06".to_string());
+		// Addresses are compared case-insensitively, networks are distinct
+		assert_eq!(
+			cache.get("ethereum_mainnet", &TOKEN.to_lowercase(), DECIMALS),
+			Some("0x06".to_string())
+		);
+		assert_eq!(cache.get("base_mainnet", TOKEN, DECIMALS), None);
+
+		std::thread::sleep(Duration::from_millis(60));
+		assert_eq!(cache.get("ethereum_mainnet", TOKEN, DECIMALS), None);
+	}
+
+	#[test]
+	fn test_size_bound() {
+		let cache = ContractCallCache::new(Duration::from_secs(60), 2);
+		cache.insert("ethereum_mainnet", "0x1", DECIMALS, "0x06".to_string());
+		cache.insert("ethereum_mainnet", "0x2", DECIMALS, "0x12".to_string());
+		// Refreshing a key does not let its previous insertion evict it
+		cache.insert("ethereum_mainnet", "0x1", DECIMALS, "0x08".to_string());
+		cache.insert("ethereum_mainnet", "0x3", DECIMALS, "0x00".to_string());
+
+		assert_eq!(cache.len(), 2);
+		assert_eq!(cache.get("ethereum_mainnet", "0x2", DECIMALS), None);
+		assert_eq!(
+			cache.get("ethereum_mainnet", "0x1", DECIMALS),
+			Some("0x08".to_string())
+		);
+
+		let disabled = ContractCallCache::new(Duration::from_secs(60), 0);
+		disabled.insert("ethereum_mainnet", "0x1", DECIMALS, "0x06".to_string());
+		assert!(disabled.is_empty());
+	}
+}
@@ -7,7 +7,7 @@ use async_trait::async_trait;
 
 use crate::{
 	models::{BlockType, ContractSpec},
-	services::filter::BlockFilter,
+	services::filter::{BlockFilter, FilterContext},
 };
 
 /// Indicates how blocks were fetched, which determines how missed blocks should be detected.
@@ -68,6 +68,17 @@ pub trait BlockChainClient: Send + Sync + Clone {
 		Err(anyhow::anyhow!("get_contract_spec not implemented"))
 	}
 
+	/// Checks whether a contract is deployed at an address
+	///
+	/// # Arguments
+	/// * `address` - The address of the contract
+	///
+	/// # Returns
+	/// * `Result<bool, anyhow::Error>` - True if the address holds contract code
+	async fn has_contract_code(&self, _address: &str) -> Result<bool, anyhow::Error> {
+		Err(anyhow::anyhow!("has_contract_code not implemented"))
+	}
+
 	/// Retrieves blocks containing only transactions relevant to the specified addresses
 	///
 	/// This is an optimized method for chains that support address-based querying (like Solana).
@@ -116,8 +127,9 @@ pub trait BlockChainClient: Send + Sync + Clone {
 /// Defines the factory interface for creating block filters
 ///
 /// This trait must be implemented by all blockchain-specific clients to provide
-/// a way to create block filters.
+/// a way to create block filters. The filters are given the state shared by the filters
+/// of the filter service creating them.
 pub trait BlockFilterFactory<T> {
-	type Filter: BlockFilter<Client = T> + Send;
-	fn filter() -> Self::Filter;
+	type Filter: BlockFilter<Client = T> + Send + Sync;
+	fn filter(context: &FilterContext) -> Self::Filter;
 }
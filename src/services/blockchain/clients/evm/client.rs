@@ -3,8 +3,16 @@
 //! This module provides functionality to interact with Ethereum and other EVM-compatible
 //! blockchains, supporting operations like block retrieval, transaction receipt lookup,
 //! and log filtering.
+//!
+//! Log queries are split to honor the `eth_getLogs` limits of the providers: ranges longer
+//! than the `max_block_range` of the network (or, unless configured, the range detected on
+//! its endpoints) are chunked, and ranges rejected by a provider for exceeding its limits are
+//! halved until accepted, merging the logs in block order.
+//!
+//! Contract reads (`eth_call`) are served from the contract call cache of the client pool
+//! while their result is fresh.
 
-use std::marker::PhantomData;
+use std::{marker::PhantomData, sync::Arc};
 
 use anyhow::Context;
 use async_trait::async_trait;
@@ -13,17 +21,36 @@ use serde_json::json;
 use tracing::instrument;
 
 use crate::{
-	models::{BlockType, EVMBlock, EVMReceiptLog, EVMTransactionReceipt, Network},
+	models::{BlockType, EVMBlock, EVMReceiptLog, EVMTransactionReceipt, GetLogsLimits, Network},
 	services::{
 		blockchain::{
+			call_cache::ContractCallCache,
 			client::BlockChainClient,
-			transports::{BlockchainTransport, EVMTransportClient},
+			transports::{
+				BlockchainTransport, EVMTransportClient, TransportContext, TransportError,
+			},
 			BlockFilterFactory,
 		},
-		filter::{evm_helpers::string_to_h256, EVMBlockFilter},
+		blockwatcher::EndpointCapabilityRegistry,
+		filter::{evm_helpers::string_to_h256, EVMBlockFilter, FilterContext},
 	},
 };
 
+/// Messages of the errors returned by common providers when an `eth_getLogs` query exceeds
+/// their block range or result limits
+const GET_LOGS_LIMIT_ERROR_PATTERNS: &[&str] = &[
+	"query returned more than",
+	"response size exceeded",
+	"block range",
+	"blocks range",
+	"range too large",
+	"range is too large",
+	"range too wide",
+	"range is too wide",
+	"too many results",
+	"too many blocks",
+];
+
 /// Client implementation for Ethereum Virtual Machine (EVM) compatible blockchains
 ///
 /// Provides high-level access to EVM blockchain data and operations through HTTP transport.
@@ -31,12 +58,55 @@ use crate::{
 pub struct EvmClient<T: Send + Sync + Clone> {
 	/// The underlying HTTP transport client for RPC communication
 	http_client: T,
+	/// Limits of the `eth_getLogs` queries accepted by the providers
+	logs_limits: GetLogsLimits,
+	/// Slug of the network, to look up the limits detected on its endpoints and to key the
+	/// cached contract reads
+	network_slug: String,
+	/// Cache of the contract reads, shared by the clients of the pool
+	call_cache: Option<Arc<ContractCallCache>>,
+	/// Capabilities detected on the endpoints, shared by the clients of the pool
+	capabilities: Arc<EndpointCapabilityRegistry>,
 }
 
 impl<T: Send + Sync + Clone> EvmClient<T> {
 	/// Creates a new EVM client instance with a specific transport client
 	pub fn new_with_transport(http_client: T) -> Self {
-		Self { http_client }
+		Self {
+			http_client,
+			logs_limits: GetLogsLimits::default(),
+			network_slug: String::new(),
+			call_cache: None,
+			capabilities: Arc::new(EndpointCapabilityRegistry::default()),
+		}
+	}
+
+	/// Sets the limits of the `eth_getLogs` queries accepted by the providers
+	///
+	/// # Arguments
+	/// * `logs_limits` - The limits of the queries
+	pub fn with_logs_limits(mut self, logs_limits: GetLogsLimits) -> Self {
+		self.logs_limits = logs_limits;
+		self
+	}
+
+	/// Sets the cache serving the contract reads of the client
+	///
+	/// # Arguments
+	/// * `call_cache` - The cache, shared with the other clients
+	pub fn with_call_cache(mut self, call_cache: Arc<ContractCallCache>) -> Self {
+		self.call_cache = Some(call_cache);
+		self
+	}
+
+	/// Sets the capabilities detected on the endpoints, whose `eth_getLogs` block range limits
+	/// the log queries unless configured
+	///
+	/// # Arguments
+	/// * `capabilities` - The registry of the capabilities, shared with the other clients
+	pub fn with_capabilities(mut self, capabilities: Arc<EndpointCapabilityRegistry>) -> Self {
+		self.capabilities = capabilities;
+		self
 	}
 }
 
@@ -49,16 +119,44 @@ impl EvmClient<EVMTransportClient> {
 	/// # Returns
 	/// * `Result<Self, anyhow::Error>` - New client instance or connection error
 	pub async fn new(network: &Network) -> Result<Self, anyhow::Error> {
-		let client = EVMTransportClient::new(network).await?;
-		Ok(Self::new_with_transport(client))
+		Self::new_with_context(network, &TransportContext::default()).await
+	}
+
+	/// Creates a new EVM client instance sharing the state of the transports of a client pool
+	///
+	/// # Arguments
+	/// * `network` - Network configuration containing RPC endpoints and chain details
+	/// * `context` - State shared by the transports of the client pool
+	///
+	/// # Returns
+	/// * `Result<Self, anyhow::Error>` - New client instance or connection error
+	pub async fn new_with_context(
+		network: &Network,
+		context: &TransportContext,
+	) -> Result<Self, anyhow::Error> {
+		let logs_limits = network.get_logs_limits.clone().unwrap_or_default();
+		// Queries exceeding the limits are split instead of rotating endpoints
+		let client = EVMTransportClient::new_with_context(network, context)
+			.await?
+			.with_request_errors(
+				"eth_getLogs",
+				logs_limit_error_patterns(&logs_limits.error_patterns),
+			);
+		Ok(Self {
+			network_slug: network.slug.clone(),
+			..Self::new_with_transport(client)
+				.with_logs_limits(logs_limits)
+				.with_capabilities(context.capabilities.clone())
+		})
 	}
 }
 
 impl<T: Send + Sync + Clone + BlockchainTransport> BlockFilterFactory<Self> for EvmClient<T> {
 	type Filter = EVMBlockFilter<Self>;
-	fn filter() -> Self::Filter {
+	fn filter(context: &FilterContext) -> Self::Filter {
 		EVMBlockFilter {
 			_client: PhantomData,
+			context: context.clone(),
 		}
 	}
 }
@@ -92,6 +190,35 @@ pub trait EvmClientTrait {
 		to_block: u64,
 		addresses: Option<Vec<String>>,
 	) -> Result<Vec<EVMReceiptLog>, anyhow::Error>;
+
+	/// Reads a contract with `eth_call` at the latest block
+	///
+	/// # Arguments
+	/// * `address` - Address of the contract
+	/// * `calldata` - Hex-encoded calldata of the read
+	/// # Returns
+	/// * `Result<String, anyhow::Error>` - Hex-encoded result of the read, possibly cached
+	async fn call(&self, address: &str, calldata: &str) -> Result<String, anyhow::Error>;
+
+	/// Reads a storage slot of a contract with `eth_getStorageAt` at the latest block
+	///
+	/// # Arguments
+	/// * `address` - Address of the contract
+	/// * `slot` - Hex-encoded position of the slot
+	/// # Returns
+	/// * `Result<String, anyhow::Error>` - Hex-encoded value of the slot
+	async fn get_storage_at(&self, address: &str, slot: &str) -> Result<String, anyhow::Error>;
+
+	/// Traces the calls of a transaction with `debug_traceTransaction` and the `callTracer`
+	///
+	/// # Arguments
+	/// * `transaction_hash` - The hash of the transaction to trace
+	/// # Returns
+	/// * `Result<serde_json::Value, anyhow::Error>` - Top-level call frame, with nested `calls`
+	async fn trace_transaction(
+		&self,
+		transaction_hash: &str,
+	) -> Result<serde_json::Value, anyhow::Error>;
 }
 
 #[async_trait]
@@ -135,6 +262,9 @@ impl<T: Send + Sync + Clone + BlockchainTransport> EvmClientTrait for EvmClient<
 
 	/// Retrieves logs within the specified block range
 	///
+	/// The range is split into queries of at most `max_block_range` blocks, and a query
+	/// rejected for exceeding a provider limit is split in halves until accepted.
+	///
 	/// # Arguments
 	/// * `from_block` - Starting block number
 	/// * `to_block` - Ending block number
@@ -147,6 +277,113 @@ impl<T: Send + Sync + Clone + BlockchainTransport> EvmClientTrait for EvmClient<
 		from_block: u64,
 		to_block: u64,
 		addresses: Option<Vec<String>>,
+	) -> Result<Vec<EVMReceiptLog>, anyhow::Error> {
+		let max_block_range = self
+			.logs_limits
+			.max_block_range
+			.or_else(|| self.capabilities.max_logs_block_range(&self.network_slug));
+		let mut pending = split_block_range(from_block, to_block, max_block_range);
+		// Ranges are processed from the end of the stack, in block order
+		pending.reverse();
+
+		let mut logs = Vec::new();
+		while let Some((from, to)) = pending.pop() {
+			match self.query_logs(from, to, &addresses).await {
+				Ok(range_logs) => logs.extend(range_logs),
+				Err(e)
+					if from < to && is_logs_limit_error(&e, &self.logs_limits.error_patterns) =>
+				{
+					let middle = from + (to - from) / 2;
+					tracing::debug!(
+						"Splitting logs query of blocks {} - {} exceeding the provider limits",
+						from,
+						to
+					);
+					pending.push((middle + 1, to));
+					pending.push((from, middle));
+				}
+				Err(e) => return Err(e),
+			}
+		}
+		Ok(logs)
+	}
+
+	/// Reads a contract with `eth_call`, through the contract call cache
+	#[instrument(skip(self), fields(address))]
+	async fn call(&self, address: &str, calldata: &str) -> Result<String, anyhow::Error> {
+		if let Some(result) = self
+			.call_cache
+			.as_ref()
+			.and_then(|cache| cache.get(&self.network_slug, address, calldata))
+		{
+			return Ok(result);
+		}
+
+		let params = json!([{ "to": address, "data": calldata }, "latest"]);
+		let response = self
+			.http_client
+			.send_raw_request("eth_call", Some(params))
+			.await
+			.with_context(|| format!("Failed to call {}", address))?;
+
+		let result = response
+			.get("result")
+			.and_then(|v| v.as_str())
+			.ok_or_else(|| anyhow::anyhow!("Missing 'result' field"))?
+			.to_string();
+		if let Some(cache) = &self.call_cache {
+			cache.insert(&self.network_slug, address, calldata, result.clone());
+		}
+		Ok(result)
+	}
+
+	/// Reads a storage slot of a contract with `eth_getStorageAt`
+	#[instrument(skip(self), fields(address, slot))]
+	async fn get_storage_at(&self, address: &str, slot: &str) -> Result<String, anyhow::Error> {
+		let params = json!([address, slot, "latest"]);
+		let response = self
+			.http_client
+			.send_raw_request("eth_getStorageAt", Some(params))
+			.await
+			.with_context(|| format!("Failed to get storage of {}", address))?;
+
+		Ok(response
+			.get("result")
+			.and_then(|v| v.as_str())
+			.ok_or_else(|| anyhow::anyhow!("Missing 'result' field"))?
+			.to_string())
+	}
+
+	/// Traces the calls of a transaction with the `callTracer`
+	#[instrument(skip(self), fields(transaction_hash))]
+	async fn trace_transaction(
+		&self,
+		transaction_hash: &str,
+	) -> Result<serde_json::Value, anyhow::Error> {
+		let params = json!([transaction_hash, { "tracer": "callTracer" }]);
+		let response = self
+			.http_client
+			.send_raw_request("debug_traceTransaction", Some(params))
+			.await
+			.with_context(|| format!("Failed to trace transaction: {}", transaction_hash))?;
+
+		let trace = response
+			.get("result")
+			.with_context(|| "Missing 'result' field")?;
+		if trace.is_null() {
+			return Err(anyhow::anyhow!("Transaction trace not found"));
+		}
+		Ok(trace.clone())
+	}
+}
+
+impl<T: Send + Sync + Clone + BlockchainTransport> EvmClient<T> {
+	/// Sends a single `eth_getLogs` query
+	async fn query_logs(
+		&self,
+		from_block: u64,
+		to_block: u64,
+		addresses: &Option<Vec<String>>,
 	) -> Result<Vec<EVMReceiptLog>, anyhow::Error> {
 		// Convert parameters to JSON-RPC format
 		let params = json!([{
@@ -169,16 +406,108 @@ impl<T: Send + Sync + Clone + BlockchainTransport> EvmClientTrait for EvmClient<
 				)
 			})?;
 
+		// Errors passed through by the transport are reported with their message, so that
+		// limit errors can be recognized
+		if let Some(error) = response.get("error") {
+			return Err(TransportError::rpc_error(
+				error
+					.get("code")
+					.and_then(|code| code.as_i64())
+					.unwrap_or(0),
+				error
+					.get("message")
+					.and_then(|message| message.as_str())
+					.unwrap_or_default(),
+				self.http_client.get_current_url().await,
+				None,
+				None,
+			))
+			.with_context(|| {
+				format!(
+					"Failed to get logs for blocks: {} - {}",
+					from_block, to_block
+				)
+			});
+		}
+
 		// Extract the "result" field from the JSON-RPC response
 		let logs_data = response
 			.get("result")
 			.with_context(|| "Missing 'result' field")?;
 
 		// Parse the response into the expected type
-		Ok(serde_json::from_value(logs_data.clone()).with_context(|| "Failed to parse logs")?)
+		serde_json::from_value(logs_data.clone()).with_context(|| "Failed to parse logs")
 	}
 }
 
+/// Splits a block range into consecutive ranges of at most `max_block_range` blocks
+///
+/// # Arguments
+/// * `from_block` - Starting block number
+/// * `to_block` - Ending block number
+/// * `max_block_range` - Maximum number of blocks of a range, unlimited if `None`
+///
+/// # Returns
+/// The inclusive ranges, in block order
+fn split_block_range(
+	from_block: u64,
+	to_block: u64,
+	max_block_range: Option<u64>,
+) -> Vec<(u64, u64)> {
+	let Some(max_block_range) = max_block_range.filter(|max| *max > 0) else {
+		return vec![(from_block, to_block)];
+	};
+	let mut ranges = Vec::new();
+	let mut from = from_block;
+	while from <= to_block {
+		let to = from.saturating_add(max_block_range - 1).min(to_block);
+		ranges.push((from, to));
+		if to == u64::MAX {
+			break;
+		}
+		from = to + 1;
+	}
+	ranges
+}
+
+/// Returns the messages of the errors of the `eth_getLogs` queries exceeding the limits of the
+/// provider
+///
+/// # Arguments
+/// * `extra_patterns` - Messages of the limit errors configured for the network, in addition to
+///   those of common providers
+fn logs_limit_error_patterns(extra_patterns: &[String]) -> Vec<String> {
+	GET_LOGS_LIMIT_ERROR_PATTERNS
+		.iter()
+		.map(|pattern| pattern.to_string())
+		.chain(extra_patterns.iter().map(|pattern| pattern.to_lowercase()))
+		.collect()
+}
+
+/// Returns whether an `eth_getLogs` query failed for exceeding the limits of the provider
+///
+/// # Arguments
+/// * `error` - The error of the query
+/// * `extra_patterns` - Messages of the limit errors configured for the network, in addition to
+///   those of common providers
+fn is_logs_limit_error(error: &anyhow::Error, extra_patterns: &[String]) -> bool {
+	let Some(message) =
+		error
+			.chain()
+			.find_map(|cause| match cause.downcast_ref::<TransportError>() {
+				Some(TransportError::RpcError { message, .. }) => Some(message.clone()),
+				Some(TransportError::Http { body, .. }) => Some(body.clone()),
+				_ => None,
+			})
+	else {
+		return false;
+	};
+	let message = message.to_lowercase();
+	logs_limit_error_patterns(extra_patterns)
+		.iter()
+		.any(|pattern| message.contains(pattern.as_str()))
+}
+
 #[async_trait]
 impl<T: Send + Sync + Clone + BlockchainTransport> BlockChainClient for EvmClient<T> {
 	/// Retrieves the latest block number with retry functionality
@@ -201,6 +530,23 @@ impl<T: Send + Sync + Clone + BlockchainTransport> BlockChainClient for EvmClien
 			.map_err(|e| anyhow::anyhow!("Failed to parse block number: {}", e))
 	}
 
+	/// Checks whether code is deployed at an address with `eth_getCode`
+	#[instrument(skip(self), fields(address))]
+	async fn has_contract_code(&self, address: &str) -> Result<bool, anyhow::Error> {
+		let params = json!([address, "latest"]);
+		let response = self
+			.http_client
+			.send_raw_request("eth_getCode", Some(params))
+			.await
+			.with_context(|| format!("Failed to get code of {}", address))?;
+
+		let code = response
+			.get("result")
+			.and_then(|v| v.as_str())
+			.ok_or_else(|| anyhow::anyhow!("Missing 'result' field"))?;
+		Ok(!code.trim_start_matches("0x").is_empty())
+	}
+
 	/// Retrieves blocks within the specified range with retry functionality
 	///
 	/// # Note
@@ -18,10 +18,10 @@ use crate::{
 	services::{
 		blockchain::{
 			client::{BlockChainClient, BlockFetchResult, BlockFilterFactory, FetchStreamKind},
-			transports::{SolanaGetBlockConfig, SolanaTransportClient},
+			transports::{SolanaGetBlockConfig, SolanaTransportClient, TransportContext},
 			BlockchainTransport,
 		},
-		filter::SolanaBlockFilter,
+		filter::{FilterContext, SolanaBlockFilter},
 	},
 };
 
@@ -456,7 +456,15 @@ impl<T: Send + Sync + Clone> SolanaClient<T> {
 impl SolanaClient<SolanaTransportClient> {
 	/// Creates a new Solana client instance
 	pub async fn new(network: &Network) -> Result<Self, anyhow::Error> {
-		let http_client = SolanaTransportClient::new(network).await?;
+		Self::new_with_context(network, &TransportContext::default()).await
+	}
+
+	/// Creates a new Solana client instance sharing the state of the transports of a client pool
+	pub async fn new_with_context(
+		network: &Network,
+		context: &TransportContext,
+	) -> Result<Self, anyhow::Error> {
+		let http_client = SolanaTransportClient::new_with_context(network, context).await?;
 		Ok(Self::new_with_transport(http_client))
 	}
 }
@@ -959,7 +967,7 @@ impl<T: Send + Sync + Clone + BlockchainTransport> SolanaClientTrait for SolanaC
 impl<T: Send + Sync + Clone + BlockchainTransport> BlockFilterFactory<Self> for SolanaClient<T> {
 	type Filter = SolanaBlockFilter<Self>;
 
-	fn filter() -> Self::Filter {
+	fn filter(_context: &FilterContext) -> Self::Filter {
 		SolanaBlockFilter {
 			_client: PhantomData {},
 		}
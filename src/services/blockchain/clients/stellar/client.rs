@@ -19,7 +19,7 @@ use crate::{
 	services::{
 		blockchain::{
 			client::{BlockChainClient, BlockFilterFactory},
-			transports::StellarTransportClient,
+			transports::{StellarTransportClient, TransportContext},
 			BlockchainTransport,
 		},
 		filter::{
@@ -27,7 +27,7 @@ use crate::{
 				get_contract_code_ledger_key, get_contract_instance_ledger_key, get_contract_spec,
 				get_wasm_code_from_ledger_entry_data, get_wasm_hash_from_ledger_entry_data,
 			},
-			StellarBlockFilter,
+			FilterContext, StellarBlockFilter,
 		},
 	},
 };
@@ -136,7 +136,22 @@ impl StellarClient<StellarTransportClient> {
 	/// # Returns
 	/// * `Result<Self, anyhow::Error>` - New client instance or connection error
 	pub async fn new(network: &Network) -> Result<Self, anyhow::Error> {
-		let http_client = StellarTransportClient::new(network).await?;
+		Self::new_with_context(network, &TransportContext::default()).await
+	}
+
+	/// Creates a new Stellar client instance sharing the state of the transports of a client pool
+	///
+	/// # Arguments
+	/// * `network` - Network configuration containing RPC endpoints and chain details
+	/// * `context` - State shared by the transports of the client pool
+	///
+	/// # Returns
+	/// * `Result<Self, anyhow::Error>` - New client instance or connection error
+	pub async fn new_with_context(
+		network: &Network,
+		context: &TransportContext,
+	) -> Result<Self, anyhow::Error> {
+		let http_client = StellarTransportClient::new_with_context(network, context).await?;
 		Ok(Self::new_with_transport(http_client))
 	}
 }
@@ -459,7 +474,7 @@ impl<T: Send + Sync + Clone + BlockchainTransport> StellarClientTrait for Stella
 impl<T: Send + Sync + Clone + BlockchainTransport> BlockFilterFactory<Self> for StellarClient<T> {
 	type Filter = StellarBlockFilter<Self>;
 
-	fn filter() -> Self::Filter {
+	fn filter(_context: &FilterContext) -> Self::Filter {
 		StellarBlockFilter {
 			_client: PhantomData {},
 		}
@@ -716,4 +731,35 @@ impl<T: Send + Sync + Clone + BlockchainTransport> BlockChainClient for StellarC
 			contract_spec,
 		)))
 	}
+
+	/// Checks whether a contract is deployed by looking up its instance ledger entry
+	#[instrument(skip(self), fields(address))]
+	async fn has_contract_code(&self, address: &str) -> Result<bool, anyhow::Error> {
+		let contract_instance_ledger_key = get_contract_instance_ledger_key(address)
+			.map_err(|e| anyhow::anyhow!("Failed to get contract instance ledger key: {}", e))?;
+		let contract_instance_ledger_key_xdr = BASE64_STANDARD.encode(
+			contract_instance_ledger_key
+				.to_xdr(Limits::none())
+				.map_err(|e| {
+					anyhow::anyhow!(
+						"Failed to convert contract instance ledger key to XDR: {}",
+						e
+					)
+				})?,
+		);
+
+		let params = json!({
+			"keys": [contract_instance_ledger_key_xdr],
+			"xdrFormat": "base64"
+		});
+		let response = self
+			.http_client
+			.send_raw_request(RPC_METHOD_GET_LEDGER_ENTRIES, Some(params))
+			.await
+			.with_context(|| format!("Failed to get contract instance of {}", address))?;
+
+		Ok(response["result"]["entries"]
+			.as_array()
+			.is_some_and(|entries| !entries.is_empty()))
+	}
 }
@@ -3,7 +3,9 @@
 //! Provides a comprehensive error handling system for blockchain operations,
 //! including network connectivity, request processing, and blockchain-specific errors.
 
-use crate::utils::logging::error::{ErrorContext, TraceableError};
+use crate::utils::logging::error::{
+	classify_error_chain, ClassifiedError, ErrorClass, ErrorContext, TraceableError,
+};
 use std::collections::HashMap;
 use thiserror::Error as ThisError;
 use uuid::Uuid;
@@ -110,6 +112,22 @@ impl TraceableError for BlockChainError {
 	}
 }
 
+impl ClassifiedError for BlockChainError {
+	fn classification(&self) -> ErrorClass {
+		match self {
+			Self::ConnectionError(ctx) | Self::ClientPoolError(ctx) => {
+				classify_error_chain(ctx, ErrorClass::Transient)
+			}
+			// The block may not be available on the endpoint yet
+			Self::BlockNotFound(_) => ErrorClass::Transient,
+			Self::RequestError(ctx) | Self::TransactionError(ctx) | Self::InternalError(ctx) => {
+				classify_error_chain(ctx, ErrorClass::Permanent)
+			}
+			Self::Other(e) => classify_error_chain(e.as_ref(), ErrorClass::Permanent),
+		}
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -272,4 +290,24 @@ mod tests {
 		// Other variant should generate a new UUID
 		assert!(!block_chain_error.trace_id().is_empty());
 	}
+
+	#[test]
+	fn test_classification() {
+		let error = BlockChainError::connection_error("test error", None, None);
+		assert_eq!(error.classification(), ErrorClass::Transient);
+
+		let error = BlockChainError::request_error("test error", None, None);
+		assert_eq!(error.classification(), ErrorClass::Permanent);
+
+		// Errors wrapped by anyhow keep the classification of their cause
+		let error = BlockChainError::Other(
+			anyhow::Error::new(crate::services::blockchain::TransportError::network(
+				"Connection reset",
+				None,
+				None,
+			))
+			.context("Failed to get block"),
+		);
+		assert_eq!(error.classification(), ErrorClass::Transient);
+	}
 }
@@ -8,13 +8,16 @@
 //! - Network transport implementations
 //! - Error handling for blockchain operations
 //! - Client pool for managing multiple clients
+//! - Cache of contract reads
 
+mod call_cache;
 mod client;
 mod clients;
 mod error;
 mod pool;
 mod transports;
 
+pub use call_cache::{ContractCallCache, DEFAULT_CALL_CACHE_MAX_ENTRIES, DEFAULT_CALL_CACHE_TTL};
 pub use client::{BlockChainClient, BlockFetchResult, BlockFilterFactory, FetchStreamKind};
 pub use clients::{
 	EvmClient, EvmClientTrait, MidnightClient, MidnightClientTrait, MidnightSubstrateClientTrait,
@@ -22,11 +25,12 @@ pub use clients::{
 	StellarClientError, StellarClientTrait,
 };
 pub use error::BlockChainError;
-pub use pool::{ClientPool, ClientPoolTrait};
+pub use pool::{client_key, ClientPool, ClientPoolTrait};
 pub use transports::{
 	BlockchainTransport, EVMTransportClient, HttpEndpointManager, HttpTransportClient,
-	MidnightWsTransportClient, RotatingTransport, SolanaCommitment, SolanaGetBlockConfig,
-	SolanaGetTransactionConfig, SolanaTransportClient, StellarTransportClient,
-	TransientErrorRetryStrategy, TransportError, WsConfig, WsEndpointManager, WsTransportClient,
+	MidnightWsTransportClient, ProviderLimiter, ProviderLimiters, RotatingTransport, RpcCache,
+	RpcCacheMode, SolanaCommitment, SolanaGetBlockConfig, SolanaGetTransactionConfig,
+	SolanaTransportClient, StellarTransportClient, TransientErrorRetryStrategy, TransportContext,
+	TransportError, WsConfig, WsEndpointManager, WsTransportClient, DEFAULT_RPC_CACHE_DIR,
 	ROTATE_ON_ERROR_CODES,
 };
@@ -1,11 +1,15 @@
 //! Client pool for managing blockchain clients.
 //!
 //! This module provides a thread-safe client pooling system that:
-//! - Caches blockchain clients by network
+//! - Caches blockchain clients by network and RPC endpoints, so that the monitors pinning their
+//!   own endpoints get their own clients
 //! - Creates clients lazily on first use
 //! - Handles EVM, Stellar, Midnight, and Solana clients
 //! - Provides type-safe access to clients
 //! - Manages client lifecycles automatically
+//! - Shares the cache of contract reads between its EVM clients
+//! - Shares the state of the transports, such as the limiters of the RPC providers, between
+//!   its clients
 //!
 //! The pool uses a fast path for existing clients and a slow path for
 //! creating new ones, optimizing performance while maintaining safety.
@@ -14,16 +18,23 @@ use crate::utils::client_storage::ClientStorage;
 use crate::{
 	models::{BlockChainType, Network},
 	services::blockchain::{
-		BlockChainClient, BlockFilterFactory, EVMTransportClient, EvmClient, EvmClientTrait,
-		MidnightClient, MidnightClientTrait, MidnightWsTransportClient, SolanaClient,
-		SolanaClientTrait, SolanaTransportClient, StellarClient, StellarClientTrait,
-		StellarTransportClient,
+		BlockChainClient, BlockFilterFactory, ContractCallCache, EVMTransportClient, EvmClient,
+		EvmClientTrait, MidnightClient, MidnightClientTrait, MidnightWsTransportClient, RpcCache,
+		SolanaClient, SolanaClientTrait, SolanaTransportClient, StellarClient, StellarClientTrait,
+		StellarTransportClient, TransportContext,
 	},
+	services::blockwatcher::EndpointCapabilityRegistry,
+	utils::RetryBudgets,
 };
 use anyhow::Context;
 use async_trait::async_trait;
 use futures::future::BoxFuture;
-use std::{any::Any, collections::HashMap, sync::Arc};
+use std::{
+	any::Any,
+	collections::HashMap,
+	hash::{DefaultHasher, Hash, Hasher},
+	sync::Arc,
+};
 
 /// Trait for the client pool.
 #[async_trait]
@@ -73,6 +84,10 @@ pub trait ClientPoolTrait: Send + Sync {
 pub struct ClientPool {
 	/// Map of client storages indexed by client type
 	pub storages: HashMap<BlockChainType, Box<dyn Any + Send + Sync>>,
+	/// Cache of the contract reads of the EVM clients
+	call_cache: Arc<ContractCallCache>,
+	/// State shared by the transports of the clients
+	transport_context: TransportContext,
 }
 
 impl ClientPool {
@@ -82,6 +97,8 @@ impl ClientPool {
 	pub fn new() -> Self {
 		let mut pool = Self {
 			storages: HashMap::new(),
+			call_cache: Arc::new(ContractCallCache::default()),
+			transport_context: TransportContext::default(),
 		};
 
 		// Register client types
@@ -95,6 +112,47 @@ impl ClientPool {
 		pool
 	}
 
+	/// Sets the cache of the contract reads of the EVM clients
+	///
+	/// # Arguments
+	/// * `call_cache` - The cache, shared by the EVM clients of the pool
+	pub fn with_call_cache(mut self, call_cache: ContractCallCache) -> Self {
+		self.call_cache = Arc::new(call_cache);
+		self
+	}
+
+	/// Sets the registry of the capabilities detected on the endpoints
+	///
+	/// # Arguments
+	/// * `capabilities` - The registry, shared by the clients of the pool
+	pub fn with_capabilities(mut self, capabilities: Arc<EndpointCapabilityRegistry>) -> Self {
+		self.transport_context.capabilities = capabilities;
+		self
+	}
+
+	/// Sets the retry budgets of the RPC requests of the clients
+	///
+	/// # Arguments
+	/// * `retry_budgets` - The budgets, kept per network
+	pub fn with_retry_budgets(mut self, retry_budgets: RetryBudgets) -> Self {
+		self.transport_context.retry_budgets = Arc::new(retry_budgets);
+		self
+	}
+
+	/// Sets the record/replay cache of the RPC responses
+	///
+	/// # Arguments
+	/// * `rpc_cache` - The cache, `None` to send every request to the endpoints
+	pub fn with_rpc_cache(mut self, rpc_cache: Option<RpcCache>) -> Self {
+		self.transport_context.rpc_cache = rpc_cache;
+		self
+	}
+
+	/// Returns the state shared by the transports of the clients of the pool
+	pub fn transport_context(&self) -> &TransportContext {
+		&self.transport_context
+	}
+
 	fn register_client_type<T: 'static + Send + Sync>(&mut self, client_type: BlockChainType) {
 		self.storages
 			.insert(client_type, Box::new(ClientStorage::<T>::new()));
@@ -120,8 +178,10 @@ impl ClientPool {
 			.and_then(|s| s.downcast_ref::<ClientStorage<T>>())
 			.with_context(|| "Invalid client type")?;
 
+		let key = client_key(network);
+
 		// Fast path: check if client exists
-		if let Some(client) = storage.clients.read().await.get(&network.slug) {
+		if let Some(client) = storage.clients.read().await.get(&key) {
 			return Ok(client.clone());
 		}
 
@@ -129,13 +189,13 @@ impl ClientPool {
 		let mut clients = storage.clients.write().await;
 
 		// Double-check client was not created while waiting for the write lock
-		if let Some(client) = clients.get(&network.slug) {
+		if let Some(client) = clients.get(&key) {
 			return Ok(client.clone());
 		}
 
 		// Create and insert
 		let client = Arc::new(create_fn(network).await?);
-		clients.insert(network.slug.clone(), client.clone());
+		clients.insert(key, client.clone());
 		Ok(client)
 	}
 
@@ -169,7 +229,13 @@ impl ClientPoolTrait for ClientPool {
 	) -> Result<Arc<Self::EvmClient>, anyhow::Error> {
 		self.get_or_create_client(BlockChainType::EVM, network, |n| {
 			let network = n.clone();
-			Box::pin(async move { Self::EvmClient::new(&network).await })
+			let call_cache = self.call_cache.clone();
+			let context = self.transport_context.clone();
+			Box::pin(async move {
+				Ok(Self::EvmClient::new_with_context(&network, &context)
+					.await?
+					.with_call_cache(call_cache))
+			})
 		})
 		.await
 		.with_context(|| "Failed to get or create EVM client")
@@ -185,7 +251,8 @@ impl ClientPoolTrait for ClientPool {
 	) -> Result<Arc<Self::StellarClient>, anyhow::Error> {
 		self.get_or_create_client(BlockChainType::Stellar, network, |n| {
 			let network = n.clone();
-			Box::pin(async move { Self::StellarClient::new(&network).await })
+			let context = self.transport_context.clone();
+			Box::pin(async move { Self::StellarClient::new_with_context(&network, &context).await })
 		})
 		.await
 		.with_context(|| "Failed to get or create Stellar client")
@@ -217,7 +284,8 @@ impl ClientPoolTrait for ClientPool {
 	) -> Result<Arc<Self::SolanaClient>, anyhow::Error> {
 		self.get_or_create_client(BlockChainType::Solana, network, |n| {
 			let network = n.clone();
-			Box::pin(async move { Self::SolanaClient::new(&network).await })
+			let context = self.transport_context.clone();
+			Box::pin(async move { Self::SolanaClient::new_with_context(&network, &context).await })
 		})
 		.await
 		.with_context(|| "Failed to get or create Solana client")
@@ -232,7 +300,7 @@ impl ClientPoolTrait for ClientPool {
 		network: &Network,
 		addresses: Vec<String>,
 	) -> Result<Arc<Self::SolanaClient>, anyhow::Error> {
-		let client = Self::SolanaClient::new(network)
+		let client = Self::SolanaClient::new_with_context(network, &self.transport_context)
 			.await
 			.with_context(|| "Failed to create Solana client")?;
 		let client = client.with_monitored_addresses(addresses);
@@ -240,6 +308,22 @@ impl ClientPoolTrait for ClientPool {
 	}
 }
 
+/// Returns the key of the client of a network in the pool
+///
+/// The key identifies the RPC endpoints along with the network, so that a network whose
+/// endpoints are overridden by a monitor (see [`Monitor::rpc_network`]) gets its own client.
+///
+/// [`Monitor::rpc_network`]: crate::models::Monitor::rpc_network
+pub fn client_key(network: &Network) -> String {
+	let mut hasher = DefaultHasher::new();
+	for rpc_url in &network.rpc_urls {
+		rpc_url.type_.hash(&mut hasher);
+		rpc_url.url.as_str().hash(&mut hasher);
+		rpc_url.weight.hash(&mut hasher);
+	}
+	format!("{}#{:016x}", network.slug, hasher.finish())
+}
+
 impl Default for ClientPool {
 	fn default() -> Self {
 		Self::new()
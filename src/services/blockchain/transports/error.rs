@@ -2,7 +2,9 @@
 //!
 //! Provides error handling for network communication, JSON parsing, request serialization and URL rotation.
 
-use crate::utils::logging::error::{ErrorContext, TraceableError};
+use crate::utils::logging::error::{
+	classify_error_chain, ClassifiedError, ErrorClass, ErrorContext, TraceableError,
+};
 use std::collections::HashMap;
 use thiserror::Error;
 
@@ -126,6 +128,47 @@ impl TraceableError for TransportError {
 	}
 }
 
+/// JSON-RPC error code commonly returned by providers when a request limit is exceeded
+const JSONRPC_LIMIT_EXCEEDED: i64 = -32005;
+
+impl ClassifiedError for TransportError {
+	fn classification(&self) -> ErrorClass {
+		match self {
+			Self::Http { status_code, .. } => ErrorClass::from_status_code(status_code.as_u16()),
+			Self::Network(_) => ErrorClass::Transient,
+			Self::ResponseParse(_) | Self::RequestSerialization(_) => ErrorClass::Permanent,
+			Self::UrlRotation(ctx) => classify_error_chain(ctx, ErrorClass::Transient),
+			Self::RpcError { code, message, .. } => classify_jsonrpc_error(*code, message),
+		}
+	}
+}
+
+/// Classifies a JSON-RPC error returned by an endpoint
+///
+/// Rate limits are reported under provider-specific codes, so the message is inspected as
+/// well. Invalid request and parse errors are permanent, any other error is assumed to be a
+/// transient condition of the endpoint.
+///
+/// # Arguments
+/// * `code` - The JSON-RPC error code
+/// * `message` - The JSON-RPC error message
+///
+/// # Returns
+/// The classification of the error
+pub fn classify_jsonrpc_error(code: i64, message: &str) -> ErrorClass {
+	let message = message.to_lowercase();
+	if code == JSONRPC_LIMIT_EXCEEDED
+		|| message.contains("rate limit")
+		|| message.contains("too many request")
+	{
+		return ErrorClass::RateLimited;
+	}
+	match code {
+		-32700 | -32600 | -32602 => ErrorClass::Permanent,
+		_ => ErrorClass::Transient,
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -321,4 +364,61 @@ mod tests {
 		};
 		assert_eq!(transport_error.trace_id(), original_trace_id);
 	}
+
+	#[test]
+	fn test_classification() {
+		let error = TransportError::http(
+			reqwest::StatusCode::TOO_MANY_REQUESTS,
+			"http://example.com".to_string(),
+			String::new(),
+			None,
+			None,
+		);
+		assert_eq!(error.classification(), ErrorClass::RateLimited);
+
+		let error = TransportError::http(
+			reqwest::StatusCode::UNAUTHORIZED,
+			"http://example.com".to_string(),
+			String::new(),
+			None,
+			None,
+		);
+		assert_eq!(error.classification(), ErrorClass::Permanent);
+
+		let error = TransportError::network("Connection reset", None, None);
+		assert_eq!(error.classification(), ErrorClass::Transient);
+
+		let error = TransportError::url_rotation(
+			"Failed to rotate",
+			Some(Box::new(TransportError::http(
+				reqwest::StatusCode::TOO_MANY_REQUESTS,
+				"http://example.com".to_string(),
+				String::new(),
+				None,
+				None,
+			))),
+			None,
+		);
+		assert_eq!(error.classification(), ErrorClass::RateLimited);
+	}
+
+	#[test]
+	fn test_classify_jsonrpc_error() {
+		assert_eq!(
+			classify_jsonrpc_error(-32005, "limit exceeded"),
+			ErrorClass::RateLimited
+		);
+		assert_eq!(
+			classify_jsonrpc_error(15, "Too many request, try again later"),
+			ErrorClass::RateLimited
+		);
+		assert_eq!(
+			classify_jsonrpc_error(-32602, "invalid params"),
+			ErrorClass::Permanent
+		);
+		assert_eq!(
+			classify_jsonrpc_error(-32603, "internal error"),
+			ErrorClass::Transient
+		);
+	}
 }
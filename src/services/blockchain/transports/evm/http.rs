@@ -11,7 +11,8 @@ use serde_json::Value;
 use crate::{
 	models::Network,
 	services::blockchain::transports::{
-		BlockchainTransport, HttpTransportClient, RotatingTransport, TransportError,
+		BlockchainTransport, HttpTransportClient, RotatingTransport, TransportContext,
+		TransportError,
 	},
 };
 
@@ -36,11 +37,39 @@ impl EVMTransportClient {
 	/// # Returns
 	/// * `Result<Self, anyhow::Error>` - A new client instance or connection error
 	pub async fn new(network: &Network) -> Result<Self, anyhow::Error> {
+		Self::new_with_context(network, &TransportContext::default()).await
+	}
+
+	/// Creates a new EVM transport client sharing the state of the transports of a client pool
+	///
+	/// # Arguments
+	/// * `network` - Network configuration containing RPC URLs and other network details
+	/// * `context` - State shared by the transports of the client pool
+	///
+	/// # Returns
+	/// * `Result<Self, anyhow::Error>` - A new client instance or connection error
+	pub async fn new_with_context(
+		network: &Network,
+		context: &TransportContext,
+	) -> Result<Self, anyhow::Error> {
 		let test_connection_payload =
 			Some(r#"{"id":1,"jsonrpc":"2.0","method":"net_version","params":[]}"#.to_string());
-		let http_client = HttpTransportClient::new(network, test_connection_payload, &[]).await?;
+		let http_client =
+			HttpTransportClient::new_with_context(network, test_connection_payload, &[], context)
+				.await?;
 		Ok(Self { http_client })
 	}
+
+	/// Sets the messages of the errors caused by the requests of a method rather than the
+	/// endpoint, returned without rotating endpoints
+	///
+	/// # Arguments
+	/// * `method` - The JSON-RPC method
+	/// * `patterns` - Case-insensitive substrings of the messages of the errors
+	pub fn with_request_errors(mut self, method: &str, patterns: Vec<String>) -> Self {
+		self.http_client = self.http_client.with_request_errors(method, patterns);
+		self
+	}
 }
 
 #[async_trait::async_trait]
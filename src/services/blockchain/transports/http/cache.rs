@@ -0,0 +1,228 @@
+//! Content-addressable cache of RPC responses.
+//!
+//! Records the responses of JSON-RPC requests on disk, keyed by a hash of the network, the
+//! method and the parameters, so that the full pipeline can later be replayed without
+//! network access (e.g. integration tests and local development). The cache is opt-in and
+//! configured with the `RPC_CACHE_MODE` (`record` or `replay`) and `RPC_CACHE_DIR` runtime
+//! settings, and shared by the transports of a client pool:
+//! - `record`: every request is sent to the endpoint and its response is stored
+//! - `replay`: responses are only read from the cache, a missing entry is an error
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::path::PathBuf;
+
+/// Default directory of the cached responses, in the data directory
+pub const DEFAULT_RPC_CACHE_DIR: &str = "rpc_cache";
+
+/// Mode of the RPC response cache
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RpcCacheMode {
+	/// Requests are sent to the endpoint and their responses stored
+	Record,
+	/// Responses are served from the cache only
+	Replay,
+}
+
+/// Cached response, stored with its request for readability
+#[derive(Debug, Serialize, Deserialize)]
+struct RpcCacheEntry {
+	network: String,
+	method: String,
+	params: Value,
+	response: Value,
+}
+
+/// On-disk cache of RPC responses
+#[derive(Debug, Clone)]
+pub struct RpcCache {
+	mode: RpcCacheMode,
+	dir: PathBuf,
+}
+
+impl RpcCache {
+	/// Creates a new cache
+	///
+	/// # Arguments
+	/// * `mode` - Record or replay mode
+	/// * `dir` - Directory of the cached responses
+	pub fn new(mode: RpcCacheMode, dir: PathBuf) -> Self {
+		Self { mode, dir }
+	}
+
+	/// Creates the cache of a configured mode
+	///
+	/// # Arguments
+	/// * `mode` - `record`, `replay` or `off`
+	/// * `dir` - Directory of the cached responses
+	///
+	/// # Returns
+	/// The cache, or `None` if caching is disabled
+	pub fn configured(mode: &str, dir: PathBuf) -> Option<Self> {
+		let mode = match mode.to_lowercase().as_str() {
+			"record" => RpcCacheMode::Record,
+			"replay" => RpcCacheMode::Replay,
+			"" | "off" => return None,
+			other => {
+				tracing::warn!("Ignoring unknown RPC_CACHE_MODE '{}'", other);
+				return None;
+			}
+		};
+		Some(Self::new(mode, dir))
+	}
+
+	/// Returns the mode of the cache
+	pub fn mode(&self) -> RpcCacheMode {
+		self.mode
+	}
+
+	/// Computes the cache key of a request
+	///
+	/// # Arguments
+	/// * `network_slug` - Network of the request
+	/// * `method` - JSON-RPC method
+	/// * `params` - Parameters of the request
+	///
+	/// # Returns
+	/// The hex-encoded SHA-256 hash of the request
+	pub fn key(network_slug: &str, method: &str, params: &Value) -> String {
+		let mut hasher = Sha256::new();
+		hasher.update(network_slug.as_bytes());
+		hasher.update([0]);
+		hasher.update(method.as_bytes());
+		hasher.update([0]);
+		hasher.update(params.to_string().as_bytes());
+		hex::encode(hasher.finalize())
+	}
+
+	/// Reads the cached response of a request
+	///
+	/// # Arguments
+	/// * `network_slug` - Network of the request
+	/// * `method` - JSON-RPC method
+	/// * `params` - Parameters of the request
+	///
+	/// # Returns
+	/// The cached response, if any
+	pub async fn get(&self, network_slug: &str, method: &str, params: &Value) -> Option<Value> {
+		let path = self.path(network_slug, method, params);
+		let content = tokio::fs::read_to_string(&path).await.ok()?;
+		match serde_json::from_str::<RpcCacheEntry>(&content) {
+			Ok(entry) => Some(entry.response),
+			Err(e) => {
+				tracing::warn!("Ignoring invalid RPC cache entry {}: {}", path.display(), e);
+				None
+			}
+		}
+	}
+
+	/// Stores the response of a request
+	///
+	/// # Arguments
+	/// * `network_slug` - Network of the request
+	/// * `method` - JSON-RPC method
+	/// * `params` - Parameters of the request
+	/// * `response` - Response to store
+	pub async fn put(&self, network_slug: &str, method: &str, params: &Value, response: &Value) {
+		let path = self.path(network_slug, method, params);
+		let entry = RpcCacheEntry {
+			network: network_slug.to_string(),
+			method: method.to_string(),
+			params: params.clone(),
+			response: response.clone(),
+		};
+
+		let result = async {
+			tokio::fs::create_dir_all(&self.dir).await?;
+			tokio::fs::write(&path, serde_json::to_string_pretty(&entry)?).await?;
+			Ok::<(), anyhow::Error>(())
+		}
+		.await;
+
+		if let Err(e) = result {
+			tracing::warn!("Failed to record RPC response to {}: {}", path.display(), e);
+		}
+	}
+
+	fn path(&self, network_slug: &str, method: &str, params: &Value) -> PathBuf {
+		self.dir
+			.join(format!("{}.json", Self::key(network_slug, method, params)))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use serde_json::json;
+	use tempfile::tempdir;
+
+	#[test]
+	fn test_key() {
+		let params = json!(["0x10", false]);
+		let key = RpcCache::key("ethereum_mainnet", "eth_getBlockByNumber", &params);
+
+		assert_eq!(key.len(), 64);
+		assert_eq!(
+			key,
+			RpcCache::key("ethereum_mainnet", "eth_getBlockByNumber", &params)
+		);
+		assert_ne!(
+			key,
+			RpcCache::key("polygon_mainnet", "eth_getBlockByNumber", &params)
+		);
+		assert_ne!(
+			key,
+			RpcCache::key(
+				"ethereum_mainnet",
+				"eth_getBlockByNumber",
+				&json!(["0x11", false])
+			)
+		);
+	}
+
+	#[test]
+	fn test_configured() {
+		let dir = PathBuf::from("data/rpc_cache");
+		assert_eq!(
+			RpcCache::configured("Replay", dir.clone()).map(|cache| cache.mode()),
+			Some(RpcCacheMode::Replay)
+		);
+		assert_eq!(
+			RpcCache::configured("record", dir.clone()).map(|cache| cache.mode()),
+			Some(RpcCacheMode::Record)
+		);
+		assert!(RpcCache::configured("off", dir.clone()).is_none());
+		assert!(RpcCache::configured("rewind", dir).is_none());
+	}
+
+	#[tokio::test]
+	async fn test_put_and_get() {
+		let temp_dir = tempdir().unwrap();
+		let cache = RpcCache::new(RpcCacheMode::Record, temp_dir.path().join("rpc"));
+		let params = json!(["0x10", false]);
+		let response = json!({"jsonrpc": "2.0", "id": 1, "result": {"number": "0x10"}});
+
+		assert!(cache
+			.get("ethereum_mainnet", "eth_getBlockByNumber", &params)
+			.await
+			.is_none());
+
+		cache
+			.put(
+				"ethereum_mainnet",
+				"eth_getBlockByNumber",
+				&params,
+				&response,
+			)
+			.await;
+
+		let replay = RpcCache::new(RpcCacheMode::Replay, temp_dir.path().join("rpc"));
+		assert_eq!(
+			replay
+				.get("ethereum_mainnet", "eth_getBlockByNumber", &params)
+				.await,
+			Some(response)
+		);
+	}
+}
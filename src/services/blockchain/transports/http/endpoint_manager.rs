@@ -1,18 +1,26 @@
 //! Manages the rotation of blockchain HTTP RPC endpoints
 //!
 //! Provides methods for rotating between multiple URLs and sending requests to the active endpoint
-//! with automatic fallback to other URLs on failure.
+//! with automatic fallback to other URLs on failure. Retrying a request on another URL is a
+//! retry of the RPC retry policy of the network, within its budget.
 use reqwest_middleware::ClientWithMiddleware;
 use serde::Serialize;
 use serde_json::Value;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 use url::Url;
 
-use crate::services::blockchain::transports::{
-	RotatingTransport, TransportError, ROTATE_ON_ERROR_CODES,
+use crate::{
+	services::{
+		blockchain::transports::{
+			classify_jsonrpc_error, http::provider::ProviderLimiter, RotatingTransport,
+			TransportError, ROTATE_ON_ERROR_CODES,
+		},
+		blockwatcher::{Capability, EndpointCapabilityRegistry},
+	},
+	utils::{logging::error::ErrorClass, RetryConfig, RetryPolicy},
 };
 
 /// Manages the rotation of blockchain RPC endpoints
@@ -28,6 +36,12 @@ use crate::services::blockchain::transports::{
 /// * `network_slug` - The network identifier for metrics labeling
 /// * `non_rotating_jsonrpc_codes` - JSON-RPC error codes that should not trigger endpoint
 ///   rotation (e.g. Solana skipped-slot codes that represent legitimate chain state).
+/// * `providers` - Limiters of the providers of the endpoints, by normalized URL
+/// * `request_errors` - Messages of the errors caused by the request rather than the endpoint,
+///   by JSON-RPC method
+/// * `retry_policy` - Retry policy of the RPC requests of the network, bounding the retries on
+///   other URLs
+/// * `capabilities` - Capabilities of the endpoints, as detected at startup
 #[derive(Clone, Debug)]
 pub struct EndpointManager {
 	pub active_url: Arc<RwLock<String>>,
@@ -36,6 +50,10 @@ pub struct EndpointManager {
 	rotation_lock: Arc<tokio::sync::Mutex<()>>,
 	network_slug: String,
 	non_rotating_jsonrpc_codes: &'static [i64],
+	providers: Arc<HashMap<String, Arc<ProviderLimiter>>>,
+	request_errors: Arc<HashMap<String, Vec<String>>>,
+	retry_policy: RetryPolicy,
+	capabilities: Arc<EndpointCapabilityRegistry>,
 }
 
 /// Represents the outcome of a `EndpointManager::attempt_request_on_url` method call
@@ -76,9 +94,101 @@ impl EndpointManager {
 			fallback_urls: Arc::new(RwLock::new(fallback_urls)),
 			rotation_lock: Arc::new(tokio::sync::Mutex::new(())),
 			client,
-			network_slug,
 			non_rotating_jsonrpc_codes,
+			providers: Arc::new(HashMap::new()),
+			request_errors: Arc::new(HashMap::new()),
+			retry_policy: RetryPolicy::new("rpc", &RetryConfig::default()),
+			capabilities: Arc::new(EndpointCapabilityRegistry::default()),
+			network_slug,
+		}
+	}
+
+	/// Sets the retry policy of the requests
+	///
+	/// Every retry of a request on another URL is counted against the policy, so that the
+	/// rotations of a failing network are bounded by its retry budget.
+	///
+	/// # Arguments
+	/// * `retry_policy` - The RPC retry policy of the network
+	pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+		self.retry_policy = retry_policy;
+		self
+	}
+
+	/// Sets the capabilities of the endpoints
+	///
+	/// Requests needing an optional capability are sent to an endpoint offering it. Endpoints
+	/// that were not probed are assumed to offer every capability.
+	///
+	/// # Arguments
+	/// * `capabilities` - The registry of the capabilities detected at startup
+	pub fn with_capabilities(mut self, capabilities: Arc<EndpointCapabilityRegistry>) -> Self {
+		self.capabilities = capabilities;
+		self
+	}
+
+	/// Sets the limiters of the providers of the endpoints
+	///
+	/// Requests to an endpoint wait for the rate limit of its provider, and its rate limit
+	/// responses pause the provider for all the networks using it.
+	///
+	/// # Arguments
+	/// * `providers` - Limiters of the providers, by endpoint URL
+	pub fn with_providers(mut self, providers: HashMap<String, Arc<ProviderLimiter>>) -> Self {
+		self.providers = Arc::new(
+			providers
+				.into_iter()
+				.map(|(url, limiter)| (normalize_url(&url), limiter))
+				.collect(),
+		);
+		self
+	}
+
+	/// Sets the messages of the errors caused by the requests of a method rather than the
+	/// endpoint, e.g. an `eth_getLogs` range exceeding the limits of the provider
+	///
+	/// Such errors are returned at once instead of rotating endpoints, since the other endpoints
+	/// would reject the request the same way and only the caller can change it.
+	///
+	/// # Arguments
+	/// * `method` - The JSON-RPC method
+	/// * `patterns` - Case-insensitive substrings of the messages of the errors
+	pub fn with_request_errors(mut self, method: &str, patterns: Vec<String>) -> Self {
+		let mut request_errors = (*self.request_errors).clone();
+		request_errors.insert(
+			method.to_string(),
+			patterns
+				.into_iter()
+				.map(|pattern| pattern.to_lowercase())
+				.collect(),
+		);
+		self.request_errors = Arc::new(request_errors);
+		self
+	}
+
+	/// Returns whether an error of a request is caused by the request rather than the endpoint
+	fn is_request_error(&self, method: &str, message: &str) -> bool {
+		let Some(patterns) = self.request_errors.get(method) else {
+			return false;
+		};
+		let message = message.to_lowercase();
+		patterns.iter().any(|pattern| message.contains(pattern))
+	}
+
+	/// Returns the limiter of the provider of an endpoint, if it has one
+	fn provider_of(&self, url: &str) -> Option<&Arc<ProviderLimiter>> {
+		if self.providers.is_empty() {
+			return None;
 		}
+		self.providers.get(&normalize_url(url))
+	}
+
+	/// Returns whether an endpoint offers a capability, as detected at startup
+	fn supports(&self, url: &str, capability: Option<Capability>) -> bool {
+		capability.is_none_or(|capability| {
+			self.capabilities
+				.supports(&self.network_slug, url, capability)
+		})
 	}
 
 	/// Updates the client with a new client
@@ -101,6 +211,22 @@ impl EndpointManager {
 	pub async fn try_rotate_url<T: RotatingTransport>(
 		&self,
 		transport: &T,
+	) -> Result<String, TransportError> {
+		self.rotate_url(transport, None).await
+	}
+
+	/// Rotates to the next available URL, preferring the endpoints offering a capability
+	///
+	/// # Arguments
+	/// * `transport` - The transport client implementing the RotatingTransport trait
+	/// * `capability` - The capability needed by the request being sent, if any
+	///
+	/// # Returns
+	/// * `Result<String, TransportError>` - The result of the rotation attempt, containing the new active URL or an error
+	async fn rotate_url<T: RotatingTransport>(
+		&self,
+		transport: &T,
+		capability: Option<Capability>,
 	) -> Result<String, TransportError> {
 		// Acquire the rotation lock to prevent concurrent rotations
 		let _guard = self.rotation_lock.lock().await;
@@ -114,9 +240,24 @@ impl EndpointManager {
 		);
 
 		// --- Select a new URL ---
-		let new_url = match current_fallbacks_snapshot
-			.iter()
-			.find(|&url| *url != initial_active_url)
+		// Endpoints without the capability needed by the request, then endpoints of providers
+		// paused by a rate limit response, are only used as a last resort
+		let candidates = || {
+			current_fallbacks_snapshot
+				.iter()
+				.filter(|&url| *url != initial_active_url)
+		};
+		let available = |url: &&String| {
+			!self
+				.provider_of(url)
+				.is_some_and(|limiter| limiter.is_backing_off())
+		};
+		let new_url = match candidates()
+			.filter(|url| self.supports(url, capability))
+			.find(available)
+			.or_else(|| candidates().find(|url| self.supports(url, capability)))
+			.or_else(|| candidates().find(available))
+			.or_else(|| candidates().next())
 		{
 			Some(url) => url.clone(),
 			None => {
@@ -192,6 +333,33 @@ impl EndpointManager {
 		Ok(new_url)
 	}
 
+	/// Rotates to the next URL to retry a failed request, unless the retry policy gives up
+	///
+	/// # Arguments
+	/// * `transport` - The transport client implementing the RotatingTransport trait
+	/// * `capability` - The capability needed by the request, if any
+	/// * `n_past_retries` - Number of retries of the request already made, incremented
+	///
+	/// # Returns
+	/// * `Result<String, TransportError>` - The new active URL, or the reason it was not rotated
+	async fn rotate_for_retry<T: RotatingTransport>(
+		&self,
+		transport: &T,
+		capability: Option<Capability>,
+		n_past_retries: &mut u32,
+	) -> Result<String, TransportError> {
+		// Other URLs are retried at once, the policy only bounds the number of retries
+		if self.retry_policy.next_retry(*n_past_retries).is_none() {
+			return Err(TransportError::url_rotation(
+				"Retries of the request exhausted",
+				None,
+				None,
+			));
+		}
+		*n_past_retries += 1;
+		self.rotate_url(transport, capability).await
+	}
+
 	/// Attempts to send a request to the specified URL
 	/// # Arguments
 	/// * `url` - The URL to send the request to
@@ -283,6 +451,25 @@ impl EndpointManager {
 			endpoints.len()
 		};
 		let mut tried_urls: HashSet<String> = HashSet::new();
+		let mut n_past_retries = 0;
+
+		// Requests of optional APIs (e.g. `debug_traceTransaction`) are sent to an endpoint
+		// offering them
+		let capability = Capability::required_by(method);
+		let active_url = self.active_url.read().await.clone();
+		if !self.supports(&active_url, capability)
+			&& self
+				.fallback_urls
+				.read()
+				.await
+				.iter()
+				.any(|url| self.supports(url, capability))
+		{
+			crate::utils::metrics::record_endpoint_rotation(&self.network_slug, "capability");
+			if let Err(e) = self.rotate_url(transport, capability).await {
+				tracing::warn!("Failed to rotate to an endpoint serving {}: {}", method, e);
+			}
+		}
 
 		loop {
 			let attempt_start = Instant::now();
@@ -291,6 +478,9 @@ impl EndpointManager {
 			crate::utils::metrics::record_rpc_request(&self.network_slug, method);
 
 			let current_url_snapshot = self.active_url.read().await.clone();
+			if let Some(limiter) = self.provider_of(&current_url_snapshot) {
+				limiter.acquire(&self.network_slug).await;
+			}
 			let current_host_snapshot = Url::parse(&current_url_snapshot)
 				.ok()
 				.and_then(|u| u.host_str().map(|s| s.to_string()))
@@ -367,16 +557,30 @@ impl EndpointManager {
 									"jsonrpc",
 								);
 
+								let error_class = classify_jsonrpc_error(code, &message);
 								tracing::warn!(
+									error_class = %error_class,
 									"JSON-RPC error from {}: code {} - {}",
 									current_host_snapshot,
 									code,
 									message,
 								);
+								if error_class == ErrorClass::RateLimited {
+									crate::utils::metrics::record_rate_limit(
+										&self.network_slug,
+										&current_host_snapshot,
+									);
+									if let Some(limiter) = self.provider_of(&current_url_snapshot) {
+										limiter.back_off(None);
+									}
+								}
 
 								// Stop once every distinct endpoint has been tried; otherwise
-								// healthy-but-erroring endpoints would cycle forever.
-								if tried_urls.len() >= total_unique_endpoints {
+								// healthy-but-erroring endpoints would cycle forever. Errors of
+								// the request itself are returned to the caller at once.
+								if tried_urls.len() >= total_unique_endpoints
+									|| self.is_request_error(method, &message)
+								{
 									return Err(TransportError::rpc_error(
 										code,
 										message,
@@ -388,10 +592,17 @@ impl EndpointManager {
 
 								crate::utils::metrics::record_endpoint_rotation(
 									&self.network_slug,
-									"jsonrpc_error",
+									if error_class == ErrorClass::RateLimited {
+										"rate_limit"
+									} else {
+										"jsonrpc_error"
+									},
 								);
 
-								match self.try_rotate_url(transport).await {
+								match self
+									.rotate_for_retry(transport, capability, &mut n_past_retries)
+									.await
+								{
 									Ok(_new_url) => continue, // Retry on the new active URL
 									Err(rotation_error) => {
 										return Err(TransportError::rpc_error(
@@ -436,7 +647,10 @@ impl EndpointManager {
 									"jsonrpc_error",
 								);
 
-								match self.try_rotate_url(transport).await {
+								match self
+									.rotate_for_retry(transport, capability, &mut n_past_retries)
+									.await
+								{
 									Ok(_new_url) => continue,
 									Err(rotation_error) => {
 										return Err(TransportError::rpc_error(
@@ -452,6 +666,7 @@ impl EndpointManager {
 						}
 					} else {
 						// HTTP error
+						let retry_after = retry_after(&response);
 						let error_body = response.text().await.unwrap_or_default();
 						let status_code = status.as_u16();
 
@@ -470,9 +685,12 @@ impl EndpointManager {
 						);
 
 						// Check if we should rotate based on status code
-						if ROTATE_ON_ERROR_CODES.contains(&status_code) {
-							// Record rate limit metric only for 429
-							if status_code == 429 {
+						let error_class = ErrorClass::from_status_code(status_code);
+						if ROTATE_ON_ERROR_CODES.contains(&status_code)
+							&& !self.is_request_error(method, &error_body)
+						{
+							// Record rate limit metric only for rate limited responses
+							if error_class == ErrorClass::RateLimited {
 								let endpoint_label = Url::parse(&current_url_snapshot)
 									.ok()
 									.and_then(|u| u.host_str().map(|h| h.to_string()))
@@ -482,6 +700,9 @@ impl EndpointManager {
 									&self.network_slug,
 									&endpoint_label,
 								);
+								if let Some(limiter) = self.provider_of(&current_url_snapshot) {
+									limiter.back_off(retry_after);
+								}
 							}
 
 							tracing::debug!(
@@ -490,7 +711,7 @@ impl EndpointManager {
 								current_url_snapshot
 							);
 
-							let rotation_reason = if status_code == 429 {
+							let rotation_reason = if error_class == ErrorClass::RateLimited {
 								"rate_limit"
 							} else {
 								"http_error"
@@ -501,7 +722,10 @@ impl EndpointManager {
 								rotation_reason,
 							);
 
-							match self.try_rotate_url(transport).await {
+							match self
+								.rotate_for_retry(transport, capability, &mut n_past_retries)
+								.await
+							{
 								Ok(_new_url) => {
 									continue; // Retry on the new active URL
 								}
@@ -551,7 +775,10 @@ impl EndpointManager {
 					);
 
 					// Always attempt rotation on network errors
-					match self.try_rotate_url(transport).await {
+					match self
+						.rotate_for_retry(transport, capability, &mut n_past_retries)
+						.await
+					{
 						Ok(new_url) => {
 							tracing::debug!(
 								"Rotation successful after network error, retrying request on new URL: '{}'",
@@ -577,3 +804,20 @@ impl EndpointManager {
 		}
 	}
 }
+
+/// Normalizes an endpoint URL the way the active URL is normalized on rotation
+fn normalize_url(url: &str) -> String {
+	Url::parse(url)
+		.map(|parsed| parsed.as_str().trim_end_matches('/').to_string())
+		.unwrap_or_else(|_| url.trim_end_matches('/').to_string())
+}
+
+/// Returns the pause requested by the `Retry-After` header of a response, in seconds
+fn retry_after(response: &reqwest::Response) -> Option<Duration> {
+	response
+		.headers()
+		.get(reqwest::header::RETRY_AFTER)
+		.and_then(|value| value.to_str().ok())
+		.and_then(|value| value.trim().parse().ok())
+		.map(Duration::from_secs)
+}
@@ -0,0 +1,371 @@
+//! Rate limits shared by the networks of an RPC provider.
+//!
+//! Several networks often use the same provider account (e.g. one Alchemy key for Ethereum and
+//! Base), whose rate limit applies to all of them. The endpoints naming the same provider
+//! (`rpc_urls[].provider`) share a [`ProviderLimiter`]:
+//!
+//! - requests are spaced to stay within `max_requests_per_second`, and when they have to wait,
+//!   the networks take turns, so that a network catching up on a backlog of blocks does not
+//!   starve another one following the chain head;
+//! - a rate limit response pauses the requests of all the networks of the provider, and the
+//!   endpoint managers prefer fallback endpoints of providers not paused when rotating.
+//!
+//! The limiters are kept by the [`ProviderLimiters`] of the client pool, handed to its
+//! transports through their `TransportContext`.
+
+use std::{
+	collections::{BTreeMap, HashMap, VecDeque},
+	sync::{Arc, Mutex},
+	time::{Duration, Instant},
+};
+use tokio::sync::oneshot;
+
+use crate::{
+	models::RpcProvider,
+	utils::metrics::{record_provider_backoff, record_provider_wait},
+};
+
+/// Pause of a provider after a rate limit response without `Retry-After`
+pub const DEFAULT_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Longest pause of a provider, whatever its `Retry-After`
+pub const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+struct LimiterState {
+	/// Minimum time between two requests, `None` for no rate limit
+	interval: Option<Duration>,
+	/// Earliest time of the next request
+	next_slot: Instant,
+	/// End of the pause following a rate limit response
+	paused_until: Option<Instant>,
+	/// Requests waiting for their turn, by network
+	waiters: BTreeMap<String, VecDeque<oneshot::Sender<()>>>,
+	/// Network of the last request sent to the provider, whose turn comes last
+	last_served: Option<String>,
+	/// Whether a task is granting turns to the waiters
+	dispatching: bool,
+}
+
+impl LimiterState {
+	fn ready_at(&self) -> Instant {
+		self.paused_until.map_or(self.next_slot, |paused_until| {
+			paused_until.max(self.next_slot)
+		})
+	}
+
+	fn take_slot(&mut self, now: Instant, network: &str) {
+		self.next_slot = now + self.interval.unwrap_or_default();
+		if self.last_served.as_deref() != Some(network) {
+			self.last_served = Some(network.to_string());
+		}
+	}
+
+	/// Grants the turn to the next network with a waiting request, after the last one served
+	///
+	/// Returns false if no request is waiting.
+	fn grant_next(&mut self, now: Instant) -> bool {
+		loop {
+			let next = match &self.last_served {
+				Some(last) => self
+					.waiters
+					.range::<String, _>((
+						std::ops::Bound::Excluded(last),
+						std::ops::Bound::Unbounded,
+					))
+					.next()
+					.or_else(|| self.waiters.iter().next()),
+				None => self.waiters.iter().next(),
+			}
+			.map(|(network, _)| network.clone());
+			let Some(network) = next else {
+				return false;
+			};
+
+			let Some(queue) = self.waiters.get_mut(&network) else {
+				return false;
+			};
+			let waiter = queue.pop_front();
+			if queue.is_empty() {
+				self.waiters.remove(&network);
+			}
+			// A request whose wait was cancelled does not take the turn
+			if waiter.is_some_and(|waiter| waiter.send(()).is_ok()) {
+				self.take_slot(now, &network);
+				return true;
+			}
+		}
+	}
+}
+
+/// Rate limit and backoff shared by the endpoints of an RPC provider
+pub struct ProviderLimiter {
+	name: String,
+	state: Mutex<LimiterState>,
+}
+
+impl ProviderLimiter {
+	/// Creates a new limiter
+	///
+	/// # Arguments
+	/// * `name` - Name of the provider
+	/// * `max_requests_per_second` - Rate limit of the provider, `None` for no limit
+	pub fn new(name: &str, max_requests_per_second: Option<u32>) -> Self {
+		Self {
+			name: name.to_string(),
+			state: Mutex::new(LimiterState {
+				interval: interval_of(max_requests_per_second),
+				next_slot: Instant::now(),
+				paused_until: None,
+				waiters: BTreeMap::new(),
+				last_served: None,
+				dispatching: false,
+			}),
+		}
+	}
+
+	/// Returns the name of the provider
+	pub fn name(&self) -> &str {
+		&self.name
+	}
+
+	/// Waits for the turn of a request to the provider
+	///
+	/// # Arguments
+	/// * `network_slug` - Network of the request
+	pub async fn acquire(self: &Arc<Self>, network_slug: &str) {
+		let ready = {
+			let mut state = self.lock();
+			let now = Instant::now();
+			if state.waiters.is_empty() && state.ready_at() <= now {
+				state.take_slot(now, network_slug);
+				return;
+			}
+			let (sender, receiver) = oneshot::channel();
+			state
+				.waiters
+				.entry(network_slug.to_string())
+				.or_default()
+				.push_back(sender);
+			if !state.dispatching {
+				state.dispatching = true;
+				tokio::spawn(Arc::clone(self).dispatch());
+			}
+			receiver
+		};
+
+		let waiting_since = Instant::now();
+		// The dispatching task grants every waiting request before exiting
+		let _ = ready.await;
+		record_provider_wait(
+			&self.name,
+			network_slug,
+			waiting_since.elapsed().as_secs_f64(),
+		);
+	}
+
+	/// Pauses the requests of all the networks of the provider after a rate limit response
+	///
+	/// # Arguments
+	/// * `retry_after` - Pause requested by the provider, [`DEFAULT_BACKOFF`] if unknown
+	pub fn back_off(&self, retry_after: Option<Duration>) {
+		let pause = retry_after.unwrap_or(DEFAULT_BACKOFF).min(MAX_BACKOFF);
+		let until = Instant::now() + pause;
+		let mut state = self.lock();
+		state.paused_until = Some(
+			state
+				.paused_until
+				.map_or(until, |current| current.max(until)),
+		);
+		record_provider_backoff(&self.name);
+		tracing::debug!("RPC provider '{}' paused for {:?}", self.name, pause);
+	}
+
+	/// Returns true if the requests to the provider are paused after a rate limit response
+	pub fn is_backing_off(&self) -> bool {
+		self.lock()
+			.paused_until
+			.is_some_and(|paused_until| paused_until > Instant::now())
+	}
+
+	/// Lowers the rate limit of the provider if the given one is lower
+	fn restrict(&self, max_requests_per_second: Option<u32>) {
+		let mut state = self.lock();
+		if let Some(interval) = interval_of(max_requests_per_second) {
+			if state.interval.is_none_or(|current| interval > current) {
+				state.interval = Some(interval);
+			}
+		}
+	}
+
+	/// Grants the turns of the waiting requests as the rate limit allows
+	async fn dispatch(self: Arc<Self>) {
+		loop {
+			let ready_at = self.lock().ready_at();
+			tokio::time::sleep_until(ready_at.into()).await;
+
+			let mut state = self.lock();
+			let now = Instant::now();
+			// The provider may have been paused while sleeping
+			if state.ready_at() > now {
+				continue;
+			}
+			if !state.grant_next(now) {
+				state.dispatching = false;
+				return;
+			}
+		}
+	}
+
+	fn lock(&self) -> std::sync::MutexGuard<'_, LimiterState> {
+		match self.state.lock() {
+			Ok(guard) => guard,
+			Err(poisoned) => poisoned.into_inner(),
+		}
+	}
+}
+
+impl std::fmt::Debug for ProviderLimiter {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("ProviderLimiter")
+			.field("name", &self.name)
+			.finish_non_exhaustive()
+	}
+}
+
+/// Limiters of the RPC providers, by provider name
+#[derive(Default)]
+pub struct ProviderLimiters {
+	limiters: Mutex<HashMap<String, Arc<ProviderLimiter>>>,
+}
+
+impl ProviderLimiters {
+	/// Returns the limiter of a provider, created on first use
+	///
+	/// The endpoints of a provider may configure different rate limits, in which case the
+	/// lowest one applies.
+	///
+	/// # Arguments
+	/// * `provider` - The provider
+	pub fn get_or_create(&self, provider: &RpcProvider) -> Arc<ProviderLimiter> {
+		let mut limiters = match self.limiters.lock() {
+			Ok(guard) => guard,
+			Err(poisoned) => poisoned.into_inner(),
+		};
+		match limiters.get(&provider.name) {
+			Some(limiter) => {
+				limiter.restrict(provider.max_requests_per_second);
+				Arc::clone(limiter)
+			}
+			None => {
+				let limiter = Arc::new(ProviderLimiter::new(
+					&provider.name,
+					provider.max_requests_per_second,
+				));
+				limiters.insert(provider.name.clone(), Arc::clone(&limiter));
+				limiter
+			}
+		}
+	}
+}
+
+fn interval_of(max_requests_per_second: Option<u32>) -> Option<Duration> {
+	max_requests_per_second
+		.filter(|rate| *rate > 0)
+		.map(|rate| Duration::from_secs(1) / rate)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[tokio::test]
+	async fn test_networks_take_turns() {
+		let limiter = Arc::new(ProviderLimiter::new("alchemy", Some(100)));
+		let order = Arc::new(Mutex::new(Vec::new()));
+
+		// A network catching up queues many requests before another follows the head
+		let mut tasks = Vec::new();
+		for _ in 0..10 {
+			let (limiter, order) = (Arc::clone(&limiter), Arc::clone(&order));
+			tasks.push(tokio::spawn(async move {
+				limiter.acquire("ethereum_mainnet").await;
+				order.lock().unwrap().push("ethereum_mainnet");
+			}));
+		}
+		tokio::task::yield_now().await;
+		let (head_limiter, head_order) = (Arc::clone(&limiter), Arc::clone(&order));
+		tasks.push(tokio::spawn(async move {
+			head_limiter.acquire("base_mainnet").await;
+			head_order.lock().unwrap().push("base_mainnet");
+		}));
+		for task in tasks {
+			task.await.unwrap();
+		}
+
+		let order = order.lock().unwrap();
+		assert_eq!(order.len(), 11);
+		let position = order.iter().position(|network| *network == "base_mainnet");
+		assert!(
+			position.is_some_and(|position| position <= 3),
+			"{:?}",
+			order
+		);
+	}
+
+	#[test]
+	fn test_turn_follows_the_last_request_of_any_network() {
+		let limiter = ProviderLimiter::new("alchemy", None);
+		let mut state = limiter.lock();
+		let now = Instant::now();
+		// A request sent without waiting still takes the turn of its network
+		state.take_slot(now, "base_mainnet");
+
+		let mut receivers = Vec::new();
+		for network in ["arbitrum_mainnet", "optimism_mainnet"] {
+			let (sender, receiver) = oneshot::channel();
+			state
+				.waiters
+				.entry(network.to_string())
+				.or_default()
+				.push_back(sender);
+			receivers.push((network, receiver));
+		}
+
+		assert!(state.grant_next(now));
+		assert_eq!(state.last_served.as_deref(), Some("optimism_mainnet"));
+		assert!(state.grant_next(now));
+		assert_eq!(state.last_served.as_deref(), Some("arbitrum_mainnet"));
+		assert!(!state.grant_next(now));
+		for (network, mut receiver) in receivers {
+			assert!(receiver.try_recv().is_ok(), "{}", network);
+		}
+	}
+
+	#[tokio::test]
+	async fn test_back_off_pauses_all_networks() {
+		let limiter = Arc::new(ProviderLimiter::new("alchemy", None));
+		limiter.acquire("ethereum_mainnet").await;
+
+		limiter.back_off(Some(Duration::from_millis(50)));
+		assert!(limiter.is_backing_off());
+		let started = Instant::now();
+		limiter.acquire("base_mainnet").await;
+		assert!(started.elapsed() >= Duration::from_millis(50));
+		assert!(!limiter.is_backing_off());
+	}
+
+	#[test]
+	fn test_lowest_rate_limit_applies() {
+		let limiters = ProviderLimiters::default();
+		let provider = |max_requests_per_second| RpcProvider {
+			name: "alchemy".to_string(),
+			max_requests_per_second,
+		};
+		let limiter = limiters.get_or_create(&provider(Some(50)));
+		let shared = limiters.get_or_create(&provider(Some(10)));
+		limiters.get_or_create(&provider(None));
+
+		assert!(Arc::ptr_eq(&limiter, &shared));
+		assert_eq!(limiter.lock().interval, Some(Duration::from_millis(100)));
+	}
+}
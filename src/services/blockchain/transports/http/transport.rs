@@ -7,22 +7,32 @@
 //! - Authentication via bearer tokens
 //! - Connection health checks
 //! - Endpoint rotation for high availability
+//! - Optional record/replay cache of responses
+//! - Rate limits shared with the other networks of a provider
 
 use anyhow::Context;
 use async_trait::async_trait;
 use reqwest_middleware::ClientWithMiddleware;
 use serde::Serialize;
 use serde_json::{json, Value};
-use std::{sync::Arc, time::Duration};
+use std::{collections::HashMap, sync::Arc, time::Duration};
 use url::Url;
 
 use crate::{
 	models::Network,
 	services::blockchain::transports::{
-		http::endpoint_manager::EndpointManager, BlockchainTransport, RotatingTransport,
-		TransientErrorRetryStrategy, TransportError,
+		http::{
+			cache::{RpcCache, RpcCacheMode},
+			endpoint_manager::EndpointManager,
+			provider::{ProviderLimiter, ProviderLimiters},
+		},
+		BlockchainTransport, RotatingTransport, TransientErrorRetryStrategy, TransportContext,
+		TransportError,
+	},
+	utils::{
+		http::{create_retryable_http_client, RetryConfig},
+		RetryPolicy,
 	},
-	utils::http::{create_retryable_http_client, RetryConfig},
 };
 
 /// Basic HTTP transport client for blockchain interactions
@@ -42,6 +52,10 @@ pub struct HttpTransportClient {
 	endpoint_manager: EndpointManager,
 	/// The stringified JSON RPC payload to use for testing the connection
 	test_connection_payload: Option<String>,
+	/// Slug of the network, part of the cache key of the responses
+	network_slug: String,
+	/// Record/replay cache of the responses, if enabled
+	rpc_cache: Option<RpcCache>,
 }
 
 impl HttpTransportClient {
@@ -64,6 +78,62 @@ impl HttpTransportClient {
 		network: &Network,
 		test_connection_payload: Option<String>,
 		non_rotating_jsonrpc_codes: &'static [i64],
+	) -> Result<Self, anyhow::Error> {
+		Self::new_with_context(
+			network,
+			test_connection_payload,
+			non_rotating_jsonrpc_codes,
+			&TransportContext::default(),
+		)
+		.await
+	}
+
+	/// Creates a new HTTP transport client sharing the state of the transports of a client pool
+	///
+	/// # Arguments
+	/// * `network` - Network configuration containing RPC URLs, weights, and other details
+	/// * `test_connection_payload` - Optional JSON RPC payload to test the connection (default is net_version)
+	/// * `non_rotating_jsonrpc_codes` - JSON-RPC error codes passed through without rotation
+	/// * `context` - State shared by the transports of the client pool
+	///
+	/// # Returns
+	/// * `Result<Self, anyhow::Error>` - New client instance or connection error
+	pub async fn new_with_context(
+		network: &Network,
+		test_connection_payload: Option<String>,
+		non_rotating_jsonrpc_codes: &'static [i64],
+		context: &TransportContext,
+	) -> Result<Self, anyhow::Error> {
+		Self::new_with_cache(
+			network,
+			test_connection_payload,
+			non_rotating_jsonrpc_codes,
+			context.rpc_cache.clone(),
+			context,
+		)
+		.await
+	}
+
+	/// Creates a new HTTP transport client with an RPC response cache
+	///
+	/// In replay mode, no connection is attempted and the responses are served from the
+	/// cache only.
+	///
+	/// # Arguments
+	/// * `network` - Network configuration containing RPC URLs, weights, and other details
+	/// * `test_connection_payload` - Optional JSON RPC payload to test the connection (default is net_version)
+	/// * `non_rotating_jsonrpc_codes` - JSON-RPC error codes passed through without rotation
+	/// * `rpc_cache` - Record/replay cache of the responses, if any
+	/// * `context` - State shared by the transports of the client pool
+	///
+	/// # Returns
+	/// * `Result<Self, anyhow::Error>` - New client instance or connection error
+	pub async fn new_with_cache(
+		network: &Network,
+		test_connection_payload: Option<String>,
+		non_rotating_jsonrpc_codes: &'static [i64],
+		rpc_cache: Option<RpcCache>,
+		context: &TransportContext,
 	) -> Result<Self, anyhow::Error> {
 		let mut rpc_urls: Vec<_> = network
 			.rpc_urls
@@ -90,11 +160,46 @@ impl HttpTransportClient {
 		// Shared across:
 		// - EndpointManager for handling endpoint rotation
 		// - Connection testing for verifying endpoint availability
-		let retryable_client = create_retryable_http_client(
+		// Retries and rotations of the requests share the retry budget of the network
+		let retry_policy = RetryPolicy::for_network(
+			"rpc",
+			&network.slug,
 			&http_retry_config,
+			&context.retry_budgets,
+		);
+		let retryable_client = create_retryable_http_client(
+			retry_policy.clone(),
 			(*base_http_client).clone(),
 			Some(TransientErrorRetryStrategy),
 		);
+
+		// Replaying recorded responses must not require network access
+		if rpc_cache
+			.as_ref()
+			.is_some_and(|c| c.mode() == RpcCacheMode::Replay)
+		{
+			let active_url = rpc_urls
+				.first()
+				.map(|rpc_url| rpc_url.url.as_ref().to_string())
+				.ok_or_else(|| anyhow::anyhow!("No RPC URL configured"))?;
+			return Ok(Self {
+				client: retryable_client.clone(),
+				endpoint_manager: EndpointManager::new(
+					retryable_client,
+					&active_url,
+					Vec::new(),
+					network.slug.clone(),
+					non_rotating_jsonrpc_codes,
+				)
+				.with_providers(provider_limiters(network, &context.provider_limiters))
+				.with_capabilities(context.capabilities.clone())
+				.with_retry_policy(retry_policy),
+				test_connection_payload,
+				network_slug: network.slug.clone(),
+				rpc_cache,
+			});
+		}
+
 		for rpc_url in rpc_urls.iter() {
 			let url = match Url::parse(rpc_url.url.as_ref()) {
 				Ok(url) => url,
@@ -146,10 +251,15 @@ impl HttpTransportClient {
 							retryable_client,
 							rpc_url.url.as_ref(),
 							fallback_urls,
-							network_slug,
+							network_slug.clone(),
 							non_rotating_jsonrpc_codes,
-						),
+						)
+						.with_providers(provider_limiters(network, &context.provider_limiters))
+						.with_capabilities(context.capabilities.clone())
+						.with_retry_policy(retry_policy),
 						test_connection_payload,
+						network_slug,
+						rpc_cache,
 					});
 				}
 				Err(_) => {
@@ -161,6 +271,39 @@ impl HttpTransportClient {
 
 		Err(anyhow::anyhow!("All RPC URLs failed to connect"))
 	}
+
+	/// Sets the messages of the errors caused by the requests of a method rather than the
+	/// endpoint, returned without rotating endpoints
+	///
+	/// # Arguments
+	/// * `method` - The JSON-RPC method
+	/// * `patterns` - Case-insensitive substrings of the messages of the errors
+	pub fn with_request_errors(mut self, method: &str, patterns: Vec<String>) -> Self {
+		self.endpoint_manager = self.endpoint_manager.with_request_errors(method, patterns);
+		self
+	}
+}
+
+/// Returns the limiters of the providers of the endpoints of a network, by endpoint URL
+///
+/// # Arguments
+/// * `network` - The network
+/// * `limiters` - Limiters of the RPC providers, shared with the other networks
+fn provider_limiters(
+	network: &Network,
+	limiters: &ProviderLimiters,
+) -> HashMap<String, Arc<ProviderLimiter>> {
+	network
+		.rpc_urls
+		.iter()
+		.filter_map(|rpc_url| {
+			let provider = rpc_url.provider.as_ref()?;
+			Some((
+				rpc_url.url.as_ref().to_string(),
+				limiters.get_or_create(provider),
+			))
+		})
+		.collect()
 }
 
 #[async_trait]
@@ -201,9 +344,35 @@ impl BlockchainTransport for HttpTransportClient {
 	where
 		P: Into<Value> + Send + Clone + Serialize,
 	{
-		self.endpoint_manager
+		let Some(cache) = &self.rpc_cache else {
+			return self
+				.endpoint_manager
+				.send_raw_request(self, method, params)
+				.await;
+		};
+
+		let params_value = params.clone().map(Into::into).unwrap_or(Value::Null);
+		if cache.mode() == RpcCacheMode::Replay {
+			return cache
+				.get(&self.network_slug, method, &params_value)
+				.await
+				.ok_or_else(|| {
+					TransportError::network(
+						format!("No recorded response for {} in replay mode", method),
+						None,
+						None,
+					)
+				});
+		}
+
+		let response = self
+			.endpoint_manager
 			.send_raw_request(self, method, params)
-			.await
+			.await?;
+		cache
+			.put(&self.network_slug, method, &params_value, &response)
+			.await;
+		Ok(response)
 	}
 
 	/// Update endpoint manager with a new client
@@ -3,6 +3,8 @@
 //! Provides concrete implementations for different blockchain network protocols:
 //!
 //! - Generic HTTP transport for all chains
+//! - Record/replay cache of HTTP RPC responses
+//! - Rate limits shared by the networks of an RPC provider
 
 mod evm {
 	pub mod http;
@@ -18,7 +20,9 @@ mod solana {
 }
 
 mod http {
+	pub mod cache;
 	pub mod endpoint_manager;
+	pub mod provider;
 	pub mod transport;
 }
 
@@ -32,14 +36,17 @@ mod ws {
 mod error;
 
 pub use http::{
-	endpoint_manager::EndpointManager as HttpEndpointManager, transport::HttpTransportClient,
+	cache::{RpcCache, RpcCacheMode, DEFAULT_RPC_CACHE_DIR},
+	endpoint_manager::EndpointManager as HttpEndpointManager,
+	provider::{ProviderLimiter, ProviderLimiters},
+	transport::HttpTransportClient,
 };
 pub use ws::{
 	config::WsConfig, endpoint_manager::EndpointManager as WsEndpointManager,
 	transport::WsTransportClient,
 };
 
-pub use error::TransportError;
+pub use error::{classify_jsonrpc_error, TransportError};
 pub use evm::http::EVMTransportClient;
 pub use midnight::ws::MidnightTransportClient as MidnightWsTransportClient;
 pub use solana::http::{
@@ -49,11 +56,15 @@ pub use solana::http::{
 pub use stellar::http::StellarTransportClient;
 
 use reqwest_middleware::ClientWithMiddleware;
-use reqwest_retry::{
-	default_on_request_failure, default_on_request_success, Retryable, RetryableStrategy,
-};
+use reqwest_retry::{default_on_request_failure, Retryable, RetryableStrategy};
 use serde::Serialize;
 use serde_json::{json, Value};
+use std::sync::Arc;
+
+use crate::{
+	services::blockwatcher::EndpointCapabilityRegistry,
+	utils::{logging::error::ErrorClass, RetryBudgets},
+};
 
 /// HTTP status codes that trigger RPC endpoint rotation
 /// - 400: Bad Request - some providers return 400 for transient/operational issues
@@ -67,6 +78,22 @@ use serde_json::{json, Value};
 /// - 504: Gateway Timeout - the upstream RPC node is unresponsive
 pub const ROTATE_ON_ERROR_CODES: [u16; 8] = [400, 408, 410, 429, 500, 502, 503, 504];
 
+/// State shared by the HTTP transports of a client pool
+///
+/// Transports are created per network, so the state shared by the networks is owned by the
+/// client pool and handed to the transports it creates.
+#[derive(Clone, Default)]
+pub struct TransportContext {
+	/// Limiters of the RPC providers
+	pub provider_limiters: Arc<ProviderLimiters>,
+	/// Capabilities of the RPC endpoints, as detected at startup
+	pub capabilities: Arc<EndpointCapabilityRegistry>,
+	/// Retry budgets of the RPC requests, by network
+	pub retry_budgets: Arc<RetryBudgets>,
+	/// Record/replay cache of the RPC responses, if enabled
+	pub rpc_cache: Option<RpcCache>,
+}
+
 /// Base trait for all blockchain transport clients
 #[async_trait::async_trait]
 pub trait BlockchainTransport: Send + Sync {
@@ -115,6 +142,10 @@ pub trait RotatingTransport: BlockchainTransport {
 
 /// A default retry strategy that retries on requests based on the status code
 /// This can be used to customise the retry strategy
+///
+/// Unsuccessful responses are retried unless their status is classified as permanent (see
+/// [`ErrorClass::from_status_code`]), so that RPC and webhook retries agree with the
+/// classification of the errors they end up returning.
 pub struct TransientErrorRetryStrategy;
 impl RetryableStrategy for TransientErrorRetryStrategy {
 	fn handle(
@@ -122,7 +153,16 @@ impl RetryableStrategy for TransientErrorRetryStrategy {
 		res: &Result<reqwest::Response, reqwest_middleware::Error>,
 	) -> Option<Retryable> {
 		match res {
-			Ok(success) => default_on_request_success(success),
+			Ok(response) => {
+				let status = response.status();
+				if status.is_success() || status.is_informational() || status.is_redirection() {
+					return None;
+				}
+				match ErrorClass::from_status_code(status.as_u16()) {
+					ErrorClass::Permanent => Some(Retryable::Fatal),
+					ErrorClass::Transient | ErrorClass::RateLimited => Some(Retryable::Transient),
+				}
+			}
 			Err(error) => default_on_request_failure(error),
 		}
 	}
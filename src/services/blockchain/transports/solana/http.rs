@@ -12,7 +12,10 @@ use crate::{
 	models::Network,
 	services::blockchain::{
 		clients::SLOT_UNAVAILABLE_ERROR_CODES,
-		transports::{BlockchainTransport, HttpTransportClient, RotatingTransport, TransportError},
+		transports::{
+			BlockchainTransport, HttpTransportClient, RotatingTransport, TransportContext,
+			TransportError,
+		},
 	},
 };
 
@@ -186,13 +189,29 @@ impl SolanaTransportClient {
 	/// # Returns
 	/// * `Result<Self, anyhow::Error>` - A new client instance or connection error
 	pub async fn new(network: &Network) -> Result<Self, anyhow::Error> {
+		Self::new_with_context(network, &TransportContext::default()).await
+	}
+
+	/// Creates a new Solana transport client sharing the state of the transports of a client pool
+	///
+	/// # Arguments
+	/// * `network` - Network configuration containing RPC URLs and other network details
+	/// * `context` - State shared by the transports of the client pool
+	///
+	/// # Returns
+	/// * `Result<Self, anyhow::Error>` - A new client instance or connection error
+	pub async fn new_with_context(
+		network: &Network,
+		context: &TransportContext,
+	) -> Result<Self, anyhow::Error> {
 		// Use getHealth as the test connection method - it's lightweight and indicates node health
 		let test_connection_payload =
 			Some(r#"{"id":1,"jsonrpc":"2.0","method":"getHealth"}"#.to_string());
-		let http_client = HttpTransportClient::new(
+		let http_client = HttpTransportClient::new_with_context(
 			network,
 			test_connection_payload,
 			SLOT_UNAVAILABLE_ERROR_CODES,
+			context,
 		)
 		.await?;
 		Ok(Self { http_client })
@@ -11,7 +11,8 @@ use serde_json::Value;
 use crate::{
 	models::Network,
 	services::blockchain::transports::{
-		BlockchainTransport, HttpTransportClient, RotatingTransport, TransportError,
+		BlockchainTransport, HttpTransportClient, RotatingTransport, TransportContext,
+		TransportError,
 	},
 };
 
@@ -36,9 +37,26 @@ impl StellarTransportClient {
 	/// # Returns
 	/// * `Result<Self, anyhow::Error>` - A new client instance or connection error
 	pub async fn new(network: &Network) -> Result<Self, anyhow::Error> {
+		Self::new_with_context(network, &TransportContext::default()).await
+	}
+
+	/// Creates a new Stellar transport client sharing the state of the transports of a client pool
+	///
+	/// # Arguments
+	/// * `network` - Network configuration containing RPC URLs and other network details
+	/// * `context` - State shared by the transports of the client pool
+	///
+	/// # Returns
+	/// * `Result<Self, anyhow::Error>` - A new client instance or connection error
+	pub async fn new_with_context(
+		network: &Network,
+		context: &TransportContext,
+	) -> Result<Self, anyhow::Error> {
 		let test_connection_payload =
 			Some(r#"{"id":1,"jsonrpc":"2.0","method":"getNetwork","params":[]}"#.to_string());
-		let http_client = HttpTransportClient::new(network, test_connection_payload, &[]).await?;
+		let http_client =
+			HttpTransportClient::new_with_context(network, test_connection_payload, &[], context)
+				.await?;
 		Ok(Self { http_client })
 	}
 }
@@ -0,0 +1,586 @@
+//! RPC endpoint capability detection.
+//!
+//! At startup every endpoint of an EVM network is probed for the optional features that
+//! providers do not all offer: the `debug_*` and `trace_*` APIs, JSON-RPC batch requests and
+//! the block range accepted by `eth_getLogs`. The results are kept in an
+//! [`EndpointCapabilityRegistry`], shared by the clients of the client pool and the filters, so
+//! that features relying on them degrade per endpoint (a fallback URL without trace support
+//! is skipped instead of failing at runtime), and exposed by the `rpc_endpoint_capability`
+//! metric. The most restrictive `eth_getLogs` range found becomes the `max_block_range` of the
+//! network, unless configured.
+//!
+//! The probes of an endpoint run concurrently, and the networks are probed in the background
+//! so that startup does not wait for slow endpoints. Until then, the endpoints are assumed to
+//! offer every capability and `eth_getLogs` queries rejected for their range are split.
+
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::{
+	collections::HashMap,
+	sync::{Arc, RwLock},
+	time::Duration,
+};
+
+use crate::{
+	models::{BlockChainType, Network},
+	services::blockwatcher::consistency::endpoint_label,
+	utils::metrics::set_endpoint_capability,
+};
+
+/// Timeout applied to every request of a probe
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Block ranges of the `eth_getLogs` probes, from the largest
+const LOGS_PROBE_RANGES: &[u64] = &[10_000, 2_000, 1_000, 100];
+
+/// `eth_getLogs` block range assumed when an endpoint rejects every probed range
+const FALLBACK_LOGS_BLOCK_RANGE: u64 = 10;
+
+/// Hash of a transaction that does not exist, used to call the trace APIs cheaply
+const UNKNOWN_TRANSACTION_HASH: &str =
+	"0x0000000000000000000000000000000000000000000000000000000000000000";
+
+/// JSON-RPC error code of an unknown method
+const JSONRPC_METHOD_NOT_FOUND: i64 = -32601;
+
+/// Messages of the errors returned by providers for methods they do not serve
+const UNSUPPORTED_METHOD_PATTERNS: &[&str] = &[
+	"method not found",
+	"does not exist",
+	"not available",
+	"not supported",
+	"unsupported",
+	"not whitelisted",
+	"not allowed",
+];
+
+/// Optional feature of an RPC endpoint
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Capability {
+	/// `debug_*` tracing API (e.g. `debug_traceTransaction`)
+	DebugTrace,
+	/// `trace_*` API (e.g. `trace_transaction`)
+	Trace,
+	/// JSON-RPC batch requests
+	Batch,
+}
+
+impl Capability {
+	/// Returns the name of the capability, used as metrics label
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			Self::DebugTrace => "debug_trace",
+			Self::Trace => "trace",
+			Self::Batch => "batch",
+		}
+	}
+
+	/// Returns the capability an endpoint needs to serve a JSON-RPC method, if optional
+	///
+	/// # Arguments
+	/// * `method` - The JSON-RPC method
+	pub fn required_by(method: &str) -> Option<Self> {
+		if method.starts_with("debug_") {
+			Some(Self::DebugTrace)
+		} else if method.starts_with("trace_") {
+			Some(Self::Trace)
+		} else {
+			None
+		}
+	}
+}
+
+/// Capabilities detected on an RPC endpoint
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct EndpointCapabilities {
+	/// Whether the `debug_*` tracing API is served
+	pub debug_trace: bool,
+	/// Whether the `trace_*` API is served
+	pub trace: bool,
+	/// Whether JSON-RPC batch requests are answered
+	pub batch: bool,
+	/// Largest `eth_getLogs` block range accepted, `None` if no limit was found
+	pub max_logs_block_range: Option<u64>,
+}
+
+impl EndpointCapabilities {
+	/// Returns whether the endpoint offers a capability
+	pub fn supports(&self, capability: Capability) -> bool {
+		match capability {
+			Capability::DebugTrace => self.debug_trace,
+			Capability::Trace => self.trace,
+			Capability::Batch => self.batch,
+		}
+	}
+}
+
+/// Capabilities of the probed endpoints, keyed by network and endpoint URL
+#[derive(Debug, Default)]
+pub struct EndpointCapabilityRegistry {
+	endpoints: RwLock<HashMap<String, HashMap<String, EndpointCapabilities>>>,
+}
+
+impl EndpointCapabilityRegistry {
+	/// Records the capabilities of an endpoint
+	///
+	/// # Arguments
+	/// * `network` - The network slug
+	/// * `url` - The endpoint URL
+	/// * `capabilities` - The detected capabilities
+	pub fn record(&self, network: &str, url: &str, capabilities: EndpointCapabilities) {
+		let mut endpoints = match self.endpoints.write() {
+			Ok(endpoints) => endpoints,
+			Err(poisoned) => poisoned.into_inner(),
+		};
+		endpoints
+			.entry(network.to_string())
+			.or_default()
+			.insert(url.to_string(), capabilities);
+	}
+
+	/// Returns the capabilities of an endpoint, if probed
+	pub fn get(&self, network: &str, url: &str) -> Option<EndpointCapabilities> {
+		let endpoints = match self.endpoints.read() {
+			Ok(endpoints) => endpoints,
+			Err(poisoned) => poisoned.into_inner(),
+		};
+		endpoints.get(network)?.get(url).cloned()
+	}
+
+	/// Returns the most restrictive `eth_getLogs` block range of the endpoints of a network
+	///
+	/// # Arguments
+	/// * `network` - The network slug
+	///
+	/// # Returns
+	/// The range, `None` if no probed endpoint of the network limits it
+	pub fn max_logs_block_range(&self, network: &str) -> Option<u64> {
+		let endpoints = match self.endpoints.read() {
+			Ok(endpoints) => endpoints,
+			Err(poisoned) => poisoned.into_inner(),
+		};
+		endpoints
+			.get(network)?
+			.values()
+			.filter_map(|capabilities| capabilities.max_logs_block_range)
+			.min()
+	}
+
+	/// Returns whether an endpoint offers a capability
+	///
+	/// Endpoints that were not probed are assumed to offer it, so that features behave as
+	/// configured when the detection did not run.
+	///
+	/// # Arguments
+	/// * `network` - The network slug
+	/// * `url` - The endpoint URL
+	/// * `capability` - The capability
+	pub fn supports(&self, network: &str, url: &str, capability: Capability) -> bool {
+		self.get(network, url)
+			.is_none_or(|capabilities| capabilities.supports(capability))
+	}
+}
+
+/// Probes the capabilities of every RPC endpoint of an EVM network
+///
+/// The capabilities are recorded in the registry and in the metrics. Other networks and
+/// unreachable endpoints are skipped.
+///
+/// # Arguments
+/// * `network` - The network to probe
+/// * `client` - HTTP client used to query the endpoints directly, bypassing rotation
+/// * `registry` - The registry recording the capabilities
+///
+/// # Returns
+/// The capabilities of the reachable endpoints, keyed by URL
+pub async fn probe_network_capabilities(
+	network: &Network,
+	client: &reqwest::Client,
+	registry: &EndpointCapabilityRegistry,
+) -> HashMap<String, EndpointCapabilities> {
+	if network.network_type != BlockChainType::EVM {
+		return HashMap::new();
+	}
+	let urls: Vec<&str> = network
+		.rpc_urls
+		.iter()
+		.filter(|rpc_url| rpc_url.type_ == "rpc" && rpc_url.weight > 0)
+		.map(|rpc_url| rpc_url.url.as_str())
+		.collect();
+
+	let probes =
+		futures::future::join_all(urls.iter().map(|url| probe_endpoint(client, url))).await;
+
+	let mut detected = HashMap::new();
+	for (url, capabilities) in urls.into_iter().zip(probes) {
+		let label = endpoint_label(url);
+		let Some(capabilities) = capabilities else {
+			tracing::warn!(
+				network = %network.slug,
+				"Could not probe the capabilities of RPC endpoint {}",
+				label
+			);
+			continue;
+		};
+		tracing::info!(
+			network = %network.slug,
+			"RPC endpoint {} capabilities: debug_trace={}, trace={}, batch={}, eth_getLogs block range={}",
+			label,
+			capabilities.debug_trace,
+			capabilities.trace,
+			capabilities.batch,
+			capabilities
+				.max_logs_block_range
+				.map_or_else(|| "unlimited".to_string(), |range| range.to_string())
+		);
+		for capability in [Capability::DebugTrace, Capability::Trace, Capability::Batch] {
+			set_endpoint_capability(
+				&network.slug,
+				&label,
+				capability.as_str(),
+				capabilities.supports(capability),
+			);
+		}
+		registry.record(&network.slug, url, capabilities.clone());
+		detected.insert(url.to_string(), capabilities);
+	}
+
+	let configured = network
+		.get_logs_limits
+		.as_ref()
+		.and_then(|limits| limits.max_block_range);
+	if let (None, Some(max_block_range)) =
+		(configured, registry.max_logs_block_range(&network.slug))
+	{
+		tracing::info!(
+			network = %network.slug,
+			"Limiting eth_getLogs queries to {} blocks, as detected on the RPC endpoints",
+			max_block_range
+		);
+	}
+	detected
+}
+
+/// Probes the capabilities of the endpoints of networks in the background
+///
+/// # Arguments
+/// * `networks` - The networks to probe
+/// * `registry` - The registry recording the capabilities
+///
+/// # Returns
+/// The handle of the task probing the networks
+pub fn spawn_capability_probes(
+	networks: Vec<Network>,
+	registry: Arc<EndpointCapabilityRegistry>,
+) -> tokio::task::JoinHandle<()> {
+	tokio::spawn(async move {
+		let client = reqwest::Client::new();
+		futures::future::join_all(
+			networks
+				.iter()
+				.map(|network| probe_network_capabilities(network, &client, &registry)),
+		)
+		.await;
+	})
+}
+
+/// Probes the capabilities of an endpoint
+///
+/// # Returns
+/// The capabilities, `None` if the endpoint is unreachable
+async fn probe_endpoint(client: &reqwest::Client, url: &str) -> Option<EndpointCapabilities> {
+	let latest = rpc_call(client, url, "eth_blockNumber", json!([]))
+		.await?
+		.get("result")
+		.and_then(Value::as_str)
+		.and_then(|hex| u64::from_str_radix(hex.trim_start_matches("0x"), 16).ok())?;
+
+	let (debug_trace, trace, batch, max_logs_block_range) = tokio::join!(
+		async {
+			is_method_supported(
+				rpc_call(
+					client,
+					url,
+					"debug_traceTransaction",
+					json!([UNKNOWN_TRANSACTION_HASH, {}]),
+				)
+				.await
+				.as_ref(),
+			)
+		},
+		async {
+			is_method_supported(
+				rpc_call(
+					client,
+					url,
+					"trace_transaction",
+					json!([UNKNOWN_TRANSACTION_HASH]),
+				)
+				.await
+				.as_ref(),
+			)
+		},
+		async {
+			post(
+				client,
+				url,
+				json!([
+					{"jsonrpc": "2.0", "id": 1, "method": "eth_blockNumber", "params": []},
+					{"jsonrpc": "2.0", "id": 2, "method": "eth_chainId", "params": []},
+				]),
+			)
+			.await
+			.and_then(|body| body.as_array().map(|responses| responses.len() == 2))
+			.unwrap_or(false)
+		},
+		probe_logs_block_range(client, url, latest),
+	);
+
+	Some(EndpointCapabilities {
+		debug_trace,
+		trace,
+		batch,
+		max_logs_block_range,
+	})
+}
+
+/// Probes the block ranges accepted by `eth_getLogs`, all ranges being queried concurrently
+///
+/// # Returns
+/// The largest range accepted, `None` if the largest probed range is accepted, and
+/// [`FALLBACK_LOGS_BLOCK_RANGE`] if every range is rejected
+async fn probe_logs_block_range(client: &reqwest::Client, url: &str, latest: u64) -> Option<u64> {
+	let accepted = futures::future::join_all(LOGS_PROBE_RANGES.iter().map(|range| {
+		let params = json!([{
+			"fromBlock": format!("0x{:x}", latest.saturating_sub(range - 1)),
+			"toBlock": format!("0x{:x}", latest),
+			"address": "0x0000000000000000000000000000000000000000",
+		}]);
+		async move {
+			rpc_call(client, url, "eth_getLogs", params)
+				.await
+				.is_some_and(|body| body.get("result").is_some_and(Value::is_array))
+		}
+	}))
+	.await;
+
+	match accepted.iter().position(|accepted| *accepted) {
+		Some(0) => None,
+		Some(index) => Some(LOGS_PROBE_RANGES[index]),
+		None => Some(FALLBACK_LOGS_BLOCK_RANGE),
+	}
+}
+
+/// Returns whether the response to a call shows that the method is served
+///
+/// Any answer but an unknown method error, including an error about the arguments, means
+/// that the method is served.
+fn is_method_supported(body: Option<&Value>) -> bool {
+	let Some(body) = body else {
+		return false;
+	};
+	match body.get("error") {
+		None => body.get("result").is_some(),
+		Some(error) => {
+			let code = error.get("code").and_then(Value::as_i64).unwrap_or(0);
+			let message = error
+				.get("message")
+				.and_then(Value::as_str)
+				.unwrap_or("")
+				.to_lowercase();
+			code != JSONRPC_METHOD_NOT_FOUND
+				&& !UNSUPPORTED_METHOD_PATTERNS
+					.iter()
+					.any(|pattern| message.contains(pattern))
+		}
+	}
+}
+
+/// Sends a JSON-RPC call and returns the response envelope
+async fn rpc_call(
+	client: &reqwest::Client,
+	url: &str,
+	method: &str,
+	params: Value,
+) -> Option<Value> {
+	post(
+		client,
+		url,
+		json!({
+			"jsonrpc": "2.0",
+			"id": 1,
+			"method": method,
+			"params": params,
+		}),
+	)
+	.await
+}
+
+/// Posts a JSON body and returns the JSON response, whatever its HTTP status
+async fn post(client: &reqwest::Client, url: &str, body: Value) -> Option<Value> {
+	client
+		.post(url)
+		.timeout(REQUEST_TIMEOUT)
+		.json(&body)
+		.send()
+		.await
+		.ok()?
+		.json()
+		.await
+		.ok()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::utils::tests::builders::network::NetworkBuilder;
+	use mockito::{Matcher, Server};
+
+	#[test]
+	fn test_is_method_supported() {
+		assert!(is_method_supported(Some(
+			&json!({"jsonrpc": "2.0", "id": 1, "result": null})
+		)));
+		assert!(is_method_supported(Some(&json!({
+			"jsonrpc": "2.0",
+			"id": 1,
+			"error": {"code": -32000, "message": "transaction not found"}
+		}))));
+		assert!(!is_method_supported(Some(&json!({
+			"jsonrpc": "2.0",
+			"id": 1,
+			"error": {"code": -32601, "message": "the method debug_traceTransaction does not exist"}
+		}))));
+		assert!(!is_method_supported(Some(&json!({
+			"jsonrpc": "2.0",
+			"id": 1,
+			"error": {"code": -32600, "message": "Method not supported on this plan"}
+		}))));
+		assert!(!is_method_supported(None));
+	}
+
+	#[tokio::test]
+	async fn test_probe_network_capabilities() {
+		let mut server = Server::new_async().await;
+		let respond =
+			|result: Value| json!({"jsonrpc": "2.0", "id": 1, "result": result}).to_string();
+		server
+			.mock("POST", "/")
+			.match_body(Matcher::PartialJson(json!({"method": "eth_blockNumber"})))
+			.with_body(respond(json!("0x5000")))
+			.create_async()
+			.await;
+		server
+			.mock("POST", "/")
+			.match_body(Matcher::PartialJson(
+				json!({"method": "debug_traceTransaction"}),
+			))
+			.with_body(
+				json!({"jsonrpc": "2.0", "id": 1, "error": {"code": -32000, "message": "transaction not found"}})
+					.to_string(),
+			)
+			.create_async()
+			.await;
+		server
+			.mock("POST", "/")
+			.match_body(Matcher::PartialJson(json!({"method": "trace_transaction"})))
+			.with_body(
+				json!({"jsonrpc": "2.0", "id": 1, "error": {"code": -32601, "message": "Method not found"}})
+					.to_string(),
+			)
+			.create_async()
+			.await;
+		// Ranges of up to 2000 blocks (0x5000 - 1999 = 0x4831)
+		server
+			.mock("POST", "/")
+			.match_body(Matcher::PartialJson(
+				json!({"method": "eth_getLogs", "params": [{"fromBlock": "0x4831"}]}),
+			))
+			.with_body(respond(json!([])))
+			.create_async()
+			.await;
+		server
+			.mock("POST", "/")
+			.match_body(Matcher::PartialJson(json!({"method": "eth_getLogs"})))
+			.with_body(
+				json!({"jsonrpc": "2.0", "id": 1, "error": {"code": -32600, "message": "block range too large"}})
+					.to_string(),
+			)
+			.create_async()
+			.await;
+
+		let network = NetworkBuilder::new()
+			.slug("capabilities_probe")
+			.add_rpc_url(&server.url(), "rpc", 100)
+			.build();
+		let registry = EndpointCapabilityRegistry::default();
+		let capabilities =
+			probe_network_capabilities(&network, &reqwest::Client::new(), &registry).await;
+
+		let expected = EndpointCapabilities {
+			debug_trace: true,
+			trace: false,
+			batch: false,
+			max_logs_block_range: Some(2000),
+		};
+		assert_eq!(capabilities.get(&server.url()), Some(&expected));
+		assert!(!registry.supports("capabilities_probe", &server.url(), Capability::Trace));
+		// Endpoints that were not probed are assumed to offer every capability
+		assert!(registry.supports("capabilities_probe", "http://unprobed", Capability::Trace));
+
+		assert_eq!(
+			registry.max_logs_block_range("capabilities_probe"),
+			Some(2000)
+		);
+	}
+
+	#[tokio::test]
+	async fn test_probe_falls_back_when_every_logs_range_is_rejected() {
+		let mut server = Server::new_async().await;
+		server
+			.mock("POST", "/")
+			.match_body(Matcher::PartialJson(json!({"method": "eth_blockNumber"})))
+			.with_body(json!({"jsonrpc": "2.0", "id": 1, "result": "0x5000"}).to_string())
+			.create_async()
+			.await;
+		server
+			.mock("POST", "/")
+			.match_body(Matcher::PartialJson(json!({"method": "eth_getLogs"})))
+			.with_body(
+				json!({"jsonrpc": "2.0", "id": 1, "error": {"code": -32600, "message": "block range too large"}})
+					.to_string(),
+			)
+			.create_async()
+			.await;
+
+		let capabilities = probe_endpoint(&reqwest::Client::new(), &server.url())
+			.await
+			.unwrap();
+		assert_eq!(
+			capabilities.max_logs_block_range,
+			Some(FALLBACK_LOGS_BLOCK_RANGE)
+		);
+	}
+
+	#[test]
+	fn test_max_logs_block_range_is_the_most_restrictive() {
+		let registry = EndpointCapabilityRegistry::default();
+		for (url, max_logs_block_range) in [
+			("http://a", Some(2000)),
+			("http://b", Some(100)),
+			("http://c", None),
+		] {
+			registry.record(
+				"ethereum_mainnet",
+				url,
+				EndpointCapabilities {
+					max_logs_block_range,
+					..Default::default()
+				},
+			);
+		}
+
+		assert_eq!(registry.max_logs_block_range("ethereum_mainnet"), Some(100));
+		assert_eq!(registry.max_logs_block_range("base_mainnet"), None);
+	}
+}
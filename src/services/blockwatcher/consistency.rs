@@ -0,0 +1,372 @@
+//! RPC consistency check module.
+//!
+//! When a network has several RPC endpoints, this module periodically compares the
+//! block hash each endpoint returns for the same block. A forked or lagging provider
+//! is detected before it causes missed matches, and the result is exposed through the
+//! `rpc_block_hash_divergence` and `rpc_endpoint_block_lag` metrics.
+
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::{collections::HashMap, time::Duration};
+use url::{Position, Url};
+
+use crate::{
+	models::{Network, RpcConsistencyConfig},
+	utils::metrics::{set_block_hash_divergence, set_endpoint_block_lag},
+};
+
+/// Timeout applied to every request of a consistency check
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Result of a consistency check execution
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConsistencyReport {
+	/// Block number at which the hashes were compared
+	pub reference_block: Option<u64>,
+	/// Endpoints whose block hash differs from the majority
+	pub divergent_endpoints: Vec<String>,
+	/// Number of blocks each endpoint lags behind the most advanced one
+	pub endpoint_lag: HashMap<String, u64>,
+	/// Endpoints that could not be queried
+	pub unreachable_endpoints: Vec<String>,
+}
+
+/// Compares the block hash returned by every RPC endpoint of an EVM network
+///
+/// # Algorithm
+/// 1. Fetch the latest block number from every endpoint
+/// 2. Pick the reference block: the lowest latest block minus `confirmation_blocks`, so
+///    every endpoint should have it and head reorgs do not raise false alarms
+/// 3. Fetch the hash of the reference block from every endpoint
+/// 4. Endpoints disagreeing with the majority hash are divergent (on a tie the hash of the
+///    highest-weight endpoint wins)
+/// 5. Update the metrics and log a warning on divergence or excessive lag
+///
+/// Networks with fewer than two endpoints are skipped.
+///
+/// # Arguments
+/// * `network` - The network to check
+/// * `config` - The consistency check configuration
+/// * `client` - HTTP client used to query the endpoints directly, bypassing rotation
+///
+/// # Returns
+/// The report of the check
+pub async fn check_block_hash_consistency(
+	network: &Network,
+	config: &RpcConsistencyConfig,
+	client: &reqwest::Client,
+) -> ConsistencyReport {
+	let mut rpc_urls: Vec<_> = network
+		.rpc_urls
+		.iter()
+		.filter(|rpc_url| rpc_url.type_ == "rpc" && rpc_url.weight > 0)
+		.collect();
+	rpc_urls.sort_by(|a, b| b.weight.cmp(&a.weight));
+
+	let mut report = ConsistencyReport::default();
+	if rpc_urls.len() < 2 {
+		return report;
+	}
+
+	let endpoints: Vec<(String, &str)> = rpc_urls
+		.iter()
+		.map(|rpc_url| (endpoint_label(rpc_url.url.as_str()), rpc_url.url.as_str()))
+		.collect();
+
+	// Latest block number of every endpoint
+	let latest_blocks = futures::future::join_all(endpoints.iter().map(|(_, url)| async move {
+		let result = rpc_request(client, url, "eth_blockNumber", json!([])).await?;
+		parse_hex_u64(&result)
+	}))
+	.await;
+
+	let mut reachable = Vec::new();
+	for ((label, url), latest) in endpoints.iter().zip(latest_blocks) {
+		match latest {
+			Some(latest) => reachable.push((label.clone(), *url, latest)),
+			None => report.unreachable_endpoints.push(label.clone()),
+		}
+	}
+
+	let Some(highest) = reachable.iter().map(|(_, _, latest)| *latest).max() else {
+		set_block_hash_divergence(&network.slug, 0);
+		log_report(network, config, &report);
+		return report;
+	};
+	for (label, _, latest) in &reachable {
+		let lag = highest - latest;
+		set_endpoint_block_lag(&network.slug, label, lag);
+		report.endpoint_lag.insert(label.clone(), lag);
+	}
+
+	let lowest = reachable
+		.iter()
+		.map(|(_, _, latest)| *latest)
+		.min()
+		.unwrap_or(highest);
+	let reference_block = lowest.saturating_sub(network.confirmation_blocks);
+	report.reference_block = Some(reference_block);
+
+	// Hash of the reference block on every reachable endpoint
+	let params = json!([format!("0x{:x}", reference_block), false]);
+	let hashes = futures::future::join_all(reachable.iter().map(|(_, url, _)| {
+		let params = params.clone();
+		async move {
+			rpc_request(client, url, "eth_getBlockByNumber", params)
+				.await?
+				.get("hash")?
+				.as_str()
+				.map(str::to_lowercase)
+		}
+	}))
+	.await;
+
+	let mut hashes_by_endpoint = Vec::new();
+	for ((label, _, _), hash) in reachable.iter().zip(hashes) {
+		match hash {
+			Some(hash) => hashes_by_endpoint.push((label.clone(), hash)),
+			None => report.unreachable_endpoints.push(label.clone()),
+		}
+	}
+
+	if let Some(majority) = majority_hash(&hashes_by_endpoint) {
+		report.divergent_endpoints = hashes_by_endpoint
+			.iter()
+			.filter(|(_, hash)| *hash != majority)
+			.map(|(label, _)| label.clone())
+			.collect();
+	}
+	set_block_hash_divergence(&network.slug, report.divergent_endpoints.len());
+
+	log_report(network, config, &report);
+	report
+}
+
+/// Returns the most common hash, preferring the earliest (highest-weight) endpoint on ties
+fn majority_hash(hashes: &[(String, String)]) -> Option<String> {
+	let mut counts: Vec<(&String, usize)> = Vec::new();
+	for (_, hash) in hashes {
+		match counts.iter_mut().find(|(known, _)| *known == hash) {
+			Some((_, count)) => *count += 1,
+			None => counts.push((hash, 1)),
+		}
+	}
+	// `max_by_key` returns the last maximum, so iterate in reverse to keep the first one
+	counts
+		.into_iter()
+		.rev()
+		.max_by_key(|(_, count)| *count)
+		.map(|(hash, _)| hash.clone())
+}
+
+fn log_report(network: &Network, config: &RpcConsistencyConfig, report: &ConsistencyReport) {
+	if !report.divergent_endpoints.is_empty() {
+		tracing::warn!(
+			network = %network.slug,
+			block = report.reference_block,
+			"RPC endpoints disagree on the block hash: {} diverge from the majority",
+			report.divergent_endpoints.join(", ")
+		);
+	}
+	for (endpoint, lag) in &report.endpoint_lag {
+		if *lag > config.max_block_lag {
+			tracing::warn!(
+				network = %network.slug,
+				"RPC endpoint {} lags {} blocks behind the most advanced endpoint",
+				endpoint,
+				lag
+			);
+		}
+	}
+	if !report.unreachable_endpoints.is_empty() {
+		tracing::debug!(
+			network = %network.slug,
+			"RPC consistency check could not query: {}",
+			report.unreachable_endpoints.join(", ")
+		);
+	}
+}
+
+/// Labels an endpoint by its host (and explicit port) so credentials in the path or query
+/// never leak into logs and metrics
+///
+/// Endpoints of the same host routed by path or query (e.g. an API key in the path) are told
+/// apart by the first 8 hex digits of the SHA-256 hash of their path and query.
+pub(super) fn endpoint_label(url: &str) -> String {
+	let Some(u) = Url::parse(url).ok().filter(|u| u.host_str().is_some()) else {
+		return "unknown".to_string();
+	};
+	let host = u.host_str().unwrap_or_default();
+	let mut label = match u.port() {
+		Some(port) => format!("{}:{}", host, port),
+		None => host.to_string(),
+	};
+	let route = &u[Position::BeforePath..Position::AfterQuery];
+	if route != "/" {
+		let hash = Sha256::digest(route.as_bytes());
+		label.push_str(&format!("/{}", hex::encode(&hash[..4])));
+	}
+	label
+}
+
+async fn rpc_request(
+	client: &reqwest::Client,
+	url: &str,
+	method: &str,
+	params: Value,
+) -> Option<Value> {
+	let response = client
+		.post(url)
+		.timeout(REQUEST_TIMEOUT)
+		.json(&json!({
+			"jsonrpc": "2.0",
+			"id": 1,
+			"method": method,
+			"params": params,
+		}))
+		.send()
+		.await
+		.ok()?;
+	let mut body: Value = response.json().await.ok()?;
+	match body.get_mut("result").map(Value::take) {
+		Some(Value::Null) | None => None,
+		Some(result) => Some(result),
+	}
+}
+
+fn parse_hex_u64(value: &Value) -> Option<u64> {
+	let hex = value.as_str()?;
+	u64::from_str_radix(hex.trim_start_matches("0x"), 16).ok()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::utils::tests::builders::network::NetworkBuilder;
+	use mockito::{Matcher, Server, ServerGuard};
+
+	fn config() -> RpcConsistencyConfig {
+		RpcConsistencyConfig {
+			enabled: true,
+			cron_schedule: "0 */1 * * * *".to_string(),
+			max_block_lag: 5,
+		}
+	}
+
+	async fn mock_endpoint(latest: u64, hash: &str) -> ServerGuard {
+		let mut server = Server::new_async().await;
+		server
+			.mock("POST", "/")
+			.match_body(Matcher::PartialJson(json!({"method": "eth_blockNumber"})))
+			.with_body(
+				json!({"jsonrpc": "2.0", "id": 1, "result": format!("0x{:x}", latest)}).to_string(),
+			)
+			.create_async()
+			.await;
+		server
+			.mock("POST", "/")
+			.match_body(Matcher::PartialJson(
+				json!({"method": "eth_getBlockByNumber"}),
+			))
+			.with_body(json!({"jsonrpc": "2.0", "id": 1, "result": {"hash": hash}}).to_string())
+			.create_async()
+			.await;
+		server
+	}
+
+	#[test]
+	fn test_endpoint_label() {
+		assert_eq!(endpoint_label("https://eth.example.com"), "eth.example.com");
+		assert_eq!(endpoint_label("http://localhost:8545/"), "localhost:8545");
+		assert_eq!(endpoint_label("not a url"), "unknown");
+
+		// Endpoints of the same host routed by path are told apart, without leaking the path
+		let first = endpoint_label("https://rpc.example.com/v2/key-one");
+		let second = endpoint_label("https://rpc.example.com/v2/key-two");
+		assert!(first.starts_with("rpc.example.com/"), "{}", first);
+		assert!(!first.contains("key-one"));
+		assert_ne!(first, second);
+		assert_eq!(first, endpoint_label("https://rpc.example.com/v2/key-one"));
+		assert_ne!(
+			endpoint_label("https://rpc.example.com/?apikey=one"),
+			endpoint_label("https://rpc.example.com/?apikey=two")
+		);
+	}
+
+	#[test]
+	fn test_majority_hash() {
+		let hashes = |values: &[&str]| {
+			values
+				.iter()
+				.enumerate()
+				.map(|(i, hash)| (format!("endpoint{}", i), hash.to_string()))
+				.collect::<Vec<_>>()
+		};
+
+		assert_eq!(
+			majority_hash(&hashes(&["0xb", "0xa", "0xa"])),
+			Some("0xa".to_string())
+		);
+		// Ties are resolved in favour of the first (highest-weight) endpoint
+		assert_eq!(
+			majority_hash(&hashes(&["0xb", "0xa"])),
+			Some("0xb".to_string())
+		);
+		assert_eq!(majority_hash(&[]), None);
+	}
+
+	#[tokio::test]
+	async fn test_check_block_hash_consistency_detects_divergence() {
+		let first = mock_endpoint(110, "0xaaa").await;
+		let second = mock_endpoint(100, "0xaaa").await;
+		let third = mock_endpoint(108, "0xbbb").await;
+
+		let network = NetworkBuilder::new()
+			.slug("consistency_divergence")
+			.confirmation_blocks(2)
+			.add_rpc_url(&first.url(), "rpc", 100)
+			.add_rpc_url(&second.url(), "rpc", 50)
+			.add_rpc_url(&third.url(), "rpc", 10)
+			.build();
+
+		let report =
+			check_block_hash_consistency(&network, &config(), &reqwest::Client::new()).await;
+
+		assert_eq!(report.reference_block, Some(98));
+		assert_eq!(report.divergent_endpoints, vec![third.host_with_port()]);
+		assert_eq!(report.endpoint_lag.get(&second.host_with_port()), Some(&10));
+		assert!(report.unreachable_endpoints.is_empty());
+	}
+
+	#[tokio::test]
+	async fn test_check_block_hash_consistency_single_endpoint() {
+		let network = NetworkBuilder::new()
+			.add_rpc_url("http://127.0.0.1:1", "rpc", 100)
+			.build();
+
+		let report =
+			check_block_hash_consistency(&network, &config(), &reqwest::Client::new()).await;
+
+		assert_eq!(report, ConsistencyReport::default());
+	}
+
+	#[tokio::test]
+	async fn test_check_block_hash_consistency_unreachable_endpoint() {
+		let first = mock_endpoint(100, "0xaaa").await;
+
+		let network = NetworkBuilder::new()
+			.slug("consistency_unreachable")
+			.add_rpc_url(&first.url(), "rpc", 100)
+			.add_rpc_url("http://localhost:1", "rpc", 50)
+			.build();
+
+		let report =
+			check_block_hash_consistency(&network, &config(), &reqwest::Client::new()).await;
+
+		assert_eq!(
+			report.unreachable_endpoints,
+			vec!["localhost:1".to_string()]
+		);
+		assert!(report.divergent_endpoints.is_empty());
+	}
+}
@@ -3,7 +3,9 @@
 //! Provides a comprehensive error handling system for block watching operations,
 //! including scheduling, network connectivity, and storage operations.
 
-use crate::utils::logging::error::{ErrorContext, TraceableError};
+use crate::utils::logging::error::{
+	classify_error_chain, ClassifiedError, ErrorClass, ErrorContext, TraceableError,
+};
 use std::collections::HashMap;
 use thiserror::Error as ThisError;
 use uuid::Uuid;
@@ -110,6 +112,20 @@ impl TraceableError for BlockWatcherError {
 	}
 }
 
+impl ClassifiedError for BlockWatcherError {
+	fn classification(&self) -> ErrorClass {
+		match self {
+			Self::NetworkError(ctx) => classify_error_chain(ctx, ErrorClass::Transient),
+			Self::SchedulerError(ctx)
+			| Self::ProcessingError(ctx)
+			| Self::StorageError(ctx)
+			| Self::BlockTrackerError(ctx)
+			| Self::RecoveryError(ctx) => classify_error_chain(ctx, ErrorClass::Permanent),
+			Self::Other(e) => classify_error_chain(e.as_ref(), ErrorClass::Permanent),
+		}
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -6,17 +6,39 @@
 //! - Block storage implementations
 //! - Error handling specific to block watching operations
 //! - Missed block recovery functionality
+//! - Capability detection of the RPC endpoints
+//! - Watchdog restarting stalled block processing runs
+//! - Skip list of poison blocks
+//! - Per-network task accounting and restart policies
 
+mod capabilities;
+mod consistency;
 mod error;
+mod poison;
 mod recovery;
 mod service;
 mod storage;
+mod task_group;
 mod tracker;
+mod watchdog;
 
+pub use capabilities::{
+	probe_network_capabilities, spawn_capability_probes, Capability, EndpointCapabilities,
+	EndpointCapabilityRegistry,
+};
+pub use consistency::{check_block_hash_consistency, ConsistencyReport};
 pub use error::BlockWatcherError;
+pub use poison::{
+	fetch_block, retry_skipped_blocks, BlockFailures, SkipNotifier, SkippedRetryResult,
+};
 pub use recovery::{process_missed_blocks, RecoveryResult};
 pub use service::{
 	process_new_blocks, BlockWatcherService, JobSchedulerTrait, NetworkBlockWatcher,
 };
-pub use storage::{BlockStorage, FileBlockStorage, MissedBlockEntry, MissedBlockStatus};
+pub use storage::{
+	BlockStorage, FileBlockStorage, MissedBlockEntry, MissedBlockStatus, SkippedBlockEntry,
+	DEFAULT_DATA_DIR,
+};
+pub use task_group::{spawn_network_task, QueuedBlocks, RestartTracker};
 pub use tracker::{BlockCheckResult, BlockTracker, BlockTrackerTrait};
+pub use watchdog::{report_progress, stalled_watchers, WatcherActivity};
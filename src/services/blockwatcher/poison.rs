@@ -0,0 +1,604 @@
+//! Poison block handling module.
+//!
+//! A block that cannot be fetched or processed (e.g. a malformed response that never
+//! decodes) makes every run fail on the same batch, so the watcher never advances. When a
+//! network has a `poison_block_policy`, the failed attempts of a batch are counted and, once
+//! the limit is reached, the batch is processed block by block: blocks that still fail are
+//! recorded in the skip list, reported through an error log, the `blocks_skipped_total`
+//! metric, the status of the systemd unit and the `on_block_skipped` triggers of the policy
+//! (see [`SkipNotifier`]), and the watcher continues. Skipped blocks can be
+//! re-attempted later with [`retry_skipped_blocks`].
+//!
+//! The block handler cannot fail, so a block whose filtering failed (e.g. its events could
+//! not be fetched) is recorded with [`BlockFailures::record_filter_failure`]. Its batch then
+//! fails instead of checkpointing the block without its matches.
+
+use anyhow::Context;
+use futures::future::BoxFuture;
+use std::{
+	collections::{BTreeMap, HashMap},
+	future::Future,
+	ops::RangeInclusive,
+	sync::{Arc, Mutex, MutexGuard},
+};
+
+use crate::{
+	models::{BlockChainType, BlockType, Network, ProcessedBlock, Trigger},
+	services::{
+		blockchain::{BlockChainClient, ClientPoolTrait},
+		blockwatcher::{
+			error::BlockWatcherError,
+			storage::{BlockStorage, SkippedBlockEntry},
+		},
+		notification::NotificationService,
+	},
+	utils::{metrics::BLOCKS_SKIPPED_TOTAL, systemd},
+};
+
+/// Result of a skipped blocks retry
+#[derive(Debug, Clone, Default)]
+pub struct SkippedRetryResult {
+	/// Number of skipped blocks attempted
+	pub attempted: usize,
+	/// Number of blocks processed and removed from the skip list
+	pub recovered: usize,
+	/// Number of blocks that failed again and remain in the skip list
+	pub failed: usize,
+}
+
+/// Notifies the `on_block_skipped` triggers of the poison block policies
+pub struct SkipNotifier {
+	notification_service: Arc<NotificationService>,
+	/// Triggers notified of the skipped blocks, keyed by network slug
+	triggers: HashMap<String, Vec<Trigger>>,
+}
+
+impl SkipNotifier {
+	/// Creates a notifier for the networks whose poison block policy sets triggers
+	///
+	/// # Arguments
+	/// * `notification_service` - The service sending the notifications
+	/// * `triggers` - The configured triggers, keyed by name
+	/// * `networks` - The configured networks, keyed by slug
+	pub fn new(
+		notification_service: Arc<NotificationService>,
+		triggers: &HashMap<String, Trigger>,
+		networks: &HashMap<String, Network>,
+	) -> Self {
+		let triggers = networks
+			.values()
+			.filter_map(|network| {
+				let policy = network.poison_block_policy.as_ref()?;
+				let network_triggers: Vec<Trigger> = policy
+					.on_block_skipped
+					.iter()
+					.filter_map(|name| triggers.get(name).cloned())
+					.collect();
+				(!network_triggers.is_empty()).then(|| (network.slug.clone(), network_triggers))
+			})
+			.collect();
+		Self {
+			notification_service,
+			triggers,
+		}
+	}
+
+	/// Notifies the triggers of a network that a block was skipped
+	///
+	/// The notifications expose `skip.network`, `skip.block`, `skip.attempts` and
+	/// `skip.error`. A notification that fails is logged.
+	///
+	/// # Arguments
+	/// * `network` - Network of the block
+	/// * `block_number` - The skipped block
+	/// * `attempts` - Number of failed attempts
+	/// * `error` - Error of the last attempt
+	async fn notify(&self, network: &Network, block_number: u64, attempts: u32, error: &str) {
+		let Some(triggers) = self.triggers.get(&network.slug) else {
+			return;
+		};
+		let variables = HashMap::from([
+			("skip.network".to_string(), network.slug.clone()),
+			("skip.block".to_string(), block_number.to_string()),
+			("skip.attempts".to_string(), attempts.to_string()),
+			("skip.error".to_string(), error.to_string()),
+		]);
+		for trigger in triggers {
+			if let Err(e) = self.notification_service.notify(trigger, &variables).await {
+				tracing::error!(
+					network = %network.slug,
+					block = block_number,
+					"Failed to notify the skipped block to trigger {}: {}",
+					trigger.name,
+					e
+				);
+			}
+		}
+	}
+}
+
+/// Failures of the blocks processed by a block watcher service
+#[derive(Default)]
+pub struct BlockFailures {
+	/// Failed attempts of the current batch per network, keyed by network slug
+	batches: Mutex<HashMap<String, (u64, u32)>>,
+	/// Errors of the blocks whose filtering failed, keyed by network slug and block number
+	filters: Mutex<HashMap<String, BTreeMap<u64, String>>>,
+	/// Notifier of the skipped blocks, if any network sets `on_block_skipped` triggers
+	skip_notifier: Option<SkipNotifier>,
+}
+
+impl BlockFailures {
+	/// Sets the notifier of the blocks skipped under a poison block policy
+	///
+	/// # Arguments
+	/// * `skip_notifier` - Notifier of the `on_block_skipped` triggers
+	pub fn with_skip_notifier(mut self, skip_notifier: SkipNotifier) -> Self {
+		self.skip_notifier = Some(skip_notifier);
+		self
+	}
+
+	/// Records a failed attempt of the batch starting at `batch_start`
+	///
+	/// # Arguments
+	/// * `network_slug` - Network of the batch
+	/// * `batch_start` - First block of the batch
+	///
+	/// # Returns
+	/// The number of consecutive failed attempts of this batch
+	pub(crate) fn record_batch_failure(&self, network_slug: &str, batch_start: u64) -> u32 {
+		let mut failures = lock(&self.batches);
+		let entry = failures
+			.entry(network_slug.to_string())
+			.or_insert((batch_start, 0));
+		if entry.0 != batch_start {
+			*entry = (batch_start, 0);
+		}
+		entry.1 += 1;
+		entry.1
+	}
+
+	/// Clears the failed attempts recorded for a network
+	///
+	/// # Arguments
+	/// * `network_slug` - Network whose batch succeeded
+	pub(crate) fn clear_batch_failures(&self, network_slug: &str) {
+		lock(&self.batches).remove(network_slug);
+	}
+
+	/// Records that the filtering of a block failed, so that the block is not checkpointed
+	///
+	/// # Arguments
+	/// * `network_slug` - Network of the block
+	/// * `block_number` - The block whose filtering failed
+	/// * `error` - Error of the filtering
+	pub fn record_filter_failure(&self, network_slug: &str, block_number: u64, error: String) {
+		lock(&self.filters)
+			.entry(network_slug.to_string())
+			.or_default()
+			.insert(block_number, error);
+	}
+
+	/// Returns whether the filtering of a block failed
+	///
+	/// # Arguments
+	/// * `network_slug` - Network of the block
+	/// * `block_number` - The block
+	pub(crate) fn filter_failed(&self, network_slug: &str, block_number: u64) -> bool {
+		lock(&self.filters)
+			.get(network_slug)
+			.is_some_and(|failures| failures.contains_key(&block_number))
+	}
+
+	/// Takes the failures recorded for a range of blocks
+	///
+	/// # Arguments
+	/// * `network_slug` - Network of the blocks
+	/// * `blocks` - Range of the blocks
+	///
+	/// # Returns
+	/// The first block of the range whose filtering failed, with its error
+	pub(crate) fn take_filter_failure(
+		&self,
+		network_slug: &str,
+		blocks: RangeInclusive<u64>,
+	) -> Option<(u64, String)> {
+		let mut failures = lock(&self.filters);
+		let network_failures = failures.get_mut(network_slug)?;
+		let failed_blocks: Vec<u64> = network_failures
+			.range(blocks)
+			.map(|(block, _)| *block)
+			.collect();
+		let first = failed_blocks
+			.into_iter()
+			.filter_map(|block| network_failures.remove(&block).map(|error| (block, error)))
+			.next();
+		if network_failures.is_empty() {
+			failures.remove(network_slug);
+		}
+		first
+	}
+}
+
+/// Adds a block to the skip list and reports it
+///
+/// # Arguments
+/// * `network` - Network of the block
+/// * `block_storage` - Storage holding the skip list
+/// * `failures` - Failures of the blocks, holding the notifier of the skipped blocks
+/// * `block_number` - The skipped block
+/// * `attempts` - Number of failed attempts
+/// * `error` - Error of the last attempt
+pub(crate) async fn skip_block<S: BlockStorage>(
+	network: &Network,
+	block_storage: &Arc<S>,
+	failures: &BlockFailures,
+	block_number: u64,
+	attempts: u32,
+	error: String,
+) -> Result<(), BlockWatcherError> {
+	tracing::error!(
+		network = %network.slug,
+		block = block_number,
+		attempts,
+		error = %error,
+		"Skipping block {} after {} failed attempts: {}",
+		block_number,
+		attempts,
+		error
+	);
+	block_storage
+		.save_skipped_block(
+			&network.slug,
+			SkippedBlockEntry::new(block_number, attempts, error.clone()),
+		)
+		.await
+		.with_context(|| format!("Failed to add block {} to the skip list", block_number))?;
+	BLOCKS_SKIPPED_TOTAL
+		.with_label_values(&[network.slug.as_str()])
+		.inc();
+	systemd::notify_or_log(&format!(
+		"STATUS=Skipped block {} of {} after {} failed attempts",
+		block_number, network.slug, attempts
+	));
+	if let Some(skip_notifier) = &failures.skip_notifier {
+		skip_notifier
+			.notify(network, block_number, attempts, &error)
+			.await;
+	}
+	Ok(())
+}
+
+/// Fetches a single block with the client of its network
+///
+/// # Arguments
+/// * `client_pool` - The client pool holding the clients of the networks
+/// * `network` - Network of the block
+/// * `block_number` - The block to fetch
+pub async fn fetch_block<CP: ClientPoolTrait>(
+	client_pool: &CP,
+	network: &Network,
+	block_number: u64,
+) -> Result<Vec<BlockType>, anyhow::Error> {
+	let end_block = Some(block_number);
+	match network.network_type {
+		BlockChainType::EVM => {
+			let client = client_pool.get_evm_client(network).await?;
+			client.get_blocks(block_number, end_block).await
+		}
+		BlockChainType::Stellar => {
+			let client = client_pool.get_stellar_client(network).await?;
+			client.get_blocks(block_number, end_block).await
+		}
+		BlockChainType::Midnight => {
+			let client = client_pool.get_midnight_client(network).await?;
+			client.get_blocks(block_number, end_block).await
+		}
+		BlockChainType::Solana => {
+			let client = client_pool.get_solana_client(network).await?;
+			client.get_blocks(block_number, end_block).await
+		}
+	}
+}
+
+/// Re-attempts the blocks in the skip list of a network
+///
+/// Each skipped block is fetched and run through the block and trigger handlers. Blocks
+/// processed successfully are removed from the skip list, the others keep their entry with
+/// the new error and attempt count.
+///
+/// # Arguments
+/// * `network` - Network of the skipped blocks
+/// * `fetch_block` - Function fetching a block by number, e.g. with [`fetch_block`]
+/// * `block_storage` - Storage holding the skip list
+/// * `block_handler` - Handler function for fetched blocks
+/// * `trigger_handler` - Handler function for processed blocks
+/// * `failures` - Failures of the blocks, recorded by the block handler
+///
+/// # Returns
+/// * `Result<SkippedRetryResult, BlockWatcherError>` - Statistics of the retry
+pub async fn retry_skipped_blocks<S, F, Fut, H, T>(
+	network: &Network,
+	fetch_block: F,
+	block_storage: Arc<S>,
+	block_handler: Arc<H>,
+	trigger_handler: Arc<T>,
+	failures: Arc<BlockFailures>,
+) -> Result<SkippedRetryResult, BlockWatcherError>
+where
+	S: BlockStorage + Send + Sync,
+	F: Fn(u64) -> Fut,
+	Fut: Future<Output = Result<Vec<BlockType>, anyhow::Error>>,
+	H: Fn(BlockType, Network) -> BoxFuture<'static, ProcessedBlock> + Send + Sync,
+	T: Fn(&ProcessedBlock) -> tokio::task::JoinHandle<()> + Send + Sync,
+{
+	let mut result = SkippedRetryResult::default();
+	let skipped_blocks = block_storage
+		.get_skipped_blocks(&network.slug)
+		.await
+		.with_context(|| "Failed to get skipped blocks")?;
+
+	let mut recovered_blocks = Vec::new();
+	for entry in skipped_blocks {
+		result.attempted += 1;
+		let block_number = entry.block_number;
+
+		let error = match fetch_block(block_number).await {
+			Ok(blocks) => match blocks.into_iter().next() {
+				Some(block) => {
+					let processed_block = (block_handler)(block, network.clone()).await;
+					match failures.take_filter_failure(&network.slug, block_number..=block_number) {
+						Some((_, error)) => Some(format!("Filtering failed: {}", error)),
+						None => match (trigger_handler)(&processed_block).await {
+							Ok(()) => None,
+							Err(e) => Some(format!("Trigger execution failed: {}", e)),
+						},
+					}
+				}
+				None => Some("Block not found in RPC response".to_string()),
+			},
+			Err(e) => Some(e.to_string()),
+		};
+
+		match error {
+			None => {
+				tracing::info!(
+					network = %network.slug,
+					block = block_number,
+					"Processed skipped block"
+				);
+				recovered_blocks.push(block_number);
+				result.recovered += 1;
+			}
+			Some(error) => {
+				tracing::warn!(
+					network = %network.slug,
+					block = block_number,
+					"Skipped block failed again: {}",
+					error
+				);
+				block_storage
+					.save_skipped_block(
+						&network.slug,
+						SkippedBlockEntry::new(block_number, entry.attempts + 1, error),
+					)
+					.await
+					.with_context(|| "Failed to update skipped block")?;
+				result.failed += 1;
+			}
+		}
+	}
+
+	block_storage
+		.remove_skipped_blocks(&network.slug, &recovered_blocks)
+		.await
+		.with_context(|| "Failed to remove processed blocks from the skip list")?;
+
+	Ok(result)
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+	match mutex.lock() {
+		Ok(guard) => guard,
+		Err(poisoned) => poisoned.into_inner(),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		models::{EVMBlock, PoisonBlockPolicy},
+		services::blockwatcher::storage::FileBlockStorage,
+		utils::tests::{network::NetworkBuilder, trigger::TriggerBuilder},
+	};
+	use tempfile::tempdir;
+
+	#[derive(Clone)]
+	struct MockRpcClient {
+		failing_block: u64,
+	}
+
+	#[async_trait::async_trait]
+	impl BlockChainClient for MockRpcClient {
+		async fn get_latest_block_number(&self) -> Result<u64, anyhow::Error> {
+			Ok(1000)
+		}
+
+		async fn get_blocks(
+			&self,
+			start: u64,
+			_end: Option<u64>,
+		) -> Result<Vec<BlockType>, anyhow::Error> {
+			if start == self.failing_block {
+				return Err(anyhow::anyhow!("Malformed block"));
+			}
+			let block = alloy::rpc::types::Block {
+				header: alloy::rpc::types::Header {
+					inner: alloy::consensus::Header {
+						number: start,
+						..Default::default()
+					},
+					..Default::default()
+				},
+				..Default::default()
+			};
+			Ok(vec![BlockType::EVM(Box::new(EVMBlock::from(block)))])
+		}
+	}
+
+	#[test]
+	fn test_record_batch_failure() {
+		let failures = BlockFailures::default();
+		assert_eq!(failures.record_batch_failure("poison_record", 100), 1);
+		assert_eq!(failures.record_batch_failure("poison_record", 100), 2);
+		// A different batch restarts the count
+		assert_eq!(failures.record_batch_failure("poison_record", 130), 1);
+		failures.clear_batch_failures("poison_record");
+		assert_eq!(failures.record_batch_failure("poison_record", 130), 1);
+	}
+
+	#[test]
+	fn test_filter_failures() {
+		let failures = BlockFailures::default();
+		failures.record_filter_failure("poison_filter", 12, "Failed to get events".into());
+		failures.record_filter_failure("poison_filter", 10, "Failed to get events".into());
+		failures.record_filter_failure("poison_filter", 30, "Failed to get events".into());
+		assert!(failures.filter_failed("poison_filter", 10));
+		assert!(!failures.filter_failed("poison_filter", 11));
+		assert!(!failures.filter_failed("other_network", 10));
+
+		// The first failure of the range is returned, the failures of the range are cleared
+		assert_eq!(
+			failures.take_filter_failure("poison_filter", 5..=20),
+			Some((10, "Failed to get events".to_string()))
+		);
+		assert!(!failures.filter_failed("poison_filter", 12));
+		assert!(failures.filter_failed("poison_filter", 30));
+		assert_eq!(failures.take_filter_failure("poison_filter", 5..=20), None);
+	}
+
+	#[tokio::test]
+	async fn test_retry_skipped_blocks() {
+		let temp_dir = tempdir().unwrap();
+		let storage = Arc::new(FileBlockStorage::new(temp_dir.path().to_path_buf()));
+		let network = NetworkBuilder::new().slug("poison_retry").build();
+
+		for block_number in [100, 200] {
+			skip_block(
+				&network,
+				&storage,
+				&BlockFailures::default(),
+				block_number,
+				3,
+				"Malformed block".into(),
+			)
+			.await
+			.unwrap();
+		}
+		assert_eq!(
+			BLOCKS_SKIPPED_TOTAL
+				.with_label_values(&["poison_retry"])
+				.get(),
+			2.0
+		);
+
+		let block_handler = Arc::new(|block: BlockType, network: Network| {
+			Box::pin(async move {
+				ProcessedBlock {
+					network_slug: network.slug,
+					block_number: block.number().unwrap_or(0),
+					processing_results: vec![],
+				}
+			}) as BoxFuture<'static, ProcessedBlock>
+		});
+		let trigger_handler = Arc::new(|_block: &ProcessedBlock| tokio::spawn(async {}));
+
+		let client = MockRpcClient { failing_block: 200 };
+		let result = retry_skipped_blocks(
+			&network,
+			|block_number| client.get_blocks(block_number, Some(block_number)),
+			storage.clone(),
+			block_handler,
+			trigger_handler,
+			Arc::new(BlockFailures::default()),
+		)
+		.await
+		.unwrap();
+
+		assert_eq!(result.attempted, 2);
+		assert_eq!(result.recovered, 1);
+		assert_eq!(result.failed, 1);
+
+		let remaining = storage.get_skipped_blocks("poison_retry").await.unwrap();
+		assert_eq!(remaining.len(), 1);
+		assert_eq!(remaining[0].block_number, 200);
+		assert_eq!(remaining[0].attempts, 4);
+	}
+
+	#[tokio::test]
+	async fn test_skip_block_notifies_triggers() {
+		let temp_dir = tempdir().unwrap();
+		let storage = Arc::new(FileBlockStorage::new(temp_dir.path().to_path_buf()));
+		let mut server = mockito::Server::new_async().await;
+		let mock = server
+			.mock("POST", "/")
+			.match_body(mockito::Matcher::Regex(
+				"Block 100 of poison_notify skipped after 3 attempts".to_string(),
+			))
+			.with_status(200)
+			.expect(1)
+			.create_async()
+			.await;
+
+		let trigger = TriggerBuilder::new()
+			.name("skipped_blocks")
+			.slack(&server.url())
+			.message(
+				"Skipped block",
+				"Block ${skip.block} of ${skip.network} skipped after ${skip.attempts} attempts",
+			)
+			.build();
+		let network = NetworkBuilder::new()
+			.slug("poison_notify")
+			.poison_block_policy(PoisonBlockPolicy {
+				enabled: true,
+				max_attempts: 3,
+				on_block_skipped: vec!["skipped_blocks".to_string()],
+			})
+			.build();
+		let other_network = NetworkBuilder::new().slug("poison_silent").build();
+		let failures = BlockFailures::default().with_skip_notifier(SkipNotifier::new(
+			Arc::new(NotificationService::new()),
+			&HashMap::from([("skipped_blocks".to_string(), trigger)]),
+			&HashMap::from([
+				(network.slug.clone(), network.clone()),
+				(other_network.slug.clone(), other_network.clone()),
+			]),
+		));
+
+		skip_block(
+			&network,
+			&storage,
+			&failures,
+			100,
+			3,
+			"Malformed block".into(),
+		)
+		.await
+		.unwrap();
+		// Networks without `on_block_skipped` triggers are not notified
+		skip_block(
+			&other_network,
+			&storage,
+			&failures,
+			100,
+			3,
+			"Malformed block".into(),
+		)
+		.await
+		.unwrap();
+
+		mock.assert_async().await;
+	}
+}
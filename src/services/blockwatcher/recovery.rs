@@ -14,6 +14,7 @@ use crate::{
 		blockchain::BlockChainClient,
 		blockwatcher::{
 			error::BlockWatcherError,
+			poison::BlockFailures,
 			storage::{BlockStorage, MissedBlockStatus},
 			tracker::BlockTrackerTrait,
 		},
@@ -59,6 +60,7 @@ pub async fn process_missed_blocks<S, C, H, T, TR>(
 	block_handler: Arc<H>,
 	trigger_handler: Arc<T>,
 	_block_tracker: Arc<TR>,
+	failures: Arc<BlockFailures>,
 ) -> Result<RecoveryResult, BlockWatcherError>
 where
 	S: BlockStorage + Send + Sync,
@@ -154,17 +156,28 @@ where
 			);
 		}
 
-		// Attempt to fetch the block
-		match rpc_client
+		// Attempt to fetch and filter the block
+		let fetch_result = match rpc_client
 			.get_blocks(block_number, Some(block_number))
 			.await
 		{
 			Ok(blocks) if !blocks.is_empty() => {
 				let block = blocks.into_iter().next().unwrap();
 
-				// Process through block handler
+				// Process through block handler, a block whose filtering failed is retried
+				// like a block that could not be fetched
 				let processed_block = (block_handler)(block, network.clone()).await;
+				match failures.take_filter_failure(&network.slug, block_number..=block_number) {
+					Some((_, error)) => Err(anyhow::anyhow!("Failed to filter block: {}", error)),
+					None => Ok(Some(processed_block)),
+				}
+			}
+			Ok(_) => Ok(None),
+			Err(e) => Err(e),
+		};
 
+		match fetch_result {
+			Ok(Some(processed_block)) => {
 				// Execute trigger handler
 				let _handle = (trigger_handler)(&processed_block);
 
@@ -195,7 +208,7 @@ where
 					"Successfully recovered missed block"
 				);
 			}
-			Ok(_) => {
+			Ok(None) => {
 				// Block not found (empty response)
 				let new_retry_count = entry.retry_count + 1;
 				let error_msg = "Block not found in RPC response".to_string();
@@ -334,7 +347,7 @@ mod tests {
 	use super::*;
 	use crate::models::{BlockChainType, MaxPastBlocks, RpcUrl, SecretString, SecretValue};
 	use crate::services::blockwatcher::storage::{
-		BlockStorage, FileBlockStorage, MissedBlockEntry,
+		BlockStorage, FileBlockStorage, MissedBlockEntry, SkippedBlockEntry,
 	};
 	use crate::services::blockwatcher::tracker::BlockTracker;
 	use std::sync::atomic::{AtomicUsize, Ordering};
@@ -349,6 +362,7 @@ mod tests {
 				type_: "rpc".to_string(),
 				url: SecretValue::Plain(SecretString::new("http://localhost:8545".to_string())),
 				weight: 100,
+				provider: None,
 			}],
 			chain_id: Some(1),
 			network_passphrase: None,
@@ -356,6 +370,7 @@ mod tests {
 			confirmation_blocks: 12,
 			cron_schedule: "*/10 * * * * *".to_string(),
 			max_past_blocks: Some(MaxPastBlocks::Limited(100)),
+			start_block: None,
 			store_blocks: Some(true),
 			recovery_config: Some(BlockRecoveryConfig {
 				enabled: true,
@@ -365,6 +380,14 @@ mod tests {
 				max_retries: 3,
 				retry_delay_ms: 100,
 			}),
+			rpc_consistency_check: None,
+			watchdog: None,
+			poison_block_policy: None,
+			rpc_cost: None,
+			get_logs_limits: None,
+			block_guardrails: None,
+			restart_policy: None,
+			trace_budget: None,
 		}
 	}
 
@@ -513,6 +536,21 @@ mod tests {
 				.await
 		}
 
+		async fn get_cold_start_target(
+			&self,
+			network_id: &str,
+		) -> Result<Option<u64>, anyhow::Error> {
+			self.inner.get_cold_start_target(network_id).await
+		}
+
+		async fn save_cold_start_target(
+			&self,
+			network_id: &str,
+			block: Option<u64>,
+		) -> Result<(), anyhow::Error> {
+			self.inner.save_cold_start_target(network_id, block).await
+		}
+
 		async fn save_blocks(
 			&self,
 			network_id: &str,
@@ -583,6 +621,31 @@ mod tests {
 				.prune_old_missed_blocks(network_id, max_block_age, current_block)
 				.await
 		}
+
+		async fn save_skipped_block(
+			&self,
+			network_id: &str,
+			entry: SkippedBlockEntry,
+		) -> Result<(), anyhow::Error> {
+			self.inner.save_skipped_block(network_id, entry).await
+		}
+
+		async fn get_skipped_blocks(
+			&self,
+			network_id: &str,
+		) -> Result<Vec<SkippedBlockEntry>, anyhow::Error> {
+			self.inner.get_skipped_blocks(network_id).await
+		}
+
+		async fn remove_skipped_blocks(
+			&self,
+			network_id: &str,
+			block_numbers: &[u64],
+		) -> Result<(), anyhow::Error> {
+			self.inner
+				.remove_skipped_blocks(network_id, block_numbers)
+				.await
+		}
 	}
 
 	fn create_block_handler() -> Arc<
@@ -625,6 +688,7 @@ mod tests {
 			block_handler,
 			trigger_handler,
 			block_tracker,
+			Arc::new(BlockFailures::default()),
 		)
 		.await
 		.unwrap();
@@ -661,6 +725,7 @@ mod tests {
 			block_handler,
 			trigger_handler,
 			block_tracker,
+			Arc::new(BlockFailures::default()),
 		)
 		.await
 		.unwrap();
@@ -706,6 +771,7 @@ mod tests {
 			block_handler,
 			trigger_handler,
 			block_tracker,
+			Arc::new(BlockFailures::default()),
 		)
 		.await
 		.unwrap();
@@ -744,6 +810,7 @@ mod tests {
 			block_handler,
 			trigger_handler,
 			block_tracker,
+			Arc::new(BlockFailures::default()),
 		)
 		.await
 		.unwrap();
@@ -788,6 +855,7 @@ mod tests {
 			block_handler,
 			trigger_handler,
 			block_tracker,
+			Arc::new(BlockFailures::default()),
 		)
 		.await
 		.unwrap();
@@ -828,6 +896,7 @@ mod tests {
 			block_handler,
 			trigger_handler,
 			block_tracker,
+			Arc::new(BlockFailures::default()),
 		)
 		.await
 		.unwrap();
@@ -877,6 +946,7 @@ mod tests {
 			block_handler,
 			trigger_handler,
 			block_tracker,
+			Arc::new(BlockFailures::default()),
 		)
 		.await
 		.unwrap();
@@ -917,6 +987,7 @@ mod tests {
 			block_handler,
 			trigger_handler,
 			block_tracker,
+			Arc::new(BlockFailures::default()),
 		)
 		.await
 		.unwrap();
@@ -966,6 +1037,7 @@ mod tests {
 			block_handler,
 			trigger_handler,
 			block_tracker,
+			Arc::new(BlockFailures::default()),
 		)
 		.await
 		.unwrap();
@@ -1007,6 +1079,7 @@ mod tests {
 			block_handler,
 			trigger_handler,
 			block_tracker,
+			Arc::new(BlockFailures::default()),
 		)
 		.await
 		.unwrap();
@@ -1045,6 +1118,7 @@ mod tests {
 			block_handler,
 			trigger_handler,
 			block_tracker,
+			Arc::new(BlockFailures::default()),
 		)
 		.await
 		.unwrap();
@@ -1085,6 +1159,7 @@ mod tests {
 			block_handler,
 			trigger_handler,
 			block_tracker,
+			Arc::new(BlockFailures::default()),
 		)
 		.await
 		.unwrap();
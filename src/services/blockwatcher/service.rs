@@ -4,12 +4,17 @@
 //! managing individual watchers for each network and coordinating block processing.
 
 use anyhow::Context;
+use chrono::Utc;
 use futures::{channel::mpsc, future::BoxFuture, stream::StreamExt, SinkExt};
 use std::{
 	collections::{BTreeMap, HashMap},
 	sync::Arc,
+	time::Duration,
+};
+use tokio::{
+	sync::RwLock,
+	task::{JoinError, JoinSet},
 };
-use tokio::sync::RwLock;
 use tokio_cron_scheduler::{Job, JobScheduler};
 use tracing::instrument;
 
@@ -18,13 +23,24 @@ use crate::{
 	services::{
 		blockchain::{BlockChainClient, BlockFetchResult, FetchStreamKind},
 		blockwatcher::{
+			consistency::check_block_hash_consistency,
 			error::BlockWatcherError,
+			poison::{skip_block, BlockFailures},
 			recovery::process_missed_blocks,
 			storage::BlockStorage,
+			task_group::{
+				join_network_tasks, spawn_network_task, spawn_network_task_in, QueuedBlocks,
+				RestartTracker,
+			},
 			tracker::{BlockCheckResult, BlockTracker, BlockTrackerTrait},
+			watchdog::WatcherActivity,
 		},
+		trigger::LatencyTracker,
+	},
+	utils::{
+		control::CONTROL, crash::network_scope, logging::error::ClassifiedError,
+		metrics::BLOCK_CHECKPOINT_LAG,
 	},
-	utils::metrics::BLOCK_CHECKPOINT_LAG,
 };
 
 /// Number of blocks fetched and processed per batch while catching up.
@@ -50,6 +66,8 @@ struct BatchProcessingContext<'a, S, H, T, TR> {
 	block_handler: &'a Arc<H>,
 	trigger_handler: &'a Arc<T>,
 	block_tracker: &'a Arc<TR>,
+	failures: &'a Arc<BlockFailures>,
+	latency: &'a Arc<LatencyTracker>,
 	latest_confirmed_block: u64,
 }
 
@@ -73,6 +91,7 @@ async fn save_checkpoint<S: BlockStorage>(
 	BLOCK_CHECKPOINT_LAG
 		.with_label_values(&[network.slug.as_str()])
 		.set(latest_confirmed_block.saturating_sub(checkpoint_block) as f64);
+	CONTROL.record_checkpoint(&network.slug, checkpoint_block, latest_confirmed_block);
 
 	Ok(())
 }
@@ -89,6 +108,14 @@ fn log_checkpoint_lag(network: &Network, checkpoint_block: u64, latest_confirmed
 	);
 }
 
+/// Resumes the panic of a pipeline stage in the run, other task failures are returned
+fn resume_panic(error: JoinError) -> JoinError {
+	if error.is_panic() {
+		std::panic::resume_unwind(error.into_panic());
+	}
+	error
+}
+
 async fn process_block_batch<
 	S: BlockStorage,
 	H: Fn(BlockType, Network) -> BoxFuture<'static, ProcessedBlock> + Send + Sync + 'static,
@@ -142,18 +169,35 @@ async fn process_block_batch<
 		}
 	}
 
+	// The blocks are received once fetched, the start of the latency of their notifications
+	let received_at = Utc::now().timestamp_millis();
+	for block in &blocks {
+		context
+			.latency
+			.record_received(&network.slug, block, received_at);
+	}
+
+	// Failures left by an earlier attempt of these blocks are stale
+	let failures = context.failures;
+	failures.take_filter_failure(&network.slug, batch_start..=checkpoint_block);
+
 	// Create channels for our pipeline
 	let channel_size = (blocks.len() * 2).max(1);
 	let (process_tx, process_rx) = mpsc::channel::<(BlockType, u64)>(channel_size);
 	let (trigger_tx, trigger_rx) = mpsc::channel::<ProcessedBlock>(channel_size);
 
+	// The pipeline stages are owned by the run, so that they are aborted with it (e.g. by the
+	// watchdog) rather than left running alongside the next run
+	let mut stages = JoinSet::new();
+
 	// Stage 1: Block Processing Pipeline
-	let process_handle = tokio::spawn({
+	let process_id = spawn_network_task_in(&mut stages, &network.slug, "filter", {
 		let network = network.clone();
 		let block_handler = Arc::clone(context.block_handler);
 		let mut trigger_tx = trigger_tx.clone();
+		let slug = network.slug.clone();
 
-		async move {
+		network_scope(&slug, async move {
 			// Process blocks concurrently, up to 32 at a time
 			let mut results = process_rx
 				.map(|(block, _)| {
@@ -172,20 +216,27 @@ async fn process_block_batch<
 			}
 
 			Ok::<(), BlockWatcherError>(())
-		}
-	});
+		})
+	})
+	.id();
 
 	// Stage 2: Trigger Pipeline
-	let trigger_handle = tokio::spawn({
+	let trigger_id = spawn_network_task_in(&mut stages, &network.slug, "dispatch", {
 		let network = network.clone();
 		let trigger_handler = Arc::clone(context.trigger_handler);
 		let block_tracker = Arc::clone(context.block_tracker);
+		let failures = Arc::clone(failures);
+		let slug = network.slug.clone();
+		let mut queued_blocks = QueuedBlocks::new(&network.slug, blocks.len());
 
-		async move {
+		network_scope(&slug, async move {
 			let mut trigger_rx = trigger_rx;
 			let mut pending_blocks = BTreeMap::new();
 			let mut next_block_number = Some(batch_start);
 			let block_tracker = block_tracker.clone();
+			// A block whose filtering failed is not handed over, nor are the blocks after it,
+			// so that the batch can be checkpointed right before it
+			let mut halted = false;
 
 			// Process all incoming blocks
 			while let Some(processed_block) = trigger_rx.next().await {
@@ -196,6 +247,10 @@ async fn process_block_batch<
 
 				// Process blocks in order as long as we have the next expected block
 				while let Some(expected) = next_block_number {
+					if halted || failures.filter_failed(&network.slug, expected) {
+						halted = true;
+						break;
+					}
 					if let Some(block) = pending_blocks.remove(&expected) {
 						// Check for duplicate or out-of-order blocks when actually executing
 						// This ensures we're checking the execution order, not arrival order
@@ -232,6 +287,7 @@ async fn process_block_batch<
 						}
 
 						(trigger_handler)(&block);
+						queued_blocks.handed_over();
 						next_block_number = Some(expected + 1);
 					} else {
 						break;
@@ -241,6 +297,9 @@ async fn process_block_batch<
 
 			// Process any remaining blocks in order after the channel is closed
 			while let Some(min_block) = pending_blocks.keys().next().copied() {
+				if halted || failures.filter_failed(&network.slug, min_block) {
+					break;
+				}
 				if let Some(block) = pending_blocks.remove(&min_block) {
 					// Check for duplicate or out-of-order blocks when executing
 					match block_tracker
@@ -276,11 +335,13 @@ async fn process_block_batch<
 					}
 
 					(trigger_handler)(&block);
+					queued_blocks.handed_over();
 				}
 			}
 			Ok::<(), BlockWatcherError>(())
-		}
-	});
+		})
+	})
+	.id();
 
 	// Feed blocks into the pipeline
 	futures::future::join_all(blocks.iter().map(|block| {
@@ -306,10 +367,40 @@ async fn process_block_batch<
 	drop(process_tx);
 	drop(trigger_tx);
 
-	// Wait for both pipeline stages to complete
-	let (process_result, trigger_result) = tokio::join!(process_handle, trigger_handle);
-	process_result.map_err(|e| anyhow::anyhow!("Block processing task failed: {}", e))??;
-	trigger_result.map_err(|e| anyhow::anyhow!("Trigger processing task failed: {}", e))??;
+	// Wait for both pipeline stages to complete, the panic of a stage being resumed in the run
+	// so that it reaches the restart tracker
+	let mut results = join_network_tasks(&mut stages).await;
+	for (id, stage) in [
+		(process_id, "Block processing"),
+		(trigger_id, "Trigger processing"),
+	] {
+		if let Some(result) = results.remove(&id) {
+			result
+				.map_err(resume_panic)
+				.map_err(|e| anyhow::anyhow!("{} task failed: {}", stage, e))??;
+		}
+	}
+
+	// The matches of a block whose filtering failed are unknown, the batch fails so that the
+	// block is retried (or skipped under a poison block policy) instead of being checkpointed
+	if let Some((failed_block, error)) =
+		failures.take_filter_failure(&network.slug, batch_start..=checkpoint_block)
+	{
+		if failed_block > batch_start {
+			save_checkpoint(
+				block_storage,
+				network,
+				failed_block - 1,
+				context.latest_confirmed_block,
+			)
+			.await?;
+		}
+		return Err(BlockWatcherError::processing_error(
+			format!("Failed to filter block {}: {}", failed_block, error),
+			None,
+			None,
+		));
+	}
 
 	if network.store_blocks.unwrap_or(false) {
 		if delete_blocks_before_save {
@@ -339,6 +430,83 @@ async fn process_block_batch<
 	})
 }
 
+/// Processes a batch block by block, skipping the blocks that fail permanently
+///
+/// Used once a batch has failed `max_attempts` times under a poison block policy, so that
+/// a single failing block does not block the watcher. A block failing transiently stops the
+/// batch, the blocks before it being checkpointed, and is retried on the next run.
+async fn process_batch_skipping_failures<
+	S: BlockStorage,
+	C: BlockChainClient,
+	H: Fn(BlockType, Network) -> BoxFuture<'static, ProcessedBlock> + Send + Sync + 'static,
+	T: Fn(&ProcessedBlock) -> tokio::task::JoinHandle<()> + Send + Sync + 'static,
+	TR: BlockTrackerTrait + Send + Sync + 'static,
+>(
+	context: &BatchProcessingContext<'_, S, H, T, TR>,
+	rpc_client: &C,
+	batch_start: u64,
+	batch_end: u64,
+	attempts: u32,
+	delete_blocks_before_save: bool,
+) -> Result<BatchProcessSummary, BlockWatcherError> {
+	let network = context.network;
+	let mut summary = BatchProcessSummary {
+		block_count: 0,
+		stream_kind: FetchStreamKind::Dense,
+	};
+	let mut delete_blocks_before_save = delete_blocks_before_save;
+
+	for block_number in batch_start..=batch_end {
+		let block_result = async {
+			let fetch_result = rpc_client
+				.get_blocks_with_meta(block_number, None)
+				.await
+				.with_context(|| format!("Failed to get block {}", block_number))?;
+
+			process_block_batch(
+				context,
+				fetch_result,
+				BatchProcessingOptions {
+					batch_start: block_number,
+					checkpoint_block: block_number,
+					delete_blocks_before_save,
+				},
+			)
+			.await
+		}
+		.await;
+
+		match block_result {
+			Ok(block_summary) => {
+				summary.block_count += block_summary.block_count;
+				summary.stream_kind = block_summary.stream_kind;
+				delete_blocks_before_save = false;
+			}
+			Err(error) if error.is_retryable() => return Err(error),
+			Err(error) => {
+				skip_block(
+					network,
+					context.block_storage,
+					context.failures,
+					block_number,
+					attempts,
+					error.to_string(),
+				)
+				.await?;
+				save_checkpoint(
+					context.block_storage,
+					network,
+					block_number,
+					context.latest_confirmed_block,
+				)
+				.await?;
+			}
+		}
+	}
+
+	Ok(summary)
+}
+
 /// Trait for job scheduler
 ///
 /// This trait is used to abstract the job scheduler implementation.
@@ -393,6 +561,10 @@ where
 	pub scheduler: J,
 	pub block_tracker: Arc<BlockTracker>,
 	pub run_lock: Arc<tokio::sync::Mutex<()>>,
+	pub activity: Arc<WatcherActivity>,
+	pub restarts: Arc<RestartTracker>,
+	pub failures: Arc<BlockFailures>,
+	pub latency: Arc<LatencyTracker>,
 }
 
 /// Map of active block watchers
@@ -417,6 +589,8 @@ where
 	pub trigger_handler: Arc<T>,
 	pub active_watchers: Arc<RwLock<BlockWatchersMap<S, H, T, J>>>,
 	pub block_tracker: Arc<BlockTracker>,
+	pub failures: Arc<BlockFailures>,
+	pub latency: Arc<LatencyTracker>,
 }
 
 impl<S, H, T, J> NetworkBlockWatcher<S, H, T, J>
@@ -454,6 +628,14 @@ where
 				)])),
 			)
 		})?;
+		let activity = Arc::new(WatcherActivity::default());
+		let stall_timeout = network
+			.watchdog
+			.as_ref()
+			.filter(|config| config.enabled)
+			.map(|config| Duration::from_millis(config.stall_timeout_ms));
+		activity.register(&network.slug, stall_timeout);
+		let restarts = Arc::new(RestartTracker::new(&network));
 		Ok(Self {
 			network,
 			block_storage,
@@ -462,9 +644,32 @@ where
 			scheduler,
 			block_tracker,
 			run_lock: Arc::new(tokio::sync::Mutex::new(())),
+			activity,
+			restarts,
+			failures: Arc::new(BlockFailures::default()),
+			latency: Arc::new(LatencyTracker::default()),
 		})
 	}
 
+	/// Sets the failures of the blocks, shared with the other watchers of the service
+	///
+	/// # Arguments
+	/// * `failures` - Failures of the blocks processed by the block watcher service
+	pub fn with_failures(mut self, failures: Arc<BlockFailures>) -> Self {
+		self.failures = failures;
+		self
+	}
+
+	/// Sets the tracker of the stage times of the blocks, shared with the other watchers of
+	/// the service
+	///
+	/// # Arguments
+	/// * `latency` - Stage times of the blocks processed by the block watcher service
+	pub fn with_latency_tracker(mut self, latency: Arc<LatencyTracker>) -> Self {
+		self.latency = latency;
+		self
+	}
+
 	/// Starts the network watcher
 	///
 	/// Initializes the scheduler and begins watching for new blocks according
@@ -483,6 +688,20 @@ where
 			}
 		}
 
+		// Start RPC consistency check job if enabled
+		if let Some(ref config) = self.network.rpc_consistency_check {
+			if config.enabled {
+				self.start_consistency_check_job().await?;
+			}
+		}
+
+		// Start watchdog job if enabled
+		if let Some(ref config) = self.network.watchdog {
+			if config.enabled {
+				self.start_watchdog_job().await?;
+			}
+		}
+
 		self.scheduler.start().await.map_err(|e| {
 			BlockWatcherError::scheduler_error(
 				e.to_string(),
@@ -499,6 +718,9 @@ where
 	}
 
 	/// Starts the main block watcher job
+	///
+	/// Each run is spawned as its own task and registered in the watcher activity, so the
+	/// watchdog can cancel a run that stalls while holding the run lock.
 	async fn start_main_watcher<C: BlockChainClient + Clone + Send + 'static>(
 		&mut self,
 		rpc_client: C,
@@ -509,6 +731,10 @@ where
 		let trigger_handler = self.trigger_handler.clone();
 		let block_tracker = self.block_tracker.clone();
 		let run_lock = self.run_lock.clone();
+		let activity = self.activity.clone();
+		let restarts = self.restarts.clone();
+		let failures = self.failures.clone();
+		let latency = self.latency.clone();
 
 		let job = Job::new_async(self.network.cron_schedule.as_str(), move |_uuid, _l| {
 			let network = network.clone();
@@ -518,27 +744,48 @@ where
 			let rpc_client = rpc_client.clone();
 			let trigger_handler = trigger_handler.clone();
 			let run_lock = run_lock.clone();
+			let activity = activity.clone();
+			let restarts = restarts.clone();
+			let failures = failures.clone();
+			let latency = latency.clone();
 			Box::pin(async move {
 				let _guard = run_lock.lock().await;
-				let _ = process_new_blocks(
-					&network,
-					&rpc_client,
-					block_storage,
-					block_handler,
-					trigger_handler,
-					block_tracker,
-				)
-				.await
-				.map_err(|e| {
-					BlockWatcherError::processing_error(
-						"Failed to process blocks".to_string(),
-						Some(e.into()),
-						Some(HashMap::from([(
-							"network".to_string(),
-							network.slug.clone(),
-						)])),
-					)
-				});
+				if !restarts.may_run() {
+					return;
+				}
+				let slug = network.slug.clone();
+				let run = spawn_network_task(
+					&slug,
+					"run",
+					network_scope(&slug, async move {
+						process_new_blocks(
+							&network,
+							&rpc_client,
+							block_storage,
+							block_handler,
+							trigger_handler,
+							block_tracker,
+							failures,
+							latency,
+						)
+						.await
+						.map_err(|e| {
+							BlockWatcherError::processing_error(
+								"Failed to process blocks".to_string(),
+								Some(e.into()),
+								Some(HashMap::from([(
+									"network".to_string(),
+									network.slug.clone(),
+								)])),
+							)
+						})
+						.is_ok()
+					}),
+				);
+				activity.run_started(run.abort_handle());
+				let result = run.await;
+				activity.run_finished();
+				restarts.record_task(result);
 			})
 		})
 		.with_context(|| "Failed to create main watcher job")?;
@@ -584,6 +831,8 @@ where
 		let trigger_handler = self.trigger_handler.clone();
 		let block_tracker = self.block_tracker.clone();
 		let run_lock = self.run_lock.clone();
+		let restarts = self.restarts.clone();
+		let failures = self.failures.clone();
 
 		let cron_schedule = recovery_config.cron_schedule.clone();
 		let job = Job::new_async(cron_schedule.as_str(), move |_uuid, _l| {
@@ -595,6 +844,8 @@ where
 			let rpc_client = rpc_client.clone();
 			let trigger_handler = trigger_handler.clone();
 			let run_lock = run_lock.clone();
+			let restarts = restarts.clone();
+			let failures = failures.clone();
 			Box::pin(async move {
 				let guard = run_lock.try_lock();
 				if guard.is_err() {
@@ -604,26 +855,44 @@ where
 					);
 					return;
 				}
-				let _ = process_missed_blocks(
-					&network,
-					&recovery_config,
-					&rpc_client,
-					block_storage,
-					block_handler,
-					trigger_handler,
-					block_tracker,
-				)
-				.await
-				.map_err(|e| {
-					BlockWatcherError::recovery_error(
-						"Failed to process missed blocks".to_string(),
-						Some(e.into()),
-						Some(HashMap::from([(
-							"network".to_string(),
-							network.slug.clone(),
-						)])),
-					)
-				});
+				if !restarts.may_run() {
+					tracing::debug!(
+						network = %network.slug,
+						"Skipping recovery run: block processing is suspended"
+					);
+					return;
+				}
+				let slug = network.slug.clone();
+				let recovery = spawn_network_task(
+					&slug,
+					"recovery",
+					network_scope(&slug, async move {
+						let _ = process_missed_blocks(
+							&network,
+							&recovery_config,
+							&rpc_client,
+							block_storage,
+							block_handler,
+							trigger_handler,
+							block_tracker,
+							failures,
+						)
+						.await
+						.map_err(|e| {
+							BlockWatcherError::recovery_error(
+								"Failed to process missed blocks".to_string(),
+								Some(e.into()),
+								Some(HashMap::from([(
+									"network".to_string(),
+									network.slug.clone(),
+								)])),
+							)
+						});
+					}),
+				);
+				if recovery.await.is_err_and(|e| e.is_panic()) {
+					restarts.record_panic();
+				}
 			})
 		})
 		.with_context(|| "Failed to create recovery job")?;
@@ -651,6 +920,105 @@ where
 		Ok(())
 	}
 
+	/// Starts the watchdog job
+	///
+	/// The job checks the watcher activity at a fraction of the stall timeout and cancels
+	/// a run exceeding it. The run lock is then released and the next scheduled run
+	/// resumes from the last checkpoint.
+	async fn start_watchdog_job(&mut self) -> Result<(), BlockWatcherError> {
+		let watchdog_config = self.network.watchdog.clone().ok_or_else(|| {
+			BlockWatcherError::scheduler_error(
+				"Watchdog config is required but not found".to_string(),
+				None,
+				Some(HashMap::from([(
+					"network".to_string(),
+					self.network.slug.clone(),
+				)])),
+			)
+		})?;
+
+		let slug = self.network.slug.clone();
+		let activity = self.activity.clone();
+		let stall_timeout = Duration::from_millis(watchdog_config.stall_timeout_ms);
+		let check_interval = (stall_timeout / 4).max(Duration::from_secs(1));
+
+		let job = Job::new_repeated_async(check_interval, move |_uuid, _l| {
+			let slug = slug.clone();
+			let activity = activity.clone();
+			Box::pin(async move {
+				activity.cancel_if_stalled(&slug, stall_timeout);
+			})
+		})
+		.with_context(|| "Failed to create watchdog job")?;
+
+		self.scheduler.add(job).await.map_err(|e| {
+			BlockWatcherError::scheduler_error(
+				e.to_string(),
+				Some(e),
+				Some(HashMap::from([(
+					"network".to_string(),
+					self.network.slug.clone(),
+				)])),
+			)
+		})?;
+
+		tracing::info!(
+			"Started watchdog for network: {} with stall timeout: {:?}",
+			self.network.slug,
+			stall_timeout
+		);
+		Ok(())
+	}
+
+	/// Starts the RPC consistency check job
+	///
+	/// The job queries every endpoint directly rather than through the rotating client, so
+	/// it does not need the network's RPC client.
+	async fn start_consistency_check_job(&mut self) -> Result<(), BlockWatcherError> {
+		let consistency_config = self.network.rpc_consistency_check.clone().ok_or_else(|| {
+			BlockWatcherError::scheduler_error(
+				"RPC consistency check config is required but not found".to_string(),
+				None,
+				Some(HashMap::from([(
+					"network".to_string(),
+					self.network.slug.clone(),
+				)])),
+			)
+		})?;
+
+		let network = self.network.clone();
+		let client = reqwest::Client::new();
+
+		let cron_schedule = consistency_config.cron_schedule.clone();
+		let job = Job::new_async(cron_schedule.as_str(), move |_uuid, _l| {
+			let network = network.clone();
+			let consistency_config = consistency_config.clone();
+			let client = client.clone();
+			Box::pin(async move {
+				check_block_hash_consistency(&network, &consistency_config, &client).await;
+			})
+		})
+		.with_context(|| "Failed to create RPC consistency check job")?;
+
+		self.scheduler.add(job).await.map_err(|e| {
+			BlockWatcherError::scheduler_error(
+				e.to_string(),
+				Some(e),
+				Some(HashMap::from([(
+					"network".to_string(),
+					self.network.slug.clone(),
+				)])),
+			)
+		})?;
+
+		tracing::info!(
+			"Started RPC consistency check job for network: {} with schedule: {}",
+			self.network.slug,
+			cron_schedule
+		);
+		Ok(())
+	}
+
 	/// Stops the network watcher
 	///
 	/// Shuts down the scheduler and stops watching for new blocks.
@@ -696,9 +1064,30 @@ where
 			trigger_handler,
 			active_watchers: Arc::new(RwLock::new(HashMap::new())),
 			block_tracker,
+			failures: Arc::new(BlockFailures::default()),
+			latency: Arc::new(LatencyTracker::default()),
 		})
 	}
 
+	/// Sets the failures of the blocks processed by the watchers of the service
+	///
+	/// # Arguments
+	/// * `failures` - Failures of the blocks
+	pub fn with_failures(mut self, failures: Arc<BlockFailures>) -> Self {
+		self.failures = failures;
+		self
+	}
+
+	/// Sets the tracker of the stage times of the blocks processed by the watchers of the
+	/// service
+	///
+	/// # Arguments
+	/// * `latency` - Stage times of the blocks, shared with the block and trigger handlers
+	pub fn with_latency_tracker(mut self, latency: Arc<LatencyTracker>) -> Self {
+		self.latency = latency;
+		self
+	}
+
 	/// Starts a watcher for a specific network
 	///
 	/// # Arguments
@@ -726,7 +1115,9 @@ where
 			self.trigger_handler.clone(),
 			self.block_tracker.clone(),
 		)
-		.await?;
+		.await?
+		.with_failures(self.failures.clone())
+		.with_latency_tracker(self.latency.clone());
 
 		watcher.start(rpc_client).await?;
 		watchers.insert(network.slug.clone(), watcher);
@@ -758,6 +1149,9 @@ where
 /// * `block_handler` - Handler function for processed blocks
 /// * `trigger_handler` - Handler function for processed blocks
 /// * `block_tracker` - Tracker implementation for block processing
+/// * `failures` - Failures of the blocks, recorded by the block handler and counted towards
+///   the poison block policy
+/// * `latency` - Stage times of the blocks, in which the receipt of the blocks is recorded
 ///
 /// # Returns
 /// * `Result<(), BlockWatcherError>` - Success or error
@@ -775,6 +1169,8 @@ pub async fn process_new_blocks<
 	block_handler: Arc<H>,
 	trigger_handler: Arc<T>,
 	block_tracker: Arc<TR>,
+	failures: Arc<BlockFailures>,
+	latency: Arc<LatencyTracker>,
 ) -> Result<(), BlockWatcherError> {
 	let start_time = std::time::Instant::now();
 
@@ -797,10 +1193,60 @@ pub async fn process_new_blocks<
 		.max_past_blocks
 		.unwrap_or(MaxPastBlocks::Limited(recommended_past_blocks));
 
+	// A cold start begins at the configured start block, if any, without clamping.
+	let cold_start_block = match network.start_block {
+		Some(start_block) if last_processed_block == 0 => Some(start_block),
+		_ => None,
+	};
+	if let Some(cold_start_block) = cold_start_block {
+		if cold_start_block > latest_confirmed_block {
+			tracing::info!(
+				network = %network.slug,
+				"Waiting for start block {} (latest confirmed block: {})",
+				cold_start_block,
+				latest_confirmed_block
+			);
+			return Ok(());
+		}
+	}
+
+	// A cold start catches up to the latest confirmed block at the time without clamping. The
+	// target is persisted, so that a catch-up interrupted by a failed run or a restart resumes
+	// from the last processed block instead of clamping to max_past_blocks.
+	let resuming_cold_start = if cold_start_block.is_some() {
+		block_storage
+			.save_cold_start_target(&network.slug, Some(latest_confirmed_block))
+			.await
+			.with_context(|| "Failed to save cold start target")?;
+		false
+	} else if network.start_block.is_some() && last_processed_block != 0 {
+		let cold_start_target = block_storage
+			.get_cold_start_target(&network.slug)
+			.await
+			.with_context(|| "Failed to get cold start target")?;
+		match cold_start_target {
+			Some(target) if last_processed_block < target => true,
+			Some(_) => {
+				block_storage
+					.save_cold_start_target(&network.slug, None)
+					.await
+					.with_context(|| "Failed to clear cold start target")?;
+				false
+			}
+			None => false,
+		}
+	} else {
+		false
+	};
+
 	// Calculate the start block number, using the default if max_past_blocks is not set.
 	// Unlimited networks never clamp: they always resume from the last processed block.
-	let start_block = if last_processed_block == 0 {
+	let start_block = if let Some(cold_start_block) = cold_start_block {
+		cold_start_block
+	} else if last_processed_block == 0 {
 		latest_confirmed_block
+	} else if resuming_cold_start {
+		last_processed_block.saturating_add(1)
 	} else {
 		match max_past_blocks {
 			MaxPastBlocks::Limited(max) => std::cmp::max(
@@ -811,7 +1257,7 @@ pub async fn process_new_blocks<
 		}
 	};
 	let skipped_blocks = match max_past_blocks {
-		MaxPastBlocks::Limited(_) if last_processed_block != 0 => {
+		MaxPastBlocks::Limited(_) if last_processed_block != 0 && !resuming_cold_start => {
 			let next_block = last_processed_block.saturating_add(1);
 			(start_block > next_block).then_some(start_block - next_block)
 		}
@@ -841,10 +1287,12 @@ pub async fn process_new_blocks<
 		block_handler: &block_handler,
 		trigger_handler: &trigger_handler,
 		block_tracker: &block_tracker,
+		failures: &failures,
+		latency: &latency,
 		latest_confirmed_block,
 	};
 
-	if last_processed_block == 0 {
+	if last_processed_block == 0 && cold_start_block.is_none() {
 		let fetch_result = rpc_client
 			.get_blocks_with_meta(latest_confirmed_block, None)
 			.await
@@ -862,7 +1310,7 @@ pub async fn process_new_blocks<
 		total_blocks_processed += summary.block_count;
 		stream_kind = summary.stream_kind;
 		checkpoint_block = latest_confirmed_block;
-	} else if last_processed_block < latest_confirmed_block {
+	} else if cold_start_block.is_some() || last_processed_block < latest_confirmed_block {
 		// The per-network run_lock serializes cron ticks while long catch-ups are still running.
 		let mut batch_start = start_block;
 		let mut batches_processed_this_tick = 0;
@@ -908,8 +1356,48 @@ pub async fn process_new_blocks<
 			}
 			.await;
 
+			let batch_result = match batch_result {
+				Err(error) => {
+					let error_class = error.classification();
+					tracing::error!(
+						network = %network.slug,
+						batch_start,
+						batch_end,
+						error = %error,
+						error_class = %error_class,
+						"Failed to process block batch"
+					);
+					// Under a poison block policy, a batch failing permanently and repeatedly is
+					// processed block by block and the failing blocks are skipped. Transient
+					// and rate-limited failures are not counted, the batch being left
+					// unprocessed for the next run.
+					match network.poison_block_policy.as_ref().filter(|p| p.enabled) {
+						Some(policy) if !error.is_retryable() => {
+							let attempts =
+								failures.record_batch_failure(&network.slug, batch_start);
+							if attempts >= policy.max_attempts {
+								process_batch_skipping_failures(
+									&batch_context,
+									rpc_client,
+									batch_start,
+									batch_end,
+									attempts,
+									network.store_blocks.unwrap_or(false) && !blocks_deleted,
+								)
+								.await
+							} else {
+								Err(error)
+							}
+						}
+						_ => Err(error),
+					}
+				}
+				ok => ok,
+			};
+
 			match batch_result {
 				Ok(summary) => {
+					failures.clear_batch_failures(&network.slug);
 					total_blocks_processed += summary.block_count;
 					stream_kind = summary.stream_kind;
 					checkpoint_block = batch_end;
@@ -917,13 +1405,6 @@ pub async fn process_new_blocks<
 					batches_processed_this_tick += 1;
 				}
 				Err(error) => {
-					tracing::error!(
-						network = %network.slug,
-						batch_start,
-						batch_end,
-						error = %error,
-						"Failed to process block batch"
-					);
 					log_checkpoint_lag(network, checkpoint_block, latest_confirmed_block);
 					return Err(error);
 				}
@@ -986,8 +1467,9 @@ pub async fn process_new_blocks<
 #[cfg(test)]
 mod tests {
 	use super::*;
-	use crate::models::{BlockChainType, BlockRecoveryConfig};
+	use crate::models::{BlockChainType, BlockRecoveryConfig, PoisonBlockPolicy};
 	use crate::services::blockwatcher::storage::FileBlockStorage;
+	use crate::utils::logging::error::ErrorClass;
 	use crate::utils::tests::network::NetworkBuilder;
 	use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
 	use tempfile::tempdir;
@@ -1047,6 +1529,8 @@ mod tests {
 		fail_get_blocks: Arc<AtomicBool>,
 		fail_on_call: Arc<AtomicUsize>,
 		call_count: Arc<AtomicUsize>,
+		poison_block: Option<u64>,
+		transient_failure: bool,
 	}
 
 	impl MockRpcClient {
@@ -1057,9 +1541,21 @@ mod tests {
 				fail_get_blocks: Arc::new(AtomicBool::new(false)),
 				fail_on_call: Arc::new(AtomicUsize::new(0)),
 				call_count: Arc::new(AtomicUsize::new(0)),
+				poison_block: None,
+				transient_failure: false,
 			}
 		}
 
+		fn with_transient_failure(mut self) -> Self {
+			self.transient_failure = true;
+			self
+		}
+
+		fn with_poison_block(mut self, block_number: u64) -> Self {
+			self.poison_block = Some(block_number);
+			self
+		}
+
 		fn with_blocks(self, blocks: Vec<BlockType>) -> Self {
 			*self.blocks_to_return.lock().unwrap() = blocks;
 			self
@@ -1095,6 +1591,23 @@ mod tests {
 				return Err(anyhow::anyhow!("Simulated RPC failure"));
 			}
 
+			if self.transient_failure {
+				return Err(crate::services::blockchain::TransportError::network(
+					"Connection reset",
+					None,
+					None,
+				)
+				.into());
+			}
+
+			let end_block = end.unwrap_or(start);
+			if self
+				.poison_block
+				.is_some_and(|poison| (start..=end_block).contains(&poison))
+			{
+				return Err(anyhow::anyhow!("Malformed block"));
+			}
+
 			let fail_on_call = self.fail_on_call.load(Ordering::SeqCst);
 			if fail_on_call != 0 && call_number >= fail_on_call {
 				return Err(anyhow::anyhow!("Simulated RPC failure"));
@@ -1106,7 +1619,6 @@ mod tests {
 			}
 
 			// Generate mock blocks for the requested range
-			let end_block = end.unwrap_or(start);
 			let mut result = Vec::new();
 			for block_num in start..=end_block {
 				result.push(create_evm_block(block_num));
@@ -1164,6 +1676,8 @@ mod tests {
 			block_handler,
 			trigger_handler,
 			block_tracker,
+			Arc::new(BlockFailures::default()),
+			Arc::new(LatencyTracker::default()),
 		)
 		.await;
 
@@ -1177,6 +1691,75 @@ mod tests {
 		assert_eq!(last_processed, Some(88));
 	}
 
+	#[tokio::test]
+	async fn test_process_new_blocks_first_run_from_start_block() {
+		let temp_dir = tempdir().unwrap();
+		let storage = Arc::new(FileBlockStorage::new(temp_dir.path().to_path_buf()));
+		let mut network = create_test_network();
+		network.max_past_blocks = Some(MaxPastBlocks::Limited(10));
+		network.start_block = Some(20);
+		let rpc_client = MockRpcClient::new(100);
+		let block_tracker = Arc::new(BlockTracker::new(100));
+		let block_handler = create_block_handler();
+		let counter = Arc::new(AtomicUsize::new(0));
+		let trigger_handler = create_counting_trigger_handler(counter.clone());
+
+		let result = process_new_blocks(
+			&network,
+			&rpc_client,
+			storage.clone(),
+			block_handler,
+			trigger_handler,
+			block_tracker,
+			Arc::new(BlockFailures::default()),
+			Arc::new(LatencyTracker::default()),
+		)
+		.await;
+
+		assert!(result.is_ok());
+		tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+
+		// Blocks 20 to 88 are processed despite max_past_blocks
+		assert_eq!(counter.load(Ordering::SeqCst), 69);
+		let last_processed = storage
+			.get_last_processed_block("test_network")
+			.await
+			.unwrap();
+		assert_eq!(last_processed, Some(88));
+	}
+
+	#[tokio::test]
+	async fn test_process_new_blocks_waits_for_start_block() {
+		let temp_dir = tempdir().unwrap();
+		let storage = Arc::new(FileBlockStorage::new(temp_dir.path().to_path_buf()));
+		let mut network = create_test_network();
+		network.start_block = Some(95);
+		let rpc_client = MockRpcClient::new(100);
+		let block_tracker = Arc::new(BlockTracker::new(100));
+		let block_handler = create_block_handler();
+		let trigger_handler = create_trigger_handler();
+
+		let result = process_new_blocks(
+			&network,
+			&rpc_client,
+			storage.clone(),
+			block_handler,
+			trigger_handler,
+			block_tracker,
+			Arc::new(BlockFailures::default()),
+			Arc::new(LatencyTracker::default()),
+		)
+		.await;
+
+		assert!(result.is_ok());
+		// Nothing is processed until the start block is confirmed
+		let last_processed = storage
+			.get_last_processed_block("test_network")
+			.await
+			.unwrap();
+		assert_eq!(last_processed, None);
+	}
+
 	#[tokio::test]
 	async fn test_process_new_blocks_subsequent_run() {
 		let temp_dir = tempdir().unwrap();
@@ -1201,6 +1784,8 @@ mod tests {
 			block_handler,
 			trigger_handler,
 			block_tracker,
+			Arc::new(BlockFailures::default()),
+			Arc::new(LatencyTracker::default()),
 		)
 		.await;
 
@@ -1234,6 +1819,8 @@ mod tests {
 			block_handler,
 			trigger_handler,
 			block_tracker,
+			Arc::new(BlockFailures::default()),
+			Arc::new(LatencyTracker::default()),
 		)
 		.await;
 
@@ -1268,6 +1855,8 @@ mod tests {
 			block_handler,
 			trigger_handler,
 			block_tracker,
+			Arc::new(BlockFailures::default()),
+			Arc::new(LatencyTracker::default()),
 		)
 		.await;
 
@@ -1308,6 +1897,8 @@ mod tests {
 			block_handler,
 			trigger_handler,
 			block_tracker,
+			Arc::new(BlockFailures::default()),
+			Arc::new(LatencyTracker::default()),
 		)
 		.await;
 
@@ -1354,6 +1945,8 @@ mod tests {
 			block_handler,
 			trigger_handler,
 			block_tracker,
+			Arc::new(BlockFailures::default()),
+			Arc::new(LatencyTracker::default()),
 		)
 		.await;
 
@@ -1401,6 +1994,8 @@ mod tests {
 			block_handler.clone(),
 			trigger_handler.clone(),
 			block_tracker.clone(),
+			Arc::new(BlockFailures::default()),
+			Arc::new(LatencyTracker::default()),
 		)
 		.await;
 
@@ -1424,6 +2019,8 @@ mod tests {
 			block_handler,
 			trigger_handler,
 			block_tracker,
+			Arc::new(BlockFailures::default()),
+			Arc::new(LatencyTracker::default()),
 		)
 		.await;
 
@@ -1475,6 +2072,8 @@ mod tests {
 			block_handler,
 			trigger_handler,
 			block_tracker,
+			Arc::new(BlockFailures::default()),
+			Arc::new(LatencyTracker::default()),
 		)
 		.await;
 
@@ -1489,6 +2088,358 @@ mod tests {
 		assert_eq!(rpc_client.call_count.load(Ordering::SeqCst), 3);
 	}
 
+	#[tokio::test]
+	async fn test_process_new_blocks_resumes_interrupted_cold_start() {
+		let temp_dir = tempdir().unwrap();
+		let storage = Arc::new(FileBlockStorage::new(temp_dir.path().to_path_buf()));
+
+		let mut network = create_test_network();
+		network.start_block = Some(10);
+		network.store_blocks = Some(false);
+
+		// The cold start fails after two batches
+		let rpc_client = MockRpcClient::new(1012).with_failing_get_blocks_after(2);
+		let block_tracker = Arc::new(BlockTracker::new(2000));
+		let result = process_new_blocks(
+			&network,
+			&rpc_client,
+			storage.clone(),
+			create_block_handler(),
+			create_trigger_handler(),
+			block_tracker.clone(),
+			Arc::new(BlockFailures::default()),
+			Arc::new(LatencyTracker::default()),
+		)
+		.await;
+
+		assert!(result.is_err());
+		assert_eq!(
+			storage
+				.get_last_processed_block("test_network")
+				.await
+				.unwrap(),
+			Some(69)
+		);
+		assert_eq!(
+			storage.get_cold_start_target("test_network").await.unwrap(),
+			Some(1000)
+		);
+
+		// The catch-up resumes from the last processed block instead of clamping to
+		// max_past_blocks
+		let trigger_count = Arc::new(AtomicUsize::new(0));
+		let result = process_new_blocks(
+			&network,
+			&MockRpcClient::new(1012),
+			storage.clone(),
+			create_block_handler(),
+			create_counting_trigger_handler(trigger_count.clone()),
+			block_tracker.clone(),
+			Arc::new(BlockFailures::default()),
+			Arc::new(LatencyTracker::default()),
+		)
+		.await;
+
+		assert!(result.is_ok());
+		assert_eq!(
+			storage
+				.get_last_processed_block("test_network")
+				.await
+				.unwrap(),
+			Some(1000)
+		);
+		tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+		assert_eq!(trigger_count.load(Ordering::SeqCst), 931);
+
+		// Once caught up, the target is cleared and the watcher clamps again
+		let result = process_new_blocks(
+			&network,
+			&MockRpcClient::new(1012),
+			storage.clone(),
+			create_block_handler(),
+			create_trigger_handler(),
+			block_tracker,
+			Arc::new(BlockFailures::default()),
+			Arc::new(LatencyTracker::default()),
+		)
+		.await;
+
+		assert!(result.is_ok());
+		assert_eq!(
+			storage.get_cold_start_target("test_network").await.unwrap(),
+			None
+		);
+	}
+
+	#[tokio::test]
+	async fn test_process_new_blocks_skips_poison_block() {
+		let temp_dir = tempdir().unwrap();
+		let storage = Arc::new(FileBlockStorage::new(temp_dir.path().to_path_buf()));
+		let mut network = create_test_network();
+		network.slug = "poison_network".to_string();
+		network.store_blocks = Some(false);
+		network.poison_block_policy = Some(PoisonBlockPolicy {
+			enabled: true,
+			max_attempts: 2,
+			on_block_skipped: Vec::new(),
+		});
+
+		storage
+			.save_last_processed_block("poison_network", 100)
+			.await
+			.unwrap();
+
+		let rpc_client = MockRpcClient::new(142).with_poison_block(110);
+		let trigger_count = Arc::new(AtomicUsize::new(0));
+		let failures = Arc::new(BlockFailures::default());
+		let run = || {
+			process_new_blocks(
+				&network,
+				&rpc_client,
+				storage.clone(),
+				create_block_handler(),
+				create_counting_trigger_handler(trigger_count.clone()),
+				Arc::new(BlockTracker::new(1000)),
+				failures.clone(),
+				Arc::new(LatencyTracker::default()),
+			)
+		};
+
+		// The first failure is retried on the next run
+		assert!(run().await.is_err());
+		assert_eq!(
+			storage
+				.get_last_processed_block("poison_network")
+				.await
+				.unwrap(),
+			Some(100)
+		);
+		assert!(storage
+			.get_skipped_blocks("poison_network")
+			.await
+			.unwrap()
+			.is_empty());
+
+		// Once max_attempts is reached, the poison block is skipped
+		run().await.unwrap();
+		tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+
+		assert_eq!(
+			storage
+				.get_last_processed_block("poison_network")
+				.await
+				.unwrap(),
+			Some(130)
+		);
+		assert_eq!(trigger_count.load(Ordering::SeqCst), 29);
+		let skipped = storage.get_skipped_blocks("poison_network").await.unwrap();
+		assert_eq!(skipped.len(), 1);
+		assert_eq!(skipped[0].block_number, 110);
+		assert_eq!(skipped[0].attempts, 2);
+	}
+
+	#[tokio::test]
+	async fn test_process_new_blocks_retries_blocks_failing_transiently() {
+		let temp_dir = tempdir().unwrap();
+		let storage = Arc::new(FileBlockStorage::new(temp_dir.path().to_path_buf()));
+		let mut network = create_test_network();
+		network.slug = "transient_network".to_string();
+		network.store_blocks = Some(false);
+		network.poison_block_policy = Some(PoisonBlockPolicy {
+			enabled: true,
+			max_attempts: 2,
+			on_block_skipped: Vec::new(),
+		});
+
+		storage
+			.save_last_processed_block("transient_network", 100)
+			.await
+			.unwrap();
+
+		let rpc_client = MockRpcClient::new(142).with_transient_failure();
+		let failures = Arc::new(BlockFailures::default());
+		let run = || {
+			process_new_blocks(
+				&network,
+				&rpc_client,
+				storage.clone(),
+				create_block_handler(),
+				create_counting_trigger_handler(Arc::new(AtomicUsize::new(0))),
+				Arc::new(BlockTracker::new(1000)),
+				failures.clone(),
+				Arc::new(LatencyTracker::default()),
+			)
+		};
+
+		// Transient failures do not count towards max_attempts: an outage spanning more
+		// runs than max_attempts leaves the blocks unprocessed instead of skipping them
+		for _ in 0..3 {
+			let result = run().await;
+			assert_eq!(result.unwrap_err().classification(), ErrorClass::Transient);
+		}
+		assert!(storage
+			.get_skipped_blocks("transient_network")
+			.await
+			.unwrap()
+			.is_empty());
+		assert_eq!(
+			storage
+				.get_last_processed_block("transient_network")
+				.await
+				.unwrap(),
+			Some(100)
+		);
+	}
+
+	#[tokio::test]
+	async fn test_process_new_blocks_stops_before_block_failing_filtering() {
+		let temp_dir = tempdir().unwrap();
+		let storage = Arc::new(FileBlockStorage::new(temp_dir.path().to_path_buf()));
+		let mut network = create_test_network();
+		network.slug = "filter_failure_network".to_string();
+		network.store_blocks = Some(false);
+
+		storage
+			.save_last_processed_block("filter_failure_network", 100)
+			.await
+			.unwrap();
+
+		// The block handler records the failures in the failures of the watcher
+		let failures = Arc::new(BlockFailures::default());
+		let block_handler = Arc::new({
+			let failures = failures.clone();
+			move |block: BlockType, network: Network| {
+				let failures = failures.clone();
+				Box::pin(async move {
+					let block_number = block.number().unwrap_or(0);
+					if block_number == 110 {
+						failures.record_filter_failure(
+							&network.slug,
+							block_number,
+							"Failed to get events".to_string(),
+						);
+					}
+					ProcessedBlock {
+						network_slug: network.slug,
+						block_number,
+						processing_results: vec![],
+					}
+				}) as BoxFuture<'static, ProcessedBlock>
+			}
+		});
+		let trigger_count = Arc::new(AtomicUsize::new(0));
+
+		let result = process_new_blocks(
+			&network,
+			&MockRpcClient::new(142),
+			storage.clone(),
+			block_handler,
+			create_counting_trigger_handler(trigger_count.clone()),
+			Arc::new(BlockTracker::new(1000)),
+			failures.clone(),
+			Arc::new(LatencyTracker::default()),
+		)
+		.await;
+		tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+
+		assert!(result
+			.unwrap_err()
+			.to_string()
+			.contains("Failed to filter block 110"));
+		// The blocks before the failing block are handed over and checkpointed
+		assert_eq!(trigger_count.load(Ordering::SeqCst), 9);
+		assert_eq!(
+			storage
+				.get_last_processed_block("filter_failure_network")
+				.await
+				.unwrap(),
+			Some(109)
+		);
+		assert!(!failures.filter_failed("filter_failure_network", 110));
+	}
+
+	#[tokio::test]
+	async fn test_aborted_run_aborts_pipeline_stages() {
+		struct DropFlag(Arc<AtomicBool>);
+
+		impl Drop for DropFlag {
+			fn drop(&mut self) {
+				self.0.store(true, Ordering::SeqCst);
+			}
+		}
+
+		let temp_dir = tempdir().unwrap();
+		let storage = Arc::new(FileBlockStorage::new(temp_dir.path().to_path_buf()));
+		let mut network = create_test_network();
+		network.slug = "aborted_run_network".to_string();
+		network.store_blocks = Some(false);
+
+		storage
+			.save_last_processed_block("aborted_run_network", 100)
+			.await
+			.unwrap();
+
+		// The filtering of block 105 stalls until the run is aborted
+		let stalled = Arc::new(tokio::sync::Notify::new());
+		let dropped = Arc::new(AtomicBool::new(false));
+		let block_handler = Arc::new({
+			let stalled = stalled.clone();
+			let dropped = dropped.clone();
+			move |block: BlockType, network: Network| {
+				let stalled = stalled.clone();
+				let dropped = dropped.clone();
+				Box::pin(async move {
+					let block_number = block.number().unwrap_or(0);
+					if block_number == 105 {
+						let _flag = DropFlag(dropped);
+						stalled.notify_one();
+						futures::future::pending::<()>().await;
+					}
+					ProcessedBlock {
+						network_slug: network.slug,
+						block_number,
+						processing_results: vec![],
+					}
+				}) as BoxFuture<'static, ProcessedBlock>
+			}
+		});
+
+		let run = tokio::spawn(async move {
+			process_new_blocks(
+				&network,
+				&MockRpcClient::new(142),
+				storage,
+				block_handler,
+				create_trigger_handler(),
+				Arc::new(BlockTracker::new(1000)),
+				Arc::new(BlockFailures::default()),
+				Arc::new(LatencyTracker::default()),
+			)
+			.await
+		});
+		stalled.notified().await;
+
+		// As done by the watchdog
+		run.abort();
+		assert!(run.await.unwrap_err().is_cancelled());
+
+		// No stage of the run survives it
+		let stages_running = || {
+			["filter", "dispatch"].iter().any(|kind| {
+				crate::utils::metrics::NETWORK_TASKS
+					.with_label_values(&["aborted_run_network", kind])
+					.get() != 0.0
+			})
+		};
+		tokio::time::timeout(Duration::from_secs(1), async {
+			while !dropped.load(Ordering::SeqCst) || stages_running() {
+				tokio::task::yield_now().await;
+			}
+		})
+		.await
+		.expect("The stages of the run should be aborted with it");
+	}
+
 	#[tokio::test]
 	async fn test_process_new_blocks_detects_missed_blocks() {
 		let temp_dir = tempdir().unwrap();
@@ -1516,6 +2467,8 @@ mod tests {
 			block_handler,
 			trigger_handler,
 			block_tracker,
+			Arc::new(BlockFailures::default()),
+			Arc::new(LatencyTracker::default()),
 		)
 		.await;
 
@@ -1554,6 +2507,8 @@ mod tests {
 			block_handler,
 			trigger_handler,
 			block_tracker,
+			Arc::new(BlockFailures::default()),
+			Arc::new(LatencyTracker::default()),
 		)
 		.await;
 
@@ -1585,6 +2540,8 @@ mod tests {
 			block_handler,
 			trigger_handler,
 			block_tracker,
+			Arc::new(BlockFailures::default()),
+			Arc::new(LatencyTracker::default()),
 		)
 		.await;
 
@@ -1628,6 +2585,8 @@ mod tests {
 			block_handler,
 			trigger_handler,
 			block_tracker,
+			Arc::new(BlockFailures::default()),
+			Arc::new(LatencyTracker::default()),
 		)
 		.await;
 
@@ -1665,6 +2624,8 @@ mod tests {
 			block_handler,
 			trigger_handler,
 			block_tracker,
+			Arc::new(BlockFailures::default()),
+			Arc::new(LatencyTracker::default()),
 		)
 		.await;
 
@@ -1700,6 +2661,8 @@ mod tests {
 			block_handler,
 			trigger_handler,
 			block_tracker,
+			Arc::new(BlockFailures::default()),
+			Arc::new(LatencyTracker::default()),
 		)
 		.await;
 
@@ -1854,6 +2817,8 @@ mod tests {
 			block_handler,
 			trigger_handler,
 			block_tracker,
+			Arc::new(BlockFailures::default()),
+			Arc::new(LatencyTracker::default()),
 		)
 		.await;
 
@@ -1897,6 +2862,8 @@ mod tests {
 			block_handler,
 			trigger_handler,
 			block_tracker,
+			Arc::new(BlockFailures::default()),
+			Arc::new(LatencyTracker::default()),
 		)
 		.await;
 
@@ -1941,6 +2908,8 @@ mod tests {
 			block_handler,
 			trigger_handler,
 			block_tracker,
+			Arc::new(BlockFailures::default()),
+			Arc::new(LatencyTracker::default()),
 		)
 		.await;
 
@@ -1984,6 +2953,8 @@ mod tests {
 			block_handler,
 			trigger_handler,
 			block_tracker,
+			Arc::new(BlockFailures::default()),
+			Arc::new(LatencyTracker::default()),
 		)
 		.await;
 
@@ -2031,6 +3002,8 @@ mod tests {
 			block_handler,
 			trigger_handler,
 			block_tracker,
+			Arc::new(BlockFailures::default()),
+			Arc::new(LatencyTracker::default()),
 		)
 		.await;
 
@@ -2092,6 +3065,8 @@ mod tests {
 			block_handler,
 			trigger_handler,
 			block_tracker,
+			Arc::new(BlockFailures::default()),
+			Arc::new(LatencyTracker::default()),
 		)
 		.await;
 
@@ -2139,6 +3114,8 @@ mod tests {
 			block_handler,
 			trigger_handler,
 			block_tracker,
+			Arc::new(BlockFailures::default()),
+			Arc::new(LatencyTracker::default()),
 		)
 		.await;
 
@@ -2254,6 +3231,8 @@ mod tests {
 			block_handler,
 			trigger_handler,
 			block_tracker,
+			Arc::new(BlockFailures::default()),
+			Arc::new(LatencyTracker::default()),
 		)
 		.await;
 
@@ -2302,6 +3281,8 @@ mod tests {
 			block_handler,
 			trigger_handler,
 			block_tracker,
+			Arc::new(BlockFailures::default()),
+			Arc::new(LatencyTracker::default()),
 		)
 		.await;
 
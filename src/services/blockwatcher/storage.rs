@@ -6,6 +6,7 @@
 //! - Last processed block tracking
 //! - Block deletion for cleanup
 //! - Missed block tracking and recovery
+//! - Skip list of poison blocks
 
 use async_trait::async_trait;
 use chrono::Utc;
@@ -59,6 +60,31 @@ impl MissedBlockEntry {
 	}
 }
 
+/// Entry of the skip list, recording a block skipped after repeated failures
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SkippedBlockEntry {
+	/// The block number that was skipped
+	pub block_number: u64,
+	/// Unix timestamp (seconds) when the block was skipped
+	pub skipped_at: i64,
+	/// Number of failed attempts before the block was skipped
+	pub attempts: u32,
+	/// Error message of the last failed attempt
+	pub error: String,
+}
+
+impl SkippedBlockEntry {
+	/// Creates a new skipped block entry
+	pub fn new(block_number: u64, attempts: u32, error: String) -> Self {
+		Self {
+			block_number,
+			skipped_at: Utc::now().timestamp(),
+			attempts,
+			error,
+		}
+	}
+}
+
 /// Interface for block storage implementations
 ///
 /// Defines the required functionality for storing and retrieving blocks
@@ -91,6 +117,29 @@ pub trait BlockStorage: Clone + Send + Sync {
 		block: u64,
 	) -> Result<(), anyhow::Error>;
 
+	/// Retrieves the block a cold start catches up to, while the catch-up is in progress
+	///
+	/// # Arguments
+	/// * `network_id` - Unique identifier for the network
+	///
+	/// # Returns
+	/// * `Result<Option<u64>, anyhow::Error>` - Target block or None if not catching up
+	async fn get_cold_start_target(&self, network_id: &str) -> Result<Option<u64>, anyhow::Error>;
+
+	/// Saves the block a cold start catches up to, or clears it once caught up
+	///
+	/// # Arguments
+	/// * `network_id` - Unique identifier for the network
+	/// * `block` - Target block, None to clear it
+	///
+	/// # Returns
+	/// * `Result<(), anyhow::Error>` - Success or error
+	async fn save_cold_start_target(
+		&self,
+		network_id: &str,
+		block: Option<u64>,
+	) -> Result<(), anyhow::Error>;
+
 	/// Saves a collection of blocks for a network
 	///
 	/// # Arguments
@@ -196,8 +245,51 @@ pub trait BlockStorage: Clone + Send + Sync {
 		max_block_age: u64,
 		current_block: u64,
 	) -> Result<usize, anyhow::Error>;
+
+	/// Adds a block to the skip list, replacing any previous entry for the same block
+	///
+	/// # Arguments
+	/// * `network_id` - Unique identifier for the network
+	/// * `entry` - The skipped block
+	///
+	/// # Returns
+	/// * `Result<(), anyhow::Error>` - Success or error
+	async fn save_skipped_block(
+		&self,
+		network_id: &str,
+		entry: SkippedBlockEntry,
+	) -> Result<(), anyhow::Error>;
+
+	/// Retrieves the skip list of a network
+	///
+	/// # Arguments
+	/// * `network_id` - Unique identifier for the network
+	///
+	/// # Returns
+	/// * `Result<Vec<SkippedBlockEntry>, anyhow::Error>` - Skipped blocks sorted by block number
+	async fn get_skipped_blocks(
+		&self,
+		network_id: &str,
+	) -> Result<Vec<SkippedBlockEntry>, anyhow::Error>;
+
+	/// Removes blocks from the skip list
+	///
+	/// # Arguments
+	/// * `network_id` - Unique identifier for the network
+	/// * `block_numbers` - Block numbers to remove
+	///
+	/// # Returns
+	/// * `Result<(), anyhow::Error>` - Success or error
+	async fn remove_skipped_blocks(
+		&self,
+		network_id: &str,
+		block_numbers: &[u64],
+	) -> Result<(), anyhow::Error>;
 }
 
+/// Default directory of the state of the service
+pub const DEFAULT_DATA_DIR: &str = "data";
+
 /// File-based implementation of block storage
 ///
 /// Stores blocks and processing state in JSON files within a configured
@@ -215,14 +307,51 @@ impl FileBlockStorage {
 	pub fn new(storage_path: PathBuf) -> Self {
 		FileBlockStorage { storage_path }
 	}
+
+	/// Advances the last processed block of a network without awaiting
+	///
+	/// Used by the panic hook, which cannot rely on the runtime. The saved block is never moved
+	/// backwards.
+	///
+	/// # Arguments
+	/// * `network_id` - Unique identifier for the network
+	/// * `block` - Block number to save
+	///
+	/// # Returns
+	/// * `Result<bool, anyhow::Error>` - Whether the saved block was advanced
+	pub fn advance_last_processed_block_blocking(
+		&self,
+		network_id: &str,
+		block: u64,
+	) -> Result<bool, anyhow::Error> {
+		let file_path = self
+			.storage_path
+			.join(format!("{}_last_block.txt", network_id));
+		let saved = match std::fs::read_to_string(&file_path) {
+			Ok(content) => content.trim().parse::<u64>().ok(),
+			Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+			Err(e) => {
+				return Err(anyhow::anyhow!(
+					"Failed to read last processed block: {}",
+					e
+				))
+			}
+		};
+		if saved.is_some_and(|saved| saved >= block) {
+			return Ok(false);
+		}
+		std::fs::write(file_path, block.to_string())
+			.map_err(|e| anyhow::anyhow!("Failed to save last processed block: {}", e))?;
+		Ok(true)
+	}
 }
 
 impl Default for FileBlockStorage {
 	/// Default implementation for FileBlockStorage
 	///
-	/// Initializes storage with the default path "data"
+	/// Initializes storage with the default data directory
 	fn default() -> Self {
-		FileBlockStorage::new(PathBuf::from("data"))
+		FileBlockStorage::new(PathBuf::from(DEFAULT_DATA_DIR))
 	}
 }
 
@@ -271,6 +400,49 @@ impl BlockStorage for FileBlockStorage {
 		Ok(())
 	}
 
+	/// Retrieves the cold start target from a network-specific file
+	///
+	/// The file is named "{network_id}_cold_start_target.txt"
+	async fn get_cold_start_target(&self, network_id: &str) -> Result<Option<u64>, anyhow::Error> {
+		let file_path = self
+			.storage_path
+			.join(format!("{}_cold_start_target.txt", network_id));
+
+		if !file_path.exists() {
+			return Ok(None);
+		}
+
+		let content = tokio::fs::read_to_string(file_path)
+			.await
+			.map_err(|e| anyhow::anyhow!("Failed to read cold start target: {}", e))?;
+		let block_number = content
+			.trim()
+			.parse::<u64>()
+			.map_err(|e| anyhow::anyhow!("Failed to parse cold start target: {}", e))?;
+		Ok(Some(block_number))
+	}
+
+	/// Saves the cold start target to a network-specific file, removing the file when cleared
+	async fn save_cold_start_target(
+		&self,
+		network_id: &str,
+		block: Option<u64>,
+	) -> Result<(), anyhow::Error> {
+		let file_path = self
+			.storage_path
+			.join(format!("{}_cold_start_target.txt", network_id));
+		match block {
+			Some(block) => tokio::fs::write(file_path, block.to_string())
+				.await
+				.map_err(|e| anyhow::anyhow!("Failed to save cold start target: {}", e))?,
+			None if file_path.exists() => tokio::fs::remove_file(file_path)
+				.await
+				.map_err(|e| anyhow::anyhow!("Failed to clear cold start target: {}", e))?,
+			None => {}
+		}
+		Ok(())
+	}
+
 	/// Saves blocks to a timestamped JSON file
 	///
 	/// # Note
@@ -453,9 +625,81 @@ impl BlockStorage for FileBlockStorage {
 
 		Ok(pruned_count)
 	}
+
+	async fn save_skipped_block(
+		&self,
+		network_id: &str,
+		entry: SkippedBlockEntry,
+	) -> Result<(), anyhow::Error> {
+		let mut entries = self.get_skipped_blocks(network_id).await?;
+		entries.retain(|e| e.block_number != entry.block_number);
+		entries.push(entry);
+		entries.sort_by_key(|e| e.block_number);
+
+		self.save_skipped_blocks_json(network_id, &entries).await
+	}
+
+	async fn get_skipped_blocks(
+		&self,
+		network_id: &str,
+	) -> Result<Vec<SkippedBlockEntry>, anyhow::Error> {
+		let json_path = self
+			.storage_path
+			.join(format!("{}_skipped_blocks.json", network_id));
+
+		if !json_path.exists() {
+			return Ok(Vec::new());
+		}
+
+		let content = tokio::fs::read_to_string(&json_path)
+			.await
+			.map_err(|e| anyhow::anyhow!("Failed to read skipped blocks JSON: {}", e))?;
+
+		if content.trim().is_empty() {
+			return Ok(Vec::new());
+		}
+
+		serde_json::from_str(&content)
+			.map_err(|e| anyhow::anyhow!("Failed to parse skipped blocks JSON: {}", e))
+	}
+
+	async fn remove_skipped_blocks(
+		&self,
+		network_id: &str,
+		block_numbers: &[u64],
+	) -> Result<(), anyhow::Error> {
+		if block_numbers.is_empty() {
+			return Ok(());
+		}
+
+		let mut entries = self.get_skipped_blocks(network_id).await?;
+		entries.retain(|e| !block_numbers.contains(&e.block_number));
+
+		self.save_skipped_blocks_json(network_id, &entries).await
+	}
 }
 
 impl FileBlockStorage {
+	/// Saves the skip list to JSON file
+	async fn save_skipped_blocks_json(
+		&self,
+		network_id: &str,
+		entries: &[SkippedBlockEntry],
+	) -> Result<(), anyhow::Error> {
+		let json_path = self
+			.storage_path
+			.join(format!("{}_skipped_blocks.json", network_id));
+
+		let json = serde_json::to_string_pretty(entries)
+			.map_err(|e| anyhow::anyhow!("Failed to serialize skipped blocks: {}", e))?;
+
+		tokio::fs::write(json_path, json)
+			.await
+			.map_err(|e| anyhow::anyhow!("Failed to save skipped blocks JSON: {}", e))?;
+
+		Ok(())
+	}
+
 	/// Loads missed blocks from JSON file, migrating from text format if needed
 	async fn load_missed_blocks_json(
 		&self,
@@ -550,6 +794,27 @@ mod tests {
 	use super::*;
 	use tempfile;
 
+	#[tokio::test]
+	async fn test_advance_last_processed_block_blocking() {
+		let temp_dir = tempfile::tempdir().unwrap();
+		let storage = FileBlockStorage::new(temp_dir.path().to_path_buf());
+
+		assert!(storage
+			.advance_last_processed_block_blocking("ethereum_mainnet", 100)
+			.unwrap());
+		// The saved block is never moved backwards
+		assert!(!storage
+			.advance_last_processed_block_blocking("ethereum_mainnet", 90)
+			.unwrap());
+		assert_eq!(
+			storage
+				.get_last_processed_block("ethereum_mainnet")
+				.await
+				.unwrap(),
+			Some(100)
+		);
+	}
+
 	#[tokio::test]
 	async fn test_get_last_processed_block() {
 		let temp_dir = tempfile::tempdir().unwrap();
@@ -0,0 +1,489 @@
+//! Per-network task groups.
+//!
+//! The watchers of all networks share the runtime, so every network is made a failure domain
+//! of its own: the tasks spawned for a network with [`spawn_network_task`] are counted by kind
+//! in the `network_tasks` metric, the blocks of its processing runs waiting in the pipeline in
+//! `network_queued_blocks`, and its [`RestartTracker`] suspends its runs after repeated
+//! failures as set by the `restart_policy` of the network, or right after a panic, without
+//! affecting the others.
+
+use prometheus::Gauge;
+use std::{
+	collections::HashMap,
+	future::Future,
+	sync::Mutex,
+	time::{Duration, Instant},
+};
+use tokio::task::{AbortHandle, Id, JoinError, JoinHandle, JoinSet};
+
+use crate::{
+	models::{Network, RestartPolicy},
+	utils::metrics::{NETWORK_QUEUED_BLOCKS, NETWORK_TASKS, NETWORK_WATCHER_SUSPENDED},
+};
+
+/// Suspension of the runs of a network without restart policy after a panic
+pub const PANIC_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Spawns a task accounted to a network
+///
+/// The task is counted in the `network_tasks` metric until it completes or is aborted.
+///
+/// # Arguments
+/// * `network_slug` - Network of the task
+/// * `kind` - Kind of the task, e.g. `run` or `trigger`
+/// * `future` - The task
+pub fn spawn_network_task<F>(network_slug: &str, kind: &str, future: F) -> JoinHandle<F::Output>
+where
+	F: Future + Send + 'static,
+	F::Output: Send + 'static,
+{
+	tokio::spawn(counted(network_slug, kind, future))
+}
+
+/// Spawns a task accounted to a network in a set of tasks
+///
+/// The task is aborted with the set, e.g. when the task owning the set is aborted, so that it
+/// does not outlive it. It is counted in the `network_tasks` metric as with
+/// [`spawn_network_task`].
+///
+/// # Arguments
+/// * `tasks` - Set of the task
+/// * `network_slug` - Network of the task
+/// * `kind` - Kind of the task, e.g. `filter` or `dispatch`
+/// * `future` - The task
+pub fn spawn_network_task_in<F>(
+	tasks: &mut JoinSet<F::Output>,
+	network_slug: &str,
+	kind: &str,
+	future: F,
+) -> AbortHandle
+where
+	F: Future + Send + 'static,
+	F::Output: Send + 'static,
+{
+	tasks.spawn(counted(network_slug, kind, future))
+}
+
+/// Waits for all the tasks of a set
+///
+/// # Returns
+/// The result of every task, by task id
+pub async fn join_network_tasks<T: 'static>(
+	tasks: &mut JoinSet<T>,
+) -> HashMap<Id, Result<T, JoinError>> {
+	let mut results = HashMap::new();
+	while let Some(result) = tasks.join_next_with_id().await {
+		match result {
+			Ok((id, output)) => results.insert(id, Ok(output)),
+			Err(e) => results.insert(e.id(), Err(e)),
+		};
+	}
+	results
+}
+
+/// Counts a task in the `network_tasks` metric until it completes or is dropped
+fn counted<F: Future>(
+	network_slug: &str,
+	kind: &str,
+	future: F,
+) -> impl Future<Output = F::Output> {
+	let tasks = NETWORK_TASKS.with_label_values(&[network_slug, kind]);
+	tasks.inc();
+	let guard = TaskGuard(tasks);
+	async move {
+		let _guard = guard;
+		future.await
+	}
+}
+
+/// Decrements the task count of a network when the task is dropped
+struct TaskGuard(Gauge);
+
+impl Drop for TaskGuard {
+	fn drop(&mut self) {
+		self.0.dec();
+	}
+}
+
+/// Blocks of a processing run waiting in the pipeline of a network
+///
+/// The blocks are counted in the `network_queued_blocks` metric until they are handed over to
+/// the triggers, or until the run ends.
+pub struct QueuedBlocks {
+	gauge: Gauge,
+	remaining: usize,
+}
+
+impl QueuedBlocks {
+	/// Counts the blocks of a run
+	///
+	/// # Arguments
+	/// * `network_slug` - Network of the run
+	/// * `count` - Number of blocks of the run
+	pub fn new(network_slug: &str, count: usize) -> Self {
+		let gauge = NETWORK_QUEUED_BLOCKS.with_label_values(&[network_slug]);
+		gauge.add(count as f64);
+		Self {
+			gauge,
+			remaining: count,
+		}
+	}
+
+	/// Records that a block was handed over to the triggers
+	pub fn handed_over(&mut self) {
+		if self.remaining > 0 {
+			self.remaining -= 1;
+			self.gauge.dec();
+		}
+	}
+}
+
+impl Drop for QueuedBlocks {
+	fn drop(&mut self) {
+		self.gauge.sub(self.remaining as f64);
+	}
+}
+
+#[derive(Debug, Default)]
+struct RestartState {
+	consecutive_failures: u32,
+	suspended_until: Option<Instant>,
+}
+
+/// Outcome of the runs of a network watcher, suspending the runs after repeated failures
+#[derive(Debug)]
+pub struct RestartTracker {
+	network_slug: String,
+	policy: Option<RestartPolicy>,
+	state: Mutex<RestartState>,
+}
+
+impl RestartTracker {
+	/// Creates the tracker of a network, following its `restart_policy`
+	///
+	/// # Arguments
+	/// * `network` - The network
+	pub fn new(network: &Network) -> Self {
+		Self {
+			network_slug: network.slug.clone(),
+			policy: network.restart_policy.clone(),
+			state: Mutex::new(RestartState::default()),
+		}
+	}
+
+	/// Returns whether a run may start, `false` while the runs are suspended
+	pub fn may_run(&self) -> bool {
+		let mut state = self.lock_state();
+		match state.suspended_until {
+			Some(until) if Instant::now() < until => false,
+			Some(_) => {
+				state.suspended_until = None;
+				NETWORK_WATCHER_SUSPENDED
+					.with_label_values(&[self.network_slug.as_str()])
+					.set(0.0);
+				tracing::info!(
+					network = %self.network_slug,
+					"Resuming block processing after {} failed runs",
+					state.consecutive_failures
+				);
+				true
+			}
+			None => true,
+		}
+	}
+
+	/// Records the outcome of a run
+	///
+	/// # Arguments
+	/// * `succeeded` - Whether the run completed without error
+	///
+	/// # Returns
+	/// The suspension of the runs, if the failure exhausted the restart policy
+	pub fn record_run(&self, succeeded: bool) -> Option<Duration> {
+		let mut state = self.lock_state();
+		if succeeded {
+			state.consecutive_failures = 0;
+			return None;
+		}
+		state.consecutive_failures = state.consecutive_failures.saturating_add(1);
+
+		let policy = self.policy.as_ref()?;
+		if state.consecutive_failures < policy.max_consecutive_failures {
+			return None;
+		}
+		let backoff = backoff(policy, state.consecutive_failures);
+		self.suspend(&mut state, backoff);
+		tracing::warn!(
+			network = %self.network_slug,
+			failures = state.consecutive_failures,
+			"Suspending block processing for {:?} after {} failed runs in a row",
+			backoff,
+			state.consecutive_failures
+		);
+		Some(backoff)
+	}
+
+	/// Records the outcome of a run from its task
+	///
+	/// A run that panicked suspends the runs right away (see [`Self::record_panic`]), a
+	/// cancelled run (e.g. by the watchdog) counts as a failed run.
+	///
+	/// # Arguments
+	/// * `result` - Result of the task of the run, whether the run completed without error
+	///
+	/// # Returns
+	/// Whether the run succeeded
+	pub fn record_task(&self, result: Result<bool, JoinError>) -> bool {
+		match result {
+			Ok(succeeded) => {
+				self.record_run(succeeded);
+				succeeded
+			}
+			Err(e) if e.is_panic() => {
+				// The panic was already logged by the panic hook
+				self.record_panic();
+				false
+			}
+			Err(e) => {
+				if e.is_cancelled() {
+					tracing::warn!(
+						network = %self.network_slug,
+						"Block processing run cancelled, resuming from the last checkpoint"
+					);
+				} else {
+					tracing::error!(
+						network = %self.network_slug,
+						"Block processing run failed: {}",
+						e
+					);
+				}
+				self.record_run(false);
+				false
+			}
+		}
+	}
+
+	/// Records a run that panicked
+	///
+	/// The panic may have left the state of the network partially updated, so the runs are
+	/// suspended right away, whatever the number of failures: for the backoff of the restart
+	/// policy, or [`PANIC_BACKOFF`] without one.
+	///
+	/// # Returns
+	/// The suspension of the runs
+	pub fn record_panic(&self) -> Duration {
+		let mut state = self.lock_state();
+		state.consecutive_failures = state.consecutive_failures.saturating_add(1);
+		let backoff = match &self.policy {
+			Some(policy) => backoff(
+				policy,
+				state
+					.consecutive_failures
+					.max(policy.max_consecutive_failures),
+			),
+			None => PANIC_BACKOFF,
+		};
+		self.suspend(&mut state, backoff);
+		tracing::error!(
+			network = %self.network_slug,
+			"Suspending block processing for {:?} after a panic",
+			backoff
+		);
+		backoff
+	}
+
+	fn suspend(&self, state: &mut RestartState, backoff: Duration) {
+		state.suspended_until = Some(Instant::now() + backoff);
+		NETWORK_WATCHER_SUSPENDED
+			.with_label_values(&[self.network_slug.as_str()])
+			.set(1.0);
+	}
+
+	fn lock_state(&self) -> std::sync::MutexGuard<'_, RestartState> {
+		match self.state.lock() {
+			Ok(state) => state,
+			Err(poisoned) => poisoned.into_inner(),
+		}
+	}
+}
+
+/// Returns the suspension of the runs after a number of failures in a row
+fn backoff(policy: &RestartPolicy, consecutive_failures: u32) -> Duration {
+	let doublings = (consecutive_failures - policy.max_consecutive_failures).min(32);
+	Duration::from_millis(
+		policy
+			.backoff_ms
+			.saturating_mul(1u64 << doublings)
+			.min(policy.max_backoff_ms),
+	)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::utils::tests::builders::network::NetworkBuilder;
+
+	#[tokio::test]
+	async fn test_spawn_network_task_counts_tasks() {
+		let tasks = NETWORK_TASKS.with_label_values(&["task_group_spawn", "run"]);
+		let (release_tx, release_rx) = tokio::sync::oneshot::channel::<()>();
+		let handle = spawn_network_task("task_group_spawn", "run", async move {
+			let _ = release_rx.await;
+		});
+		assert_eq!(tasks.get(), 1.0);
+
+		release_tx.send(()).unwrap();
+		handle.await.unwrap();
+		assert_eq!(tasks.get(), 0.0);
+
+		// Aborted tasks are no longer counted
+		let handle =
+			spawn_network_task("task_group_spawn", "run", futures::future::pending::<()>());
+		handle.abort();
+		assert!(handle.await.unwrap_err().is_cancelled());
+		assert_eq!(tasks.get(), 0.0);
+	}
+
+	#[tokio::test]
+	async fn test_network_tasks_aborted_with_set() {
+		let tasks = NETWORK_TASKS.with_label_values(&["task_group_set", "filter"]);
+		let mut set = JoinSet::new();
+		let finished = spawn_network_task_in(&mut set, "task_group_set", "filter", async { true });
+		spawn_network_task_in(
+			&mut set,
+			"task_group_set",
+			"filter",
+			futures::future::pending::<bool>(),
+		)
+		.abort();
+		let results = join_network_tasks(&mut set).await;
+		assert_eq!(results.len(), 2);
+		assert!(*results[&finished.id()].as_ref().unwrap());
+		assert_eq!(tasks.get(), 0.0);
+
+		// Dropping the set aborts its tasks
+		let (started_tx, started_rx) = tokio::sync::oneshot::channel();
+		spawn_network_task_in(&mut set, "task_group_set", "filter", async move {
+			let _ = started_tx.send(());
+			futures::future::pending::<bool>().await
+		});
+		started_rx.await.unwrap();
+		assert_eq!(tasks.get(), 1.0);
+		drop(set);
+		tokio::time::timeout(Duration::from_secs(1), async {
+			while tasks.get() != 0.0 {
+				tokio::task::yield_now().await;
+			}
+		})
+		.await
+		.expect("The task should be aborted with the set");
+	}
+
+	#[test]
+	fn test_queued_blocks() {
+		let gauge = NETWORK_QUEUED_BLOCKS.with_label_values(&["task_group_queue"]);
+		let mut queued = QueuedBlocks::new("task_group_queue", 3);
+		assert_eq!(gauge.get(), 3.0);
+		queued.handed_over();
+		assert_eq!(gauge.get(), 2.0);
+		// Blocks never handed over are released with the run
+		drop(queued);
+		assert_eq!(gauge.get(), 0.0);
+	}
+
+	#[test]
+	fn test_restart_tracker_suspends_after_failures() {
+		let network = NetworkBuilder::new()
+			.slug("task_group_restarts")
+			.restart_policy(RestartPolicy {
+				max_consecutive_failures: 2,
+				backoff_ms: 1_000,
+				max_backoff_ms: 3_000,
+			})
+			.build();
+		let tracker = RestartTracker::new(&network);
+
+		assert_eq!(tracker.record_run(false), None);
+		assert!(tracker.may_run());
+		assert_eq!(tracker.record_run(false), Some(Duration::from_secs(1)));
+		assert!(!tracker.may_run());
+
+		// The backoff doubles with every further failure, up to the maximum
+		assert_eq!(tracker.record_run(false), Some(Duration::from_secs(2)));
+		assert_eq!(tracker.record_run(false), Some(Duration::from_secs(3)));
+
+		// A successful run resets the failures
+		assert_eq!(tracker.record_run(true), None);
+		assert_eq!(tracker.record_run(false), None);
+	}
+
+	#[test]
+	fn test_restart_tracker_resumes_after_backoff() {
+		let network = NetworkBuilder::new()
+			.slug("task_group_resume")
+			.restart_policy(RestartPolicy {
+				max_consecutive_failures: 1,
+				backoff_ms: 10,
+				max_backoff_ms: 10,
+			})
+			.build();
+		let tracker = RestartTracker::new(&network);
+		let suspended = NETWORK_WATCHER_SUSPENDED.with_label_values(&["task_group_resume"]);
+
+		tracker.record_run(false);
+		assert!(!tracker.may_run());
+		assert_eq!(suspended.get(), 1.0);
+
+		std::thread::sleep(Duration::from_millis(20));
+		assert!(tracker.may_run());
+		assert_eq!(suspended.get(), 0.0);
+	}
+
+	#[test]
+	fn test_restart_tracker_suspends_after_panic() {
+		let network = NetworkBuilder::new()
+			.slug("task_group_panic")
+			.restart_policy(RestartPolicy {
+				max_consecutive_failures: 3,
+				backoff_ms: 1_000,
+				max_backoff_ms: 3_000,
+			})
+			.build();
+		let tracker = RestartTracker::new(&network);
+		assert_eq!(tracker.record_panic(), Duration::from_secs(1));
+		assert!(!tracker.may_run());
+
+		let tracker = RestartTracker::new(&NetworkBuilder::new().build());
+		assert_eq!(tracker.record_panic(), PANIC_BACKOFF);
+		assert!(!tracker.may_run());
+	}
+
+	#[tokio::test]
+	async fn test_panicked_task_suspends_network() {
+		let network = NetworkBuilder::new().slug("task_group_panicked").build();
+		let tracker = RestartTracker::new(&network);
+		let handle = spawn_network_task("task_group_panicked", "run", async {
+			if true {
+				panic!("filter bug");
+			}
+			true
+		});
+		assert!(!tracker.record_task(handle.await));
+		assert!(!tracker.may_run());
+		assert_eq!(
+			NETWORK_TASKS
+				.with_label_values(&["task_group_panicked", "run"])
+				.get(),
+			0.0
+		);
+	}
+
+	#[test]
+	fn test_restart_tracker_without_policy() {
+		let tracker = RestartTracker::new(&NetworkBuilder::new().build());
+		for _ in 0..10 {
+			assert_eq!(tracker.record_run(false), None);
+		}
+		assert!(tracker.may_run());
+	}
+}
@@ -0,0 +1,232 @@
+//! Block processing watchdog module.
+//!
+//! A network watcher runs block processing on a schedule. If a run never completes (e.g.
+//! an RPC call hanging on a half-open connection), it keeps holding the run lock and the
+//! watcher stops making progress without any error. The watchdog tracks the activity of
+//! each watcher, cancels a run that exceeds the stall timeout, along with the filtering and
+//! dispatch stages it spawned, so the next scheduled run starts from the last checkpoint
+//! without racing the stages of the cancelled one, and counts restarts in the `watcher_restarts_total`
+//! metric. Long runs that are still making progress, such as the chunked processing of an
+//! oversized block, report it with [`report_progress`] so that they are not cancelled.
+//! Watchers whose stalled run was not cancelled in time are reported by [`stalled_watchers`],
+//! e.g. to withhold the pings of the service manager's watchdog.
+
+use lazy_static::lazy_static;
+use std::{
+	collections::HashMap,
+	sync::{Arc, Mutex, Weak},
+	time::{Duration, Instant},
+};
+use tokio::task::AbortHandle;
+
+use crate::utils::metrics::WATCHER_RESTARTS_TOTAL;
+
+/// Activity of a registered watcher and its stall timeout, if its watchdog is enabled
+type RegisteredActivity = (Weak<WatcherActivity>, Option<Duration>);
+
+lazy_static! {
+	/// Activity of the network watchers, keyed by network slug
+	static ref ACTIVITIES: Mutex<HashMap<String, RegisteredActivity>> = Mutex::new(HashMap::new());
+}
+
+/// Records progress of the run in progress of a network's watcher
+///
+/// The stall timeout of the watchdog then counts from this point, so that a long run which
+/// keeps making progress is not cancelled.
+///
+/// # Arguments
+/// * `network_slug` - Network of the watcher
+pub fn report_progress(network_slug: &str) {
+	let activity = lock(&ACTIVITIES)
+		.get(network_slug)
+		.and_then(|(activity, _)| activity.upgrade());
+	if let Some(activity) = activity {
+		activity.touch();
+	}
+}
+
+/// Lists the networks whose watcher has a run stalled for more than twice its stall timeout
+///
+/// The watchdog cancels a run after the stall timeout, so a run still stalled after twice the
+/// timeout means that the watchdog itself is not running. Watchers without an enabled
+/// watchdog are never reported.
+///
+/// # Returns
+/// The slugs of the stalled networks, sorted
+pub fn stalled_watchers() -> Vec<String> {
+	let mut stalled: Vec<String> = lock(&ACTIVITIES)
+		.iter()
+		.filter_map(|(network_slug, (activity, stall_timeout))| {
+			let activity = activity.upgrade()?;
+			let stall_timeout = (*stall_timeout)?;
+			(lock(&activity.running).is_some() && activity.idle_for() > stall_timeout * 2)
+				.then(|| network_slug.clone())
+		})
+		.collect();
+	stalled.sort();
+	stalled
+}
+
+/// Activity of a network watcher, shared between its processing job and its watchdog
+#[derive(Debug)]
+pub struct WatcherActivity {
+	/// Last time the watcher started or finished a run
+	last_activity: Mutex<Instant>,
+	/// Handle of the run in progress, if any
+	running: Mutex<Option<AbortHandle>>,
+}
+
+impl Default for WatcherActivity {
+	fn default() -> Self {
+		Self {
+			last_activity: Mutex::new(Instant::now()),
+			running: Mutex::new(None),
+		}
+	}
+}
+
+impl WatcherActivity {
+	/// Registers the activity of the watcher of a network, so that its runs can report their
+	/// progress with [`report_progress`]
+	///
+	/// # Arguments
+	/// * `network_slug` - Network of the watcher
+	/// * `stall_timeout` - Stall timeout of the watcher, if its watchdog is enabled
+	pub fn register(self: &Arc<Self>, network_slug: &str, stall_timeout: Option<Duration>) {
+		lock(&ACTIVITIES).insert(
+			network_slug.to_string(),
+			(Arc::downgrade(self), stall_timeout),
+		);
+	}
+
+	/// Records the start of a run
+	///
+	/// # Arguments
+	/// * `handle` - Handle used to cancel the run if it stalls
+	pub fn run_started(&self, handle: AbortHandle) {
+		self.touch();
+		*lock(&self.running) = Some(handle);
+	}
+
+	/// Records the end of a run
+	pub fn run_finished(&self) {
+		lock(&self.running).take();
+		self.touch();
+	}
+
+	/// Time elapsed since the last recorded activity
+	pub fn idle_for(&self) -> Duration {
+		lock(&self.last_activity).elapsed()
+	}
+
+	/// Cancels the run in progress if it has been active for longer than `stall_timeout`
+	///
+	/// # Arguments
+	/// * `network_slug` - Network of the watcher, used for logging and metrics
+	/// * `stall_timeout` - Maximum duration of a run
+	///
+	/// # Returns
+	/// `true` if a stalled run was cancelled
+	pub fn cancel_if_stalled(&self, network_slug: &str, stall_timeout: Duration) -> bool {
+		let idle_for = self.idle_for();
+		if idle_for <= stall_timeout {
+			return false;
+		}
+		let Some(handle) = lock(&self.running).take() else {
+			return false;
+		};
+
+		tracing::error!(
+			network = %network_slug,
+			"Block processing stalled for {:?}, restarting the watcher run",
+			idle_for
+		);
+		handle.abort();
+		WATCHER_RESTARTS_TOTAL
+			.with_label_values(&[network_slug])
+			.inc();
+		self.touch();
+		true
+	}
+
+	fn touch(&self) {
+		*lock(&self.last_activity) = Instant::now();
+	}
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+	match mutex.lock() {
+		Ok(guard) => guard,
+		Err(poisoned) => poisoned.into_inner(),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[tokio::test]
+	async fn test_cancel_if_stalled_aborts_hung_run() {
+		let activity = WatcherActivity::default();
+		let handle = tokio::spawn(futures::future::pending::<()>());
+		activity.run_started(handle.abort_handle());
+
+		// Not stalled yet
+		assert!(!activity.cancel_if_stalled("watchdog_hung", Duration::from_secs(60)));
+
+		tokio::time::sleep(Duration::from_millis(20)).await;
+		assert!(activity.cancel_if_stalled("watchdog_hung", Duration::from_millis(10)));
+		assert!(handle.await.unwrap_err().is_cancelled());
+
+		let restarts = WATCHER_RESTARTS_TOTAL
+			.get_metric_with_label_values(&["watchdog_hung"])
+			.unwrap();
+		assert_eq!(restarts.get(), 1.0);
+	}
+
+	#[tokio::test]
+	async fn test_report_progress_defers_cancellation() {
+		let activity = Arc::new(WatcherActivity::default());
+		activity.register("watchdog_progress", None);
+		let handle = tokio::spawn(futures::future::pending::<()>());
+		activity.run_started(handle.abort_handle());
+
+		tokio::time::sleep(Duration::from_millis(50)).await;
+		report_progress("watchdog_progress");
+		// Progress was reported within the stall timeout
+		assert!(!activity.cancel_if_stalled("watchdog_progress", Duration::from_millis(40)));
+		handle.abort();
+	}
+
+	#[tokio::test]
+	async fn test_stalled_watchers() {
+		let activity = Arc::new(WatcherActivity::default());
+		activity.register("watchdog_stalled", Some(Duration::from_millis(10)));
+		let idle = Arc::new(WatcherActivity::default());
+		idle.register("watchdog_stalled_idle", Some(Duration::from_millis(10)));
+		let handle = tokio::spawn(futures::future::pending::<()>());
+		activity.run_started(handle.abort_handle());
+
+		tokio::time::sleep(Duration::from_millis(30)).await;
+		let stalled = stalled_watchers();
+		assert!(stalled.contains(&"watchdog_stalled".to_string()));
+		// No run in progress
+		assert!(!stalled.contains(&"watchdog_stalled_idle".to_string()));
+
+		assert!(activity.cancel_if_stalled("watchdog_stalled", Duration::from_millis(10)));
+		assert!(!stalled_watchers().contains(&"watchdog_stalled".to_string()));
+	}
+
+	#[tokio::test]
+	async fn test_cancel_if_stalled_ignores_idle_watcher() {
+		let activity = WatcherActivity::default();
+		let handle = tokio::spawn(async {});
+		activity.run_started(handle.abort_handle());
+		handle.await.unwrap();
+		activity.run_finished();
+
+		tokio::time::sleep(Duration::from_millis(20)).await;
+		// No run in progress, nothing to cancel
+		assert!(!activity.cancel_if_stalled("watchdog_idle", Duration::from_millis(10)));
+	}
+}
@@ -3,7 +3,9 @@
 //! Defines the error cases that can occur during block filtering
 //! and provides helper methods for error creation and formatting.
 
-use crate::utils::logging::error::{ErrorContext, TraceableError};
+use crate::utils::logging::error::{
+	classify_error_chain, ClassifiedError, ErrorClass, ErrorContext, TraceableError,
+};
 use std::collections::HashMap;
 use thiserror::Error as ThisError;
 use uuid::Uuid;
@@ -68,6 +70,17 @@ impl TraceableError for FilterError {
 	}
 }
 
+impl ClassifiedError for FilterError {
+	fn classification(&self) -> ErrorClass {
+		match self {
+			Self::BlockTypeMismatch(_) => ErrorClass::Permanent,
+			Self::NetworkError(ctx) => classify_error_chain(ctx, ErrorClass::Transient),
+			Self::InternalError(ctx) => classify_error_chain(ctx, ErrorClass::Permanent),
+			Self::Other(e) => classify_error_chain(e.as_ref(), ErrorClass::Permanent),
+		}
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
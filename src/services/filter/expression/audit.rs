@@ -0,0 +1,214 @@
+//! Audit of expression evaluation.
+//!
+//! Evaluates an expression like [`super::evaluate`], while recording every comparison that
+//! was actually evaluated (the variable, its value, the operator, the literal and the
+//! result), so that users can see why a condition matched or not.
+
+use std::cell::RefCell;
+
+use super::{
+	ast::{Accessor, ComparisonOperator, ConditionLeft, Expression, LiteralValue, LogicalOperator},
+	error::EvaluationError,
+	evaluation::ConditionEvaluator,
+	helpers::evaluate,
+};
+use crate::models::OperandAudit;
+
+/// Condition evaluator recording the comparisons performed by the wrapped evaluator
+struct RecordingEvaluator<'e, E: ConditionEvaluator> {
+	inner: &'e E,
+	variable: RefCell<String>,
+	operands: RefCell<Vec<OperandAudit>>,
+}
+
+impl<E: ConditionEvaluator> ConditionEvaluator for RecordingEvaluator<'_, E> {
+	fn get_base_param(&self, name: &str) -> Result<(&str, &str), EvaluationError> {
+		self.inner.get_base_param(name)
+	}
+
+	fn compare_final_values(
+		&self,
+		left_kind: &str,
+		left_resolved_value: &str,
+		operator: &ComparisonOperator,
+		right_literal: &LiteralValue,
+	) -> Result<bool, EvaluationError> {
+		let result = self.inner.compare_final_values(
+			left_kind,
+			left_resolved_value,
+			operator,
+			right_literal,
+		)?;
+		self.operands.borrow_mut().push(OperandAudit {
+			variable: self.variable.borrow().clone(),
+			value: left_resolved_value.to_string(),
+			operator: operator_symbol(operator).to_string(),
+			literal: literal_string(right_literal),
+			result,
+		});
+		Ok(result)
+	}
+
+	fn get_kind_from_json_value(&self, value: &serde_json::Value) -> String {
+		self.inner.get_kind_from_json_value(value)
+	}
+}
+
+/// Evaluates an expression and records the comparisons that were evaluated
+///
+/// Logical operators short-circuit, so comparisons that did not need to be evaluated are
+/// not recorded.
+///
+/// # Arguments
+/// * `expression` - The parsed expression
+/// * `evaluator` - Chain-specific condition evaluator
+///
+/// # Returns
+/// The result of the expression and the evaluated comparisons, in evaluation order
+pub fn evaluate_with_audit(
+	expression: &Expression<'_>,
+	evaluator: &impl ConditionEvaluator,
+) -> Result<(bool, Vec<OperandAudit>), EvaluationError> {
+	let recorder = RecordingEvaluator {
+		inner: evaluator,
+		variable: RefCell::new(String::new()),
+		operands: RefCell::new(Vec::new()),
+	};
+	let result = evaluate_recorded(expression, &recorder)?;
+	Ok((result, recorder.operands.into_inner()))
+}
+
+fn evaluate_recorded<E: ConditionEvaluator>(
+	expression: &Expression<'_>,
+	recorder: &RecordingEvaluator<'_, E>,
+) -> Result<bool, EvaluationError> {
+	match expression {
+		Expression::Condition(condition) => {
+			*recorder.variable.borrow_mut() = variable_path(&condition.left);
+			evaluate(expression, recorder)
+		}
+		Expression::Logical {
+			left,
+			operator,
+			right,
+		} => {
+			let left_val = evaluate_recorded(left, recorder)?;
+			match operator {
+				LogicalOperator::And if !left_val => Ok(false),
+				LogicalOperator::Or if left_val => Ok(true),
+				_ => evaluate_recorded(right, recorder),
+			}
+		}
+	}
+}
+
+/// Formats the left side of a condition as written in the expression
+fn variable_path(left: &ConditionLeft<'_>) -> String {
+	let mut path = left.base_name().to_string();
+	for accessor in left.accessors() {
+		match accessor {
+			Accessor::Index(index) => path.push_str(&format!("[{}]", index)),
+			Accessor::Key(key) => {
+				path.push('.');
+				path.push_str(key);
+			}
+		}
+	}
+	path
+}
+
+fn operator_symbol(operator: &ComparisonOperator) -> &'static str {
+	match operator {
+		ComparisonOperator::Eq => "==",
+		ComparisonOperator::Ne => "!=",
+		ComparisonOperator::Gt => ">",
+		ComparisonOperator::Gte => ">=",
+		ComparisonOperator::Lt => "<",
+		ComparisonOperator::Lte => "<=",
+		ComparisonOperator::StartsWith => "starts_with",
+		ComparisonOperator::EndsWith => "ends_with",
+		ComparisonOperator::Contains => "contains",
+	}
+}
+
+fn literal_string(literal: &LiteralValue<'_>) -> String {
+	match literal {
+		LiteralValue::Bool(value) => value.to_string(),
+		LiteralValue::Str(value) | LiteralValue::Number(value) => value.to_string(),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::services::filter::expression::parse;
+
+	/// Evaluator over integer variables
+	struct TestEvaluator(Vec<(&'static str, &'static str)>);
+
+	impl ConditionEvaluator for TestEvaluator {
+		fn get_base_param(&self, name: &str) -> Result<(&str, &str), EvaluationError> {
+			self.0
+				.iter()
+				.find(|(param, _)| *param == name)
+				.map(|(_, value)| (*value, "number"))
+				.ok_or_else(|| EvaluationError::variable_not_found(name.to_string(), None, None))
+		}
+
+		fn compare_final_values(
+			&self,
+			_left_kind: &str,
+			left_resolved_value: &str,
+			operator: &ComparisonOperator,
+			right_literal: &LiteralValue,
+		) -> Result<bool, EvaluationError> {
+			let left: i64 = left_resolved_value.parse().unwrap();
+			let right: i64 = literal_string(right_literal).parse().unwrap();
+			Ok(match operator {
+				ComparisonOperator::Gt => left > right,
+				ComparisonOperator::Lt => left < right,
+				_ => left == right,
+			})
+		}
+
+		fn get_kind_from_json_value(&self, _value: &serde_json::Value) -> String {
+			"number".to_string()
+		}
+	}
+
+	#[test]
+	fn test_evaluate_with_audit() {
+		let evaluator = TestEvaluator(vec![("amount", "1500"), ("fee", "3")]);
+		let expression = parse("amount > 1000 AND fee < 2 OR fee == 3").unwrap();
+
+		let (result, operands) = evaluate_with_audit(&expression, &evaluator).unwrap();
+
+		assert!(result);
+		assert_eq!(operands.len(), 3);
+		assert_eq!(
+			operands[0],
+			OperandAudit {
+				variable: "amount".to_string(),
+				value: "1500".to_string(),
+				operator: ">".to_string(),
+				literal: "1000".to_string(),
+				result: true,
+			}
+		);
+		assert!(!operands[1].result);
+		assert_eq!(operands[2].variable, "fee");
+		assert!(operands[2].result);
+	}
+
+	#[test]
+	fn test_evaluate_with_audit_short_circuits() {
+		let evaluator = TestEvaluator(vec![("amount", "10"), ("fee", "3")]);
+		let expression = parse("amount > 1000 AND fee < 2").unwrap();
+
+		let (result, operands) = evaluate_with_audit(&expression, &evaluator).unwrap();
+
+		assert!(!result);
+		assert_eq!(operands.len(), 1);
+		assert_eq!(operands[0].variable, "amount");
+	}
+}
@@ -84,6 +84,18 @@ pub fn evaluate(
 	}
 }
 
+/// Collects the base names of the variables an expression compares, in order of appearance
+pub fn variable_names<'a>(expression: &Expression<'a>) -> Vec<&'a str> {
+	match expression {
+		Expression::Condition(condition) => vec![condition.left.base_name()],
+		Expression::Logical { left, right, .. } => {
+			let mut names = variable_names(left);
+			names.extend(variable_names(right));
+			names
+		}
+	}
+}
+
 /// Compares two values implementing the Ord trait using the specified comparison operator
 /// Returns true if the comparison is valid, false otherwise
 /// Returns an error if the operator is not supported for the given types
@@ -343,4 +355,16 @@ mod tests {
 			vec!["base.field".to_string(), "base.field[0]".to_string()]
 		);
 	}
+
+	#[test]
+	fn test_variable_names() {
+		let expression = crate::services::filter::expression::parse(
+			"gas_price > 100 && (to == '0x1' || input.len > 4)",
+		)
+		.unwrap();
+		assert_eq!(
+			variable_names(&expression),
+			vec!["gas_price", "to", "input"]
+		);
+	}
 }
@@ -1,13 +1,15 @@
 //! Shared logic for parsing and evaluating expressions
 
 mod ast;
+mod audit;
 mod error;
 mod evaluation;
 mod helpers;
 mod parsing;
 
-pub use ast::{ComparisonOperator, LiteralValue};
+pub use ast::{ComparisonOperator, Expression, LiteralValue};
+pub use audit::evaluate_with_audit;
 pub use error::EvaluationError;
 pub use evaluation::ConditionEvaluator;
-pub use helpers::{compare_ordered_values, evaluate};
+pub use helpers::{compare_ordered_values, evaluate, variable_names};
 pub use parsing::parse;
@@ -5,21 +5,31 @@
 //! - Prepares notification payloads by converting blockchain-specific data into a generic format
 //! - Handles match execution through configured triggers
 //! - Manages the transformation of complex blockchain data into template variables
+//! - Enriches the template variables with the output of the post-processing script of the monitor
 
 use std::collections::HashMap;
 
-use alloy::primitives::Address;
+use alloy::primitives::{Address, I256};
+use chrono::Utc;
 use serde_json::{json, Value as JsonValue};
 
 use crate::{
-	models::{MonitorMatch, ScriptLanguage},
+	models::{
+		MonitorMatch, NormalizedCall, NormalizedMatch, NormalizedMonitor, NormalizedTransaction,
+		ScriptLanguage, NORMALIZED_MATCH_EXTENSIONS, NORMALIZED_MATCH_SCHEMA_VERSION,
+	},
 	services::{
 		filter::{
 			evm_helpers::{b256_to_string, h160_to_string},
+			filters::evm::funds_flow,
 			FilterError,
 		},
-		trigger::TriggerExecutionServiceTrait,
+		trigger::{
+			match_triggers, owner_template_json, ScriptExecutorFactory,
+			TriggerExecutionServiceTrait,
+		},
 	},
+	utils::normalize_string,
 };
 
 /// Process a monitor match by executing associated triggers.
@@ -48,13 +58,267 @@ use crate::{
 /// "events.0.args.to": "0x70bf6634ee8cb27d04478f184b9b8bb13e5f4710"
 /// "events.0.args.from": "0x2e8135be71230c6b1b4045696d41c09db0414226"
 /// "events.0.args.value": "88248701"
+/// "audit.0.expression": "value > 1000000"
+/// "audit.0.operands.0.value": "88248701"
 /// ```
+///
+/// When the monitor has a post-processing script, the object it outputs is added as
+/// `enrichment.*` variables.
 pub async fn handle_match<T: TriggerExecutionServiceTrait>(
 	matching_monitor: MonitorMatch,
 	trigger_service: &T,
 	trigger_scripts: &HashMap<String, (ScriptLanguage, String)>,
 ) -> Result<(), FilterError> {
-	match &matching_monitor {
+	let mut data_json = match_template_json(&matching_monitor);
+	enrich_template_json(&matching_monitor, &mut data_json, trigger_scripts).await;
+
+	// Swallow any errors since it's logged in the trigger service and we want to continue
+	// processing other matches
+	let _ = trigger_service
+		.execute(
+			match_triggers(&matching_monitor),
+			json_to_hashmap(&data_json),
+			&matching_monitor,
+			trigger_scripts,
+		)
+		.await;
+	Ok(())
+}
+
+/// Process the matches of a monitor in a block as a single notification.
+///
+/// The template variables are those of the first match, with the `functions` and `events` of
+/// all the matches and a `batch` object holding the number of matched events (`batch.count`)
+/// and matches (`batch.matches`), the transaction of each match (`batch.transactions`), and
+/// the minimum, maximum and sum of the `aggregate_arg` event argument of the monitor
+/// (`batch.aggregate`). Script triggers and raw webhooks receive the first match, and the
+/// post-processing script of the monitor enriches the variables from the first match.
+///
+/// # Arguments
+/// * `matches` - The matches of the monitor in the block, in block order
+/// * `trigger_service` - Service responsible for executing triggers
+/// * `trigger_scripts` - Scripts to be executed for each trigger
+///
+/// # Returns
+/// Result indicating success or failure of trigger execution
+pub async fn handle_batch<T: TriggerExecutionServiceTrait>(
+	matches: &[MonitorMatch],
+	trigger_service: &T,
+	trigger_scripts: &HashMap<String, (ScriptLanguage, String)>,
+) -> Result<(), FilterError> {
+	let Some(first_match) = matches.first() else {
+		return Ok(());
+	};
+	let mut data_json = batch_template_json(matches);
+	enrich_template_json(first_match, &mut data_json, trigger_scripts).await;
+
+	// Swallow any errors since it's logged in the trigger service and we want to continue
+	// processing other matches
+	let _ = trigger_service
+		.execute(
+			match_triggers(first_match),
+			json_to_hashmap(&data_json),
+			first_match,
+			trigger_scripts,
+		)
+		.await;
+	Ok(())
+}
+
+/// Adds the output of the post-processing script of the monitor of a match as `enrichment`
+///
+/// A script that is not loaded, fails or does not output a JSON object is logged, and the
+/// match is notified without enrichment.
+async fn enrich_template_json(
+	matching_monitor: &MonitorMatch,
+	data_json: &mut JsonValue,
+	trigger_scripts: &HashMap<String, (ScriptLanguage, String)>,
+) {
+	let monitor = matching_monitor.monitor();
+	let Some(post_processing) = &monitor.post_processing else {
+		return;
+	};
+	let Some((language, content)) = trigger_scripts.get(&format!(
+		"{}|{}",
+		normalize_string(&monitor.name),
+		post_processing.script_path
+	)) else {
+		tracing::warn!(
+			"Post-processing script {} of monitor {} is not loaded",
+			post_processing.script_path,
+			monitor.name
+		);
+		return;
+	};
+
+	let executor = ScriptExecutorFactory::create(language, content);
+	match executor
+		.enrich(
+			matching_monitor.clone(),
+			&post_processing.timeout_ms,
+			post_processing.arguments.as_deref(),
+		)
+		.await
+	{
+		Ok(enrichment) => data_json["enrichment"] = JsonValue::Object(enrichment),
+		Err(e) => tracing::warn!(
+			"Post-processing script {} of monitor {} failed, notifying without enrichment: {}",
+			post_processing.script_path,
+			monitor.name,
+			e
+		),
+	}
+}
+
+/// Aggregates the structured data of the matches of a monitor in a block
+fn batch_template_json(matches: &[MonitorMatch]) -> JsonValue {
+	let mut data_json = json!({});
+	let mut functions = Vec::new();
+	let mut events = Vec::new();
+	let mut transactions = Vec::new();
+	for (i, monitor_match) in matches.iter().enumerate() {
+		let match_json = match_template_json(monitor_match);
+		let transaction = &match_json["transaction"];
+		if let Some(id) = transaction["hash"]
+			.as_str()
+			.or_else(|| transaction["signature"].as_str())
+		{
+			transactions.push(json!(id));
+		}
+		if let Some(match_functions) = match_json["functions"].as_array() {
+			functions.extend(match_functions.iter().cloned());
+		}
+		if let Some(match_events) = match_json["events"].as_array() {
+			events.extend(match_events.iter().cloned());
+		}
+		if i == 0 {
+			data_json = match_json;
+		}
+	}
+
+	let mut batch = json!({
+		"count": events.len(),
+		"matches": matches.len(),
+		"transactions": transactions,
+	});
+	let aggregate_arg = matches
+		.first()
+		.and_then(|monitor_match| monitor_match.monitor().batch.as_ref())
+		.and_then(|config| config.aggregate_arg.as_ref());
+	if let Some(arg) = aggregate_arg {
+		let values: Vec<I256> = events
+			.iter()
+			.filter_map(|event| event["args"][arg].as_str())
+			.filter_map(|value| I256::from_dec_str(value).ok())
+			.collect();
+		if let (Some(min), Some(max)) = (values.iter().min(), values.iter().max()) {
+			let sum = values
+				.iter()
+				.fold(I256::ZERO, |sum, value| sum.saturating_add(*value));
+			batch["aggregate"] = json!({
+				"min": min.to_string(),
+				"max": max.to_string(),
+				"sum": sum.to_string(),
+			});
+		}
+	}
+
+	data_json["functions"] = json!(functions);
+	data_json["events"] = json!(events);
+	data_json["batch"] = batch;
+	data_json
+}
+
+/// Converts a match into the versioned normalized schema for downstream consumers
+///
+/// The normalized match is built from the same data as the template variables: the monitor,
+/// the transaction, the matched functions and events, and the chain-specific data of the
+/// match as `extensions`.
+///
+/// # Arguments
+/// * `matching_monitor` - The match
+///
+/// # Returns
+/// The normalized match
+pub fn normalize_match(matching_monitor: &MonitorMatch) -> NormalizedMatch {
+	let chain = match matching_monitor {
+		MonitorMatch::EVM(_) => "evm",
+		MonitorMatch::Stellar(_) => "stellar",
+		MonitorMatch::Midnight(_) => "midnight",
+		MonitorMatch::Solana(_) => "solana",
+	};
+	let monitor = matching_monitor.monitor();
+
+	let mut data_json = match_template_json(matching_monitor);
+	let transaction = data_json["transaction"].take();
+	let transaction_field = |name: &str| transaction[name].as_str().map(|value| value.to_string());
+	// Every matched function and event is kept, even without decoded arguments
+	let calls = |calls: &JsonValue| -> Vec<NormalizedCall> {
+		calls
+			.as_array()
+			.map(|calls| {
+				calls
+					.iter()
+					.map(|call| NormalizedCall {
+						signature: call["signature"].as_str().unwrap_or_default().to_string(),
+						args: call["args"].as_object().cloned().unwrap_or_default(),
+					})
+					.collect()
+			})
+			.unwrap_or_default()
+	};
+	let functions = calls(&data_json["functions"]);
+	let events = calls(&data_json["events"]);
+	let extensions = NORMALIZED_MATCH_EXTENSIONS
+		.iter()
+		.filter_map(|key| {
+			let value = data_json.get_mut(*key)?.take();
+			Some((key.to_string(), value))
+		})
+		.collect();
+
+	NormalizedMatch {
+		schema_version: NORMALIZED_MATCH_SCHEMA_VERSION,
+		chain: chain.to_string(),
+		network: matching_monitor.network_slug().to_string(),
+		monitor: NormalizedMonitor {
+			name: monitor.name.clone(),
+			severity: monitor.severity,
+		},
+		transaction: NormalizedTransaction {
+			id: transaction_field("hash")
+				.or_else(|| transaction_field("signature"))
+				.unwrap_or_default(),
+			from: transaction_field("from"),
+			to: transaction_field("to"),
+			value: transaction_field("value"),
+			fee: transaction_field("fee"),
+		},
+		functions,
+		events,
+		extensions,
+	}
+}
+
+/// Converts a match into the structured data its template variables are flattened from
+///
+/// # Arguments
+/// * `matching_monitor` - The match
+///
+/// # Returns
+/// The monitor, transaction, matched functions and events, owner of the matched address and
+/// chain-specific data of the match
+fn match_template_json(matching_monitor: &MonitorMatch) -> JsonValue {
+	let mut data_json = chain_template_json(matching_monitor);
+	if let Some(owner) = owner_template_json(matching_monitor) {
+		data_json["owners"] = owner;
+	}
+	data_json
+}
+
+/// Converts the chain-specific data of a match into structured template data
+fn chain_template_json(matching_monitor: &MonitorMatch) -> JsonValue {
+	match matching_monitor {
 		MonitorMatch::EVM(evm_monitor_match) => {
 			let transaction = evm_monitor_match.transaction.clone();
 			// If sender does not exist, we replace with 0x0000000000000000000000000000000000000000
@@ -64,6 +328,7 @@ pub async fn handle_match<T: TriggerExecutionServiceTrait>(
 			let mut data_json = json!({
 				"monitor": {
 					"name": evm_monitor_match.monitor.name.clone(),
+					"severity": evm_monitor_match.monitor.severity.as_str(),
 				},
 				"transaction": {
 					"hash": b256_to_string(*transaction.hash()),
@@ -79,6 +344,39 @@ pub async fn handle_match<T: TriggerExecutionServiceTrait>(
 				data_json["transaction"]["to"] = json!(h160_to_string(*to));
 			}
 
+			// Add the timestamp and age of the block, if known
+			if let Some(timestamp) = evm_monitor_match.block_timestamp {
+				let now = Utc::now().timestamp().max(0) as u64;
+				data_json["block"] = json!({
+					"timestamp": timestamp,
+					"age": now.saturating_sub(timestamp),
+				});
+			}
+
+			// Add the fee paid if the receipt was fetched
+			if let Some(fee) = evm_monitor_match
+				.receipt
+				.as_ref()
+				.and_then(|receipt| receipt.fee())
+			{
+				data_json["transaction"]["fee"] = json!(fee.to_string());
+			}
+
+			// Add net flows per address if enabled for the monitor
+			if funds_flow::is_enabled(&evm_monitor_match.monitor) {
+				data_json["funds_flow"] = funds_flow::to_template_json(evm_monitor_match);
+			}
+
+			// Mark whether the match was produced with trace data
+			if let Some(trace) = &evm_monitor_match.trace {
+				data_json["trace"] = json!({ "status": trace.status.as_str() });
+			}
+
+			// Add the audit of the matched conditions
+			if !evm_monitor_match.matched_on_audit.is_empty() {
+				data_json["audit"] = json!(evm_monitor_match.matched_on_audit);
+			}
+
 			// Process matched functions
 			let functions = data_json["functions"].as_array_mut().unwrap();
 			for (i, func) in evm_monitor_match.matched_on.functions.iter().enumerate() {
@@ -129,21 +427,7 @@ pub async fn handle_match<T: TriggerExecutionServiceTrait>(
 				events.push(event_data);
 			}
 
-			// Swallow any errors since it's logged in the trigger service and we want to continue
-			// processing other matches
-			let _ = trigger_service
-				.execute(
-					&evm_monitor_match
-						.monitor
-						.triggers
-						.iter()
-						.map(|s| s.to_string())
-						.collect::<Vec<_>>(),
-					json_to_hashmap(&data_json),
-					&matching_monitor,
-					trigger_scripts,
-				)
-				.await;
+			data_json
 		}
 		MonitorMatch::Stellar(stellar_monitor_match) => {
 			let transaction = stellar_monitor_match.transaction.clone();
@@ -152,6 +436,7 @@ pub async fn handle_match<T: TriggerExecutionServiceTrait>(
 			let mut data_json = json!({
 				"monitor": {
 					"name": stellar_monitor_match.monitor.name.clone(),
+					"severity": stellar_monitor_match.monitor.severity.as_str(),
 				},
 				"transaction": {
 					"hash": transaction.hash().to_string(),
@@ -160,6 +445,14 @@ pub async fn handle_match<T: TriggerExecutionServiceTrait>(
 				"events": []
 			});
 
+			// Flag the matches found without some of the data of the ledger
+			data_json["data"] = json!({
+				"complete": stellar_monitor_match.missing_data.is_empty(),
+			});
+			if !stellar_monitor_match.missing_data.is_empty() {
+				data_json["data"]["missing"] = json!(stellar_monitor_match.missing_data.join(","));
+			}
+
 			// Process matched functions
 			let functions = data_json["functions"].as_array_mut().unwrap();
 			for (i, func) in stellar_monitor_match
@@ -215,21 +508,7 @@ pub async fn handle_match<T: TriggerExecutionServiceTrait>(
 				events.push(event_data);
 			}
 
-			// Swallow any errors since it's logged in the trigger service and we want to continue
-			// processing other matches
-			let _ = trigger_service
-				.execute(
-					&stellar_monitor_match
-						.monitor
-						.triggers
-						.iter()
-						.map(|s| s.to_string())
-						.collect::<Vec<_>>(),
-					json_to_hashmap(&data_json),
-					&matching_monitor,
-					trigger_scripts,
-				)
-				.await;
+			data_json
 		}
 		MonitorMatch::Midnight(midnight_monitor_match) => {
 			let transaction = midnight_monitor_match.transaction.clone();
@@ -238,6 +517,7 @@ pub async fn handle_match<T: TriggerExecutionServiceTrait>(
 			let mut data_json = json!({
 				"monitor": {
 					"name": midnight_monitor_match.monitor.name.clone(),
+					"severity": midnight_monitor_match.monitor.severity.as_str(),
 				},
 				"transaction": {
 					"hash": transaction.hash().to_string(),
@@ -301,21 +581,7 @@ pub async fn handle_match<T: TriggerExecutionServiceTrait>(
 				events.push(event_data);
 			}
 
-			// Swallow any errors since it's logged in the trigger service and we want to continue
-			// processing other matches
-			let _ = trigger_service
-				.execute(
-					&midnight_monitor_match
-						.monitor
-						.triggers
-						.iter()
-						.map(|s| s.to_string())
-						.collect::<Vec<_>>(),
-					json_to_hashmap(&data_json),
-					&matching_monitor,
-					trigger_scripts,
-				)
-				.await;
+			data_json
 		}
 		MonitorMatch::Solana(solana_monitor_match) => {
 			let transaction = solana_monitor_match.transaction.clone();
@@ -324,6 +590,7 @@ pub async fn handle_match<T: TriggerExecutionServiceTrait>(
 			let mut data_json = json!({
 				"monitor": {
 					"name": solana_monitor_match.monitor.name.clone(),
+					"severity": solana_monitor_match.monitor.severity.as_str(),
 				},
 				"transaction": {
 					"signature": transaction.signature().to_string(),
@@ -382,28 +649,13 @@ pub async fn handle_match<T: TriggerExecutionServiceTrait>(
 				events.push(event_data);
 			}
 
-			// Swallow any errors since it's logged in the trigger service and we want to continue
-			// processing other matches
-			let _ = trigger_service
-				.execute(
-					&solana_monitor_match
-						.monitor
-						.triggers
-						.iter()
-						.map(|s| s.to_string())
-						.collect::<Vec<_>>(),
-					json_to_hashmap(&data_json),
-					&matching_monitor,
-					trigger_scripts,
-				)
-				.await;
+			data_json
 		}
 	}
-	Ok(())
 }
 
 /// Converts a JsonValue to a flattened HashMap with dotted path notation
-fn json_to_hashmap(json: &JsonValue) -> HashMap<String, String> {
+pub fn json_to_hashmap(json: &JsonValue) -> HashMap<String, String> {
 	let mut result = HashMap::new();
 	flatten_json_path(json, "", &mut result);
 	result
@@ -448,7 +700,186 @@ fn insert_primitive<T: ToString>(prefix: &str, result: &mut HashMap<String, Stri
 #[cfg(test)]
 mod tests {
 	use super::*;
-	use serde_json::json;
+	use crate::{
+		models::{
+			EVMMatchArguments, EVMMatchParamEntry, EVMMatchParamsMap, EVMMonitorMatch,
+			EVMTraceStatus, EVMTransactionTrace, EventCondition, MatchConditions, Monitor,
+		},
+		utils::tests::builders::evm::{
+			monitor::MonitorBuilder, receipt::ReceiptBuilder, transaction::TransactionBuilder,
+		},
+	};
+	use alloy::primitives::{B256, U256};
+	use serde_json::{json, Map};
+
+	fn transfer_match(monitor: &Monitor, hash: u8, values: &[&str]) -> MonitorMatch {
+		let signature = "Transfer(address,address,uint256)".to_string();
+		MonitorMatch::EVM(Box::new(EVMMonitorMatch {
+			monitor: monitor.clone(),
+			transaction: TransactionBuilder::new()
+				.hash(B256::with_last_byte(hash))
+				.build(),
+			receipt: None,
+			logs: None,
+			network_slug: "ethereum_mainnet".to_string(),
+			matched_on: MatchConditions {
+				events: values
+					.iter()
+					.map(|_| EventCondition {
+						signature: signature.clone(),
+						expression: None,
+					})
+					.collect(),
+				..Default::default()
+			},
+			matched_on_audit: Vec::new(),
+			matched_on_args: Some(EVMMatchArguments {
+				functions: None,
+				events: Some(
+					values
+						.iter()
+						.map(|value| EVMMatchParamsMap {
+							signature: signature.clone(),
+							args: Some(vec![EVMMatchParamEntry {
+								name: "value".to_string(),
+								value: value.to_string(),
+								kind: "uint256".to_string(),
+								indexed: false,
+							}]),
+							hex_signature: None,
+						})
+						.collect(),
+				),
+			}),
+			block_timestamp: None,
+			trace: None,
+		}))
+	}
+
+	#[test]
+	fn test_normalize_match() {
+		let monitor = MonitorBuilder::new().name("Transfers").build();
+		let normalized = normalize_match(&transfer_match(&monitor, 1, &["300"]));
+
+		assert_eq!(normalized.schema_version, NORMALIZED_MATCH_SCHEMA_VERSION);
+		assert_eq!(normalized.chain, "evm");
+		assert_eq!(normalized.network, "ethereum_mainnet");
+		assert_eq!(normalized.monitor.name, "Transfers");
+		assert_eq!(
+			normalized.transaction.id,
+			b256_to_string(B256::with_last_byte(1))
+		);
+		assert!(normalized.transaction.from.is_some());
+		assert_eq!(normalized.functions, vec![]);
+		assert_eq!(
+			normalized.events,
+			vec![NormalizedCall {
+				signature: "Transfer(address,address,uint256)".to_string(),
+				args: Map::from_iter([("value".to_string(), json!("300"))]),
+			}]
+		);
+
+		let json = serde_json::to_value(&normalized).unwrap();
+		assert_eq!(json["schema_version"], NORMALIZED_MATCH_SCHEMA_VERSION);
+		assert_eq!(json["monitor"]["severity"], "medium");
+		assert_eq!(json["events"][0]["args"]["value"], "300");
+		assert!(json["transaction"].get("fee").is_none());
+		assert!(json.get("extensions").is_none());
+	}
+
+	#[test]
+	fn test_normalize_match_fee_and_extensions() {
+		let monitor = MonitorBuilder::new().name("Transfers").build();
+		let mut monitor_match = transfer_match(&monitor, 1, &["300"]);
+		if let MonitorMatch::EVM(evm_match) = &mut monitor_match {
+			evm_match.receipt = Some(
+				ReceiptBuilder::new()
+					.gas_used(U256::from(21_000))
+					.effective_gas_price(U256::from(2))
+					.build(),
+			);
+			evm_match.block_timestamp = Some(1_700_000_000);
+		}
+
+		let normalized = normalize_match(&monitor_match);
+		assert_eq!(normalized.transaction.fee.as_deref(), Some("42000"));
+		assert_eq!(
+			normalized.extensions.keys().collect::<Vec<_>>(),
+			vec!["block"]
+		);
+		assert_eq!(normalized.extensions["block"]["timestamp"], 1_700_000_000);
+	}
+
+	#[test]
+	fn test_match_template_json_block() {
+		let monitor = MonitorBuilder::new().name("Transfers").build();
+		let mut monitor_match = transfer_match(&monitor, 1, &["300"]);
+		assert!(!json_to_hashmap(&match_template_json(&monitor_match)).contains_key("block.age"));
+
+		let timestamp = Utc::now().timestamp() as u64 - 3_600;
+		if let MonitorMatch::EVM(evm_match) = &mut monitor_match {
+			evm_match.block_timestamp = Some(timestamp);
+		}
+		let variables = json_to_hashmap(&match_template_json(&monitor_match));
+		assert_eq!(variables["block.timestamp"], timestamp.to_string());
+		let age: u64 = variables["block.age"].parse().unwrap();
+		assert!((3_600..3_700).contains(&age));
+	}
+
+	#[test]
+	fn test_match_template_json_trace() {
+		let monitor = MonitorBuilder::new().name("Transfers").build();
+		let mut monitor_match = transfer_match(&monitor, 1, &["300"]);
+		assert!(!json_to_hashmap(&match_template_json(&monitor_match)).contains_key("trace.status"));
+
+		if let MonitorMatch::EVM(evm_match) = &mut monitor_match {
+			evm_match.trace = Some(EVMTransactionTrace {
+				status: EVMTraceStatus::OverBudget,
+				internal_transfers: Vec::new(),
+			});
+		}
+		let variables = json_to_hashmap(&match_template_json(&monitor_match));
+		assert_eq!(variables["trace.status"], "over_budget");
+	}
+
+	#[test]
+	fn test_batch_template_json() {
+		let monitor = MonitorBuilder::new()
+			.name("Batched")
+			.batch(Some("value"))
+			.build();
+		let matches = vec![
+			transfer_match(&monitor, 1, &["300", "100"]),
+			transfer_match(&monitor, 2, &["not a number", "200"]),
+		];
+
+		let variables = json_to_hashmap(&batch_template_json(&matches));
+		assert_eq!(variables["monitor.name"], "Batched");
+		assert_eq!(
+			variables["transaction.hash"],
+			b256_to_string(B256::with_last_byte(1))
+		);
+		assert_eq!(variables["events.3.args.value"], "200");
+		assert_eq!(variables["batch.count"], "4");
+		assert_eq!(variables["batch.matches"], "2");
+		assert_eq!(
+			variables["batch.transactions.1"],
+			b256_to_string(B256::with_last_byte(2))
+		);
+		assert_eq!(variables["batch.aggregate.min"], "100");
+		assert_eq!(variables["batch.aggregate.max"], "300");
+		assert_eq!(variables["batch.aggregate.sum"], "600");
+
+		// No aggregate without an argument
+		let monitor = MonitorBuilder::new().name("Batched").batch(None).build();
+		let variables = json_to_hashmap(&batch_template_json(&[transfer_match(
+			&monitor,
+			1,
+			&["300"],
+		)]));
+		assert_eq!(variables["batch.count"], "1");
+		assert!(!variables.contains_key("batch.aggregate.sum"));
+	}
 
 	#[test]
 	fn test_json_to_hashmap() {
@@ -0,0 +1,205 @@
+//! Execution audit of EVM matches.
+//!
+//! Records, for every condition a monitor matched on, the expression that was evaluated, the
+//! values of the operands it compared and the monitored address that triggered it. The audit
+//! is attached to the match (`matched_on_audit`) and exposed to templates, to debug why a
+//! monitor fired.
+
+use crate::{
+	models::{
+		ConditionAudit, EVMMatchArguments, EVMMatchParamEntry, EVMReceiptLog, EVMTransaction,
+		MatchConditions, OperandAudit,
+	},
+	services::filter::{
+		evm_helpers::{are_same_address, b256_to_string, h160_to_string},
+		expression,
+		filters::evm::evaluator::EVMConditionEvaluator,
+	},
+};
+
+/// Builds the audit of the conditions of a match
+///
+/// # Arguments
+/// * `matched_on` - Conditions the monitor matched on
+/// * `matched_on_args` - Decoded arguments of the matched functions and events
+/// * `transaction` - The matched transaction
+/// * `logs` - Logs of the transaction
+/// * `tx_params` - Parameters available to transaction condition expressions
+/// * `monitored_addresses` - Addresses watched by the monitor
+///
+/// # Returns
+/// One audit entry per matched condition
+pub fn build_audit(
+	matched_on: &MatchConditions,
+	matched_on_args: &EVMMatchArguments,
+	transaction: &EVMTransaction,
+	logs: &[EVMReceiptLog],
+	tx_params: &[EVMMatchParamEntry],
+	monitored_addresses: &[String],
+) -> Vec<ConditionAudit> {
+	let is_monitored = |address: &str| {
+		monitored_addresses
+			.iter()
+			.any(|monitored| are_same_address(monitored, address))
+	};
+	let mut audit = Vec::new();
+
+	let matched_functions = matched_on_args.functions.as_deref().unwrap_or_default();
+	for (i, function) in matched_on.functions.iter().enumerate() {
+		let args = matched_functions
+			.get(i)
+			.and_then(|f| f.args.as_deref())
+			.unwrap_or_default();
+		audit.push(ConditionAudit {
+			kind: "function".to_string(),
+			signature: Some(function.signature.clone()),
+			expression: function.expression.clone(),
+			operands: audit_expression(function.expression.as_deref(), args),
+			address: transaction.to.map(h160_to_string),
+		});
+	}
+
+	let matched_events = matched_on_args.events.as_deref().unwrap_or_default();
+	for (i, event) in matched_on.events.iter().enumerate() {
+		let params = matched_events.get(i);
+		let args = params.and_then(|e| e.args.as_deref()).unwrap_or_default();
+		// The emitter is the first monitored contract with a log of this event
+		let address = params
+			.and_then(|e| e.hex_signature.as_deref())
+			.and_then(|topic| {
+				logs.iter().find(|log| {
+					log.topics
+						.first()
+						.is_some_and(|t| b256_to_string(*t).eq_ignore_ascii_case(topic))
+						&& is_monitored(&h160_to_string(log.address))
+				})
+			})
+			.map(|log| h160_to_string(log.address));
+		audit.push(ConditionAudit {
+			kind: "event".to_string(),
+			signature: Some(event.signature.clone()),
+			expression: event.expression.clone(),
+			operands: audit_expression(event.expression.as_deref(), args),
+			address,
+		});
+	}
+
+	for condition in &matched_on.transactions {
+		let address = [transaction.to, transaction.from]
+			.into_iter()
+			.flatten()
+			.map(h160_to_string)
+			.find(|address| is_monitored(address));
+		audit.push(ConditionAudit {
+			kind: "transaction".to_string(),
+			signature: None,
+			expression: condition.expression.clone(),
+			operands: audit_expression(condition.expression.as_deref(), tx_params),
+			address,
+		});
+	}
+
+	audit
+}
+
+/// Re-evaluates an expression, recording the comparisons it performs
+fn audit_expression(expression: Option<&str>, args: &[EVMMatchParamEntry]) -> Vec<OperandAudit> {
+	let Some(expression) = expression.filter(|e| !e.trim().is_empty()) else {
+		return Vec::new();
+	};
+	let Ok(parsed) = expression::parse(expression) else {
+		return Vec::new();
+	};
+	expression::evaluate_with_audit(&parsed, &EVMConditionEvaluator::new(args))
+		.map(|(_, operands)| operands)
+		.unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		models::{EVMMatchParamsMap, EventCondition, TransactionCondition, TransactionStatus},
+		utils::tests::evm::transaction::TransactionBuilder,
+	};
+	use alloy::primitives::{Address, B256, U256};
+	use std::str::FromStr;
+
+	const CONTRACT: &str = "0x0000000000000000000000000000000000004321";
+	const TRANSFER_TOPIC: &str =
+		"0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef";
+
+	fn param(name: &str, value: &str, kind: &str) -> EVMMatchParamEntry {
+		EVMMatchParamEntry {
+			name: name.to_string(),
+			value: value.to_string(),
+			kind: kind.to_string(),
+			indexed: false,
+		}
+	}
+
+	#[test]
+	fn test_build_audit() {
+		let contract = Address::from_str(CONTRACT).unwrap();
+		let transaction = TransactionBuilder::new()
+			.to(contract)
+			.value(U256::from(5))
+			.build();
+		let log = EVMReceiptLog {
+			address: contract,
+			topics: vec![B256::from_str(TRANSFER_TOPIC).unwrap()],
+			data: Default::default(),
+			block_hash: None,
+			block_number: None,
+			transaction_hash: None,
+			transaction_index: None,
+			log_index: None,
+			transaction_log_index: None,
+			log_type: None,
+			removed: None,
+		};
+		let matched_on = MatchConditions {
+			functions: vec![],
+			events: vec![EventCondition {
+				signature: "Transfer(address,address,uint256)".to_string(),
+				expression: Some("value > 100".to_string()),
+			}],
+			transactions: vec![TransactionCondition {
+				status: TransactionStatus::Success,
+				expression: Some("value == 5".to_string()),
+			}],
+		};
+		let matched_on_args = EVMMatchArguments {
+			functions: None,
+			events: Some(vec![EVMMatchParamsMap {
+				signature: "Transfer(address,address,uint256)".to_string(),
+				args: Some(vec![param("value", "150", "uint256")]),
+				hex_signature: Some(TRANSFER_TOPIC.to_string()),
+			}]),
+		};
+		let tx_params = vec![param("value", "5", "uint256")];
+
+		let audit = build_audit(
+			&matched_on,
+			&matched_on_args,
+			&transaction,
+			&[log],
+			&tx_params,
+			&[CONTRACT.to_string()],
+		);
+
+		assert_eq!(audit.len(), 2);
+		assert_eq!(audit[0].kind, "event");
+		assert_eq!(audit[0].address.as_deref(), Some(CONTRACT));
+		assert_eq!(audit[0].operands.len(), 1);
+		assert_eq!(audit[0].operands[0].variable, "value");
+		assert_eq!(audit[0].operands[0].value, "150");
+		assert_eq!(audit[0].operands[0].operator, ">");
+		assert_eq!(audit[0].operands[0].literal, "100");
+		assert!(audit[0].operands[0].result);
+
+		assert_eq!(audit[1].kind, "transaction");
+		assert_eq!(audit[1].address.as_deref(), Some(CONTRACT));
+		assert_eq!(audit[1].operands[0].value, "5");
+	}
+}
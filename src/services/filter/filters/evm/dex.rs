@@ -0,0 +1,594 @@
+//! Built-in decoding for common DEX pool events.
+//!
+//! Uniswap v2 and v3 style pools emit a small, stable set of events (`Swap`, `Mint`, `Burn`)
+//! that are shared by most of their forks. This module provides:
+//! - Event definitions used to decode these logs when the pool ABI is not configured
+//! - Computed fields (swap direction, amounts in/out, execution price and price impact)
+//!   that are appended to the arguments decoded with these definitions so they can be used
+//!   in match expressions and notification templates like any other event argument
+
+use alloy::core::json_abi::Event;
+use alloy::primitives::{B256, I256, U256};
+use lazy_static::lazy_static;
+
+use crate::{
+	models::{EVMMatchParamEntry, EVMReceiptLog},
+	services::filter::evm_helpers::{string_to_i256, string_to_u256},
+};
+
+/// Human-readable definitions of the supported DEX events
+const UNISWAP_V2_SWAP: &str = "event Swap(address indexed sender, uint256 amount0In, uint256 amount1In, uint256 amount0Out, uint256 amount1Out, address indexed to)";
+const UNISWAP_V2_MINT: &str =
+	"event Mint(address indexed sender, uint256 amount0, uint256 amount1)";
+const UNISWAP_V2_BURN: &str =
+	"event Burn(address indexed sender, uint256 amount0, uint256 amount1, address indexed to)";
+const UNISWAP_V2_SYNC: &str = "event Sync(uint112 reserve0, uint112 reserve1)";
+const UNISWAP_V3_SWAP: &str = "event Swap(address indexed sender, address indexed recipient, int256 amount0, int256 amount1, uint160 sqrtPriceX96, uint128 liquidity, int24 tick)";
+const UNISWAP_V3_MINT: &str = "event Mint(address sender, address indexed owner, int24 indexed tickLower, int24 indexed tickUpper, uint128 amount, uint256 amount0, uint256 amount1)";
+const UNISWAP_V3_BURN: &str = "event Burn(address indexed owner, int24 indexed tickLower, int24 indexed tickUpper, uint128 amount, uint256 amount0, uint256 amount1)";
+
+lazy_static! {
+	/// Parsed DEX events indexed by kind
+	static ref DEX_EVENTS: Vec<(DexEventKind, Event)> = [
+		(DexEventKind::UniswapV2Swap, UNISWAP_V2_SWAP),
+		(DexEventKind::UniswapV2Mint, UNISWAP_V2_MINT),
+		(DexEventKind::UniswapV2Burn, UNISWAP_V2_BURN),
+		(DexEventKind::UniswapV3Swap, UNISWAP_V3_SWAP),
+		(DexEventKind::UniswapV3Mint, UNISWAP_V3_MINT),
+		(DexEventKind::UniswapV3Burn, UNISWAP_V3_BURN),
+	]
+	.into_iter()
+	.map(|(kind, definition)| {
+		(
+			kind,
+			Event::parse(definition).expect("built-in DEX event definition must be valid"),
+		)
+	})
+	.collect();
+
+	/// Topic of the Uniswap v2 `Sync` event, used to recover pool reserves around a swap
+	static ref UNISWAP_V2_SYNC_TOPIC: B256 = Event::parse(UNISWAP_V2_SYNC)
+		.expect("built-in DEX event definition must be valid")
+		.selector();
+}
+
+/// Supported DEX pool events
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DexEventKind {
+	UniswapV2Swap,
+	UniswapV2Mint,
+	UniswapV2Burn,
+	UniswapV3Swap,
+	UniswapV3Mint,
+	UniswapV3Burn,
+}
+
+impl DexEventKind {
+	/// Returns the protocol family the event belongs to
+	pub fn protocol(&self) -> &'static str {
+		match self {
+			Self::UniswapV2Swap | Self::UniswapV2Mint | Self::UniswapV2Burn => "uniswap_v2",
+			Self::UniswapV3Swap | Self::UniswapV3Mint | Self::UniswapV3Burn => "uniswap_v3",
+		}
+	}
+
+	/// Identifies a DEX event from the first topic of a log
+	///
+	/// # Arguments
+	/// * `topic` - The event selector (topic0) of the log
+	///
+	/// # Returns
+	/// The matching event kind, or None if the topic is not a supported DEX event
+	pub fn from_topic(topic: &B256) -> Option<Self> {
+		DEX_EVENTS
+			.iter()
+			.find(|(_, event)| event.selector() == *topic)
+			.map(|(kind, _)| *kind)
+	}
+}
+
+/// Returns the built-in event definition matching a log, if any
+///
+/// # Arguments
+/// * `log` - The log to look up
+///
+/// # Returns
+/// The built-in event definition for the log's topic0, or None if the log is not a
+/// supported DEX event
+pub fn builtin_event(log: &EVMReceiptLog) -> Option<&'static Event> {
+	let topic = log.topics.first()?;
+	DEX_EVENTS
+		.iter()
+		.find(|(_, event)| event.selector() == *topic)
+		.map(|(_, event)| event)
+}
+
+/// Determines the direction of a swap directly from the raw log data
+///
+/// # Arguments
+/// * `log` - The log to inspect
+///
+/// # Returns
+/// `Some(true)` when token0 is sold for token1, `Some(false)` for the opposite direction,
+/// or None if the log is not a supported swap event
+pub fn swap_direction(log: &EVMReceiptLog) -> Option<bool> {
+	let first_word: [u8; 32] = log.data.get(0..32)?.try_into().ok()?;
+	match log.topics.first().and_then(DexEventKind::from_topic)? {
+		// amount0In is the first non-indexed parameter
+		DexEventKind::UniswapV2Swap => Some(U256::from_be_bytes(first_word) > U256::ZERO),
+		// amount0 is positive when token0 is paid into the pool
+		DexEventKind::UniswapV3Swap => Some(I256::from_be_bytes(first_word).is_positive()),
+		_ => None,
+	}
+}
+
+/// Computes derived fields for a DEX event decoded with its built-in definition
+///
+/// Events decoded with a user supplied ABI do not get the computed fields, which could
+/// otherwise shadow parameters of the same name. Fields that cannot be derived (e.g. price
+/// impact of a v2 swap without a matching `Sync` log, or from a zero price) are omitted.
+///
+/// Computed fields:
+/// * `dex_protocol` - `uniswap_v2` or `uniswap_v3`
+/// * `zero_for_one` - Swaps only, `true` when token0 is sold for token1
+/// * `amount_in` / `amount_out` - Swaps only, absolute amounts entering and leaving the pool
+/// * `execution_price` - Average price of token0 in token1 raw units
+/// * `spot_price` - v3 swaps only, pool price after the swap derived from `sqrtPriceX96`
+/// * `price_impact_bps` - Swaps only, price movement caused by the swap in basis points
+///
+/// # Arguments
+/// * `args` - Event arguments decoded with the definition returned by [`builtin_event`]
+/// * `log` - The log the arguments were decoded from
+/// * `tx_logs` - All logs emitted by the same transaction
+///
+/// # Returns
+/// The computed fields, or an empty vector if the log is not a supported DEX event
+pub fn computed_fields(
+	args: &[EVMMatchParamEntry],
+	log: &EVMReceiptLog,
+	tx_logs: &[EVMReceiptLog],
+) -> Vec<EVMMatchParamEntry> {
+	let Some(kind) = log.topics.first().and_then(DexEventKind::from_topic) else {
+		return Vec::new();
+	};
+
+	let mut fields = vec![param("dex_protocol", kind.protocol(), "string")];
+
+	match kind {
+		DexEventKind::UniswapV2Swap => {
+			let (Some(amount0_in), Some(amount1_in), Some(amount0_out), Some(amount1_out)) = (
+				uint_arg(args, 1),
+				uint_arg(args, 2),
+				uint_arg(args, 3),
+				uint_arg(args, 4),
+			) else {
+				return fields;
+			};
+			let zero_for_one = amount0_in > U256::ZERO;
+			let (amount_in, amount_out) = if zero_for_one {
+				(amount0_in, amount1_out)
+			} else {
+				(amount1_in, amount0_out)
+			};
+			push_swap_fields(&mut fields, zero_for_one, amount_in, amount_out);
+
+			// The pool emits Sync with the post-swap reserves right before Swap, which lets us
+			// recover the pre-swap reserves and measure how far the price moved.
+			if let Some((reserve0, reserve1)) = preceding_sync_reserves(log, tx_logs) {
+				let pre_reserve0 = reserve0
+					.checked_add(amount0_out)
+					.and_then(|r| r.checked_sub(amount0_in));
+				let pre_reserve1 = reserve1
+					.checked_add(amount1_out)
+					.and_then(|r| r.checked_sub(amount1_in));
+				if let (Some(pre_reserve0), Some(pre_reserve1)) = (pre_reserve0, pre_reserve1) {
+					if let (Some(pre_price), Some(post_price)) = (
+						ratio(f64::from(pre_reserve1), f64::from(pre_reserve0)),
+						ratio(f64::from(reserve1), f64::from(reserve0)),
+					) {
+						fields.extend(price_impact_field(pre_price, post_price));
+					}
+				}
+			}
+		}
+		DexEventKind::UniswapV3Swap => {
+			let (Some(amount0), Some(amount1)) = (int_arg(args, 2), int_arg(args, 3)) else {
+				return fields;
+			};
+			// Positive amounts are owed to the pool, negative amounts leave it
+			let zero_for_one = amount0.is_positive();
+			let (amount_in, amount_out) = if zero_for_one {
+				(amount0.unsigned_abs(), amount1.unsigned_abs())
+			} else {
+				(amount1.unsigned_abs(), amount0.unsigned_abs())
+			};
+			push_swap_fields(&mut fields, zero_for_one, amount_in, amount_out);
+
+			// v3 swaps only report the post-swap price, so the impact is measured between the
+			// average execution price and the resulting spot price.
+			if let Some(sqrt_price_x96) = uint_arg(args, 4) {
+				let sqrt_price = f64::from(sqrt_price_x96) / 2f64.powi(96);
+				let spot_price = sqrt_price * sqrt_price;
+				fields.push(param("spot_price", &format_price(spot_price), "string"));
+				if let Some(execution_price) = ratio(
+					f64::from(amount1.unsigned_abs()),
+					f64::from(amount0.unsigned_abs()),
+				) {
+					fields.extend(price_impact_field(execution_price, spot_price));
+				}
+			}
+		}
+		DexEventKind::UniswapV2Mint
+		| DexEventKind::UniswapV2Burn
+		| DexEventKind::UniswapV3Mint
+		| DexEventKind::UniswapV3Burn => {
+			// Liquidity is added and removed at the pool price, so the ratio of the token
+			// amounts gives the price at which the position was changed
+			let (amount0_index, amount1_index) = match kind {
+				DexEventKind::UniswapV3Mint => (5, 6),
+				DexEventKind::UniswapV3Burn => (4, 5),
+				_ => (1, 2),
+			};
+			if let (Some(amount0), Some(amount1)) =
+				(uint_arg(args, amount0_index), uint_arg(args, amount1_index))
+			{
+				if let Some(price) = ratio(f64::from(amount1), f64::from(amount0)) {
+					fields.push(param("execution_price", &format_price(price), "string"));
+				}
+			}
+		}
+	}
+
+	fields
+}
+
+/// Appends the direction, amounts and execution price of a swap
+fn push_swap_fields(
+	fields: &mut Vec<EVMMatchParamEntry>,
+	zero_for_one: bool,
+	amount_in: U256,
+	amount_out: U256,
+) {
+	fields.push(param("zero_for_one", &zero_for_one.to_string(), "bool"));
+	fields.push(param("amount_in", &amount_in.to_string(), "uint256"));
+	fields.push(param("amount_out", &amount_out.to_string(), "uint256"));
+
+	// Express the price as token1 per token0 regardless of the swap direction
+	let (amount0, amount1) = if zero_for_one {
+		(amount_in, amount_out)
+	} else {
+		(amount_out, amount_in)
+	};
+	if let Some(price) = ratio(f64::from(amount1), f64::from(amount0)) {
+		fields.push(param("execution_price", &format_price(price), "string"));
+	}
+}
+
+/// Finds the reserves reported by the last `Sync` log of the same pool before `log`
+fn preceding_sync_reserves(log: &EVMReceiptLog, tx_logs: &[EVMReceiptLog]) -> Option<(U256, U256)> {
+	tx_logs
+		.iter()
+		.take_while(|candidate| match (candidate.log_index, log.log_index) {
+			(Some(candidate_index), Some(index)) => candidate_index < index,
+			_ => !std::ptr::eq(*candidate, log),
+		})
+		.filter(|candidate| {
+			candidate.address == log.address
+				&& candidate.topics.first() == Some(&*UNISWAP_V2_SYNC_TOPIC)
+				&& candidate.data.len() >= 64
+		})
+		.last()
+		.map(|sync| {
+			(
+				U256::from_be_slice(&sync.data[0..32]),
+				U256::from_be_slice(&sync.data[32..64]),
+			)
+		})
+}
+
+/// Builds the `price_impact_bps` field from the price before and after a swap
+///
+/// Returns `None` if the impact is undefined, the price before the swap being zero.
+fn price_impact_field(before: f64, after: f64) -> Option<EVMMatchParamEntry> {
+	let impact_bps = (ratio(after, before)? - 1.0).abs() * 10_000.0;
+	if !impact_bps.is_finite() {
+		return None;
+	}
+	Some(param(
+		"price_impact_bps",
+		&(impact_bps.round() as u64).to_string(),
+		"uint256",
+	))
+}
+
+fn param(name: &str, value: &str, kind: &str) -> EVMMatchParamEntry {
+	EVMMatchParamEntry {
+		name: name.to_string(),
+		value: value.to_string(),
+		kind: kind.to_string(),
+		indexed: false,
+	}
+}
+
+fn uint_arg(args: &[EVMMatchParamEntry], index: usize) -> Option<U256> {
+	args.get(index)
+		.and_then(|arg| string_to_u256(&arg.value).ok())
+}
+
+fn int_arg(args: &[EVMMatchParamEntry], index: usize) -> Option<I256> {
+	args.get(index)
+		.and_then(|arg| string_to_i256(&arg.value).ok())
+}
+
+fn ratio(numerator: f64, denominator: f64) -> Option<f64> {
+	if denominator == 0.0 || !numerator.is_finite() || !denominator.is_finite() {
+		return None;
+	}
+	Some(numerator / denominator)
+}
+
+fn format_price(price: f64) -> String {
+	price.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use alloy::primitives::{Address, Bytes};
+
+	fn word(value: U256) -> [u8; 32] {
+		value.to_be_bytes::<32>()
+	}
+
+	fn address_topic(address: Address) -> B256 {
+		B256::from_slice(&[&[0u8; 12], address.as_slice()].concat())
+	}
+
+	fn create_log(
+		pool: Address,
+		topics: Vec<B256>,
+		data: Vec<u8>,
+		log_index: u64,
+	) -> EVMReceiptLog {
+		EVMReceiptLog {
+			address: pool,
+			topics,
+			data: Bytes::from(data),
+			block_hash: None,
+			block_number: None,
+			transaction_hash: None,
+			transaction_index: None,
+			log_index: Some(U256::from(log_index)),
+			transaction_log_index: None,
+			log_type: None,
+			removed: Some(false),
+		}
+	}
+
+	fn arg(name: &str, value: &str, kind: &str) -> EVMMatchParamEntry {
+		param(name, value, kind)
+	}
+
+	fn field<'a>(fields: &'a [EVMMatchParamEntry], name: &str) -> Option<&'a str> {
+		fields
+			.iter()
+			.find(|f| f.name == name)
+			.map(|f| f.value.as_str())
+	}
+
+	fn v2_swap_args(
+		amount0_in: u64,
+		amount1_in: u64,
+		amount0_out: u64,
+		amount1_out: u64,
+	) -> Vec<EVMMatchParamEntry> {
+		vec![
+			arg(
+				"sender",
+				"0x0000000000000000000000000000000000000001",
+				"address",
+			),
+			arg("amount0In", &amount0_in.to_string(), "uint256"),
+			arg("amount1In", &amount1_in.to_string(), "uint256"),
+			arg("amount0Out", &amount0_out.to_string(), "uint256"),
+			arg("amount1Out", &amount1_out.to_string(), "uint256"),
+			arg(
+				"to",
+				"0x0000000000000000000000000000000000000002",
+				"address",
+			),
+		]
+	}
+
+	fn v2_swap_log(pool: Address, log_index: u64) -> EVMReceiptLog {
+		let event = Event::parse(UNISWAP_V2_SWAP).unwrap();
+		create_log(
+			pool,
+			vec![
+				event.selector(),
+				address_topic(Address::with_last_byte(1)),
+				address_topic(Address::with_last_byte(2)),
+			],
+			Vec::new(),
+			log_index,
+		)
+	}
+
+	fn sync_log(pool: Address, reserve0: u64, reserve1: u64, log_index: u64) -> EVMReceiptLog {
+		create_log(
+			pool,
+			vec![*UNISWAP_V2_SYNC_TOPIC],
+			[word(U256::from(reserve0)), word(U256::from(reserve1))].concat(),
+			log_index,
+		)
+	}
+
+	#[test]
+	fn test_builtin_event_recognizes_dex_topics() {
+		let pool = Address::with_last_byte(9);
+		let log = v2_swap_log(pool, 0);
+		let event = builtin_event(&log).unwrap();
+		assert_eq!(event.name, "Swap");
+		assert_eq!(
+			DexEventKind::from_topic(&log.topics[0]),
+			Some(DexEventKind::UniswapV2Swap)
+		);
+
+		let unknown = create_log(pool, vec![B256::ZERO], Vec::new(), 0);
+		assert!(builtin_event(&unknown).is_none());
+		assert!(computed_fields(&[], &unknown, &[]).is_empty());
+	}
+
+	#[test]
+	fn test_v2_swap_computed_fields_with_sync() {
+		let pool = Address::with_last_byte(9);
+		// Pre-swap reserves 1000/2000, user sells 100 token0 for 180 token1
+		let sync = sync_log(pool, 1100, 1820, 0);
+		let swap = v2_swap_log(pool, 1);
+		let tx_logs = vec![sync, swap.clone()];
+
+		let fields = computed_fields(&v2_swap_args(100, 0, 0, 180), &swap, &tx_logs);
+
+		assert_eq!(field(&fields, "dex_protocol"), Some("uniswap_v2"));
+		assert_eq!(field(&fields, "zero_for_one"), Some("true"));
+		assert_eq!(field(&fields, "amount_in"), Some("100"));
+		assert_eq!(field(&fields, "amount_out"), Some("180"));
+		assert_eq!(field(&fields, "execution_price"), Some("1.8"));
+		// Price moves from 2.0 to 1820/1100 (~1.6545), i.e. ~1727 bps
+		assert_eq!(field(&fields, "price_impact_bps"), Some("1727"));
+	}
+
+	#[test]
+	fn test_v2_swap_without_sync_omits_price_impact() {
+		let pool = Address::with_last_byte(9);
+		let swap = v2_swap_log(pool, 0);
+		// A Sync from another pool must be ignored
+		let other_sync = sync_log(Address::with_last_byte(8), 1, 1, 0);
+
+		let fields = computed_fields(
+			&v2_swap_args(0, 50, 25, 0),
+			&swap,
+			&[other_sync, swap.clone()],
+		);
+
+		assert_eq!(field(&fields, "zero_for_one"), Some("false"));
+		assert_eq!(field(&fields, "amount_in"), Some("50"));
+		assert_eq!(field(&fields, "amount_out"), Some("25"));
+		assert_eq!(field(&fields, "execution_price"), Some("2"));
+		assert!(field(&fields, "price_impact_bps").is_none());
+	}
+
+	#[test]
+	fn test_v3_swap_computed_fields() {
+		let pool = Address::with_last_byte(9);
+		let event = Event::parse(UNISWAP_V3_SWAP).unwrap();
+		let swap = create_log(pool, vec![event.selector()], Vec::new(), 0);
+		// sqrtPriceX96 for a spot price of 4.0 is 2 * 2^96
+		let sqrt_price_x96: U256 = U256::from(2u64) << 96usize;
+		let args = vec![
+			arg(
+				"sender",
+				"0x0000000000000000000000000000000000000001",
+				"address",
+			),
+			arg(
+				"recipient",
+				"0x0000000000000000000000000000000000000002",
+				"address",
+			),
+			arg("amount0", "-100", "int256"),
+			arg("amount1", "500", "int256"),
+			arg("sqrtPriceX96", &sqrt_price_x96.to_string(), "uint160"),
+			arg("liquidity", "1000000", "uint128"),
+			arg("tick", "13863", "int24"),
+		];
+
+		let fields = computed_fields(&args, &swap, std::slice::from_ref(&swap));
+
+		assert_eq!(field(&fields, "dex_protocol"), Some("uniswap_v3"));
+		assert_eq!(field(&fields, "zero_for_one"), Some("false"));
+		assert_eq!(field(&fields, "amount_in"), Some("500"));
+		assert_eq!(field(&fields, "amount_out"), Some("100"));
+		assert_eq!(field(&fields, "execution_price"), Some("5"));
+		assert_eq!(field(&fields, "spot_price"), Some("4"));
+		assert_eq!(field(&fields, "price_impact_bps"), Some("2000"));
+	}
+
+	#[test]
+	fn test_swap_from_zero_price_omits_price_impact() {
+		let pool = Address::with_last_byte(9);
+		let event = Event::parse(UNISWAP_V3_SWAP).unwrap();
+		let swap = create_log(pool, vec![event.selector()], Vec::new(), 0);
+		let sqrt_price_x96: U256 = U256::from(2u64) << 96usize;
+		let args = vec![
+			arg(
+				"sender",
+				"0x0000000000000000000000000000000000000001",
+				"address",
+			),
+			arg(
+				"recipient",
+				"0x0000000000000000000000000000000000000002",
+				"address",
+			),
+			arg("amount0", "-100", "int256"),
+			arg("amount1", "0", "int256"),
+			arg("sqrtPriceX96", &sqrt_price_x96.to_string(), "uint160"),
+			arg("liquidity", "1000000", "uint128"),
+			arg("tick", "13863", "int24"),
+		];
+
+		let fields = computed_fields(&args, &swap, std::slice::from_ref(&swap));
+
+		assert_eq!(field(&fields, "execution_price"), Some("0"));
+		assert_eq!(field(&fields, "spot_price"), Some("4"));
+		assert!(field(&fields, "price_impact_bps").is_none());
+	}
+
+	#[test]
+	fn test_mint_and_burn_execution_price() {
+		let pool = Address::with_last_byte(9);
+
+		let v2_mint = create_log(
+			pool,
+			vec![Event::parse(UNISWAP_V2_MINT).unwrap().selector()],
+			Vec::new(),
+			0,
+		);
+		let args = vec![
+			arg(
+				"sender",
+				"0x0000000000000000000000000000000000000001",
+				"address",
+			),
+			arg("amount0", "10", "uint256"),
+			arg("amount1", "25", "uint256"),
+		];
+		let fields = computed_fields(&args, &v2_mint, &[]);
+		assert_eq!(field(&fields, "execution_price"), Some("2.5"));
+		assert!(field(&fields, "amount_in").is_none());
+
+		let v3_burn = create_log(
+			pool,
+			vec![Event::parse(UNISWAP_V3_BURN).unwrap().selector()],
+			Vec::new(),
+			0,
+		);
+		let args = vec![
+			arg(
+				"owner",
+				"0x0000000000000000000000000000000000000001",
+				"address",
+			),
+			arg("tickLower", "-10", "int24"),
+			arg("tickUpper", "10", "int24"),
+			arg("amount", "1000", "uint128"),
+			arg("amount0", "0", "uint256"),
+			arg("amount1", "25", "uint256"),
+		];
+		let fields = computed_fields(&args, &v3_burn, &[]);
+		assert_eq!(field(&fields, "dex_protocol"), Some("uniswap_v3"));
+		// Single-sided positions have no meaningful price
+		assert!(field(&fields, "execution_price").is_none());
+	}
+}
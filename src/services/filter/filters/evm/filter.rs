@@ -8,15 +8,19 @@
 //! - ABI-based decoding of function calls and events
 
 use alloy::core::dyn_abi::{DynSolType, DynSolValue, EventExt};
-use alloy::core::json_abi::{AbiItem, JsonAbi};
+use alloy::core::json_abi::{AbiItem, Event, JsonAbi};
 use alloy::primitives::{LogData, U64};
 use async_trait::async_trait;
-use std::marker::PhantomData;
+use chrono::Utc;
+use std::{
+	collections::{HashMap, HashSet},
+	marker::PhantomData,
+};
 use tracing::instrument;
 
 use crate::{
 	models::{
-		AddressWithSpec, BlockType, ContractSpec, EVMContractSpec, EVMMatchArguments,
+		AddressWithSpec, BlockType, ContractSpec, EVMBlock, EVMContractSpec, EVMMatchArguments,
 		EVMMatchParamEntry, EVMMatchParamsMap, EVMMonitorMatch, EVMReceiptLog, EVMTransaction,
 		EVMTransactionReceipt, EventCondition, FunctionCondition, MatchConditions, Monitor,
 		MonitorMatch, Network, TransactionCondition, TransactionStatus,
@@ -29,8 +33,11 @@ use crate::{
 				h160_to_string, normalize_address,
 			},
 			expression::{self, EvaluationError},
-			filters::evm::evaluator::EVMConditionEvaluator,
-			BlockFilter, FilterError,
+			filters::evm::{
+				audit, dex, evaluator::EVMConditionEvaluator, heuristics, interactions, nonces,
+				poisoning, traces,
+			},
+			BlockFilter, FilterContext, FilterError,
 		},
 	},
 };
@@ -38,6 +45,8 @@ use crate::{
 /// Filter implementation for EVM-compatible blockchains
 pub struct EVMBlockFilter<T> {
 	pub _client: PhantomData<T>,
+	/// State shared by the filters of the filter service
+	pub context: FilterContext,
 }
 
 impl<T> EVMBlockFilter<T> {
@@ -56,6 +65,35 @@ impl<T> EVMBlockFilter<T> {
 		tx_receipt: &Option<EVMTransactionReceipt>,
 		monitor: &Monitor,
 		matched_transactions: &mut Vec<TransactionCondition>,
+	) {
+		self.find_matching_transaction_with_params(
+			tx_status,
+			transaction,
+			tx_receipt,
+			monitor,
+			&[],
+			matched_transactions,
+		);
+	}
+
+	/// Finds transactions that match the monitor's conditions, exposing additional
+	/// computed fields to the transaction expressions.
+	///
+	/// # Arguments
+	/// * `tx_status` - Status of the transaction (success/failure)
+	/// * `transaction` - The transaction to check
+	/// * `tx_receipt` - Transaction receipt
+	/// * `monitor` - Monitor containing match conditions
+	/// * `extra_params` - Additional fields available to expressions (e.g. heuristics flags)
+	/// * `matched_transactions` - Vector to store matching transactions
+	pub fn find_matching_transaction_with_params(
+		&self,
+		tx_status: &TransactionStatus,
+		transaction: &EVMTransaction,
+		tx_receipt: &Option<EVMTransactionReceipt>,
+		monitor: &Monitor,
+		extra_params: &[EVMMatchParamEntry],
+		matched_transactions: &mut Vec<TransactionCondition>,
 	) {
 		if monitor.match_conditions.transactions.is_empty() {
 			// Match all transactions
@@ -74,88 +112,7 @@ impl<T> EVMBlockFilter<T> {
 
 				if status_matches {
 					if let Some(expr) = &condition.expression {
-						let tx_params = vec![
-							EVMMatchParamEntry {
-								name: "value".to_string(),
-								value: transaction.value.to_string(),
-								kind: "uint256".to_string(),
-								indexed: false,
-							},
-							EVMMatchParamEntry {
-								name: "from".to_string(),
-								value: transaction.from.map_or("".to_string(), h160_to_string),
-								kind: "address".to_string(),
-								indexed: false,
-							},
-							EVMMatchParamEntry {
-								name: "to".to_string(),
-								value: transaction.to.map_or("".to_string(), h160_to_string),
-								kind: "address".to_string(),
-								indexed: false,
-							},
-							EVMMatchParamEntry {
-								name: "hash".to_string(),
-								value: b256_to_string(transaction.hash),
-								kind: "string".to_string(),
-								indexed: false,
-							},
-							EVMMatchParamEntry {
-								name: "gas_price".to_string(),
-								value: transaction.gas_price.unwrap_or_default().to_string(),
-								kind: "uint256".to_string(),
-								indexed: false,
-							},
-							EVMMatchParamEntry {
-								name: "max_fee_per_gas".to_string(),
-								value: transaction.max_fee_per_gas.unwrap_or_default().to_string(),
-								kind: "uint256".to_string(),
-								indexed: false,
-							},
-							EVMMatchParamEntry {
-								name: "max_priority_fee_per_gas".to_string(),
-								value: transaction
-									.max_priority_fee_per_gas
-									.unwrap_or_default()
-									.to_string(),
-								kind: "uint256".to_string(),
-								indexed: false,
-							},
-							EVMMatchParamEntry {
-								name: "gas_limit".to_string(),
-								value: transaction.gas.to_string(),
-								kind: "uint256".to_string(),
-								indexed: false,
-							},
-							EVMMatchParamEntry {
-								name: "nonce".to_string(),
-								value: transaction.nonce.to_string(),
-								kind: "uint256".to_string(),
-								indexed: false,
-							},
-							EVMMatchParamEntry {
-								name: "input".to_string(),
-								value: format!("0x{}", hex::encode(&transaction.input)),
-								kind: "string".to_string(),
-								indexed: false,
-							},
-							EVMMatchParamEntry {
-								name: "gas_used".to_string(),
-								value: tx_receipt
-									.as_ref()
-									.map(|r| r.gas_used.unwrap_or_default().to_string())
-									.unwrap_or_default(),
-								kind: "uint256".to_string(),
-								indexed: false,
-							},
-							EVMMatchParamEntry {
-								name: "transaction_index".to_string(),
-								value: transaction
-									.transaction_index
-									.map_or("0".to_string(), |idx| idx.0.to_string()),
-								kind: "uint64".to_string(),
-								indexed: false,
-							},
-						];
+						let tx_params = transaction_params(transaction, tx_receipt, extra_params);
 
 						// Evaluate the expression with transaction parameters
 						match self.evaluate_expression(expr, &tx_params) {
@@ -401,63 +358,78 @@ impl<T> EVMBlockFilter<T> {
 			// Add the contract address that emitted the event
 			involved_addresses.push(h160_to_string(log.address));
 
-			// Process the matching address's ABI
-			if let Some(abi) = &monitored_addr.contract_spec {
-				let decoded_log = self.decode_events(abi, log);
-
-				if let Some(event_condition) = decoded_log {
+			// Decode with the matching address's ABI, falling back to the built-in DEX event
+			// definitions when the monitor explicitly lists event conditions, in which case
+			// the computed DEX fields are appended to the arguments
+			let decoded_log = monitored_addr
+				.contract_spec
+				.as_ref()
+				.and_then(|abi| self.decode_events(abi, log))
+				.or_else(|| {
 					if monitor.match_conditions.events.is_empty() {
-						// Match all events
-						matched_events.push(EventCondition {
-							signature: event_condition.signature.clone(),
-							expression: None,
-						});
-						if let Some(events) = &mut matched_on_args.events {
-							events.push(event_condition);
-						}
-					} else {
-						// Check if this event matches any of the conditions
-						for condition in &monitor.match_conditions.events {
-							// Remove any whitespaces to ensure accurate matching
-							// For example: Transfer(address, address, uint256) ==
-							// Transfer(address,address,uint256)
-							if are_same_signature(&condition.signature, &event_condition.signature)
-							{
-								if condition.expression.is_none() {
-									matched_events.push(EventCondition {
-										signature: event_condition.signature.clone(),
-										expression: None,
-									});
-									if let Some(events) = &mut matched_on_args.events {
-										events.push(event_condition);
-									}
-									break;
-								} else {
-									// Evaluate the expression condition
-									if let Some(expr) = &condition.expression {
-										match self.evaluate_expression(
-											expr,
-											event_condition.args.as_deref().unwrap_or_default(),
-										) {
-											Ok(true) => {
-												matched_events.push(EventCondition {
-													signature: event_condition.signature.clone(),
-													expression: Some(expr.to_string()),
-												});
-												if let Some(events) = &mut matched_on_args.events {
-													events.push(event_condition);
-												}
-												break;
-											}
-											Ok(false) => continue,
-											Err(e) => {
-												tracing::error!(
-													"Failed to evaluate expression '{}': {}",
-													expr,
-													e
-												);
-												continue;
+						return None;
+					}
+					let mut event = dex::builtin_event(log)
+						.and_then(|event| self.decode_event_log(event, log))?;
+					let computed =
+						dex::computed_fields(event.args.as_deref().unwrap_or_default(), log, logs);
+					if !computed.is_empty() {
+						event.args.get_or_insert_with(Vec::new).extend(computed);
+					}
+					Some(event)
+				});
+
+			if let Some(event_condition) = decoded_log {
+				if monitor.match_conditions.events.is_empty() {
+					// Match all events
+					matched_events.push(EventCondition {
+						signature: event_condition.signature.clone(),
+						expression: None,
+					});
+					if let Some(events) = &mut matched_on_args.events {
+						events.push(event_condition);
+					}
+				} else {
+					// Check if this event matches any of the conditions
+					for condition in &monitor.match_conditions.events {
+						// Remove any whitespaces to ensure accurate matching
+						// For example: Transfer(address, address, uint256) ==
+						// Transfer(address,address,uint256)
+						if are_same_signature(&condition.signature, &event_condition.signature) {
+							if condition.expression.is_none() {
+								matched_events.push(EventCondition {
+									signature: event_condition.signature.clone(),
+									expression: None,
+								});
+								if let Some(events) = &mut matched_on_args.events {
+									events.push(event_condition);
+								}
+								break;
+							} else {
+								// Evaluate the expression condition
+								if let Some(expr) = &condition.expression {
+									match self.evaluate_expression(
+										expr,
+										event_condition.args.as_deref().unwrap_or_default(),
+									) {
+										Ok(true) => {
+											matched_events.push(EventCondition {
+												signature: event_condition.signature.clone(),
+												expression: Some(expr.to_string()),
+											});
+											if let Some(events) = &mut matched_on_args.events {
+												events.push(event_condition);
 											}
+											break;
+										}
+										Ok(false) => continue,
+										Err(e) => {
+											tracing::error!(
+												"Failed to evaluate expression '{}': {}",
+												expr,
+												e
+											);
+											continue;
 										}
 									}
 								}
@@ -553,6 +525,22 @@ impl<T> EVMBlockFilter<T> {
 			}
 		};
 
+		self.decode_event_log(&event, log)
+	}
+
+	/// Decodes an event log using a specific event definition.
+	///
+	/// # Arguments
+	/// * `event` - Event definition matching the log's topic0
+	/// * `log` - Event log to decode
+	///
+	/// # Returns
+	/// Option containing EVMMatchParamsMap with decoded event data if successful
+	pub fn decode_event_log(
+		&self,
+		event: &Event,
+		log: &EVMReceiptLog,
+	) -> Option<EVMMatchParamsMap> {
 		// Decode event in one call (covering non-indexed and indexed params)
 		let log_data = match LogData::new(log.topics.clone(), log.data.clone()) {
 			Some(data) => data,
@@ -619,86 +607,69 @@ impl<T> EVMBlockFilter<T> {
 		})
 	}
 
-	/// Checks if a monitor has any transaction conditions that require a receipt
+	/// Checks if a monitor has any transaction conditions, or trigger templates, that require
+	/// a receipt
 	///
 	/// # Arguments
 	/// * `monitor` - Monitor to check
 	/// * `logs` - Logs to check
 	///
 	/// # Returns
-	/// `true` if the monitor has any transaction conditions that require a receipt, `false` otherwise
+	/// `true` if the monitor has any transaction conditions or trigger templates that require a receipt, `false` otherwise
 	fn needs_receipt(&self, monitor: &Monitor, logs: &[EVMReceiptLog]) -> bool {
-		monitor
-			.match_conditions
-			.transactions
-			.iter()
-			.any(|condition| {
-				// If the status is not Any, and there are no logs, we need a receipt to validate the transaction most likely failed
-				let status_needs_receipt =
-					condition.status != TransactionStatus::Any && logs.is_empty();
-				// If the expression contains gas_used, we need a receipt to get the gas used
-				let gas_used_in_expr = condition
-					.clone()
-					.expression
-					.is_some_and(|expr| expr.contains("gas_used"));
-				status_needs_receipt || gas_used_in_expr
-			})
+		self.context.receipt_templates.need_receipt(monitor)
+			|| monitor
+				.match_conditions
+				.transactions
+				.iter()
+				.any(|condition| {
+					// If the status is not Any, and there are no logs, we need a receipt to validate the transaction most likely failed
+					let status_needs_receipt =
+						condition.status != TransactionStatus::Any && logs.is_empty();
+					// If the expression contains gas_used or fee, we need a receipt to get the gas used
+					let gas_used_in_expr = condition.expression.as_ref().is_some_and(|expr| {
+						expr.contains("gas_used")
+							|| expr
+								.split(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+								.any(|token| token == "fee")
+					});
+					status_needs_receipt || gas_used_in_expr
+				})
 	}
 }
 
-#[async_trait]
-impl<T: BlockChainClient + EvmClientTrait> BlockFilter for EVMBlockFilter<T> {
-	type Client = T;
-	/// Processes a block and finds matches based on monitor conditions.
+impl<T: BlockChainClient + EvmClientTrait> EVMBlockFilter<T> {
+	/// Finds the matches of the transactions of a block, or of a chunk of a block.
 	///
 	/// # Arguments
 	/// * `client` - Blockchain client for additional data fetching
 	/// * `network` - Network of the blockchain
-	/// * `block` - The block to process
+	/// * `evm_block` - The block, or chunk of a block, whose transactions are processed
+	/// * `logs` - Logs of the transactions of `evm_block`
+	/// * `block_heuristics` - Heuristics flags of the transactions of the whole block
 	/// * `monitors` - Active monitors containing match conditions
+	/// * `contract_specs` - Contract specs of the monitored addresses
 	///
 	/// # Returns
-	/// Vector of matches found in the block
-	#[instrument(skip_all, fields(network = %network.slug))]
-	async fn filter_block(
+	/// Vector of matches found in the transactions
+	#[allow(clippy::too_many_arguments)]
+	async fn filter_transactions(
 		&self,
 		client: &T,
 		network: &Network,
-		block: &BlockType,
+		evm_block: &EVMBlock,
+		logs: &[EVMReceiptLog],
+		block_heuristics: &HashMap<String, heuristics::TransactionHeuristics>,
 		monitors: &[Monitor],
 		contract_specs: Option<&[(String, ContractSpec)]>,
 	) -> Result<Vec<MonitorMatch>, FilterError> {
-		let evm_block = match block {
-			BlockType::EVM(block) => block,
-			_ => {
-				return Err(FilterError::block_type_mismatch(
-					"Expected EVM block",
-					None,
-					None,
-				));
-			}
-		};
-
-		tracing::debug!("Processing block {}", evm_block.number().unwrap_or(0));
-
 		let current_block_number = evm_block.number.unwrap_or(U64::from(0)).to::<u64>();
-
-		// Get logs for the block
-		// We use this to get all the logs for a single block.
-		// We could further optimize by getting logs for a range of blocks and calling this in the parent function
-		// However, due to limitations by certain RPC providers (e.g. Quicknode only allows a block range of 5),
-		// it's safer to just fetch the logs for a single block at a time as it's more reliable.
-		let all_block_logs = client
-			.get_logs_for_blocks(current_block_number, current_block_number, None)
-			.await?;
-
-		tracing::debug!(
-			"Found {} logs for block {}",
-			all_block_logs.len(),
-			current_block_number
-		);
+		let block_timestamp = evm_block.timestamp.saturating_to::<u64>();
+		let block_params = block_params(block_timestamp, Utc::now().timestamp().max(0) as u64);
 
 		let mut matching_results = Vec::new();
+		// Matches whose transaction is traced, by index in the results
+		let mut traced_matches: Vec<(usize, String)> = Vec::new();
 
 		// Cast contract specs to EVMContractSpec
 		let contract_specs = contract_specs
@@ -710,13 +681,7 @@ impl<T: BlockChainClient + EvmClientTrait> BlockFilter for EVMBlockFilter<T> {
 			})
 			.collect::<Vec<(String, EVMContractSpec)>>();
 
-		// Group logs by transaction hash
-		let mut logs_by_tx: std::collections::HashMap<String, Vec<EVMReceiptLog>> =
-			std::collections::HashMap::new();
-		for log in all_block_logs.clone() {
-			let tx_hash = b256_to_string(log.transaction_hash.unwrap_or_default());
-			logs_by_tx.entry(tx_hash).or_default().push(log);
-		}
+		let logs_by_tx = group_logs_by_tx(logs);
 
 		tracing::debug!("Processing {} transactions with logs", logs_by_tx.len());
 
@@ -729,7 +694,11 @@ impl<T: BlockChainClient + EvmClientTrait> BlockFilter for EVMBlockFilter<T> {
 				.collect();
 
 			// Check if this monitor needs a receipt
-			let should_fetch_receipt = self.needs_receipt(monitor, &all_block_logs);
+			let should_fetch_receipt = self.needs_receipt(monitor, logs);
+			let heuristics_config = heuristics::monitor_config(monitor);
+			let first_interaction_config = interactions::monitor_config(monitor);
+			let poisoning_config = poisoning::monitor_config(monitor);
+			let nonce_anomaly_config = nonces::monitor_config(monitor);
 
 			// Process all transactions in the block
 			for transaction in &evm_block.transactions {
@@ -779,12 +748,93 @@ impl<T: BlockChainClient + EvmClientTrait> BlockFilter for EVMBlockFilter<T> {
 				let mut matched_transactions = Vec::<TransactionCondition>::new();
 				let mut matched_functions = Vec::<FunctionCondition>::new();
 
+				// Expose the heuristics flags as transaction fields if enabled for this monitor
+				let mut extra_params = heuristics_config
+					.map(|config| {
+						block_heuristics
+							.get(&tx_hash)
+							.cloned()
+							.unwrap_or_default()
+							.to_params(config)
+					})
+					.unwrap_or_default();
+				extra_params.extend_from_slice(&block_params);
+
+				// Track senders calling the monitored contracts for the first time
+				if let Some(config) = first_interaction_config {
+					let is_first_interaction = match (transaction.from, transaction.to) {
+						(Some(from), Some(to))
+							if monitored_addresses
+								.iter()
+								.any(|addr| are_same_address(addr, &h160_to_string(to))) =>
+						{
+							interactions::record_interaction(
+								&self.context.interactions,
+								&monitor.name,
+								&network.slug,
+								&h160_to_string(from),
+								&h160_to_string(to),
+								current_block_number,
+								config.lookback_blocks,
+							)
+							.await
+						}
+						_ => false,
+					};
+					extra_params.push(EVMMatchParamEntry {
+						name: "is_first_interaction".to_string(),
+						value: is_first_interaction.to_string(),
+						kind: "bool".to_string(),
+						indexed: false,
+					});
+				}
+
+				// Flag zero-value transfers and look-alike counterparties
+				if let Some(config) = poisoning_config {
+					extra_params.extend(
+						poisoning::analyze_transaction(
+							transaction,
+							logs,
+							&monitored_addresses,
+							config,
+						)
+						.to_params(),
+					);
+				}
+
+				// Compare the nonces of the transactions sent by monitored addresses with their
+				// previous transactions
+				if let Some(config) = nonce_anomaly_config {
+					let nonce = transaction.nonce.saturating_to::<u64>();
+					let signals = match transaction.from {
+						Some(from)
+							if monitored_addresses
+								.iter()
+								.any(|addr| are_same_address(addr, &h160_to_string(from))) =>
+						{
+							nonces::record_transaction(
+								&self.context.nonces,
+								&monitor.name,
+								&network.slug,
+								&h160_to_string(from),
+								nonce,
+								current_block_number,
+								config,
+							)
+							.await
+						}
+						_ => nonces::NonceSignals::default(),
+					};
+					extra_params.extend(signals.to_params(nonce));
+				}
+
 				// Check transaction match conditions
-				self.find_matching_transaction(
+				self.find_matching_transaction_with_params(
 					&tx_status,
 					transaction,
 					&receipt.clone(),
 					monitor,
+					&extra_params,
 					&mut matched_transactions,
 				);
 
@@ -848,6 +898,48 @@ impl<T: BlockChainClient + EvmClientTrait> BlockFilter for EVMBlockFilter<T> {
 					};
 
 					if should_match {
+						let matched_on = MatchConditions {
+							events: matched_events
+								.clone()
+								.into_iter()
+								.filter(|_| has_event_match)
+								.collect(),
+							functions: matched_functions
+								.clone()
+								.into_iter()
+								.filter(|_| has_function_match)
+								.collect(),
+							transactions: matched_transactions
+								.clone()
+								.into_iter()
+								.filter(|_| has_transaction_match)
+								.collect(),
+						};
+						let matched_on_args = EVMMatchArguments {
+							events: if has_event_match {
+								matched_on_args.events.clone()
+							} else {
+								None
+							},
+							functions: if has_function_match {
+								matched_on_args.functions.clone()
+							} else {
+								None
+							},
+						};
+						let matched_on_audit = audit::build_audit(
+							&matched_on,
+							&matched_on_args,
+							transaction,
+							logs,
+							&transaction_params(transaction, &receipt, &extra_params),
+							&monitored_addresses,
+						);
+						// The transaction is traced once the block is filtered if the monitor
+						// inspects internal calls
+						if traces::is_enabled(monitor) {
+							traced_matches.push((matching_results.len(), tx_hash.clone()));
+						}
 						matching_results.push(MonitorMatch::EVM(Box::new(EVMMonitorMatch {
 							monitor: Monitor {
 								// Omit ABI from monitor since we do not need it here
@@ -865,51 +957,353 @@ impl<T: BlockChainClient + EvmClientTrait> BlockFilter for EVMBlockFilter<T> {
 							receipt,
 							logs: Some(logs.clone()),
 							network_slug: network.slug.clone(),
-							matched_on: MatchConditions {
-								events: matched_events
-									.clone()
-									.into_iter()
-									.filter(|_| has_event_match)
-									.collect(),
-								functions: matched_functions
-									.clone()
-									.into_iter()
-									.filter(|_| has_function_match)
-									.collect(),
-								transactions: matched_transactions
-									.clone()
-									.into_iter()
-									.filter(|_| has_transaction_match)
-									.collect(),
-							},
-							matched_on_args: Some(EVMMatchArguments {
-								events: if has_event_match {
-									matched_on_args.events.clone()
-								} else {
-									None
-								},
-								functions: if has_function_match {
-									matched_on_args.functions.clone()
-								} else {
-									None
-								},
-							}),
+							matched_on,
+							matched_on_audit,
+							matched_on_args: Some(matched_on_args),
+							block_timestamp: Some(block_timestamp),
+							trace: None,
 						})));
 					}
 				}
 			}
 		}
 
+		// Transactions matched by several monitors are traced once
+		if !traced_matches.is_empty() {
+			let traces = traces::trace_transactions(
+				&self.context.trace_budgets,
+				&self.context.capabilities,
+				client,
+				network,
+				current_block_number,
+				traced_matches.iter().map(|(_, tx_hash)| tx_hash),
+			)
+			.await;
+			for (index, tx_hash) in &traced_matches {
+				if let MonitorMatch::EVM(evm_match) = &mut matching_results[*index] {
+					evm_match.trace = traces.get(tx_hash).cloned();
+				}
+			}
+		}
+
 		Ok(matching_results)
 	}
+
+	/// Fetches the logs of a block
+	///
+	/// We use this to get all the logs for a single block.
+	/// We could further optimize by getting logs for a range of blocks and calling this in the parent function
+	/// However, due to limitations by certain RPC providers (e.g. Quicknode only allows a block range of 5),
+	/// it's safer to just fetch the logs for a single block at a time as it's more reliable.
+	async fn block_logs(
+		&self,
+		client: &T,
+		evm_block: &EVMBlock,
+	) -> Result<Vec<EVMReceiptLog>, FilterError> {
+		let block_number = evm_block.number.unwrap_or(U64::from(0)).to::<u64>();
+		let logs = client
+			.get_logs_for_blocks(block_number, block_number, None)
+			.await?;
+		tracing::debug!("Found {} logs for block {}", logs.len(), block_number);
+		Ok(logs)
+	}
+}
+
+/// Groups logs by transaction hash
+fn group_logs_by_tx(logs: &[EVMReceiptLog]) -> HashMap<String, Vec<EVMReceiptLog>> {
+	let mut logs_by_tx: HashMap<String, Vec<EVMReceiptLog>> = HashMap::new();
+	for log in logs {
+		let tx_hash = b256_to_string(log.transaction_hash.unwrap_or_default());
+		logs_by_tx.entry(tx_hash).or_default().push(log.clone());
+	}
+	logs_by_tx
+}
+
+/// Runs the heuristics analysis over the whole block if any monitor requested it
+fn block_heuristics(
+	evm_block: &EVMBlock,
+	logs: &[EVMReceiptLog],
+	monitors: &[Monitor],
+) -> HashMap<String, heuristics::TransactionHeuristics> {
+	if monitors
+		.iter()
+		.any(|monitor| heuristics::monitor_config(monitor).is_some())
+	{
+		heuristics::analyze_block(&evm_block.transactions, &group_logs_by_tx(logs))
+	} else {
+		HashMap::new()
+	}
+}
+
+/// Returns the EVM block of a block type
+fn expect_evm_block(block: &BlockType) -> Result<&EVMBlock, FilterError> {
+	match block {
+		BlockType::EVM(block) => Ok(block),
+		_ => Err(FilterError::block_type_mismatch(
+			"Expected EVM block",
+			None,
+			None,
+		)),
+	}
+}
+
+#[async_trait]
+impl<T: BlockChainClient + EvmClientTrait> BlockFilter for EVMBlockFilter<T> {
+	type Client = T;
+	/// Processes a block and finds matches based on monitor conditions.
+	///
+	/// # Arguments
+	/// * `client` - Blockchain client for additional data fetching
+	/// * `network` - Network of the blockchain
+	/// * `block` - The block to process
+	/// * `monitors` - Active monitors containing match conditions
+	///
+	/// # Returns
+	/// Vector of matches found in the block
+	#[instrument(skip_all, fields(network = %network.slug))]
+	async fn filter_block(
+		&self,
+		client: &T,
+		network: &Network,
+		block: &BlockType,
+		monitors: &[Monitor],
+		contract_specs: Option<&[(String, ContractSpec)]>,
+	) -> Result<Vec<MonitorMatch>, FilterError> {
+		let evm_block = expect_evm_block(block)?;
+		tracing::debug!("Processing block {}", evm_block.number().unwrap_or(0));
+
+		let logs = self.block_logs(client, evm_block).await?;
+		let block_heuristics = block_heuristics(evm_block, &logs, monitors);
+		self.filter_transactions(
+			client,
+			network,
+			evm_block,
+			&logs,
+			&block_heuristics,
+			monitors,
+			contract_specs,
+		)
+		.await
+	}
+
+	/// Processes the chunks of an oversized block, fetching its logs and running the
+	/// heuristics over its transactions once for all the chunks.
+	#[instrument(skip_all, fields(network = %network.slug))]
+	async fn filter_block_chunks(
+		&self,
+		client: &T,
+		network: &Network,
+		block: &BlockType,
+		chunks: &[BlockType],
+		monitors: &[Monitor],
+		contract_specs: Option<&[(String, ContractSpec)]>,
+		on_chunk: &mut (dyn FnMut(usize, &BlockType) + Send),
+	) -> Result<Vec<MonitorMatch>, FilterError> {
+		let evm_block = expect_evm_block(block)?;
+		tracing::debug!("Processing block {}", evm_block.number().unwrap_or(0));
+
+		let logs = self.block_logs(client, evm_block).await?;
+		let block_heuristics = block_heuristics(evm_block, &logs, monitors);
+
+		let mut matches = Vec::new();
+		for (index, chunk) in chunks.iter().enumerate() {
+			let evm_chunk = expect_evm_block(chunk)?;
+			let chunk_transactions: HashSet<_> = evm_chunk
+				.transactions
+				.iter()
+				.map(|transaction| transaction.hash)
+				.collect();
+			let chunk_logs: Vec<EVMReceiptLog> = logs
+				.iter()
+				.filter(|log| {
+					log.transaction_hash
+						.is_some_and(|hash| chunk_transactions.contains(&hash))
+				})
+				.cloned()
+				.collect();
+			matches.extend(
+				self.filter_transactions(
+					client,
+					network,
+					evm_chunk,
+					&chunk_logs,
+					&block_heuristics,
+					monitors,
+					contract_specs,
+				)
+				.await?,
+			);
+			on_chunk(index, chunk);
+		}
+		Ok(matches)
+	}
+}
+
+/// Variables available to transaction condition expressions: the parameters of
+/// [`transaction_params`] followed by those of [`block_params`], besides the parameters of the
+/// analyses enabled per monitor (e.g. `is_first_interaction`)
+pub const TRANSACTION_VARIABLES: &[&str] = &[
+	"value",
+	"from",
+	"to",
+	"hash",
+	"gas_price",
+	"max_fee_per_gas",
+	"max_priority_fee_per_gas",
+	"gas_limit",
+	"nonce",
+	"input",
+	"gas_used",
+	"fee",
+	"transaction_index",
+	"block_timestamp",
+	"block_age",
+];
+
+/// Builds the parameters available to transaction condition expressions
+///
+/// # Arguments
+/// * `transaction` - The transaction
+/// * `tx_receipt` - Transaction receipt, providing `gas_used` and `fee`
+/// * `extra_params` - Additional fields available to expressions (e.g. heuristics flags)
+///
+/// # Returns
+/// The transaction parameters followed by the extra parameters
+pub fn transaction_params(
+	transaction: &EVMTransaction,
+	tx_receipt: &Option<EVMTransactionReceipt>,
+	extra_params: &[EVMMatchParamEntry],
+) -> Vec<EVMMatchParamEntry> {
+	let mut params = vec![
+		EVMMatchParamEntry {
+			name: "value".to_string(),
+			value: transaction.value.to_string(),
+			kind: "uint256".to_string(),
+			indexed: false,
+		},
+		EVMMatchParamEntry {
+			name: "from".to_string(),
+			value: transaction.from.map_or("".to_string(), h160_to_string),
+			kind: "address".to_string(),
+			indexed: false,
+		},
+		EVMMatchParamEntry {
+			name: "to".to_string(),
+			value: transaction.to.map_or("".to_string(), h160_to_string),
+			kind: "address".to_string(),
+			indexed: false,
+		},
+		EVMMatchParamEntry {
+			name: "hash".to_string(),
+			value: b256_to_string(transaction.hash),
+			kind: "string".to_string(),
+			indexed: false,
+		},
+		EVMMatchParamEntry {
+			name: "gas_price".to_string(),
+			value: transaction.gas_price.unwrap_or_default().to_string(),
+			kind: "uint256".to_string(),
+			indexed: false,
+		},
+		EVMMatchParamEntry {
+			name: "max_fee_per_gas".to_string(),
+			value: transaction.max_fee_per_gas.unwrap_or_default().to_string(),
+			kind: "uint256".to_string(),
+			indexed: false,
+		},
+		EVMMatchParamEntry {
+			name: "max_priority_fee_per_gas".to_string(),
+			value: transaction
+				.max_priority_fee_per_gas
+				.unwrap_or_default()
+				.to_string(),
+			kind: "uint256".to_string(),
+			indexed: false,
+		},
+		EVMMatchParamEntry {
+			name: "gas_limit".to_string(),
+			value: transaction.gas.to_string(),
+			kind: "uint256".to_string(),
+			indexed: false,
+		},
+		EVMMatchParamEntry {
+			name: "nonce".to_string(),
+			value: transaction.nonce.to_string(),
+			kind: "uint256".to_string(),
+			indexed: false,
+		},
+		EVMMatchParamEntry {
+			name: "input".to_string(),
+			value: format!("0x{}", hex::encode(&transaction.input)),
+			kind: "string".to_string(),
+			indexed: false,
+		},
+		EVMMatchParamEntry {
+			name: "gas_used".to_string(),
+			value: tx_receipt
+				.as_ref()
+				.map(|r| r.gas_used.unwrap_or_default().to_string())
+				.unwrap_or_default(),
+			kind: "uint256".to_string(),
+			indexed: false,
+		},
+		EVMMatchParamEntry {
+			name: "fee".to_string(),
+			value: tx_receipt
+				.as_ref()
+				.map(|r| r.fee().unwrap_or_default().to_string())
+				.unwrap_or_default(),
+			kind: "uint256".to_string(),
+			indexed: false,
+		},
+		EVMMatchParamEntry {
+			name: "transaction_index".to_string(),
+			value: transaction
+				.transaction_index
+				.map_or("0".to_string(), |idx| idx.0.to_string()),
+			kind: "uint64".to_string(),
+			indexed: false,
+		},
+	];
+	params.extend_from_slice(extra_params);
+	params
+}
+
+/// Builds the parameters of the block of a transaction available to transaction condition
+/// expressions
+///
+/// # Arguments
+/// * `block_timestamp` - Timestamp of the block (unix seconds)
+/// * `now` - Current unix timestamp, in seconds
+///
+/// # Returns
+/// The `block_timestamp` and `block_age` (seconds elapsed since the block) parameters
+pub fn block_params(block_timestamp: u64, now: u64) -> Vec<EVMMatchParamEntry> {
+	vec![
+		EVMMatchParamEntry {
+			name: "block_timestamp".to_string(),
+			value: block_timestamp.to_string(),
+			kind: "uint64".to_string(),
+			indexed: false,
+		},
+		EVMMatchParamEntry {
+			name: "block_age".to_string(),
+			value: now.saturating_sub(block_timestamp).to_string(),
+			kind: "uint64".to_string(),
+			indexed: false,
+		},
+	]
 }
 
 #[cfg(test)]
 mod tests {
 	use crate::{
 		models::{ContractSpec, EVMContractSpec},
-		utils::tests::evm::{
-			monitor::MonitorBuilder, receipt::ReceiptBuilder, transaction::TransactionBuilder,
+		utils::tests::{
+			evm::{
+				monitor::MonitorBuilder, receipt::ReceiptBuilder, transaction::TransactionBuilder,
+			},
+			trigger::TriggerBuilder,
 		},
 	};
 
@@ -924,6 +1318,7 @@ mod tests {
 	fn create_test_filter() -> EVMBlockFilter<()> {
 		EVMBlockFilter::<()> {
 			_client: PhantomData,
+			context: FilterContext::default(),
 		}
 	}
 
@@ -1606,6 +2001,99 @@ mod tests {
 		assert_eq!(matched.len(), 0);
 	}
 
+	#[test]
+	fn test_fee_matching() {
+		let expression = "fee > 1000000000000000".to_string(); // more than 0.001 ETH
+		let condition = TransactionCondition {
+			status: TransactionStatus::Any,
+			expression: Some(expression.clone()),
+		};
+		let filter = create_test_filter();
+		let mut matched = Vec::new();
+		let monitor = create_test_monitor(vec![], vec![], vec![condition], vec![]);
+		assert!(filter.needs_receipt(&monitor, &[]));
+
+		// Test transaction with a fee of 0.0021 ETH
+		let tx_matching = TransactionBuilder::new().build();
+		let tx_receipt_matching = ReceiptBuilder::new()
+			.transaction_hash(tx_matching.hash)
+			.gas_used(U256::from(21000))
+			.effective_gas_price(U256::from(100000000000u64)) // 100 Gwei
+			.build();
+		assert_eq!(
+			tx_receipt_matching.fee(),
+			Some(U256::from(2100000000000000u64))
+		);
+
+		filter.find_matching_transaction(
+			&TransactionStatus::Success,
+			&tx_matching,
+			&Some(tx_receipt_matching),
+			&monitor,
+			&mut matched,
+		);
+		assert_eq!(matched.len(), 1);
+		assert_eq!(matched[0].expression, Some(expression));
+
+		// Test transaction with a fee of 0.00021 ETH
+		let tx_non_matching = TransactionBuilder::new().build();
+		let tx_receipt_non_matching = ReceiptBuilder::new()
+			.transaction_hash(tx_non_matching.hash)
+			.gas_used(U256::from(21000))
+			.effective_gas_price(U256::from(10000000000u64)) // 10 Gwei
+			.build();
+
+		matched.clear();
+		filter.find_matching_transaction(
+			&TransactionStatus::Success,
+			&tx_non_matching,
+			&Some(tx_receipt_non_matching),
+			&monitor,
+			&mut matched,
+		);
+		assert_eq!(matched.len(), 0);
+
+		// Fee-like parameter names do not require a receipt
+		let monitor = create_test_monitor(
+			vec![],
+			vec![],
+			vec![TransactionCondition {
+				status: TransactionStatus::Any,
+				expression: Some("max_fee_per_gas > 0".to_string()),
+			}],
+			vec![],
+		);
+		assert!(!filter.needs_receipt(&monitor, &[]));
+	}
+
+	#[test]
+	fn test_needs_receipt_for_registered_templates() {
+		let triggers = HashMap::from([(
+			"fee_alert".to_string(),
+			TriggerBuilder::new()
+				.name("fee_alert")
+				.slack("https://hooks.slack.com/services/fee")
+				.message("Fee paid", "Paid ${transaction.fee} wei")
+				.build(),
+		)]);
+		let monitor = MonitorBuilder::new()
+			.name("test")
+			.triggers(vec!["fee_alert".to_string()])
+			.build();
+
+		let filter = create_test_filter();
+		assert!(!filter.needs_receipt(&monitor, &[]));
+
+		filter
+			.context
+			.receipt_templates
+			.register(&triggers, &[monitor.clone()]);
+		assert!(filter.needs_receipt(&monitor, &[]));
+
+		// The registered templates are owned by the context of the filter
+		assert!(!create_test_filter().needs_receipt(&monitor, &[]));
+	}
+
 	#[test]
 	fn test_transaction_index_matching() {
 		let expression = "transaction_index == 15".to_string();
@@ -1650,6 +2138,158 @@ mod tests {
 		assert_eq!(matched.len(), 0);
 	}
 
+	#[test]
+	fn test_extra_params_matching() {
+		let filter = create_test_filter();
+		let mut matched = Vec::new();
+		let monitor = create_test_monitor(
+			vec![],
+			vec![],
+			vec![TransactionCondition {
+				status: TransactionStatus::Any,
+				expression: Some("is_flashloan == true AND flashloan_amount >= 1000".to_string()),
+			}],
+			vec![],
+		);
+		let transaction = TransactionBuilder::new().build();
+		let flags = |is_flashloan: &str, amount: &str| {
+			vec![
+				create_test_param("is_flashloan", is_flashloan, "bool"),
+				create_test_param("flashloan_amount", amount, "uint256"),
+			]
+		};
+
+		filter.find_matching_transaction_with_params(
+			&TransactionStatus::Success,
+			&transaction,
+			&None,
+			&monitor,
+			&flags("true", "5000"),
+			&mut matched,
+		);
+		assert_eq!(matched.len(), 1);
+
+		matched.clear();
+		filter.find_matching_transaction_with_params(
+			&TransactionStatus::Success,
+			&transaction,
+			&None,
+			&monitor,
+			&flags("false", "0"),
+			&mut matched,
+		);
+		assert!(matched.is_empty());
+
+		// Without the extra fields the expression cannot be evaluated
+		filter.find_matching_transaction(
+			&TransactionStatus::Success,
+			&transaction,
+			&None,
+			&monitor,
+			&mut matched,
+		);
+		assert!(matched.is_empty());
+	}
+
+	#[test]
+	fn test_nonce_anomaly_params_matching() {
+		let filter = create_test_filter();
+		let mut matched = Vec::new();
+		let monitor = create_test_monitor(
+			vec![],
+			vec![],
+			vec![TransactionCondition {
+				status: TransactionStatus::Any,
+				expression: Some("is_nonce_gap == true OR is_dormant_sender == true".to_string()),
+			}],
+			vec![],
+		);
+		let transaction = TransactionBuilder::new().nonce(U256::from(10)).build();
+
+		let gap = nonces::NonceSignals {
+			expected_nonce: Some(7),
+			nonce_gap: 3,
+			idle_blocks: Some(2),
+			dormant_sender: false,
+		};
+		filter.find_matching_transaction_with_params(
+			&TransactionStatus::Success,
+			&transaction,
+			&None,
+			&monitor,
+			&gap.to_params(10),
+			&mut matched,
+		);
+		assert_eq!(matched.len(), 1);
+
+		matched.clear();
+		filter.find_matching_transaction_with_params(
+			&TransactionStatus::Success,
+			&transaction,
+			&None,
+			&monitor,
+			&nonces::NonceSignals::default().to_params(10),
+			&mut matched,
+		);
+		assert!(matched.is_empty());
+	}
+
+	#[test]
+	fn test_block_age_matching() {
+		let filter = create_test_filter();
+		let mut matched = Vec::new();
+		// Suppress matches of blocks older than 5 minutes, e.g. during catch-up
+		let monitor = create_test_monitor(
+			vec![],
+			vec![],
+			vec![TransactionCondition {
+				status: TransactionStatus::Any,
+				expression: Some("block_age < 300".to_string()),
+			}],
+			vec![],
+		);
+		let transaction = TransactionBuilder::new().build();
+		let now = 1_750_000_000;
+
+		let live = block_params(now - 12, now);
+		assert_eq!(live[0].value, (now - 12).to_string());
+		assert_eq!(live[1].value, "12");
+		filter.find_matching_transaction_with_params(
+			&TransactionStatus::Success,
+			&transaction,
+			&None,
+			&monitor,
+			&live,
+			&mut matched,
+		);
+		assert_eq!(matched.len(), 1);
+
+		matched.clear();
+		filter.find_matching_transaction_with_params(
+			&TransactionStatus::Success,
+			&transaction,
+			&None,
+			&monitor,
+			&block_params(now - 3_600, now),
+			&mut matched,
+		);
+		assert!(matched.is_empty());
+
+		// Blocks with a timestamp ahead of the local clock have no age
+		assert_eq!(block_params(now + 5, now)[1].value, "0");
+	}
+
+	#[test]
+	fn test_transaction_variables() {
+		let params = transaction_params(
+			&TransactionBuilder::new().build(),
+			&None,
+			&block_params(0, 0),
+		);
+		let names: Vec<&str> = params.iter().map(|p| p.name.as_str()).collect();
+		assert_eq!(names, TRANSACTION_VARIABLES);
+	}
+
 	//////////////////////////////////////////////////////////////////////////////
 	// Test cases for find_matching_functions_for_transaction method:
 	//////////////////////////////////////////////////////////////////////////////
@@ -2109,6 +2749,135 @@ mod tests {
 		assert_eq!(matched_events.len(), 0);
 	}
 
+	#[test]
+	fn test_find_matching_events_builtin_dex_swap() {
+		let filter = create_test_filter();
+		let mut matched_events = Vec::new();
+		let mut matched_on_args = EVMMatchArguments {
+			events: Some(Vec::new()),
+			functions: None,
+		};
+		let mut involved_addresses = Vec::new();
+
+		// The pool has no ABI configured, so the built-in Uniswap v2 definition is used
+		let monitor = create_test_monitor(
+			vec![EventCondition {
+				signature: "Swap(address,uint256,uint256,uint256,uint256,address)".to_string(),
+				expression: Some("amount_in > 50 AND zero_for_one == true".to_string()),
+			}],
+			vec![],
+			vec![],
+			vec![create_test_address(
+				"0x0000000000000000000000000000000000004321",
+				None,
+			)],
+		);
+
+		let pool = Address::from_str("0x0000000000000000000000000000000000004321").unwrap();
+		let swap_topic = keccak256("Swap(address,uint256,uint256,uint256,uint256,address)");
+		let data = [100u64, 0, 0, 180]
+			.iter()
+			.flat_map(|amount| U256::from(*amount).to_be_bytes::<32>())
+			.collect::<Vec<u8>>();
+		let log = create_test_log(
+			pool,
+			&format!("0x{}", hex::encode(swap_topic)),
+			Address::from_str("0x0000000000000000000000000000000000001234").unwrap(),
+			Address::from_str("0x0000000000000000000000000000000000005678").unwrap(),
+			&hex::encode(data),
+		);
+
+		filter.find_matching_events_for_transaction(
+			&[log],
+			&monitor,
+			&mut matched_events,
+			&mut matched_on_args,
+			&mut involved_addresses,
+		);
+
+		assert_eq!(matched_events.len(), 1);
+		let args = matched_on_args.events.unwrap()[0].args.clone().unwrap();
+		let get = |name: &str| {
+			args.iter()
+				.find(|arg| arg.name == name)
+				.map(|arg| arg.value.clone())
+		};
+		assert_eq!(get("amount0In"), Some("100".to_string()));
+		assert_eq!(get("amount_out"), Some("180".to_string()));
+		assert_eq!(get("execution_price"), Some("1.8".to_string()));
+		assert_eq!(get("dex_protocol"), Some("uniswap_v2".to_string()));
+	}
+
+	#[test]
+	fn test_find_matching_events_dex_swap_with_abi_has_no_computed_fields() {
+		let filter = create_test_filter();
+		let mut matched_events = Vec::new();
+		let mut matched_on_args = EVMMatchArguments {
+			events: Some(Vec::new()),
+			functions: None,
+		};
+		let mut involved_addresses = Vec::new();
+
+		// The pool ABI names a parameter like a computed field, which must not be shadowed
+		let spec = json!([{
+			"type": "event",
+			"name": "Swap",
+			"inputs": [
+				{ "name": "sender", "type": "address", "indexed": true },
+				{ "name": "amount0In", "type": "uint256", "indexed": false },
+				{ "name": "amount1In", "type": "uint256", "indexed": false },
+				{ "name": "amount_in", "type": "uint256", "indexed": false },
+				{ "name": "amount1Out", "type": "uint256", "indexed": false },
+				{ "name": "to", "type": "address", "indexed": true }
+			],
+			"anonymous": false,
+		}]);
+		let monitor = create_test_monitor(
+			vec![EventCondition {
+				signature: "Swap(address,uint256,uint256,uint256,uint256,address)".to_string(),
+				expression: Some("amount_in == 0".to_string()),
+			}],
+			vec![],
+			vec![],
+			vec![create_test_address(
+				"0x0000000000000000000000000000000000004321",
+				Some(ContractSpec::EVM(EVMContractSpec::from(spec))),
+			)],
+		);
+
+		let pool = Address::from_str("0x0000000000000000000000000000000000004321").unwrap();
+		let swap_topic = keccak256("Swap(address,uint256,uint256,uint256,uint256,address)");
+		let data = [100u64, 0, 0, 180]
+			.iter()
+			.flat_map(|amount| U256::from(*amount).to_be_bytes::<32>())
+			.collect::<Vec<u8>>();
+		let log = create_test_log(
+			pool,
+			&format!("0x{}", hex::encode(swap_topic)),
+			Address::from_str("0x0000000000000000000000000000000000001234").unwrap(),
+			Address::from_str("0x0000000000000000000000000000000000005678").unwrap(),
+			&hex::encode(data),
+		);
+
+		filter.find_matching_events_for_transaction(
+			&[log],
+			&monitor,
+			&mut matched_events,
+			&mut matched_on_args,
+			&mut involved_addresses,
+		);
+
+		assert_eq!(matched_events.len(), 1);
+		let args = matched_on_args.events.unwrap()[0].args.clone().unwrap();
+		let amounts_in: Vec<_> = args
+			.iter()
+			.filter(|arg| arg.name == "amount_in")
+			.map(|arg| arg.value.as_str())
+			.collect();
+		assert_eq!(amounts_in, vec!["0"]);
+		assert!(!args.iter().any(|arg| arg.name == "dex_protocol"));
+	}
+
 	#[tokio::test]
 	async fn test_find_matching_events_non_matching_address() {
 		let filter = create_test_filter();
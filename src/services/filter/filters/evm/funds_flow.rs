@@ -0,0 +1,268 @@
+//! Funds-flow tracing for EVM transactions.
+//!
+//! Computes the net amount of each asset gained or lost by every address involved in a
+//! transaction. Native flows are derived from the transaction value and token flows from
+//! ERC20 `Transfer` logs. Value moved by internal calls is not visible in logs and is only
+//! included for the transactions traced for monitors with `internal_transfers`.
+
+use alloy::primitives::{Address, I256, U256};
+use serde_json::{json, Value as JsonValue};
+use std::collections::BTreeMap;
+
+use crate::{
+	models::{
+		EVMInternalTransfer, EVMMonitorMatch, EVMReceiptLog, EVMTraceStatus, EVMTransaction,
+		Monitor,
+	},
+	services::filter::{
+		evm_helpers::h160_to_string, filters::evm::heuristics::ERC20_TRANSFER_TOPIC,
+	},
+};
+
+/// Asset key used for the chain's native currency
+pub const NATIVE_ASSET: &str = "native";
+
+/// Net flows keyed by address, then by asset (`native` or the token address)
+pub type NetFlows = BTreeMap<String, BTreeMap<String, I256>>;
+
+/// Returns whether funds-flow tracing is enabled for a monitor
+///
+/// # Arguments
+/// * `monitor` - The monitor to inspect
+pub fn is_enabled(monitor: &Monitor) -> bool {
+	monitor
+		.chain_configurations
+		.iter()
+		.filter_map(|config| config.evm.as_ref())
+		.any(|evm| evm.funds_flow)
+}
+
+/// Computes the net flow of every asset per address for a transaction
+///
+/// Addresses whose flows cancel out (e.g. routers forwarding tokens) are omitted.
+///
+/// # Arguments
+/// * `transaction` - The transaction to analyze
+/// * `logs` - Logs emitted by the transaction
+/// * `internal_transfers` - Native value moved by internal calls, from the transaction trace
+/// * `succeeded` - Whether the transaction succeeded; failed transactions move no value
+///
+/// # Returns
+/// The non-zero net flows keyed by address and asset
+pub fn compute_net_flows(
+	transaction: &EVMTransaction,
+	logs: &[EVMReceiptLog],
+	internal_transfers: &[EVMInternalTransfer],
+	succeeded: bool,
+) -> NetFlows {
+	let mut flows = NetFlows::new();
+	if !succeeded {
+		return flows;
+	}
+
+	if let (Some(from), Some(to)) = (transaction.from, transaction.to) {
+		record_transfer(
+			&mut flows,
+			NATIVE_ASSET,
+			&h160_to_string(from),
+			&h160_to_string(to),
+			transaction.value,
+		);
+	}
+
+	for transfer in internal_transfers {
+		record_transfer(
+			&mut flows,
+			NATIVE_ASSET,
+			&transfer.from,
+			&transfer.to,
+			transfer.value,
+		);
+	}
+
+	for log in logs {
+		if log.topics.len() != 3 || log.topics[0] != ERC20_TRANSFER_TOPIC {
+			continue;
+		}
+		let Some(amount) = log.data.get(0..32).and_then(|word| word.try_into().ok()) else {
+			continue;
+		};
+		record_transfer(
+			&mut flows,
+			&h160_to_string(log.address),
+			&h160_to_string(Address::from_word(log.topics[1])),
+			&h160_to_string(Address::from_word(log.topics[2])),
+			U256::from_be_bytes::<32>(amount),
+		);
+	}
+
+	for assets in flows.values_mut() {
+		assets.retain(|_, net| !net.is_zero());
+	}
+	flows.retain(|_, assets| !assets.is_empty());
+	flows
+}
+
+/// Builds the `funds_flow` template variables for a matched transaction
+///
+/// The result maps `address -> asset -> signed net amount` in raw units, so the flattened
+/// variables read `funds_flow.<address>.<asset>` (e.g. `funds_flow.0xabc...native`).
+///
+/// # Arguments
+/// * `evm_match` - The matched transaction
+///
+/// # Returns
+/// The JSON object to expose under the `funds_flow` key
+pub fn to_template_json(evm_match: &EVMMonitorMatch) -> JsonValue {
+	let succeeded = evm_match
+		.receipt
+		.as_ref()
+		.and_then(|receipt| receipt.status)
+		.map(|status| status.to::<u64>() == 1)
+		// Without a receipt the transaction is assumed successful, like in the filter
+		.unwrap_or(true);
+	let internal_transfers = evm_match
+		.trace
+		.as_ref()
+		.filter(|trace| trace.status == EVMTraceStatus::Traced)
+		.map(|trace| trace.internal_transfers.as_slice())
+		.unwrap_or_default();
+	let flows = compute_net_flows(
+		&evm_match.transaction,
+		evm_match.logs.as_deref().unwrap_or_default(),
+		internal_transfers,
+		succeeded,
+	);
+
+	let flows: BTreeMap<String, BTreeMap<String, String>> = flows
+		.into_iter()
+		.map(|(address, assets)| {
+			let assets = assets
+				.into_iter()
+				.map(|(asset, net)| (asset, net.to_string()))
+				.collect();
+			(address, assets)
+		})
+		.collect();
+	json!(flows)
+}
+
+fn record_transfer(flows: &mut NetFlows, asset: &str, from: &str, to: &str, amount: U256) {
+	if amount.is_zero() || from == to {
+		return;
+	}
+	let amount = I256::try_from(amount).unwrap_or(I256::MAX);
+	let outflow = flows
+		.entry(from.to_string())
+		.or_default()
+		.entry(asset.to_string())
+		.or_default();
+	*outflow = outflow.saturating_sub(amount);
+	let inflow = flows
+		.entry(to.to_string())
+		.or_default()
+		.entry(asset.to_string())
+		.or_default();
+	*inflow = inflow.saturating_add(amount);
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::utils::tests::evm::transaction::TransactionBuilder;
+	use alloy::primitives::{Bytes, B256};
+
+	fn address_topic(address: Address) -> B256 {
+		B256::from_slice(&[&[0u8; 12], address.as_slice()].concat())
+	}
+
+	fn transfer_log(token: Address, from: Address, to: Address, amount: u64) -> EVMReceiptLog {
+		EVMReceiptLog {
+			address: token,
+			topics: vec![ERC20_TRANSFER_TOPIC, address_topic(from), address_topic(to)],
+			data: Bytes::from(U256::from(amount).to_be_bytes::<32>().to_vec()),
+			block_hash: None,
+			block_number: None,
+			transaction_hash: None,
+			transaction_index: None,
+			log_index: None,
+			transaction_log_index: None,
+			log_type: None,
+			removed: Some(false),
+		}
+	}
+
+	#[test]
+	fn test_compute_net_flows_native_and_tokens() {
+		let treasury = Address::with_last_byte(1);
+		let router = Address::with_last_byte(2);
+		let attacker = Address::with_last_byte(3);
+		let usdc = Address::with_last_byte(10);
+
+		let transaction = TransactionBuilder::new()
+			.from(attacker)
+			.to(router)
+			.value(U256::from(5))
+			.build();
+		let logs = vec![
+			transfer_log(usdc, treasury, router, 1_200_000),
+			transfer_log(usdc, router, attacker, 1_200_000),
+		];
+
+		let flows = compute_net_flows(&transaction, &logs, &[], true);
+
+		let net = |address: Address, asset: &str| {
+			flows
+				.get(&h160_to_string(address))
+				.and_then(|assets| assets.get(asset))
+				.map(|net| net.to_string())
+		};
+		assert_eq!(
+			net(treasury, &h160_to_string(usdc)),
+			Some("-1200000".to_string())
+		);
+		assert_eq!(
+			net(attacker, &h160_to_string(usdc)),
+			Some("1200000".to_string())
+		);
+		assert_eq!(net(attacker, NATIVE_ASSET), Some("-5".to_string()));
+		assert_eq!(net(router, NATIVE_ASSET), Some("5".to_string()));
+		// The router only forwarded tokens, so it has no token flow
+		assert_eq!(net(router, &h160_to_string(usdc)), None);
+	}
+
+	#[test]
+	fn test_compute_net_flows_internal_transfers() {
+		let user = Address::with_last_byte(1);
+		let vault = Address::with_last_byte(2);
+		let recipient = Address::with_last_byte(3);
+
+		let transaction = TransactionBuilder::new().from(user).to(vault).build();
+		let internal_transfers = vec![EVMInternalTransfer {
+			from: h160_to_string(vault),
+			to: h160_to_string(recipient),
+			value: U256::from(7),
+		}];
+
+		let flows = compute_net_flows(&transaction, &[], &internal_transfers, true);
+		assert_eq!(
+			flows[&h160_to_string(vault)][NATIVE_ASSET].to_string(),
+			"-7"
+		);
+		assert_eq!(
+			flows[&h160_to_string(recipient)][NATIVE_ASSET].to_string(),
+			"7"
+		);
+	}
+
+	#[test]
+	fn test_compute_net_flows_failed_transaction() {
+		let transaction = TransactionBuilder::new()
+			.from(Address::with_last_byte(1))
+			.to(Address::with_last_byte(2))
+			.value(U256::from(5))
+			.build();
+
+		assert!(compute_net_flows(&transaction, &[], &[], false).is_empty());
+	}
+}
@@ -0,0 +1,483 @@
+//! Per-block heuristics for EVM transactions.
+//!
+//! This module implements an optional analysis pass over all transactions of a block that
+//! flags common attack patterns:
+//! - Flashloans: a token is transferred to an address and transferred back to the same
+//!   lender, for at least the same amount, within a single transaction
+//! - Sandwiches: a sender swaps on a pool, another sender swaps in the same direction on the
+//!   same pool, and the first sender swaps in the opposite direction later in the block
+//!
+//! The resulting flags are exposed as transaction fields so they can be matched with
+//! transaction condition expressions.
+
+use alloy::primitives::{b256, Address, B256, U256};
+use std::collections::HashMap;
+
+use crate::{
+	models::{EVMHeuristicsConfig, EVMMatchParamEntry, EVMReceiptLog, EVMTransaction, Monitor},
+	services::filter::{
+		evm_helpers::{b256_to_string, string_to_u256},
+		filters::evm::dex,
+	},
+};
+
+/// Topic of the ERC20 `Transfer(address,address,uint256)` event
+pub const ERC20_TRANSFER_TOPIC: B256 =
+	b256!("ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef");
+
+/// Role of a transaction in a detected sandwich
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SandwichRole {
+	/// Attacker swap placed before the victim
+	Frontrun,
+	/// Swap executed between the attacker's swaps
+	Victim,
+	/// Attacker swap reverting the front-run position after the victim
+	Backrun,
+}
+
+impl SandwichRole {
+	/// Returns the role name exposed to expressions
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			Self::Frontrun => "frontrun",
+			Self::Victim => "victim",
+			Self::Backrun => "backrun",
+		}
+	}
+}
+
+/// Heuristics flags computed for a single transaction
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TransactionHeuristics {
+	/// Largest amount borrowed and repaid within the transaction
+	pub flashloan_amount: Option<U256>,
+
+	/// Role of the transaction in a detected sandwich
+	pub sandwich_role: Option<SandwichRole>,
+}
+
+impl TransactionHeuristics {
+	/// Builds the transaction fields exposed to condition expressions
+	///
+	/// Only the fields of the heuristics enabled in the configuration are returned.
+	///
+	/// # Arguments
+	/// * `config` - Heuristics configuration of the monitor
+	///
+	/// # Returns
+	/// The fields to add to the transaction parameters
+	pub fn to_params(&self, config: &EVMHeuristicsConfig) -> Vec<EVMMatchParamEntry> {
+		let mut params = Vec::new();
+
+		if config.flashloan {
+			let min_amount = config
+				.flashloan_min_amount
+				.as_deref()
+				.and_then(|amount| string_to_u256(amount).ok())
+				.unwrap_or(U256::ZERO);
+			let is_flashloan = self
+				.flashloan_amount
+				.is_some_and(|amount| amount >= min_amount);
+			params.push(param("is_flashloan", &is_flashloan.to_string(), "bool"));
+			params.push(param(
+				"flashloan_amount",
+				&self.flashloan_amount.unwrap_or_default().to_string(),
+				"uint256",
+			));
+		}
+
+		if config.sandwich {
+			params.push(param(
+				"is_sandwich",
+				&self.sandwich_role.is_some().to_string(),
+				"bool",
+			));
+			params.push(param(
+				"sandwich_role",
+				self.sandwich_role.map_or("none", |role| role.as_str()),
+				"string",
+			));
+		}
+
+		params
+	}
+}
+
+/// Returns the heuristics configuration of a monitor, if any heuristic is enabled
+///
+/// # Arguments
+/// * `monitor` - The monitor to inspect
+///
+/// # Returns
+/// The heuristics configuration, or None if the analysis is disabled for the monitor
+pub fn monitor_config(monitor: &Monitor) -> Option<&EVMHeuristicsConfig> {
+	monitor
+		.chain_configurations
+		.iter()
+		.filter_map(|config| config.evm.as_ref())
+		.filter_map(|evm| evm.heuristics.as_ref())
+		.find(|heuristics| heuristics.flashloan || heuristics.sandwich)
+}
+
+/// Runs all heuristics over the transactions of a block
+///
+/// # Arguments
+/// * `transactions` - Transactions of the block, in block order
+/// * `logs_by_tx` - Logs of the block grouped by transaction hash
+///
+/// # Returns
+/// The flags of every transaction that matched at least one heuristic, keyed by hash
+pub fn analyze_block(
+	transactions: &[EVMTransaction],
+	logs_by_tx: &HashMap<String, Vec<EVMReceiptLog>>,
+) -> HashMap<String, TransactionHeuristics> {
+	let mut results: HashMap<String, TransactionHeuristics> = HashMap::new();
+	let mut swaps = Vec::new();
+
+	for transaction in transactions {
+		let tx_hash = b256_to_string(transaction.hash);
+		let Some(logs) = logs_by_tx.get(&tx_hash) else {
+			continue;
+		};
+
+		if let Some(amount) = detect_flashloan(logs) {
+			results.entry(tx_hash.clone()).or_default().flashloan_amount = Some(amount);
+		}
+
+		swaps.extend(logs.iter().filter_map(|log| {
+			dex::swap_direction(log).map(|zero_for_one| SwapObservation {
+				tx_hash: tx_hash.clone(),
+				sender: transaction.from.unwrap_or_default(),
+				pool: log.address,
+				zero_for_one,
+			})
+		}));
+	}
+
+	for (tx_hash, role) in detect_sandwiches(&swaps) {
+		results.entry(tx_hash).or_default().sandwich_role = Some(role);
+	}
+
+	results
+}
+
+/// Detects a borrow and repay of the same token within a transaction
+///
+/// A borrow is a `Transfer` of a token from a lender to a borrower; it is repaid when a
+/// later `Transfer` of the same token moves at least the same amount from the borrower back
+/// to the lender.
+///
+/// # Arguments
+/// * `logs` - Logs of a single transaction, in emission order
+///
+/// # Returns
+/// The largest repaid borrow amount, or None if no borrow and repay pair was found
+pub fn detect_flashloan(logs: &[EVMReceiptLog]) -> Option<U256> {
+	let transfers: Vec<TokenTransfer> = logs.iter().filter_map(TokenTransfer::from_log).collect();
+
+	transfers
+		.iter()
+		.enumerate()
+		.filter(|(_, borrow)| borrow.from != borrow.to)
+		.filter(|(index, borrow)| {
+			transfers[index + 1..].iter().any(|repay| {
+				repay.token == borrow.token
+					&& repay.from == borrow.to
+					&& repay.to == borrow.from
+					&& repay.amount >= borrow.amount
+			})
+		})
+		.map(|(_, borrow)| borrow.amount)
+		.max()
+}
+
+/// Detects sandwiches among the swaps of a block
+///
+/// # Arguments
+/// * `swaps` - Swaps of the block, in execution order
+///
+/// # Returns
+/// The role of every transaction involved in a sandwich, keyed by hash. Attacker roles take
+/// precedence over the victim role.
+fn detect_sandwiches(swaps: &[SwapObservation]) -> HashMap<String, SandwichRole> {
+	let mut roles = HashMap::new();
+
+	for (front_index, front) in swaps.iter().enumerate() {
+		let later = &swaps[front_index + 1..];
+		let Some(back_offset) = later.iter().position(|back| {
+			back.pool == front.pool
+				&& back.sender == front.sender
+				&& back.tx_hash != front.tx_hash
+				&& back.zero_for_one != front.zero_for_one
+		}) else {
+			continue;
+		};
+
+		let victims: Vec<&SwapObservation> = later[..back_offset]
+			.iter()
+			.filter(|swap| {
+				swap.pool == front.pool
+					&& swap.sender != front.sender
+					&& swap.zero_for_one == front.zero_for_one
+			})
+			.collect();
+		if victims.is_empty() {
+			continue;
+		}
+
+		for victim in victims {
+			roles
+				.entry(victim.tx_hash.clone())
+				.or_insert(SandwichRole::Victim);
+		}
+		roles.insert(front.tx_hash.clone(), SandwichRole::Frontrun);
+		roles.insert(later[back_offset].tx_hash.clone(), SandwichRole::Backrun);
+	}
+
+	roles
+}
+
+/// Swap observed in a block, reduced to what the sandwich heuristic needs
+struct SwapObservation {
+	tx_hash: String,
+	sender: Address,
+	pool: Address,
+	zero_for_one: bool,
+}
+
+/// ERC20 transfer decoded from a raw `Transfer` log
+struct TokenTransfer {
+	token: Address,
+	from: B256,
+	to: B256,
+	amount: U256,
+}
+
+impl TokenTransfer {
+	fn from_log(log: &EVMReceiptLog) -> Option<Self> {
+		// ERC721 transfers share the topic but index the token id, so require exactly
+		// three topics and a 32 bytes amount in the data
+		if log.topics.len() != 3 || log.topics[0] != ERC20_TRANSFER_TOPIC {
+			return None;
+		}
+		let amount: [u8; 32] = log.data.get(0..32)?.try_into().ok()?;
+		Some(Self {
+			token: log.address,
+			from: log.topics[1],
+			to: log.topics[2],
+			amount: U256::from_be_bytes(amount),
+		})
+	}
+}
+
+fn param(name: &str, value: &str, kind: &str) -> EVMMatchParamEntry {
+	EVMMatchParamEntry {
+		name: name.to_string(),
+		value: value.to_string(),
+		kind: kind.to_string(),
+		indexed: false,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::utils::tests::evm::transaction::TransactionBuilder;
+	use alloy::core::json_abi::Event;
+	use alloy::primitives::Bytes;
+
+	fn address_topic(address: Address) -> B256 {
+		B256::from_slice(&[&[0u8; 12], address.as_slice()].concat())
+	}
+
+	fn create_log(address: Address, topics: Vec<B256>, data: Vec<u8>) -> EVMReceiptLog {
+		EVMReceiptLog {
+			address,
+			topics,
+			data: Bytes::from(data),
+			block_hash: None,
+			block_number: None,
+			transaction_hash: None,
+			transaction_index: None,
+			log_index: None,
+			transaction_log_index: None,
+			log_type: None,
+			removed: Some(false),
+		}
+	}
+
+	fn transfer_log(token: Address, from: Address, to: Address, amount: u64) -> EVMReceiptLog {
+		create_log(
+			token,
+			vec![ERC20_TRANSFER_TOPIC, address_topic(from), address_topic(to)],
+			U256::from(amount).to_be_bytes::<32>().to_vec(),
+		)
+	}
+
+	fn v2_swap_log(pool: Address, zero_for_one: bool) -> EVMReceiptLog {
+		let event = Event::parse("event Swap(address indexed sender, uint256 amount0In, uint256 amount1In, uint256 amount0Out, uint256 amount1Out, address indexed to)").unwrap();
+		let amounts: [u64; 4] = if zero_for_one {
+			[100, 0, 0, 90]
+		} else {
+			[0, 100, 90, 0]
+		};
+		create_log(
+			pool,
+			vec![
+				event.selector(),
+				address_topic(Address::ZERO),
+				address_topic(Address::ZERO),
+			],
+			amounts
+				.iter()
+				.flat_map(|amount| U256::from(*amount).to_be_bytes::<32>())
+				.collect(),
+		)
+	}
+
+	fn transaction(hash_byte: u8, from: Address) -> EVMTransaction {
+		TransactionBuilder::new()
+			.hash(B256::with_last_byte(hash_byte))
+			.from(from)
+			.build()
+	}
+
+	#[test]
+	fn test_detect_flashloan_borrow_and_repay() {
+		let token = Address::with_last_byte(1);
+		let lender = Address::with_last_byte(2);
+		let borrower = Address::with_last_byte(3);
+		let other = Address::with_last_byte(4);
+
+		let logs = vec![
+			transfer_log(token, lender, borrower, 1_000_000),
+			transfer_log(token, borrower, other, 10),
+			transfer_log(token, borrower, lender, 1_000_900),
+		];
+		assert_eq!(detect_flashloan(&logs), Some(U256::from(1_000_000)));
+
+		// Partial repayment is not a flashloan
+		let logs = vec![
+			transfer_log(token, lender, borrower, 1_000_000),
+			transfer_log(token, borrower, lender, 999_999),
+		];
+		assert_eq!(detect_flashloan(&logs), None);
+
+		// Repayment in another token is not a flashloan
+		let logs = vec![
+			transfer_log(token, lender, borrower, 1_000_000),
+			transfer_log(other, borrower, lender, 1_000_000),
+		];
+		assert_eq!(detect_flashloan(&logs), None);
+	}
+
+	#[test]
+	fn test_analyze_block_detects_sandwich() {
+		let pool = Address::with_last_byte(10);
+		let attacker = Address::with_last_byte(1);
+		let victim = Address::with_last_byte(2);
+		let bystander = Address::with_last_byte(3);
+
+		let transactions = vec![
+			transaction(1, attacker),
+			transaction(2, victim),
+			transaction(3, bystander),
+			transaction(4, attacker),
+		];
+		let logs_by_tx = HashMap::from([
+			(
+				b256_to_string(B256::with_last_byte(1)),
+				vec![v2_swap_log(pool, true)],
+			),
+			(
+				b256_to_string(B256::with_last_byte(2)),
+				vec![v2_swap_log(pool, true)],
+			),
+			(
+				b256_to_string(B256::with_last_byte(3)),
+				vec![v2_swap_log(pool, false)],
+			),
+			(
+				b256_to_string(B256::with_last_byte(4)),
+				vec![v2_swap_log(pool, false)],
+			),
+		]);
+
+		let results = analyze_block(&transactions, &logs_by_tx);
+		let role = |byte: u8| {
+			results
+				.get(&b256_to_string(B256::with_last_byte(byte)))
+				.and_then(|h| h.sandwich_role)
+		};
+
+		assert_eq!(role(1), Some(SandwichRole::Frontrun));
+		assert_eq!(role(2), Some(SandwichRole::Victim));
+		assert_eq!(role(3), None);
+		assert_eq!(role(4), Some(SandwichRole::Backrun));
+	}
+
+	#[test]
+	fn test_analyze_block_ignores_swaps_on_other_pools() {
+		let attacker = Address::with_last_byte(1);
+		let victim = Address::with_last_byte(2);
+
+		let transactions = vec![
+			transaction(1, attacker),
+			transaction(2, victim),
+			transaction(3, attacker),
+		];
+		let logs_by_tx = HashMap::from([
+			(
+				b256_to_string(B256::with_last_byte(1)),
+				vec![v2_swap_log(Address::with_last_byte(10), true)],
+			),
+			(
+				b256_to_string(B256::with_last_byte(2)),
+				vec![v2_swap_log(Address::with_last_byte(11), true)],
+			),
+			(
+				b256_to_string(B256::with_last_byte(3)),
+				vec![v2_swap_log(Address::with_last_byte(10), false)],
+			),
+		]);
+
+		assert!(analyze_block(&transactions, &logs_by_tx).is_empty());
+	}
+
+	#[test]
+	fn test_to_params_respects_config() {
+		let heuristics = TransactionHeuristics {
+			flashloan_amount: Some(U256::from(500)),
+			sandwich_role: Some(SandwichRole::Victim),
+		};
+
+		let params = heuristics.to_params(&EVMHeuristicsConfig {
+			flashloan: true,
+			flashloan_min_amount: Some("1000".to_string()),
+			sandwich: false,
+		});
+		assert_eq!(params.len(), 2);
+		assert_eq!(params[0].name, "is_flashloan");
+		assert_eq!(params[0].value, "false");
+		assert_eq!(params[1].value, "500");
+
+		let params = heuristics.to_params(&EVMHeuristicsConfig {
+			flashloan: false,
+			flashloan_min_amount: None,
+			sandwich: true,
+		});
+		assert_eq!(params.len(), 2);
+		assert_eq!(params[0].name, "is_sandwich");
+		assert_eq!(params[0].value, "true");
+		assert_eq!(params[1].value, "victim");
+
+		let params = TransactionHeuristics::default().to_params(&EVMHeuristicsConfig {
+			flashloan: true,
+			flashloan_min_amount: None,
+			sandwich: true,
+		});
+		assert_eq!(params[0].value, "false");
+		assert_eq!(params[3].value, "none");
+	}
+}
@@ -0,0 +1,177 @@
+//! First-time interaction tracking for EVM monitors.
+//!
+//! Keeps, per monitor and network, the last block at which each sender called each monitored
+//! contract. A call is a first-time interaction when the sender has not called the contract on
+//! the network within the monitor's lookback window. The sets are kept in a
+//! [`MonitorStateStore`] owned by the filter context, where the interactions of a block are
+//! committed once the block is handed over to the triggers.
+
+use crate::{
+	models::{EVMFirstInteractionConfig, Monitor},
+	services::filter::{
+		evm_helpers::normalize_address,
+		filters::evm::monitor_state::{MonitorStateStore, StateEntry},
+	},
+};
+
+/// Name of the interaction sets, and of their storage directory
+pub const INTERACTIONS_STATE: &str = "interactions";
+
+/// Last block at which a `sender|contract` pair interacted
+impl StateEntry for u64 {
+	fn block(&self) -> u64 {
+		*self
+	}
+
+	fn replaces(&self, committed: &Self) -> bool {
+		self > committed
+	}
+}
+
+/// Returns the first-time interaction configuration of a monitor, if enabled
+///
+/// # Arguments
+/// * `monitor` - The monitor to inspect
+pub fn monitor_config(monitor: &Monitor) -> Option<&EVMFirstInteractionConfig> {
+	monitor
+		.chain_configurations
+		.iter()
+		.filter_map(|config| config.evm.as_ref())
+		.find_map(|evm| evm.first_interaction.as_ref())
+}
+
+/// Stages an interaction and returns whether it is the first within the lookback window
+///
+/// # Arguments
+/// * `store` - Interaction sets of the monitors
+/// * `monitor_name` - Name of the monitor owning the set
+/// * `network_slug` - Network of the call, addresses being per network
+/// * `sender` - Address calling the contract
+/// * `contract` - Monitored contract being called
+/// * `block_number` - Block containing the call
+/// * `lookback_blocks` - Number of blocks after which a previous interaction is forgotten
+///
+/// # Returns
+/// `true` if the sender did not call the contract in the lookback window
+pub async fn record_interaction(
+	store: &MonitorStateStore<u64>,
+	monitor_name: &str,
+	network_slug: &str,
+	sender: &str,
+	contract: &str,
+	block_number: u64,
+	lookback_blocks: u64,
+) -> bool {
+	let key = format!(
+		"{}|{}",
+		normalize_address(sender),
+		normalize_address(contract)
+	);
+	store
+		.stage(
+			monitor_name,
+			network_slug,
+			block_number,
+			key,
+			Some(lookback_blocks),
+			|last_seen| {
+				let is_first = match last_seen {
+					Some(&last_seen) => block_number.saturating_sub(last_seen) > lookback_blocks,
+					None => true,
+				};
+				// Blocks processed again do not move the interaction backwards
+				let staged = last_seen
+					.is_none_or(|&last_seen| block_number > last_seen)
+					.then_some(block_number);
+				(is_first, staged)
+			},
+		)
+		.await
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use tempfile::TempDir;
+
+	const SENDER: &str = "0x0000000000000000000000000000000000000001";
+	const CONTRACT: &str = "0x00000000000000000000000000000000000000AA";
+	const NETWORK: &str = "ethereum_mainnet";
+
+	async fn record(
+		store: &MonitorStateStore<u64>,
+		monitor_name: &str,
+		network_slug: &str,
+		contract: &str,
+		block_number: u64,
+	) -> bool {
+		let is_first = record_interaction(
+			store,
+			monitor_name,
+			network_slug,
+			SENDER,
+			contract,
+			block_number,
+			10,
+		)
+		.await;
+		store.commit(network_slug, block_number);
+		is_first
+	}
+
+	#[tokio::test]
+	async fn test_record_interaction_lookback_window() {
+		let store = MonitorStateStore::new(INTERACTIONS_STATE);
+
+		assert!(record(&store, "monitor", NETWORK, CONTRACT, 100).await);
+		// Same sender within the window
+		assert!(!record(&store, "monitor", NETWORK, CONTRACT, 105).await);
+		// Address casing does not matter
+		assert!(!record(&store, "monitor", NETWORK, &CONTRACT.to_lowercase(), 110).await);
+		// Window is measured from the last interaction
+		assert!(!record(&store, "monitor", NETWORK, CONTRACT, 120).await);
+		assert!(record(&store, "monitor", NETWORK, CONTRACT, 131).await);
+		// Sets are kept per monitor
+		assert!(record(&store, "other monitor", NETWORK, CONTRACT, 131).await);
+	}
+
+	#[tokio::test]
+	async fn test_interactions_of_a_block_are_seen_before_commit() {
+		let store = MonitorStateStore::new(INTERACTIONS_STATE);
+
+		// Two calls of the same block, then a call of the next block filtered concurrently
+		for (block_number, expected) in [(100, true), (100, false), (101, false)] {
+			let is_first = record_interaction(
+				&store,
+				"monitor",
+				NETWORK,
+				SENDER,
+				CONTRACT,
+				block_number,
+				10,
+			)
+			.await;
+			assert_eq!(is_first, expected);
+		}
+	}
+
+	#[tokio::test]
+	async fn test_interactions_are_kept_per_network() {
+		let temp_dir = TempDir::new().unwrap();
+		let store = MonitorStateStore::new(INTERACTIONS_STATE);
+		store.configure_storage(temp_dir.path().to_path_buf());
+
+		assert!(record(&store, "monitor", NETWORK, CONTRACT, 100).await);
+		// The same counterparty is seen for the first time on another network
+		assert!(record(&store, "monitor", "base_mainnet", CONTRACT, 100).await);
+		assert!(!record(&store, "monitor", "base_mainnet", CONTRACT, 101).await);
+
+		store.persist().await;
+
+		// Each network reloads its own set
+		let store = MonitorStateStore::new(INTERACTIONS_STATE);
+		store.configure_storage(temp_dir.path().to_path_buf());
+		assert!(!record(&store, "monitor", NETWORK, CONTRACT, 105).await);
+		assert!(record(&store, "monitor", "polygon_mainnet", CONTRACT, 105).await);
+	}
+}
@@ -0,0 +1,470 @@
+//! Persisted state of the EVM filters, kept per monitor and network.
+//!
+//! Some filters remember what they saw in previous blocks (the senders of first-time
+//! interactions, the last nonces of monitored addresses). Blocks are filtered concurrently, so
+//! a filter does not change the state itself: it stages its entries under its block, and the
+//! entries of a block are committed once the block is handed over to the triggers, in block
+//! order. The committed entries are kept in memory and, once a storage path is configured,
+//! persisted in batches as one JSON file per monitor and network (`<network>.<monitor>.json`)
+//! so they survive restarts.
+//!
+//! Block filters are created per block through `BlockFilterFactory::filter`, so the stores
+//! are owned by the `FilterContext` of the filter service rather than by a filter instance.
+
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+	collections::{BTreeMap, HashMap},
+	path::{Path, PathBuf},
+	sync::{Mutex, MutexGuard, RwLock, TryLockError},
+};
+
+use crate::utils::normalize_string;
+
+/// Entry of a monitor state
+pub trait StateEntry: Clone + Serialize + DeserializeOwned + Send + Sync {
+	/// Block at which the entry was recorded
+	fn block(&self) -> u64;
+
+	/// Whether the entry, staged by a block, replaces the committed entry of its key
+	fn replaces(&self, committed: &Self) -> bool;
+}
+
+/// State of a single monitor on a network
+struct MonitorState<V> {
+	/// Committed entries, keyed by the filter
+	entries: HashMap<String, V>,
+	/// Entries staged by the blocks not committed yet
+	staged: BTreeMap<u64, HashMap<String, V>>,
+	/// Number of blocks after which a committed entry is pruned, if entries expire
+	retention: Option<u64>,
+	/// Whether the committed entries changed since the last write
+	dirty: bool,
+}
+
+impl<V> MonitorState<V> {
+	fn new(entries: HashMap<String, V>) -> Self {
+		Self {
+			entries,
+			staged: BTreeMap::new(),
+			retention: None,
+			dirty: false,
+		}
+	}
+}
+
+/// Store of the states of the monitors, keyed by normalized monitor name and network slug
+pub struct MonitorStateStore<V> {
+	/// Name of the state, used in logs and as the name of its storage directory
+	name: &'static str,
+	storage_path: RwLock<Option<PathBuf>>,
+	states: Mutex<HashMap<(String, String), MonitorState<V>>>,
+	/// Serializes the writes, so that an older snapshot never overwrites a newer one
+	persist_lock: tokio::sync::Mutex<()>,
+}
+
+impl<V: StateEntry> MonitorStateStore<V> {
+	/// Creates an empty store, persisted once a storage path is configured
+	///
+	/// # Arguments
+	/// * `name` - Name of the state, used in logs and as the name of its storage directory
+	pub fn new(name: &'static str) -> Self {
+		Self {
+			name,
+			storage_path: RwLock::new(None),
+			states: Mutex::new(HashMap::new()),
+			persist_lock: tokio::sync::Mutex::new(()),
+		}
+	}
+
+	/// Returns the name of the state
+	pub fn name(&self) -> &'static str {
+		self.name
+	}
+
+	/// Sets the directory where the states are persisted
+	///
+	/// States already loaded in memory are kept; states of other monitors are loaded from this
+	/// directory on first use.
+	///
+	/// # Arguments
+	/// * `path` - Directory holding one JSON file per monitor and network
+	pub fn configure_storage(&self, path: PathBuf) {
+		if let Ok(mut storage_path) = self.storage_path.write() {
+			*storage_path = Some(path);
+		}
+	}
+
+	/// Reads the entry of a key as seen by a block and stages its new value under the block
+	///
+	/// The block sees the entries it staged itself, then those staged by the closest earlier
+	/// block that is not committed yet, then the committed entries. Entries staged by later
+	/// blocks are not seen.
+	///
+	/// # Arguments
+	/// * `monitor_name` - Name of the monitor owning the state
+	/// * `network_slug` - Network of the state
+	/// * `block_number` - Block being filtered
+	/// * `key` - Key of the entry
+	/// * `retention` - Number of blocks after which an entry is pruned, if entries expire
+	/// * `stage` - Function reading the entry and returning the value to stage, if any
+	pub async fn stage<R>(
+		&self,
+		monitor_name: &str,
+		network_slug: &str,
+		block_number: u64,
+		key: String,
+		retention: Option<u64>,
+		stage: impl FnOnce(Option<&V>) -> (R, Option<V>),
+	) -> R {
+		self.load(monitor_name, network_slug).await;
+
+		let mut states = self.lock_states();
+		let state = states
+			.entry(state_key(monitor_name, network_slug))
+			.or_insert_with(|| MonitorState::new(HashMap::new()));
+		state.retention = retention;
+		let current = state
+			.staged
+			.range(..=block_number)
+			.rev()
+			.find_map(|(_, entries)| entries.get(&key))
+			.or_else(|| state.entries.get(&key));
+		let (result, value) = stage(current);
+		if let Some(value) = value {
+			state
+				.staged
+				.entry(block_number)
+				.or_default()
+				.insert(key, value);
+		}
+		result
+	}
+
+	/// Discards the entries staged by a previous filtering of a block on a network
+	///
+	/// # Arguments
+	/// * `network_slug` - Network of the block
+	/// * `block_number` - Block filtered again
+	pub fn discard(&self, network_slug: &str, block_number: u64) {
+		for ((_, network), state) in self.lock_states().iter_mut() {
+			if network == network_slug {
+				state.staged.remove(&block_number);
+			}
+		}
+	}
+
+	/// Commits the entries staged by a block on a network
+	///
+	/// Entries staged by earlier blocks that were never committed are dropped, and expired
+	/// entries are pruned.
+	///
+	/// # Arguments
+	/// * `network_slug` - Network of the block
+	/// * `block_number` - Block handed over to the triggers
+	pub fn commit(&self, network_slug: &str, block_number: u64) {
+		for ((_, network), state) in self.lock_states().iter_mut() {
+			if network != network_slug {
+				continue;
+			}
+			let later = state.staged.split_off(&block_number.saturating_add(1));
+			let mut committed = std::mem::replace(&mut state.staged, later);
+			for (key, value) in committed.remove(&block_number).unwrap_or_default() {
+				if state
+					.entries
+					.get(&key)
+					.is_none_or(|previous| value.replaces(previous))
+				{
+					state.entries.insert(key, value);
+					state.dirty = true;
+				}
+			}
+			if let Some(retention) = state.retention {
+				let count = state.entries.len();
+				state
+					.entries
+					.retain(|_, entry| block_number.saturating_sub(entry.block()) <= retention);
+				state.dirty |= state.entries.len() != count;
+			}
+		}
+	}
+
+	/// Writes the states whose committed entries changed since the last write
+	pub async fn persist(&self) {
+		let Some(storage_path) = self.storage_path() else {
+			return;
+		};
+		let _guard = self.persist_lock.lock().await;
+
+		let dirty = self.take_dirty(&mut self.lock_states());
+		for ((monitor_name, network_slug), entries) in dirty {
+			let path = file_path(&storage_path, &monitor_name, &network_slug);
+			let result = async {
+				tokio::fs::create_dir_all(&storage_path).await?;
+				tokio::fs::write(&path, serde_json::to_string(&entries)?).await?;
+				Ok::<(), anyhow::Error>(())
+			}
+			.await;
+
+			if let Err(e) = result {
+				self.mark_dirty(&monitor_name, &network_slug);
+				tracing::error!(
+					"Failed to persist {} of monitor '{}' to {}: {}",
+					self.name,
+					monitor_name,
+					path.display(),
+					e
+				);
+			}
+		}
+	}
+
+	/// Writes the states whose committed entries changed without awaiting, skipping the write
+	/// rather than waiting for a lock
+	///
+	/// Used by the panic hook, which cannot rely on the runtime nor on the locks held by the
+	/// panicking thread.
+	pub fn persist_blocking(&self) {
+		let Some(storage_path) = self
+			.storage_path
+			.try_read()
+			.ok()
+			.and_then(|storage_path| storage_path.clone())
+		else {
+			return;
+		};
+		let Ok(_guard) = self.persist_lock.try_lock() else {
+			tracing::error!("The {} are being persisted, skipping the write", self.name);
+			return;
+		};
+		let dirty = match self.states.try_lock() {
+			Ok(mut states) => self.take_dirty(&mut states),
+			Err(TryLockError::Poisoned(poisoned)) => self.take_dirty(&mut poisoned.into_inner()),
+			Err(TryLockError::WouldBlock) => {
+				tracing::error!("The {} are locked, skipping the write", self.name);
+				return;
+			}
+		};
+
+		for ((monitor_name, network_slug), entries) in dirty {
+			let path = file_path(&storage_path, &monitor_name, &network_slug);
+			let result = (|| {
+				std::fs::create_dir_all(&storage_path)?;
+				std::fs::write(&path, serde_json::to_string(&entries)?)?;
+				Ok::<(), anyhow::Error>(())
+			})();
+
+			if let Err(e) = result {
+				tracing::error!(
+					"Failed to persist {} of monitor '{}' to {}: {}",
+					self.name,
+					monitor_name,
+					path.display(),
+					e
+				);
+			}
+		}
+	}
+
+	/// Loads the persisted state of a monitor on a network on first use, starting empty if
+	/// there is none
+	async fn load(&self, monitor_name: &str, network_slug: &str) {
+		let key = state_key(monitor_name, network_slug);
+		if self.lock_states().contains_key(&key) {
+			return;
+		}
+		let entries = match self.storage_path() {
+			Some(storage_path) => {
+				tokio::fs::read_to_string(file_path(&storage_path, &key.0, &key.1))
+					.await
+					.ok()
+					.and_then(|content| serde_json::from_str(&content).ok())
+					.unwrap_or_default()
+			}
+			None => HashMap::new(),
+		};
+		// A state loaded concurrently is kept, as it may already have staged entries
+		self.lock_states()
+			.entry(key)
+			.or_insert_with(|| MonitorState::new(entries));
+	}
+
+	/// Clears the dirty flags and returns the committed entries of the states that had one
+	fn take_dirty(
+		&self,
+		states: &mut HashMap<(String, String), MonitorState<V>>,
+	) -> Vec<((String, String), HashMap<String, V>)> {
+		states
+			.iter_mut()
+			.filter(|(_, state)| state.dirty)
+			.map(|(key, state)| {
+				state.dirty = false;
+				(key.clone(), state.entries.clone())
+			})
+			.collect()
+	}
+
+	fn mark_dirty(&self, monitor_name: &str, network_slug: &str) {
+		if let Some(state) = self
+			.lock_states()
+			.get_mut(&state_key(monitor_name, network_slug))
+		{
+			state.dirty = true;
+		}
+	}
+
+	fn storage_path(&self) -> Option<PathBuf> {
+		self.storage_path.read().ok()?.clone()
+	}
+
+	fn lock_states(&self) -> MutexGuard<'_, HashMap<(String, String), MonitorState<V>>> {
+		match self.states.lock() {
+			Ok(states) => states,
+			Err(poisoned) => poisoned.into_inner(),
+		}
+	}
+}
+
+fn state_key(monitor_name: &str, network_slug: &str) -> (String, String) {
+	(normalize_string(monitor_name), network_slug.to_string())
+}
+
+/// Returns the file of a state, named `<network>.<monitor>.json`
+fn file_path(storage_path: &Path, monitor_name: &str, network_slug: &str) -> PathBuf {
+	let sanitize = |name: &str| -> String {
+		name.chars()
+			.map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+			.collect()
+	};
+	storage_path.join(format!(
+		"{}.{}.json",
+		sanitize(network_slug),
+		sanitize(&normalize_string(monitor_name))
+	))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use tempfile::TempDir;
+
+	/// Stages the block of the latest increment and returns the number of increments seen
+	async fn increment(
+		store: &MonitorStateStore<u64>,
+		monitor: &str,
+		network: &str,
+		block: u64,
+	) -> Option<u64> {
+		store
+			.stage(monitor, network, block, "last".to_string(), None, |last| {
+				(last.copied(), Some(block))
+			})
+			.await
+	}
+
+	#[tokio::test]
+	async fn test_states_are_kept_per_monitor_and_network() {
+		let temp_dir = TempDir::new().unwrap();
+		let store = MonitorStateStore::<u64>::new("counters");
+		store.configure_storage(temp_dir.path().to_path_buf());
+
+		assert_eq!(
+			increment(&store, "Monitor", "ethereum_mainnet", 1).await,
+			None
+		);
+		assert_eq!(
+			increment(&store, "monitor", "ethereum_mainnet", 1).await,
+			Some(1)
+		);
+		assert_eq!(increment(&store, "monitor", "base_mainnet", 1).await, None);
+		store.commit("ethereum_mainnet", 1);
+
+		store.persist().await;
+		assert!(temp_dir
+			.path()
+			.join("ethereum_mainnet.monitor.json")
+			.exists());
+		// Entries are only written once committed
+		assert!(!temp_dir.path().join("base_mainnet.monitor.json").exists());
+
+		// A fresh store reloads the state from disk
+		let store = MonitorStateStore::<u64>::new("counters");
+		store.configure_storage(temp_dir.path().to_path_buf());
+		assert_eq!(
+			increment(&store, "monitor", "ethereum_mainnet", 2).await,
+			Some(1)
+		);
+		assert_eq!(increment(&store, "monitor", "base_mainnet", 2).await, None);
+	}
+
+	#[tokio::test]
+	async fn test_staged_entries_are_committed_in_block_order() {
+		let store = MonitorStateStore::<u64>::new("blocks");
+
+		// Block 12 is filtered before block 11 and does not see its entry
+		assert_eq!(
+			increment(&store, "monitor", "ethereum_mainnet", 10).await,
+			None
+		);
+		assert_eq!(
+			increment(&store, "monitor", "ethereum_mainnet", 12).await,
+			Some(10)
+		);
+		assert_eq!(
+			increment(&store, "monitor", "ethereum_mainnet", 11).await,
+			Some(10)
+		);
+
+		// A block filtered again does not see its previous filtering
+		store.discard("ethereum_mainnet", 11);
+		assert_eq!(
+			increment(&store, "monitor", "ethereum_mainnet", 11).await,
+			Some(10)
+		);
+
+		// Nothing is committed before the block is handed over
+		assert!(store
+			.lock_states()
+			.values()
+			.all(|state| state.entries.is_empty()));
+		store.commit("ethereum_mainnet", 11);
+		assert_eq!(
+			store.lock_states().values().next().unwrap().entries["last"],
+			11
+		);
+		// The entry of block 10, never committed, was dropped with it
+		assert_eq!(
+			store
+				.lock_states()
+				.values()
+				.next()
+				.unwrap()
+				.staged
+				.keys()
+				.collect::<Vec<_>>(),
+			vec![&12]
+		);
+	}
+
+	#[tokio::test]
+	async fn test_commit_prunes_expired_entries() {
+		let store = MonitorStateStore::<u64>::new("blocks");
+		for (key, block) in [("old", 100), ("recent", 195)] {
+			store
+				.stage(
+					"monitor",
+					"ethereum_mainnet",
+					block,
+					key.to_string(),
+					Some(10),
+					|_| ((), Some(block)),
+				)
+				.await;
+			store.commit("ethereum_mainnet", block);
+		}
+
+		let states = store.lock_states();
+		let entries = &states.values().next().unwrap().entries;
+		assert_eq!(entries.len(), 1);
+		assert!(entries.contains_key("recent"));
+	}
+}
@@ -0,0 +1,438 @@
+//! Nonce anomaly detection for EVM monitors.
+//!
+//! Keeps, per monitor and network, the nonce and block of the last transaction sent by each
+//! monitored address. A compromised key often shows up as transactions the operator did not
+//! send: nonces are skipped because transactions were sent through another node or bundler, or
+//! a key that was expected to stay cold starts sending. The states are kept in a
+//! [`MonitorStateStore`] owned by the filter context, where the transactions of a block are
+//! committed once the block is handed over to the triggers.
+//!
+//! A block filtered again after later blocks were committed, such as a recovered block, sees
+//! the state of the later blocks. Its transactions are neither compared with nor recorded over
+//! that state.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	models::{EVMMatchParamEntry, EVMNonceAnomalyConfig, Monitor},
+	services::filter::{
+		evm_helpers::{are_same_address, normalize_address},
+		filters::evm::monitor_state::{MonitorStateStore, StateEntry},
+	},
+};
+
+/// Name of the sender states, and of their storage directory
+pub const NONCES_STATE: &str = "nonces";
+
+/// Returns the nonce anomaly configuration of a monitor, if enabled
+///
+/// # Arguments
+/// * `monitor` - The monitor to inspect
+pub fn monitor_config(monitor: &Monitor) -> Option<&EVMNonceAnomalyConfig> {
+	monitor
+		.chain_configurations
+		.iter()
+		.filter_map(|config| config.evm.as_ref())
+		.find_map(|evm| evm.nonce_anomaly.as_ref())
+}
+
+/// Nonce anomalies detected in a single transaction
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NonceSignals {
+	/// Nonce following the previous transaction of the sender, `None` if it is unknown
+	pub expected_nonce: Option<u64>,
+	/// Number of nonces skipped since the previous transaction of the sender
+	pub nonce_gap: u64,
+	/// Blocks since the previous transaction of the sender, `None` if it is unknown
+	pub idle_blocks: Option<u64>,
+	/// The sender is listed as dormant, or was idle for longer than `dormant_after_blocks`
+	pub dormant_sender: bool,
+}
+
+impl NonceSignals {
+	/// Converts the signals into transaction fields usable in expressions
+	///
+	/// # Arguments
+	/// * `nonce` - Nonce of the transaction, reported as expected when there is no history
+	pub fn to_params(&self, nonce: u64) -> Vec<EVMMatchParamEntry> {
+		vec![
+			param("is_nonce_gap", &(self.nonce_gap > 0).to_string(), "bool"),
+			param("nonce_gap", &self.nonce_gap.to_string(), "uint256"),
+			param(
+				"expected_nonce",
+				&self.expected_nonce.unwrap_or(nonce).to_string(),
+				"uint256",
+			),
+			param(
+				"is_dormant_sender",
+				&self.dormant_sender.to_string(),
+				"bool",
+			),
+			param(
+				"idle_blocks",
+				&self.idle_blocks.unwrap_or_default().to_string(),
+				"uint256",
+			),
+		]
+	}
+}
+
+fn param(name: &str, value: &str, kind: &str) -> EVMMatchParamEntry {
+	EVMMatchParamEntry {
+		name: name.to_string(),
+		value: value.to_string(),
+		kind: kind.to_string(),
+		indexed: false,
+	}
+}
+
+/// Nonce and block of the last transaction of a sender
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SenderState {
+	nonce: u64,
+	block: u64,
+}
+
+impl StateEntry for SenderState {
+	fn block(&self) -> u64 {
+		self.block
+	}
+
+	fn replaces(&self, committed: &Self) -> bool {
+		self.nonce > committed.nonce
+	}
+}
+
+/// Stages a transaction sent by a monitored address and returns its deviations from the
+/// previous transaction of the address
+///
+/// # Arguments
+/// * `store` - Sender states of the monitors
+/// * `monitor_name` - Name of the monitor owning the states
+/// * `network_slug` - Network of the transaction, nonces being per network
+/// * `sender` - Monitored address sending the transaction
+/// * `nonce` - Nonce of the transaction
+/// * `block_number` - Block containing the transaction
+/// * `config` - The monitor's nonce anomaly configuration
+///
+/// # Returns
+/// The detected signals
+pub async fn record_transaction(
+	store: &MonitorStateStore<SenderState>,
+	monitor_name: &str,
+	network_slug: &str,
+	sender: &str,
+	nonce: u64,
+	block_number: u64,
+	config: &EVMNonceAnomalyConfig,
+) -> NonceSignals {
+	let listed_dormant = config
+		.dormant_addresses
+		.iter()
+		.any(|address| are_same_address(address, sender));
+
+	store
+		.stage(
+			monitor_name,
+			network_slug,
+			block_number,
+			normalize_address(sender),
+			None,
+			|previous| {
+				let previous = previous.copied();
+
+				// The state of a later block says nothing about the nonce of an earlier one
+				if previous.is_some_and(|state| block_number < state.block) {
+					let signals = NonceSignals {
+						dormant_sender: listed_dormant,
+						..NonceSignals::default()
+					};
+					return (signals, None);
+				}
+
+				let expected_nonce = previous.map(|state| state.nonce.saturating_add(1));
+				let idle_blocks = previous.map(|state| block_number.saturating_sub(state.block));
+				let dormant_sender = listed_dormant
+					|| matches!(
+						(config.dormant_after_blocks, idle_blocks),
+						(Some(after), Some(idle)) if idle > after
+					);
+
+				// Transactions of blocks processed again do not move the state backwards
+				let staged =
+					previous
+						.is_none_or(|state| nonce > state.nonce)
+						.then_some(SenderState {
+							nonce,
+							block: block_number,
+						});
+
+				let signals = NonceSignals {
+					expected_nonce,
+					nonce_gap: expected_nonce.map_or(0, |expected| nonce.saturating_sub(expected)),
+					idle_blocks,
+					dormant_sender,
+				};
+				(signals, staged)
+			},
+		)
+		.await
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use tempfile::TempDir;
+
+	const SENDER: &str = "0x00000000000000000000000000000000000000AA";
+
+	/// Stages a transaction, then commits its block
+	async fn record(
+		store: &MonitorStateStore<SenderState>,
+		monitor_name: &str,
+		network_slug: &str,
+		sender: &str,
+		nonce: u64,
+		block_number: u64,
+		config: &EVMNonceAnomalyConfig,
+	) -> NonceSignals {
+		let signals = record_transaction(
+			store,
+			monitor_name,
+			network_slug,
+			sender,
+			nonce,
+			block_number,
+			config,
+		)
+		.await;
+		store.commit(network_slug, block_number);
+		signals
+	}
+
+	#[tokio::test]
+	async fn test_record_transaction_nonce_gap() {
+		let store = MonitorStateStore::new(NONCES_STATE);
+		let config = EVMNonceAnomalyConfig::default();
+
+		// Without history nothing is expected
+		let signals = record(
+			&store,
+			"monitor",
+			"ethereum_mainnet",
+			SENDER,
+			5,
+			100,
+			&config,
+		)
+		.await;
+		assert_eq!(signals, NonceSignals::default());
+
+		let signals = record(
+			&store,
+			"monitor",
+			"ethereum_mainnet",
+			SENDER,
+			6,
+			101,
+			&config,
+		)
+		.await;
+		assert_eq!(signals.expected_nonce, Some(6));
+		assert_eq!(signals.nonce_gap, 0);
+
+		// Nonces 7 to 9 were never seen
+		let signals = record(
+			&store,
+			"monitor",
+			"ethereum_mainnet",
+			&SENDER.to_lowercase(),
+			10,
+			102,
+			&config,
+		)
+		.await;
+		assert_eq!(signals.nonce_gap, 3);
+		assert_eq!(signals.idle_blocks, Some(1));
+
+		// Blocks processed again do not report a gap nor move the state backwards
+		let signals = record(
+			&store,
+			"monitor",
+			"ethereum_mainnet",
+			SENDER,
+			6,
+			101,
+			&config,
+		)
+		.await;
+		assert_eq!(signals, NonceSignals::default());
+		let signals = record(
+			&store,
+			"monitor",
+			"ethereum_mainnet",
+			SENDER,
+			11,
+			103,
+			&config,
+		)
+		.await;
+		assert_eq!(signals.expected_nonce, Some(11));
+		assert_eq!(signals.nonce_gap, 0);
+
+		// Nonces are tracked per network and per monitor
+		let signals = record(&store, "monitor", "base_mainnet", SENDER, 10, 102, &config).await;
+		assert_eq!(signals.expected_nonce, None);
+		let signals = record(
+			&store,
+			"other",
+			"ethereum_mainnet",
+			SENDER,
+			10,
+			102,
+			&config,
+		)
+		.await;
+		assert_eq!(signals.expected_nonce, None);
+	}
+
+	#[tokio::test]
+	async fn test_transactions_of_a_block_are_seen_before_commit() {
+		let store = MonitorStateStore::new(NONCES_STATE);
+		let config = EVMNonceAnomalyConfig::default();
+
+		for (nonce, block_number) in [(5, 100), (6, 100), (8, 101)] {
+			record_transaction(
+				&store,
+				"monitor",
+				"ethereum_mainnet",
+				SENDER,
+				nonce,
+				block_number,
+				&config,
+			)
+			.await;
+		}
+		// Block 100 is committed while block 101 is still being filtered
+		store.commit("ethereum_mainnet", 100);
+		let signals = record_transaction(
+			&store,
+			"monitor",
+			"ethereum_mainnet",
+			SENDER,
+			9,
+			101,
+			&config,
+		)
+		.await;
+		assert_eq!(signals.expected_nonce, Some(9));
+		assert_eq!(signals.nonce_gap, 0);
+
+		// A block filtered again starts from the committed state
+		store.discard("ethereum_mainnet", 101);
+		let signals = record_transaction(
+			&store,
+			"monitor",
+			"ethereum_mainnet",
+			SENDER,
+			8,
+			101,
+			&config,
+		)
+		.await;
+		assert_eq!(signals.expected_nonce, Some(7));
+		assert_eq!(signals.nonce_gap, 1);
+	}
+
+	#[tokio::test]
+	async fn test_record_transaction_dormant_sender() {
+		let store = MonitorStateStore::new(NONCES_STATE);
+		let config = EVMNonceAnomalyConfig {
+			dormant_after_blocks: Some(1000),
+			dormant_addresses: Vec::new(),
+		};
+
+		let signals = record(
+			&store,
+			"monitor",
+			"ethereum_mainnet",
+			SENDER,
+			1,
+			100,
+			&config,
+		)
+		.await;
+		assert!(!signals.dormant_sender);
+		let signals = record(
+			&store,
+			"monitor",
+			"ethereum_mainnet",
+			SENDER,
+			2,
+			1100,
+			&config,
+		)
+		.await;
+		assert!(!signals.dormant_sender);
+		let signals = record(
+			&store,
+			"monitor",
+			"ethereum_mainnet",
+			SENDER,
+			3,
+			2101,
+			&config,
+		)
+		.await;
+		assert!(signals.dormant_sender);
+		assert_eq!(signals.idle_blocks, Some(1001));
+
+		// Listed addresses are dormant whatever their history
+		let config = EVMNonceAnomalyConfig {
+			dormant_after_blocks: None,
+			dormant_addresses: vec![SENDER.to_lowercase()],
+		};
+		let signals = record(&store, "cold", "ethereum_mainnet", SENDER, 0, 100, &config).await;
+		assert!(signals.dormant_sender);
+	}
+
+	#[tokio::test]
+	async fn test_nonces_are_persisted() {
+		let temp_dir = TempDir::new().unwrap();
+		let config = EVMNonceAnomalyConfig::default();
+		let store = MonitorStateStore::new(NONCES_STATE);
+		store.configure_storage(temp_dir.path().to_path_buf());
+
+		record(
+			&store,
+			"Persisted Monitor",
+			"ethereum_mainnet",
+			SENDER,
+			5,
+			100,
+			&config,
+		)
+		.await;
+		store.persist().await;
+		assert!(temp_dir
+			.path()
+			.join("ethereum_mainnet.persisted_monitor.json")
+			.exists());
+
+		// A fresh store reloads the states from disk
+		let store = MonitorStateStore::new(NONCES_STATE);
+		store.configure_storage(temp_dir.path().to_path_buf());
+		let signals = record(
+			&store,
+			"Persisted Monitor",
+			"ethereum_mainnet",
+			SENDER,
+			7,
+			110,
+			&config,
+		)
+		.await;
+		assert_eq!(signals.expected_nonce, Some(6));
+		assert_eq!(signals.nonce_gap, 1);
+	}
+}
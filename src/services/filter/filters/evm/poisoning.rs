@@ -0,0 +1,278 @@
+//! Address-poisoning detection for EVM transactions.
+//!
+//! Address poisoning fills the history of a victim with transfers involving addresses that
+//! share their leading and trailing characters with an address the victim trusts, hoping
+//! the victim later copies the wrong one. The usual vectors are zero-value `transferFrom`
+//! calls on real tokens and dust transfers of fake tokens, both visible as ERC20 `Transfer`
+//! logs involving the monitored addresses.
+
+use alloy::primitives::{Address, U256};
+
+use crate::{
+	models::{
+		EVMAddressPoisoningConfig, EVMMatchParamEntry, EVMReceiptLog, EVMTransaction, Monitor,
+	},
+	services::filter::{
+		evm_helpers::{h160_to_string, normalize_address},
+		filters::evm::heuristics::ERC20_TRANSFER_TOPIC,
+	},
+};
+
+/// Returns the address-poisoning configuration of a monitor, if enabled
+///
+/// # Arguments
+/// * `monitor` - The monitor to inspect
+pub fn monitor_config(monitor: &Monitor) -> Option<&EVMAddressPoisoningConfig> {
+	monitor
+		.chain_configurations
+		.iter()
+		.filter_map(|config| config.evm.as_ref())
+		.find_map(|evm| evm.address_poisoning.as_ref())
+}
+
+/// Address-poisoning signals detected in a single transaction
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PoisoningSignals {
+	/// A zero-value token transfer involves a monitored address
+	pub zero_value_transfer: bool,
+	/// Trusted address imitated by a counterparty of a monitored address
+	pub lookalike_of: Option<String>,
+}
+
+impl PoisoningSignals {
+	/// Converts the signals into transaction fields usable in expressions
+	pub fn to_params(&self) -> Vec<EVMMatchParamEntry> {
+		vec![
+			param(
+				"is_zero_value_transfer",
+				&self.zero_value_transfer.to_string(),
+				"bool",
+			),
+			param(
+				"is_lookalike_address",
+				&self.lookalike_of.is_some().to_string(),
+				"bool",
+			),
+			param(
+				"lookalike_of",
+				self.lookalike_of.as_deref().unwrap_or_default(),
+				"string",
+			),
+		]
+	}
+}
+
+/// Checks a transaction for zero-value transfers and look-alike counterparties
+///
+/// Only transfers where one side is a monitored address are considered. The other side is
+/// a look-alike when it is not trusted itself but resembles one of the monitored or known
+/// addresses.
+///
+/// # Arguments
+/// * `transaction` - The transaction to analyze
+/// * `logs` - Logs emitted by the transaction
+/// * `monitored_addresses` - Addresses of the monitor
+/// * `config` - The monitor's address-poisoning configuration
+///
+/// # Returns
+/// The detected signals
+pub fn analyze_transaction(
+	transaction: &EVMTransaction,
+	logs: &[EVMReceiptLog],
+	monitored_addresses: &[String],
+	config: &EVMAddressPoisoningConfig,
+) -> PoisoningSignals {
+	let monitored: Vec<String> = monitored_addresses
+		.iter()
+		.map(|address| normalize_address(address))
+		.collect();
+	let trusted: Vec<String> = monitored
+		.iter()
+		.cloned()
+		.chain(
+			config
+				.known_addresses
+				.iter()
+				.map(|address| normalize_address(address)),
+		)
+		.collect();
+
+	let mut signals = PoisoningSignals::default();
+	let mut check_transfer = |from: Address, to: Address, amount: Option<U256>| {
+		let from = normalize_address(&h160_to_string(from));
+		let to = normalize_address(&h160_to_string(to));
+		let counterparty = match (monitored.contains(&from), monitored.contains(&to)) {
+			(true, _) => &to,
+			(false, true) => &from,
+			(false, false) => return,
+		};
+
+		if amount.is_some_and(|amount| amount.is_zero()) {
+			signals.zero_value_transfer = true;
+		}
+		if signals.lookalike_of.is_none() && !trusted.contains(counterparty) {
+			signals.lookalike_of = trusted
+				.iter()
+				.find(|reference| {
+					is_lookalike(
+						counterparty,
+						reference,
+						config.prefix_length,
+						config.suffix_length,
+					)
+				})
+				.map(|reference| format!("0x{}", reference));
+		}
+	};
+
+	if let (Some(from), Some(to)) = (transaction.from, transaction.to) {
+		check_transfer(from, to, None);
+	}
+	for log in logs {
+		if log.topics.len() != 3 || log.topics[0] != ERC20_TRANSFER_TOPIC {
+			continue;
+		}
+		let amount =
+			U256::try_from_be_slice(log.data.get(0..32).unwrap_or_default()).unwrap_or_default();
+		check_transfer(
+			Address::from_word(log.topics[1]),
+			Address::from_word(log.topics[2]),
+			Some(amount),
+		);
+	}
+
+	signals
+}
+
+/// Returns whether an address imitates a reference address
+///
+/// Both addresses must differ while sharing the given number of leading and trailing hex
+/// characters. Comparison is case-insensitive and ignores the `0x` prefix.
+///
+/// # Arguments
+/// * `address` - The address to check
+/// * `reference` - The trusted address
+/// * `prefix_length` - Number of leading hex characters to compare
+/// * `suffix_length` - Number of trailing hex characters to compare
+pub fn is_lookalike(
+	address: &str,
+	reference: &str,
+	prefix_length: usize,
+	suffix_length: usize,
+) -> bool {
+	let address = normalize_address(address);
+	let reference = normalize_address(reference);
+	if address == reference
+		|| address.len() != reference.len()
+		|| prefix_length + suffix_length == 0
+		|| prefix_length + suffix_length > address.len()
+	{
+		return false;
+	}
+	let len = address.len();
+	address[..prefix_length] == reference[..prefix_length]
+		&& address[len - suffix_length..] == reference[len - suffix_length..]
+}
+
+fn param(name: &str, value: &str, kind: &str) -> EVMMatchParamEntry {
+	EVMMatchParamEntry {
+		name: name.to_string(),
+		value: value.to_string(),
+		kind: kind.to_string(),
+		indexed: false,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::utils::tests::evm::transaction::TransactionBuilder;
+	use alloy::primitives::{Bytes, B256};
+	use std::str::FromStr;
+
+	const TREASURY: &str = "0x1234000000000000000000000000000000005678";
+	const EXCHANGE: &str = "0xabcd00000000000000000000000000000000ef01";
+	const FAKE_EXCHANGE: &str = "0xabcd11111111111111111111111111111111ef01";
+
+	fn address(value: &str) -> Address {
+		Address::from_str(value).unwrap()
+	}
+
+	fn transfer_log(from: &str, to: &str, amount: u64) -> EVMReceiptLog {
+		EVMReceiptLog {
+			address: Address::with_last_byte(10),
+			topics: vec![
+				ERC20_TRANSFER_TOPIC,
+				B256::left_padding_from(address(from).as_slice()),
+				B256::left_padding_from(address(to).as_slice()),
+			],
+			data: Bytes::from(U256::from(amount).to_be_bytes::<32>().to_vec()),
+			block_hash: None,
+			block_number: None,
+			transaction_hash: None,
+			transaction_index: None,
+			log_index: None,
+			transaction_log_index: None,
+			log_type: None,
+			removed: Some(false),
+		}
+	}
+
+	#[test]
+	fn test_is_lookalike() {
+		assert!(is_lookalike(FAKE_EXCHANGE, EXCHANGE, 4, 4));
+		assert!(is_lookalike(
+			&format!("0x{}", FAKE_EXCHANGE[2..].to_uppercase()),
+			EXCHANGE,
+			4,
+			4
+		));
+		assert!(!is_lookalike(EXCHANGE, EXCHANGE, 4, 4));
+		assert!(!is_lookalike(FAKE_EXCHANGE, EXCHANGE, 6, 4));
+		assert!(!is_lookalike(TREASURY, EXCHANGE, 4, 4));
+	}
+
+	#[test]
+	fn test_analyze_zero_value_transfer_to_lookalike() {
+		let config = EVMAddressPoisoningConfig {
+			known_addresses: vec![EXCHANGE.to_string()],
+			..Default::default()
+		};
+		let transaction = TransactionBuilder::new()
+			.from(Address::with_last_byte(1))
+			.to(Address::with_last_byte(10))
+			.build();
+		let logs = vec![transfer_log(TREASURY, FAKE_EXCHANGE, 0)];
+
+		let signals = analyze_transaction(&transaction, &logs, &[TREASURY.to_string()], &config);
+
+		assert!(signals.zero_value_transfer);
+		assert_eq!(signals.lookalike_of, Some(EXCHANGE.to_string()));
+	}
+
+	#[test]
+	fn test_analyze_regular_transfer() {
+		let config = EVMAddressPoisoningConfig {
+			known_addresses: vec![EXCHANGE.to_string()],
+			..Default::default()
+		};
+		let transaction = TransactionBuilder::new()
+			.from(address(TREASURY))
+			.to(Address::with_last_byte(10))
+			.build();
+		// Transfers between trusted addresses and transfers not involving the monitored
+		// addresses are ignored
+		let logs = vec![
+			transfer_log(TREASURY, EXCHANGE, 1_000),
+			transfer_log(EXCHANGE, FAKE_EXCHANGE, 0),
+		];
+
+		let signals = analyze_transaction(&transaction, &logs, &[TREASURY.to_string()], &config);
+
+		assert_eq!(signals, PoisoningSignals::default());
+		let params = signals.to_params();
+		assert_eq!(params.len(), 3);
+		assert_eq!(params[1].name, "is_lookalike_address");
+		assert_eq!(params[1].value, "false");
+	}
+}
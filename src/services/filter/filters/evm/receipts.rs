@@ -0,0 +1,115 @@
+//! Receipts needed by the notification templates of EVM monitors.
+//!
+//! Some template variables, such as `transaction.fee`, are only provided when the receipt of
+//! the transaction was fetched. The filter fetches receipts for the conditions that need them;
+//! the monitors whose triggers use these variables in their templates are registered at
+//! startup in the `ReceiptTemplates` of the filter service, so that the receipts of their
+//! matches are fetched too.
+
+use std::{
+	collections::{HashMap, HashSet},
+	sync::RwLock,
+};
+
+use crate::models::{BlockChainType, Monitor, Trigger};
+
+/// Template variables only provided when the receipt of the transaction was fetched
+pub const RECEIPT_VARIABLES: [&str; 1] = ["transaction.fee"];
+
+/// Monitors whose trigger templates use receipt variables
+#[derive(Default)]
+pub struct ReceiptTemplates {
+	/// Names of the registered monitors
+	monitors: RwLock<HashSet<String>>,
+}
+
+impl ReceiptTemplates {
+	/// Registers the monitors whose trigger templates use receipt variables
+	///
+	/// Replaces the previously registered monitors.
+	///
+	/// # Arguments
+	/// * `triggers` - The triggers, keyed by name
+	/// * `monitors` - The monitors
+	pub fn register(&self, triggers: &HashMap<String, Trigger>, monitors: &[Monitor]) {
+		let names: HashSet<String> = monitors
+			.iter()
+			.filter(|monitor| {
+				monitor
+					.routed_triggers()
+					.filter_map(|name| triggers.get(name))
+					.any(uses_receipt_variables)
+			})
+			.map(|monitor| monitor.name.clone())
+			.collect();
+		match self.monitors.write() {
+			Ok(mut monitors) => *monitors = names,
+			Err(poisoned) => *poisoned.into_inner() = names,
+		}
+	}
+
+	/// Returns true if the trigger templates of a monitor use receipt variables
+	///
+	/// # Arguments
+	/// * `monitor` - The monitor to inspect
+	pub fn need_receipt(&self, monitor: &Monitor) -> bool {
+		match self.monitors.read() {
+			Ok(monitors) => monitors.contains(&monitor.name),
+			Err(poisoned) => poisoned.into_inner().contains(&monitor.name),
+		}
+	}
+}
+
+/// Returns true if the message sent for EVM matches by a trigger uses receipt variables
+fn uses_receipt_variables(trigger: &Trigger) -> bool {
+	let Some(message) = trigger.config.get_message() else {
+		return false;
+	};
+	let message = message.for_chain(&BlockChainType::EVM);
+	RECEIPT_VARIABLES.iter().any(|variable| {
+		let placeholder = format!("${{{}}}", variable);
+		message.title.contains(&placeholder) || message.body.contains(&placeholder)
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::utils::tests::builders::{evm::monitor::MonitorBuilder, trigger::TriggerBuilder};
+
+	#[test]
+	fn test_register_templates() {
+		let triggers = HashMap::from([
+			(
+				"fee_alert".to_string(),
+				TriggerBuilder::new()
+					.name("fee_alert")
+					.slack("https://hooks.slack.com/services/fee")
+					.message("Fee paid", "Paid ${transaction.fee} wei")
+					.build(),
+			),
+			(
+				"plain_alert".to_string(),
+				TriggerBuilder::new()
+					.name("plain_alert")
+					.slack("https://hooks.slack.com/services/plain")
+					.message("Transfer", "Sent by ${transaction.from}")
+					.build(),
+			),
+		]);
+		let fee_monitor = MonitorBuilder::new()
+			.name("receipt_templates_fee")
+			.triggers(vec!["plain_alert".to_string(), "fee_alert".to_string()])
+			.build();
+		let plain_monitor = MonitorBuilder::new()
+			.name("receipt_templates_plain")
+			.triggers(vec!["plain_alert".to_string()])
+			.build();
+
+		let templates = ReceiptTemplates::default();
+		templates.register(&triggers, &[fee_monitor.clone(), plain_monitor.clone()]);
+
+		assert!(templates.need_receipt(&fee_monitor));
+		assert!(!templates.need_receipt(&plain_monitor));
+	}
+}
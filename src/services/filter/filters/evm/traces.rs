@@ -0,0 +1,393 @@
+//! Budgeted tracing of matched EVM transactions.
+//!
+//! Monitors with `internal_transfers` trace their matched transactions
+//! (`debug_traceTransaction` with the `callTracer`) to find the native value moved by internal
+//! calls, which neither the transaction nor its logs show. Traces are among the most expensive
+//! RPC calls, so the candidate transactions of a network are sampled and counted against its
+//! `trace_budget`. A transaction matched by several monitors is traced once, and the trace is
+//! requested from an endpoint that serves `debug_traceTransaction`. A match whose transaction
+//! is not traced carries the reason in its `trace` marker, so that notifications produced
+//! without trace data can be told apart.
+//!
+//! Block filters are created per block through `BlockFilterFactory::filter`, so the budgets
+//! are owned by the filter service and shared by the filters it creates through their
+//! `FilterContext`, along with the capabilities detected on the endpoints.
+
+use alloy::primitives::U256;
+use futures::{stream, StreamExt};
+use serde_json::Value as JsonValue;
+use std::{
+	collections::{BTreeMap, BTreeSet, HashMap},
+	sync::Mutex,
+	time::{Duration, Instant},
+};
+
+use crate::{
+	models::{EVMInternalTransfer, EVMTraceStatus, EVMTransactionTrace, Monitor, Network},
+	services::{
+		blockchain::EvmClientTrait,
+		blockwatcher::{Capability, EndpointCapabilityRegistry},
+	},
+	utils::metrics::record_traced_transaction,
+};
+
+/// Window of the `max_per_minute` budget
+const BUDGET_WINDOW: Duration = Duration::from_secs(60);
+
+/// Number of recent blocks whose trace counts are kept, blocks being filtered concurrently
+const TRACKED_BLOCKS: usize = 64;
+
+/// Maximum number of transactions of a block traced concurrently
+const TRACE_CONCURRENCY: usize = 8;
+
+/// Call types moving the value of their frame
+const VALUE_CALL_TYPES: &[&str] = &["CALL", "CALLCODE", "CREATE", "CREATE2", "SELFDESTRUCT"];
+
+/// Returns whether a monitor traces its matched transactions
+///
+/// # Arguments
+/// * `monitor` - The monitor to inspect
+pub fn is_enabled(monitor: &Monitor) -> bool {
+	monitor
+		.chain_configurations
+		.iter()
+		.filter_map(|config| config.evm.as_ref())
+		.any(|evm| evm.internal_transfers)
+}
+
+/// Usage of the trace budget of a network
+#[derive(Default)]
+struct BudgetUsage {
+	/// Number of candidate transactions, for sampling
+	candidates: u64,
+	/// Number of traced transactions of the recent blocks
+	blocks: BTreeMap<u64, u32>,
+	/// Start of the current `max_per_minute` window
+	window_start: Option<Instant>,
+	/// Number of traced transactions in the current window
+	window_count: u32,
+}
+
+/// Trace budgets of the networks
+#[derive(Default)]
+pub struct TraceBudgets {
+	usages: Mutex<HashMap<String, BudgetUsage>>,
+}
+
+impl TraceBudgets {
+	/// Decides whether a candidate transaction is traced, counting it against the budget
+	///
+	/// # Arguments
+	/// * `network` - The network of the transaction
+	/// * `block_number` - The block of the transaction
+	/// * `now` - The current instant
+	///
+	/// # Returns
+	/// `Traced` if the transaction may be traced, the reason it may not otherwise
+	pub fn acquire(&self, network: &Network, block_number: u64, now: Instant) -> EVMTraceStatus {
+		let Some(budget) = &network.trace_budget else {
+			return EVMTraceStatus::Traced;
+		};
+		let mut usages = match self.usages.lock() {
+			Ok(usages) => usages,
+			Err(poisoned) => poisoned.into_inner(),
+		};
+		let usage = usages.entry(network.slug.clone()).or_default();
+
+		let candidate = usage.candidates;
+		usage.candidates += 1;
+		if let Some(n) = budget.sample_one_in {
+			if candidate % n.max(1) != 0 {
+				return EVMTraceStatus::SampledOut;
+			}
+		}
+
+		if usage
+			.window_start
+			.is_none_or(|start| now.duration_since(start) >= BUDGET_WINDOW)
+		{
+			usage.window_start = Some(now);
+			usage.window_count = 0;
+		}
+		let block_count = usage.blocks.get(&block_number).copied().unwrap_or(0);
+		let over_budget = budget.max_per_block.is_some_and(|max| block_count >= max)
+			|| budget
+				.max_per_minute
+				.is_some_and(|max| usage.window_count >= max);
+		if over_budget {
+			return EVMTraceStatus::OverBudget;
+		}
+
+		usage.window_count += 1;
+		usage.blocks.insert(block_number, block_count + 1);
+		while usage.blocks.len() > TRACKED_BLOCKS {
+			usage.blocks.pop_first();
+		}
+		EVMTraceStatus::Traced
+	}
+}
+
+/// Traces a matched transaction within the trace budget of its network
+///
+/// Failures are logged and reported in the status of the trace rather than failing the
+/// filtering of the block.
+///
+/// # Arguments
+/// * `budgets` - The trace budgets of the networks
+/// * `capabilities` - The capabilities detected on the endpoints
+/// * `client` - The client of the network
+/// * `network` - The network of the transaction
+/// * `block_number` - The block of the transaction
+/// * `transaction_hash` - The hash of the transaction
+///
+/// # Returns
+/// The trace of the transaction, with the internal transfers if it was traced
+pub async fn trace_transaction<C: EvmClientTrait>(
+	budgets: &TraceBudgets,
+	capabilities: &EndpointCapabilityRegistry,
+	client: &C,
+	network: &Network,
+	block_number: u64,
+	transaction_hash: &str,
+) -> EVMTransactionTrace {
+	let supported = network.rpc_urls.iter().any(|rpc_url| {
+		capabilities.supports(&network.slug, rpc_url.url.as_str(), Capability::DebugTrace)
+	});
+	let mut status = if supported {
+		budgets.acquire(network, block_number, Instant::now())
+	} else {
+		EVMTraceStatus::Unsupported
+	};
+
+	let mut internal_transfers = Vec::new();
+	if status == EVMTraceStatus::Traced {
+		match client.trace_transaction(transaction_hash).await {
+			Ok(frame) => internal_transfers = parse_internal_transfers(&frame),
+			Err(e) => {
+				tracing::warn!(
+					network = %network.slug,
+					"Failed to trace transaction {}: {}",
+					transaction_hash,
+					e
+				);
+				status = EVMTraceStatus::Failed;
+			}
+		}
+	}
+	record_traced_transaction(&network.slug, status.as_str());
+
+	EVMTransactionTrace {
+		status,
+		internal_transfers,
+	}
+}
+
+/// Traces the matched transactions of a block, once per transaction whatever the number of
+/// monitors matching it
+///
+/// # Arguments
+/// * `budgets` - The trace budgets of the networks
+/// * `capabilities` - The capabilities detected on the endpoints
+/// * `client` - The client of the network
+/// * `network` - The network of the transactions
+/// * `block_number` - The block of the transactions
+/// * `transaction_hashes` - The hashes of the matched transactions, possibly repeated
+///
+/// # Returns
+/// The traces of the transactions, keyed by hash
+pub async fn trace_transactions<C: EvmClientTrait + Sync>(
+	budgets: &TraceBudgets,
+	capabilities: &EndpointCapabilityRegistry,
+	client: &C,
+	network: &Network,
+	block_number: u64,
+	transaction_hashes: impl IntoIterator<Item = &String>,
+) -> HashMap<String, EVMTransactionTrace> {
+	let transaction_hashes: BTreeSet<&String> = transaction_hashes.into_iter().collect();
+	stream::iter(transaction_hashes)
+		.map(|transaction_hash| async move {
+			let trace = trace_transaction(
+				budgets,
+				capabilities,
+				client,
+				network,
+				block_number,
+				transaction_hash,
+			)
+			.await;
+			(transaction_hash.clone(), trace)
+		})
+		.buffer_unordered(TRACE_CONCURRENCY)
+		.collect()
+		.await
+}
+
+/// Extracts the native value moved by the internal calls of a `callTracer` frame
+///
+/// The value of the top-level call is the value of the transaction and is left out. Reverted
+/// calls move no value, nor do the calls they made.
+///
+/// # Arguments
+/// * `frame` - The top-level call frame
+pub fn parse_internal_transfers(frame: &JsonValue) -> Vec<EVMInternalTransfer> {
+	let mut transfers = Vec::new();
+	// Calls are popped from the end of the stack, in execution order
+	let mut pending: Vec<&JsonValue> = frame["calls"]
+		.as_array()
+		.into_iter()
+		.flatten()
+		.rev()
+		.collect();
+	while let Some(call) = pending.pop() {
+		if call.get("error").is_some() {
+			continue;
+		}
+		let call_type = call["type"].as_str().unwrap_or_default();
+		let value = call["value"]
+			.as_str()
+			.and_then(|value| U256::from_str_radix(value.trim_start_matches("0x"), 16).ok())
+			.unwrap_or_default();
+		if VALUE_CALL_TYPES.contains(&call_type) && !value.is_zero() {
+			if let (Some(from), Some(to)) = (call["from"].as_str(), call["to"].as_str()) {
+				transfers.push(EVMInternalTransfer {
+					from: from.to_lowercase(),
+					to: to.to_lowercase(),
+					value,
+				});
+			}
+		}
+		pending.extend(call["calls"].as_array().into_iter().flatten().rev());
+	}
+	transfers
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{models::TraceBudget, utils::tests::builders::network::NetworkBuilder};
+	use serde_json::json;
+
+	fn network(budget: TraceBudget) -> Network {
+		NetworkBuilder::new()
+			.slug("trace_budget_test")
+			.trace_budget(budget)
+			.build()
+	}
+
+	#[test]
+	fn test_acquire_per_block_and_sampling() {
+		let budgets = TraceBudgets::default();
+		let network = network(TraceBudget {
+			max_per_block: Some(2),
+			max_per_minute: None,
+			sample_one_in: Some(2),
+		});
+		let now = Instant::now();
+
+		let statuses: Vec<_> = (0..6)
+			.map(|_| budgets.acquire(&network, 100, now))
+			.collect();
+		assert_eq!(
+			statuses,
+			vec![
+				EVMTraceStatus::Traced,
+				EVMTraceStatus::SampledOut,
+				EVMTraceStatus::Traced,
+				EVMTraceStatus::SampledOut,
+				EVMTraceStatus::OverBudget,
+				EVMTraceStatus::SampledOut,
+			]
+		);
+		// The budget of the next block is fresh
+		assert_eq!(budgets.acquire(&network, 101, now), EVMTraceStatus::Traced);
+	}
+
+	#[test]
+	fn test_acquire_per_minute() {
+		let budgets = TraceBudgets::default();
+		let network = network(TraceBudget {
+			max_per_minute: Some(1),
+			..Default::default()
+		});
+		let now = Instant::now();
+
+		assert_eq!(budgets.acquire(&network, 1, now), EVMTraceStatus::Traced);
+		assert_eq!(
+			budgets.acquire(&network, 2, now),
+			EVMTraceStatus::OverBudget
+		);
+		assert_eq!(
+			budgets.acquire(&network, 3, now + BUDGET_WINDOW),
+			EVMTraceStatus::Traced
+		);
+
+		// Without a budget every transaction is traced
+		let unlimited = NetworkBuilder::new().build();
+		assert_eq!(budgets.acquire(&unlimited, 1, now), EVMTraceStatus::Traced);
+	}
+
+	#[test]
+	fn test_parse_internal_transfers() {
+		let frame = json!({
+			"type": "CALL",
+			"from": "0x00000000000000000000000000000000000000a1",
+			"to": "0x00000000000000000000000000000000000000b2",
+			"value": "0x64",
+			"calls": [
+				{
+					"type": "CALL",
+					"from": "0x00000000000000000000000000000000000000B2",
+					"to": "0x00000000000000000000000000000000000000c3",
+					"value": "0xa",
+					"calls": [{
+						"type": "CALL",
+						"from": "0x00000000000000000000000000000000000000c3",
+						"to": "0x00000000000000000000000000000000000000d4",
+						"value": "0x5"
+					}]
+				},
+				{
+					"type": "CALL",
+					"from": "0x00000000000000000000000000000000000000b2",
+					"to": "0x00000000000000000000000000000000000000e5",
+					"value": "0x7",
+					"error": "execution reverted",
+					"calls": [{
+						"type": "CALL",
+						"from": "0x00000000000000000000000000000000000000e5",
+						"to": "0x00000000000000000000000000000000000000f6",
+						"value": "0x7"
+					}]
+				},
+				{
+					"type": "DELEGATECALL",
+					"from": "0x00000000000000000000000000000000000000b2",
+					"to": "0x00000000000000000000000000000000000000c3",
+					"value": "0x64"
+				},
+				{
+					"type": "STATICCALL",
+					"from": "0x00000000000000000000000000000000000000b2",
+					"to": "0x00000000000000000000000000000000000000c3"
+				}
+			]
+		});
+
+		let transfers = parse_internal_transfers(&frame);
+		assert_eq!(transfers.len(), 2);
+		assert_eq!(
+			transfers[0].from,
+			"0x00000000000000000000000000000000000000b2"
+		);
+		assert_eq!(
+			transfers[0].to,
+			"0x00000000000000000000000000000000000000c3"
+		);
+		assert_eq!(transfers[0].value, U256::from(10));
+		assert_eq!(
+			transfers[1].to,
+			"0x00000000000000000000000000000000000000d4"
+		);
+		assert_eq!(transfers[1].value, U256::from(5));
+	}
+}
@@ -3,15 +3,25 @@
 //! Provides trait definition and implementations for filtering blocks
 //! across different blockchain types. Includes:
 //! - Generic BlockFilter trait
-//! - EVM-specific implementation
+//! - EVM-specific implementation (including built-in DEX event decoding)
 //! - Stellar-specific implementation
 //! - Midnight-specific implementation
 //! - Solana-specific implementation
 
 pub mod evm {
+	pub mod audit;
+	pub mod dex;
 	pub mod evaluator;
 	pub mod filter;
+	pub mod funds_flow;
 	pub mod helpers;
+	pub mod heuristics;
+	pub mod interactions;
+	pub mod monitor_state;
+	pub mod nonces;
+	pub mod poisoning;
+	pub mod receipts;
+	pub mod traces;
 }
 pub mod stellar {
 	pub mod evaluator;
@@ -31,10 +41,24 @@ pub mod solana {
 }
 
 use async_trait::async_trait;
+use std::{path::Path, sync::Arc, time::Duration};
 
 use crate::{
 	models::{BlockType, ContractSpec, Monitor, MonitorMatch, Network},
-	services::{blockchain::BlockFilterFactory, filter::error::FilterError},
+	services::{
+		blockchain::BlockFilterFactory,
+		blockwatcher::EndpointCapabilityRegistry,
+		filter::{
+			error::FilterError,
+			filters::evm::{
+				interactions::INTERACTIONS_STATE,
+				monitor_state::MonitorStateStore,
+				nonces::{SenderState, NONCES_STATE},
+				receipts::ReceiptTemplates,
+				traces::TraceBudgets,
+			},
+		},
+	},
 };
 
 /// Trait for filtering blockchain data
@@ -52,6 +76,36 @@ pub trait BlockFilter {
 		monitors: &[Monitor],
 		contract_specs: Option<&[(String, ContractSpec)]>,
 	) -> Result<Vec<MonitorMatch>, FilterError>;
+
+	/// Processes the chunks of an oversized block, one chunk at a time, calling `on_chunk`
+	/// once a chunk is processed.
+	///
+	/// Each chunk is processed as a block of its own by default. Filters fetching data for
+	/// the whole block override it to fetch that data once for all the chunks.
+	#[allow(clippy::too_many_arguments)]
+	async fn filter_block_chunks(
+		&self,
+		client: &Self::Client,
+		network: &Network,
+		_block: &BlockType,
+		chunks: &[BlockType],
+		monitors: &[Monitor],
+		contract_specs: Option<&[(String, ContractSpec)]>,
+		on_chunk: &mut (dyn FnMut(usize, &BlockType) + Send),
+	) -> Result<Vec<MonitorMatch>, FilterError>
+	where
+		Self::Client: Sync,
+	{
+		let mut matches = Vec::new();
+		for (index, chunk) in chunks.iter().enumerate() {
+			matches.extend(
+				self.filter_block(client, network, chunk, monitors, contract_specs)
+					.await?,
+			);
+			on_chunk(index, chunk);
+		}
+		Ok(matches)
+	}
 }
 
 #[async_trait]
@@ -66,14 +120,121 @@ pub trait FilterServiceTrait: Send + Sync {
 	) -> Result<Vec<MonitorMatch>, FilterError>;
 }
 
+/// State shared by the block filters of a filter service
+///
+/// Block filters are created per block, so the state outliving a block is owned by the
+/// service and handed to the filters it creates.
+#[derive(Clone)]
+pub struct FilterContext {
+	/// Monitors whose trigger templates use receipt variables
+	pub receipt_templates: Arc<ReceiptTemplates>,
+	/// Trace budgets of the networks
+	pub trace_budgets: Arc<TraceBudgets>,
+	/// Capabilities detected on the RPC endpoints, shared with the clients of the client pool
+	pub capabilities: Arc<EndpointCapabilityRegistry>,
+	/// Last interactions of the senders with the monitored contracts
+	pub interactions: Arc<MonitorStateStore<u64>>,
+	/// Last transactions of the monitored senders
+	pub nonces: Arc<MonitorStateStore<SenderState>>,
+}
+
+impl Default for FilterContext {
+	fn default() -> Self {
+		Self {
+			receipt_templates: Arc::default(),
+			trace_budgets: Arc::default(),
+			capabilities: Arc::default(),
+			interactions: Arc::new(MonitorStateStore::new(INTERACTIONS_STATE)),
+			nonces: Arc::new(MonitorStateStore::new(NONCES_STATE)),
+		}
+	}
+}
+
+impl FilterContext {
+	/// Sets the directory where the monitor states are persisted, in a directory per state
+	///
+	/// # Arguments
+	/// * `data_dir` - Data directory of the service
+	pub fn configure_storage(&self, data_dir: &Path) {
+		self.interactions
+			.configure_storage(data_dir.join(self.interactions.name()));
+		self.nonces
+			.configure_storage(data_dir.join(self.nonces.name()));
+	}
+
+	/// Discards the monitor states staged by a previous filtering of a block
+	///
+	/// # Arguments
+	/// * `network_slug` - Network of the block
+	/// * `block_number` - Block about to be filtered
+	pub fn discard_block(&self, network_slug: &str, block_number: u64) {
+		self.interactions.discard(network_slug, block_number);
+		self.nonces.discard(network_slug, block_number);
+	}
+
+	/// Commits the monitor states staged by a block
+	///
+	/// Must be called in block order, as the block is handed over to the triggers.
+	///
+	/// # Arguments
+	/// * `network_slug` - Network of the block
+	/// * `block_number` - Block handed over to the triggers
+	pub fn commit_block(&self, network_slug: &str, block_number: u64) {
+		self.interactions.commit(network_slug, block_number);
+		self.nonces.commit(network_slug, block_number);
+	}
+
+	/// Writes the monitor states changed since the last write
+	pub async fn persist(&self) {
+		self.interactions.persist().await;
+		self.nonces.persist().await;
+	}
+
+	/// Writes the monitor states changed since the last write without awaiting
+	///
+	/// Used by the panic hook, states whose lock is held are not written.
+	pub fn persist_blocking(&self) {
+		self.interactions.persist_blocking();
+		self.nonces.persist_blocking();
+	}
+}
+
+/// Periodically persists the monitor states of a filter context
+///
+/// # Arguments
+/// * `context` - The filter context
+/// * `interval` - Time between two writes
+pub fn start_monitor_state_snapshots(
+	context: FilterContext,
+	interval: Duration,
+) -> tokio::task::JoinHandle<()> {
+	tokio::spawn(async move {
+		let mut ticker = tokio::time::interval(interval);
+		ticker.tick().await;
+		loop {
+			ticker.tick().await;
+			context.persist().await;
+		}
+	})
+}
+
 /// Service for filtering blockchain data
 ///
 /// This service provides a way to filter blockchain data based on a set of monitors.
-pub struct FilterService {}
+pub struct FilterService {
+	context: FilterContext,
+}
 
 impl FilterService {
 	pub fn new() -> Self {
-		FilterService {}
+		FilterService {
+			context: FilterContext::default(),
+		}
+	}
+
+	/// Returns the state shared by the filters of the service
+	pub fn context(&self) -> &FilterContext {
+		&self.context
 	}
 }
 
@@ -92,11 +253,37 @@ impl FilterService {
 		monitors: &[Monitor],
 		contract_specs: Option<&[(String, ContractSpec)]>,
 	) -> Result<Vec<MonitorMatch>, FilterError> {
-		let filter = T::filter();
+		let filter = T::filter(&self.context);
 		filter
 			.filter_block(client, network, block, monitors, contract_specs)
 			.await
 	}
+
+	/// Filters the chunks of an oversized block, calling `on_chunk` once a chunk is processed
+	#[allow(clippy::too_many_arguments)]
+	pub async fn filter_block_chunks<T: BlockFilterFactory<T> + Sync>(
+		&self,
+		client: &T,
+		network: &Network,
+		block: &BlockType,
+		chunks: &[BlockType],
+		monitors: &[Monitor],
+		contract_specs: Option<&[(String, ContractSpec)]>,
+		on_chunk: &mut (dyn FnMut(usize, &BlockType) + Send),
+	) -> Result<Vec<MonitorMatch>, FilterError> {
+		let filter = T::filter(&self.context);
+		filter
+			.filter_block_chunks(
+				client,
+				network,
+				block,
+				chunks,
+				monitors,
+				contract_specs,
+				on_chunk,
+			)
+			.await
+	}
 }
 
 #[async_trait]
@@ -109,7 +296,7 @@ impl FilterServiceTrait for FilterService {
 		monitors: &[Monitor],
 		contract_specs: Option<&[(String, ContractSpec)]>,
 	) -> Result<Vec<MonitorMatch>, FilterError> {
-		let filter = T::filter();
+		let filter = T::filter(&self.context);
 		filter
 			.filter_block(client, network, block, monitors, contract_specs)
 			.await
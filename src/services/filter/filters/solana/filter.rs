@@ -26,6 +26,17 @@ use crate::{
 
 use super::evaluator::SolanaConditionEvaluator;
 
+/// Variables available to transaction condition expressions, `fee_payer` and `accounts` being
+/// omitted when the transaction has none
+pub const TRANSACTION_VARIABLES: &[&str] = &[
+	"signature",
+	"slot",
+	"fee",
+	"is_success",
+	"fee_payer",
+	"accounts",
+];
+
 /// Implementation of the block filter for Solana blockchain
 pub struct SolanaBlockFilter<T> {
 	pub _client: PhantomData<T>,
@@ -450,6 +461,8 @@ mod tests {
 
 		let tx = SolanaTransaction::from(tx_info);
 		let params = filter.build_transaction_params(&tx);
+		let names: Vec<&str> = params.iter().map(|p| p.name.as_str()).collect();
+		assert_eq!(names, TRANSACTION_VARIABLES);
 
 		// Verify fee_payer is set correctly
 		let fee_payer_param = params.iter().find(|p| p.name == "fee_payer").unwrap();
@@ -498,6 +511,19 @@ mod tests {
 			trigger_conditions: vec![],
 			triggers: vec![],
 			chain_configurations: vec![],
+			start_block: None,
+			network_addresses: Default::default(),
+			severity: Default::default(),
+			observe: false,
+			canary: None,
+			activate_on_deployment: None,
+			abi_drift: None,
+			sampling: None,
+			batch: None,
+			trigger_params: Default::default(),
+			owners: Vec::new(),
+			post_processing: None,
+			rpc_overrides: Default::default(),
 		};
 
 		let tx_info = SolanaTransactionInfo {
@@ -579,6 +605,19 @@ mod tests {
 			trigger_conditions: vec![],
 			triggers: vec![],
 			chain_configurations: vec![],
+			start_block: None,
+			network_addresses: Default::default(),
+			severity: Default::default(),
+			observe: false,
+			canary: None,
+			activate_on_deployment: None,
+			abi_drift: None,
+			sampling: None,
+			batch: None,
+			trigger_params: Default::default(),
+			owners: Vec::new(),
+			post_processing: None,
+			rpc_overrides: Default::default(),
 		};
 
 		let tx_info = SolanaTransactionInfo {
@@ -661,6 +700,19 @@ mod tests {
 			trigger_conditions: vec![],
 			triggers: vec![],
 			chain_configurations: vec![],
+			start_block: None,
+			network_addresses: Default::default(),
+			severity: Default::default(),
+			observe: false,
+			canary: None,
+			activate_on_deployment: None,
+			abi_drift: None,
+			sampling: None,
+			batch: None,
+			trigger_params: Default::default(),
+			owners: Vec::new(),
+			post_processing: None,
+			rpc_overrides: Default::default(),
 		};
 
 		let tx_info = SolanaTransactionInfo {
@@ -1053,6 +1105,19 @@ mod tests {
 			trigger_conditions: vec![],
 			triggers: vec![],
 			chain_configurations: vec![],
+			start_block: None,
+			network_addresses: Default::default(),
+			severity: Default::default(),
+			observe: false,
+			canary: None,
+			activate_on_deployment: None,
+			abi_drift: None,
+			sampling: None,
+			batch: None,
+			trigger_params: Default::default(),
+			owners: Vec::new(),
+			post_processing: None,
+			rpc_overrides: Default::default(),
 		}
 	}
 
@@ -1310,6 +1375,19 @@ mod tests {
 			trigger_conditions: vec![],
 			triggers: vec![],
 			chain_configurations: vec![],
+			start_block: None,
+			network_addresses: Default::default(),
+			severity: Default::default(),
+			observe: false,
+			canary: None,
+			activate_on_deployment: None,
+			abi_drift: None,
+			sampling: None,
+			batch: None,
+			trigger_params: Default::default(),
+			owners: Vec::new(),
+			post_processing: None,
+			rpc_overrides: Default::default(),
 		}
 	}
 
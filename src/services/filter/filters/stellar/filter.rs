@@ -44,6 +44,70 @@ pub struct EventMap {
 	pub tx_hash: String,
 }
 
+/// Variables available to transaction condition expressions
+pub const TRANSACTION_VARIABLES: &[&str] = &["hash", "ledger", "value", "from", "to"];
+
+/// Operation of a transaction, exposed to transaction condition expressions
+struct TxOperation {
+	_operation_type: String,
+	sender: String,
+	receiver: String,
+	value: Option<String>,
+}
+
+/// Builds the parameters available to transaction condition expressions
+///
+/// # Arguments
+/// * `transaction` - The transaction
+/// * `operation` - Operation of the transaction providing `value`, `from` and `to`, if any
+///
+/// # Returns
+/// The parameters, `value` being `0` and `from` and `to` omitted without operation
+fn transaction_params(
+	transaction: &StellarTransaction,
+	operation: Option<&TxOperation>,
+) -> Vec<StellarMatchParamEntry> {
+	let mut params = vec![
+		StellarMatchParamEntry {
+			name: "hash".to_string(),
+			value: transaction.hash().clone(),
+			kind: "string".to_string(),
+			indexed: false,
+		},
+		StellarMatchParamEntry {
+			name: "ledger".to_string(),
+			value: transaction.ledger.to_string(),
+			kind: "i64".to_string(),
+			indexed: false,
+		},
+		StellarMatchParamEntry {
+			name: "value".to_string(),
+			value: operation
+				.and_then(|operation| operation.value.clone())
+				.unwrap_or("0".to_string()),
+			kind: "i64".to_string(),
+			indexed: false,
+		},
+	];
+	if let Some(operation) = operation {
+		params.extend(vec![
+			StellarMatchParamEntry {
+				name: "from".to_string(),
+				value: operation.sender.clone(),
+				kind: "address".to_string(),
+				indexed: false,
+			},
+			StellarMatchParamEntry {
+				name: "to".to_string(),
+				value: operation.receiver.clone(),
+				kind: "address".to_string(),
+				indexed: false,
+			},
+		]);
+	}
+	params
+}
+
 /// Implementation of the block filter for Stellar blockchain
 pub struct StellarBlockFilter<T> {
 	pub _client: PhantomData<T>,
@@ -69,13 +133,6 @@ impl<T> StellarBlockFilter<T> {
 			_ => TransactionStatus::Any,
 		};
 
-		struct TxOperation {
-			_operation_type: String,
-			sender: String,
-			receiver: String,
-			value: Option<String>,
-		}
-
 		let mut tx_operations: Vec<TxOperation> = vec![];
 
 		if let Some(decoded) = transaction.decoded() {
@@ -127,55 +184,10 @@ impl<T> StellarBlockFilter<T> {
 
 				if status_matches {
 					if let Some(expr) = &condition.expression {
-						// Create base transaction parameters outside operation loop
-						let base_params = vec![
-							StellarMatchParamEntry {
-								name: "hash".to_string(),
-								value: transaction.hash().clone(),
-								kind: "string".to_string(),
-								indexed: false,
-							},
-							StellarMatchParamEntry {
-								name: "ledger".to_string(),
-								value: transaction.ledger.to_string(),
-								kind: "i64".to_string(),
-								indexed: false,
-							},
-							// Default value for value
-							StellarMatchParamEntry {
-								name: "value".to_string(),
-								value: "0".to_string(),
-								kind: "i64".to_string(),
-								indexed: false,
-							},
-						];
-
 						// If we have operations, check each one
 						if !tx_operations.is_empty() {
 							for operation in &tx_operations {
-								let mut tx_params = base_params.clone();
-								// Remove default value for value
-								tx_params.remove(tx_params.len() - 1);
-								tx_params.extend(vec![
-									StellarMatchParamEntry {
-										name: "value".to_string(),
-										value: operation.value.clone().unwrap_or("0".to_string()),
-										kind: "i64".to_string(),
-										indexed: false,
-									},
-									StellarMatchParamEntry {
-										name: "from".to_string(),
-										value: operation.sender.clone(),
-										kind: "address".to_string(),
-										indexed: false,
-									},
-									StellarMatchParamEntry {
-										name: "to".to_string(),
-										value: operation.receiver.clone(),
-										kind: "address".to_string(),
-										indexed: false,
-									},
-								]);
+								let tx_params = transaction_params(transaction, Some(operation));
 
 								// Evaluate the expression with transaction parameters
 								match self.evaluate_expression(expr, &tx_params) {
@@ -199,7 +211,9 @@ impl<T> StellarBlockFilter<T> {
 							}
 						} else {
 							// Even with no operations, still evaluate base parameters
-							match self.evaluate_expression(expr, &base_params) {
+							match self
+								.evaluate_expression(expr, &transaction_params(transaction, None))
+							{
 								Ok(true) => {
 									matched_transactions.push(TransactionCondition {
 										expression: Some(expr.to_string()),
@@ -876,15 +890,32 @@ impl<T: BlockChainClient + StellarClientTrait> BlockFilter for StellarBlockFilte
 
 		tracing::debug!("Processing {} transaction(s)", transactions.len());
 
+		// The ledger is still filtered without its events, the matches being flagged as
+		// incomplete, rather than failing on an RPC outage limited to events. The events are
+		// required by the monitors matching events, the ledger then fails so that it is retried.
+		let mut missing_data = Vec::new();
 		let events = match client.get_events(stellar_block.sequence, None).await {
 			Ok(events) => events,
-			Err(e) => {
+			Err(e)
+				if monitors
+					.iter()
+					.any(|monitor| !monitor.match_conditions.events.is_empty()) =>
+			{
 				return Err(FilterError::network_error(
 					format!("Failed to get events for block {}", stellar_block.sequence),
 					Some(e.into()),
 					None,
 				));
 			}
+			Err(e) => {
+				tracing::warn!(
+					"Failed to get events for block {}, filtering without events: {}",
+					stellar_block.sequence,
+					e
+				);
+				missing_data.push("events".to_string());
+				Vec::new()
+			}
 		};
 
 		tracing::debug!("Processing {} event(s)", events.len());
@@ -1014,6 +1045,7 @@ impl<T: BlockChainClient + StellarClientTrait> BlockFilter for StellarBlockFilte
 								None
 							},
 						}),
+						missing_data: missing_data.clone(),
 					})));
 				}
 			}
@@ -1289,6 +1321,38 @@ mod tests {
 		BASE64.encode(buffer)
 	}
 
+	#[test]
+	fn test_transaction_params_names() {
+		let transaction = create_test_transaction(
+			"SUCCESS",
+			"3389e9f0f1a65f19736cacf544c2e825313e8447f569233bb8db39aa607c8889",
+			1,
+			None,
+			None,
+			None,
+			None,
+			false,
+		);
+		let operation = TxOperation {
+			_operation_type: "payment".to_string(),
+			sender: "sender".to_string(),
+			receiver: "receiver".to_string(),
+			value: Some("100".to_string()),
+		};
+
+		let names = |params: Vec<StellarMatchParamEntry>| -> Vec<String> {
+			params.into_iter().map(|param| param.name).collect()
+		};
+		assert_eq!(
+			names(transaction_params(&transaction, Some(&operation))),
+			TRANSACTION_VARIABLES
+		);
+		assert_eq!(
+			names(transaction_params(&transaction, None)),
+			TRANSACTION_VARIABLES[..3]
+		);
+	}
+
 	//////////////////////////////////////////////////////////////////////////////
 	// Test cases for find_matching_transaction method:
 	//////////////////////////////////////////////////////////////////////////////
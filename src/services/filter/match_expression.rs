@@ -0,0 +1,327 @@
+//! Inline expression evaluation against monitor matches.
+//!
+//! Trigger conditions can use an `expression` instead of a script. The expression uses the
+//! same language as match conditions and is evaluated in-process against the JSON
+//! representation of the match (the payload passed to trigger condition scripts). The base
+//! variables are the top-level fields of the match, e.g. `transaction`, `network_slug` or
+//! `matched_on_args`, and nested fields are reached with accessors:
+//! `transaction.value > 1000000000000000000 AND network_slug == 'ethereum_mainnet'`.
+
+use alloy::primitives::U256;
+use serde_json::{Map, Value};
+use std::str::FromStr;
+
+use crate::{
+	models::MonitorMatch,
+	services::filter::expression::{
+		self, compare_ordered_values, ComparisonOperator, ConditionEvaluator, EvaluationError,
+		Expression, LiteralValue,
+	},
+};
+
+/// Evaluator for expressions over the fields of a serialized match
+pub struct MatchPayloadEvaluator {
+	fields: Vec<(String, String, String)>,
+}
+
+impl MatchPayloadEvaluator {
+	/// Creates a new evaluator over the fields of a JSON object
+	///
+	/// # Arguments
+	/// * `payload` - The fields of the serialized match
+	pub fn new(payload: &Map<String, Value>) -> Self {
+		let fields = payload
+			.iter()
+			.map(|(name, value)| {
+				let raw = match value {
+					Value::String(s) => s.clone(),
+					other => other.to_string(),
+				};
+				(name.clone(), raw, json_kind(value).to_string())
+			})
+			.collect();
+		Self { fields }
+	}
+
+	/// Compares numeric values, accepting decimal and hexadecimal representations
+	fn compare_numeric(
+		&self,
+		left: &str,
+		operator: &ComparisonOperator,
+		right: &str,
+	) -> Result<bool, EvaluationError> {
+		if let (Some(left), Some(right)) = (parse_u256(left), parse_u256(right)) {
+			return compare_ordered_values(&left, operator, &right);
+		}
+
+		let parse = |value: &str| {
+			value.parse::<f64>().map_err(|_| {
+				EvaluationError::type_mismatch(
+					format!("Cannot parse '{}' as number", value),
+					None,
+					None,
+				)
+			})
+		};
+		let (left, right) = (parse(left)?, parse(right)?);
+		match operator {
+			ComparisonOperator::Eq => Ok(left == right),
+			ComparisonOperator::Ne => Ok(left != right),
+			ComparisonOperator::Gt => Ok(left > right),
+			ComparisonOperator::Gte => Ok(left >= right),
+			ComparisonOperator::Lt => Ok(left < right),
+			ComparisonOperator::Lte => Ok(left <= right),
+			_ => Err(EvaluationError::unsupported_operator(
+				format!(
+					"Operator {:?} not supported for numeric comparison",
+					operator
+				),
+				None,
+				None,
+			)),
+		}
+	}
+
+	/// Compares string values, hexadecimal strings are compared case-insensitively
+	fn compare_string(
+		&self,
+		left: &str,
+		operator: &ComparisonOperator,
+		right: &str,
+	) -> Result<bool, EvaluationError> {
+		let (left, right) = if left.starts_with("0x") && right.starts_with("0x") {
+			(left.to_lowercase(), right.to_lowercase())
+		} else {
+			(left.to_string(), right.to_string())
+		};
+
+		match operator {
+			ComparisonOperator::Eq => Ok(left == right),
+			ComparisonOperator::Ne => Ok(left != right),
+			ComparisonOperator::StartsWith => Ok(left.starts_with(&right)),
+			ComparisonOperator::EndsWith => Ok(left.ends_with(&right)),
+			ComparisonOperator::Contains => Ok(left.contains(&right)),
+			// Ordering operators compare numeric strings, e.g. hex-encoded quantities
+			_ => self.compare_numeric(&left, operator, &right),
+		}
+	}
+
+	/// Compares boolean values
+	fn compare_bool(
+		&self,
+		left: &str,
+		operator: &ComparisonOperator,
+		right: &LiteralValue<'_>,
+	) -> Result<bool, EvaluationError> {
+		let left = left.parse::<bool>().map_err(|_| {
+			EvaluationError::type_mismatch(
+				format!("Cannot parse '{}' as boolean", left),
+				None,
+				None,
+			)
+		})?;
+		let right = match right {
+			LiteralValue::Bool(b) => *b,
+			_ => {
+				return Err(EvaluationError::type_mismatch(
+					format!("Expected boolean for comparison, got {:?}", right),
+					None,
+					None,
+				))
+			}
+		};
+
+		match operator {
+			ComparisonOperator::Eq => Ok(left == right),
+			ComparisonOperator::Ne => Ok(left != right),
+			_ => Err(EvaluationError::unsupported_operator(
+				format!(
+					"Operator {:?} not supported for boolean comparison",
+					operator
+				),
+				None,
+				None,
+			)),
+		}
+	}
+}
+
+impl ConditionEvaluator for MatchPayloadEvaluator {
+	/// Gets the raw string value and kind for a top-level field of the match
+	fn get_base_param(&self, name: &str) -> Result<(&str, &str), EvaluationError> {
+		self.fields
+			.iter()
+			.find(|(field, _, _)| field == name)
+			.map(|(_, value, kind)| (value.as_str(), kind.as_str()))
+			.ok_or_else(|| EvaluationError::variable_not_found(name, None, None))
+	}
+
+	/// Performs the final comparison between the left resolved value and the literal value
+	fn compare_final_values(
+		&self,
+		left_kind: &str,
+		left_resolved_value: &str,
+		operator: &ComparisonOperator,
+		right_literal: &LiteralValue,
+	) -> Result<bool, EvaluationError> {
+		match (left_kind, right_literal) {
+			("bool", _) => self.compare_bool(left_resolved_value, operator, right_literal),
+			("number" | "string", LiteralValue::Number(right)) => {
+				self.compare_numeric(left_resolved_value, operator, right)
+			}
+			("number", LiteralValue::Str(right)) => {
+				self.compare_numeric(left_resolved_value, operator, right)
+			}
+			(_, LiteralValue::Str(right)) => {
+				self.compare_string(left_resolved_value, operator, right)
+			}
+			_ => Err(EvaluationError::type_mismatch(
+				format!(
+					"Cannot compare value '{}' of kind '{}' with {:?}",
+					left_resolved_value, left_kind, right_literal
+				),
+				None,
+				None,
+			)),
+		}
+	}
+
+	/// Gets the kind of a value from a JSON value
+	fn get_kind_from_json_value(&self, value: &Value) -> String {
+		json_kind(value).to_string()
+	}
+}
+
+/// Checks that an inline trigger expression is valid
+///
+/// # Arguments
+/// * `expression` - The expression to check
+///
+/// # Returns
+/// * `Result<(), EvaluationError>` - An error if the expression cannot be parsed
+pub fn validate_match_expression(expression: &str) -> Result<(), EvaluationError> {
+	parse_match_expression(expression).map(|_| ())
+}
+
+/// Evaluates an inline trigger expression against a match
+///
+/// # Arguments
+/// * `expression` - The expression to evaluate
+/// * `monitor_match` - The match the expression is evaluated against
+///
+/// # Returns
+/// * `Result<bool, EvaluationError>` - Whether the expression holds for the match
+pub fn evaluate_match_expression(
+	expression: &str,
+	monitor_match: &MonitorMatch,
+) -> Result<bool, EvaluationError> {
+	let parsed_ast = parse_match_expression(expression)?;
+
+	let payload = serde_json::to_value(monitor_match).map_err(|e| {
+		EvaluationError::parse_error(
+			format!("Failed to serialize match: {}", e),
+			Some(e.into()),
+			None,
+		)
+	})?;
+	// The match serializes as `{ "<chain>": { ... } }`, fields are read from the inner object
+	let fields = payload
+		.as_object()
+		.and_then(|chain| chain.values().next())
+		.and_then(Value::as_object)
+		.ok_or_else(|| {
+			EvaluationError::type_mismatch("Match is not a JSON object".to_string(), None, None)
+		})?;
+
+	expression::evaluate(&parsed_ast, &MatchPayloadEvaluator::new(fields))
+}
+
+fn parse_match_expression(expression: &str) -> Result<Expression<'_>, EvaluationError> {
+	if expression.trim().is_empty() {
+		return Err(EvaluationError::parse_error(
+			"Expression cannot be empty".to_string(),
+			None,
+			None,
+		));
+	}
+	expression::parse(expression).map_err(|e| {
+		let msg = format!("Failed to parse expression '{}': {}", expression, e);
+		EvaluationError::parse_error(msg, None, None)
+	})
+}
+
+fn json_kind(value: &Value) -> &'static str {
+	match value {
+		Value::Bool(_) => "bool",
+		Value::Number(_) => "number",
+		Value::String(_) => "string",
+		Value::Array(_) => "array",
+		Value::Object(_) => "object",
+		Value::Null => "null",
+	}
+}
+
+fn parse_u256(value: &str) -> Option<U256> {
+	if value.starts_with('-') || value.contains('.') {
+		return None;
+	}
+	U256::from_str(value).ok()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use serde_json::json;
+
+	fn evaluate_payload(expression: &str, payload: Value) -> Result<bool, EvaluationError> {
+		let parsed_ast = expression::parse(expression).unwrap();
+		let evaluator = MatchPayloadEvaluator::new(payload.as_object().unwrap());
+		expression::evaluate(&parsed_ast, &evaluator)
+	}
+
+	#[test]
+	fn test_evaluate_payload() {
+		let payload = json!({
+			"network_slug": "ethereum_mainnet",
+			"transaction": {
+				"from": "0xAbC0000000000000000000000000000000000001",
+				"value": "0xde0b6b3a7640000",
+				"nonce": 7
+			},
+			"matched_on_args": {"events": [{"signature": "Transfer(address,address,uint256)"}]},
+			"finalized": true
+		});
+
+		assert!(evaluate_payload("network_slug == 'ethereum_mainnet'", payload.clone()).unwrap());
+		assert!(
+			evaluate_payload("transaction.value >= 1000000000000000000", payload.clone()).unwrap()
+		);
+		assert!(
+			!evaluate_payload("transaction.value > 1000000000000000000", payload.clone()).unwrap()
+		);
+		assert!(evaluate_payload("transaction.nonce < 10", payload.clone()).unwrap());
+		assert!(evaluate_payload(
+			"transaction.from == 0xabc0000000000000000000000000000000000001",
+			payload.clone()
+		)
+		.unwrap());
+		assert!(evaluate_payload(
+			"matched_on_args.events[0].signature starts_with 'Transfer'",
+			payload.clone()
+		)
+		.unwrap());
+		assert!(evaluate_payload(
+			"finalized == true AND network_slug contains 'main'",
+			payload.clone()
+		)
+		.unwrap());
+		assert!(evaluate_payload("unknown == 1", payload).is_err());
+	}
+
+	#[test]
+	fn test_validate_match_expression() {
+		assert!(validate_match_expression("transaction.value > 100").is_ok());
+		assert!(validate_match_expression("transaction.value >").is_err());
+		assert!(validate_match_expression("  ").is_err());
+	}
+}
@@ -4,6 +4,8 @@
 //! - Block filtering for different chain types
 //! - Match handling and processing
 //! - Chain-specific helper functions
+//! - Windowed state storage for stateful conditions
+//! - Stream-based filtering pipeline for embedders
 
 mod error;
 #[cfg(fuzzing)]
@@ -12,15 +14,29 @@ pub mod expression;
 mod expression;
 mod filter_match;
 mod filters;
+mod match_expression;
+mod pipeline;
+pub mod state;
+
+use crate::models::BlockChainType;
 
 pub use error::FilterError;
-pub use filter_match::handle_match;
+pub use filter_match::{handle_batch, handle_match, json_to_hashmap, normalize_match};
+pub use match_expression::{
+	evaluate_match_expression, validate_match_expression, MatchPayloadEvaluator,
+};
+pub use pipeline::{
+	applicable_monitors, filter_network_monitors, match_block, rpc_groups, BlockStreamExt,
+	FilterPipeline,
+};
+pub use state::{WindowEntry, WindowStore, WindowStoreError};
 
 pub use filters::{
 	evm::{
 		evaluator::{EVMArgs, EVMConditionEvaluator},
 		filter::EVMBlockFilter,
-		helpers as evm_helpers,
+		helpers as evm_helpers, interactions as evm_interactions, nonces as evm_nonces,
+		receipts as evm_receipts,
 	},
 	midnight::{filter::MidnightBlockFilter, helpers as midnight_helpers},
 	solana::{
@@ -28,12 +44,46 @@ pub use filters::{
 		filter::SolanaBlockFilter,
 		helpers as solana_helpers,
 	},
+	start_monitor_state_snapshots,
 	stellar::{
 		evaluator::{StellarArgs, StellarConditionEvaluator},
 		filter::{EventMap, StellarBlockFilter},
 		helpers as stellar_helpers,
 	},
-	BlockFilter, FilterService, FilterServiceTrait,
+	BlockFilter, FilterContext, FilterService, FilterServiceTrait,
 };
 
 pub use expression::{ComparisonOperator, ConditionEvaluator, EvaluationError, LiteralValue};
+
+/// Returns the base names of the variables compared by an expression
+///
+/// # Arguments
+/// * `expression` - The expression
+///
+/// # Returns
+/// The variable names, `None` if the expression cannot be parsed
+pub fn expression_variables(expression: &str) -> Option<Vec<String>> {
+	let parsed = expression::parse(expression).ok()?;
+	Some(
+		expression::variable_names(&parsed)
+			.into_iter()
+			.map(str::to_string)
+			.collect(),
+	)
+}
+
+/// Returns the variables available to the transaction condition expressions of a chain
+///
+/// # Arguments
+/// * `chain` - The chain
+///
+/// # Returns
+/// The variable names, `None` if the chain does not evaluate transaction expressions
+pub fn transaction_variables(chain: &BlockChainType) -> Option<&'static [&'static str]> {
+	match chain {
+		BlockChainType::EVM => Some(filters::evm::filter::TRANSACTION_VARIABLES),
+		BlockChainType::Stellar => Some(filters::stellar::filter::TRANSACTION_VARIABLES),
+		BlockChainType::Solana => Some(filters::solana::filter::TRANSACTION_VARIABLES),
+		BlockChainType::Midnight => None,
+	}
+}
@@ -0,0 +1,398 @@
+//! Stream-based block filtering pipeline.
+//!
+//! Exposes the matching engine of the block watcher as a stream combinator: blocks go in,
+//! processed blocks with their matches come out, in the order of the input stream. Embedders
+//! can feed blocks from their own source (e.g. a Kafka topic) and send the matches to their
+//! own sink while reusing the monitor selection, the block guardrails and the chain filters:
+//!
+//! ```rust,ignore
+//! let pipeline = FilterPipeline::new(client, network, monitors);
+//! let mut processed = blocks.filter_matches(pipeline);
+//! while let Some(processed_block) = processed.next().await {
+//!     // forward processed_block?.processing_results
+//! }
+//! ```
+
+use futures::{stream::BoxStream, Stream, StreamExt};
+use std::sync::Arc;
+
+use crate::{
+	models::{BlockType, ContractSpec, Monitor, MonitorMatch, Network, ProcessedBlock},
+	services::{
+		blockchain::{client_key, BlockChainClient, BlockFilterFactory},
+		blockwatcher::report_progress,
+		filter::{error::FilterError, FilterService},
+	},
+	utils::{control::CONTROL, metrics::OVERSIZED_BLOCKS_TOTAL, monitor::activation::DEPLOYMENTS},
+};
+
+/// Number of blocks filtered concurrently by default
+const DEFAULT_CONCURRENCY: usize = 32;
+
+/// Pipeline filtering the blocks of a network against a set of monitors
+pub struct FilterPipeline<T> {
+	client: Arc<T>,
+	network: Network,
+	monitors: Vec<Monitor>,
+	contract_specs: Vec<(String, ContractSpec)>,
+	filter_service: Arc<FilterService>,
+	concurrency: usize,
+}
+
+impl<T> FilterPipeline<T>
+where
+	T: BlockChainClient + BlockFilterFactory<T> + Send + Sync + 'static,
+{
+	/// Creates a pipeline for the blocks of a network
+	///
+	/// # Arguments
+	/// * `client` - Client of the network, used by the filters to fetch logs, receipts, etc.
+	/// * `network` - Network of the blocks
+	/// * `monitors` - Monitors to match, those not configured for the network are ignored
+	pub fn new(client: Arc<T>, network: Network, monitors: Vec<Monitor>) -> Self {
+		Self {
+			client,
+			network,
+			monitors,
+			contract_specs: Vec::new(),
+			filter_service: Arc::new(FilterService::new()),
+			concurrency: DEFAULT_CONCURRENCY,
+		}
+	}
+
+	/// Sets the contract specs used to decode the matched calls and events
+	pub fn with_contract_specs(mut self, contract_specs: Vec<(String, ContractSpec)>) -> Self {
+		self.contract_specs = contract_specs;
+		self
+	}
+
+	/// Sets the number of blocks filtered concurrently (at least 1)
+	pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+		self.concurrency = concurrency.max(1);
+		self
+	}
+
+	/// Filters a single block
+	///
+	/// # Arguments
+	/// * `block` - Block of the pipeline's network
+	///
+	/// # Returns
+	/// The processed block with the matches of the monitors applicable to it
+	///
+	/// # Errors
+	/// Returns an error if the block could not be filtered
+	pub async fn process(&self, block: &BlockType) -> Result<ProcessedBlock, FilterError> {
+		let block_number = block.number().unwrap_or(0);
+		let monitors = applicable_monitors(&self.monitors, &self.network.slug, block_number);
+		let processing_results = if monitors.is_empty() {
+			Vec::new()
+		} else {
+			match_block(
+				self.client.as_ref(),
+				&self.network,
+				block,
+				&monitors,
+				Some(&self.contract_specs),
+				&self.filter_service,
+			)
+			.await?
+		};
+
+		Ok(ProcessedBlock {
+			block_number,
+			network_slug: self.network.slug.clone(),
+			processing_results,
+		})
+	}
+
+	/// Filters a stream of blocks
+	///
+	/// Up to `concurrency` blocks are filtered at once, and the processed blocks are yielded
+	/// in the order of the input stream.
+	///
+	/// # Arguments
+	/// * `blocks` - Blocks of the pipeline's network
+	///
+	/// # Returns
+	/// A stream of processed blocks, or of the errors of the blocks that could not be filtered
+	pub fn run<S>(self, blocks: S) -> BoxStream<'static, Result<ProcessedBlock, FilterError>>
+	where
+		S: Stream<Item = BlockType> + Send + 'static,
+	{
+		let concurrency = self.concurrency;
+		let pipeline = Arc::new(self);
+		blocks
+			.map(move |block| {
+				let pipeline = pipeline.clone();
+				async move { pipeline.process(&block).await }
+			})
+			.buffered(concurrency)
+			.boxed()
+	}
+}
+
+/// Stream combinator filtering blocks with a [`FilterPipeline`]
+pub trait BlockStreamExt: Stream<Item = BlockType> + Send + Sized + 'static {
+	/// Filters the blocks of the stream, see [`FilterPipeline::run`]
+	fn filter_matches<T>(
+		self,
+		pipeline: FilterPipeline<T>,
+	) -> BoxStream<'static, Result<ProcessedBlock, FilterError>>
+	where
+		T: BlockChainClient + BlockFilterFactory<T> + Send + Sync + 'static,
+	{
+		pipeline.run(self)
+	}
+}
+
+impl<S> BlockStreamExt for S where S: Stream<Item = BlockType> + Send + Sized + 'static {}
+
+/// Filters monitors that are applicable to a specific network.
+///
+/// # Arguments
+/// * `monitors` - List of monitors to filter
+/// * `network_slug` - Network identifier to filter by
+///
+/// # Returns
+/// Returns a vector of monitors that are configured for the specified network, with their
+/// addresses resolved for that network
+pub fn filter_network_monitors(monitors: &[Monitor], network_slug: &String) -> Vec<Monitor> {
+	monitors
+		.iter()
+		.filter(|m| m.networks.contains(network_slug))
+		.map(|m| m.for_network(network_slug))
+		.collect()
+}
+
+/// Selects the monitors applicable to a block.
+///
+/// Monitors only apply to the networks they are configured for, from their start block,
+/// from the deployment of the contract they depend on, and not while paused at runtime.
+///
+/// # Arguments
+/// * `monitors` - List of monitors to select from
+/// * `network_slug` - Network of the block
+/// * `block_number` - Number of the block
+///
+/// # Returns
+/// The applicable monitors, with their addresses resolved for the network
+pub fn applicable_monitors(
+	monitors: &[Monitor],
+	network_slug: &String,
+	block_number: u64,
+) -> Vec<Monitor> {
+	let mut applicable_monitors = filter_network_monitors(monitors, network_slug);
+	applicable_monitors.retain(|monitor| {
+		monitor
+			.start_block
+			.is_none_or(|start| block_number >= start)
+			&& DEPLOYMENTS.is_active(monitor, network_slug, block_number)
+			&& !CONTROL.is_monitor_paused(&monitor.name)
+	});
+	applicable_monitors
+}
+
+/// Groups monitors by the RPC endpoints they are evaluated with.
+///
+/// Monitors with an RPC override for the network (see [`Monitor::rpc_network`]) are evaluated
+/// with their own endpoints, the others with those of the network.
+///
+/// # Arguments
+/// * `network` - The network the monitors are evaluated on
+/// * `monitors` - Monitors applicable to the network
+///
+/// # Returns
+/// The network with the endpoints of each group, along with the monitors of the group, the
+/// group of the network endpoints first
+pub fn rpc_groups(network: &Network, monitors: &[Monitor]) -> Vec<(Network, Vec<Monitor>)> {
+	let mut groups: Vec<(String, Network, Vec<Monitor>)> = Vec::new();
+	for monitor in monitors {
+		let rpc_network = monitor
+			.rpc_network(network)
+			.unwrap_or_else(|| network.clone());
+		let key = client_key(&rpc_network);
+		match groups
+			.iter_mut()
+			.find(|(group_key, _, _)| *group_key == key)
+		{
+			Some((_, _, group)) => group.push(monitor.clone()),
+			None => groups.push((key, rpc_network, vec![monitor.clone()])),
+		}
+	}
+	let default_key = client_key(network);
+	groups.sort_by_key(|(key, _, _)| *key != default_key);
+	groups
+		.into_iter()
+		.map(|(_, network, monitors)| (network, monitors))
+		.collect()
+}
+
+/// Filters a block for the given monitors.
+///
+/// A block exceeding the transaction count of the network's `block_guardrails` is processed
+/// in chunks of transactions, one chunk at a time, logging its progress and reporting it to
+/// the watchdog. Data fetched for the whole block, such as its EVM logs, is fetched once for
+/// all the chunks. Oversized blocks are counted in the `oversized_blocks_total` metric.
+///
+/// # Arguments
+/// * `client` - The client to use to filter the block
+/// * `network` - The network the block belongs to
+/// * `block` - The block to filter
+/// * `monitors` - Monitors applicable to the block
+/// * `contract_specs` - Contract specs of the monitored addresses
+/// * `filter_service` - Service for filtering blockchain data
+///
+/// # Errors
+/// Returns an error if the block, or one of its chunks, could not be filtered
+pub async fn match_block<T>(
+	client: &T,
+	network: &Network,
+	block: &BlockType,
+	monitors: &[Monitor],
+	contract_specs: Option<&[(String, ContractSpec)]>,
+	filter_service: &FilterService,
+) -> Result<Vec<MonitorMatch>, FilterError>
+where
+	T: BlockChainClient + BlockFilterFactory<T>,
+{
+	let Some(chunks) = oversized_block_chunks(network, block) else {
+		return filter_service
+			.filter_block(client, network, block, monitors, contract_specs)
+			.await;
+	};
+
+	let block_number = block.number().unwrap_or(0);
+	let transaction_count = block.transaction_count().unwrap_or(0);
+	OVERSIZED_BLOCKS_TOTAL
+		.with_label_values(&[&network.slug])
+		.inc();
+	tracing::warn!(
+		network = %network.slug,
+		block_number,
+		transactions = transaction_count,
+		chunks = chunks.len(),
+		"Block {} has {} transactions, processing it in {} chunks",
+		block_number,
+		transaction_count,
+		chunks.len()
+	);
+
+	let chunk_count = chunks.len();
+	let mut transactions_processed = 0;
+	let mut on_chunk = |index: usize, chunk: &BlockType| {
+		transactions_processed += chunk.transaction_count().unwrap_or(0);
+		report_progress(&network.slug);
+		tracing::info!(
+			network = %network.slug,
+			block_number,
+			"Processed chunk {}/{} of block {} ({}/{} transactions)",
+			index + 1,
+			chunk_count,
+			block_number,
+			transactions_processed,
+			transaction_count
+		);
+	};
+	let matches = filter_service
+		.filter_block_chunks(
+			client,
+			network,
+			block,
+			&chunks,
+			monitors,
+			contract_specs,
+			&mut on_chunk,
+		)
+		.await?;
+
+	Ok(matches)
+}
+
+/// Splits a block exceeding the transaction count of the network's guardrails into chunks
+///
+/// # Returns
+/// The chunks of the block, or `None` if the block is not oversized or its transactions
+/// cannot be split
+fn oversized_block_chunks(network: &Network, block: &BlockType) -> Option<Vec<BlockType>> {
+	let guardrails = network.block_guardrails.as_ref()?;
+	if block.transaction_count()? <= guardrails.max_block_transactions {
+		return None;
+	}
+	block.transaction_chunks(guardrails.chunk_size)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::utils::tests::builders::{evm::monitor::MonitorBuilder, network::NetworkBuilder};
+
+	#[test]
+	fn test_applicable_monitors() {
+		let monitors = vec![
+			MonitorBuilder::new()
+				.name("from_genesis")
+				.networks(vec!["ethereum_mainnet".to_string()])
+				.build(),
+			MonitorBuilder::new()
+				.name("from_block_100")
+				.networks(vec!["ethereum_mainnet".to_string()])
+				.start_block(100)
+				.build(),
+			MonitorBuilder::new()
+				.name("other_network")
+				.networks(vec!["base_mainnet".to_string()])
+				.build(),
+		];
+		let network_slug = "ethereum_mainnet".to_string();
+
+		let names = |block_number| {
+			applicable_monitors(&monitors, &network_slug, block_number)
+				.into_iter()
+				.map(|monitor| monitor.name)
+				.collect::<Vec<_>>()
+		};
+		assert_eq!(names(99), vec!["from_genesis"]);
+		assert_eq!(names(100), vec!["from_genesis", "from_block_100"]);
+	}
+
+	#[test]
+	fn test_rpc_groups() {
+		let network = NetworkBuilder::new()
+			.slug("ethereum_mainnet")
+			.rpc_urls(vec!["https://eth.public.example.com"])
+			.build();
+		let monitors = vec![
+			MonitorBuilder::new()
+				.name("traced")
+				.rpc_override("ethereum_mainnet", "https://eth.private.example.com")
+				.build(),
+			MonitorBuilder::new().name("shared").build(),
+			MonitorBuilder::new()
+				.name("other_override")
+				.rpc_override("base_mainnet", "https://base.private.example.com")
+				.build(),
+			MonitorBuilder::new()
+				.name("traced_too")
+				.rpc_override("ethereum_mainnet", "https://eth.private.example.com")
+				.build(),
+		];
+
+		let groups = rpc_groups(&network, &monitors);
+		assert_eq!(groups.len(), 2);
+		let names = |monitors: &[Monitor]| {
+			monitors
+				.iter()
+				.map(|monitor| monitor.name.clone())
+				.collect::<Vec<_>>()
+		};
+		assert_eq!(groups[0].0.rpc_urls, network.rpc_urls);
+		assert_eq!(names(&groups[0].1), vec!["shared", "other_override"]);
+		assert_eq!(groups[1].0.slug, "ethereum_mainnet");
+		assert_eq!(
+			groups[1].0.rpc_urls[0].url.as_str(),
+			"https://eth.private.example.com"
+		);
+		assert_eq!(names(&groups[1].1), vec!["traced", "traced_too"]);
+	}
+}
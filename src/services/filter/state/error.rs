@@ -0,0 +1,97 @@
+//! Window store error types and handling.
+//!
+//! Provides error types for the windowed state store backends.
+
+use crate::utils::logging::error::{ClassifiedError, ErrorClass, ErrorContext, TraceableError};
+use std::collections::HashMap;
+use thiserror::Error as ThisError;
+use uuid::Uuid;
+
+/// Represents errors that can occur while accessing the window store
+#[derive(ThisError, Debug)]
+pub enum WindowStoreError {
+	/// Errors related to the connection to the backend
+	#[error("Connection error: {0}")]
+	ConnectionError(ErrorContext),
+
+	/// Errors returned by the backend for a command
+	#[error("Command error: {0}")]
+	CommandError(ErrorContext),
+
+	/// Errors related to encoding or decoding stored entries
+	#[error("Serialization error: {0}")]
+	SerializationError(ErrorContext),
+
+	/// Other errors that don't fit into the categories above
+	#[error(transparent)]
+	Other(#[from] anyhow::Error),
+}
+
+impl WindowStoreError {
+	// Connection error
+	pub fn connection_error(
+		msg: impl Into<String>,
+		source: Option<Box<dyn std::error::Error + Send + Sync + 'static>>,
+		metadata: Option<HashMap<String, String>>,
+	) -> Self {
+		Self::ConnectionError(ErrorContext::new_with_log(msg, source, metadata))
+	}
+
+	// Command error
+	pub fn command_error(
+		msg: impl Into<String>,
+		source: Option<Box<dyn std::error::Error + Send + Sync + 'static>>,
+		metadata: Option<HashMap<String, String>>,
+	) -> Self {
+		Self::CommandError(ErrorContext::new_with_log(msg, source, metadata))
+	}
+
+	// Serialization error
+	pub fn serialization_error(
+		msg: impl Into<String>,
+		source: Option<Box<dyn std::error::Error + Send + Sync + 'static>>,
+		metadata: Option<HashMap<String, String>>,
+	) -> Self {
+		Self::SerializationError(ErrorContext::new_with_log(msg, source, metadata))
+	}
+}
+
+impl TraceableError for WindowStoreError {
+	fn trace_id(&self) -> String {
+		match self {
+			Self::ConnectionError(ctx) => ctx.trace_id.clone(),
+			Self::CommandError(ctx) => ctx.trace_id.clone(),
+			Self::SerializationError(ctx) => ctx.trace_id.clone(),
+			Self::Other(_) => Uuid::new_v4().to_string(),
+		}
+	}
+}
+
+impl ClassifiedError for WindowStoreError {
+	fn classification(&self) -> ErrorClass {
+		match self {
+			Self::ConnectionError(_) => ErrorClass::Transient,
+			Self::CommandError(_) | Self::SerializationError(_) | Self::Other(_) => {
+				ErrorClass::Permanent
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_classification() {
+		assert_eq!(
+			WindowStoreError::connection_error("refused", None, None).classification(),
+			ErrorClass::Transient
+		);
+		assert_eq!(
+			WindowStoreError::command_error("WRONGTYPE", None, None).classification(),
+			ErrorClass::Permanent
+		);
+		assert!(!WindowStoreError::serialization_error("invalid", None, None).is_retryable());
+	}
+}
@@ -0,0 +1,404 @@
+//! In-memory window store.
+//!
+//! Keeps the windows in process memory. When a snapshot path is set, the windows are
+//! loaded from it on creation and written back by [`WindowStore::persist`], so that they
+//! survive restarts. [`WindowStore::persist_blocking`] writes the same snapshot without the
+//! runtime, for the panic hook.
+
+use async_trait::async_trait;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::{
+	collections::HashMap,
+	path::{Path, PathBuf},
+	sync::{
+		atomic::{AtomicBool, Ordering},
+		RwLock, TryLockError,
+	},
+	time::Duration,
+};
+
+use super::{error::WindowStoreError, WindowEntry, WindowStore};
+use crate::utils::metrics::set_window_store_keys;
+
+/// Entries of a key, sorted by position
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Series {
+	entries: Vec<WindowEntry>,
+	/// Unix timestamp (milliseconds) after which the key is expired
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	expires_at: Option<i64>,
+}
+
+impl Series {
+	fn is_expired(&self, now: i64) -> bool {
+		self.expires_at.is_some_and(|expires_at| now >= expires_at)
+	}
+}
+
+/// Window store keeping the windows in process memory
+#[derive(Default)]
+pub struct InMemoryWindowStore {
+	series: RwLock<HashMap<String, Series>>,
+	snapshot_path: Option<PathBuf>,
+	/// Whether the windows changed since the last snapshot
+	dirty: AtomicBool,
+}
+
+impl InMemoryWindowStore {
+	/// Creates an empty store that is not snapshotted
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Creates a store snapshotted to a JSON file, loading its content if it exists
+	///
+	/// # Arguments
+	/// * `path` - Path of the snapshot file
+	pub fn with_snapshot(path: PathBuf) -> Self {
+		let now = Utc::now().timestamp_millis();
+		let series: HashMap<String, Series> = match std::fs::read_to_string(&path) {
+			Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+				tracing::warn!(
+					"Ignoring invalid window store snapshot {}: {}",
+					path.display(),
+					e
+				);
+				HashMap::new()
+			}),
+			Err(_) => HashMap::new(),
+		};
+		let series: HashMap<String, Series> = series
+			.into_iter()
+			.filter(|(_, series)| !series.is_expired(now))
+			.collect();
+		set_window_store_keys("memory", series.len());
+
+		Self {
+			series: RwLock::new(series),
+			snapshot_path: Some(path),
+			dirty: AtomicBool::new(false),
+		}
+	}
+
+	/// Returns the number of keys that are not expired
+	pub fn len(&self) -> usize {
+		let now = Utc::now().timestamp_millis();
+		self.read()
+			.values()
+			.filter(|series| !series.is_expired(now))
+			.count()
+	}
+
+	/// Returns whether the store holds no key that is not expired
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	/// Drops the expired keys
+	///
+	/// # Returns
+	/// The number of dropped keys
+	pub fn purge_expired(&self) -> usize {
+		let now = Utc::now().timestamp_millis();
+		let mut series = self.write();
+		let before = series.len();
+		series.retain(|_, series| !series.is_expired(now));
+		set_window_store_keys("memory", series.len());
+		before - series.len()
+	}
+
+	fn read(&self) -> std::sync::RwLockReadGuard<'_, HashMap<String, Series>> {
+		match self.series.read() {
+			Ok(guard) => guard,
+			Err(poisoned) => poisoned.into_inner(),
+		}
+	}
+
+	fn write(&self) -> std::sync::RwLockWriteGuard<'_, HashMap<String, Series>> {
+		match self.series.write() {
+			Ok(guard) => guard,
+			Err(poisoned) => poisoned.into_inner(),
+		}
+	}
+
+	/// Marks the store dirty again after a failed write, and describes the failure
+	fn write_error(&self, path: &Path, error: std::io::Error) -> WindowStoreError {
+		// Written again by the next snapshot
+		self.dirty.store(true, Ordering::SeqCst);
+		WindowStoreError::connection_error(
+			format!(
+				"Failed to write the window store snapshot {}",
+				path.display()
+			),
+			Some(error.into()),
+			None,
+		)
+	}
+}
+
+#[async_trait]
+impl WindowStore for InMemoryWindowStore {
+	fn backend(&self) -> &'static str {
+		"memory"
+	}
+
+	async fn push(
+		&self,
+		key: &str,
+		entry: WindowEntry,
+		ttl: Option<Duration>,
+	) -> Result<(), WindowStoreError> {
+		let now = Utc::now().timestamp_millis();
+		let mut series = self.write();
+		let key_series = series.entry(key.to_string()).or_default();
+		if key_series.is_expired(now) {
+			*key_series = Series::default();
+		}
+		let index = key_series
+			.entries
+			.partition_point(|existing| existing.position <= entry.position);
+		key_series.entries.insert(index, entry);
+		key_series.expires_at = ttl.map(|ttl| now.saturating_add(ttl.as_millis() as i64));
+		set_window_store_keys("memory", series.len());
+		self.dirty.store(true, Ordering::SeqCst);
+		Ok(())
+	}
+
+	async fn range(
+		&self,
+		key: &str,
+		from: u64,
+		to: u64,
+	) -> Result<Vec<WindowEntry>, WindowStoreError> {
+		let now = Utc::now().timestamp_millis();
+		Ok(self
+			.read()
+			.get(key)
+			.filter(|series| !series.is_expired(now))
+			.map(|series| {
+				series
+					.entries
+					.iter()
+					.filter(|entry| entry.position >= from && entry.position <= to)
+					.copied()
+					.collect()
+			})
+			.unwrap_or_default())
+	}
+
+	async fn trim(&self, key: &str, before: u64) -> Result<usize, WindowStoreError> {
+		let mut series = self.write();
+		let Some(key_series) = series.get_mut(key) else {
+			return Ok(0);
+		};
+		let removed = key_series
+			.entries
+			.partition_point(|entry| entry.position < before);
+		key_series.entries.drain(..removed);
+		if key_series.entries.is_empty() {
+			series.remove(key);
+			set_window_store_keys("memory", series.len());
+		}
+		if removed > 0 {
+			self.dirty.store(true, Ordering::SeqCst);
+		}
+		Ok(removed)
+	}
+
+	async fn remove(&self, key: &str) -> Result<(), WindowStoreError> {
+		let mut series = self.write();
+		if series.remove(key).is_some() {
+			set_window_store_keys("memory", series.len());
+			self.dirty.store(true, Ordering::SeqCst);
+		}
+		Ok(())
+	}
+
+	async fn increment(&self, key: &str, amount: u64) -> Result<u64, WindowStoreError> {
+		let mut series = self.write();
+		// A counter is kept as a single entry holding its value
+		let key_series = series.entry(key.to_string()).or_default();
+		let value = key_series
+			.entries
+			.iter()
+			.map(|entry| entry.value)
+			.sum::<f64>()
+			.round() as u64
+			+ amount;
+		key_series.entries = vec![WindowEntry {
+			position: 0,
+			value: value as f64,
+		}];
+		key_series.expires_at = None;
+		set_window_store_keys("memory", series.len());
+		self.dirty.store(true, Ordering::SeqCst);
+		Ok(value)
+	}
+
+	async fn persist(&self) -> Result<(), WindowStoreError> {
+		let Some(path) = &self.snapshot_path else {
+			return Ok(());
+		};
+		if !self.dirty.swap(false, Ordering::SeqCst) {
+			return Ok(());
+		}
+		self.purge_expired();
+		let snapshot = serialize_snapshot(&self.read())?;
+
+		let result = async {
+			if let Some(parent) = path.parent() {
+				tokio::fs::create_dir_all(parent).await?;
+			}
+			tokio::fs::write(path, snapshot).await
+		}
+		.await;
+		result.map_err(|e| self.write_error(path, e))
+	}
+
+	fn persist_blocking(&self) -> Result<(), WindowStoreError> {
+		let Some(path) = &self.snapshot_path else {
+			return Ok(());
+		};
+		if !self.dirty.swap(false, Ordering::SeqCst) {
+			return Ok(());
+		}
+		// The caller may hold the lock, expired keys are dropped when the snapshot is loaded
+		let series = match self.series.try_read() {
+			Ok(guard) => guard,
+			Err(TryLockError::Poisoned(poisoned)) => poisoned.into_inner(),
+			Err(TryLockError::WouldBlock) => {
+				self.dirty.store(true, Ordering::SeqCst);
+				return Err(WindowStoreError::connection_error(
+					"The window store is locked, snapshot skipped",
+					None,
+					None,
+				));
+			}
+		};
+		let snapshot = serialize_snapshot(&series)?;
+		drop(series);
+
+		let result = path
+			.parent()
+			.map_or(Ok(()), std::fs::create_dir_all)
+			.and_then(|_| std::fs::write(path, snapshot));
+		result.map_err(|e| self.write_error(path, e))
+	}
+}
+
+/// Serializes the windows of a snapshot
+fn serialize_snapshot(series: &HashMap<String, Series>) -> Result<String, WindowStoreError> {
+	serde_json::to_string(series).map_err(|e| {
+		WindowStoreError::serialization_error(
+			"Failed to serialize the window store snapshot",
+			Some(e.into()),
+			None,
+		)
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn entry(position: u64, value: f64) -> WindowEntry {
+		WindowEntry { position, value }
+	}
+
+	#[tokio::test]
+	async fn test_push_range_and_trim() {
+		let store = InMemoryWindowStore::new();
+		store.push("key", entry(12, 2.0), None).await.unwrap();
+		store.push("key", entry(10, 1.0), None).await.unwrap();
+		store.push("key", entry(15, 4.0), None).await.unwrap();
+		store.push("other", entry(11, 8.0), None).await.unwrap();
+
+		assert_eq!(
+			store.range("key", 10, 12).await.unwrap(),
+			vec![entry(10, 1.0), entry(12, 2.0)]
+		);
+		assert_eq!(store.count("key", 11, 20).await.unwrap(), 2);
+		assert_eq!(store.sum("key", 0, u64::MAX).await.unwrap(), 7.0);
+
+		assert_eq!(store.trim("key", 12).await.unwrap(), 1);
+		assert_eq!(store.count("key", 0, u64::MAX).await.unwrap(), 2);
+
+		store.remove("key").await.unwrap();
+		assert!(store.range("key", 0, u64::MAX).await.unwrap().is_empty());
+		assert_eq!(store.len(), 1);
+	}
+
+	#[tokio::test]
+	async fn test_increment() {
+		let store = InMemoryWindowStore::new();
+		assert_eq!(store.increment("counter", 2).await.unwrap(), 2);
+		assert_eq!(store.increment("counter", 3).await.unwrap(), 5);
+		assert_eq!(store.increment("other", 1).await.unwrap(), 1);
+		assert_eq!(store.sum("counter", 0, u64::MAX).await.unwrap(), 5.0);
+	}
+
+	#[tokio::test]
+	async fn test_ttl() {
+		let store = InMemoryWindowStore::new();
+		store
+			.push("expired", entry(1, 1.0), Some(Duration::ZERO))
+			.await
+			.unwrap();
+		store
+			.push("alive", entry(1, 1.0), Some(Duration::from_secs(3600)))
+			.await
+			.unwrap();
+
+		assert!(store.range("expired", 0, 10).await.unwrap().is_empty());
+		assert_eq!(store.count("alive", 0, 10).await.unwrap(), 1);
+		assert_eq!(store.len(), 1);
+		assert_eq!(store.purge_expired(), 1);
+
+		// Writing to an expired key starts a new window
+		store.push("expired", entry(2, 1.0), None).await.unwrap();
+		assert_eq!(store.count("expired", 0, 10).await.unwrap(), 1);
+	}
+
+	#[tokio::test]
+	async fn test_snapshot() {
+		let temp_dir = tempfile::tempdir().unwrap();
+		let path = temp_dir.path().join("state").join("windows.json");
+
+		let store = InMemoryWindowStore::with_snapshot(path.clone());
+		store.push("key", entry(1, 3.0), None).await.unwrap();
+		store
+			.push("expired", entry(1, 1.0), Some(Duration::ZERO))
+			.await
+			.unwrap();
+		store.persist().await.unwrap();
+
+		let restored = InMemoryWindowStore::with_snapshot(path);
+		assert_eq!(
+			restored.range("key", 0, 10).await.unwrap(),
+			vec![entry(1, 3.0)]
+		);
+		assert!(restored.range("expired", 0, 10).await.unwrap().is_empty());
+	}
+
+	#[tokio::test]
+	async fn test_persist_blocking() {
+		let temp_dir = tempfile::tempdir().unwrap();
+		let path = temp_dir.path().join("state").join("windows.json");
+
+		let store = InMemoryWindowStore::with_snapshot(path.clone());
+		store.push("key", entry(1, 3.0), None).await.unwrap();
+		{
+			let _guard = store.series.write().unwrap();
+			assert!(store.persist_blocking().is_err());
+		}
+		assert!(!path.exists());
+		store.persist_blocking().unwrap();
+
+		let restored = InMemoryWindowStore::with_snapshot(path);
+		assert_eq!(
+			restored.range("key", 0, 10).await.unwrap(),
+			vec![entry(1, 3.0)]
+		);
+	}
+}
@@ -0,0 +1,273 @@
+//! Windowed state store.
+//!
+//! Stateful conditions (rate of occurrence, aggregations over recent blocks) keep entries
+//! over a rolling window, shared by the block filters that are created for every block. A
+//! [`WindowStore`] keeps, under a key, entries positioned by block number or timestamp; a key
+//! expires once its TTL elapsed since its last write. Two backends are provided:
+//! - [`InMemoryWindowStore`]: process-local, snapshotted to a JSON file to survive restarts
+//! - [`RedisWindowStore`]: shared by several instances of the service
+//!
+//! The store of the service is created at startup from the runtime settings
+//! ([`create_window_store`]): Redis when `WINDOW_STORE_URL` is set, and memory snapshotted to
+//! `WINDOW_STORE_SNAPSHOT_PATH` otherwise. Its operations are counted by the
+//! `window_store_operations_total` and `window_store_errors_total` metrics.
+
+mod error;
+mod memory;
+mod redis;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::{path::PathBuf, sync::Arc, time::Duration};
+
+use crate::utils::{
+	metrics::{record_window_store_error, record_window_store_operation},
+	RetryBudget,
+};
+
+pub use error::WindowStoreError;
+pub use memory::InMemoryWindowStore;
+pub use redis::RedisWindowStore;
+
+/// Default path of the snapshot of the in-memory store, in the data directory
+pub const DEFAULT_WINDOW_STORE_SNAPSHOT_PATH: &str = "window_store.json";
+
+/// Prefix of the keys written to Redis
+const REDIS_KEY_PREFIX: &str = "openzeppelin-monitor:window:";
+
+/// An entry of a window
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WindowEntry {
+	/// Position of the entry, a block number or a timestamp
+	pub position: u64,
+	/// Value of the entry, `1` to count occurrences
+	pub value: f64,
+}
+
+/// Storage of rolling windows of entries
+#[async_trait]
+pub trait WindowStore: Send + Sync {
+	/// Name of the backend, used as metrics label
+	fn backend(&self) -> &'static str;
+
+	/// Appends an entry to the window of a key
+	///
+	/// # Arguments
+	/// * `key` - The key of the window
+	/// * `entry` - The entry to append
+	/// * `ttl` - Time after which the key expires, reset by every write. `None` keeps it
+	///   until removed
+	async fn push(
+		&self,
+		key: &str,
+		entry: WindowEntry,
+		ttl: Option<Duration>,
+	) -> Result<(), WindowStoreError>;
+
+	/// Returns the entries of a key with a position in `from..=to`, sorted by position
+	async fn range(
+		&self,
+		key: &str,
+		from: u64,
+		to: u64,
+	) -> Result<Vec<WindowEntry>, WindowStoreError>;
+
+	/// Drops the entries of a key with a position before `before`
+	///
+	/// # Returns
+	/// The number of dropped entries
+	async fn trim(&self, key: &str, before: u64) -> Result<usize, WindowStoreError>;
+
+	/// Drops a key and its entries
+	async fn remove(&self, key: &str) -> Result<(), WindowStoreError>;
+
+	/// Atomically adds to the counter of a key, which never expires
+	///
+	/// Counters are kept under their own keys, distinct from the keys of the windows.
+	///
+	/// # Returns
+	/// The value of the counter, including the added amount
+	async fn increment(&self, key: &str, amount: u64) -> Result<u64, WindowStoreError>;
+
+	/// Returns the number of entries of a key with a position in `from..=to`
+	async fn count(&self, key: &str, from: u64, to: u64) -> Result<usize, WindowStoreError> {
+		Ok(self.range(key, from, to).await?.len())
+	}
+
+	/// Returns the sum of the values of a key with a position in `from..=to`
+	async fn sum(&self, key: &str, from: u64, to: u64) -> Result<f64, WindowStoreError> {
+		Ok(self
+			.range(key, from, to)
+			.await?
+			.iter()
+			.map(|entry| entry.value)
+			.sum())
+	}
+
+	/// Persists the buffered state of the store, if the backend buffers it
+	async fn persist(&self) -> Result<(), WindowStoreError> {
+		Ok(())
+	}
+
+	/// Persists the buffered state of the store without awaiting, failing rather than waiting
+	/// for a lock
+	///
+	/// Used by the panic hook, which cannot rely on the runtime nor on the locks held by the
+	/// panicking thread.
+	fn persist_blocking(&self) -> Result<(), WindowStoreError> {
+		Ok(())
+	}
+}
+
+/// Window store recording the operations of another store in the metrics
+pub struct MeteredWindowStore<S: WindowStore> {
+	inner: S,
+}
+
+impl<S: WindowStore> MeteredWindowStore<S> {
+	/// Wraps a store
+	pub fn new(inner: S) -> Self {
+		Self { inner }
+	}
+
+	#[allow(clippy::result_large_err)]
+	fn record<T>(
+		&self,
+		operation: &str,
+		result: Result<T, WindowStoreError>,
+	) -> Result<T, WindowStoreError> {
+		record_window_store_operation(self.inner.backend(), operation);
+		if result.is_err() {
+			record_window_store_error(self.inner.backend(), operation);
+		}
+		result
+	}
+}
+
+#[async_trait]
+impl<S: WindowStore> WindowStore for MeteredWindowStore<S> {
+	fn backend(&self) -> &'static str {
+		self.inner.backend()
+	}
+
+	async fn push(
+		&self,
+		key: &str,
+		entry: WindowEntry,
+		ttl: Option<Duration>,
+	) -> Result<(), WindowStoreError> {
+		self.record("push", self.inner.push(key, entry, ttl).await)
+	}
+
+	async fn range(
+		&self,
+		key: &str,
+		from: u64,
+		to: u64,
+	) -> Result<Vec<WindowEntry>, WindowStoreError> {
+		self.record("range", self.inner.range(key, from, to).await)
+	}
+
+	async fn trim(&self, key: &str, before: u64) -> Result<usize, WindowStoreError> {
+		self.record("trim", self.inner.trim(key, before).await)
+	}
+
+	async fn remove(&self, key: &str) -> Result<(), WindowStoreError> {
+		self.record("remove", self.inner.remove(key).await)
+	}
+
+	async fn increment(&self, key: &str, amount: u64) -> Result<u64, WindowStoreError> {
+		self.record("increment", self.inner.increment(key, amount).await)
+	}
+
+	async fn persist(&self) -> Result<(), WindowStoreError> {
+		self.record("persist", self.inner.persist().await)
+	}
+
+	fn persist_blocking(&self) -> Result<(), WindowStoreError> {
+		self.inner.persist_blocking()
+	}
+}
+
+/// Creates a window store
+///
+/// Uses Redis when a URL is given, falling back to memory if the URL is invalid, and memory
+/// snapshotted to a file otherwise.
+///
+/// # Arguments
+/// * `url` - URL of the Redis server, if any
+/// * `snapshot_path` - Snapshot of the in-memory store
+/// * `retry_budget` - Budget of the retries of the Redis commands
+pub fn create_window_store(
+	url: Option<&str>,
+	snapshot_path: PathBuf,
+	retry_budget: Arc<RetryBudget>,
+) -> Arc<dyn WindowStore> {
+	if let Some(url) = url {
+		match RedisWindowStore::from_url(url, REDIS_KEY_PREFIX) {
+			Ok(store) => {
+				return Arc::new(MeteredWindowStore::new(
+					store.with_retry_budget(retry_budget),
+				))
+			}
+			Err(e) => tracing::error!("Falling back to the in-memory window store: {}", e),
+		}
+	}
+	Arc::new(MeteredWindowStore::new(InMemoryWindowStore::with_snapshot(
+		snapshot_path,
+	)))
+}
+
+/// Periodically persists a window store
+///
+/// # Arguments
+/// * `store` - The window store
+/// * `interval` - Time between two snapshots
+pub fn start_window_store_snapshots(
+	store: Arc<dyn WindowStore>,
+	interval: Duration,
+) -> tokio::task::JoinHandle<()> {
+	tokio::spawn(async move {
+		let mut ticker = tokio::time::interval(interval);
+		ticker.tick().await;
+		loop {
+			ticker.tick().await;
+			if let Err(e) = store.persist().await {
+				tracing::error!("Failed to persist the window store: {}", e);
+			}
+		}
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::utils::metrics::WINDOW_STORE_OPERATIONS_TOTAL;
+
+	#[tokio::test]
+	async fn test_metered_window_store() {
+		let store = MeteredWindowStore::new(InMemoryWindowStore::new());
+		let before = WINDOW_STORE_OPERATIONS_TOTAL
+			.with_label_values(&["memory", "push"])
+			.get();
+
+		store
+			.push(
+				"metered",
+				WindowEntry {
+					position: 1,
+					value: 1.0,
+				},
+				None,
+			)
+			.await
+			.unwrap();
+
+		assert_eq!(store.count("metered", 0, 1).await.unwrap(), 1);
+		assert!(
+			WINDOW_STORE_OPERATIONS_TOTAL
+				.with_label_values(&["memory", "push"])
+				.get() > before
+		);
+	}
+}
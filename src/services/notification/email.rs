@@ -3,14 +3,12 @@
 //! Provides functionality to send formatted messages to email addresses
 //! via SMTP, supporting message templates with variable substitution.
 
-use backon::{BackoffBuilder, ExponentialBuilder, Retryable};
 use email_address::EmailAddress;
 use lettre::{
 	message::{
 		header::{self, ContentType},
 		Mailbox, Mailboxes,
 	},
-	transport::smtp::Error as SmtpError,
 	AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
 };
 use pulldown_cmark::{html, Options, Parser};
@@ -19,7 +17,7 @@ use std::{collections::HashMap, error::Error as StdError, sync::Arc};
 use crate::{
 	models::TriggerTypeConfig,
 	services::notification::{template_formatter, NotificationError},
-	utils::{JitterSetting, RetryConfig},
+	utils::{RetryBudget, RetryConfig, RetryPolicy},
 };
 
 /// Implementation of email notifications via SMTP
@@ -37,6 +35,8 @@ pub struct EmailNotifier<T: AsyncTransport + Send + Sync> {
 	recipients: Vec<EmailAddress>,
 	/// Retry policy for SMTP requests
 	retry_policy: RetryConfig,
+	/// Budget of the retries, shared with the other email notifiers of the client pool
+	retry_budget: Arc<RetryBudget>,
 }
 
 /// Configuration for SMTP connection
@@ -84,9 +84,19 @@ where
 			recipients: email_content.recipients,
 			client: Arc::new(transport),
 			retry_policy,
+			retry_budget: Arc::new(RetryBudget::new(0)),
 		}
 	}
 
+	/// Sets the budget of the retries of the SMTP requests
+	///
+	/// # Arguments
+	/// * `retry_budget` - The budget, shared with the other email notifiers
+	pub fn with_retry_budget(mut self, retry_budget: Arc<RetryBudget>) -> Self {
+		self.retry_budget = retry_budget;
+		self
+	}
+
 	/// Sends a formatted message to email
 	///
 	/// # Arguments
@@ -150,34 +160,9 @@ where
 			Ok(())
 		};
 
-		let backoff = ExponentialBuilder::default()
-			.with_min_delay(self.retry_policy.initial_backoff)
-			.with_max_delay(self.retry_policy.max_backoff);
-
-		let backoff_with_jitter = match self.retry_policy.jitter {
-			JitterSetting::Full => backoff.with_jitter(),
-			JitterSetting::None => backoff,
-		};
-
-		// Retry if the error is SmtpError and not permanent
-		let should_retry = |e: &NotificationError| -> bool {
-			if let NotificationError::NotifyFailed(context) = e {
-				if let Some(source) = context.source() {
-					if let Some(smtp_error) = source.downcast_ref::<SmtpError>() {
-						return !smtp_error.is_permanent();
-					}
-				}
-			}
-			true
-		};
-
-		operation
-			.retry(
-				backoff_with_jitter
-					.build()
-					.take(self.retry_policy.max_retries as usize),
-			)
-			.when(should_retry)
+		// Retry unless the error is classified as permanent (e.g. a permanent SMTP error)
+		RetryPolicy::with_budget("email", &self.retry_policy, self.retry_budget.clone())
+			.retry(operation)
 			.await
 	}
 }
@@ -203,6 +188,7 @@ impl EmailNotifier<AsyncSmtpTransport<Tokio1Executor>> {
 			recipients: email_content.recipients,
 			client: smtp_client,
 			retry_policy,
+			retry_budget: Arc::new(RetryBudget::new(0)),
 		})
 	}
 
@@ -278,9 +264,9 @@ mod tests {
 	use lettre::transport::{smtp::authentication::Credentials, stub::AsyncStubTransport};
 
 	use crate::{
-		models::{NotificationMessage, SecretString, SecretValue},
+		models::{ChainMessages, NotificationMessage, SecretString, SecretValue},
 		services::notification::pool::NotificationClientPool,
-		utils::RetryConfig,
+		utils::{HttpClientConfig, RetryConfig},
 	};
 
 	use super::*;
@@ -322,6 +308,7 @@ mod tests {
 			message: NotificationMessage {
 				title: "Test Subject".to_string(),
 				body: "Hello ${name}".to_string(),
+				chains: ChainMessages::default(),
 			},
 			sender: "sender@test.com".parse().unwrap(),
 			recipients: vec!["recipient@test.com".parse().unwrap()],
@@ -423,8 +410,10 @@ mod tests {
 			message: NotificationMessage {
 				title: "Test Slack".to_string(),
 				body: "Hello ${name}".to_string(),
+				chains: ChainMessages::default(),
 			},
 			retry_policy: RetryConfig::default(),
+			http_client: HttpClientConfig::default(),
 		};
 
 		// Correct config to create SmtpTransport
@@ -3,7 +3,9 @@
 //! Provides error types for notification-related operations,
 //! including network issues and configuration problems.
 
-use crate::utils::logging::error::{ErrorContext, TraceableError};
+use crate::utils::logging::error::{
+	classify_error_chain, ClassifiedError, ErrorClass, ErrorContext, TraceableError,
+};
 use std::collections::HashMap;
 use thiserror::Error as ThisError;
 
@@ -90,6 +92,19 @@ impl TraceableError for NotificationError {
 	}
 }
 
+impl ClassifiedError for NotificationError {
+	fn classification(&self) -> ErrorClass {
+		match self {
+			Self::NetworkError(ctx) | Self::NotifyFailed(ctx) => {
+				classify_error_chain(ctx.as_ref(), ErrorClass::Transient)
+			}
+			Self::ConfigError(_) | Self::InternalError(_) | Self::ExecutionError(_) => {
+				ErrorClass::Permanent
+			}
+		}
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -248,4 +263,19 @@ mod tests {
 			);
 		}
 	}
+
+	#[test]
+	fn test_classification() {
+		let error = NotificationError::config_error("test error", None, None);
+		assert_eq!(error.classification(), ErrorClass::Permanent);
+		assert!(!error.is_retryable());
+
+		let error = NotificationError::notify_failed("test error", None, None);
+		assert_eq!(error.classification(), ErrorClass::Transient);
+
+		let source_error = IoError::new(ErrorKind::PermissionDenied, "test source");
+		let error =
+			NotificationError::notify_failed("test error", Some(Box::new(source_error)), None);
+		assert_eq!(error.classification(), ErrorClass::Permanent);
+	}
 }
@@ -5,22 +5,24 @@
 
 use async_trait::async_trait;
 
-use std::{collections::HashMap, sync::Arc};
+use std::{borrow::Cow, collections::HashMap, sync::Arc};
 
 mod email;
 mod error;
 pub mod payload_builder;
 mod pool;
 mod script;
+mod template_check;
 mod template_formatter;
 mod webhook;
 
 use crate::{
 	models::{
-		MonitorMatch, NotificationMessage, ScriptLanguage, Trigger, TriggerType, TriggerTypeConfig,
-		WebhookPayloadMode,
+		BlockChainType, MonitorMatch, NotificationMessage, ScriptLanguage, Trigger, TriggerType,
+		TriggerTypeConfig, WebhookPayloadMode,
 	},
-	utils::{normalize_string, RetryConfig},
+	services::filter::normalize_match,
+	utils::{normalize_string, HttpClientConfig, RetryBudgets, RetryConfig},
 };
 
 pub use email::{EmailContent, EmailNotifier, SmtpConfig};
@@ -31,12 +33,14 @@ pub use payload_builder::{
 };
 pub use pool::NotificationClientPool;
 pub use script::ScriptNotifier;
+pub use template_check::{check_templates, fixture_variables, TemplateCheck, TemplateIssue};
 pub use webhook::{WebhookConfig, WebhookNotifier};
 
 /// A container for all components needed to configure and send a webhook notification.
 struct WebhookComponents {
 	config: WebhookConfig,
 	retry_policy: RetryConfig,
+	http_client: HttpClientConfig,
 	builder: Box<dyn WebhookPayloadBuilder>,
 }
 
@@ -54,8 +58,8 @@ type WebhookParts = (
 /// This abstracts away the specific details of each webhook provider.
 trait AsWebhookComponents {
 	/// Consolidates the logic for creating webhook components from a trigger config.
-	/// It returns the generic `WebhookConfig`, RetryConfig and the specific `WebhookPayloadBuilder`
-	/// needed for the given trigger type.
+	/// It returns the generic `WebhookConfig`, RetryConfig, HttpClientConfig and the specific
+	/// `WebhookPayloadBuilder` needed for the given trigger type.
 	fn as_webhook_components(&self) -> Result<WebhookComponents, NotificationError>;
 }
 
@@ -149,6 +153,7 @@ impl AsWebhookComponents for TriggerTypeConfig {
 		Ok(WebhookComponents {
 			config,
 			retry_policy,
+			http_client: self.get_http_client_config().unwrap_or_default(),
 			builder,
 		})
 	}
@@ -189,6 +194,16 @@ impl NotificationService {
 		}
 	}
 
+	/// Creates a new notification service whose retries are limited by budgets
+	///
+	/// # Arguments
+	/// * `retry_budgets` - The budgets shared by the notification clients, by component
+	pub fn with_retry_budgets(retry_budgets: RetryBudgets) -> Self {
+		NotificationService {
+			client_pool: Arc::new(NotificationClientPool::new().with_retry_budgets(retry_budgets)),
+		}
+	}
+
 	/// Executes a notification based on the trigger configuration
 	///
 	/// # Arguments
@@ -207,28 +222,71 @@ impl NotificationService {
 		monitor_match: &MonitorMatch,
 		trigger_scripts: &HashMap<String, (ScriptLanguage, String)>,
 	) -> Result<(), NotificationError> {
+		self.send(trigger, variables, Some(monitor_match), trigger_scripts)
+			.await
+	}
+
+	/// Sends a notification rendered from the message templates of a trigger, for events of
+	/// the service itself rather than monitor matches
+	///
+	/// # Arguments
+	/// * `trigger` - Trigger containing the notification type and parameters
+	/// * `variables` - Variables to substitute in message templates
+	///
+	/// # Returns
+	/// * `Result<(), NotificationError>` - Success or error, for script triggers and raw
+	///   webhook payloads which require a monitor match
+	pub async fn notify(
+		&self,
+		trigger: &Trigger,
+		variables: &HashMap<String, String>,
+	) -> Result<(), NotificationError> {
+		self.send(trigger, variables, None, &HashMap::new()).await
+	}
+
+	async fn send(
+		&self,
+		trigger: &Trigger,
+		variables: &HashMap<String, String>,
+		monitor_match: Option<&MonitorMatch>,
+		trigger_scripts: &HashMap<String, (ScriptLanguage, String)>,
+	) -> Result<(), NotificationError> {
+		let trigger = &*trigger_for_match(trigger, monitor_match);
+		let require_match = || {
+			monitor_match.ok_or_else(|| {
+				NotificationError::config_error(
+					format!(
+						"Trigger '{}' requires a monitor match to send a notification",
+						trigger.name
+					),
+					None,
+					None,
+				)
+			})
+		};
 		match &trigger.trigger_type {
 			// Match Webhook-based triggers
 			TriggerType::Slack
 			| TriggerType::Discord
 			| TriggerType::Webhook
 			| TriggerType::Telegram => {
-				// Check if this is a webhook trigger with raw payload mode
-				let is_raw_mode = matches!(
-					&trigger.config,
-					TriggerTypeConfig::Webhook {
-						payload_mode: WebhookPayloadMode::Raw,
-						..
-					}
-				);
+				// Webhook triggers can send the match itself instead of the rendered templates
+				let payload_mode = match &trigger.config {
+					TriggerTypeConfig::Webhook { payload_mode, .. } => payload_mode.clone(),
+					_ => WebhookPayloadMode::Template,
+				};
 
 				// Use the Webhookable trait to get config, retry policy and payload builder
 				let components = trigger.config.as_webhook_components()?;
 
-				// Get or create the HTTP client from the pool based on the retry policy
+				// Get or create the HTTP client from the pool based on the retry policy and the
+				// HTTP client settings of the trigger
 				let http_client = self
 					.client_pool
-					.get_or_create_http_client(&components.retry_policy)
+					.get_or_create_configured_http_client(
+						&components.retry_policy,
+						&components.http_client,
+					)
 					.await
 					.map_err(|e| {
 						NotificationError::execution_error(
@@ -239,22 +297,33 @@ impl NotificationService {
 					})?;
 
 				// Build the payload based on the mode
-				let payload = if is_raw_mode {
+				let payload = match payload_mode {
 					// In raw mode, serialize the MonitorMatch directly
-					serde_json::to_value(monitor_match).map_err(|e| {
-						NotificationError::internal_error(
-							format!("Failed to serialize MonitorMatch: {}", e),
-							Some(e.into()),
-							None,
-						)
-					})?
-				} else {
+					WebhookPayloadMode::Raw => {
+						serde_json::to_value(require_match()?).map_err(|e| {
+							NotificationError::internal_error(
+								format!("Failed to serialize MonitorMatch: {}", e),
+								Some(e.into()),
+								None,
+							)
+						})?
+					}
+					// In normalized mode, serialize the match in the versioned schema
+					WebhookPayloadMode::Normalized => {
+						serde_json::to_value(normalize_match(require_match()?)).map_err(|e| {
+							NotificationError::internal_error(
+								format!("Failed to serialize the normalized match: {}", e),
+								Some(e.into()),
+								None,
+							)
+						})?
+					}
 					// In template mode, use the payload builder
-					components.builder.build_payload(
+					WebhookPayloadMode::Template => components.builder.build_payload(
 						&components.config.title,
 						&components.config.body_template,
 						variables,
-					)
+					),
 				};
 
 				// Create the notifier
@@ -299,12 +368,14 @@ impl NotificationService {
 						)
 					})?;
 
-				let notifier = EmailNotifier::from_config(&trigger.config, smtp_client)?;
+				let notifier = EmailNotifier::from_config(&trigger.config, smtp_client)?
+					.with_retry_budget(self.client_pool.retry_budget("email"));
 				let message = EmailNotifier::format_message(notifier.body_template(), variables);
 				notifier.notify(&message).await?;
 			}
 			TriggerType::Script => {
 				let notifier = ScriptNotifier::from_config(&trigger.config)?;
+				let monitor_match = require_match()?;
 				let monitor_name = match monitor_match {
 					MonitorMatch::EVM(evm_match) => &evm_match.monitor.name,
 					MonitorMatch::Stellar(stellar_match) => &stellar_match.monitor.name,
@@ -350,6 +421,34 @@ impl NotificationService {
 	}
 }
 
+/// Returns the trigger with the message variant of the chain type of a match, if it has one
+fn trigger_for_match<'a>(
+	trigger: &'a Trigger,
+	monitor_match: Option<&MonitorMatch>,
+) -> Cow<'a, Trigger> {
+	let Some(monitor_match) = monitor_match else {
+		return Cow::Borrowed(trigger);
+	};
+	let chain = match monitor_match {
+		MonitorMatch::EVM(_) => BlockChainType::EVM,
+		MonitorMatch::Stellar(_) => BlockChainType::Stellar,
+		MonitorMatch::Midnight(_) => BlockChainType::Midnight,
+		MonitorMatch::Solana(_) => BlockChainType::Solana,
+	};
+	let has_variant = trigger
+		.config
+		.get_message()
+		.is_some_and(|message| message.chains.get(&chain).is_some());
+	if !has_variant {
+		return Cow::Borrowed(trigger);
+	}
+	let mut trigger = trigger.clone();
+	if let Some(message) = trigger.config.get_message_mut() {
+		*message = message.for_chain(&chain);
+	}
+	Cow::Owned(trigger)
+}
+
 impl Default for NotificationService {
 	fn default() -> Self {
 		Self::new()
@@ -361,7 +460,7 @@ mod tests {
 	use super::*;
 	use crate::{
 		models::{
-			AddressWithSpec, EVMMonitorMatch, EVMTransactionReceipt, EventCondition,
+			AddressWithSpec, ChainMessages, EVMMonitorMatch, EVMTransactionReceipt, EventCondition,
 			FunctionCondition, MatchConditions, Monitor, MonitorMatch, NotificationMessage,
 			ScriptLanguage, SecretString, SecretValue, SolanaMonitorMatch, TransactionCondition,
 			TriggerType,
@@ -447,7 +546,10 @@ mod tests {
 				events: vec![],
 				transactions: vec![],
 			},
+			matched_on_audit: Vec::new(),
 			matched_on_args: None,
+			block_timestamp: None,
+			trace: None,
 		}))
 	}
 
@@ -649,6 +751,73 @@ mod tests {
 		}
 	}
 
+	#[test]
+	fn test_trigger_for_match_uses_chain_variant() {
+		let trigger = TriggerBuilder::new()
+			.name("multichain")
+			.slack("https://hooks.slack.com/services/test")
+			.message("Alert", "${transaction.hash}")
+			.chain_message(BlockChainType::Solana, None, "${transaction.signature}")
+			.build();
+
+		let solana_match = create_mock_solana_monitor_match();
+		let resolved = trigger_for_match(&trigger, Some(&solana_match));
+		assert!(matches!(resolved, Cow::Owned(_)));
+		let message = resolved.config.get_message().unwrap();
+		assert_eq!(message.title, "Alert");
+		assert_eq!(message.body, "${transaction.signature}");
+		assert_eq!(message.chains, ChainMessages::default());
+
+		// Chain types without a variant and notifications without a match use the message
+		let evm_match = create_mock_evm_monitor_match();
+		let resolved = trigger_for_match(&trigger, Some(&evm_match));
+		assert!(matches!(resolved, Cow::Borrowed(_)));
+		assert_eq!(
+			resolved.config.get_message().unwrap().body,
+			"${transaction.hash}"
+		);
+		assert!(matches!(
+			trigger_for_match(&trigger, None),
+			Cow::Borrowed(_)
+		));
+	}
+
+	#[tokio::test]
+	async fn test_notify_without_match() {
+		let service = NotificationService::new();
+		let mut server = mockito::Server::new_async().await;
+		let mock = server
+			.mock("POST", "/")
+			.match_body(mockito::Matcher::Regex(
+				"missing on ethereum_mainnet".to_string(),
+			))
+			.with_status(200)
+			.create_async()
+			.await;
+
+		let trigger = TriggerBuilder::new()
+			.name("test_slack")
+			.slack(&server.url())
+			.message("Drift", "missing on ${drift.network}")
+			.build();
+		let variables =
+			HashMap::from([("drift.network".to_string(), "ethereum_mainnet".to_string())]);
+		service.notify(&trigger, &variables).await.unwrap();
+		mock.assert_async().await;
+
+		// Script triggers need the match passed to the script
+		let trigger = TriggerBuilder::new()
+			.name("test_script")
+			.script("script.py", ScriptLanguage::Python)
+			.build();
+		match service.notify(&trigger, &variables).await {
+			Err(NotificationError::ConfigError(ctx)) => {
+				assert!(ctx.message.contains("requires a monitor match"));
+			}
+			_ => panic!("Expected ConfigError"),
+		}
+	}
+
 	#[test]
 	fn as_webhook_components_trait_for_slack_config() {
 		let title = "Slack Title";
@@ -661,8 +830,10 @@ mod tests {
 			message: NotificationMessage {
 				title: title.to_string(),
 				body: message.to_string(),
+				chains: ChainMessages::default(),
 			},
 			retry_policy: RetryConfig::default(),
+			http_client: HttpClientConfig::default(),
 		};
 
 		let components = slack_config.as_webhook_components().unwrap();
@@ -699,8 +870,10 @@ mod tests {
 			message: NotificationMessage {
 				title: title.to_string(),
 				body: message.to_string(),
+				chains: ChainMessages::default(),
 			},
 			retry_policy: RetryConfig::default(),
+			http_client: HttpClientConfig::default(),
 		};
 
 		let components = discord_config.as_webhook_components().unwrap();
@@ -736,8 +909,10 @@ mod tests {
 			message: NotificationMessage {
 				title: title.to_string(),
 				body: message.to_string(),
+				chains: ChainMessages::default(),
 			},
 			retry_policy: RetryConfig::default(),
+			http_client: HttpClientConfig::default(),
 		};
 
 		let components = telegram_config.as_webhook_components().unwrap();
@@ -768,6 +943,7 @@ mod tests {
 			message: NotificationMessage {
 				title: title.to_string(),
 				body: body_template.to_string(),
+				chains: ChainMessages::default(),
 			},
 			method: Some("PUT".to_string()),
 			secret: Some(SecretValue::Plain(SecretString::new(
@@ -776,6 +952,7 @@ mod tests {
 			headers: Some([("X-Custom".to_string(), "Value".to_string())].into()),
 			payload_mode: WebhookPayloadMode::default(),
 			retry_policy: RetryConfig::default(),
+			http_client: HttpClientConfig::default(),
 		};
 
 		let components = webhook_config.as_webhook_components().unwrap();
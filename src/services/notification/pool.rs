@@ -1,7 +1,10 @@
 use crate::services::blockchain::TransientErrorRetryStrategy;
 use crate::services::notification::SmtpConfig;
 use crate::utils::client_storage::ClientStorage;
-use crate::utils::{create_retryable_http_client, RetryConfig};
+use crate::utils::{
+	create_retryable_http_client, HttpClientConfig, RetryBudget, RetryBudgets, RetryConfig,
+	RetryPolicy,
+};
 use lettre::Tokio1Executor;
 use lettre::{transport::smtp::authentication::Credentials, AsyncSmtpTransport};
 use reqwest::Client as ReqwestClient;
@@ -24,9 +27,11 @@ pub enum NotificationPoolError {
 /// Provides a thread-safe way to access and create HTTP and SMTP clients
 /// for sending notifications. It uses a `ClientStorage` to hold the clients,
 /// allowing for efficient reuse and management of HTTP and SMTP connections.
+/// The retries of its clients share the budgets of the pool, by component.
 pub struct NotificationClientPool {
 	http_clients: ClientStorage<ClientWithMiddleware>,
 	smtp_clients: ClientStorage<AsyncSmtpTransport<Tokio1Executor>>,
+	retry_budgets: RetryBudgets,
 }
 
 impl NotificationClientPool {
@@ -34,9 +39,27 @@ impl NotificationClientPool {
 		Self {
 			http_clients: ClientStorage::new(),
 			smtp_clients: ClientStorage::new(),
+			retry_budgets: RetryBudgets::default(),
 		}
 	}
 
+	/// Sets the retry budgets shared by the clients of the pool
+	///
+	/// # Arguments
+	/// * `retry_budgets` - The budgets, by component
+	pub fn with_retry_budgets(mut self, retry_budgets: RetryBudgets) -> Self {
+		self.retry_budgets = retry_budgets;
+		self
+	}
+
+	/// Returns the retry budget shared by the clients of a component
+	///
+	/// # Arguments
+	/// * `component` - Name of the component (e.g. `email`)
+	pub fn retry_budget(&self, component: &str) -> Arc<RetryBudget> {
+		self.retry_budgets.get(component, None)
+	}
+
 	/// A private, generic method to handle the core logic of getting or creating a client.
 	async fn get_or_create_client<T, F>(
 		&self,
@@ -80,17 +103,39 @@ impl NotificationClientPool {
 		&self,
 		retry_policy: &RetryConfig,
 	) -> Result<Arc<ClientWithMiddleware>, NotificationPoolError> {
-		let key = format!("{:?}", retry_policy);
+		self.get_or_create_configured_http_client(retry_policy, &HttpClientConfig::default())
+			.await
+	}
+
+	/// Get or create an HTTP client with retry capabilities and custom client settings.
+	///
+	/// Clients are shared by the triggers with the same retry policy and settings.
+	///
+	/// # Arguments
+	/// * `retry_policy` - Configuration for HTTP retry policy
+	/// * `http_client` - Timeouts, TLS and proxy settings of the client
+	/// # Returns
+	/// * `Result<Arc<ClientWithMiddleware>, NotificationPoolError>` - The HTTP client
+	///   wrapped in an `Arc` for shared ownership, or an error if client creation
+	///   fails.
+	pub async fn get_or_create_configured_http_client(
+		&self,
+		retry_policy: &RetryConfig,
+		http_client: &HttpClientConfig,
+	) -> Result<Arc<ClientWithMiddleware>, NotificationPoolError> {
+		let key = format!("{:?}|{:?}", retry_policy, http_client);
 		self.get_or_create_client(&key, &self.http_clients, || {
-			let base_client = ReqwestClient::builder()
+			let builder = ReqwestClient::builder()
 				.pool_max_idle_per_host(10)
-				.pool_idle_timeout(Some(Duration::from_secs(90)))
-				.connect_timeout(Duration::from_secs(10))
+				.pool_idle_timeout(Some(Duration::from_secs(90)));
+			let base_client = http_client
+				.apply(builder)
+				.map_err(|e| NotificationPoolError::HttpClientBuildError(e.to_string()))?
 				.build()
 				.map_err(|e| NotificationPoolError::HttpClientBuildError(e.to_string()))?;
 
 			Ok(create_retryable_http_client(
-				retry_policy,
+				RetryPolicy::shared("webhook", retry_policy, &self.retry_budgets),
 				base_client,
 				Some(TransientErrorRetryStrategy),
 			))
@@ -310,6 +355,62 @@ mod tests {
 		);
 	}
 
+	#[tokio::test]
+	async fn test_pool_returns_different_http_clients_for_different_client_settings() {
+		let pool = create_pool();
+		let retry_config = RetryConfig::default();
+		let slow_endpoint = HttpClientConfig {
+			timeout_ms: Some(60_000),
+			connect_timeout_ms: Some(30_000),
+			..Default::default()
+		};
+
+		let default_client = pool.get_or_create_http_client(&retry_config).await.unwrap();
+		let slow_client = pool
+			.get_or_create_configured_http_client(&retry_config, &slow_endpoint)
+			.await
+			.unwrap();
+		let default_client_again = pool
+			.get_or_create_configured_http_client(&retry_config, &HttpClientConfig::default())
+			.await
+			.unwrap();
+
+		assert!(!Arc::ptr_eq(&default_client, &slow_client));
+		assert!(Arc::ptr_eq(&default_client, &default_client_again));
+		assert_eq!(pool.get_active_http_client_count().await, 2);
+	}
+
+	#[tokio::test]
+	async fn test_pool_rejects_invalid_http_client_settings() {
+		let pool = create_pool();
+		let retry_config = RetryConfig::default();
+
+		let missing_ca = HttpClientConfig {
+			ca_cert_path: Some("/nonexistent/ca.pem".to_string()),
+			..Default::default()
+		};
+		let result = pool
+			.get_or_create_configured_http_client(&retry_config, &missing_ca)
+			.await;
+		assert!(matches!(
+			result,
+			Err(NotificationPoolError::HttpClientBuildError(_))
+		));
+
+		let invalid_proxy = HttpClientConfig {
+			proxy: Some("not a url".to_string()),
+			..Default::default()
+		};
+		let result = pool
+			.get_or_create_configured_http_client(&retry_config, &invalid_proxy)
+			.await;
+		assert!(matches!(
+			result,
+			Err(NotificationPoolError::HttpClientBuildError(_))
+		));
+		assert_eq!(pool.get_active_http_client_count().await, 0);
+	}
+
 	#[tokio::test]
 	async fn test_pool_returns_different_smtp_clients_for_different_configs() {
 		let pool = create_pool();
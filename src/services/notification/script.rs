@@ -86,8 +86,8 @@ mod tests {
 	use super::*;
 	use crate::{
 		models::{
-			EVMMonitorMatch, EVMTransactionReceipt, MatchConditions, Monitor, MonitorMatch,
-			NotificationMessage, SecretString, SecretValue, TriggerType,
+			ChainMessages, EVMMonitorMatch, EVMTransactionReceipt, MatchConditions, Monitor,
+			MonitorMatch, NotificationMessage, SecretString, SecretValue, TriggerType,
 		},
 		services::notification::NotificationService,
 		utils::tests::{
@@ -128,7 +128,10 @@ mod tests {
 			logs: Some(vec![]),
 			network_slug: "ethereum_mainnet".to_string(),
 			matched_on: MatchConditions::default(),
+			matched_on_audit: Vec::new(),
 			matched_on_args: None,
+			block_timestamp: None,
+			trace: None,
 		}))
 	}
 
@@ -147,8 +150,10 @@ mod tests {
 			message: NotificationMessage {
 				title: "Test Slack".to_string(),
 				body: "This is a test message".to_string(),
+				chains: ChainMessages::default(),
 			},
 			retry_policy: Default::default(),
+			http_client: Default::default(),
 		};
 
 		let notifier = ScriptNotifier::from_config(&config);
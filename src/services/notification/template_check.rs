@@ -0,0 +1,789 @@
+//! Offline validation of notification templates.
+//!
+//! Every message template of the configured triggers is rendered against fixture variable maps
+//! shaped like the variables of a real match on each chain type. Malformed placeholders and
+//! variables that a match of the chain never provides are reported, so that broken templates
+//! are caught before deploy instead of producing notifications with raw `${...}` text.
+//!
+//! The `enrichment.*` variables output by post-processing scripts are only known at runtime, and
+//! are accepted in the templates of the triggers of monitors with a post-processing script.
+
+use std::{
+	collections::{BTreeSet, HashMap},
+	fmt,
+};
+
+use serde_json::json;
+
+use super::template_formatter::format_template;
+use crate::{
+	models::{
+		BlockChainType, Monitor, Network, NotificationMessage, Trigger, TriggerTypeConfig,
+		WebhookPayloadMode,
+	},
+	services::filter::json_to_hashmap,
+	utils::monitor::abi_drift::DRIFT_VARIABLES,
+};
+
+/// Chain types checked when a trigger is not used by any active monitor
+const ALL_CHAINS: [BlockChainType; 4] = [
+	BlockChainType::EVM,
+	BlockChainType::Stellar,
+	BlockChainType::Midnight,
+	BlockChainType::Solana,
+];
+
+/// Variables whose segments depend on the matched data. `#` matches an index, `?` matches any
+/// single segment and `*` matches one or more trailing segments.
+const DYNAMIC_VARIABLES: &[&str] = &[
+	"functions.#.signature",
+	"functions.#.args.*",
+	"events.#.signature",
+	"events.#.args.*",
+	"batch.transactions.#",
+];
+
+/// EVM only variables whose segments depend on the matched data
+const EVM_DYNAMIC_VARIABLES: &[&str] = &[
+	"funds_flow.?.?",
+	"audit.#.kind",
+	"audit.#.signature",
+	"audit.#.expression",
+	"audit.#.address",
+	"audit.#.operands.#.variable",
+	"audit.#.operands.#.value",
+	"audit.#.operands.#.operator",
+	"audit.#.operands.#.literal",
+	"audit.#.operands.#.result",
+];
+
+/// A problem found in a template
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TemplateIssue {
+	/// A `${` that is never closed
+	Unclosed {
+		/// Byte offset of the placeholder in the template
+		offset: usize,
+	},
+	/// A `${}` placeholder
+	Empty {
+		/// Byte offset of the placeholder in the template
+		offset: usize,
+	},
+	/// A placeholder whose name contains characters a variable never has
+	InvalidName {
+		/// Name of the placeholder
+		name: String,
+	},
+	/// A variable that a match on the chain never provides
+	UnknownVariable {
+		/// Name of the variable
+		name: String,
+	},
+}
+
+impl fmt::Display for TemplateIssue {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::Unclosed { offset } => write!(f, "unclosed placeholder at offset {}", offset),
+			Self::Empty { offset } => write!(f, "empty placeholder at offset {}", offset),
+			Self::InvalidName { name } => write!(f, "invalid placeholder '${{{}}}'", name),
+			Self::UnknownVariable { name } => write!(f, "unknown variable '${{{}}}'", name),
+		}
+	}
+}
+
+/// Outcome of rendering a template of a trigger with the fixture of a chain type
+#[derive(Debug, Clone, PartialEq)]
+pub struct TemplateCheck {
+	/// Name of the trigger
+	pub trigger: String,
+	/// Template field (`message.title`, `message.body`, or `message.chains.<chain>.title` and
+	/// `.body` for the variant of a chain type)
+	pub field: &'static str,
+	/// Chain type of the fixture, or `None` when the trigger is not used by any active
+	/// monitor and the variables of any chain type are accepted
+	pub chain: Option<BlockChainType>,
+	/// Whether the template is rendered as the notification of an ABI drift, whose
+	/// variables are not those of a match
+	pub abi_drift: bool,
+	/// Template rendered with the fixture variables
+	pub rendered: String,
+	/// Problems found in the template
+	pub issues: Vec<TemplateIssue>,
+}
+
+impl TemplateCheck {
+	/// Returns true if no problem was found in the template
+	pub fn passed(&self) -> bool {
+		self.issues.is_empty()
+	}
+}
+
+/// Returns the variables of a sample match on a chain type, shaped like the variables passed
+/// to the triggers by the filter service
+///
+/// # Arguments
+/// * `chain` - The chain type of the match
+///
+/// # Returns
+/// * `HashMap<String, String>` - The flattened fixture variables
+pub fn fixture_variables(chain: &BlockChainType) -> HashMap<String, String> {
+	let mut data = json!({
+		"monitor": {
+			"name": "Fixture Monitor",
+			"severity": "high",
+			"stats": {
+				"matches_1h": 3,
+				"matches_24h": 40,
+				"matches_7d": 250,
+				"matches_total": 1200,
+			},
+		},
+		"sampling": {"count": 100, "total": 1000},
+		"latency": {"receipt_to_detection_ms": 150, "detection_to_dispatch_ms": 20},
+		"owners": {
+			"name": "Fixture Team",
+			"mention": "@fixture-team",
+			"address": "0x0000000000000000000000000000000000000001",
+		},
+		"batch": {
+			"count": 2,
+			"matches": 1,
+			"aggregate": {"min": "1000", "max": "1000", "sum": "2000"},
+		},
+		"functions": [{
+			"signature": "transfer(address,uint256)",
+			"args": {"to": "0x0000000000000000000000000000000000000002", "amount": "1000"}
+		}],
+		"events": [{
+			"signature": "Transfer(address,address,uint256)",
+			"args": {
+				"from": "0x0000000000000000000000000000000000000001",
+				"to": "0x0000000000000000000000000000000000000002",
+				"value": "1000"
+			}
+		}]
+	});
+	match chain {
+		BlockChainType::EVM => {
+			data["transaction"] = json!({
+				"hash": format!("0x{}", "ab".repeat(32)),
+				"from": "0x0000000000000000000000000000000000000001",
+				"to": "0x0000000000000000000000000000000000000002",
+				"value": "1000",
+				"fee": "21000000000000",
+			});
+			data["block"] = json!({"timestamp": 1750000000, "age": 12});
+			data["trace"] = json!({"status": "traced"});
+			data["funds_flow"] = json!({
+				"0x0000000000000000000000000000000000000001": {"native": "-1000"},
+				"0x0000000000000000000000000000000000000002": {"native": "1000"},
+			});
+			data["audit"] = json!([{
+				"kind": "event",
+				"signature": "Transfer(address,address,uint256)",
+				"expression": "value > 100",
+				"address": "0x0000000000000000000000000000000000000003",
+				"operands": [{
+					"variable": "value",
+					"value": "1000",
+					"operator": ">",
+					"literal": "100",
+					"result": true
+				}]
+			}]);
+		}
+		BlockChainType::Stellar => {
+			data["transaction"] = json!({"hash": "ab".repeat(32)});
+			data["data"] = json!({"complete": false, "missing": "events"});
+		}
+		BlockChainType::Midnight => {
+			data["transaction"] = json!({"hash": "ab".repeat(32)});
+		}
+		BlockChainType::Solana => {
+			data["transaction"] = json!({"signature": "1".repeat(88)});
+		}
+	}
+	// Midnight blocks have no timestamp to measure the latency from
+	if *chain != BlockChainType::Midnight {
+		data["latency"]["block_to_receipt_ms"] = json!(2400);
+		data["latency"]["end_to_end_ms"] = json!(2570);
+	}
+	json_to_hashmap(&data)
+}
+
+/// Renders the message templates of the triggers with the fixtures of the chain types they
+/// are used on, and reports the problems found
+///
+/// # Arguments
+/// * `triggers` - The configured triggers, keyed by name
+/// * `monitors` - The active monitors
+/// * `networks` - The configured networks, keyed by slug
+///
+/// # Returns
+/// * `Vec<TemplateCheck>` - The outcome of each template, ordered by trigger name
+pub fn check_templates(
+	triggers: &HashMap<String, Trigger>,
+	monitors: &[Monitor],
+	networks: &HashMap<String, Network>,
+) -> Vec<TemplateCheck> {
+	let mut names: Vec<&String> = triggers.keys().collect();
+	names.sort();
+
+	let mut checks = Vec::new();
+	for name in names {
+		let trigger = &triggers[name];
+		let Some(message) = message_of(&trigger.config) else {
+			continue;
+		};
+		let first_check = checks.len();
+		// Parameters are rendered with their defaults, monitors may only set declared ones
+		let params: HashMap<String, String> = trigger
+			.parameters
+			.iter()
+			.map(|(param, value)| (format!("params.{}", param), value.clone()))
+			.collect();
+
+		// Chain types of the networks of the monitors notifying the trigger
+		let mut chains: Vec<BlockChainType> = Vec::new();
+		for monitor in monitors
+			.iter()
+			.filter(|m| m.routed_triggers().any(|trigger| trigger == name))
+		{
+			for network in monitor
+				.networks
+				.iter()
+				.filter_map(|slug| networks.get(slug))
+			{
+				if !chains.contains(&network.network_type) {
+					chains.push(network.network_type.clone());
+				}
+			}
+		}
+		chains.sort_by_key(|chain| ALL_CHAINS.iter().position(|c| c == chain));
+		let enriched = monitors.iter().any(|monitor| {
+			monitor.post_processing.is_some()
+				&& monitor.routed_triggers().any(|trigger| trigger == name)
+		});
+		let abi_drift = monitors.iter().any(|monitor| {
+			monitor
+				.abi_drift
+				.as_ref()
+				.is_some_and(|drift| drift.triggers.contains(name))
+		});
+
+		for (field, template) in [
+			("message.title", &message.title),
+			("message.body", &message.body),
+		] {
+			if chains.is_empty() && !abi_drift {
+				checks.push(check_template(name, field, template, None, &params));
+			}
+			// Matches of chain types with a variant are not rendered with the default message
+			for chain in chains
+				.iter()
+				.filter(|chain| message.chains.get(chain).is_none())
+			{
+				checks.push(check_template(name, field, template, Some(chain), &params));
+			}
+			if abi_drift {
+				checks.push(check_drift_template(name, field, template, &params));
+			}
+		}
+
+		// Chain-specific variants are checked against the fixture of their chain type
+		for chain in ALL_CHAINS
+			.iter()
+			.filter(|chain| message.chains.get(chain).is_some())
+		{
+			let variant = message.for_chain(chain);
+			let (title_field, body_field) = variant_fields(chain);
+			checks.push(check_template(
+				name,
+				title_field,
+				&variant.title,
+				Some(chain),
+				&params,
+			));
+			checks.push(check_template(
+				name,
+				body_field,
+				&variant.body,
+				Some(chain),
+				&params,
+			));
+		}
+
+		if enriched {
+			for check in checks[first_check..]
+				.iter_mut()
+				.filter(|check| !check.abi_drift)
+			{
+				check.issues.retain(|issue| {
+					!matches!(issue, TemplateIssue::UnknownVariable { name } if name.starts_with("enrichment."))
+				});
+			}
+		}
+	}
+	checks
+}
+
+/// Returns the message of a trigger, if its notifications are rendered from templates
+fn message_of(config: &TriggerTypeConfig) -> Option<&NotificationMessage> {
+	match config {
+		TriggerTypeConfig::Webhook {
+			payload_mode: WebhookPayloadMode::Raw | WebhookPayloadMode::Normalized,
+			..
+		} => None,
+		_ => config.get_message(),
+	}
+}
+
+/// Returns the template fields of the message variant of a chain type
+fn variant_fields(chain: &BlockChainType) -> (&'static str, &'static str) {
+	match chain {
+		BlockChainType::EVM => ("message.chains.evm.title", "message.chains.evm.body"),
+		BlockChainType::Stellar => (
+			"message.chains.stellar.title",
+			"message.chains.stellar.body",
+		),
+		BlockChainType::Midnight => (
+			"message.chains.midnight.title",
+			"message.chains.midnight.body",
+		),
+		BlockChainType::Solana => ("message.chains.solana.title", "message.chains.solana.body"),
+	}
+}
+
+/// Checks a template against the fixture of a chain type, or of all chain types if `None`,
+/// extended with the parameter variables of the trigger
+fn check_template(
+	trigger: &str,
+	field: &'static str,
+	template: &str,
+	chain: Option<&BlockChainType>,
+	params: &HashMap<String, String>,
+) -> TemplateCheck {
+	let chains: Vec<&BlockChainType> = match chain {
+		Some(chain) => vec![chain],
+		None => ALL_CHAINS.iter().collect(),
+	};
+	let fixtures: Vec<HashMap<String, String>> = chains
+		.iter()
+		.map(|chain| {
+			let mut fixture = fixture_variables(chain);
+			fixture.extend(params.clone());
+			fixture
+		})
+		.collect();
+
+	let (names, mut issues) = parse_placeholders(template);
+	for name in &names {
+		let known = chains
+			.iter()
+			.zip(&fixtures)
+			.any(|(chain, fixture)| is_known_variable(name, chain, fixture));
+		if !known {
+			issues.push(TemplateIssue::UnknownVariable { name: name.clone() });
+		}
+	}
+
+	TemplateCheck {
+		trigger: trigger.to_string(),
+		field,
+		chain: chain.cloned(),
+		abi_drift: false,
+		rendered: format_template(template, &fixtures[0]),
+		issues,
+	}
+}
+
+/// Checks a template against the variables of the notification of an ABI drift, extended with
+/// the parameter variables of the trigger
+fn check_drift_template(
+	trigger: &str,
+	field: &'static str,
+	template: &str,
+	params: &HashMap<String, String>,
+) -> TemplateCheck {
+	let mut fixture: HashMap<String, String> = [
+		("monitor.name", "Fixture Monitor"),
+		("drift.network", "ethereum_mainnet"),
+		(
+			"drift.address",
+			"0x0000000000000000000000000000000000000001",
+		),
+		("drift.missing", "approve(address,uint256)"),
+		(
+			"drift.changed",
+			"transfer(address,uint256) -> transfer(address,uint128)",
+		),
+	]
+	.into_iter()
+	.map(|(name, value)| (name.to_string(), value.to_string()))
+	.collect();
+	fixture.extend(params.clone());
+
+	let (names, mut issues) = parse_placeholders(template);
+	issues.extend(
+		names
+			.into_iter()
+			.filter(|name| !DRIFT_VARIABLES.contains(&name.as_str()) && !params.contains_key(name))
+			.map(|name| TemplateIssue::UnknownVariable { name }),
+	);
+
+	TemplateCheck {
+		trigger: trigger.to_string(),
+		field,
+		chain: None,
+		abi_drift: true,
+		rendered: format_template(template, &fixture),
+		issues,
+	}
+}
+
+/// Extracts the variable names of the placeholders of a template, and the malformed ones
+fn parse_placeholders(template: &str) -> (BTreeSet<String>, Vec<TemplateIssue>) {
+	let mut names = BTreeSet::new();
+	let mut issues = Vec::new();
+	let mut rest = template;
+	let mut consumed = 0;
+	while let Some(start) = rest.find("${") {
+		let offset = consumed + start;
+		let after = &rest[start + 2..];
+		let Some(end) = after.find('}') else {
+			issues.push(TemplateIssue::Unclosed { offset });
+			break;
+		};
+		let name = &after[..end];
+		if name.is_empty() {
+			issues.push(TemplateIssue::Empty { offset });
+		} else if !name
+			.chars()
+			.all(|c| c.is_alphanumeric() || matches!(c, '_' | '.' | '-'))
+			|| name.starts_with('.')
+			|| name.ends_with('.')
+			|| name.contains("..")
+		{
+			issues.push(TemplateIssue::InvalidName {
+				name: name.to_string(),
+			});
+		} else {
+			names.insert(name.to_string());
+		}
+		consumed = offset + 2 + end + 1;
+		rest = &after[end + 1..];
+	}
+	(names, issues)
+}
+
+/// Returns true if a match on the chain type can provide the variable
+fn is_known_variable(
+	name: &str,
+	chain: &BlockChainType,
+	fixture: &HashMap<String, String>,
+) -> bool {
+	if name == "events" || name == "functions" || fixture.contains_key(name) {
+		return true;
+	}
+	let evm_patterns: &[&str] = match chain {
+		BlockChainType::EVM => EVM_DYNAMIC_VARIABLES,
+		_ => &[],
+	};
+	DYNAMIC_VARIABLES
+		.iter()
+		.chain(evm_patterns)
+		.any(|pattern| matches_pattern(name, pattern))
+}
+
+/// Matches the segments of a variable against a pattern of `DYNAMIC_VARIABLES`
+fn matches_pattern(name: &str, pattern: &str) -> bool {
+	let segments: Vec<&str> = name.split('.').collect();
+	let pattern: Vec<&str> = pattern.split('.').collect();
+	for (i, expected) in pattern.iter().enumerate() {
+		let Some(segment) = segments.get(i) else {
+			return false;
+		};
+		match *expected {
+			"*" => return true,
+			"?" => {}
+			"#" if segment.parse::<usize>().is_ok() => {}
+			"#" => return false,
+			literal if literal == *segment => {}
+			_ => return false,
+		}
+	}
+	segments.len() == pattern.len()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		models::ScriptLanguage,
+		utils::tests::builders::{
+			evm::monitor::MonitorBuilder, network::NetworkBuilder, trigger::TriggerBuilder,
+		},
+	};
+
+	fn slack_trigger(name: &str, title: &str, body: &str) -> Trigger {
+		TriggerBuilder::new()
+			.name(name)
+			.slack("https://hooks.slack.com/services/test")
+			.message(title, body)
+			.build()
+	}
+
+	#[test]
+	fn test_parse_placeholders_reports_malformed() {
+		let (names, issues) =
+			parse_placeholders("${monitor.name} ${} ${bad name} ${events.0.args.to} ${open");
+		assert_eq!(
+			names.into_iter().collect::<Vec<_>>(),
+			vec!["events.0.args.to", "monitor.name"]
+		);
+		assert_eq!(
+			issues,
+			vec![
+				TemplateIssue::Empty { offset: 16 },
+				TemplateIssue::InvalidName {
+					name: "bad name".to_string()
+				},
+				TemplateIssue::Unclosed { offset: 52 },
+			]
+		);
+	}
+
+	#[test]
+	fn test_check_templates_per_chain() {
+		let triggers = HashMap::from([
+			(
+				"evm_slack".to_string(),
+				slack_trigger(
+					"evm_slack",
+					"${monitor.name} (${monitor.stats.matches_24h} in 24h)",
+					"${transaction.from} ${events.0.args.value} ${audit.0.operands.0.value} \
+					 ${funds_flow.0xabc.native} ${transaction.sender} ${events}",
+				),
+			),
+			(
+				"unused".to_string(),
+				slack_trigger("unused", "${transaction.signature}", "${nope}"),
+			),
+		]);
+		let network = NetworkBuilder::new()
+			.slug("ethereum_mainnet")
+			.network_type(BlockChainType::EVM)
+			.build();
+		let networks = HashMap::from([("ethereum_mainnet".to_string(), network)]);
+		let monitors = vec![MonitorBuilder::new()
+			.networks(vec!["ethereum_mainnet".to_string()])
+			.triggers(vec!["evm_slack".to_string()])
+			.build()];
+
+		let checks = check_templates(&triggers, &monitors, &networks);
+		assert_eq!(checks.len(), 4);
+
+		let body = &checks[1];
+		assert_eq!(body.trigger, "evm_slack");
+		assert_eq!(body.chain, Some(BlockChainType::EVM));
+		assert_eq!(
+			body.issues,
+			vec![TemplateIssue::UnknownVariable {
+				name: "transaction.sender".to_string()
+			}]
+		);
+		assert!(body
+			.rendered
+			.starts_with("0x0000000000000000000000000000000000000001 1000 1000 ${funds_flow"));
+		assert!(body.rendered.contains("*Matched Events:*"));
+
+		// Triggers of ABI drifts are checked against the variables of the drift
+		let monitors = vec![MonitorBuilder::new()
+			.networks(vec![])
+			.abi_drift(3600, vec!["unused".to_string()])
+			.build()];
+		let checks = check_templates(&triggers, &monitors, &networks);
+		assert_eq!(checks.len(), 4);
+		assert!(checks[2].abi_drift);
+		assert_eq!(
+			checks[2].issues,
+			vec![TemplateIssue::UnknownVariable {
+				name: "transaction.signature".to_string()
+			}]
+		);
+		let checks = check_templates(&triggers, &[], &networks);
+
+		// Unused triggers accept the variables of any chain type
+		assert!(checks[2].passed());
+		assert_eq!(checks[2].chain, None);
+		assert_eq!(
+			checks[3].issues,
+			vec![TemplateIssue::UnknownVariable {
+				name: "nope".to_string()
+			}]
+		);
+	}
+
+	#[test]
+	fn test_check_templates_chain_variants() {
+		let trigger = TriggerBuilder::new()
+			.name("multichain")
+			.slack("https://hooks.slack.com/services/test")
+			.message("${monitor.name}", "${transaction.hash}")
+			.chain_message(
+				BlockChainType::Solana,
+				Some("Solana: ${monitor.name}"),
+				"${transaction.signature} ${transaction.hash}",
+			)
+			.build();
+		let triggers = HashMap::from([("multichain".to_string(), trigger)]);
+		let networks = HashMap::from([
+			(
+				"ethereum_mainnet".to_string(),
+				NetworkBuilder::new()
+					.slug("ethereum_mainnet")
+					.network_type(BlockChainType::EVM)
+					.build(),
+			),
+			(
+				"solana_mainnet".to_string(),
+				NetworkBuilder::new()
+					.slug("solana_mainnet")
+					.network_type(BlockChainType::Solana)
+					.build(),
+			),
+		]);
+		let monitors = vec![MonitorBuilder::new()
+			.networks(vec![
+				"ethereum_mainnet".to_string(),
+				"solana_mainnet".to_string(),
+			])
+			.triggers(vec!["multichain".to_string()])
+			.build()];
+
+		// The default message is only checked for EVM, the variant for Solana
+		let checks = check_templates(&triggers, &monitors, &networks);
+		let fields: Vec<(&str, Option<BlockChainType>)> = checks
+			.iter()
+			.map(|check| (check.field, check.chain.clone()))
+			.collect();
+		assert_eq!(
+			fields,
+			vec![
+				("message.title", Some(BlockChainType::EVM)),
+				("message.body", Some(BlockChainType::EVM)),
+				("message.chains.solana.title", Some(BlockChainType::Solana)),
+				("message.chains.solana.body", Some(BlockChainType::Solana)),
+			]
+		);
+		assert!(checks[..3].iter().all(|check| check.passed()));
+		assert_eq!(checks[2].rendered, "Solana: Fixture Monitor");
+		assert_eq!(
+			checks[3].issues,
+			vec![TemplateIssue::UnknownVariable {
+				name: "transaction.hash".to_string()
+			}]
+		);
+	}
+
+	#[test]
+	fn test_chain_specific_variables() {
+		let stellar = check_template(
+			"t",
+			"message.body",
+			"${transaction.hash} ${transaction.from} ${transaction.fee} ${audit.0.kind}",
+			Some(&BlockChainType::Stellar),
+			&HashMap::new(),
+		);
+		assert_eq!(
+			stellar.issues,
+			vec![
+				TemplateIssue::UnknownVariable {
+					name: "audit.0.kind".to_string()
+				},
+				TemplateIssue::UnknownVariable {
+					name: "transaction.fee".to_string()
+				},
+				TemplateIssue::UnknownVariable {
+					name: "transaction.from".to_string()
+				},
+			]
+		);
+		let stellar = check_template(
+			"t",
+			"message.body",
+			"${data.complete} ${data.missing}",
+			Some(&BlockChainType::Stellar),
+			&HashMap::new(),
+		);
+		assert!(stellar.passed());
+		let evm = check_template(
+			"t",
+			"message.body",
+			"${transaction.fee} ${block.timestamp} ${block.age}",
+			Some(&BlockChainType::EVM),
+			&HashMap::new(),
+		);
+		assert!(evm.passed());
+		let solana = check_template(
+			"t",
+			"message.body",
+			"${transaction.signature}",
+			Some(&BlockChainType::Solana),
+			&HashMap::new(),
+		);
+		assert!(solana.passed());
+		assert_eq!(solana.rendered, "1".repeat(88));
+	}
+
+	#[test]
+	fn test_check_templates_parameters() {
+		let trigger = TriggerBuilder::new()
+			.name("team_slack")
+			.slack("https://hooks.slack.com/services/test")
+			.message("${params.team}", "${params.mention} ${params.unknown}")
+			.parameter("team", "Security")
+			.parameter("mention", "@oncall")
+			.build();
+		let triggers = HashMap::from([("team_slack".to_string(), trigger)]);
+
+		let checks = check_templates(&triggers, &[], &HashMap::new());
+		assert!(checks[0].passed());
+		assert_eq!(checks[0].rendered, "Security");
+		assert_eq!(
+			checks[1].issues,
+			vec![TemplateIssue::UnknownVariable {
+				name: "params.unknown".to_string()
+			}]
+		);
+	}
+
+	#[test]
+	fn test_check_templates_enrichment() {
+		let triggers = HashMap::from([(
+			"evm_slack".to_string(),
+			slack_trigger("evm_slack", "${monitor.name}", "Owner: ${enrichment.owner}"),
+		)]);
+		let monitor = MonitorBuilder::new()
+			.triggers(vec!["evm_slack".to_string()])
+			.build();
+
+		let checks = check_templates(&triggers, &[monitor], &HashMap::new());
+		assert_eq!(
+			checks[1].issues,
+			vec![TemplateIssue::UnknownVariable {
+				name: "enrichment.owner".to_string()
+			}]
+		);
+
+		// Monitors with a post-processing script provide the enrichment variables
+		let monitor = MonitorBuilder::new()
+			.triggers(vec!["evm_slack".to_string()])
+			.post_processing("enrich.py", ScriptLanguage::Python, None)
+			.build();
+		let checks = check_templates(&triggers, &[monitor], &HashMap::new());
+		assert!(checks.iter().all(|check| check.passed()));
+	}
+}
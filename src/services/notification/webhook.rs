@@ -259,9 +259,10 @@ impl WebhookNotifier {
 		let status = response.status();
 
 		if !status.is_success() {
+			// Keep the status error as source, so that the failure can be classified
 			return Err(NotificationError::notify_failed(
 				format!("Webhook request failed with status: {}", status),
-				None,
+				response.error_for_status().err().map(|e| e.into()),
 				None,
 			));
 		}
@@ -273,9 +274,11 @@ impl WebhookNotifier {
 #[cfg(test)]
 mod tests {
 	use crate::{
-		models::{NotificationMessage, SecretString, SecretValue, WebhookPayloadMode},
+		models::{
+			ChainMessages, NotificationMessage, SecretString, SecretValue, WebhookPayloadMode,
+		},
 		services::notification::{GenericWebhookPayloadBuilder, WebhookPayloadBuilder},
-		utils::{tests::create_test_http_client, RetryConfig},
+		utils::{tests::create_test_http_client, HttpClientConfig, RetryConfig},
 	};
 
 	use super::*;
@@ -310,9 +313,11 @@ mod tests {
 			message: NotificationMessage {
 				title: "Test Alert".to_string(),
 				body: "Test message ${value}".to_string(),
+				chains: ChainMessages::default(),
 			},
 			payload_mode: WebhookPayloadMode::default(),
 			retry_policy: RetryConfig::default(),
+			http_client: HttpClientConfig::default(),
 		}
 	}
 
@@ -387,8 +392,10 @@ mod tests {
 			message: NotificationMessage {
 				title: "Test Alert".to_string(),
 				body: "Test message ${value}".to_string(),
+				chains: ChainMessages::default(),
 			},
 			retry_policy: RetryConfig::default(),
+			http_client: HttpClientConfig::default(),
 		};
 
 		let http_client = create_test_http_client();
@@ -0,0 +1,97 @@
+//! Batching of notifications.
+//!
+//! The matches of a monitor with a `batch` section are sent as one notification per block
+//! instead of one per match. They are grouped here, and the group is dispatched at the position
+//! of its first match.
+
+use std::collections::HashMap;
+
+use crate::models::MonitorMatch;
+
+/// Groups the matches of a block for dispatch
+///
+/// # Arguments
+/// * `matches` - The matches of the block about to be notified, in block order
+///
+/// # Returns
+/// One group per monitor with a `batch` section, holding all of its matches in block order,
+/// and one group per match of the other monitors
+pub fn group_block_matches(matches: Vec<MonitorMatch>) -> Vec<Vec<MonitorMatch>> {
+	let mut groups: Vec<Vec<MonitorMatch>> = Vec::new();
+	let mut batch_positions: HashMap<String, usize> = HashMap::new();
+	for monitor_match in matches {
+		let monitor = monitor_match.monitor();
+		if monitor.batch.is_none() {
+			groups.push(vec![monitor_match]);
+			continue;
+		}
+		match batch_positions.get(&monitor.name) {
+			Some(&position) => groups[position].push(monitor_match),
+			None => {
+				batch_positions.insert(monitor.name.clone(), groups.len());
+				groups.push(vec![monitor_match]);
+			}
+		}
+	}
+	groups
+}
+
+/// Returns true if the matches of the monitor of a match are batched
+///
+/// # Arguments
+/// * `monitor_match` - The match
+pub fn is_batched(monitor_match: &MonitorMatch) -> bool {
+	monitor_match.monitor().batch.is_some()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		models::{EVMMonitorMatch, MatchConditions, Monitor},
+		utils::tests::builders::evm::{monitor::MonitorBuilder, transaction::TransactionBuilder},
+	};
+
+	fn evm_match(monitor: &Monitor) -> MonitorMatch {
+		MonitorMatch::EVM(Box::new(EVMMonitorMatch {
+			monitor: monitor.clone(),
+			transaction: TransactionBuilder::new().build(),
+			receipt: None,
+			logs: None,
+			network_slug: "ethereum_mainnet".to_string(),
+			matched_on: MatchConditions::default(),
+			matched_on_audit: Vec::new(),
+			matched_on_args: None,
+			block_timestamp: None,
+			trace: None,
+		}))
+	}
+
+	#[test]
+	fn test_group_block_matches() {
+		let batched = MonitorBuilder::new().name("batched").batch(None).build();
+		let live = MonitorBuilder::new().name("live").build();
+
+		let groups = group_block_matches(vec![
+			evm_match(&live),
+			evm_match(&batched),
+			evm_match(&live),
+			evm_match(&batched),
+		]);
+		let names: Vec<Vec<&str>> = groups
+			.iter()
+			.map(|group| {
+				group
+					.iter()
+					.map(|monitor_match| monitor_match.monitor().name.as_str())
+					.collect()
+			})
+			.collect();
+		assert_eq!(
+			names,
+			vec![vec!["live"], vec!["batched", "batched"], vec!["live"]]
+		);
+		assert!(is_batched(&groups[1][0]));
+		assert!(!is_batched(&groups[0][0]));
+	}
+}
@@ -0,0 +1,234 @@
+//! Delivery reports of triggers.
+//!
+//! A trigger with a `delivery_report` posts a small JSON report to its URL after each
+//! notification attempt, with the outcome, the latency and the number of retries of the
+//! attempt, so that external tooling can track the delivery reliability of the alerts without
+//! scraping logs. Reports are posted in the background, once and with a short timeout: a
+//! report that cannot be posted is logged and counted, but never delays nor fails the
+//! notification.
+
+use chrono::Utc;
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use std::{fmt::Display, time::Duration};
+
+use crate::{
+	models::{DeliveryReportConfig, MonitorMatch},
+	utils::metrics::record_delivery_report,
+};
+
+/// Timeout of the request posting a report
+const DELIVERY_REPORT_TIMEOUT: Duration = Duration::from_secs(5);
+
+lazy_static! {
+	static ref DELIVERY_REPORT_CLIENT: reqwest::Client = reqwest::Client::builder()
+		.timeout(DELIVERY_REPORT_TIMEOUT)
+		.build()
+		.unwrap_or_default();
+}
+
+/// Outcome of a notification attempt
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DeliveryStatus {
+	/// The notification was delivered
+	Success,
+	/// The notification failed, after its retries
+	Failure,
+}
+
+/// Report of a notification attempt, posted to the delivery report URL of the trigger
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeliveryReport {
+	/// Slug of the trigger
+	pub trigger: String,
+	/// Name of the monitor whose match was notified
+	pub monitor: String,
+	/// Network of the match
+	pub network: String,
+	/// Outcome of the attempt
+	pub status: DeliveryStatus,
+	/// Duration of the attempt including its retries, in milliseconds
+	pub latency_ms: u64,
+	/// Number of retries of the attempt
+	pub retries: u32,
+	/// Error of a failed attempt
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub error: Option<String>,
+	/// Unix timestamp (milliseconds) of the end of the attempt
+	pub timestamp: i64,
+}
+
+impl DeliveryReport {
+	/// Creates the report of a notification attempt
+	///
+	/// # Arguments
+	/// * `trigger` - Slug of the trigger
+	/// * `monitor_match` - The notified match
+	/// * `result` - Result of the attempt
+	/// * `latency` - Duration of the attempt
+	/// * `retries` - Number of retries of the attempt
+	pub fn new<E: Display>(
+		trigger: &str,
+		monitor_match: &MonitorMatch,
+		result: &Result<(), E>,
+		latency: Duration,
+		retries: u32,
+	) -> Self {
+		Self {
+			trigger: trigger.to_string(),
+			monitor: monitor_match.monitor().name.clone(),
+			network: monitor_match.network_slug().to_string(),
+			status: match result {
+				Ok(()) => DeliveryStatus::Success,
+				Err(_) => DeliveryStatus::Failure,
+			},
+			latency_ms: latency.as_millis() as u64,
+			retries,
+			error: result.as_ref().err().map(|e| e.to_string()),
+			timestamp: Utc::now().timestamp_millis(),
+		}
+	}
+
+	/// Returns whether the report is posted with a delivery report configuration
+	pub fn is_reported(&self, config: &DeliveryReportConfig) -> bool {
+		!config.failures_only || self.status == DeliveryStatus::Failure
+	}
+}
+
+/// Posts the report of a notification attempt in the background
+///
+/// # Arguments
+/// * `config` - Delivery report configuration of the trigger
+/// * `report` - The report
+pub fn send_delivery_report(config: &DeliveryReportConfig, report: DeliveryReport) {
+	if !report.is_reported(config) {
+		return;
+	}
+	let url = config.url.clone();
+	tokio::spawn(async move {
+		let result = match url.resolve().await {
+			Ok(url) => post_delivery_report(url.as_ref(), &report).await,
+			Err(e) => Err(anyhow::anyhow!("failed to resolve the URL: {}", e)),
+		};
+		record_delivery_report(&report.trigger, result.is_ok());
+		if let Err(e) = result {
+			tracing::warn!(
+				trigger = %report.trigger,
+				"Failed to post the delivery report: {}",
+				e
+			);
+		}
+	});
+}
+
+/// Posts a report to a delivery report URL
+///
+/// # Arguments
+/// * `url` - The URL
+/// * `report` - The report
+pub async fn post_delivery_report(url: &str, report: &DeliveryReport) -> Result<(), anyhow::Error> {
+	DELIVERY_REPORT_CLIENT
+		.post(url)
+		.json(report)
+		.send()
+		.await?
+		.error_for_status()?;
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		models::{EVMMonitorMatch, MatchConditions, SecretString, SecretValue},
+		utils::tests::builders::evm::{monitor::MonitorBuilder, transaction::TransactionBuilder},
+	};
+
+	fn evm_match() -> MonitorMatch {
+		MonitorMatch::EVM(Box::new(EVMMonitorMatch {
+			monitor: MonitorBuilder::new().name("transfers").build(),
+			transaction: TransactionBuilder::new().build(),
+			receipt: None,
+			logs: None,
+			network_slug: "ethereum_mainnet".to_string(),
+			matched_on: MatchConditions::default(),
+			matched_on_audit: Vec::new(),
+			matched_on_args: None,
+			block_timestamp: None,
+			trace: None,
+		}))
+	}
+
+	fn config(failures_only: bool) -> DeliveryReportConfig {
+		DeliveryReportConfig {
+			url: SecretValue::Plain(SecretString::new("https://slo.example.com".to_string())),
+			failures_only,
+		}
+	}
+
+	#[test]
+	fn test_delivery_report() {
+		let result: Result<(), String> = Err("HTTP 500".to_string());
+		let report = DeliveryReport::new(
+			"slack_alerts",
+			&evm_match(),
+			&result,
+			Duration::from_millis(1250),
+			3,
+		);
+		assert_eq!(report.trigger, "slack_alerts");
+		assert_eq!(report.monitor, "transfers");
+		assert_eq!(report.network, "ethereum_mainnet");
+		assert_eq!(report.status, DeliveryStatus::Failure);
+		assert_eq!(report.latency_ms, 1250);
+		assert_eq!(report.retries, 3);
+		assert_eq!(report.error.as_deref(), Some("HTTP 500"));
+		assert!(report.is_reported(&config(true)));
+
+		let report = DeliveryReport::new::<String>(
+			"slack_alerts",
+			&evm_match(),
+			&Ok(()),
+			Duration::from_millis(80),
+			0,
+		);
+		assert_eq!(report.status, DeliveryStatus::Success);
+		assert_eq!(report.error, None);
+		assert!(report.is_reported(&config(false)));
+		assert!(!report.is_reported(&config(true)));
+	}
+
+	#[tokio::test]
+	async fn test_post_delivery_report() {
+		let mut server = mockito::Server::new_async().await;
+		let mock = server
+			.mock("POST", "/reports")
+			.match_body(mockito::Matcher::PartialJson(serde_json::json!({
+				"trigger": "slack_alerts",
+				"status": "success",
+				"retries": 0,
+			})))
+			.with_status(204)
+			.create_async()
+			.await;
+
+		let report = DeliveryReport::new::<String>(
+			"slack_alerts",
+			&evm_match(),
+			&Ok(()),
+			Duration::from_millis(80),
+			0,
+		);
+		post_delivery_report(&format!("{}/reports", server.url()), &report)
+			.await
+			.unwrap();
+		mock.assert_async().await;
+
+		assert!(
+			post_delivery_report(&format!("{}/missing", server.url()), &report)
+				.await
+				.is_err()
+		);
+	}
+}
@@ -0,0 +1,388 @@
+//! Match journal.
+//!
+//! When enabled with `--match-journal`, every match that passes the trigger conditions is
+//! appended to a JSON lines file, whether its notifications are sent or not. The file is
+//! rotated once it reaches its maximum size, and rotated files are deleted past their maximum
+//! age. Monitors in observe mode (the `observe` field of the monitor, or `--observe` for all
+//! monitors) run the full pipeline but their matches are only journaled and counted by the
+//! `monitor_observed_matches_total` metric, which allows a new monitor to be burnt in and its
+//! noise measured before it goes live.
+
+use chrono::Utc;
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use std::{
+	path::{Path, PathBuf},
+	sync::RwLock,
+	time::{Duration, SystemTime},
+};
+use tokio::io::AsyncWriteExt;
+
+use crate::{models::MonitorMatch, utils::metrics::record_observed_match};
+
+/// Default size of the journal file before it is rotated (100 MB)
+pub const DEFAULT_JOURNAL_MAX_SIZE: u64 = 100 * 1000 * 1000;
+
+/// Default age of the rotated journal files before they are deleted (30 days)
+pub const DEFAULT_JOURNAL_MAX_AGE: Duration = Duration::from_secs(30 * 24 * 3600);
+
+lazy_static! {
+	/// Process-wide match journal.
+	pub static ref MATCH_JOURNAL: MatchJournal = MatchJournal::default();
+}
+
+/// Rotation of the journal file
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct JournalRotation {
+	/// Size in bytes of the journal file before it is rotated
+	pub max_size: u64,
+	/// Age of the rotated files before they are deleted
+	pub max_age: Duration,
+}
+
+impl Default for JournalRotation {
+	fn default() -> Self {
+		Self {
+			max_size: DEFAULT_JOURNAL_MAX_SIZE,
+			max_age: DEFAULT_JOURNAL_MAX_AGE,
+		}
+	}
+}
+
+/// A journaled match
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalEntry {
+	/// Unix timestamp (milliseconds) of the match
+	pub recorded_at: i64,
+	/// Slug of the network
+	pub network: String,
+	/// Name of the monitor
+	pub monitor: String,
+	/// Whether the match was only observed, without sending notifications
+	pub observed: bool,
+	/// The match
+	#[serde(rename = "match")]
+	pub monitor_match: MonitorMatch,
+}
+
+impl JournalEntry {
+	/// Creates the entry of a match
+	///
+	/// # Arguments
+	/// * `monitor_match` - The match
+	/// * `network` - Slug of the network
+	/// * `observed` - Whether the match is only observed
+	pub fn new(monitor_match: &MonitorMatch, network: &str, observed: bool) -> Self {
+		Self {
+			recorded_at: Utc::now().timestamp_millis(),
+			network: network.to_string(),
+			monitor: monitor_match.monitor().name.clone(),
+			observed,
+			monitor_match: monitor_match.clone(),
+		}
+	}
+}
+
+/// Append-only journal of matches, disabled until its storage is configured
+#[derive(Default)]
+pub struct MatchJournal {
+	storage: RwLock<Option<(PathBuf, JournalRotation)>>,
+	/// Serializes appends so that lines of concurrent blocks are not interleaved
+	write_lock: tokio::sync::Mutex<()>,
+}
+
+impl MatchJournal {
+	/// Enables the journal, appending the matches to a file
+	///
+	/// # Arguments
+	/// * `path` - Path of the JSON lines file
+	/// * `rotation` - Rotation of the file
+	pub fn configure_storage(&self, path: PathBuf, rotation: JournalRotation) {
+		if let Ok(mut storage) = self.storage.write() {
+			*storage = Some((path, rotation));
+		}
+	}
+
+	/// Returns the file the matches are appended to, `None` if the journal is disabled
+	pub fn storage_path(&self) -> Option<PathBuf> {
+		self.storage
+			.read()
+			.ok()
+			.and_then(|storage| storage.as_ref().map(|(path, _)| path.clone()))
+	}
+
+	/// Appends entries to the journal, if its storage is configured
+	///
+	/// # Arguments
+	/// * `entries` - The entries to append
+	pub async fn append(&self, entries: &[JournalEntry]) {
+		if entries.is_empty() {
+			return;
+		}
+		let Some((path, rotation)) = self.storage.read().ok().and_then(|storage| storage.clone())
+		else {
+			return;
+		};
+
+		let _guard = self.write_lock.lock().await;
+		let result = async {
+			let mut lines = String::new();
+			for entry in entries {
+				lines.push_str(&serde_json::to_string(entry)?);
+				lines.push('\n');
+			}
+			if let Some(parent) = path.parent() {
+				tokio::fs::create_dir_all(parent).await?;
+			}
+			let size = tokio::fs::metadata(&path)
+				.await
+				.map(|metadata| metadata.len())
+				.unwrap_or(0);
+			if size > 0 && size + lines.len() as u64 > rotation.max_size {
+				rotate(&path, rotation.max_age).await?;
+			}
+			let mut file = tokio::fs::OpenOptions::new()
+				.create(true)
+				.append(true)
+				.open(&path)
+				.await?;
+			file.write_all(lines.as_bytes()).await?;
+			file.flush().await?;
+			Ok::<(), anyhow::Error>(())
+		}
+		.await;
+
+		if let Err(e) = result {
+			tracing::error!(
+				"Failed to append matches to the journal {}: {}",
+				path.display(),
+				e
+			);
+		}
+	}
+}
+
+/// Returns the files of a journal in chronological order: the rotated files, then the file
+/// the matches are appended to
+///
+/// # Arguments
+/// * `path` - Path of the journal file
+pub fn journal_files(path: &Path) -> Vec<PathBuf> {
+	let mut files = rotated_files(path);
+	if path.exists() {
+		files.push(path.to_path_buf());
+	}
+	files
+}
+
+/// Moves the journal file aside and deletes the rotated files past their maximum age
+///
+/// Rotated files are named after the journal file and the time of the rotation, e.g.
+/// `match_journal.20240501T120000123.jsonl`, so that they sort chronologically.
+async fn rotate(path: &Path, max_age: Duration) -> Result<(), anyhow::Error> {
+	let (stem, extension) = file_name_parts(path);
+	let rotated = path.with_file_name(format!(
+		"{}.{}{}",
+		stem,
+		Utc::now().format("%Y%m%dT%H%M%S%3f"),
+		extension
+	));
+	tokio::fs::rename(path, &rotated).await?;
+	tracing::info!("Rotated the match journal to {}", rotated.display());
+
+	let now = SystemTime::now();
+	for file in rotated_files(path) {
+		let expired = tokio::fs::metadata(&file)
+			.await
+			.and_then(|metadata| metadata.modified())
+			.is_ok_and(|modified| now.duration_since(modified).unwrap_or_default() > max_age);
+		if expired {
+			if let Err(e) = tokio::fs::remove_file(&file).await {
+				tracing::warn!(
+					"Failed to delete the rotated match journal {}: {}",
+					file.display(),
+					e
+				);
+			}
+		}
+	}
+	Ok(())
+}
+
+/// Returns the rotated files of a journal, oldest first
+fn rotated_files(path: &Path) -> Vec<PathBuf> {
+	let (stem, extension) = file_name_parts(path);
+	let prefix = format!("{}.", stem);
+	let directory = match path.parent() {
+		Some(parent) if !parent.as_os_str().is_empty() => parent,
+		_ => Path::new("."),
+	};
+	let Ok(entries) = std::fs::read_dir(directory) else {
+		return Vec::new();
+	};
+	let mut files: Vec<PathBuf> = entries
+		.filter_map(|entry| entry.ok())
+		.map(|entry| entry.path())
+		.filter(|file| {
+			file.file_name()
+				.and_then(|name| name.to_str())
+				.and_then(|name| name.strip_prefix(&prefix))
+				.and_then(|name| name.strip_suffix(&extension))
+				.is_some_and(|timestamp| {
+					!timestamp.is_empty()
+						&& timestamp.chars().all(|c| c.is_ascii_digit() || c == 'T')
+				})
+		})
+		.collect();
+	files.sort();
+	files
+}
+
+/// Splits the file name of a journal into its stem and its extension (with the dot)
+fn file_name_parts(path: &Path) -> (String, String) {
+	let stem = path
+		.file_stem()
+		.and_then(|stem| stem.to_str())
+		.unwrap_or_default()
+		.to_string();
+	let extension = path
+		.extension()
+		.and_then(|extension| extension.to_str())
+		.map(|extension| format!(".{}", extension))
+		.unwrap_or_default();
+	(stem, extension)
+}
+
+/// Returns whether a match is only observed, without sending notifications
+///
+/// # Arguments
+/// * `monitor_match` - The match
+/// * `observe_all` - Whether all monitors are in observe mode
+pub fn is_observed(monitor_match: &MonitorMatch, observe_all: bool) -> bool {
+	observe_all || monitor_match.monitor().observe
+}
+
+/// Records a match that is only observed in the metrics
+///
+/// # Arguments
+/// * `monitor_match` - The match
+/// * `network` - Slug of the network
+pub fn record_observation(monitor_match: &MonitorMatch, network: &str) {
+	record_observed_match(&monitor_match.monitor().name, network);
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		models::{EVMMonitorMatch, MatchConditions, Monitor},
+		utils::tests::builders::evm::{monitor::MonitorBuilder, transaction::TransactionBuilder},
+	};
+
+	fn evm_match(monitor: Monitor) -> MonitorMatch {
+		MonitorMatch::EVM(Box::new(EVMMonitorMatch {
+			monitor,
+			transaction: TransactionBuilder::new().build(),
+			receipt: None,
+			logs: None,
+			network_slug: "ethereum_mainnet".to_string(),
+			matched_on: MatchConditions::default(),
+			matched_on_audit: Vec::new(),
+			matched_on_args: None,
+			block_timestamp: None,
+			trace: None,
+		}))
+	}
+
+	#[test]
+	fn test_is_observed() {
+		let live = evm_match(MonitorBuilder::new().name("live").build());
+		let observed = evm_match(MonitorBuilder::new().name("burn-in").observe(true).build());
+
+		assert!(!is_observed(&live, false));
+		assert!(is_observed(&live, true));
+		assert!(is_observed(&observed, false));
+	}
+
+	#[tokio::test]
+	async fn test_append() {
+		let temp_dir = tempfile::tempdir().unwrap();
+		let path = temp_dir.path().join("journal").join("matches.jsonl");
+		let journal = MatchJournal::default();
+
+		// Not persisted until the storage is configured
+		let monitor_match = evm_match(MonitorBuilder::new().name("burn-in").build());
+		journal
+			.append(&[JournalEntry::new(&monitor_match, "ethereum_mainnet", true)])
+			.await;
+		assert!(!path.exists());
+
+		journal.configure_storage(path.clone(), JournalRotation::default());
+		journal
+			.append(&[JournalEntry::new(&monitor_match, "ethereum_mainnet", true)])
+			.await;
+		journal
+			.append(&[JournalEntry::new(&monitor_match, "ethereum_mainnet", false)])
+			.await;
+
+		let content = std::fs::read_to_string(&path).unwrap();
+		let entries: Vec<JournalEntry> = content
+			.lines()
+			.map(|line| serde_json::from_str(line).unwrap())
+			.collect();
+		assert_eq!(entries.len(), 2);
+		assert_eq!(entries[0].monitor, "burn-in");
+		assert!(entries[0].observed);
+		assert!(!entries[1].observed);
+		assert_eq!(journal.storage_path(), Some(path.clone()));
+		assert_eq!(journal_files(&path), vec![path]);
+	}
+
+	#[tokio::test]
+	async fn test_append_rotates() {
+		let temp_dir = tempfile::tempdir().unwrap();
+		let path = temp_dir.path().join("match_journal.jsonl");
+		let journal = MatchJournal::default();
+		journal.configure_storage(
+			path.clone(),
+			JournalRotation {
+				max_size: 1,
+				max_age: DEFAULT_JOURNAL_MAX_AGE,
+			},
+		);
+
+		let monitor_match = evm_match(MonitorBuilder::new().name("burn-in").build());
+		for _ in 0..2 {
+			journal
+				.append(&[JournalEntry::new(&monitor_match, "ethereum_mainnet", false)])
+				.await;
+			// Rotated files are named after the time of the rotation, in milliseconds
+			tokio::time::sleep(Duration::from_millis(5)).await;
+		}
+		journal
+			.append(&[JournalEntry::new(&monitor_match, "ethereum_mainnet", false)])
+			.await;
+
+		// Each append exceeds the maximum size, the previous entries are rotated
+		let files = journal_files(&path);
+		assert_eq!(files.len(), 3);
+		assert_eq!(files[2], path);
+		for file in &files {
+			assert_eq!(std::fs::read_to_string(file).unwrap().lines().count(), 1);
+		}
+
+		// Rotated files past their maximum age are deleted on the next rotation
+		journal.configure_storage(
+			path.clone(),
+			JournalRotation {
+				max_size: 1,
+				max_age: Duration::ZERO,
+			},
+		);
+		tokio::time::sleep(Duration::from_millis(5)).await;
+		journal
+			.append(&[JournalEntry::new(&monitor_match, "ethereum_mainnet", false)])
+			.await;
+		assert_eq!(journal_files(&path), vec![path]);
+	}
+}
@@ -0,0 +1,417 @@
+//! End-to-end latency of the notifications.
+//!
+//! The blocks of the live pipeline are timestamped at each stage on their way to the
+//! notifications: the timestamp of the block on chain, its receipt by the monitor once
+//! fetched, and the detection of its matches once filtered. When a match is dispatched to its
+//! triggers, the latency between the stages is exposed to the templates (`latency.*`), and
+//! once its notifications are sent it is observed in the `notification_latency_seconds`
+//! metric, so that the detection SLA of a monitor can be measured and alerted on.
+//!
+//! Blocks are fetched, filtered and dispatched by different tasks, so the times are kept in a
+//! tracker shared by the block watcher service and the block and trigger handlers, bounded to
+//! the most recent blocks of each network.
+
+use async_trait::async_trait;
+use chrono::Utc;
+use std::{
+	collections::{BTreeMap, HashMap},
+	sync::Mutex,
+};
+
+use crate::{
+	models::{BlockType, Monitor, MonitorMatch, ScriptLanguage},
+	services::trigger::{TriggerError, TriggerExecutionServiceTrait},
+	utils::metrics::record_notification_latency,
+};
+
+/// Number of recent blocks whose times are kept per network
+const TRACKED_BLOCKS: usize = 1024;
+
+/// Times of a block, as unix timestamps in milliseconds
+#[derive(Debug, Clone, Copy)]
+struct BlockTimes {
+	/// Timestamp of the block on chain
+	block_timestamp: Option<i64>,
+	/// Receipt of the block by the monitor
+	received_at: i64,
+	/// Detection of the matches of the block
+	detected_at: Option<i64>,
+}
+
+/// Latency of a notification between the stages of its block, in milliseconds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NotificationLatency {
+	/// From the block timestamp to the receipt of the block, unknown without a block timestamp
+	pub block_to_receipt_ms: Option<u64>,
+	/// From the receipt of the block to the detection of its matches
+	pub receipt_to_detection_ms: u64,
+	/// From the detection of the match to the dispatch of the notification
+	pub detection_to_dispatch_ms: u64,
+	/// From the block timestamp to the dispatch, unknown without a block timestamp
+	pub end_to_end_ms: Option<u64>,
+}
+
+impl NotificationLatency {
+	/// Returns the stages of the latency with their duration, leaving out the unknown ones
+	pub fn stages(&self) -> Vec<(&'static str, u64)> {
+		[
+			("block_to_receipt", self.block_to_receipt_ms),
+			("receipt_to_detection", Some(self.receipt_to_detection_ms)),
+			("detection_to_dispatch", Some(self.detection_to_dispatch_ms)),
+			("end_to_end", self.end_to_end_ms),
+		]
+		.into_iter()
+		.filter_map(|(stage, latency)| latency.map(|latency| (stage, latency)))
+		.collect()
+	}
+
+	/// Returns the template variables of the latency
+	pub fn variables(&self) -> HashMap<String, String> {
+		self.stages()
+			.into_iter()
+			.map(|(stage, latency)| (format!("latency.{}_ms", stage), latency.to_string()))
+			.collect()
+	}
+
+	/// Observes the stages of the latency in the metrics
+	///
+	/// # Arguments
+	/// * `network` - The network of the notification
+	pub fn record(&self, network: &str) {
+		for (stage, latency) in self.stages() {
+			record_notification_latency(network, stage, latency as f64 / 1000.0);
+		}
+	}
+}
+
+/// Stage times of the recent blocks of the networks
+#[derive(Default)]
+pub struct LatencyTracker {
+	blocks: Mutex<HashMap<String, BTreeMap<u64, BlockTimes>>>,
+}
+
+impl LatencyTracker {
+	fn blocks(&self) -> std::sync::MutexGuard<'_, HashMap<String, BTreeMap<u64, BlockTimes>>> {
+		match self.blocks.lock() {
+			Ok(blocks) => blocks,
+			Err(poisoned) => poisoned.into_inner(),
+		}
+	}
+
+	/// Records the receipt of a block, replacing the times of a block received again
+	///
+	/// # Arguments
+	/// * `network` - The network of the block
+	/// * `block` - The block
+	/// * `now_ms` - The current unix timestamp in milliseconds
+	pub fn record_received(&self, network: &str, block: &BlockType, now_ms: i64) {
+		let Some(block_number) = block.number() else {
+			return;
+		};
+		let times = BlockTimes {
+			block_timestamp: block
+				.timestamp()
+				.and_then(|timestamp| i64::try_from(timestamp).ok())
+				.map(|timestamp| timestamp.saturating_mul(1000)),
+			received_at: now_ms,
+			detected_at: None,
+		};
+		let mut blocks = self.blocks();
+		let network_blocks = blocks.entry(network.to_string()).or_default();
+		network_blocks.insert(block_number, times);
+		while network_blocks.len() > TRACKED_BLOCKS {
+			network_blocks.pop_first();
+		}
+	}
+
+	/// Records the detection of the matches of a received block
+	///
+	/// # Arguments
+	/// * `network` - The network of the block
+	/// * `block_number` - The number of the block
+	/// * `now_ms` - The current unix timestamp in milliseconds
+	pub fn record_detected(&self, network: &str, block_number: u64, now_ms: i64) {
+		if let Some(times) = self
+			.blocks()
+			.get_mut(network)
+			.and_then(|network_blocks| network_blocks.get_mut(&block_number))
+		{
+			times.detected_at = Some(now_ms);
+		}
+	}
+
+	/// Returns the latency of a notification of a block dispatched now
+	///
+	/// # Arguments
+	/// * `network` - The network of the block
+	/// * `block_number` - The number of the block
+	/// * `now_ms` - The current unix timestamp in milliseconds
+	///
+	/// # Returns
+	/// The latency, or `None` if the receipt or the detection of the block was not recorded
+	pub fn latency(
+		&self,
+		network: &str,
+		block_number: u64,
+		now_ms: i64,
+	) -> Option<NotificationLatency> {
+		let times = *self.blocks().get(network)?.get(&block_number)?;
+		let detected_at = times.detected_at?;
+		// Clocks of the chain and of the monitor may be skewed, never report negative latencies
+		let elapsed = |from: i64, to: i64| u64::try_from(to.saturating_sub(from)).unwrap_or(0);
+		Some(NotificationLatency {
+			block_to_receipt_ms: times
+				.block_timestamp
+				.map(|timestamp| elapsed(timestamp, times.received_at)),
+			receipt_to_detection_ms: elapsed(times.received_at, detected_at),
+			detection_to_dispatch_ms: elapsed(detected_at, now_ms),
+			end_to_end_ms: times
+				.block_timestamp
+				.map(|timestamp| elapsed(timestamp, now_ms)),
+		})
+	}
+}
+
+/// Trigger execution service adding the latency of the notification to its variables, and
+/// observing it in the metrics once the notification is sent
+pub struct LatencyTriggerExecution<'a, S> {
+	inner: &'a S,
+	tracker: &'a LatencyTracker,
+	network: &'a str,
+	block_number: u64,
+}
+
+impl<'a, S> LatencyTriggerExecution<'a, S> {
+	/// Wraps a trigger execution service for the matches of a block
+	///
+	/// # Arguments
+	/// * `inner` - The trigger execution service
+	/// * `tracker` - The tracker of the stage times of the block
+	/// * `network` - The network of the block
+	/// * `block_number` - The number of the block
+	pub fn new(
+		inner: &'a S,
+		tracker: &'a LatencyTracker,
+		network: &'a str,
+		block_number: u64,
+	) -> Self {
+		Self {
+			inner,
+			tracker,
+			network,
+			block_number,
+		}
+	}
+
+	fn latency(&self) -> Option<NotificationLatency> {
+		self.tracker.latency(
+			self.network,
+			self.block_number,
+			Utc::now().timestamp_millis(),
+		)
+	}
+}
+
+#[async_trait]
+impl<S: TriggerExecutionServiceTrait + Send + Sync> TriggerExecutionServiceTrait
+	for LatencyTriggerExecution<'_, S>
+{
+	async fn execute(
+		&self,
+		trigger_slugs: &[String],
+		mut variables: HashMap<String, String>,
+		monitor_match: &MonitorMatch,
+		trigger_scripts: &HashMap<String, (ScriptLanguage, String)>,
+	) -> Result<(), TriggerError> {
+		if let Some(latency) = self.latency() {
+			variables.extend(latency.variables());
+		}
+		self.inner
+			.execute(trigger_slugs, variables, monitor_match, trigger_scripts)
+			.await?;
+		// Failed notifications are not observed, their latency is not the one of a delivery
+		if let Some(latency) = self.latency() {
+			latency.record(self.network);
+		}
+		Ok(())
+	}
+
+	async fn load_scripts(
+		&self,
+		monitors: &[Monitor],
+	) -> Result<HashMap<String, (ScriptLanguage, String)>, TriggerError> {
+		self.inner.load_scripts(monitors).await
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		models::{EVMBlock, EVMMonitorMatch, MatchConditions},
+		utils::{
+			metrics::NOTIFICATION_LATENCY_SECONDS,
+			tests::builders::evm::{monitor::MonitorBuilder, transaction::TransactionBuilder},
+		},
+	};
+	use alloy::primitives::{U256, U64};
+
+	/// Trigger service recording the variables of the notifications, failing if asked to
+	struct RecordingTriggerService {
+		fail: bool,
+		variables: Mutex<Vec<HashMap<String, String>>>,
+	}
+
+	#[async_trait]
+	impl TriggerExecutionServiceTrait for RecordingTriggerService {
+		async fn execute(
+			&self,
+			_trigger_slugs: &[String],
+			variables: HashMap<String, String>,
+			_monitor_match: &MonitorMatch,
+			_trigger_scripts: &HashMap<String, (ScriptLanguage, String)>,
+		) -> Result<(), TriggerError> {
+			self.variables.lock().unwrap().push(variables);
+			if self.fail {
+				return Err(TriggerError::execution_error_without_log(
+					"webhook unreachable",
+					None,
+					None,
+				));
+			}
+			Ok(())
+		}
+
+		async fn load_scripts(
+			&self,
+			_monitors: &[Monitor],
+		) -> Result<HashMap<String, (ScriptLanguage, String)>, TriggerError> {
+			Ok(HashMap::new())
+		}
+	}
+
+	fn evm_block(number: u64, timestamp: u64) -> BlockType {
+		let mut block = EVMBlock::default();
+		block.0.number = Some(U64::from(number));
+		block.0.timestamp = U256::from(timestamp);
+		BlockType::EVM(Box::new(block))
+	}
+
+	#[test]
+	fn test_latency() {
+		let tracker = LatencyTracker::default();
+		tracker.record_received("ethereum_mainnet", &evm_block(100, 1_000), 1_004_500);
+
+		// Matches are dispatched once detected
+		assert_eq!(tracker.latency("ethereum_mainnet", 100, 1_006_000), None);
+		tracker.record_detected("ethereum_mainnet", 100, 1_005_000);
+
+		let latency = tracker.latency("ethereum_mainnet", 100, 1_006_250).unwrap();
+		assert_eq!(
+			latency,
+			NotificationLatency {
+				block_to_receipt_ms: Some(4_500),
+				receipt_to_detection_ms: 500,
+				detection_to_dispatch_ms: 1_250,
+				end_to_end_ms: Some(6_250),
+			}
+		);
+		let variables = latency.variables();
+		assert_eq!(variables["latency.end_to_end_ms"], "6250");
+		assert_eq!(variables["latency.detection_to_dispatch_ms"], "1250");
+
+		assert_eq!(tracker.latency("ethereum_mainnet", 101, 1_006_250), None);
+		assert_eq!(tracker.latency("polygon_mainnet", 100, 1_006_250), None);
+	}
+
+	#[test]
+	fn test_latency_without_block_timestamp() {
+		let tracker = LatencyTracker::default();
+		// A block timestamp ahead of the clock of the monitor
+		tracker.record_received("ethereum_mainnet", &evm_block(7, 2_000), 1_999_000);
+		tracker.record_detected("ethereum_mainnet", 7, 1_999_100);
+		let latency = tracker.latency("ethereum_mainnet", 7, 1_999_300).unwrap();
+		assert_eq!(latency.block_to_receipt_ms, Some(0));
+		assert_eq!(latency.end_to_end_ms, Some(0));
+
+		tracker.record_received("stellar_mainnet", &BlockType::Stellar(Box::default()), 500);
+		tracker.record_detected("stellar_mainnet", 0, 800);
+		let latency = tracker.latency("stellar_mainnet", 0, 900).unwrap();
+		assert_eq!(latency.block_to_receipt_ms, None);
+		assert_eq!(latency.end_to_end_ms, None);
+		let stages: Vec<_> = latency
+			.stages()
+			.into_iter()
+			.map(|(stage, _)| stage)
+			.collect();
+		assert_eq!(
+			stages,
+			vec!["receipt_to_detection", "detection_to_dispatch"]
+		);
+		assert!(!latency.variables().contains_key("latency.end_to_end_ms"));
+	}
+
+	#[test]
+	fn test_tracked_blocks_are_bounded() {
+		let tracker = LatencyTracker::default();
+		for number in 0..(TRACKED_BLOCKS as u64 + 10) {
+			tracker.record_received("ethereum_mainnet", &evm_block(number, 1), 1_000);
+			tracker.record_detected("ethereum_mainnet", number, 1_000);
+		}
+		assert_eq!(tracker.blocks()["ethereum_mainnet"].len(), TRACKED_BLOCKS);
+		assert_eq!(tracker.latency("ethereum_mainnet", 0, 1_000), None);
+		assert!(tracker
+			.latency("ethereum_mainnet", TRACKED_BLOCKS as u64 + 9, 1_000)
+			.is_some());
+	}
+
+	#[tokio::test]
+	async fn test_latency_is_observed_once_sent() {
+		let network = "latency_trigger_test";
+		let tracker = LatencyTracker::default();
+		let now_ms = Utc::now().timestamp_millis();
+		tracker.record_received(network, &evm_block(5, 1), now_ms);
+		tracker.record_detected(network, 5, now_ms);
+		let monitor_match = MonitorMatch::EVM(Box::new(EVMMonitorMatch {
+			monitor: MonitorBuilder::new().build(),
+			transaction: TransactionBuilder::new().build(),
+			receipt: None,
+			logs: None,
+			network_slug: network.to_string(),
+			matched_on: MatchConditions::default(),
+			matched_on_audit: Vec::new(),
+			matched_on_args: None,
+			block_timestamp: None,
+			trace: None,
+		}));
+		let observed = || {
+			NOTIFICATION_LATENCY_SECONDS
+				.with_label_values(&[network, "end_to_end"])
+				.get_sample_count()
+		};
+
+		// A failed notification carries the latency but is not observed
+		let failing = RecordingTriggerService {
+			fail: true,
+			variables: Mutex::new(Vec::new()),
+		};
+		let service = LatencyTriggerExecution::new(&failing, &tracker, network, 5);
+		assert!(service
+			.execute(&[], HashMap::new(), &monitor_match, &HashMap::new())
+			.await
+			.is_err());
+		assert!(failing.variables.lock().unwrap()[0].contains_key("latency.end_to_end_ms"));
+		assert_eq!(observed(), 0);
+
+		let sending = RecordingTriggerService {
+			fail: false,
+			variables: Mutex::new(Vec::new()),
+		};
+		let service = LatencyTriggerExecution::new(&sending, &tracker, network, 5);
+		service
+			.execute(&[], HashMap::new(), &monitor_match, &HashMap::new())
+			.await
+			.unwrap();
+		assert_eq!(observed(), 1);
+	}
+}
@@ -4,13 +4,47 @@
 //! which are configurable actions that can be initiated based on
 //! various conditions.
 
+mod batch;
+mod delivery_report;
 mod error;
+mod journal;
+mod latency;
+mod monitor_stats;
+mod ordering;
+mod owners;
+mod pending;
+mod priority;
+mod replay;
+mod sampling;
 mod script;
 mod service;
 
+pub use batch::{group_block_matches, is_batched};
+pub use delivery_report::{
+	post_delivery_report, send_delivery_report, DeliveryReport, DeliveryStatus,
+};
 pub use error::TriggerError;
+pub use journal::{
+	is_observed, journal_files, record_observation, JournalEntry, JournalRotation, MatchJournal,
+	DEFAULT_JOURNAL_MAX_AGE, DEFAULT_JOURNAL_MAX_SIZE, MATCH_JOURNAL,
+};
+pub use latency::{LatencyTracker, LatencyTriggerExecution, NotificationLatency};
+pub use monitor_stats::{
+	record_block_matches, record_monitor_matches, MonitorStats, MonitorStatsTriggerExecution,
+};
+pub use ordering::{DispatchTurns, NotificationOrdering, NotificationSequencer};
+pub use owners::{match_owner, match_triggers, owner_template_json};
+pub use pending::{
+	resume_pending_notifications, HandedOverBlock, PendingNotifications, QueuedMatches,
+	PENDING_NOTIFICATIONS_FILE,
+};
+pub use priority::{DispatchPermit, MatchPriority, PriorityDispatchQueue, DEFAULT_MAX_CONCURRENCY};
+pub use replay::{
+	parse_replay_time, read_replay_matches, replay_matches, ReplayOptions, ReplayResult,
+};
+pub use sampling::{MatchSampler, SampleCounts, SampleDecision, SampledTriggerExecution};
 pub use script::{
-	process_script_output, validate_script_config, ScriptError, ScriptExecutor,
-	ScriptExecutorFactory,
+	process_enrichment_output, process_script_output, validate_script_config, ScriptError,
+	ScriptExecutor, ScriptExecutorFactory,
 };
 pub use service::{TriggerExecutionService, TriggerExecutionServiceTrait};
@@ -0,0 +1,313 @@
+//! Match statistics of monitors surfaced in notifications.
+//!
+//! The matches of every monitor are counted in the [`WindowStore`] of the service, one entry
+//! per block in a window of a week and an atomic counter for the total, so that the counts
+//! survive restarts (and are shared by the instances of the service when the store
+//! is backed by Redis). Notifications carry the number of matches of their monitor over the
+//! last hour, day and week and since the statistics are kept (`monitor.stats.*`), which lets
+//! responders tell a routine alert from an anomalous one at a glance.
+
+use async_trait::async_trait;
+use chrono::Utc;
+use futures::future::join_all;
+use std::{collections::HashMap, time::Duration};
+
+use crate::{
+	models::{Monitor, MonitorMatch, ScriptLanguage},
+	services::{
+		filter::{WindowEntry, WindowStore, WindowStoreError},
+		trigger::{TriggerError, TriggerExecutionServiceTrait},
+	},
+};
+
+/// Prefix of the keys of the statistics in the window store
+const KEY_PREFIX: &str = "monitor_stats:";
+
+/// Lengths in seconds of the windows of the match counts (1h, 24h and 7d)
+const WINDOWS: [u64; 3] = [3_600, 86_400, 604_800];
+
+/// Match counts of a monitor
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MonitorStats {
+	/// Matches over the last hour
+	pub matches_1h: u64,
+	/// Matches over the last 24 hours
+	pub matches_24h: u64,
+	/// Matches over the last 7 days
+	pub matches_7d: u64,
+	/// Matches since the statistics are kept
+	pub matches_total: u64,
+}
+
+impl MonitorStats {
+	/// Returns the template variables of the counts
+	pub fn variables(&self) -> HashMap<String, String> {
+		HashMap::from([
+			(
+				"monitor.stats.matches_1h".to_string(),
+				self.matches_1h.to_string(),
+			),
+			(
+				"monitor.stats.matches_24h".to_string(),
+				self.matches_24h.to_string(),
+			),
+			(
+				"monitor.stats.matches_7d".to_string(),
+				self.matches_7d.to_string(),
+			),
+			(
+				"monitor.stats.matches_total".to_string(),
+				self.matches_total.to_string(),
+			),
+		])
+	}
+}
+
+/// Records the matches of a monitor and returns its updated statistics
+///
+/// The counts of the windows are computed from a single read of the longest window, and the
+/// total is updated with an atomic increment, so that instances sharing the store never lose
+/// matches of each other.
+///
+/// # Arguments
+/// * `store` - The window store keeping the statistics
+/// * `monitor` - Name of the monitor
+/// * `matches` - Number of new matches
+/// * `now` - Current unix timestamp, in seconds
+///
+/// # Returns
+/// The statistics of the monitor, including the new matches
+///
+/// # Errors
+/// Returns an error if the store fails
+pub async fn record_monitor_matches(
+	store: &dyn WindowStore,
+	monitor: &str,
+	matches: u64,
+	now: u64,
+) -> Result<MonitorStats, WindowStoreError> {
+	let longest_window = WINDOWS[WINDOWS.len() - 1];
+	let window_key = format!("{}{}", KEY_PREFIX, monitor);
+
+	// One entry per block, holding the number of matches of the block
+	store
+		.push(
+			&window_key,
+			WindowEntry {
+				position: now,
+				value: matches as f64,
+			},
+			Some(Duration::from_secs(longest_window)),
+		)
+		.await?;
+	store
+		.trim(&window_key, now.saturating_sub(longest_window))
+		.await?;
+	let entries = store
+		.range(&window_key, now.saturating_sub(longest_window), now)
+		.await?;
+
+	let mut counts = [0; WINDOWS.len()];
+	for (count, length) in counts.iter_mut().zip(WINDOWS) {
+		*count = entries
+			.iter()
+			.filter(|entry| entry.position >= now.saturating_sub(length))
+			.map(|entry| entry.value)
+			.sum::<f64>()
+			.round() as u64;
+	}
+	Ok(MonitorStats {
+		matches_1h: counts[0],
+		matches_24h: counts[1],
+		matches_7d: counts[2],
+		matches_total: increment_total(store, monitor, matches).await?,
+	})
+}
+
+/// Adds the matches of a monitor to its total and returns the new total
+async fn increment_total(
+	store: &dyn WindowStore,
+	monitor: &str,
+	matches: u64,
+) -> Result<u64, WindowStoreError> {
+	let total_key = format!("{}{}:matches_total", KEY_PREFIX, monitor);
+	let total = store.increment(&total_key, matches).await?;
+	if total != matches {
+		return Ok(total);
+	}
+
+	// A new counter carries over the total kept by previous versions as a window entry
+	let legacy_key = format!("{}{}:total", KEY_PREFIX, monitor);
+	let legacy_total = store.sum(&legacy_key, 0, u64::MAX).await?.round() as u64;
+	if legacy_total == 0 {
+		return Ok(total);
+	}
+	let total = store.increment(&total_key, legacy_total).await?;
+	store.remove(&legacy_key).await?;
+	Ok(total)
+}
+
+/// Records the matches of a block and returns the statistics of their monitors
+///
+/// The monitors of the block are recorded concurrently.
+///
+/// # Arguments
+/// * `store` - The window store keeping the statistics
+/// * `matches` - The matches of the block
+///
+/// # Returns
+/// The statistics of the monitors of the matches, keyed by name. Monitors whose statistics
+/// could not be updated are left out, the failure is logged.
+pub async fn record_block_matches(
+	store: &dyn WindowStore,
+	matches: &[MonitorMatch],
+) -> HashMap<String, MonitorStats> {
+	let mut counts: HashMap<&str, u64> = HashMap::new();
+	for monitor_match in matches {
+		*counts.entry(&monitor_match.monitor().name).or_default() += 1;
+	}
+
+	let now = Utc::now().timestamp().max(0) as u64;
+	let results = join_all(counts.into_iter().map(|(monitor, count)| async move {
+		(
+			monitor,
+			record_monitor_matches(store, monitor, count, now).await,
+		)
+	}))
+	.await;
+
+	let mut stats = HashMap::new();
+	for (monitor, result) in results {
+		match result {
+			Ok(monitor_stats) => {
+				stats.insert(monitor.to_string(), monitor_stats);
+			}
+			Err(e) => tracing::warn!(
+				"Failed to record the match statistics of monitor '{}': {}",
+				monitor,
+				e
+			),
+		}
+	}
+	stats
+}
+
+/// Trigger execution service adding the statistics of their monitor to the notifications
+pub struct MonitorStatsTriggerExecution<'a, S> {
+	inner: &'a S,
+	stats: HashMap<String, MonitorStats>,
+}
+
+impl<'a, S> MonitorStatsTriggerExecution<'a, S> {
+	/// Wraps a trigger execution service
+	///
+	/// # Arguments
+	/// * `inner` - The trigger execution service
+	/// * `stats` - The statistics of the monitors, keyed by name
+	pub fn new(inner: &'a S, stats: HashMap<String, MonitorStats>) -> Self {
+		Self { inner, stats }
+	}
+}
+
+#[async_trait]
+impl<S: TriggerExecutionServiceTrait + Send + Sync> TriggerExecutionServiceTrait
+	for MonitorStatsTriggerExecution<'_, S>
+{
+	async fn execute(
+		&self,
+		trigger_slugs: &[String],
+		mut variables: HashMap<String, String>,
+		monitor_match: &MonitorMatch,
+		trigger_scripts: &HashMap<String, (ScriptLanguage, String)>,
+	) -> Result<(), TriggerError> {
+		if let Some(stats) = self.stats.get(&monitor_match.monitor().name) {
+			variables.extend(stats.variables());
+		}
+		self.inner
+			.execute(trigger_slugs, variables, monitor_match, trigger_scripts)
+			.await
+	}
+
+	async fn load_scripts(
+		&self,
+		monitors: &[Monitor],
+	) -> Result<HashMap<String, (ScriptLanguage, String)>, TriggerError> {
+		self.inner.load_scripts(monitors).await
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::services::filter::state::InMemoryWindowStore;
+
+	#[tokio::test]
+	async fn test_record_monitor_matches() {
+		let store = InMemoryWindowStore::new();
+		let day = 86_400;
+		let start = 10 * day;
+
+		let stats = record_monitor_matches(&store, "Transfers", 2, start)
+			.await
+			.unwrap();
+		assert_eq!(
+			stats,
+			MonitorStats {
+				matches_1h: 2,
+				matches_24h: 2,
+				matches_7d: 2,
+				matches_total: 2,
+			}
+		);
+
+		// Two hours later, the first matches left the 1h window only
+		let stats = record_monitor_matches(&store, "Transfers", 1, start + 7_200)
+			.await
+			.unwrap();
+		assert_eq!(stats.matches_1h, 1);
+		assert_eq!(stats.matches_24h, 3);
+
+		// Matches older than the longest window only remain in the total
+		let stats = record_monitor_matches(&store, "Transfers", 1, start + 8 * day)
+			.await
+			.unwrap();
+		assert_eq!(
+			stats,
+			MonitorStats {
+				matches_1h: 1,
+				matches_24h: 1,
+				matches_7d: 1,
+				matches_total: 4,
+			}
+		);
+
+		// The total kept by previous versions is carried over
+		store
+			.push(
+				"monitor_stats:Upgraded:total",
+				WindowEntry {
+					position: start,
+					value: 40.0,
+				},
+				None,
+			)
+			.await
+			.unwrap();
+		let stats = record_monitor_matches(&store, "Upgraded", 2, start)
+			.await
+			.unwrap();
+		assert_eq!(stats.matches_total, 42);
+		assert_eq!(stats.matches_1h, 2);
+		let stats = record_monitor_matches(&store, "Upgraded", 1, start)
+			.await
+			.unwrap();
+		assert_eq!(stats.matches_total, 43);
+
+		// Monitors are counted independently
+		let stats = record_monitor_matches(&store, "Ownership", 1, start)
+			.await
+			.unwrap();
+		assert_eq!(stats.matches_total, 1);
+		assert_eq!(stats.variables()["monitor.stats.matches_24h"], "1");
+	}
+}
@@ -0,0 +1,261 @@
+//! Ordering of notification dispatch.
+//!
+//! Blocks are handed to the trigger handler in order, but each block is handled by its own
+//! task, so the notifications of consecutive blocks can interleave. When ordering is
+//! enabled with `--notification-ordering` (`NOTIFICATION_ORDERING`), every block reserves a
+//! turn per ordering key (the network, or the network and monitor) when it is handed over,
+//! and only dispatches its notifications once the previous block holding the same key is
+//! done. Trigger condition filtering still runs concurrently.
+
+use std::{collections::HashMap, str::FromStr, sync::Mutex};
+use tokio::sync::oneshot;
+
+use crate::models::{MonitorMatch, ProcessedBlock};
+
+/// Ordering guarantee of notification dispatch
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum NotificationOrdering {
+	/// Notifications are dispatched as soon as their block is filtered
+	#[default]
+	None,
+	/// Notifications of a network are dispatched in block order
+	Network,
+	/// Notifications of a monitor on a network are dispatched in block order
+	Monitor,
+}
+
+impl FromStr for NotificationOrdering {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s.to_lowercase().as_str() {
+			"" | "none" => Ok(Self::None),
+			"network" => Ok(Self::Network),
+			"monitor" => Ok(Self::Monitor),
+			other => Err(format!(
+				"Invalid notification ordering '{}', expected none, network or monitor",
+				other
+			)),
+		}
+	}
+}
+
+/// A reserved turn: waits for the previous holder and releases the next one when dropped
+struct Turn {
+	previous: Option<oneshot::Receiver<()>>,
+	_done: oneshot::Sender<()>,
+}
+
+/// Turns reserved by a block, released when dropped
+pub struct DispatchTurns {
+	ordering: NotificationOrdering,
+	network_slug: String,
+	turns: HashMap<String, Turn>,
+}
+
+impl DispatchTurns {
+	/// Waits until the previous blocks sharing the ordering key of a match are dispatched
+	///
+	/// # Arguments
+	/// * `monitor_match` - The match about to be dispatched
+	pub async fn wait(&mut self, monitor_match: &MonitorMatch) {
+		let Some(key) = ordering_key(
+			self.ordering,
+			&self.network_slug,
+			&monitor_match.monitor().name,
+		) else {
+			return;
+		};
+		if let Some(previous) = self
+			.turns
+			.get_mut(&key)
+			.and_then(|turn| turn.previous.take())
+		{
+			// An error means the previous block was dropped (e.g. shutdown), which releases it
+			let _ = previous.await;
+		}
+	}
+}
+
+/// Hands out dispatch turns to blocks in the order they are processed
+pub struct NotificationSequencer {
+	ordering: NotificationOrdering,
+	tails: Mutex<HashMap<String, oneshot::Receiver<()>>>,
+}
+
+impl NotificationSequencer {
+	/// Creates a new sequencer
+	///
+	/// # Arguments
+	/// * `ordering` - Ordering guarantee to enforce
+	pub fn new(ordering: NotificationOrdering) -> Self {
+		Self {
+			ordering,
+			tails: Mutex::new(HashMap::new()),
+		}
+	}
+
+	/// Reserves the dispatch turns of a block
+	///
+	/// Must be called in block order, before the block is handed to its own task.
+	///
+	/// # Arguments
+	/// * `block` - The processed block
+	///
+	/// # Returns
+	/// The turns of the block, to keep until its notifications are dispatched
+	pub fn reserve(&self, block: &ProcessedBlock) -> DispatchTurns {
+		let mut keys = block
+			.processing_results
+			.iter()
+			.filter_map(|m| ordering_key(self.ordering, &block.network_slug, &m.monitor().name))
+			.collect::<Vec<_>>();
+		keys.sort();
+		keys.dedup();
+
+		let mut tails = match self.tails.lock() {
+			Ok(guard) => guard,
+			Err(poisoned) => poisoned.into_inner(),
+		};
+		let turns = keys
+			.into_iter()
+			.map(|key| {
+				let (done, next) = oneshot::channel();
+				let previous = tails.insert(key.clone(), next);
+				(
+					key,
+					Turn {
+						previous,
+						_done: done,
+					},
+				)
+			})
+			.collect();
+
+		DispatchTurns {
+			ordering: self.ordering,
+			network_slug: block.network_slug.clone(),
+			turns,
+		}
+	}
+}
+
+fn ordering_key(
+	ordering: NotificationOrdering,
+	network_slug: &str,
+	monitor_name: &str,
+) -> Option<String> {
+	match ordering {
+		NotificationOrdering::None => None,
+		NotificationOrdering::Network => Some(network_slug.to_string()),
+		NotificationOrdering::Monitor => Some(format!("{}|{}", network_slug, monitor_name)),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		models::{EVMMonitorMatch, MatchConditions},
+		utils::tests::builders::evm::{monitor::MonitorBuilder, transaction::TransactionBuilder},
+	};
+	use std::{sync::Arc, time::Duration};
+
+	fn create_block(block_number: u64, monitor_names: &[&str]) -> ProcessedBlock {
+		ProcessedBlock {
+			network_slug: "ethereum_mainnet".to_string(),
+			block_number,
+			processing_results: monitor_names
+				.iter()
+				.map(|name| {
+					MonitorMatch::EVM(Box::new(EVMMonitorMatch {
+						monitor: MonitorBuilder::new().name(name).build(),
+						transaction: TransactionBuilder::new().build(),
+						receipt: None,
+						logs: None,
+						network_slug: "ethereum_mainnet".to_string(),
+						matched_on: MatchConditions::default(),
+						matched_on_audit: Vec::new(),
+						matched_on_args: None,
+						block_timestamp: None,
+						trace: None,
+					}))
+				})
+				.collect(),
+		}
+	}
+
+	async fn dispatch_order(ordering: NotificationOrdering) -> Vec<u64> {
+		let sequencer = NotificationSequencer::new(ordering);
+		let dispatched = Arc::new(Mutex::new(Vec::new()));
+
+		let mut handles = Vec::new();
+		for (block_number, delay) in [(1, 50), (2, 0)] {
+			let block = create_block(block_number, &["monitor"]);
+			let mut turns = sequencer.reserve(&block);
+			let dispatched = dispatched.clone();
+			handles.push(tokio::spawn(async move {
+				// Simulates a slow trigger condition on the first block
+				tokio::time::sleep(Duration::from_millis(delay)).await;
+				for monitor_match in &block.processing_results {
+					turns.wait(monitor_match).await;
+					dispatched.lock().unwrap().push(block.block_number);
+				}
+			}));
+		}
+		for handle in handles {
+			handle.await.unwrap();
+		}
+
+		let order = dispatched.lock().unwrap().clone();
+		order
+	}
+
+	#[test]
+	fn test_notification_ordering_from_str() {
+		assert_eq!("none".parse(), Ok(NotificationOrdering::None));
+		assert_eq!("Network".parse(), Ok(NotificationOrdering::Network));
+		assert_eq!("monitor".parse(), Ok(NotificationOrdering::Monitor));
+		assert!("block".parse::<NotificationOrdering>().is_err());
+	}
+
+	#[tokio::test]
+	async fn test_sequencer_orders_dispatch() {
+		assert_eq!(dispatch_order(NotificationOrdering::None).await, vec![2, 1]);
+		assert_eq!(
+			dispatch_order(NotificationOrdering::Network).await,
+			vec![1, 2]
+		);
+		assert_eq!(
+			dispatch_order(NotificationOrdering::Monitor).await,
+			vec![1, 2]
+		);
+	}
+
+	#[tokio::test]
+	async fn test_sequencer_monitor_ordering_is_per_monitor() {
+		let sequencer = NotificationSequencer::new(NotificationOrdering::Monitor);
+		let first = create_block(1, &["monitor_a"]);
+		let _first_turns = sequencer.reserve(&first);
+
+		// A later block of another monitor is not held back by the pending block
+		let second = create_block(2, &["monitor_b"]);
+		let mut turns = sequencer.reserve(&second);
+		tokio::time::timeout(
+			Duration::from_millis(100),
+			turns.wait(&second.processing_results[0]),
+		)
+		.await
+		.unwrap();
+
+		// A later block of the same monitor waits for it
+		let third = create_block(3, &["monitor_a"]);
+		let mut turns = sequencer.reserve(&third);
+		assert!(tokio::time::timeout(
+			Duration::from_millis(100),
+			turns.wait(&third.processing_results[0]),
+		)
+		.await
+		.is_err());
+	}
+}
@@ -0,0 +1,162 @@
+//! Owners of the monitored addresses.
+//!
+//! A monitor can map its addresses to owners (`owners`), so that the notification of a match
+//! mentions the team owning the affected contract (`owners.*`) and can be routed to its own
+//! triggers. The address of a match is the monitored address that triggered its first matched
+//! condition on EVM, or the address of the monitor when it watches a single address on the
+//! network of the match.
+
+use serde_json::{json, Value as JsonValue};
+
+use crate::models::{AddressOwner, MonitorMatch};
+
+/// Returns the owned address of a match and its owner
+///
+/// # Arguments
+/// * `monitor_match` - The match
+///
+/// # Returns
+/// The address and its owner, or `None` if the address of the match has no owner
+pub fn match_owner(monitor_match: &MonitorMatch) -> Option<(String, &AddressOwner)> {
+	let monitor = monitor_match.monitor();
+	if monitor.owners.is_empty() {
+		return None;
+	}
+
+	let mut candidates: Vec<String> = match monitor_match {
+		MonitorMatch::EVM(evm_match) => evm_match
+			.matched_on_audit
+			.iter()
+			.filter_map(|audit| audit.address.clone())
+			.collect(),
+		_ => Vec::new(),
+	};
+	let addresses = monitor.addresses_for_network(monitor_match.network_slug());
+	if let [address] = addresses.as_slice() {
+		candidates.push(address.address.clone());
+	}
+
+	candidates
+		.into_iter()
+		.find_map(|address| monitor.owner_of(&address).map(|owner| (address, owner)))
+}
+
+/// Returns the triggers a match is sent to
+///
+/// # Arguments
+/// * `monitor_match` - The match
+///
+/// # Returns
+/// The triggers of the owner of the address of the match if it has any, otherwise the
+/// triggers of the monitor
+pub fn match_triggers(monitor_match: &MonitorMatch) -> &[String] {
+	match match_owner(monitor_match) {
+		Some((_, owner)) if !owner.triggers.is_empty() => &owner.triggers,
+		_ => &monitor_match.monitor().triggers,
+	}
+}
+
+/// Returns the template data of the owner of a match
+///
+/// # Arguments
+/// * `monitor_match` - The match
+///
+/// # Returns
+/// The `name`, `mention` and `address` of the owner, or `None` if the match has no owner
+pub fn owner_template_json(monitor_match: &MonitorMatch) -> Option<JsonValue> {
+	let (address, owner) = match_owner(monitor_match)?;
+	let mut data = json!({
+		"name": owner.name,
+		"address": address,
+	});
+	if let Some(mention) = &owner.mention {
+		data["mention"] = json!(mention);
+	}
+	Some(data)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		models::{ConditionAudit, EVMMonitorMatch, MatchConditions, Monitor},
+		utils::tests::builders::evm::{monitor::MonitorBuilder, transaction::TransactionBuilder},
+	};
+
+	const VAULT: &str = "0x0000000000000000000000000000000000000001";
+	const TOKEN: &str = "0x0000000000000000000000000000000000000002";
+
+	fn evm_match(monitor: Monitor, audit_address: Option<&str>) -> MonitorMatch {
+		MonitorMatch::EVM(Box::new(EVMMonitorMatch {
+			monitor,
+			transaction: TransactionBuilder::new().build(),
+			receipt: None,
+			logs: None,
+			network_slug: "ethereum_mainnet".to_string(),
+			matched_on: MatchConditions::default(),
+			matched_on_audit: audit_address
+				.map(|address| ConditionAudit {
+					kind: "event".to_string(),
+					address: Some(address.to_string()),
+					..Default::default()
+				})
+				.into_iter()
+				.collect(),
+			matched_on_args: None,
+			block_timestamp: None,
+			trace: None,
+		}))
+	}
+
+	#[test]
+	fn test_match_owner_routing() {
+		let monitor = MonitorBuilder::new()
+			.addresses(vec![VAULT.to_string(), TOKEN.to_string()])
+			.triggers(vec!["default_slack".to_string()])
+			.owner("Vaults", vec![VAULT], Some("@vaults-oncall"))
+			.owner_triggers(vec!["vaults_slack".to_string()])
+			.owner("Tokens", vec![TOKEN], None)
+			.build();
+
+		// Addresses are compared case-insensitively
+		let vault_match = evm_match(monitor.clone(), Some(&VAULT.to_uppercase()));
+		assert_eq!(match_triggers(&vault_match), ["vaults_slack".to_string()]);
+		assert_eq!(
+			owner_template_json(&vault_match),
+			Some(json!({
+				"name": "Vaults",
+				"mention": "@vaults-oncall",
+				"address": VAULT.to_uppercase(),
+			}))
+		);
+
+		// Owners without triggers keep the triggers of the monitor
+		let token_match = evm_match(monitor.clone(), Some(TOKEN));
+		assert_eq!(match_triggers(&token_match), ["default_slack".to_string()]);
+		assert_eq!(
+			owner_template_json(&token_match).unwrap()["name"],
+			json!("Tokens")
+		);
+
+		// The address of a match is unknown without audit when several addresses are watched
+		let unknown_match = evm_match(monitor, None);
+		assert!(owner_template_json(&unknown_match).is_none());
+		assert_eq!(
+			match_triggers(&unknown_match),
+			["default_slack".to_string()]
+		);
+	}
+
+	#[test]
+	fn test_match_owner_single_address() {
+		let monitor = MonitorBuilder::new()
+			.address(VAULT)
+			.owner("Vaults", vec![VAULT], None)
+			.build();
+		let (address, owner) = match_owner(&evm_match(monitor, None))
+			.map(|(address, owner)| (address, owner.clone()))
+			.unwrap();
+		assert_eq!(address, VAULT);
+		assert_eq!(owner.name, "Vaults");
+	}
+}
@@ -0,0 +1,339 @@
+//! Pending notifications.
+//!
+//! The notifications of a block are dispatched by a task of their own, where they wait for the
+//! turn of their block (see [`NotificationSequencer`](super::NotificationSequencer)) and for a
+//! slot of the dispatch queue (see [`PriorityDispatchQueue`](super::PriorityDispatchQueue)).
+//! [`PendingNotifications`] keeps track of the matches waiting there, and of the blocks handed
+//! over to the triggers whose matches are not queued yet, so that the panic hook can flush them
+//! before aborting: the waiting matches are written to a JSON lines file in the format of the
+//! match journal, which is dispatched again by [`resume_pending_notifications`] on the next
+//! start, and every network is checkpointed at its last block whose notifications are either
+//! dispatched or written.
+
+use std::{
+	collections::{BTreeMap, BTreeSet, HashMap},
+	io::Write,
+	path::{Path, PathBuf},
+	sync::{Arc, Mutex, MutexGuard, TryLockError},
+};
+
+use crate::{
+	models::MonitorMatch,
+	services::trigger::{
+		read_replay_matches, replay_matches, JournalEntry, ReplayOptions, ReplayResult,
+		TriggerExecutionServiceTrait,
+	},
+};
+
+/// File of the pending notifications, in the data directory
+pub const PENDING_NOTIFICATIONS_FILE: &str = "pending_notifications.jsonl";
+
+#[derive(Debug, Default)]
+struct NetworkProgress {
+	/// Last block handed over to the triggers
+	last_block: Option<u64>,
+	/// Blocks handed over to the triggers whose matches are not queued yet
+	unqueued: BTreeSet<u64>,
+}
+
+#[derive(Debug, Default)]
+struct PendingState {
+	next_id: u64,
+	matches: BTreeMap<u64, JournalEntry>,
+	networks: HashMap<String, NetworkProgress>,
+}
+
+/// Matches waiting to be dispatched and blocks whose matches are not queued yet
+#[derive(Debug, Default)]
+pub struct PendingNotifications {
+	path: Option<PathBuf>,
+	state: Mutex<PendingState>,
+}
+
+/// Block handed over to the triggers, until its matches are queued
+pub struct HandedOverBlock {
+	pending: Arc<PendingNotifications>,
+	network_slug: String,
+	block_number: u64,
+}
+
+impl Drop for HandedOverBlock {
+	fn drop(&mut self) {
+		if let Some(progress) = self
+			.pending
+			.lock_state()
+			.networks
+			.get_mut(&self.network_slug)
+		{
+			progress.unqueued.remove(&self.block_number);
+		}
+	}
+}
+
+/// Matches waiting for their turn or a dispatch slot, until dispatched
+pub struct QueuedMatches {
+	pending: Arc<PendingNotifications>,
+	ids: Vec<u64>,
+}
+
+impl Drop for QueuedMatches {
+	fn drop(&mut self) {
+		let mut state = self.pending.lock_state();
+		for id in &self.ids {
+			state.matches.remove(id);
+		}
+	}
+}
+
+impl PendingNotifications {
+	/// Creates the pending notifications, written to a file by [`Self::persist_blocking`]
+	///
+	/// # Arguments
+	/// * `path` - JSON lines file of the pending notifications
+	pub fn with_storage(path: PathBuf) -> Self {
+		Self {
+			path: Some(path),
+			state: Mutex::new(PendingState::default()),
+		}
+	}
+
+	/// Records that a block is handed over to the triggers
+	///
+	/// Must be called in block order, before the block is handed to its own task.
+	///
+	/// # Arguments
+	/// * `network_slug` - Network of the block
+	/// * `block_number` - Number of the block
+	///
+	/// # Returns
+	/// The block, to keep until its matches are queued
+	pub fn hand_over(self: &Arc<Self>, network_slug: &str, block_number: u64) -> HandedOverBlock {
+		let mut state = self.lock_state();
+		let progress = state.networks.entry(network_slug.to_string()).or_default();
+		progress.last_block = progress.last_block.max(Some(block_number));
+		progress.unqueued.insert(block_number);
+		HandedOverBlock {
+			pending: self.clone(),
+			network_slug: network_slug.to_string(),
+			block_number,
+		}
+	}
+
+	/// Records matches waiting to be dispatched
+	///
+	/// # Arguments
+	/// * `network_slug` - Network of the matches
+	/// * `matches` - The matches
+	///
+	/// # Returns
+	/// The matches, to keep until they are dispatched
+	pub fn queue(self: &Arc<Self>, network_slug: &str, matches: &[MonitorMatch]) -> QueuedMatches {
+		let mut state = self.lock_state();
+		let mut ids = Vec::with_capacity(matches.len());
+		for monitor_match in matches {
+			let id = state.next_id;
+			state.next_id += 1;
+			state
+				.matches
+				.insert(id, JournalEntry::new(monitor_match, network_slug, false));
+			ids.push(id);
+		}
+		QueuedMatches {
+			pending: self.clone(),
+			ids,
+		}
+	}
+
+	/// Returns the number of matches waiting to be dispatched
+	pub fn len(&self) -> usize {
+		self.lock_state().matches.len()
+	}
+
+	/// Returns whether no match is waiting to be dispatched
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	/// Returns the last block of every network whose notifications are dispatched or pending
+	///
+	/// The blocks handed over after a block whose matches are not queued yet are not included,
+	/// as the matches of that block would be lost.
+	pub fn checkpoints(&self) -> BTreeMap<String, u64> {
+		checkpoints(&self.lock_state())
+	}
+
+	/// Appends the pending notifications to their file without awaiting, failing rather than
+	/// waiting for the lock
+	///
+	/// Used by the panic hook, which cannot rely on the runtime nor on the locks held by the
+	/// panicking thread.
+	///
+	/// # Returns
+	/// The number of matches written and the checkpoints of the networks, as returned by
+	/// [`Self::checkpoints`]
+	pub fn persist_blocking(&self) -> Result<(usize, BTreeMap<String, u64>), std::io::Error> {
+		let state = match self.state.try_lock() {
+			Ok(guard) => guard,
+			Err(TryLockError::Poisoned(poisoned)) => poisoned.into_inner(),
+			Err(TryLockError::WouldBlock) => {
+				return Err(std::io::Error::new(
+					std::io::ErrorKind::WouldBlock,
+					"The pending notifications are locked",
+				))
+			}
+		};
+		let checkpoints = checkpoints(&state);
+		if state.matches.is_empty() {
+			return Ok((0, checkpoints));
+		}
+		let Some(path) = &self.path else {
+			return Err(std::io::Error::other(
+				"No file is set for the pending notifications",
+			));
+		};
+
+		let mut lines = String::new();
+		for entry in state.matches.values() {
+			lines.push_str(&serde_json::to_string(entry)?);
+			lines.push('\n');
+		}
+		if let Some(parent) = path.parent() {
+			std::fs::create_dir_all(parent)?;
+		}
+		let mut file = std::fs::OpenOptions::new()
+			.create(true)
+			.append(true)
+			.open(path)?;
+		file.write_all(lines.as_bytes())?;
+		file.sync_all()?;
+		Ok((state.matches.len(), checkpoints))
+	}
+
+	fn lock_state(&self) -> MutexGuard<'_, PendingState> {
+		match self.state.lock() {
+			Ok(state) => state,
+			Err(poisoned) => poisoned.into_inner(),
+		}
+	}
+}
+
+fn checkpoints(state: &PendingState) -> BTreeMap<String, u64> {
+	state
+		.networks
+		.iter()
+		.filter_map(|(network_slug, progress)| {
+			let checkpoint = match progress.unqueued.first() {
+				Some(first) => first.checked_sub(1)?,
+				None => progress.last_block?,
+			};
+			Some((network_slug.clone(), checkpoint))
+		})
+		.collect()
+}
+
+/// Dispatches the notifications left pending by an aborted process, then deletes their file
+///
+/// # Arguments
+/// * `path` - JSON lines file of the pending notifications
+/// * `trigger_service` - Service responsible for executing triggers
+///
+/// # Returns
+/// The number of dispatched and failed matches, `None` if no notification was pending
+pub async fn resume_pending_notifications<T: TriggerExecutionServiceTrait + Send + Sync>(
+	path: &Path,
+	trigger_service: &T,
+) -> Result<Option<ReplayResult>, anyhow::Error> {
+	if !path.exists() {
+		return Ok(None);
+	}
+	let matches = read_replay_matches(path, &ReplayOptions::default()).await?;
+	let result = replay_matches(matches, trigger_service).await?;
+	tokio::fs::remove_file(path).await?;
+	Ok(Some(result))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		models::{EVMMonitorMatch, MatchConditions},
+		utils::tests::builders::evm::{monitor::MonitorBuilder, transaction::TransactionBuilder},
+	};
+
+	fn evm_match(monitor_name: &str) -> MonitorMatch {
+		MonitorMatch::EVM(Box::new(EVMMonitorMatch {
+			monitor: MonitorBuilder::new().name(monitor_name).build(),
+			transaction: TransactionBuilder::new().build(),
+			receipt: None,
+			logs: None,
+			network_slug: "ethereum_mainnet".to_string(),
+			matched_on: MatchConditions::default(),
+			matched_on_audit: Vec::new(),
+			matched_on_args: None,
+			block_timestamp: None,
+			trace: None,
+		}))
+	}
+
+	#[test]
+	fn test_checkpoints_stop_before_unqueued_blocks() {
+		let pending = Arc::new(PendingNotifications::default());
+		let first = pending.hand_over("ethereum_mainnet", 10);
+		let second = pending.hand_over("ethereum_mainnet", 11);
+		let _third = pending.hand_over("ethereum_mainnet", 12);
+
+		// The matches of block 10 are not queued yet
+		drop(second);
+		assert_eq!(
+			pending.checkpoints(),
+			BTreeMap::from([("ethereum_mainnet".to_string(), 9)])
+		);
+
+		let queued = pending.queue("ethereum_mainnet", &[evm_match("transfers")]);
+		drop(first);
+		assert_eq!(
+			pending.checkpoints(),
+			BTreeMap::from([("ethereum_mainnet".to_string(), 11)])
+		);
+		assert_eq!(pending.len(), 1);
+		drop(queued);
+		assert!(pending.is_empty());
+	}
+
+	#[tokio::test]
+	async fn test_persist_blocking() {
+		let temp_dir = tempfile::tempdir().unwrap();
+		let path = temp_dir
+			.path()
+			.join("data")
+			.join(PENDING_NOTIFICATIONS_FILE);
+		let pending = Arc::new(PendingNotifications::with_storage(path.clone()));
+
+		// Nothing is written while no match is pending
+		let block = pending.hand_over("ethereum_mainnet", 5);
+		drop(block);
+		let (written, checkpoints) = pending.persist_blocking().unwrap();
+		assert_eq!(written, 0);
+		assert_eq!(checkpoints.get("ethereum_mainnet"), Some(&5));
+		assert!(!path.exists());
+
+		let _queued = pending.queue(
+			"ethereum_mainnet",
+			&[evm_match("transfers"), evm_match("approvals")],
+		);
+		{
+			let _guard = pending.state.lock().unwrap();
+			assert!(pending.persist_blocking().is_err());
+		}
+		assert_eq!(pending.persist_blocking().unwrap().0, 2);
+
+		let matches = read_replay_matches(&path, &ReplayOptions::default())
+			.await
+			.unwrap();
+		let monitors: Vec<_> = matches
+			.iter()
+			.map(|monitor_match| monitor_match.monitor().name.clone())
+			.collect();
+		assert_eq!(monitors, vec!["transfers", "approvals"]);
+	}
+}
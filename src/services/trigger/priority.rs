@@ -0,0 +1,284 @@
+//! Priority of notification dispatch.
+//!
+//! When blocks produce matches faster than notifications are sent, dispatches queue up. If
+//! the `NOTIFICATION_MAX_CONCURRENCY` setting is set, at most that many notifications are
+//! dispatched at once and, when one completes, its slot goes to the waiting match with the
+//! highest score: the severity level of its monitor, raised by one level for every
+//! [`AGING_INTERVAL`] elapsed since the match was detected. Aging protects low severity alerts
+//! from starving behind a steady stream of critical ones. The queue is owned by the trigger
+//! handler, and its depth is reported by the control socket and the state dumps.
+
+use std::{
+	sync::Mutex,
+	time::{Duration, Instant},
+};
+use tokio::sync::oneshot;
+
+use crate::models::{MonitorMatch, MonitorSeverity};
+
+/// Default maximum number of notifications dispatched at once, `0` for no limit
+pub const DEFAULT_MAX_CONCURRENCY: usize = 0;
+
+/// Waiting time raising the priority of a match by one severity level
+pub const AGING_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Priority metadata of a match
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MatchPriority {
+	/// Severity of the monitor that matched
+	pub severity: MonitorSeverity,
+	/// When the match was detected
+	pub detected_at: Instant,
+}
+
+impl MatchPriority {
+	/// Creates the priority of a match from the severity of its monitor
+	///
+	/// # Arguments
+	/// * `monitor_match` - The match
+	/// * `detected_at` - When the match was detected
+	pub fn of(monitor_match: &MonitorMatch, detected_at: Instant) -> Self {
+		Self {
+			severity: monitor_match.monitor().severity,
+			detected_at,
+		}
+	}
+
+	/// Returns the time-weighted score of the match, higher is dispatched first
+	///
+	/// # Arguments
+	/// * `now` - The current time
+	pub fn score(&self, now: Instant) -> f64 {
+		let age = now.saturating_duration_since(self.detected_at);
+		f64::from(self.severity.level()) + age.as_secs_f64() / AGING_INTERVAL.as_secs_f64()
+	}
+}
+
+struct Waiter {
+	priority: MatchPriority,
+	sequence: u64,
+	ready: oneshot::Sender<()>,
+}
+
+struct QueueState {
+	available: usize,
+	next_sequence: u64,
+	waiters: Vec<Waiter>,
+}
+
+/// Limits concurrent notification dispatches, granting free slots by priority
+pub struct PriorityDispatchQueue {
+	state: Mutex<QueueState>,
+}
+
+/// A dispatch slot, released to the next waiting match when dropped
+pub struct DispatchPermit<'a> {
+	queue: &'a PriorityDispatchQueue,
+}
+
+impl Drop for DispatchPermit<'_> {
+	fn drop(&mut self) {
+		self.queue.release();
+	}
+}
+
+/// A slot being waited for, released if it was granted after the wait was cancelled
+struct PendingPermit<'a> {
+	queue: &'a PriorityDispatchQueue,
+	ready: Option<oneshot::Receiver<()>>,
+}
+
+impl Drop for PendingPermit<'_> {
+	fn drop(&mut self) {
+		if let Some(mut ready) = self.ready.take() {
+			ready.close();
+			if ready.try_recv().is_ok() {
+				self.queue.release();
+			}
+		}
+	}
+}
+
+impl PriorityDispatchQueue {
+	/// Creates a new queue
+	///
+	/// # Arguments
+	/// * `max_concurrency` - Maximum number of notifications dispatched at once, `0` for no
+	///   limit
+	pub fn new(max_concurrency: usize) -> Self {
+		Self {
+			state: Mutex::new(QueueState {
+				available: if max_concurrency == 0 {
+					usize::MAX
+				} else {
+					max_concurrency
+				},
+				next_sequence: 0,
+				waiters: Vec::new(),
+			}),
+		}
+	}
+
+	/// Waits for a dispatch slot
+	///
+	/// # Arguments
+	/// * `priority` - Priority of the match about to be dispatched
+	///
+	/// # Returns
+	/// The slot, to keep until the notification is dispatched
+	pub async fn acquire(&self, priority: MatchPriority) -> DispatchPermit<'_> {
+		let ready = {
+			let mut state = self.lock();
+			if state.available > 0 && state.waiters.is_empty() {
+				state.available -= 1;
+				return DispatchPermit { queue: self };
+			}
+			let (sender, receiver) = oneshot::channel();
+			let sequence = state.next_sequence;
+			state.next_sequence += 1;
+			state.waiters.push(Waiter {
+				priority,
+				sequence,
+				ready: sender,
+			});
+			receiver
+		};
+
+		let mut pending = PendingPermit {
+			queue: self,
+			ready: Some(ready),
+		};
+		if let Some(ready) = pending.ready.as_mut() {
+			// The queue outlives its waiters, so the slot is always granted
+			let _ = ready.await;
+		}
+		pending.ready = None;
+		DispatchPermit { queue: self }
+	}
+
+	/// Returns the number of matches waiting for a dispatch slot
+	pub fn pending(&self) -> usize {
+		self.lock()
+			.waiters
+			.iter()
+			.filter(|waiter| !waiter.ready.is_closed())
+			.count()
+	}
+
+	/// Hands a released slot to the waiting match with the highest score
+	fn release(&self) {
+		let mut state = self.lock();
+		let now = Instant::now();
+		loop {
+			let next = state
+				.waiters
+				.iter()
+				.enumerate()
+				.max_by(|(_, a), (_, b)| {
+					a.priority
+						.score(now)
+						.total_cmp(&b.priority.score(now))
+						.then(b.sequence.cmp(&a.sequence))
+				})
+				.map(|(index, _)| index);
+			let Some(index) = next else {
+				state.available += 1;
+				return;
+			};
+			// A waiter whose wait was cancelled does not take the slot
+			if state.waiters.swap_remove(index).ready.send(()).is_ok() {
+				return;
+			}
+		}
+	}
+
+	fn lock(&self) -> std::sync::MutexGuard<'_, QueueState> {
+		match self.state.lock() {
+			Ok(guard) => guard,
+			Err(poisoned) => poisoned.into_inner(),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::sync::Arc;
+
+	fn priority(severity: MonitorSeverity, age: Duration) -> MatchPriority {
+		MatchPriority {
+			severity,
+			detected_at: Instant::now() - age,
+		}
+	}
+
+	#[test]
+	fn test_score_ages() {
+		let now = Instant::now();
+		let fresh_critical = priority(MonitorSeverity::Critical, Duration::ZERO);
+		let old_low = priority(MonitorSeverity::Low, AGING_INTERVAL * 4);
+
+		assert!(
+			fresh_critical.score(now) > priority(MonitorSeverity::High, Duration::ZERO).score(now)
+		);
+		assert!(old_low.score(now) > fresh_critical.score(now));
+	}
+
+	#[tokio::test]
+	async fn test_acquire_by_priority() {
+		let queue = Arc::new(PriorityDispatchQueue::new(1));
+		let permit = queue
+			.acquire(priority(MonitorSeverity::Medium, Duration::ZERO))
+			.await;
+
+		let (order_tx, mut order_rx) = tokio::sync::mpsc::unbounded_channel();
+		let mut handles = Vec::new();
+		for (name, severity) in [
+			("low", MonitorSeverity::Low),
+			("critical", MonitorSeverity::Critical),
+			("high", MonitorSeverity::High),
+		] {
+			let queue = queue.clone();
+			let order_tx = order_tx.clone();
+			handles.push(tokio::spawn(async move {
+				let _permit = queue.acquire(priority(severity, Duration::ZERO)).await;
+				order_tx.send(name).unwrap();
+			}));
+			tokio::task::yield_now().await;
+		}
+		while queue.pending() < 3 {
+			tokio::task::yield_now().await;
+		}
+
+		drop(permit);
+		for handle in handles {
+			handle.await.unwrap();
+		}
+		let order: Vec<_> = std::iter::from_fn(|| order_rx.try_recv().ok()).collect();
+		assert_eq!(order, vec!["critical", "high", "low"]);
+	}
+
+	#[tokio::test]
+	async fn test_cancelled_wait_releases_slot() {
+		let queue = PriorityDispatchQueue::new(1);
+		let permit = queue
+			.acquire(priority(MonitorSeverity::Low, Duration::ZERO))
+			.await;
+
+		let cancelled = tokio::time::timeout(
+			Duration::from_millis(10),
+			queue.acquire(priority(MonitorSeverity::Critical, Duration::ZERO)),
+		)
+		.await;
+		assert!(cancelled.is_err());
+		assert_eq!(queue.pending(), 0);
+
+		drop(permit);
+		let _permit = tokio::time::timeout(
+			Duration::from_millis(100),
+			queue.acquire(priority(MonitorSeverity::Low, Duration::ZERO)),
+		)
+		.await
+		.unwrap();
+	}
+}
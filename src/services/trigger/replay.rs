@@ -0,0 +1,362 @@
+//! Replay of journaled matches.
+//!
+//! The matches recorded in the match journal can be sent through their triggers again, e.g.
+//! when a notification channel was misconfigured during an incident. The matches journaled
+//! in a time range are replayed in journal order, to the triggers they were originally sent
+//! to or to a single replacement trigger. Matches of monitors in observe mode were never
+//! notified and are not replayed, and the matches of a batched monitor are replayed one by
+//! one.
+
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, NaiveTime};
+use std::{
+	collections::HashMap,
+	path::Path,
+	sync::atomic::{AtomicUsize, Ordering},
+};
+use tokio::io::{AsyncBufReadExt, BufReader};
+
+use crate::{
+	models::{Monitor, MonitorMatch, ScriptLanguage},
+	services::{
+		filter::handle_match,
+		trigger::{journal_files, JournalEntry, TriggerError, TriggerExecutionServiceTrait},
+	},
+};
+
+/// Selection of the journaled matches to replay
+#[derive(Debug, Clone, Default)]
+pub struct ReplayOptions {
+	/// Unix timestamp (milliseconds) of the first match to replay
+	pub since: Option<i64>,
+	/// Unix timestamp (milliseconds) after which matches are not replayed
+	pub until: Option<i64>,
+	/// Network of the matches to replay, all networks if unset
+	pub network: Option<String>,
+	/// Trigger the matches are sent to instead of their own triggers
+	pub trigger: Option<String>,
+}
+
+impl ReplayOptions {
+	/// Returns whether a journaled match is replayed
+	///
+	/// # Arguments
+	/// * `entry` - The journaled match
+	pub fn selects(&self, entry: &JournalEntry) -> bool {
+		!entry.observed
+			&& self.since.is_none_or(|since| entry.recorded_at >= since)
+			&& self.until.is_none_or(|until| entry.recorded_at <= until)
+			&& self
+				.network
+				.as_ref()
+				.is_none_or(|network| *network == entry.network)
+	}
+}
+
+/// Result of a replay
+#[derive(Debug, Clone, Default)]
+pub struct ReplayResult {
+	/// Number of matches replayed
+	pub replayed: usize,
+	/// Number of replayed matches whose triggers failed
+	pub failed: usize,
+}
+
+/// Parses a bound of the replayed time range
+///
+/// # Arguments
+/// * `value` - An RFC 3339 timestamp, or a date (`YYYY-MM-DD`) for midnight UTC
+///
+/// # Returns
+/// The Unix timestamp (milliseconds) of the bound
+pub fn parse_replay_time(value: &str) -> Result<i64, String> {
+	if let Ok(timestamp) = DateTime::parse_from_rfc3339(value) {
+		return Ok(timestamp.timestamp_millis());
+	}
+	NaiveDate::parse_from_str(value, "%Y-%m-%d")
+		.map(|date| date.and_time(NaiveTime::MIN).and_utc().timestamp_millis())
+		.map_err(|_| {
+			format!(
+				"Invalid replay time '{}', expected an RFC 3339 timestamp or a YYYY-MM-DD date",
+				value
+			)
+		})
+}
+
+/// Reads the journaled matches selected for a replay
+///
+/// The rotated files of the journal are read first, then the file the matches are appended
+/// to. Each file is read line by line, and lines that cannot be parsed (e.g. a line cut by a
+/// crash) are skipped with a warning.
+///
+/// # Arguments
+/// * `path` - Path of the match journal
+/// * `options` - Selection of the matches
+///
+/// # Returns
+/// The selected matches in journal order, retargeted to the replacement trigger if any
+pub async fn read_replay_matches(
+	path: &Path,
+	options: &ReplayOptions,
+) -> Result<Vec<MonitorMatch>, anyhow::Error> {
+	let files = journal_files(path);
+	if files.is_empty() {
+		return Err(anyhow::anyhow!("No match journal at {}", path.display()));
+	}
+
+	let mut matches = Vec::new();
+	for file in files {
+		let read_error = |e: std::io::Error| {
+			anyhow::anyhow!("Failed to read the match journal {}: {}", file.display(), e)
+		};
+		let mut lines =
+			BufReader::new(tokio::fs::File::open(&file).await.map_err(read_error)?).lines();
+		let mut line_number = 0;
+		while let Some(line) = lines.next_line().await.map_err(read_error)? {
+			line_number += 1;
+			if line.trim().is_empty() {
+				continue;
+			}
+			let entry = match serde_json::from_str::<JournalEntry>(&line) {
+				Ok(entry) => entry,
+				Err(e) => {
+					tracing::warn!(
+						"Skipping line {} of the match journal {}: {}",
+						line_number,
+						file.display(),
+						e
+					);
+					continue;
+				}
+			};
+			if !options.selects(&entry) {
+				continue;
+			}
+			let mut monitor_match = entry.monitor_match;
+			if let Some(trigger) = &options.trigger {
+				retarget(&mut monitor_match, trigger);
+			}
+			matches.push(monitor_match);
+		}
+	}
+	Ok(matches)
+}
+
+/// Sends journaled matches through their triggers again
+///
+/// # Arguments
+/// * `matches` - The matches, as returned by [`read_replay_matches`]
+/// * `trigger_service` - Service responsible for executing triggers
+///
+/// # Returns
+/// The number of replayed and failed matches
+pub async fn replay_matches<T: TriggerExecutionServiceTrait + Send + Sync>(
+	matches: Vec<MonitorMatch>,
+	trigger_service: &T,
+) -> Result<ReplayResult, anyhow::Error> {
+	// The scripts are those of the monitors as journaled, one set per monitor
+	let mut monitors: Vec<Monitor> = Vec::new();
+	for monitor_match in &matches {
+		let monitor = monitor_match.monitor();
+		if !monitors.iter().any(|known| known.name == monitor.name) {
+			monitors.push(monitor.clone());
+		}
+	}
+	let trigger_scripts = trigger_service.load_scripts(&monitors).await?;
+
+	let counting = CountingTriggerExecution::new(trigger_service);
+	let replayed = matches.len();
+	for monitor_match in matches {
+		handle_match(monitor_match, &counting, &trigger_scripts).await?;
+	}
+	Ok(ReplayResult {
+		replayed,
+		failed: counting.failures.load(Ordering::Relaxed),
+	})
+}
+
+/// Sends a match to a single trigger instead of those of its monitor and owners
+fn retarget(monitor_match: &mut MonitorMatch, trigger: &str) {
+	let monitor = monitor_match.monitor_mut();
+	monitor.triggers = vec![trigger.to_string()];
+	for owner in &mut monitor.owners {
+		owner.triggers.clear();
+	}
+}
+
+/// Trigger execution service counting the failed executions
+struct CountingTriggerExecution<'a, S> {
+	inner: &'a S,
+	failures: AtomicUsize,
+}
+
+impl<'a, S> CountingTriggerExecution<'a, S> {
+	fn new(inner: &'a S) -> Self {
+		Self {
+			inner,
+			failures: AtomicUsize::new(0),
+		}
+	}
+}
+
+#[async_trait]
+impl<S: TriggerExecutionServiceTrait + Send + Sync> TriggerExecutionServiceTrait
+	for CountingTriggerExecution<'_, S>
+{
+	async fn execute(
+		&self,
+		trigger_slugs: &[String],
+		variables: HashMap<String, String>,
+		monitor_match: &MonitorMatch,
+		trigger_scripts: &HashMap<String, (ScriptLanguage, String)>,
+	) -> Result<(), TriggerError> {
+		let result = self
+			.inner
+			.execute(trigger_slugs, variables, monitor_match, trigger_scripts)
+			.await;
+		if result.is_err() {
+			self.failures.fetch_add(1, Ordering::Relaxed);
+		}
+		result
+	}
+
+	async fn load_scripts(
+		&self,
+		monitors: &[Monitor],
+	) -> Result<HashMap<String, (ScriptLanguage, String)>, TriggerError> {
+		self.inner.load_scripts(monitors).await
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		models::{AddressOwner, EVMMonitorMatch, MatchConditions},
+		utils::tests::builders::evm::{monitor::MonitorBuilder, transaction::TransactionBuilder},
+	};
+
+	fn evm_match(monitor: Monitor, network: &str) -> MonitorMatch {
+		MonitorMatch::EVM(Box::new(EVMMonitorMatch {
+			monitor,
+			transaction: TransactionBuilder::new().build(),
+			receipt: None,
+			logs: None,
+			network_slug: network.to_string(),
+			matched_on: MatchConditions::default(),
+			matched_on_audit: Vec::new(),
+			matched_on_args: None,
+			block_timestamp: None,
+			trace: None,
+		}))
+	}
+
+	fn entry(recorded_at: i64, network: &str, observed: bool) -> JournalEntry {
+		let monitor = MonitorBuilder::new()
+			.name("transfers")
+			.triggers(vec!["slack_incident".to_string()])
+			.build();
+		let mut entry = JournalEntry::new(&evm_match(monitor, network), network, observed);
+		entry.recorded_at = recorded_at;
+		entry
+	}
+
+	#[test]
+	fn test_parse_replay_time() {
+		assert_eq!(parse_replay_time("1970-01-02"), Ok(86_400_000));
+		assert_eq!(parse_replay_time("1970-01-01T01:00:00+01:00"), Ok(0));
+		assert!(parse_replay_time("yesterday").is_err());
+	}
+
+	#[test]
+	fn test_replay_options_selects() {
+		let options = ReplayOptions {
+			since: Some(1_000),
+			until: Some(2_000),
+			network: Some("ethereum_mainnet".to_string()),
+			trigger: None,
+		};
+		assert!(options.selects(&entry(1_000, "ethereum_mainnet", false)));
+		assert!(options.selects(&entry(2_000, "ethereum_mainnet", false)));
+		assert!(!options.selects(&entry(999, "ethereum_mainnet", false)));
+		assert!(!options.selects(&entry(2_001, "ethereum_mainnet", false)));
+		assert!(!options.selects(&entry(1_500, "polygon_mainnet", false)));
+		// Observed matches were never notified
+		assert!(!options.selects(&entry(1_500, "ethereum_mainnet", true)));
+	}
+
+	#[tokio::test]
+	async fn test_read_replay_matches() {
+		let temp_dir = tempfile::tempdir().unwrap();
+		let path = temp_dir.path().join("match_journal.jsonl");
+		assert!(read_replay_matches(&path, &ReplayOptions::default())
+			.await
+			.is_err());
+
+		// The oldest match was rotated to another file
+		let rotated = temp_dir
+			.path()
+			.join("match_journal.20240501T120000000.jsonl");
+		let mut content = serde_json::to_string(&entry(1_000, "ethereum_mainnet", false)).unwrap();
+		content.push('\n');
+		std::fs::write(&rotated, content).unwrap();
+		let mut content = String::new();
+		for entry in [
+			entry(2_000, "ethereum_mainnet", false),
+			entry(3_000, "ethereum_mainnet", false),
+		] {
+			content.push_str(&serde_json::to_string(&entry).unwrap());
+			content.push('\n');
+		}
+		content.push_str("{\"recorded_at\":");
+		std::fs::write(&path, content).unwrap();
+
+		let matches = read_replay_matches(
+			&path,
+			&ReplayOptions {
+				since: Some(2_000),
+				..Default::default()
+			},
+		)
+		.await
+		.unwrap();
+		assert_eq!(matches.len(), 2);
+		assert_eq!(
+			matches[0].monitor().triggers,
+			vec!["slack_incident".to_string()]
+		);
+
+		let matches = read_replay_matches(
+			&path,
+			&ReplayOptions {
+				trigger: Some("slack_fixed".to_string()),
+				..Default::default()
+			},
+		)
+		.await
+		.unwrap();
+		assert_eq!(matches.len(), 3);
+		assert_eq!(
+			matches[0].monitor().triggers,
+			vec!["slack_fixed".to_string()]
+		);
+	}
+
+	#[test]
+	fn test_retarget_clears_owner_triggers() {
+		let mut monitor = MonitorBuilder::new()
+			.triggers(vec!["slack_incident".to_string()])
+			.build();
+		monitor.owners = vec![AddressOwner {
+			name: "treasury".to_string(),
+			triggers: vec!["treasury_pager".to_string()],
+			..Default::default()
+		}];
+		let mut monitor_match = evm_match(monitor, "ethereum_mainnet");
+
+		retarget(&mut monitor_match, "slack_fixed");
+		let monitor = monitor_match.monitor();
+		assert_eq!(monitor.triggers, vec!["slack_fixed".to_string()]);
+		assert!(monitor.owners[0].triggers.is_empty());
+	}
+}
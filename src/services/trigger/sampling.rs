@@ -0,0 +1,306 @@
+//! Sampling of notifications.
+//!
+//! Monitors with a `sampling` section match too often for every match to be notified. All of
+//! their matches are still journaled and counted, but only sampled matches are dispatched to
+//! the triggers. The notification of a sampled match carries the number of matches it stands
+//! for (`sampling.count`) and the number of matches of the monitor on the network since
+//! startup (`sampling.total`). Matches left out are counted by the
+//! `monitor_sampled_out_matches_total` metric. The sampling state is owned by the trigger
+//! handler dispatching the matches.
+
+use async_trait::async_trait;
+use std::{collections::HashMap, sync::Mutex};
+
+use crate::{
+	models::{Monitor, MonitorMatch, SamplingConfig, ScriptLanguage},
+	services::trigger::{TriggerError, TriggerExecutionServiceTrait},
+	utils::metrics::record_sampled_out_match,
+};
+
+/// Match counts a sampled notification stands for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SampleCounts {
+	/// The match and the matches left out since the previous notification
+	pub count: u64,
+	/// Matches of the monitor on the network since startup
+	pub total: u64,
+}
+
+impl SampleCounts {
+	/// Returns the template variables of the counts
+	pub fn variables(&self) -> HashMap<String, String> {
+		HashMap::from([
+			("sampling.count".to_string(), self.count.to_string()),
+			("sampling.total".to_string(), self.total.to_string()),
+		])
+	}
+}
+
+/// Outcome of the sampling of a match
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SampleDecision {
+	/// The monitor is not sampled, the match is notified
+	Unsampled,
+	/// The match is notified on behalf of several matches
+	Sampled(SampleCounts),
+	/// The match is left out
+	Skipped,
+}
+
+/// Sampling state of a monitor on a network
+#[derive(Debug, Default)]
+struct SampleState {
+	/// Matches left out since the previous notification
+	skipped: u64,
+	/// Matches since startup
+	total: u64,
+}
+
+/// Samples the matches of monitors with a `sampling` section
+#[derive(Default)]
+pub struct MatchSampler {
+	/// States keyed by monitor name and network slug
+	states: Mutex<HashMap<(String, String), SampleState>>,
+}
+
+impl MatchSampler {
+	/// Decides which matches of a block are notified
+	///
+	/// # Arguments
+	/// * `network` - Slug of the network of the block
+	/// * `matches` - The matches of the block about to be notified, in block order
+	///
+	/// # Returns
+	/// The decision for each match, in the order of `matches`
+	pub fn sample<'a>(
+		&self,
+		network: &str,
+		matches: impl IntoIterator<Item = &'a MonitorMatch>,
+	) -> Vec<SampleDecision> {
+		let matches: Vec<&MonitorMatch> = matches.into_iter().collect();
+		let mut block_counts: HashMap<&str, u64> = HashMap::new();
+		for monitor_match in &matches {
+			*block_counts
+				.entry(&monitor_match.monitor().name)
+				.or_default() += 1;
+		}
+
+		let mut states = match self.states.lock() {
+			Ok(states) => states,
+			Err(poisoned) => poisoned.into_inner(),
+		};
+		let mut notified_in_block: HashMap<&str, bool> = HashMap::new();
+		matches
+			.iter()
+			.map(|monitor_match| {
+				let monitor = monitor_match.monitor();
+				let Some(sampling) = &monitor.sampling else {
+					return SampleDecision::Unsampled;
+				};
+				let state = states
+					.entry((monitor.name.clone(), network.to_string()))
+					.or_default();
+				state.total += 1;
+
+				let decision = match sampling {
+					SamplingConfig::OneIn { n } => {
+						if (state.total - 1) % (*n).max(1) == 0 {
+							let count = state.skipped + 1;
+							state.skipped = 0;
+							SampleDecision::Sampled(SampleCounts {
+								count,
+								total: state.total,
+							})
+						} else {
+							state.skipped += 1;
+							SampleDecision::Skipped
+						}
+					}
+					SamplingConfig::FirstPerBlock => {
+						let notified = notified_in_block.entry(&monitor.name).or_default();
+						if *notified {
+							SampleDecision::Skipped
+						} else {
+							*notified = true;
+							// The first match stands for all the matches of the block
+							let count = block_counts[monitor.name.as_str()];
+							SampleDecision::Sampled(SampleCounts {
+								count,
+								total: state.total + count - 1,
+							})
+						}
+					}
+				};
+				if decision == SampleDecision::Skipped {
+					record_sampled_out_match(&monitor.name, network);
+				}
+				decision
+			})
+			.collect()
+	}
+}
+
+/// Trigger execution service adding the sampling variables to the notifications of a match
+pub struct SampledTriggerExecution<'a, S> {
+	inner: &'a S,
+	counts: SampleCounts,
+}
+
+impl<'a, S> SampledTriggerExecution<'a, S> {
+	/// Wraps a trigger execution service for a sampled match
+	///
+	/// # Arguments
+	/// * `inner` - The trigger execution service
+	/// * `counts` - The counts of the sampled match
+	pub fn new(inner: &'a S, counts: SampleCounts) -> Self {
+		Self { inner, counts }
+	}
+}
+
+#[async_trait]
+impl<S: TriggerExecutionServiceTrait + Send + Sync> TriggerExecutionServiceTrait
+	for SampledTriggerExecution<'_, S>
+{
+	async fn execute(
+		&self,
+		trigger_slugs: &[String],
+		mut variables: HashMap<String, String>,
+		monitor_match: &MonitorMatch,
+		trigger_scripts: &HashMap<String, (ScriptLanguage, String)>,
+	) -> Result<(), TriggerError> {
+		variables.extend(self.counts.variables());
+		self.inner
+			.execute(trigger_slugs, variables, monitor_match, trigger_scripts)
+			.await
+	}
+
+	async fn load_scripts(
+		&self,
+		monitors: &[Monitor],
+	) -> Result<HashMap<String, (ScriptLanguage, String)>, TriggerError> {
+		self.inner.load_scripts(monitors).await
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		models::{EVMMonitorMatch, MatchConditions},
+		utils::tests::builders::evm::{monitor::MonitorBuilder, transaction::TransactionBuilder},
+	};
+
+	fn evm_match(monitor: &Monitor) -> MonitorMatch {
+		MonitorMatch::EVM(Box::new(EVMMonitorMatch {
+			monitor: monitor.clone(),
+			transaction: TransactionBuilder::new().build(),
+			receipt: None,
+			logs: None,
+			network_slug: "ethereum_mainnet".to_string(),
+			matched_on: MatchConditions::default(),
+			matched_on_audit: Vec::new(),
+			matched_on_args: None,
+			block_timestamp: None,
+			trace: None,
+		}))
+	}
+
+	fn sampled(count: u64, total: u64) -> SampleDecision {
+		SampleDecision::Sampled(SampleCounts { count, total })
+	}
+
+	#[test]
+	fn test_sample_one_in() {
+		let sampler = MatchSampler::default();
+		let monitor = MonitorBuilder::new()
+			.name("analytics")
+			.sampling(SamplingConfig::OneIn { n: 3 })
+			.build();
+		let unsampled = MonitorBuilder::new().name("live").build();
+
+		let decisions = sampler.sample(
+			"ethereum_mainnet",
+			&[
+				evm_match(&monitor),
+				evm_match(&unsampled),
+				evm_match(&monitor),
+			],
+		);
+		assert_eq!(
+			decisions,
+			vec![
+				sampled(1, 1),
+				SampleDecision::Unsampled,
+				SampleDecision::Skipped
+			]
+		);
+
+		// The counts carry over blocks
+		let decisions = sampler.sample(
+			"ethereum_mainnet",
+			&[
+				evm_match(&monitor),
+				evm_match(&monitor),
+				evm_match(&monitor),
+			],
+		);
+		assert_eq!(
+			decisions,
+			vec![
+				SampleDecision::Skipped,
+				sampled(3, 4),
+				SampleDecision::Skipped
+			]
+		);
+
+		// Networks are sampled independently
+		let decisions = sampler.sample("base_mainnet", &[evm_match(&monitor)]);
+		assert_eq!(decisions, vec![sampled(1, 1)]);
+	}
+
+	#[test]
+	fn test_sample_first_per_block() {
+		let sampler = MatchSampler::default();
+		let monitor = MonitorBuilder::new()
+			.name("analytics")
+			.sampling(SamplingConfig::FirstPerBlock)
+			.build();
+		let other = MonitorBuilder::new()
+			.name("other")
+			.sampling(SamplingConfig::FirstPerBlock)
+			.build();
+
+		let decisions = sampler.sample(
+			"ethereum_mainnet",
+			&[
+				evm_match(&monitor),
+				evm_match(&other),
+				evm_match(&monitor),
+				evm_match(&monitor),
+			],
+		);
+		assert_eq!(
+			decisions,
+			vec![
+				sampled(3, 3),
+				sampled(1, 1),
+				SampleDecision::Skipped,
+				SampleDecision::Skipped
+			]
+		);
+
+		let decisions = sampler.sample("ethereum_mainnet", &[evm_match(&monitor)]);
+		assert_eq!(decisions, vec![sampled(1, 4)]);
+	}
+
+	#[test]
+	fn test_sample_counts_variables() {
+		let variables = SampleCounts {
+			count: 10,
+			total: 250,
+		}
+		.variables();
+		assert_eq!(variables["sampling.count"], "10");
+		assert_eq!(variables["sampling.total"], "250");
+	}
+}
@@ -5,6 +5,7 @@
 use crate::models::MonitorMatch;
 use anyhow::Context;
 use async_trait::async_trait;
+use serde_json::{Map, Value as JsonValue};
 use std::{any::Any, process::Stdio, time::Duration};
 use tokio::{io::AsyncWriteExt, time::timeout};
 
@@ -31,6 +32,24 @@ pub trait ScriptExecutor: Send + Sync + Any {
 		args: Option<&[String]>,
 		from_custom_notification: bool,
 	) -> Result<bool, anyhow::Error>;
+
+	/// Executes the script with the given MonitorMatch input and returns the JSON object it
+	/// prints on its last line of output.
+	///
+	/// # Arguments
+	/// * `input` - A MonitorMatch instance containing the data to be processed by the script
+	/// * `timeout_ms` - The timeout for the script execution in milliseconds
+	/// * `args` - Additional arguments passed to the script
+	///
+	/// # Returns
+	/// * `Result<Map<String, JsonValue>, anyhow::Error>` - The object printed by the script or
+	///   an error
+	async fn enrich(
+		&self,
+		input: MonitorMatch,
+		timeout_ms: &u32,
+		args: Option<&[String]>,
+	) -> Result<Map<String, JsonValue>, anyhow::Error>;
 }
 
 /// Executes Python scripts using the python3 interpreter.
@@ -69,6 +88,23 @@ impl ScriptExecutor for PythonScriptExecutor {
 
 		process_command(cmd, &input_json, timeout_ms, from_custom_notification).await
 	}
+
+	async fn enrich(
+		&self,
+		input: MonitorMatch,
+		timeout_ms: &u32,
+		args: Option<&[String]>,
+	) -> Result<Map<String, JsonValue>, anyhow::Error> {
+		run_enrichment(
+			"python3",
+			"-c",
+			&self.script_content,
+			input,
+			timeout_ms,
+			args,
+		)
+		.await
+	}
 }
 
 /// Executes JavaScript scripts using the Node.js runtime.
@@ -107,6 +143,15 @@ impl ScriptExecutor for JavaScriptScriptExecutor {
 			.with_context(|| "Failed to spawn node process")?;
 		process_command(cmd, &input_json, timeout_ms, from_custom_notification).await
 	}
+
+	async fn enrich(
+		&self,
+		input: MonitorMatch,
+		timeout_ms: &u32,
+		args: Option<&[String]>,
+	) -> Result<Map<String, JsonValue>, anyhow::Error> {
+		run_enrichment("node", "-e", &self.script_content, input, timeout_ms, args).await
+	}
 }
 
 /// Executes Bash shell scripts.
@@ -147,6 +192,15 @@ impl ScriptExecutor for BashScriptExecutor {
 
 		process_command(cmd, &input_json, timeout_ms, from_custom_notification).await
 	}
+
+	async fn enrich(
+		&self,
+		input: MonitorMatch,
+		timeout_ms: &u32,
+		args: Option<&[String]>,
+	) -> Result<Map<String, JsonValue>, anyhow::Error> {
+		run_enrichment("sh", "-c", &self.script_content, input, timeout_ms, args).await
+	}
 }
 
 /// Processes the output from script execution.
@@ -203,12 +257,100 @@ pub fn process_script_output(
 	}
 }
 
+/// Processes the output from the execution of an enrichment script.
+///
+/// # Arguments
+/// * `output` - The process output containing stdout, stderr, and status
+///
+/// # Returns
+/// * `Result<Map<String, JsonValue>, anyhow::Error>` - Returns the parsed object or error
+///
+/// # Errors
+/// Returns an error if:
+/// * The script execution was not successful (non-zero exit code)
+/// * The last line of output is not a JSON object
+/// * The script produced no output
+pub fn process_enrichment_output(
+	output: std::process::Output,
+) -> Result<Map<String, JsonValue>, anyhow::Error> {
+	if !output.status.success() {
+		let error_message = String::from_utf8_lossy(&output.stderr).to_string();
+		return Err(anyhow::anyhow!(
+			"Script execution failed: {}",
+			error_message
+		));
+	}
+
+	let stdout = String::from_utf8_lossy(&output.stdout);
+	let last_line = stdout
+		.lines()
+		.rev()
+		.map(str::trim)
+		.find(|line| !line.is_empty())
+		.ok_or_else(|| anyhow::anyhow!("Script produced no output"))?;
+
+	match serde_json::from_str(last_line) {
+		Ok(JsonValue::Object(object)) => Ok(object),
+		_ => Err(anyhow::anyhow!(
+			"Last line of output is not a JSON object: {}",
+			last_line
+		)),
+	}
+}
+
+/// Runs an enrichment script and returns the object it prints
+///
+/// # Arguments
+/// * `program` - Interpreter running the script
+/// * `flag` - Flag of the interpreter for an inline script
+/// * `script_content` - Content of the script
+/// * `input` - The match passed to the script
+/// * `timeout_ms` - The timeout for the script execution in milliseconds
+/// * `args` - Additional arguments passed to the script
+async fn run_enrichment(
+	program: &str,
+	flag: &str,
+	script_content: &str,
+	input: MonitorMatch,
+	timeout_ms: &u32,
+	args: Option<&[String]>,
+) -> Result<Map<String, JsonValue>, anyhow::Error> {
+	let combined_input = serde_json::json!({
+		"monitor_match": input,
+		"args": args
+	});
+	let input_json = serde_json::to_string(&combined_input)
+		.with_context(|| "Failed to serialize monitor match and arguments")?;
+
+	let cmd = tokio::process::Command::new(program)
+		.arg(flag)
+		.arg(script_content)
+		.stdin(Stdio::piped())
+		.stdout(Stdio::piped())
+		.stderr(Stdio::piped())
+		.spawn()
+		.with_context(|| format!("Failed to spawn {} process", program))?;
+
+	let output = run_command(cmd, &input_json, timeout_ms).await?;
+	process_enrichment_output(output)
+}
+
 async fn process_command(
-	mut cmd: tokio::process::Child,
+	cmd: tokio::process::Child,
 	input_json: &str,
 	timeout_ms: &u32,
 	from_custom_notification: bool,
 ) -> Result<bool, anyhow::Error> {
+	let output = run_command(cmd, input_json, timeout_ms).await?;
+	process_script_output(output, from_custom_notification)
+}
+
+/// Writes the input to a spawned script and waits for its output
+async fn run_command(
+	mut cmd: tokio::process::Child,
+	input_json: &str,
+	timeout_ms: &u32,
+) -> Result<std::process::Output, anyhow::Error> {
 	if let Some(mut stdin) = cmd.stdin.take() {
 		stdin
 			.write_all(input_json.as_bytes())
@@ -228,9 +370,7 @@ async fn process_command(
 
 	match timeout(timeout_duration, cmd.wait_with_output()).await {
 		Ok(result) => {
-			let output =
-				result.map_err(|e| anyhow::anyhow!("Failed to wait for script output: {}", e))?;
-			process_script_output(output, from_custom_notification)
+			result.map_err(|e| anyhow::anyhow!("Failed to wait for script output: {}", e))
 		}
 		Err(_) => Err(anyhow::anyhow!("Script execution timed out")),
 	}
@@ -305,7 +445,10 @@ mod tests {
 				events: vec![],
 				transactions: vec![],
 			},
+			matched_on_audit: Vec::new(),
 			matched_on_args: None,
+			block_timestamp: None,
+			trace: None,
 		}))
 	}
 
@@ -367,6 +510,57 @@ print(result)
 		}
 	}
 
+	#[tokio::test]
+	async fn test_python_script_executor_enrich() {
+		let script_content = r#"
+import sys
+import json
+
+data = json.loads(sys.stdin.read())
+print("debugging...")
+print(json.dumps({
+    "network": data["monitor_match"]["EVM"]["network_slug"],
+    "team": data["args"][0],
+    "labels": {"risk": 3},
+}))
+"#;
+
+		let executor = PythonScriptExecutor {
+			script_content: script_content.to_string(),
+		};
+
+		let input = create_mock_monitor_match();
+		let args = vec!["security".to_string()];
+		let enrichment = executor.enrich(input, &1000, Some(&args)).await.unwrap();
+		assert_eq!(
+			JsonValue::Object(enrichment),
+			serde_json::json!({
+				"network": "evm_mainnet",
+				"team": "security",
+				"labels": {"risk": 3},
+			})
+		);
+	}
+
+	#[tokio::test]
+	async fn test_bash_script_executor_enrich_invalid_output() {
+		let executor = BashScriptExecutor {
+			script_content: "cat > /dev/null; echo true".to_string(),
+		};
+
+		let input = create_mock_monitor_match();
+		let err = executor.enrich(input, &1000, None).await.unwrap_err();
+		assert!(err
+			.to_string()
+			.contains("Last line of output is not a JSON object: true"));
+
+		let executor = BashScriptExecutor {
+			script_content: "cat > /dev/null; echo '{}' ; exit 1".to_string(),
+		};
+		let input = create_mock_monitor_match();
+		assert!(executor.enrich(input, &1000, None).await.is_err());
+	}
+
 	#[tokio::test]
 	async fn test_python_script_executor_multiple_prints() {
 		let script_content = r#"
@@ -3,7 +3,7 @@
 //! Provides functionality to execute triggers with variable substitution
 //! and notification delivery. Manages trigger lookup and execution flow.
 
-use std::{collections::HashMap, path::Path};
+use std::{collections::HashMap, path::Path, time::Instant};
 
 use anyhow::Context;
 use async_trait::async_trait;
@@ -11,8 +11,14 @@ use async_trait::async_trait;
 use crate::{
 	models::{Monitor, MonitorMatch, ScriptLanguage, TriggerTypeConfig},
 	repositories::{TriggerRepositoryTrait, TriggerService},
-	services::{notification::NotificationService, trigger::error::TriggerError},
-	utils::normalize_string,
+	services::{
+		notification::NotificationService,
+		trigger::{
+			delivery_report::{send_delivery_report, DeliveryReport},
+			error::TriggerError,
+		},
+	},
+	utils::{count_retries, normalize_string},
 };
 
 /// Trait for executing triggers
@@ -63,6 +69,11 @@ impl<T: TriggerRepositoryTrait> TriggerExecutionService<T> {
 			notification_service,
 		}
 	}
+
+	/// Returns the service used to look up the triggers
+	pub fn trigger_service(&self) -> &TriggerService<T> {
+		&self.trigger_service
+	}
 }
 
 #[async_trait]
@@ -94,13 +105,35 @@ impl<T: TriggerRepositoryTrait + Send + Sync> TriggerExecutionServiceTrait
 			let trigger = self
 				.trigger_service
 				.get(trigger_slug)
-				.ok_or_else(|| TriggerError::not_found(trigger_slug.to_string(), None, None))?;
+				.ok_or_else(|| TriggerError::not_found(trigger_slug.to_string(), None, None))?
+				.with_parameters(monitor_match.monitor());
+
+			let mut variables = variables.clone();
+			variables.extend(trigger.parameter_variables(monitor_match.monitor()));
 
-			self.notification_service
-				.execute(&trigger, &variables, monitor_match, trigger_scripts)
-				.await
-				// We remove logging capability here since we're logging it further down
-				.map_err(|e| TriggerError::execution_error_without_log(e.to_string(), None, None))
+			let started = Instant::now();
+			let (result, retries) = count_retries(self.notification_service.execute(
+				&trigger,
+				&variables,
+				monitor_match,
+				trigger_scripts,
+			))
+			.await;
+			if let Some(delivery_report) = &trigger.delivery_report {
+				send_delivery_report(
+					delivery_report,
+					DeliveryReport::new(
+						trigger_slug,
+						monitor_match,
+						&result,
+						started.elapsed(),
+						retries,
+					),
+				);
+			}
+
+			// We remove logging capability here since we're logging it further down
+			result.map_err(|e| TriggerError::execution_error_without_log(e.to_string(), None, None))
 		});
 
 		let results = join_all(futures).await;
@@ -151,12 +184,16 @@ impl<T: TriggerRepositoryTrait + Send + Sync> TriggerExecutionServiceTrait
 
 		for monitor in monitors {
 			// Skip monitors without trigger conditions
-			if monitor.trigger_conditions.is_empty() && monitor.triggers.is_empty() {
+			if monitor.trigger_conditions.is_empty() && monitor.routed_triggers().next().is_none() {
 				continue;
 			}
 
 			// For each monitor, we'll load all its trigger condition scripts
 			for condition in &monitor.trigger_conditions {
+				// Inline expressions are evaluated in-process and have no script to load
+				if condition.is_expression() {
+					continue;
+				}
 				let script_path = Path::new(&condition.script_path);
 
 				// Read the script content
@@ -176,8 +213,28 @@ impl<T: TriggerRepositoryTrait + Send + Sync> TriggerExecutionServiceTrait
 				);
 			}
 
+			// The post-processing script enriching the matches before they are notified
+			if let Some(post_processing) = &monitor.post_processing {
+				let content = tokio::fs::read_to_string(&post_processing.script_path)
+					.await
+					.with_context(|| {
+						format!(
+							"Failed to read post-processing script file: {}",
+							post_processing.script_path
+						)
+					})?;
+				scripts.insert(
+					format!(
+						"{}|{}",
+						normalize_string(&monitor.name),
+						post_processing.script_path
+					),
+					(post_processing.language.clone(), content),
+				);
+			}
+
 			// For each trigger, we'll load the script
-			for trigger in &monitor.triggers {
+			for trigger in monitor.routed_triggers() {
 				let trigger_config =
 					self.trigger_service.get(trigger.as_str()).ok_or_else(|| {
 						TriggerError::configuration_error(
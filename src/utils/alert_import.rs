@@ -0,0 +1,888 @@
+//! Import of third-party alert definitions.
+//!
+//! Teams moving to self-hosted monitoring can convert their existing alerts into monitor and
+//! trigger configurations instead of rewriting them by hand. Two export formats are
+//! supported:
+//! - OpenZeppelin Defender Sentinels (`eventConditions`, `functionConditions`, `txCondition`
+//!   and `notificationChannels`)
+//! - Tenderly alerts (`expressions` and `delivery_channels`)
+//!
+//! A file may hold a single alert, an array of alerts or an object with an `items` array, and
+//! the format of each alert is detected from its fields unless given explicitly. Networks are
+//! resolved through their chain id to the configured EVM networks.
+//!
+//! The conversion is best effort: every part of an alert that cannot be converted is reported
+//! as a warning, and a monitor that lost a part of its alert is written paused so that it can
+//! be reviewed before it notifies anyone.
+
+use anyhow::Context;
+use regex::Regex;
+use serde_json::{Map, Value};
+use std::{
+	collections::{BTreeMap, BTreeSet, HashMap},
+	path::{Path, PathBuf},
+	str::FromStr,
+};
+
+use crate::{
+	models::{
+		AddressWithSpec, BlockChainType, ContractSpec, EVMContractSpec, EventCondition,
+		FunctionCondition, MatchConditions, Monitor, Network, NotificationMessage, SecretValue,
+		TransactionCondition, TransactionStatus, Trigger, TriggerType, TriggerTypeConfig,
+	},
+	services::filter::expression_variables,
+	utils::{HttpClientConfig, RetryConfig},
+};
+
+/// Chain ids of the network names of Defender
+const DEFENDER_NETWORKS: &[(&str, u64)] = &[
+	("mainnet", 1),
+	("sepolia", 11155111),
+	("holesky", 17000),
+	("matic", 137),
+	("amoy", 80002),
+	("arbitrum", 42161),
+	("arbitrum-nova", 42170),
+	("arbitrum-sepolia", 421614),
+	("optimism", 10),
+	("optimism-sepolia", 11155420),
+	("base", 8453),
+	("base-sepolia", 84532),
+	("bsc", 56),
+	("bsctest", 97),
+	("avalanche", 43114),
+	("fuji", 43113),
+	("linea", 59144),
+	("linea-sepolia", 59141),
+	("zksync", 324),
+	("zksync-sepolia", 300),
+	("scroll", 534352),
+	("scroll-sepolia", 534351),
+	("mantle", 5000),
+	("mantle-sepolia", 5003),
+	("celo", 42220),
+	("alfajores", 44787),
+	("sonic", 146),
+	("unichain-sepolia", 1301),
+];
+
+/// Transaction fields of Defender and their names in transaction conditions
+const DEFENDER_TRANSACTION_FIELDS: &[(&str, &str)] = &[
+	("gasPrice", "gas_price"),
+	("gasUsed", "gas_used"),
+	("gasLimit", "gas_limit"),
+	("maxFeePerGas", "max_fee_per_gas"),
+	("maxPriorityFeePerGas", "max_priority_fee_per_gas"),
+	("transactionIndex", "transaction_index"),
+];
+
+/// Format of exported alert definitions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlertFormat {
+	/// OpenZeppelin Defender Sentinel
+	DefenderSentinel,
+	/// Tenderly alert
+	Tenderly,
+}
+
+impl FromStr for AlertFormat {
+	type Err = String;
+
+	fn from_str(value: &str) -> Result<Self, Self::Err> {
+		match value.to_lowercase().as_str() {
+			"defender" | "sentinel" | "defender-sentinel" => Ok(AlertFormat::DefenderSentinel),
+			"tenderly" => Ok(AlertFormat::Tenderly),
+			_ => Err(format!(
+				"Unknown alert format '{}', expected 'defender' or 'tenderly'",
+				value
+			)),
+		}
+	}
+}
+
+impl AlertFormat {
+	/// Returns the format of an alert from its fields
+	fn detect(alert: &Value) -> Option<Self> {
+		let has = |fields: &[&str]| fields.iter().any(|field| alert.get(field).is_some());
+		if has(&[
+			"eventConditions",
+			"functionConditions",
+			"txCondition",
+			"notificationChannels",
+		]) {
+			Some(AlertFormat::DefenderSentinel)
+		} else if has(&["expressions", "delivery_channels"]) {
+			Some(AlertFormat::Tenderly)
+		} else {
+			None
+		}
+	}
+}
+
+/// Monitors and triggers converted from alert definitions
+#[derive(Debug, Clone, Default)]
+pub struct ImportedConfig {
+	/// Converted monitors
+	pub monitors: Vec<Monitor>,
+	/// Converted triggers, keyed by slug
+	pub triggers: BTreeMap<String, Trigger>,
+	/// Parts of the alerts that could not be converted
+	pub warnings: Vec<String>,
+	/// Environment variables the converted triggers read their secrets from
+	pub secret_variables: BTreeSet<String>,
+}
+
+/// Monitor being converted from an alert
+#[derive(Default)]
+struct Draft {
+	name: String,
+	paused: bool,
+	chain_ids: Vec<u64>,
+	addresses: Vec<AddressWithSpec>,
+	conditions: MatchConditions,
+	channels: Vec<Value>,
+	/// Whether a part of the alert could not be converted
+	lossy: bool,
+	warnings: Vec<String>,
+}
+
+impl Draft {
+	fn warn(&mut self, message: String) {
+		self.lossy = true;
+		self.warnings
+			.push(format!("Alert '{}': {}", self.name, message));
+	}
+}
+
+/// Converts exported alert definitions into monitors and triggers
+///
+/// # Arguments
+/// * `content` - JSON export of the alerts
+/// * `format` - Format of the alerts, detected per alert if unset
+/// * `networks` - Configured networks, keyed by slug
+///
+/// # Returns
+/// The converted monitors and triggers, and the warnings of the conversion
+pub fn convert_alerts(
+	content: &str,
+	format: Option<AlertFormat>,
+	networks: &HashMap<String, Network>,
+) -> Result<ImportedConfig, anyhow::Error> {
+	let value: Value = serde_json::from_str(content).context("Invalid alert export")?;
+	let alerts = match value {
+		Value::Array(alerts) => alerts,
+		Value::Object(mut object) => match object.remove("items") {
+			Some(Value::Array(alerts)) => alerts,
+			_ => vec![Value::Object(object)],
+		},
+		_ => anyhow::bail!("Invalid alert export: expected an object or an array"),
+	};
+
+	let mut imported = ImportedConfig::default();
+	for (index, alert) in alerts.iter().enumerate() {
+		let Some(format) = format.or_else(|| AlertFormat::detect(alert)) else {
+			imported.warnings.push(format!(
+				"Alert #{}: unknown format, skipped (set the format explicitly)",
+				index + 1
+			));
+			continue;
+		};
+		let draft = match format {
+			AlertFormat::DefenderSentinel => convert_sentinel(alert, index),
+			AlertFormat::Tenderly => convert_tenderly(alert, index),
+		};
+		let Some(draft) = draft else {
+			imported.warnings.push(format!(
+				"Alert #{}: not a {:?} alert, skipped",
+				index + 1,
+				format
+			));
+			continue;
+		};
+		add_monitor(&mut imported, draft, networks);
+	}
+	Ok(imported)
+}
+
+/// Resolves the networks and triggers of a draft and adds its monitor
+fn add_monitor(
+	imported: &mut ImportedConfig,
+	mut draft: Draft,
+	networks: &HashMap<String, Network>,
+) {
+	let mut monitor_networks = Vec::new();
+	for chain_id in draft.chain_ids.clone() {
+		match network_slug(chain_id, networks) {
+			Some(slug) => monitor_networks.push(slug),
+			None => draft.warn(format!(
+				"no EVM network with chain id {} is configured",
+				chain_id
+			)),
+		}
+	}
+	if monitor_networks.is_empty() {
+		draft.warn("the monitor has no network".to_string());
+	}
+
+	let mut triggers = Vec::new();
+	for channel in std::mem::take(&mut draft.channels) {
+		match channel_trigger(&channel) {
+			Ok((slug, trigger, secret_variables)) => {
+				for (variable, exported) in secret_variables {
+					if !exported {
+						draft.warnings.push(format!(
+							"Alert '{}': the export has no secret for trigger '{}', set {}",
+							draft.name, slug, variable
+						));
+					}
+					imported.secret_variables.insert(variable);
+				}
+				if !triggers.contains(&slug) {
+					triggers.push(slug.clone());
+				}
+				imported.triggers.entry(slug).or_insert(trigger);
+			}
+			Err(message) => draft.warn(message),
+		}
+	}
+
+	let expressions = draft
+		.conditions
+		.functions
+		.iter()
+		.map(|function| &function.expression)
+		.chain(
+			draft
+				.conditions
+				.events
+				.iter()
+				.map(|event| &event.expression),
+		)
+		.chain(
+			draft
+				.conditions
+				.transactions
+				.iter()
+				.map(|transaction| &transaction.expression),
+		)
+		.flatten()
+		.filter(|expression| expression_variables(expression).is_none())
+		.cloned()
+		.collect::<Vec<_>>();
+	for expression in expressions {
+		draft.warn(format!("expression '{}' could not be parsed", expression));
+	}
+
+	imported.monitors.push(Monitor {
+		name: draft.name,
+		networks: monitor_networks,
+		paused: draft.paused || draft.lossy,
+		addresses: draft.addresses,
+		match_conditions: draft.conditions,
+		triggers,
+		..Default::default()
+	});
+	imported.warnings.append(&mut draft.warnings);
+}
+
+/// Converts a Defender Sentinel
+fn convert_sentinel(alert: &Value, index: usize) -> Option<Draft> {
+	let alert = alert.as_object()?;
+	let mut draft = Draft {
+		name: string_field(alert, &["name"]).unwrap_or_else(|| format!("Sentinel {}", index + 1)),
+		paused: alert
+			.get("paused")
+			.and_then(Value::as_bool)
+			.unwrap_or(false),
+		..Default::default()
+	};
+	if let Some(kind) = string_field(alert, &["type"]) {
+		if !kind.eq_ignore_ascii_case("block") {
+			draft.warn(format!("{} sentinels are not supported", kind));
+		}
+	}
+
+	match string_field(alert, &["network"]) {
+		Some(network) => match DEFENDER_NETWORKS
+			.iter()
+			.find(|(name, _)| name.eq_ignore_ascii_case(&network))
+		{
+			Some((_, chain_id)) => draft.chain_ids.push(*chain_id),
+			None => draft.warn(format!("unknown Defender network '{}'", network)),
+		},
+		None => draft.warn("the sentinel has no network".to_string()),
+	}
+
+	let contract_spec = match string_field(alert, &["abi"]) {
+		Some(abi) => match serde_json::from_str::<EVMContractSpec>(&abi) {
+			Ok(spec) => Some(ContractSpec::EVM(spec)),
+			Err(e) => {
+				draft.warn(format!("the ABI could not be parsed: {}", e));
+				None
+			}
+		},
+		None => None,
+	};
+	draft.addresses = string_list(alert.get("addresses"))
+		.into_iter()
+		.map(|address| AddressWithSpec {
+			address,
+			contract_spec: contract_spec.clone(),
+		})
+		.collect();
+
+	for condition in array(alert.get("eventConditions")) {
+		match condition.get("eventSignature").and_then(Value::as_str) {
+			Some(signature) => draft.conditions.events.push(EventCondition {
+				signature: signature.to_string(),
+				expression: optional_expression(condition.get("expression")),
+			}),
+			None => draft.warn("event condition without signature".to_string()),
+		}
+	}
+	for condition in array(alert.get("functionConditions")) {
+		match condition.get("functionSignature").and_then(Value::as_str) {
+			Some(signature) => draft.conditions.functions.push(FunctionCondition {
+				signature: signature.to_string(),
+				expression: optional_expression(condition.get("expression")),
+			}),
+			None => draft.warn("function condition without signature".to_string()),
+		}
+	}
+	if let Some(condition) = optional_expression(alert.get("txCondition")) {
+		let (status, expression) = convert_transaction_condition(&condition);
+		if expression
+			.as_deref()
+			.is_some_and(|expression| expression.contains("status"))
+		{
+			draft.warn(format!(
+				"the status in transaction condition '{}' must be set as the condition status",
+				condition
+			));
+		}
+		draft
+			.conditions
+			.transactions
+			.push(TransactionCondition { status, expression });
+	}
+
+	draft.channels = array(alert.get("notificationChannels")).cloned().collect();
+	Some(draft)
+}
+
+/// Converts a Defender transaction condition into a status and an expression
+fn convert_transaction_condition(condition: &str) -> (TransactionStatus, Option<String>) {
+	let mut expression = condition.to_string();
+	for (field, variable) in DEFENDER_TRANSACTION_FIELDS {
+		let pattern = Regex::new(&format!(r"\b{}\b", field)).expect("valid field pattern");
+		expression = pattern.replace_all(&expression, *variable).into_owned();
+	}
+
+	// A status clause is only converted alone or joined by `and` to the rest of the condition
+	const STATUS: &str = r#"status\s*==\s*["'](success|failed|failure)["']"#;
+	let patterns = [
+		format!(r"(?i)^\s*{}\s*$", STATUS),
+		format!(r"(?i)^\s*{}\s+and\s+(?P<rest>.+)$", STATUS),
+		format!(r"(?i)^(?P<rest>.+?)\s+and\s+{}\s*$", STATUS),
+	];
+	for pattern in &patterns {
+		let pattern = Regex::new(pattern).expect("valid status pattern");
+		if let Some(captures) = pattern.captures(&expression) {
+			let status = if captures[1].eq_ignore_ascii_case("success") {
+				TransactionStatus::Success
+			} else {
+				TransactionStatus::Failure
+			};
+			let rest = captures
+				.name("rest")
+				.map(|rest| rest.as_str().trim().to_string());
+			return (status, rest);
+		}
+	}
+	(TransactionStatus::Any, Some(expression))
+}
+
+/// Converts a Tenderly alert
+fn convert_tenderly(alert: &Value, index: usize) -> Option<Draft> {
+	let alert = alert.as_object()?;
+	let mut draft = Draft {
+		name: string_field(alert, &["name"]).unwrap_or_else(|| format!("Alert {}", index + 1)),
+		paused: !alert
+			.get("enabled")
+			.and_then(Value::as_bool)
+			.unwrap_or(true),
+		..Default::default()
+	};
+	let mut status = None;
+
+	for expression in array(alert.get("expressions")) {
+		let kind = expression
+			.get("type")
+			.and_then(Value::as_str)
+			.unwrap_or_default();
+		let empty = Map::new();
+		let fields = expression
+			.get("expression")
+			.and_then(Value::as_object)
+			.unwrap_or(&empty);
+		match kind {
+			"network" => match fields.get("network_id").and_then(as_u64) {
+				Some(chain_id) => draft.chain_ids.push(chain_id),
+				None => draft.warn("network expression without network id".to_string()),
+			},
+			"contract_address" | "address" => match string_field(fields, &["address"]) {
+				Some(address) => draft.addresses.push(AddressWithSpec {
+					address,
+					contract_spec: None,
+				}),
+				None => draft.warn("address expression without address".to_string()),
+			},
+			"method_call" => {
+				match signature(fields, &["method_signature", "signature", "method_name"]) {
+					Ok(signature) => draft.conditions.functions.push(FunctionCondition {
+						signature,
+						expression: None,
+					}),
+					Err(name) => draft.warn(format!(
+						"method call '{}' has no signature with parameter types",
+						name
+					)),
+				}
+			}
+			"emitted_log" | "event_emitted" => {
+				match signature(fields, &["event_signature", "signature", "event_name"]) {
+					Ok(signature) => draft.conditions.events.push(EventCondition {
+						signature,
+						expression: None,
+					}),
+					Err(name) => draft.warn(format!(
+						"event '{}' has no signature with parameter types",
+						name
+					)),
+				}
+			}
+			"tx_status" => {
+				status = fields
+					.get("transaction_success")
+					.and_then(Value::as_bool)
+					.map(|success| {
+						if success {
+							TransactionStatus::Success
+						} else {
+							TransactionStatus::Failure
+						}
+					});
+			}
+			other => draft.warn(format!("'{}' expressions are not supported", other)),
+		}
+	}
+	if let Some(status) = status {
+		draft.conditions.transactions.push(TransactionCondition {
+			status,
+			expression: None,
+		});
+	}
+
+	draft.channels = array(alert.get("delivery_channels")).cloned().collect();
+	Some(draft)
+}
+
+/// Converts a notification channel into a trigger
+///
+/// Secrets are never written to the configuration: the trigger reads them from environment
+/// variables named after its slug, whose values are those of the export if it has them.
+///
+/// # Returns
+/// The slug and trigger, and the environment variables of its secrets with whether the
+/// export has their value
+fn channel_trigger(channel: &Value) -> Result<(String, Trigger, Vec<(String, bool)>), String> {
+	let Some(channel) = channel.as_object() else {
+		let id = channel
+			.as_str()
+			.map(str::to_string)
+			.unwrap_or_else(|| channel.to_string());
+		return Err(format!(
+			"notification channel '{}' is only referenced by id, create its trigger by hand",
+			id
+		));
+	};
+	let kind = string_field(channel, &["type", "channel_type"])
+		.unwrap_or_default()
+		.to_lowercase();
+	let name = string_field(channel, &["name", "label", "title", "id"])
+		.unwrap_or_else(|| format!("Imported {}", kind));
+	let slug = slugify(&name);
+	let config = ["config", "information", "configuration"]
+		.iter()
+		.find_map(|key| channel.get(*key).and_then(Value::as_object))
+		.unwrap_or(channel);
+
+	let mut secret_variables = Vec::new();
+	let mut secret = |keys: &[&str], suffix: &str| {
+		let variable = format!("{}_{}", slug.to_uppercase(), suffix);
+		secret_variables.push((variable.clone(), string_field(config, keys).is_some()));
+		SecretValue::Environment(variable)
+	};
+	let message = NotificationMessage {
+		title: "${monitor.name} triggered".to_string(),
+		body: "Transaction ${transaction.hash} matched ${monitor.name}".to_string(),
+		..Default::default()
+	};
+	let (trigger_type, config) = match kind.as_str() {
+		"slack" => (
+			TriggerType::Slack,
+			TriggerTypeConfig::Slack {
+				slack_url: secret(&["url", "webhookUrl", "webhook_url", "slackUrl"], "URL"),
+				message,
+				retry_policy: RetryConfig::default(),
+				http_client: HttpClientConfig::default(),
+			},
+		),
+		"discord" => (
+			TriggerType::Discord,
+			TriggerTypeConfig::Discord {
+				discord_url: secret(&["url", "webhookUrl", "webhook_url", "discordUrl"], "URL"),
+				message,
+				retry_policy: RetryConfig::default(),
+				http_client: HttpClientConfig::default(),
+			},
+		),
+		"webhook" => (
+			TriggerType::Webhook,
+			TriggerTypeConfig::Webhook {
+				url: secret(&["url", "webhookUrl", "webhook_url"], "URL"),
+				method: Some("POST".to_string()),
+				secret: None,
+				headers: None,
+				message,
+				payload_mode: Default::default(),
+				retry_policy: RetryConfig::default(),
+				http_client: HttpClientConfig::default(),
+			},
+		),
+		"telegram" => {
+			let Some(chat_id) = string_field(config, &["chatId", "chat_id"]) else {
+				return Err(format!("telegram channel '{}' has no chat id", name));
+			};
+			(
+				TriggerType::Telegram,
+				TriggerTypeConfig::Telegram {
+					token: secret(&["botToken", "bot_token", "token"], "TOKEN"),
+					chat_id,
+					disable_web_preview: None,
+					message,
+					retry_policy: RetryConfig::default(),
+					http_client: HttpClientConfig::default(),
+				},
+			)
+		}
+		other => {
+			return Err(format!(
+				"{} channel '{}' cannot be converted, create its trigger by hand",
+				other, name
+			))
+		}
+	};
+	Ok((
+		slug,
+		Trigger {
+			name,
+			trigger_type,
+			config,
+			parameters: BTreeMap::new(),
+			delivery_report: None,
+		},
+		secret_variables,
+	))
+}
+
+/// Writes the converted monitors and triggers into a configuration directory
+///
+/// Each monitor is written to its own file in `monitors/` and the triggers to a single file
+/// in `triggers/` named after the source. No file is written if any of them already exists.
+///
+/// # Arguments
+/// * `imported` - The converted configuration
+/// * `config_dir` - The configuration directory
+/// * `source` - Path of the imported export
+///
+/// # Returns
+/// The paths of the written files
+pub fn write_imported_config(
+	imported: &ImportedConfig,
+	config_dir: &Path,
+	source: &Path,
+) -> Result<Vec<PathBuf>, anyhow::Error> {
+	let mut files: Vec<(PathBuf, String)> = Vec::new();
+	for monitor in &imported.monitors {
+		let path = config_dir
+			.join("monitors")
+			.join(format!("{}.json", slugify(&monitor.name)));
+		if files.iter().any(|(known, _)| *known == path) {
+			anyhow::bail!("Several imported monitors are named '{}'", monitor.name);
+		}
+		files.push((path, serde_json::to_string_pretty(monitor)?));
+	}
+	if !imported.triggers.is_empty() {
+		let stem = source
+			.file_stem()
+			.map(|stem| stem.to_string_lossy().into_owned())
+			.unwrap_or_default();
+		let path = config_dir
+			.join("triggers")
+			.join(format!("imported_{}.json", slugify(&stem)));
+		files.push((path, serde_json::to_string_pretty(&imported.triggers)?));
+	}
+
+	if let Some((path, _)) = files.iter().find(|(path, _)| path.exists()) {
+		anyhow::bail!("{} already exists, nothing was imported", path.display());
+	}
+	for (path, content) in &files {
+		if let Some(parent) = path.parent() {
+			std::fs::create_dir_all(parent)?;
+		}
+		std::fs::write(path, content)
+			.with_context(|| format!("Failed to write {}", path.display()))?;
+	}
+	Ok(files.into_iter().map(|(path, _)| path).collect())
+}
+
+/// Returns the slug of the configured EVM network with a chain id
+fn network_slug(chain_id: u64, networks: &HashMap<String, Network>) -> Option<String> {
+	networks
+		.values()
+		.filter(|network| {
+			network.network_type == BlockChainType::EVM && network.chain_id == Some(chain_id)
+		})
+		.map(|network| network.slug.clone())
+		.min()
+}
+
+/// Returns a complete signature, or the incomplete name of the condition
+fn signature(fields: &Map<String, Value>, keys: &[&str]) -> Result<String, String> {
+	let value = string_field(fields, keys).unwrap_or_default();
+	if value.contains('(') && value.ends_with(')') {
+		Ok(value.replace(' ', ""))
+	} else {
+		Err(value)
+	}
+}
+
+fn string_field(object: &Map<String, Value>, keys: &[&str]) -> Option<String> {
+	keys.iter()
+		.find_map(|key| object.get(*key).and_then(Value::as_str))
+		.filter(|value| !value.trim().is_empty())
+		.map(str::to_string)
+}
+
+fn string_list(value: Option<&Value>) -> Vec<String> {
+	array(value)
+		.filter_map(Value::as_str)
+		.map(str::to_string)
+		.collect()
+}
+
+fn array(value: Option<&Value>) -> impl Iterator<Item = &Value> {
+	value.and_then(Value::as_array).into_iter().flatten()
+}
+
+fn optional_expression(value: Option<&Value>) -> Option<String> {
+	value
+		.and_then(Value::as_str)
+		.map(str::trim)
+		.filter(|expression| !expression.is_empty())
+		.map(str::to_string)
+}
+
+fn as_u64(value: &Value) -> Option<u64> {
+	value
+		.as_u64()
+		.or_else(|| value.as_str().and_then(|value| value.parse().ok()))
+}
+
+/// Returns a lowercase identifier of a name, for file names and trigger slugs
+fn slugify(name: &str) -> String {
+	name.to_lowercase()
+		.split(|c: char| !c.is_ascii_alphanumeric())
+		.filter(|part| !part.is_empty())
+		.collect::<Vec<_>>()
+		.join("_")
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::utils::tests::builders::network::NetworkBuilder;
+
+	fn networks() -> HashMap<String, Network> {
+		[("ethereum_mainnet", 1), ("polygon_mainnet", 137)]
+			.into_iter()
+			.map(|(slug, chain_id)| {
+				let network = NetworkBuilder::new()
+					.slug(slug)
+					.network_type(BlockChainType::EVM)
+					.chain_id(chain_id)
+					.build();
+				(slug.to_string(), network)
+			})
+			.collect()
+	}
+
+	#[test]
+	fn test_convert_sentinel() {
+		let content = r#"{
+			"name": "USDC Large Transfers",
+			"type": "BLOCK",
+			"network": "mainnet",
+			"addresses": ["0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48"],
+			"paused": false,
+			"eventConditions": [
+				{"eventSignature": "Transfer(address,address,uint256)", "expression": "value > 1000000"}
+			],
+			"functionConditions": [{"functionSignature": "transfer(address,uint256)"}],
+			"txCondition": "status == \"success\" and gasPrice > 100",
+			"notificationChannels": [
+				{"type": "slack", "name": "Security Alerts", "config": {"url": "https://hooks.slack.com/services/A/B/C"}}
+			]
+		}"#;
+
+		let imported =
+			convert_alerts(content, Some(AlertFormat::DefenderSentinel), &networks()).unwrap();
+		assert!(imported.warnings.is_empty(), "{:?}", imported.warnings);
+		let monitor = &imported.monitors[0];
+		assert_eq!(monitor.name, "USDC Large Transfers");
+		assert!(!monitor.paused);
+		assert_eq!(monitor.networks, vec!["ethereum_mainnet".to_string()]);
+		assert_eq!(
+			monitor.match_conditions.events[0].expression.as_deref(),
+			Some("value > 1000000")
+		);
+		assert_eq!(
+			monitor.match_conditions.transactions,
+			vec![TransactionCondition {
+				status: TransactionStatus::Success,
+				expression: Some("gas_price > 100".to_string()),
+			}]
+		);
+		assert_eq!(monitor.triggers, vec!["security_alerts".to_string()]);
+		assert_eq!(
+			imported.triggers["security_alerts"].trigger_type,
+			TriggerType::Slack
+		);
+		// The webhook URL of the export is not written to the configuration
+		assert!(matches!(
+			&imported.triggers["security_alerts"].config,
+			TriggerTypeConfig::Slack {
+				slack_url: SecretValue::Environment(variable),
+				..
+			} if variable == "SECURITY_ALERTS_URL"
+		));
+		assert_eq!(
+			imported.secret_variables,
+			BTreeSet::from(["SECURITY_ALERTS_URL".to_string()])
+		);
+	}
+
+	#[test]
+	fn test_convert_tenderly_detects_format_and_pauses_lossy_alerts() {
+		let content = r#"{"items": [{
+			"name": "Failed withdrawals",
+			"enabled": true,
+			"expressions": [
+				{"type": "network", "expression": {"network_id": "137"}},
+				{"type": "contract_address", "expression": {"address": "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48"}},
+				{"type": "method_call", "expression": {"method_name": "withdraw"}},
+				{"type": "emitted_log", "expression": {"event_signature": "Withdrawal(address, uint256)"}},
+				{"type": "tx_status", "expression": {"transaction_success": false}}
+			],
+			"delivery_channels": [{"type": "webhook", "name": "Pager"}, {"type": "email", "name": "Team"}]
+		}]}"#;
+
+		let imported = convert_alerts(content, None, &networks()).unwrap();
+		let monitor = &imported.monitors[0];
+		assert_eq!(monitor.networks, vec!["polygon_mainnet".to_string()]);
+		assert_eq!(
+			monitor.match_conditions.events[0].signature,
+			"Withdrawal(address,uint256)"
+		);
+		assert!(monitor.match_conditions.functions.is_empty());
+		assert_eq!(
+			monitor.match_conditions.transactions[0].status,
+			TransactionStatus::Failure
+		);
+		// The method without signature and the email channel were dropped
+		assert!(monitor.paused);
+		assert_eq!(monitor.triggers, vec!["pager".to_string()]);
+		assert_eq!(imported.warnings.len(), 3);
+		assert!(imported.warnings[0].contains("method call 'withdraw'"));
+		assert!(imported.warnings[1].contains("set PAGER_URL"));
+		assert!(imported.warnings[2].contains("email channel 'Team'"));
+	}
+
+	#[test]
+	fn test_convert_transaction_condition() {
+		assert_eq!(
+			convert_transaction_condition("status == 'failed'"),
+			(TransactionStatus::Failure, None)
+		);
+		assert_eq!(
+			convert_transaction_condition("value > 0 and status == \"success\""),
+			(TransactionStatus::Success, Some("value > 0".to_string()))
+		);
+		assert_eq!(
+			convert_transaction_condition("gasUsed > 21000 or maxFeePerGas < 5"),
+			(
+				TransactionStatus::Any,
+				Some("gas_used > 21000 or max_fee_per_gas < 5".to_string())
+			)
+		);
+	}
+
+	#[test]
+	fn test_unknown_network_and_channel_ids() {
+		let content = r#"[{"name": "Arbitrum", "network": "arbitrum", "notificationChannels": ["2f6e-channel"]}]"#;
+		let imported = convert_alerts(content, None, &networks()).unwrap();
+		let monitor = &imported.monitors[0];
+		assert!(monitor.paused);
+		assert!(monitor.networks.is_empty());
+		assert!(monitor.triggers.is_empty());
+		assert!(imported.warnings[0].contains("chain id 42161"));
+		assert!(imported
+			.warnings
+			.iter()
+			.any(|warning| warning.contains("'2f6e-channel' is only referenced by id")));
+	}
+
+	#[test]
+	fn test_write_imported_config_does_not_overwrite() {
+		let temp_dir = tempfile::tempdir().unwrap();
+		let content = r#"{"name": "Transfers", "network": "mainnet", "notificationChannels": [{"type": "discord", "name": "Ops", "url": "https://discord.com/api/webhooks/1/a"}]}"#;
+		let imported = convert_alerts(content, None, &networks()).unwrap();
+
+		let written =
+			write_imported_config(&imported, temp_dir.path(), Path::new("defender.json")).unwrap();
+		assert_eq!(
+			written,
+			vec![
+				temp_dir.path().join("monitors").join("transfers.json"),
+				temp_dir
+					.path()
+					.join("triggers")
+					.join("imported_defender.json"),
+			]
+		);
+		let monitor: Monitor =
+			serde_json::from_str(&std::fs::read_to_string(&written[0]).unwrap()).unwrap();
+		assert_eq!(monitor, imported.monitors[0]);
+		let triggers: BTreeMap<String, Trigger> =
+			serde_json::from_str(&std::fs::read_to_string(&written[1]).unwrap()).unwrap();
+		assert_eq!(triggers, imported.triggers);
+
+		assert!(
+			write_imported_config(&imported, temp_dir.path(), Path::new("defender.json"))
+				.unwrap_err()
+				.to_string()
+				.contains("already exists")
+		);
+	}
+}
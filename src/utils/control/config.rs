@@ -0,0 +1,586 @@
+//! Applied configuration.
+//!
+//! Every start of the service, including the restart of a reload, fingerprints the active
+//! monitors, the networks and the triggers it runs with, and compares them with the
+//! fingerprints of the previous start saved in `applied_config.json` of the data directory.
+//! The differences are logged: monitors, networks and triggers added, removed or changed (with
+//! the changed fields), the networks affected, and the watchers started, stopped or
+//! reconfigured. The version of the applied configuration is exposed by the
+//! `config_version_info` metric and the `config-version` control command.
+//!
+//! Only fingerprints are saved, so the secrets of the configuration are never written to disk.
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::{
+	collections::{BTreeMap, BTreeSet, HashMap},
+	path::{Path, PathBuf},
+};
+
+use crate::{
+	models::{Monitor, Network, Trigger},
+	utils::{
+		control::CONTROL,
+		metrics::{CONFIG_APPLIED_TIMESTAMP_SECONDS, CONFIG_VERSION_INFO},
+	},
+};
+
+/// Length of the configuration versions, in hexadecimal digits
+const VERSION_LENGTH: usize = 12;
+
+/// Returns the file holding the fingerprints of the applied configuration
+///
+/// # Arguments
+/// * `data_dir` - Directory of the state of the service
+pub fn applied_config_path(data_dir: &Path) -> PathBuf {
+	data_dir.join("applied_config.json")
+}
+
+/// Fingerprints of a monitor, network or trigger
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ConfigItem {
+	/// Fingerprint of each field
+	pub fields: BTreeMap<String, String>,
+	/// Networks of a monitor
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub networks: Vec<String>,
+	/// Triggers of a monitor, including those of the owners of its addresses
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub triggers: Vec<String>,
+}
+
+impl ConfigItem {
+	fn new<T: Serialize>(item: &T) -> Self {
+		let fields = match serde_json::to_value(item) {
+			Ok(Value::Object(fields)) => fields
+				.into_iter()
+				.map(|(name, value)| (name, fingerprint(&value)))
+				.collect(),
+			Ok(value) => BTreeMap::from([(String::new(), fingerprint(&value))]),
+			Err(e) => {
+				tracing::warn!("Failed to fingerprint a configuration item: {}", e);
+				BTreeMap::new()
+			}
+		};
+		Self {
+			fields,
+			..Default::default()
+		}
+	}
+}
+
+/// Fingerprints of the configuration the service runs with
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ConfigSnapshot {
+	/// Version of the configuration, derived from the fingerprints
+	pub version: String,
+	/// Unix timestamp (seconds) when the configuration was applied
+	pub applied_at: i64,
+	/// Active monitors, keyed by name
+	pub monitors: BTreeMap<String, ConfigItem>,
+	/// Networks, keyed by slug
+	pub networks: BTreeMap<String, ConfigItem>,
+	/// Triggers, keyed by slug
+	pub triggers: BTreeMap<String, ConfigItem>,
+}
+
+impl ConfigSnapshot {
+	/// Fingerprints a configuration
+	///
+	/// # Arguments
+	/// * `monitors` - The active monitors
+	/// * `networks` - The networks, keyed by slug
+	/// * `triggers` - The triggers, keyed by slug
+	pub fn new(
+		monitors: &[Monitor],
+		networks: &HashMap<String, Network>,
+		triggers: &HashMap<String, Trigger>,
+	) -> Self {
+		let monitors: BTreeMap<String, ConfigItem> = monitors
+			.iter()
+			.map(|monitor| {
+				let mut item = ConfigItem::new(monitor);
+				item.networks = monitor.networks.clone();
+				item.triggers = monitor
+					.routed_triggers()
+					.cloned()
+					.collect::<BTreeSet<_>>()
+					.into_iter()
+					.collect();
+				(monitor.name.clone(), item)
+			})
+			.collect();
+		let networks: BTreeMap<String, ConfigItem> = networks
+			.iter()
+			.map(|(slug, network)| (slug.clone(), ConfigItem::new(network)))
+			.collect();
+		let triggers: BTreeMap<String, ConfigItem> = triggers
+			.iter()
+			.map(|(slug, trigger)| (slug.clone(), ConfigItem::new(trigger)))
+			.collect();
+
+		let version = serde_json::to_value((&monitors, &networks, &triggers))
+			.map(|value| fingerprint(&value)[..VERSION_LENGTH].to_string())
+			.unwrap_or_default();
+		Self {
+			version,
+			applied_at: Utc::now().timestamp(),
+			monitors,
+			networks,
+			triggers,
+		}
+	}
+
+	/// Returns the networks watched with this configuration, those with an active monitor
+	pub fn watchers(&self) -> BTreeSet<String> {
+		self.monitors
+			.values()
+			.flat_map(|monitor| &monitor.networks)
+			.filter(|slug| self.networks.contains_key(*slug))
+			.cloned()
+			.collect()
+	}
+
+	/// Loads the fingerprints saved by the previous start
+	///
+	/// # Arguments
+	/// * `path` - File of the fingerprints
+	///
+	/// # Returns
+	/// The saved fingerprints, `None` if there are none or they cannot be read
+	pub async fn load(path: &Path) -> Option<Self> {
+		let content = tokio::fs::read_to_string(path).await.ok()?;
+		match serde_json::from_str(&content) {
+			Ok(snapshot) => Some(snapshot),
+			Err(e) => {
+				tracing::warn!(
+					"Ignoring the applied configuration {}: {}",
+					path.display(),
+					e
+				);
+				None
+			}
+		}
+	}
+
+	/// Saves the fingerprints
+	///
+	/// # Arguments
+	/// * `path` - File of the fingerprints
+	pub async fn save(&self, path: &Path) -> Result<(), anyhow::Error> {
+		if let Some(parent) = path.parent() {
+			tokio::fs::create_dir_all(parent).await?;
+		}
+		tokio::fs::write(path, serde_json::to_vec_pretty(self)?).await?;
+		Ok(())
+	}
+}
+
+/// Monitors, networks or triggers added, removed or changed
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ItemChanges {
+	/// Names of the added items
+	pub added: Vec<String>,
+	/// Names of the removed items
+	pub removed: Vec<String>,
+	/// Changed fields, keyed by the name of the changed item
+	pub changed: BTreeMap<String, Vec<String>>,
+}
+
+impl ItemChanges {
+	fn between(
+		previous: &BTreeMap<String, ConfigItem>,
+		current: &BTreeMap<String, ConfigItem>,
+	) -> Self {
+		let mut changes = Self::default();
+		for (name, item) in current {
+			match previous.get(name) {
+				None => changes.added.push(name.clone()),
+				Some(previous_item) => {
+					let fields: BTreeSet<&String> = item
+						.fields
+						.keys()
+						.chain(previous_item.fields.keys())
+						.filter(|field| item.fields.get(*field) != previous_item.fields.get(*field))
+						.collect();
+					if !fields.is_empty() {
+						changes
+							.changed
+							.insert(name.clone(), fields.into_iter().cloned().collect());
+					}
+				}
+			}
+		}
+		changes.removed = previous
+			.keys()
+			.filter(|name| !current.contains_key(*name))
+			.cloned()
+			.collect();
+		changes
+	}
+
+	/// Returns the names of the added, removed and changed items
+	fn names(&self) -> impl Iterator<Item = &String> {
+		self.added
+			.iter()
+			.chain(&self.removed)
+			.chain(self.changed.keys())
+	}
+
+	fn is_empty(&self) -> bool {
+		self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+	}
+
+	fn describe(&self, kind: &str, lines: &mut Vec<String>) {
+		if !self.added.is_empty() {
+			lines.push(format!("{}s added: {}", kind, self.added.join(", ")));
+		}
+		if !self.removed.is_empty() {
+			lines.push(format!("{}s removed: {}", kind, self.removed.join(", ")));
+		}
+		if !self.changed.is_empty() {
+			let changed: Vec<String> = self
+				.changed
+				.iter()
+				.map(|(name, fields)| format!("{} ({})", name, fields.join(", ")))
+				.collect();
+			lines.push(format!("{}s changed: {}", kind, changed.join(", ")));
+		}
+	}
+}
+
+/// Differences between two configurations
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ConfigDiff {
+	/// Version of the previous configuration, `None` on the first start
+	pub from_version: Option<String>,
+	/// Version of the new configuration
+	pub to_version: String,
+	/// Changes of the active monitors
+	pub monitors: ItemChanges,
+	/// Changes of the networks
+	pub networks: ItemChanges,
+	/// Changes of the triggers
+	pub triggers: ItemChanges,
+	/// Networks whose processing is affected by the changes
+	pub affected_networks: Vec<String>,
+	/// Networks watched only with the new configuration
+	pub started_watchers: Vec<String>,
+	/// Networks no longer watched with the new configuration
+	pub stopped_watchers: Vec<String>,
+	/// Networks watched with both configurations and affected by the changes
+	pub reconfigured_watchers: Vec<String>,
+}
+
+impl ConfigDiff {
+	/// Compares two configurations
+	///
+	/// # Arguments
+	/// * `previous` - The previous configuration, `None` on the first start
+	/// * `current` - The new configuration
+	pub fn between(previous: Option<&ConfigSnapshot>, current: &ConfigSnapshot) -> Self {
+		let empty = ConfigSnapshot::default();
+		let old = previous.unwrap_or(&empty);
+		let monitors = ItemChanges::between(&old.monitors, &current.monitors);
+		let networks = ItemChanges::between(&old.networks, &current.networks);
+		let triggers = ItemChanges::between(&old.triggers, &current.triggers);
+
+		// A monitor affects its networks, before and after the change
+		let monitor_networks = |name: &String| {
+			old.monitors
+				.get(name)
+				.into_iter()
+				.chain(current.monitors.get(name))
+				.flat_map(|monitor| monitor.networks.iter().cloned())
+				.collect::<Vec<_>>()
+		};
+		let mut affected: BTreeSet<String> = networks.names().cloned().collect();
+		affected.extend(monitors.names().flat_map(monitor_networks));
+		for trigger in triggers.names() {
+			for monitor in old.monitors.values().chain(current.monitors.values()) {
+				if monitor.triggers.contains(trigger) {
+					affected.extend(monitor.networks.iter().cloned());
+				}
+			}
+		}
+
+		let old_watchers = old.watchers();
+		let new_watchers = current.watchers();
+		Self {
+			from_version: previous.map(|snapshot| snapshot.version.clone()),
+			to_version: current.version.clone(),
+			started_watchers: new_watchers.difference(&old_watchers).cloned().collect(),
+			stopped_watchers: old_watchers.difference(&new_watchers).cloned().collect(),
+			reconfigured_watchers: old_watchers
+				.intersection(&new_watchers)
+				.filter(|slug| affected.contains(*slug))
+				.cloned()
+				.collect(),
+			affected_networks: affected.into_iter().collect(),
+			monitors,
+			networks,
+			triggers,
+		}
+	}
+
+	/// Returns whether the configurations are identical
+	pub fn is_empty(&self) -> bool {
+		self.monitors.is_empty() && self.networks.is_empty() && self.triggers.is_empty()
+	}
+
+	/// Describes the differences, one line per kind of change
+	pub fn summary(&self) -> Vec<String> {
+		let mut lines = Vec::new();
+		self.monitors.describe("Monitor", &mut lines);
+		self.networks.describe("Network", &mut lines);
+		self.triggers.describe("Trigger", &mut lines);
+		for (label, slugs) in [
+			("Networks affected", &self.affected_networks),
+			("Watchers started", &self.started_watchers),
+			("Watchers stopped", &self.stopped_watchers),
+			("Watchers reconfigured", &self.reconfigured_watchers),
+		] {
+			if !slugs.is_empty() {
+				lines.push(format!("{}: {}", label, slugs.join(", ")));
+			}
+		}
+		lines
+	}
+
+	/// Logs the differences
+	///
+	/// # Arguments
+	/// * `context` - What the differences are about, e.g. `Applied configuration`
+	pub fn log(&self, context: &str) {
+		let from = self.from_version.as_deref().unwrap_or("none");
+		if self.is_empty() {
+			tracing::info!("{}: configuration {} unchanged", context, self.to_version);
+			return;
+		}
+		tracing::info!("{}: configuration {} -> {}", context, from, self.to_version);
+		for line in self.summary() {
+			tracing::info!("{}: {}", context, line);
+		}
+	}
+}
+
+/// The configuration the service runs with
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AppliedConfig {
+	/// Fingerprints of the configuration
+	#[serde(skip)]
+	pub snapshot: ConfigSnapshot,
+	/// Version of the configuration
+	pub version: String,
+	/// Unix timestamp (seconds) when the configuration was applied
+	pub applied_at: i64,
+	/// Differences with the configuration of the previous start
+	pub diff: ConfigDiff,
+}
+
+/// Applies the configuration the service starts with
+///
+/// The differences with the configuration of the previous start are logged, the fingerprints
+/// are saved for the next start, and the version is exposed by the metrics and the control
+/// socket.
+///
+/// # Arguments
+/// * `snapshot` - Fingerprints of the configuration
+/// * `path` - File of the fingerprints of the previous start
+///
+/// # Returns
+/// The differences with the configuration of the previous start
+pub async fn apply_config(snapshot: ConfigSnapshot, path: &Path) -> ConfigDiff {
+	let previous = ConfigSnapshot::load(path).await;
+	let diff = ConfigDiff::between(previous.as_ref(), &snapshot);
+	diff.log("Applied configuration");
+	if let Err(e) = snapshot.save(path).await {
+		tracing::error!(
+			"Failed to save the applied configuration {}: {}",
+			path.display(),
+			e
+		);
+	}
+
+	CONFIG_VERSION_INFO.reset();
+	CONFIG_VERSION_INFO
+		.with_label_values(&[snapshot.version.as_str()])
+		.set(1.0);
+	CONFIG_APPLIED_TIMESTAMP_SECONDS.set(snapshot.applied_at as f64);
+	CONTROL.set_applied_config(AppliedConfig {
+		version: snapshot.version.clone(),
+		applied_at: snapshot.applied_at,
+		diff: diff.clone(),
+		snapshot,
+	});
+	diff
+}
+
+/// Returns the SHA-256 of a JSON value, in hexadecimal
+fn fingerprint(value: &Value) -> String {
+	hex::encode(Sha256::digest(value.to_string().as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::utils::tests::builders::{
+		evm::monitor::MonitorBuilder, network::NetworkBuilder, trigger::TriggerBuilder,
+	};
+
+	fn snapshot(
+		monitors: &[Monitor],
+		networks: &[Network],
+		triggers: &[Trigger],
+	) -> ConfigSnapshot {
+		ConfigSnapshot::new(
+			monitors,
+			&networks
+				.iter()
+				.map(|network| (network.slug.clone(), network.clone()))
+				.collect(),
+			&triggers
+				.iter()
+				.map(|trigger| (trigger.name.clone(), trigger.clone()))
+				.collect(),
+		)
+	}
+
+	fn monitor(name: &str, network: &str, trigger: &str) -> MonitorBuilder {
+		MonitorBuilder::new()
+			.name(name)
+			.networks(vec![network.to_string()])
+			.triggers(vec![trigger.to_string()])
+	}
+
+	#[test]
+	fn test_version_is_stable() {
+		let monitors = [monitor("transfers", "ethereum_mainnet", "slack").build()];
+		let networks = [NetworkBuilder::new().slug("ethereum_mainnet").build()];
+		let first = snapshot(&monitors, &networks, &[]);
+		let second = snapshot(&monitors, &networks, &[]);
+		assert_eq!(first.version, second.version);
+		assert_eq!(first.version.len(), VERSION_LENGTH);
+
+		let changed = snapshot(
+			&[monitor("transfers", "ethereum_mainnet", "email").build()],
+			&networks,
+			&[],
+		);
+		assert_ne!(first.version, changed.version);
+	}
+
+	#[test]
+	fn test_diff_between_configurations() {
+		let networks = [
+			NetworkBuilder::new().slug("ethereum_mainnet").build(),
+			NetworkBuilder::new().slug("polygon_mainnet").build(),
+			NetworkBuilder::new().slug("base_mainnet").build(),
+		];
+		let slack = TriggerBuilder::new()
+			.name("slack")
+			.slack("https://hooks.slack.com/1")
+			.build();
+		let previous = snapshot(
+			&[
+				monitor("transfers", "ethereum_mainnet", "slack").build(),
+				monitor("mints", "polygon_mainnet", "email").build(),
+			],
+			&networks,
+			&[slack.clone()],
+		);
+		let current = snapshot(
+			&[
+				monitor("transfers", "ethereum_mainnet", "slack").build(),
+				monitor("burns", "base_mainnet", "email").build(),
+			],
+			&networks,
+			&[TriggerBuilder::new()
+				.name("slack")
+				.slack("https://hooks.slack.com/2")
+				.build()],
+		);
+
+		let diff = ConfigDiff::between(Some(&previous), &current);
+		assert_eq!(
+			diff.from_version.as_deref(),
+			Some(previous.version.as_str())
+		);
+		assert_eq!(diff.monitors.added, vec!["burns".to_string()]);
+		assert_eq!(diff.monitors.removed, vec!["mints".to_string()]);
+		assert!(diff.networks.is_empty());
+		assert_eq!(
+			diff.triggers.changed.get("slack"),
+			Some(&vec!["config".to_string()])
+		);
+		assert_eq!(
+			diff.affected_networks,
+			vec![
+				"base_mainnet".to_string(),
+				"ethereum_mainnet".to_string(),
+				"polygon_mainnet".to_string()
+			]
+		);
+		assert_eq!(diff.started_watchers, vec!["base_mainnet".to_string()]);
+		assert_eq!(diff.stopped_watchers, vec!["polygon_mainnet".to_string()]);
+		assert_eq!(
+			diff.reconfigured_watchers,
+			vec!["ethereum_mainnet".to_string()]
+		);
+		assert!(!diff.summary().is_empty());
+
+		assert!(ConfigDiff::between(Some(&current), &current).is_empty());
+	}
+
+	#[test]
+	fn test_diff_reports_changed_fields() {
+		let previous = snapshot(
+			&[monitor("transfers", "ethereum_mainnet", "slack").build()],
+			&[],
+			&[],
+		);
+		let current = snapshot(
+			&[monitor("transfers", "ethereum_mainnet", "slack")
+				.observe(true)
+				.build()],
+			&[],
+			&[],
+		);
+		let diff = ConfigDiff::between(Some(&previous), &current);
+		assert_eq!(
+			diff.monitors.changed.get("transfers"),
+			Some(&vec!["observe".to_string()])
+		);
+		assert_eq!(diff.affected_networks, vec!["ethereum_mainnet".to_string()]);
+		// Networks without configuration are not watched
+		assert!(diff.reconfigured_watchers.is_empty());
+	}
+
+	#[tokio::test]
+	async fn test_apply_config() {
+		let temp_dir = tempfile::tempdir().unwrap();
+		let path = temp_dir.path().join("applied_config.json");
+		let networks = [NetworkBuilder::new().slug("ethereum_mainnet").build()];
+
+		let first = snapshot(
+			&[monitor("transfers", "ethereum_mainnet", "slack").build()],
+			&networks,
+			&[],
+		);
+		let diff = apply_config(first.clone(), &path).await;
+		assert_eq!(diff.from_version, None);
+		assert_eq!(diff.started_watchers, vec!["ethereum_mainnet".to_string()]);
+		assert_eq!(ConfigSnapshot::load(&path).await, Some(first.clone()));
+
+		let second = snapshot(&[], &networks, &[]);
+		let diff = apply_config(second.clone(), &path).await;
+		assert_eq!(diff.from_version, Some(first.version));
+		assert_eq!(diff.stopped_watchers, vec!["ethereum_mainnet".to_string()]);
+		assert_eq!(
+			CONTROL.applied_config().map(|applied| applied.version),
+			Some(second.version)
+		);
+	}
+}
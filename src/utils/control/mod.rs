@@ -0,0 +1,288 @@
+//! Runtime control of the service.
+//!
+//! Holds the process-wide state that operators can change while the service runs, and the
+//! control socket serving it (see [`server`]):
+//! - monitors paused at runtime, skipped by the block handler until resumed
+//! - the number of in-flight notification dispatches, used to flush pending notifications
+//! - reload requests, handled by the main loop with a graceful restart
+//! - the active networks and their checkpoints, reported by state dumps (see [`state`])
+//! - the applied configuration and its differences with the previous one (see [`config`])
+
+pub mod config;
+pub mod server;
+pub mod state;
+
+use chrono::Utc;
+use lazy_static::lazy_static;
+use serde::Serialize;
+use std::{
+	collections::{BTreeMap, BTreeSet},
+	sync::{
+		atomic::{AtomicUsize, Ordering},
+		RwLock,
+	},
+	time::Duration,
+};
+use tokio::sync::Notify;
+
+use crate::utils::normalize_string;
+
+pub use config::{applied_config_path, apply_config, AppliedConfig, ConfigDiff, ConfigSnapshot};
+pub use server::{handle_command, ControlContext, ControlResponse};
+#[cfg(unix)]
+pub use server::{remove_control_socket, start_control_socket};
+pub use state::{write_state_dump, StateDump, DEFAULT_STATE_DUMP_DIR};
+
+lazy_static! {
+	/// Process-wide runtime control state.
+	pub static ref CONTROL: ControlState = ControlState::default();
+}
+
+/// Block processing progress of a network
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct NetworkState {
+	/// Last block saved as processed
+	pub last_processed_block: Option<u64>,
+	/// Latest confirmed block when the checkpoint was saved
+	pub latest_confirmed_block: Option<u64>,
+	/// Unix timestamp (seconds) when the checkpoint was saved
+	pub last_checkpoint_at: Option<i64>,
+}
+
+/// Runtime control state
+#[derive(Default)]
+pub struct ControlState {
+	networks: RwLock<BTreeMap<String, NetworkState>>,
+	paused_monitors: RwLock<BTreeSet<String>>,
+	in_flight_dispatches: AtomicUsize,
+	idle: Notify,
+	reload: Notify,
+	applied_config: RwLock<Option<AppliedConfig>>,
+}
+
+/// Marks a notification dispatch as in flight until dropped
+pub struct DispatchGuard<'a> {
+	state: &'a ControlState,
+}
+
+impl Drop for DispatchGuard<'_> {
+	fn drop(&mut self) {
+		if self
+			.state
+			.in_flight_dispatches
+			.fetch_sub(1, Ordering::SeqCst)
+			== 1
+		{
+			self.state.idle.notify_waiters();
+		}
+	}
+}
+
+impl ControlState {
+	/// Pauses a monitor at runtime
+	///
+	/// # Arguments
+	/// * `monitor_name` - Name of the monitor
+	///
+	/// # Returns
+	/// `true` if the monitor was not already paused
+	pub fn pause_monitor(&self, monitor_name: &str) -> bool {
+		self.write_paused().insert(normalize_string(monitor_name))
+	}
+
+	/// Resumes a monitor paused at runtime
+	///
+	/// # Arguments
+	/// * `monitor_name` - Name of the monitor
+	///
+	/// # Returns
+	/// `true` if the monitor was paused
+	pub fn resume_monitor(&self, monitor_name: &str) -> bool {
+		self.write_paused().remove(&normalize_string(monitor_name))
+	}
+
+	/// Returns whether a monitor is paused at runtime
+	pub fn is_monitor_paused(&self, monitor_name: &str) -> bool {
+		let paused = match self.paused_monitors.read() {
+			Ok(guard) => guard,
+			Err(poisoned) => poisoned.into_inner(),
+		};
+		!paused.is_empty() && paused.contains(&normalize_string(monitor_name))
+	}
+
+	/// Returns the (normalized) names of the monitors paused at runtime
+	pub fn paused_monitors(&self) -> Vec<String> {
+		match self.paused_monitors.read() {
+			Ok(guard) => guard.iter().cloned().collect(),
+			Err(poisoned) => poisoned.into_inner().iter().cloned().collect(),
+		}
+	}
+
+	/// Registers an in-flight notification dispatch
+	///
+	/// # Returns
+	/// A guard ending the dispatch when dropped
+	pub fn dispatch_started(&self) -> DispatchGuard<'_> {
+		self.in_flight_dispatches.fetch_add(1, Ordering::SeqCst);
+		DispatchGuard { state: self }
+	}
+
+	/// Returns the number of in-flight notification dispatches
+	pub fn in_flight_dispatches(&self) -> usize {
+		self.in_flight_dispatches.load(Ordering::SeqCst)
+	}
+
+	/// Waits until no notification dispatch is in flight
+	///
+	/// # Arguments
+	/// * `timeout` - Maximum time to wait
+	///
+	/// # Returns
+	/// `true` if all dispatches completed within the timeout
+	pub async fn wait_idle(&self, timeout: Duration) -> bool {
+		tokio::time::timeout(timeout, async {
+			loop {
+				let idle = self.idle.notified();
+				if self.in_flight_dispatches() == 0 {
+					return;
+				}
+				idle.await;
+			}
+		})
+		.await
+		.is_ok()
+	}
+
+	/// Registers the networks watched by the service
+	///
+	/// # Arguments
+	/// * `network_slugs` - Slugs of the active networks
+	pub fn register_networks(&self, network_slugs: &[String]) {
+		let mut networks = self.write_networks();
+		for slug in network_slugs {
+			networks.entry(slug.clone()).or_default();
+		}
+	}
+
+	/// Records the checkpoint saved for a network
+	///
+	/// # Arguments
+	/// * `network_slug` - Slug of the network
+	/// * `checkpoint_block` - Last processed block
+	/// * `latest_confirmed_block` - Latest confirmed block of the network
+	pub fn record_checkpoint(
+		&self,
+		network_slug: &str,
+		checkpoint_block: u64,
+		latest_confirmed_block: u64,
+	) {
+		self.write_networks().insert(
+			network_slug.to_string(),
+			NetworkState {
+				last_processed_block: Some(checkpoint_block),
+				latest_confirmed_block: Some(latest_confirmed_block),
+				last_checkpoint_at: Some(Utc::now().timestamp()),
+			},
+		);
+	}
+
+	/// Returns the progress of the active networks, keyed by slug
+	pub fn networks(&self) -> BTreeMap<String, NetworkState> {
+		match self.networks.read() {
+			Ok(guard) => guard.clone(),
+			Err(poisoned) => poisoned.into_inner().clone(),
+		}
+	}
+
+	/// Requests a reload of the service
+	pub fn request_reload(&self) {
+		self.reload.notify_one();
+	}
+
+	/// Waits until a reload is requested
+	pub async fn reload_requested(&self) {
+		self.reload.notified().await;
+	}
+
+	/// Records the configuration the service runs with
+	///
+	/// # Arguments
+	/// * `applied` - The applied configuration
+	pub fn set_applied_config(&self, applied: AppliedConfig) {
+		match self.applied_config.write() {
+			Ok(mut guard) => *guard = Some(applied),
+			Err(poisoned) => *poisoned.into_inner() = Some(applied),
+		}
+	}
+
+	/// Returns the configuration the service runs with, once applied
+	pub fn applied_config(&self) -> Option<AppliedConfig> {
+		match self.applied_config.read() {
+			Ok(guard) => guard.clone(),
+			Err(poisoned) => poisoned.into_inner().clone(),
+		}
+	}
+
+	fn write_networks(&self) -> std::sync::RwLockWriteGuard<'_, BTreeMap<String, NetworkState>> {
+		match self.networks.write() {
+			Ok(guard) => guard,
+			Err(poisoned) => poisoned.into_inner(),
+		}
+	}
+
+	fn write_paused(&self) -> std::sync::RwLockWriteGuard<'_, BTreeSet<String>> {
+		match self.paused_monitors.write() {
+			Ok(guard) => guard,
+			Err(poisoned) => poisoned.into_inner(),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_pause_and_resume_monitor() {
+		let state = ControlState::default();
+		assert!(!state.is_monitor_paused("Large Transfer"));
+
+		assert!(state.pause_monitor("Large Transfer"));
+		assert!(!state.pause_monitor("large transfer"));
+		assert!(state.is_monitor_paused("Large Transfer"));
+		assert_eq!(state.paused_monitors(), vec!["large transfer".to_string()]);
+
+		assert!(state.resume_monitor("Large Transfer"));
+		assert!(!state.resume_monitor("Large Transfer"));
+		assert!(!state.is_monitor_paused("Large Transfer"));
+	}
+
+	#[test]
+	fn test_record_checkpoint() {
+		let state = ControlState::default();
+		state.register_networks(&["ethereum_mainnet".to_string()]);
+		assert_eq!(
+			state.networks().get("ethereum_mainnet"),
+			Some(&NetworkState::default())
+		);
+
+		state.record_checkpoint("ethereum_mainnet", 100, 112);
+		let network = state.networks().get("ethereum_mainnet").cloned().unwrap();
+		assert_eq!(network.last_processed_block, Some(100));
+		assert_eq!(network.latest_confirmed_block, Some(112));
+		assert!(network.last_checkpoint_at.is_some());
+	}
+
+	#[tokio::test]
+	async fn test_wait_idle() {
+		let state = ControlState::default();
+		assert!(state.wait_idle(Duration::from_millis(10)).await);
+
+		let guard = state.dispatch_started();
+		assert_eq!(state.in_flight_dispatches(), 1);
+		assert!(!state.wait_idle(Duration::from_millis(10)).await);
+
+		drop(guard);
+		assert!(state.wait_idle(Duration::from_millis(10)).await);
+	}
+}
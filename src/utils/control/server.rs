@@ -0,0 +1,290 @@
+//! Control socket.
+//!
+//! A Unix domain socket accepting one command per line and answering each with a line of
+//! JSON, so that operators and wrapper tooling can control the service without a TCP admin
+//! port. The socket is enabled with the `CONTROL_SOCKET_PATH` environment variable (or the
+//! `--control-socket` flag) and is only accessible by the owner of the process.
+//!
+//! Commands:
+//! - `ping`: checks that the service is responsive
+//! - `reload`: gracefully restarts the service, reloading its configuration
+//! - `pause-monitor <name>` / `resume-monitor <name>`: pauses or resumes a monitor at runtime
+//! - `flush-queues`: waits for in-flight notifications and persists buffered state (match
+//!   statistics and the window store)
+//! - `dump-state`: writes a state dump (see [`super::state`]) and returns its path
+//! - `config-version`: returns the version of the applied configuration and its differences
+//!   with the configuration of the previous start (see [`super::config`])
+
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::{path::PathBuf, sync::Arc, time::Duration};
+
+use crate::{
+	services::{filter::WindowStore, trigger::PriorityDispatchQueue},
+	utils::{
+		control::{write_state_dump, CONTROL},
+		metrics::stats::MATCH_STATS,
+	},
+};
+
+/// Maximum time `flush-queues` waits for in-flight notifications
+const FLUSH_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// State of the service the control commands act on
+#[derive(Clone)]
+pub struct ControlContext {
+	/// Notification dispatch queue of the trigger handler
+	pub dispatch_queue: Arc<PriorityDispatchQueue>,
+	/// Window store of the service
+	pub window_store: Arc<dyn WindowStore>,
+	/// Directory of the state dumps
+	pub state_dump_dir: PathBuf,
+}
+
+/// Response to a control command
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ControlResponse {
+	/// Whether the command succeeded
+	pub ok: bool,
+	/// Human readable result
+	pub message: String,
+	/// Data returned by the command
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub data: Option<Value>,
+}
+
+impl ControlResponse {
+	fn ok(message: impl Into<String>) -> Self {
+		Self {
+			ok: true,
+			message: message.into(),
+			data: None,
+		}
+	}
+
+	fn error(message: impl Into<String>) -> Self {
+		Self {
+			ok: false,
+			message: message.into(),
+			data: None,
+		}
+	}
+}
+
+/// Executes a control command
+///
+/// # Arguments
+/// * `line` - The command and its argument, e.g. `pause-monitor Large Transfer`
+/// * `context` - The state of the service the commands act on
+///
+/// # Returns
+/// The response to send back to the client
+pub async fn handle_command(line: &str, context: &ControlContext) -> ControlResponse {
+	let line = line.trim();
+	let (command, argument) = match line.split_once(char::is_whitespace) {
+		Some((command, argument)) => (command, argument.trim()),
+		None => (line, ""),
+	};
+
+	match (command, argument) {
+		("ping", _) => ControlResponse::ok("pong"),
+		("reload", _) => {
+			CONTROL.request_reload();
+			ControlResponse::ok("Reload requested")
+		}
+		("pause-monitor" | "resume-monitor", "") => {
+			ControlResponse::error(format!("Usage: {} <monitor name>", command))
+		}
+		("pause-monitor", name) => {
+			if CONTROL.pause_monitor(name) {
+				tracing::info!("Monitor '{}' paused through the control socket", name);
+				ControlResponse::ok(format!("Monitor '{}' paused", name))
+			} else {
+				ControlResponse::ok(format!("Monitor '{}' is already paused", name))
+			}
+		}
+		("resume-monitor", name) => {
+			if CONTROL.resume_monitor(name) {
+				tracing::info!("Monitor '{}' resumed through the control socket", name);
+				ControlResponse::ok(format!("Monitor '{}' resumed", name))
+			} else {
+				ControlResponse::error(format!("Monitor '{}' is not paused", name))
+			}
+		}
+		("flush-queues", _) => {
+			let flushed = CONTROL.wait_idle(FLUSH_TIMEOUT).await;
+			MATCH_STATS.persist().await;
+			if let Err(e) = context.window_store.persist().await {
+				return ControlResponse::error(format!(
+					"Failed to persist the window store: {}",
+					e
+				));
+			}
+			if flushed {
+				ControlResponse::ok("Queues flushed")
+			} else {
+				ControlResponse::error(format!(
+					"Timed out with {} notification dispatches in flight ({} matches queued)",
+					CONTROL.in_flight_dispatches(),
+					context.dispatch_queue.pending()
+				))
+			}
+		}
+		("dump-state", _) => {
+			match write_state_dump(&context.state_dump_dir, &context.dispatch_queue).await {
+				Ok(path) => ControlResponse {
+					data: Some(json!({ "path": path })),
+					..ControlResponse::ok(format!("State dumped to {}", path.display()))
+				},
+				Err(e) => ControlResponse::error(format!("Failed to dump state: {}", e)),
+			}
+		}
+		("config-version", _) => match CONTROL.applied_config() {
+			Some(applied) => ControlResponse {
+				data: serde_json::to_value(&applied).ok(),
+				..ControlResponse::ok(format!("Configuration version {}", applied.version))
+			},
+			None => ControlResponse::error("No configuration applied yet"),
+		},
+		("", _) => ControlResponse::error("Empty command"),
+		(other, _) => ControlResponse::error(format!("Unknown command '{}'", other)),
+	}
+}
+
+#[cfg(unix)]
+mod unix {
+	use super::{handle_command, ControlContext};
+	use std::{os::unix::fs::PermissionsExt, path::Path};
+	use tokio::{
+		io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+		net::{UnixListener, UnixStream},
+		task::JoinHandle,
+	};
+
+	/// Starts listening for commands on a Unix domain socket
+	///
+	/// # Arguments
+	/// * `path` - Path of the socket, an existing socket file is replaced
+	/// * `context` - The state of the service the commands act on
+	///
+	/// # Returns
+	/// * `std::io::Result<JoinHandle<()>>` - The task accepting connections
+	pub fn start_control_socket(
+		path: &Path,
+		context: ControlContext,
+	) -> std::io::Result<JoinHandle<()>> {
+		remove_control_socket(path);
+		let listener = UnixListener::bind(path)?;
+		std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+
+		Ok(tokio::spawn(async move {
+			loop {
+				match listener.accept().await {
+					Ok((stream, _)) => {
+						tokio::spawn(handle_connection(stream, context.clone()));
+					}
+					Err(e) => {
+						tracing::error!("Failed to accept control socket connection: {}", e);
+					}
+				}
+			}
+		}))
+	}
+
+	/// Removes the socket file
+	///
+	/// # Arguments
+	/// * `path` - Path of the socket
+	pub fn remove_control_socket(path: &Path) {
+		if path.exists() {
+			if let Err(e) = std::fs::remove_file(path) {
+				tracing::warn!("Failed to remove control socket {}: {}", path.display(), e);
+			}
+		}
+	}
+
+	async fn handle_connection(stream: UnixStream, context: ControlContext) {
+		let (reader, mut writer) = stream.into_split();
+		let mut lines = BufReader::new(reader).lines();
+
+		while let Ok(Some(line)) = lines.next_line().await {
+			let response = handle_command(&line, &context).await;
+			let mut output = serde_json::to_string(&response).unwrap_or_default();
+			output.push('\n');
+			if writer.write_all(output.as_bytes()).await.is_err() {
+				break;
+			}
+		}
+	}
+}
+
+#[cfg(unix)]
+pub use unix::{remove_control_socket, start_control_socket};
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::services::filter::state::InMemoryWindowStore;
+
+	fn test_context() -> ControlContext {
+		ControlContext {
+			dispatch_queue: Arc::new(PriorityDispatchQueue::new(0)),
+			window_store: Arc::new(InMemoryWindowStore::new()),
+			state_dump_dir: PathBuf::from("data/state_dumps"),
+		}
+	}
+
+	#[tokio::test]
+	async fn test_handle_command() {
+		let context = test_context();
+		assert!(handle_command("ping", &context).await.ok);
+		assert!(!handle_command("", &context).await.ok);
+		assert!(!handle_command("unknown", &context).await.ok);
+		assert!(!handle_command("pause-monitor", &context).await.ok);
+
+		assert!(
+			handle_command("pause-monitor Control Test Monitor", &context)
+				.await
+				.ok
+		);
+		assert!(CONTROL.is_monitor_paused("Control Test Monitor"));
+
+		assert!(
+			handle_command("resume-monitor Control Test Monitor", &context)
+				.await
+				.ok
+		);
+		assert!(!CONTROL.is_monitor_paused("Control Test Monitor"));
+		assert!(
+			!handle_command("resume-monitor Control Test Monitor", &context)
+				.await
+				.ok
+		);
+	}
+
+	#[cfg(unix)]
+	#[tokio::test]
+	async fn test_control_socket() {
+		use tokio::{
+			io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+			net::UnixStream,
+		};
+
+		let temp_dir = tempfile::tempdir().unwrap();
+		let path = temp_dir.path().join("control.sock");
+		let handle = start_control_socket(&path, test_context()).unwrap();
+
+		let stream = UnixStream::connect(&path).await.unwrap();
+		let (reader, mut writer) = stream.into_split();
+		writer.write_all(b"ping\n").await.unwrap();
+		let response = BufReader::new(reader).lines().next_line().await.unwrap();
+		assert_eq!(
+			response.unwrap(),
+			r#"{"ok":true,"message":"pong"}"#.to_string()
+		);
+
+		handle.abort();
+		remove_control_socket(&path);
+		assert!(!path.exists());
+	}
+}
@@ -0,0 +1,262 @@
+//! State dumps.
+//!
+//! Captures the in-memory state of the service (active networks and their checkpoints,
+//! queue depths, per-monitor match statistics and RPC endpoint health) as JSON, to debug
+//! stuck deployments without attaching a debugger. A dump is written to the directory of the
+//! `STATE_DUMP_DIR` setting (`state_dumps` in the data directory by default) by the
+//! `dump-state` command of the control socket, or on Unix when the process receives `SIGUSR1`.
+
+use chrono::Utc;
+use prometheus::proto::MetricFamily;
+use serde::Serialize;
+use std::{
+	collections::BTreeMap,
+	path::{Path, PathBuf},
+};
+
+use crate::{
+	services::trigger::PriorityDispatchQueue,
+	utils::{
+		control::{NetworkState, CONTROL},
+		metrics::{stats::MATCH_STATS, REGISTRY},
+	},
+};
+
+/// Default directory of the state dumps, in the data directory
+pub const DEFAULT_STATE_DUMP_DIR: &str = "state_dumps";
+
+/// Depth of the notification queues
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct QueueState {
+	/// Blocks whose notifications are being filtered or dispatched
+	pub in_flight_dispatches: usize,
+	/// Matches waiting for a dispatch slot
+	pub pending_dispatches: usize,
+}
+
+/// Match statistics of a monitor on a network
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct MonitorStats {
+	/// Matches of the current day (UTC)
+	pub matches_today: u64,
+	/// Matches over the retained history
+	pub matches_total: u64,
+}
+
+/// Health of the RPC endpoints of a network, from the RPC metrics
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct EndpointHealth {
+	/// Number of RPC requests
+	pub requests: f64,
+	/// Number of failed RPC requests
+	pub errors: f64,
+	/// Number of endpoint rotations
+	pub rotations: f64,
+	/// Number of rate limited responses, keyed by endpoint
+	pub rate_limits: BTreeMap<String, f64>,
+	/// Number of blocks each endpoint lags behind the most advanced one
+	pub block_lag: BTreeMap<String, f64>,
+}
+
+/// Snapshot of the in-memory state of the service
+#[derive(Debug, Clone, Serialize)]
+pub struct StateDump {
+	/// Unix timestamp (seconds) of the snapshot
+	pub generated_at: i64,
+	/// Active networks and their checkpoints, keyed by slug
+	pub networks: BTreeMap<String, NetworkState>,
+	/// Monitors paused at runtime
+	pub paused_monitors: Vec<String>,
+	/// Depth of the notification queues
+	pub queues: QueueState,
+	/// Match statistics keyed by monitor name, then network slug
+	pub monitor_stats: BTreeMap<String, BTreeMap<String, MonitorStats>>,
+	/// Health of the RPC endpoints, keyed by network slug
+	pub endpoints: BTreeMap<String, EndpointHealth>,
+}
+
+impl StateDump {
+	/// Captures the current state of the service
+	///
+	/// # Arguments
+	/// * `dispatch_queue` - The notification dispatch queue of the trigger handler
+	pub fn capture(dispatch_queue: &PriorityDispatchQueue) -> Self {
+		let today = Utc::now().date_naive().to_string();
+		let mut monitor_stats: BTreeMap<String, BTreeMap<String, MonitorStats>> = BTreeMap::new();
+		for count in MATCH_STATS.query(None, None, None) {
+			let stats = monitor_stats
+				.entry(count.monitor)
+				.or_default()
+				.entry(count.network)
+				.or_default();
+			stats.matches_total += count.matches;
+			if count.date == today {
+				stats.matches_today += count.matches;
+			}
+		}
+
+		Self {
+			generated_at: Utc::now().timestamp(),
+			networks: CONTROL.networks(),
+			paused_monitors: CONTROL.paused_monitors(),
+			queues: QueueState {
+				in_flight_dispatches: CONTROL.in_flight_dispatches(),
+				pending_dispatches: dispatch_queue.pending(),
+			},
+			monitor_stats,
+			endpoints: endpoint_health(&REGISTRY.gather()),
+		}
+	}
+}
+
+/// Captures the state of the service and writes it to a directory
+///
+/// # Arguments
+/// * `dir` - Directory of the dumps, created if missing
+/// * `dispatch_queue` - The notification dispatch queue of the trigger handler
+///
+/// # Returns
+/// * `std::io::Result<PathBuf>` - Path of the written file
+pub async fn write_state_dump(
+	dir: &Path,
+	dispatch_queue: &PriorityDispatchQueue,
+) -> std::io::Result<PathBuf> {
+	write_state_dump_to(dir, &StateDump::capture(dispatch_queue)).await
+}
+
+/// Writes a state dump to a directory
+///
+/// # Arguments
+/// * `dir` - Directory of the dumps, created if missing
+/// * `dump` - The dump to write
+///
+/// # Returns
+/// * `std::io::Result<PathBuf>` - Path of the written file
+pub async fn write_state_dump_to(dir: &Path, dump: &StateDump) -> std::io::Result<PathBuf> {
+	tokio::fs::create_dir_all(dir).await?;
+	let path = dir.join(format!(
+		"state_{}.json",
+		Utc::now().format("%Y%m%dT%H%M%S%.3fZ")
+	));
+	tokio::fs::write(&path, serde_json::to_string_pretty(dump)?).await?;
+	Ok(path)
+}
+
+/// Collects the RPC endpoint health from the gathered metrics
+fn endpoint_health(families: &[MetricFamily]) -> BTreeMap<String, EndpointHealth> {
+	let mut endpoints: BTreeMap<String, EndpointHealth> = BTreeMap::new();
+
+	for family in families {
+		for metric in family.get_metric() {
+			let label = |name: &str| {
+				metric
+					.get_label()
+					.iter()
+					.find(|label| label.name() == name)
+					.map(|label| label.value().to_string())
+			};
+			let Some(network) = label("network") else {
+				continue;
+			};
+			let counter = metric.get_counter().value();
+			let gauge = metric.get_gauge().value();
+
+			match family.name() {
+				"rpc_requests_total" => endpoints.entry(network).or_default().requests += counter,
+				"rpc_request_errors_total" => {
+					endpoints.entry(network).or_default().errors += counter
+				}
+				"rpc_endpoint_rotations_total" => {
+					endpoints.entry(network).or_default().rotations += counter
+				}
+				"rpc_rate_limits_total" => {
+					let endpoint = label("endpoint").unwrap_or_default();
+					*endpoints
+						.entry(network)
+						.or_default()
+						.rate_limits
+						.entry(endpoint)
+						.or_default() += counter;
+				}
+				"rpc_endpoint_block_lag" => {
+					let endpoint = label("endpoint").unwrap_or_default();
+					endpoints
+						.entry(network)
+						.or_default()
+						.block_lag
+						.insert(endpoint, gauge);
+				}
+				_ => {}
+			}
+		}
+	}
+
+	endpoints
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use prometheus::{CounterVec, GaugeVec, Opts, Registry};
+
+	#[test]
+	fn test_capture() {
+		CONTROL.register_networks(&["state_dump_network".to_string()]);
+		CONTROL.record_checkpoint("state_dump_network", 100, 105);
+
+		let dump = StateDump::capture(&PriorityDispatchQueue::new(0));
+
+		assert_eq!(
+			dump.networks["state_dump_network"].last_processed_block,
+			Some(100)
+		);
+	}
+
+	#[test]
+	fn test_endpoint_health() {
+		let registry = Registry::new();
+		let requests = CounterVec::new(
+			Opts::new("rpc_requests_total", "Requests"),
+			&["network", "method"],
+		)
+		.unwrap();
+		let block_lag = GaugeVec::new(
+			Opts::new("rpc_endpoint_block_lag", "Block lag"),
+			&["network", "endpoint"],
+		)
+		.unwrap();
+		registry.register(Box::new(requests.clone())).unwrap();
+		registry.register(Box::new(block_lag.clone())).unwrap();
+
+		requests
+			.with_label_values(&["ethereum_mainnet", "eth_blockNumber"])
+			.inc_by(3.0);
+		requests
+			.with_label_values(&["ethereum_mainnet", "eth_getLogs"])
+			.inc_by(2.0);
+		block_lag
+			.with_label_values(&["ethereum_mainnet", "https://rpc.example.com"])
+			.set(7.0);
+
+		let endpoints = endpoint_health(&registry.gather());
+		let health = &endpoints["ethereum_mainnet"];
+		assert_eq!(health.requests, 5.0);
+		assert_eq!(health.block_lag["https://rpc.example.com"], 7.0);
+	}
+
+	#[tokio::test]
+	async fn test_write_state_dump_to() {
+		let temp_dir = tempfile::tempdir().unwrap();
+		let dir = temp_dir.path().join("dumps");
+
+		let path = write_state_dump_to(&dir, &StateDump::capture(&PriorityDispatchQueue::new(0)))
+			.await
+			.unwrap();
+
+		let content: serde_json::Value =
+			serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+		assert!(content.get("networks").is_some());
+		assert!(content.get("queues").is_some());
+		assert!(content.get("endpoints").is_some());
+	}
+}
@@ -0,0 +1,234 @@
+//! Panic handling.
+//!
+//! [`install_panic_hook`] replaces the default panic hook of the service. Every panic is
+//! logged and counted in the `panics_total` metric. With [`PanicMode::Abort`], the default
+//! `--panic-mode`, a panic flushes the buffered state to disk before aborting the
+//! process. The hook runs on the panicking thread, which may hold locks of the state and
+//! cannot rely on the runtime, so the state is written synchronously and skipped if its lock
+//! is held: the match statistics, the in-memory window store, the monitor states of the
+//! filters, the notifications waiting in
+//! the ordering buffers and the dispatch queue (dispatched again on the next start, see
+//! [`PendingNotifications`]) and the last block of every network whose notifications are
+//! dispatched or written. Notifications being sent are lost and reported.
+//!
+//! With [`PanicMode::Isolate`], a panic in a network watcher (code run within
+//! [`network_scope`]) is only logged by the hook and unwinds to the task of the network: the
+//! block processing run fails at its `JoinHandle`, the checkpoint of the network is not
+//! advanced, and the [`RestartTracker`](crate::services::blockwatcher::RestartTracker) of the
+//! network suspends its runs. The other networks are not affected. Panics elsewhere still
+//! flush the state and abort.
+
+use std::{
+	future::Future,
+	panic::PanicHookInfo,
+	str::FromStr,
+	sync::{
+		atomic::{AtomicBool, Ordering},
+		Arc,
+	},
+};
+
+use crate::{
+	services::{
+		blockwatcher::FileBlockStorage,
+		filter::{FilterContext, WindowStore},
+		trigger::PendingNotifications,
+	},
+	utils::{
+		control::CONTROL,
+		metrics::{stats::MATCH_STATS, PANICS_TOTAL},
+	},
+};
+
+tokio::task_local! {
+	/// Slug of the network whose watcher runs the current task
+	static WATCHER_NETWORK: String;
+}
+
+/// Set by the first fatal panic, so that concurrent panics do not flush twice
+static ABORTING: AtomicBool = AtomicBool::new(false);
+
+/// Handling of panics
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PanicMode {
+	/// Any panic flushes the state and aborts the process
+	#[default]
+	Abort,
+	/// A panic in a network watcher only fails its task and suspends the network, other
+	/// panics abort
+	Isolate,
+}
+
+/// State of the service flushed by the panic hook before aborting
+#[derive(Clone)]
+pub struct PanicContext {
+	/// The window store of the service
+	pub window_store: Arc<dyn WindowStore>,
+	/// The notifications waiting to be dispatched
+	pub pending_notifications: Arc<PendingNotifications>,
+	/// The storage of the last processed blocks
+	pub block_storage: Arc<FileBlockStorage>,
+	/// The state shared by the filters, holding the monitor states
+	pub filter_context: FilterContext,
+}
+
+impl FromStr for PanicMode {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s.to_lowercase().as_str() {
+			"" | "abort" => Ok(Self::Abort),
+			"isolate" => Ok(Self::Isolate),
+			other => Err(format!(
+				"Invalid panic mode '{}', expected abort or isolate",
+				other
+			)),
+		}
+	}
+}
+
+/// Runs a future as part of the watcher of a network
+///
+/// Panics of the future are attributed to the network and, with [`PanicMode::Isolate`], do
+/// not abort the process. Tasks spawned by the future are not part of the scope and must be
+/// wrapped themselves.
+///
+/// # Arguments
+/// * `network_slug` - Slug of the network
+/// * `future` - The future to run
+pub fn network_scope<F: Future>(network_slug: &str, future: F) -> impl Future<Output = F::Output> {
+	WATCHER_NETWORK.scope(network_slug.to_string(), future)
+}
+
+/// Installs the panic hook of the service
+///
+/// # Arguments
+/// * `mode` - Handling of panics
+/// * `context` - The state flushed before aborting
+pub fn install_panic_hook(mode: PanicMode, context: PanicContext) {
+	let default_hook = std::panic::take_hook();
+	std::panic::set_hook(Box::new(move |info| {
+		let network = WATCHER_NETWORK.try_with(|slug| slug.clone()).ok();
+		PANICS_TOTAL
+			.with_label_values(&[network.as_deref().unwrap_or_default()])
+			.inc();
+
+		if let (PanicMode::Isolate, Some(network)) = (mode, &network) {
+			// The panic unwinds to the task of the network, whose failure suspends the network
+			tracing::error!(
+				network = %network,
+				"Network watcher panicked, failing its task: {}",
+				describe(info)
+			);
+			return;
+		}
+
+		if ABORTING.swap(true, Ordering::SeqCst) {
+			// Another panic is already flushing the state, the process is about to abort
+			loop {
+				std::thread::park();
+			}
+		}
+		tracing::error!(
+			network = network.as_deref().unwrap_or_default(),
+			"Service panicked, flushing state before aborting: {}",
+			describe(info)
+		);
+		default_hook(info);
+		flush_state(&context);
+		std::process::abort();
+	}));
+	tracing::info!("Panic handling mode: {:?}", mode);
+}
+
+/// Persists the state that can be written without the runtime
+///
+/// Locks held by the panicking thread are not waited for, the state they guard is not
+/// flushed. The networks are only checkpointed once their pending notifications are written.
+fn flush_state(context: &PanicContext) {
+	MATCH_STATS.persist_blocking();
+	if let Err(e) = context.window_store.persist_blocking() {
+		tracing::error!("Failed to persist the window store: {}", e);
+	}
+	context.filter_context.persist_blocking();
+
+	let pending = context.pending_notifications.persist_blocking();
+	let checkpoints = match pending {
+		Ok((written, checkpoints)) => {
+			if written > 0 {
+				tracing::error!(
+					"Wrote {} pending notifications, dispatched on the next start",
+					written
+				);
+			}
+			checkpoints
+		}
+		Err(e) => {
+			tracing::error!("Failed to persist the pending notifications: {}", e);
+			Default::default()
+		}
+	};
+	for (network, block) in checkpoints {
+		match context
+			.block_storage
+			.advance_last_processed_block_blocking(&network, block)
+		{
+			Ok(true) => tracing::error!(
+				network = %network,
+				"Saved block {} as the last processed block",
+				block
+			),
+			Ok(false) => {}
+			Err(e) => tracing::error!(
+				network = %network,
+				"Failed to save the last processed block: {}",
+				e
+			),
+		}
+	}
+
+	let in_flight = CONTROL.in_flight_dispatches();
+	if in_flight > 0 {
+		tracing::error!(
+			"Aborting with {} notification dispatches in flight",
+			in_flight
+		);
+	}
+}
+
+/// Formats the message and location of a panic
+fn describe(info: &PanicHookInfo<'_>) -> String {
+	let message = info
+		.payload()
+		.downcast_ref::<&str>()
+		.map(|message| message.to_string())
+		.or_else(|| info.payload().downcast_ref::<String>().cloned())
+		.unwrap_or_else(|| "unknown panic".to_string());
+	match info.location() {
+		Some(location) => format!("{} at {}", message, location),
+		None => message,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_panic_mode_from_str() {
+		assert_eq!("abort".parse::<PanicMode>(), Ok(PanicMode::Abort));
+		assert_eq!("".parse::<PanicMode>(), Ok(PanicMode::Abort));
+		assert_eq!("Isolate".parse::<PanicMode>(), Ok(PanicMode::Isolate));
+		assert!("ignore".parse::<PanicMode>().is_err());
+	}
+
+	#[tokio::test]
+	async fn test_network_scope() {
+		assert!(WATCHER_NETWORK.try_with(|_| ()).is_err());
+		let network = network_scope("ethereum_mainnet", async {
+			WATCHER_NETWORK.try_with(|slug| slug.clone()).ok()
+		})
+		.await;
+		assert_eq!(network.as_deref(), Some("ethereum_mainnet"));
+	}
+}
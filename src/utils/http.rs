@@ -1,10 +1,10 @@
 use reqwest_middleware::{ClientBuilder, ClientWithMiddleware};
-use reqwest_retry::{
-	policies::ExponentialBackoff, Jitter, RetryTransientMiddleware, RetryableStrategy,
-};
+use reqwest_retry::{RetryTransientMiddleware, RetryableStrategy};
 use serde::{Deserialize, Serialize};
 use std::time::Duration;
 
+use crate::utils::retry::RetryPolicy;
+
 /// --- Default values for retry configuration settings ---
 fn default_max_attempts() -> u32 {
 	3
@@ -66,10 +66,86 @@ impl Default for RetryConfig {
 	}
 }
 
+/// HTTP client settings of a notifier, for endpoints that are slow, use a private certificate
+/// authority or can only be reached through a proxy
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(deny_unknown_fields)]
+pub struct HttpClientConfig {
+	/// Timeout of a request, in milliseconds (no timeout by default)
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub timeout_ms: Option<u64>,
+	/// Timeout of the connection, in milliseconds (10 seconds by default)
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub connect_timeout_ms: Option<u64>,
+	/// Path of a PEM file with additional root certificates to trust
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub ca_cert_path: Option<String>,
+	/// Whether invalid TLS certificates and host names are accepted
+	#[serde(default, skip_serializing_if = "std::ops::Not::not")]
+	pub accept_invalid_certs: bool,
+	/// URL of the proxy the requests are sent through
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub proxy: Option<String>,
+}
+
+impl HttpClientConfig {
+	/// Default timeout of the connection
+	pub const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+	/// Returns true if no setting differs from the defaults
+	pub fn is_default(&self) -> bool {
+		*self == Self::default()
+	}
+
+	/// Applies the settings to an HTTP client builder
+	///
+	/// # Arguments
+	/// * `builder` - The builder to configure
+	///
+	/// # Returns
+	/// The configured builder
+	///
+	/// # Errors
+	/// Returns an error if the root certificates cannot be read or the proxy URL is invalid
+	pub fn apply(
+		&self,
+		mut builder: reqwest::ClientBuilder,
+	) -> Result<reqwest::ClientBuilder, anyhow::Error> {
+		builder = builder.connect_timeout(
+			self.connect_timeout_ms
+				.map(Duration::from_millis)
+				.unwrap_or(Self::DEFAULT_CONNECT_TIMEOUT),
+		);
+		if let Some(timeout_ms) = self.timeout_ms {
+			builder = builder.timeout(Duration::from_millis(timeout_ms));
+		}
+		if let Some(path) = &self.ca_cert_path {
+			let pem = std::fs::read(path)
+				.map_err(|e| anyhow::anyhow!("Failed to read CA certificates {}: {}", path, e))?;
+			for certificate in reqwest::Certificate::from_pem_bundle(&pem)
+				.map_err(|e| anyhow::anyhow!("Invalid CA certificates {}: {}", path, e))?
+			{
+				builder = builder.add_root_certificate(certificate);
+			}
+		}
+		if self.accept_invalid_certs {
+			builder = builder.danger_accept_invalid_certs(true);
+		}
+		if let Some(proxy) = &self.proxy {
+			builder = builder.proxy(
+				reqwest::Proxy::all(proxy)
+					.map_err(|e| anyhow::anyhow!("Invalid proxy URL {}: {}", proxy, e))?,
+			);
+		}
+		Ok(builder)
+	}
+}
+
 /// Creates a retryable HTTP client with middleware for a single URL
 ///
 /// # Parameters:
-/// - `config`: Configuration for retry policies
+/// - `retry_policy`: Retry policy of the component sending the requests (e.g. `rpc`,
+///   `webhook`), whose budget and metrics the retries share
 /// - `base_client`: The base HTTP client to use
 /// - `custom_strategy`: Optional custom retry strategy, complementing the default retry behavior
 ///
@@ -77,25 +153,13 @@ impl Default for RetryConfig {
 /// A `ClientWithMiddleware` that includes retry capabilities
 ///
 pub fn create_retryable_http_client<S>(
-	config: &RetryConfig,
+	retry_policy: RetryPolicy,
 	base_client: reqwest::Client,
 	custom_strategy: Option<S>,
 ) -> ClientWithMiddleware
 where
 	S: RetryableStrategy + Send + Sync + 'static,
 {
-	// Determine the jitter setting and create the policy builder accordingly
-	let policy_builder = match config.jitter {
-		JitterSetting::None => ExponentialBackoff::builder().jitter(Jitter::None),
-		JitterSetting::Full => ExponentialBackoff::builder().jitter(Jitter::Full),
-	};
-
-	// Create the retry policy based on the provided configuration
-	let retry_policy = policy_builder
-		.base(config.base_for_backoff)
-		.retry_bounds(config.initial_backoff, config.max_backoff)
-		.build_with_max_retries(config.max_retries);
-
 	// If a custom strategy is provided, use it with the retry policy; otherwise, use the retry policy with the default strategy.
 	if let Some(strategy) = custom_strategy {
 		ClientBuilder::new(base_client).with(
@@ -1,17 +1,18 @@
-//! ## Sets up logging by reading configuration from environment variables.
+//! ## Sets up logging from a [`LoggingConfig`].
 //!
-//! Environment variables used:
+//! The service builds the configuration from its runtime settings:
 //! - LOG_MODE: "stdout" (default) or "file"
 //! - LOG_LEVEL: log level ("trace", "debug", "info", "warn", "error"); default is "info"
 //! - LOG_DATA_DIR: directory for log files; default is "logs/"
-//! - LOG_MAX_SIZE: maximum size of log files in bytes; default is 1GB
-//! - IN_DOCKER: "true" if running in Docker; default is "false"
+//! - LOG_MAX_SIZE: maximum size of log files; default is 1GB
+//! - LOG_REDACT_PATTERN: additional regex whose matches are redacted from log output
+//! - IN_DOCKER: "true" if running in Docker, logs are then written to "logs/"
 
 pub mod error;
+pub mod redaction;
 
 use chrono::Utc;
 use std::{
-	env,
 	fs::{create_dir_all, metadata},
 	path::Path,
 };
@@ -62,6 +63,38 @@ where
 	}
 }
 
+/// Custom formatter that redacts secrets from log output
+struct RedactingFormatter<T> {
+	inner: T,
+}
+
+impl<T> RedactingFormatter<T> {
+	fn new(inner: T) -> Self {
+		Self { inner }
+	}
+}
+
+impl<S, N, T> FormatEvent<S, N> for RedactingFormatter<T>
+where
+	S: Subscriber + for<'a> LookupSpan<'a>,
+	N: for<'a> FormatFields<'a> + 'static,
+	T: FormatEvent<S, N>,
+{
+	fn format_event(
+		&self,
+		ctx: &FmtContext<'_, S, N>,
+		mut writer: Writer<'_>,
+		event: &tracing::Event<'_>,
+	) -> std::fmt::Result {
+		let mut buf = String::new();
+		let string_writer = Writer::new(&mut buf);
+
+		self.inner.format_event(ctx, string_writer, event)?;
+
+		write!(writer, "{}", redaction::redact(&buf))
+	}
+}
+
 /// Strips ANSI escape codes from a string
 fn strip_ansi_escapes(s: &str) -> String {
 	// Simple regex to match ANSI escape sequences
@@ -104,6 +137,36 @@ pub fn space_based_rolling(
 	final_path
 }
 
+/// Default maximum size of a log file, in bytes (1GB)
+pub const DEFAULT_LOG_MAX_SIZE: u64 = 1_073_741_824;
+
+/// Configuration of the logging
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoggingConfig {
+	/// Whether logs are written to files instead of stdout
+	pub to_file: bool,
+	/// Log level ("trace", "debug", "info", "warn", "error")
+	pub level: String,
+	/// Directory of the log files
+	pub data_dir: String,
+	/// Size of a log file before a new one is started, in bytes
+	pub max_size: u64,
+	/// Additional regex whose matches are redacted from the logs
+	pub redact_pattern: Option<String>,
+}
+
+impl Default for LoggingConfig {
+	fn default() -> Self {
+		Self {
+			to_file: false,
+			level: "info".to_string(),
+			data_dir: "logs/".to_string(),
+			max_size: DEFAULT_LOG_MAX_SIZE,
+			redact_pattern: None,
+		}
+	}
+}
+
 /// Creates a log format with configurable ANSI support
 fn create_log_format(with_ansi: bool) -> fmt::format::Format<fmt::format::Compact> {
 	fmt::format()
@@ -115,10 +178,13 @@ fn create_log_format(with_ansi: bool) -> fmt::format::Format<fmt::format::Compac
 		.compact()
 }
 
-/// Sets up logging by reading configuration from environment variables.
-pub fn setup_logging() -> Result<(), Box<dyn std::error::Error>> {
-	let log_mode = env::var("LOG_MODE").unwrap_or_else(|_| "stdout".to_string());
-	let log_level = env::var("LOG_LEVEL").unwrap_or_else(|_| "info".to_string());
+/// Sets up logging
+///
+/// # Arguments
+/// * `config` - Configuration of the logging
+pub fn setup_logging(config: &LoggingConfig) -> Result<(), Box<dyn std::error::Error>> {
+	let log_mode = if config.to_file { "file" } else { "stdout" };
+	let log_level = &config.level;
 
 	// Parse the log level
 	let level_filter = match log_level.to_lowercase().as_str() {
@@ -130,27 +196,24 @@ pub fn setup_logging() -> Result<(), Box<dyn std::error::Error>> {
 		_ => tracing::Level::INFO,
 	};
 
+	// Register the user-defined redaction pattern
+	if let Some(pattern) = &config.redact_pattern {
+		let pattern = regex::Regex::new(pattern)
+			.map_err(|e| format!("LOG_REDACT_PATTERN must be a valid regex: {}", e))?;
+		redaction::set_extra_pattern(pattern);
+	}
+
 	// Create a format with ANSI disabled for file logging and enabled for stdout
-	let with_ansi = log_mode.to_lowercase() != "file";
-	let format = create_log_format(with_ansi);
+	let with_ansi = !config.to_file;
+	let format = RedactingFormatter::new(create_log_format(with_ansi));
 
 	// Create a subscriber with the specified log level
 	let subscriber = tracing_subscriber::registry().with(EnvFilter::new(level_filter.to_string()));
 
-	if log_mode.to_lowercase() == "file" {
+	if config.to_file {
 		info!("Logging to file: {}", log_level);
 
-		// Use logs/ directly in container path, otherwise use LOG_DATA_DIR or default to logs/ for host path
-		let log_dir = if env::var("IN_DOCKER")
-			.map(|val| val == "true")
-			.unwrap_or(false)
-		{
-			"logs/".to_string()
-		} else {
-			env::var("LOG_DATA_DIR").unwrap_or_else(|_| "logs/".to_string())
-		};
-
-		let log_dir = format!("{}/", log_dir.trim_end_matches('/'));
+		let log_dir = format!("{}/", config.data_dir.trim_end_matches('/'));
 		// set dates
 		let now = Utc::now();
 		let date_str = now.format("%Y-%m-%d").to_string();
@@ -174,11 +237,13 @@ pub fn setup_logging() -> Result<(), Box<dyn std::error::Error>> {
 			create_dir_all(parent).expect("Failed to create log directory");
 		}
 
-		// Space-based rolling: if an existing log file exceeds 1GB, adopt a new file name.
-		let max_size = parse_log_max_size();
-
-		let final_path =
-			space_based_rolling(&time_based_path, &base_file_path, &date_str, max_size);
+		// Space-based rolling: if an existing log file exceeds max_size, adopt a new file name.
+		let final_path = space_based_rolling(
+			&time_based_path,
+			&base_file_path,
+			&date_str,
+			config.max_size,
+		);
 
 		// Create a file appender
 		let file_appender = tracing_appender::rolling::never(
@@ -211,15 +276,6 @@ pub fn setup_logging() -> Result<(), Box<dyn std::error::Error>> {
 	Ok(())
 }
 
-fn parse_log_max_size() -> u64 {
-	env::var("LOG_MAX_SIZE")
-		.map(|s| {
-			s.parse::<u64>()
-				.expect("LOG_MAX_SIZE must be a valid u64 if set")
-		})
-		.unwrap_or(1_073_741_824)
-}
-
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -273,12 +329,4 @@ mod tests {
 		let result = space_based_rolling(&initial_path, &base_path, date_str, 200);
 		assert_eq!(result, initial_path);
 	}
-
-	// This test checks if the LOG_MAX_SIZE environment variable is set to a valid u64 value.
-	#[test]
-	#[should_panic(expected = "LOG_MAX_SIZE must be a valid u64 if set")]
-	fn test_invalid_log_max_size_panics() {
-		std::env::set_var("LOG_MAX_SIZE", "not_a_number");
-		let _ = parse_log_max_size(); // should panic here
-	}
 }
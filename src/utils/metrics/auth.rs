@@ -0,0 +1,363 @@
+//! API token authentication for the metrics server.
+//!
+//! Tokens are configured with a scope (`read`, `operator` or `admin`), each scope granting the
+//! permissions of the previous ones. Read-only requests require the `read` scope, mutating
+//! requests the `operator` scope and `/admin` routes the `admin` scope. Mutating requests are
+//! written to the `audit` log target. When no token is configured, authentication is disabled.
+//!
+//! Tokens are loaded from the JSON file of the `API_TOKENS_FILE` setting and from the
+//! `API_READ_TOKEN`, `API_OPERATOR_TOKEN` and `API_ADMIN_TOKEN` environment variables.
+
+use actix_web::{
+	body::MessageBody,
+	dev::{ServiceRequest, ServiceResponse},
+	error::{ErrorForbidden, ErrorUnauthorized},
+	http::{header::AUTHORIZATION, Method},
+	middleware::Next,
+	web, Error,
+};
+use serde::Deserialize;
+use std::{env, path::Path};
+
+use crate::models::{SecretString, SecretValue};
+
+/// Permission level of an API token
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ApiScope {
+	/// Read-only access
+	Read,
+	/// Read access and mutating operations
+	Operator,
+	/// Full access, including the admin routes
+	Admin,
+}
+
+impl ApiScope {
+	/// Returns the scope required to perform a request
+	///
+	/// # Arguments
+	/// * `method` - HTTP method of the request
+	/// * `path` - Path of the request
+	pub fn required_for(method: &Method, path: &str) -> Self {
+		if path == "/admin" || path.starts_with("/admin/") {
+			ApiScope::Admin
+		} else if is_mutating(method) {
+			ApiScope::Operator
+		} else {
+			ApiScope::Read
+		}
+	}
+}
+
+/// API token as defined in the tokens file
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ApiTokenConfig {
+	/// Name of the token, used in audit logs
+	pub name: String,
+	/// Value of the token
+	pub token: SecretValue,
+	/// Permission level of the token
+	pub scope: ApiScope,
+}
+
+/// Resolved API token
+#[derive(Debug, Clone)]
+struct ApiToken {
+	name: String,
+	token: SecretString,
+	scope: ApiScope,
+}
+
+/// Set of API tokens accepted by the server
+#[derive(Debug, Clone, Default)]
+pub struct ApiTokens {
+	tokens: Vec<ApiToken>,
+}
+
+impl ApiTokens {
+	/// Loads the tokens from the tokens file and the environment
+	///
+	/// # Arguments
+	/// * `tokens_file` - JSON file of the tokens, if any
+	///
+	/// # Returns
+	/// The tokens, or an error if the tokens file cannot be read or a secret cannot be resolved
+	pub async fn load(tokens_file: Option<&Path>) -> Result<Self, anyhow::Error> {
+		let mut configs = match tokens_file {
+			Some(path) => Self::read_file(path)?,
+			None => Vec::new(),
+		};
+		for (var, scope) in [
+			("API_READ_TOKEN", ApiScope::Read),
+			("API_OPERATOR_TOKEN", ApiScope::Operator),
+			("API_ADMIN_TOKEN", ApiScope::Admin),
+		] {
+			if env::var(var).is_ok_and(|value| !value.is_empty()) {
+				configs.push(ApiTokenConfig {
+					name: var.to_lowercase(),
+					token: SecretValue::Environment(var.to_string()),
+					scope,
+				});
+			}
+		}
+		Self::resolve(configs).await
+	}
+
+	/// Resolves the secret of every token
+	///
+	/// # Arguments
+	/// * `configs` - The tokens to resolve
+	pub async fn resolve(configs: Vec<ApiTokenConfig>) -> Result<Self, anyhow::Error> {
+		let mut tokens = Vec::with_capacity(configs.len());
+		for config in configs {
+			let token = config.token.resolve().await.map_err(|e| {
+				anyhow::anyhow!("Failed to resolve API token '{}': {}", config.name, e)
+			})?;
+			if token.as_str().is_empty() {
+				anyhow::bail!("API token '{}' is empty", config.name);
+			}
+			tokens.push(ApiToken {
+				name: config.name,
+				token,
+				scope: config.scope,
+			});
+		}
+		Ok(Self { tokens })
+	}
+
+	/// Returns whether authentication is enabled
+	pub fn is_enabled(&self) -> bool {
+		!self.tokens.is_empty()
+	}
+
+	/// Finds the token matching a bearer value
+	///
+	/// # Returns
+	/// The name and scope of the token
+	fn authenticate(&self, bearer: &str) -> Option<(&str, ApiScope)> {
+		self.tokens
+			.iter()
+			.find(|token| constant_time_eq(token.token.as_str().as_bytes(), bearer.as_bytes()))
+			.map(|token| (token.name.as_str(), token.scope))
+	}
+
+	fn read_file(path: &Path) -> Result<Vec<ApiTokenConfig>, anyhow::Error> {
+		let content = std::fs::read_to_string(path)
+			.map_err(|e| anyhow::anyhow!("Failed to read {}: {}", path.display(), e))?;
+		serde_json::from_str(&content)
+			.map_err(|e| anyhow::anyhow!("Failed to parse {}: {}", path.display(), e))
+	}
+}
+
+/// Middleware enforcing the API token scopes and logging mutating requests
+///
+/// Requests pass through unchanged when no [`ApiTokens`] are registered in the app data or
+/// none is configured.
+pub async fn authorize(
+	req: ServiceRequest,
+	next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, Error> {
+	let tokens = req
+		.app_data::<web::Data<ApiTokens>>()
+		.filter(|tokens| tokens.is_enabled())
+		.cloned();
+	let Some(tokens) = tokens else {
+		return next.call(req).await;
+	};
+
+	let method = req.method().clone();
+	let path = req.path().to_string();
+	let bearer = req
+		.headers()
+		.get(AUTHORIZATION)
+		.and_then(|value| value.to_str().ok())
+		.and_then(|value| value.strip_prefix("Bearer "))
+		.map(str::trim);
+
+	let Some((name, scope)) = bearer.and_then(|bearer| tokens.authenticate(bearer)) else {
+		tracing::warn!(target: "audit", method = %method, path = %path, "Rejected request without a valid API token");
+		return Err(ErrorUnauthorized("Missing or invalid API token"));
+	};
+	let name = name.to_string();
+
+	let required = ApiScope::required_for(&method, &path);
+	if scope < required {
+		tracing::warn!(
+			target: "audit",
+			token = %name,
+			method = %method,
+			path = %path,
+			"Rejected request: token scope {:?} does not grant {:?}",
+			scope,
+			required
+		);
+		return Err(ErrorForbidden("Insufficient API token scope"));
+	}
+
+	let response = next.call(req).await?;
+	if is_mutating(&method) {
+		tracing::info!(
+			target: "audit",
+			token = %name,
+			method = %method,
+			path = %path,
+			status = response.status().as_u16(),
+			"Mutating API request"
+		);
+	}
+	Ok(response)
+}
+
+fn is_mutating(method: &Method) -> bool {
+	!matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS)
+}
+
+/// Compares two byte strings in constant time with respect to their content
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+	a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use actix_web::{
+		http::StatusCode,
+		middleware::from_fn,
+		test::{init_service, try_call_service, TestRequest},
+		web::Data,
+		App, HttpResponse,
+	};
+	use std::io::Write;
+	use tempfile::NamedTempFile;
+
+	fn token(name: &str, value: &str, scope: ApiScope) -> ApiTokenConfig {
+		ApiTokenConfig {
+			name: name.to_string(),
+			token: SecretValue::Plain(SecretString::new(value.to_string())),
+			scope,
+		}
+	}
+
+	async fn call(
+		tokens: ApiTokens,
+		method: Method,
+		path: &str,
+		bearer: Option<&str>,
+	) -> StatusCode {
+		let app = init_service(
+			App::new()
+				.wrap(from_fn(authorize))
+				.app_data(Data::new(tokens))
+				.default_service(web::to(HttpResponse::Ok)),
+		)
+		.await;
+
+		let mut req = TestRequest::default().method(method).uri(path);
+		if let Some(bearer) = bearer {
+			req = req.insert_header((AUTHORIZATION, format!("Bearer {}", bearer)));
+		}
+		match try_call_service(&app, req.to_request()).await {
+			Ok(response) => response.status(),
+			Err(e) => e.as_response_error().status_code(),
+		}
+	}
+
+	#[test]
+	fn test_required_scope() {
+		assert_eq!(
+			ApiScope::required_for(&Method::GET, "/stats"),
+			ApiScope::Read
+		);
+		assert_eq!(
+			ApiScope::required_for(&Method::POST, "/monitors"),
+			ApiScope::Operator
+		);
+		assert_eq!(
+			ApiScope::required_for(&Method::GET, "/admin/tokens"),
+			ApiScope::Admin
+		);
+		assert!(ApiScope::Admin > ApiScope::Operator && ApiScope::Operator > ApiScope::Read);
+	}
+
+	#[actix_web::test]
+	async fn test_authorize_disabled_without_tokens() {
+		let status = call(ApiTokens::default(), Method::POST, "/admin", None).await;
+		assert_eq!(status, StatusCode::OK);
+	}
+
+	#[actix_web::test]
+	async fn test_authorize_enforces_scopes() {
+		let tokens = ApiTokens::resolve(vec![
+			token("grafana", "read-token-value", ApiScope::Read),
+			token("ops", "operator-token-value", ApiScope::Operator),
+		])
+		.await
+		.unwrap();
+
+		let cases = [
+			(Method::GET, "/stats", None, StatusCode::UNAUTHORIZED),
+			(
+				Method::GET,
+				"/stats",
+				Some("wrong"),
+				StatusCode::UNAUTHORIZED,
+			),
+			(
+				Method::GET,
+				"/stats",
+				Some("read-token-value"),
+				StatusCode::OK,
+			),
+			(
+				Method::POST,
+				"/stats",
+				Some("read-token-value"),
+				StatusCode::FORBIDDEN,
+			),
+			(
+				Method::POST,
+				"/stats",
+				Some("operator-token-value"),
+				StatusCode::OK,
+			),
+			(
+				Method::GET,
+				"/admin/state",
+				Some("operator-token-value"),
+				StatusCode::FORBIDDEN,
+			),
+		];
+		for (method, path, bearer, expected) in cases {
+			assert_eq!(
+				call(tokens.clone(), method.clone(), path, bearer).await,
+				expected,
+				"{} {} with {:?}",
+				method,
+				path,
+				bearer
+			);
+		}
+	}
+
+	#[tokio::test]
+	async fn test_read_tokens_file() {
+		let mut file = NamedTempFile::new().unwrap();
+		write!(
+			file,
+			r#"[{{"name": "admin", "scope": "admin", "token": {{"type": "plain", "value": "admin-token-value"}}}}]"#
+		)
+		.unwrap();
+
+		let configs = ApiTokens::read_file(file.path()).unwrap();
+		let tokens = ApiTokens::resolve(configs).await.unwrap();
+
+		assert!(tokens.is_enabled());
+		assert_eq!(
+			tokens.authenticate("admin-token-value"),
+			Some(("admin", ApiScope::Admin))
+		);
+		assert_eq!(tokens.authenticate("admin-token"), None);
+	}
+}
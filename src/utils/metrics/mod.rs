@@ -3,7 +3,10 @@
 //! - This module contains the global Prometheus registry.
 //! - Defines specific metrics for the application.
 
+pub mod auth;
 pub mod server;
+pub mod stats;
+pub mod usage;
 use lazy_static::lazy_static;
 use prometheus::{
 	CounterVec, Encoder, Gauge, GaugeVec, HistogramOpts, HistogramVec, Opts, Registry, TextEncoder,
@@ -125,6 +128,27 @@ lazy_static! {
 		gauge
 	};
 
+	/// Gauge Vector for the applied configuration version.
+	///
+	/// Set to 1 for the fingerprint of the monitors, networks and triggers the service runs with.
+	pub static ref CONFIG_VERSION_INFO: GaugeVec = {
+		let gauge = GaugeVec::new(
+			Opts::new("config_version_info", "Version of the applied configuration"),
+			&["version"]
+		).unwrap();
+		REGISTRY.register(Box::new(gauge.clone())).unwrap();
+		gauge
+	};
+
+	/// Gauge for the time the configuration was applied.
+	///
+	/// Tracks the Unix timestamp at which the service started with its current configuration.
+	pub static ref CONFIG_APPLIED_TIMESTAMP_SECONDS: Gauge = {
+		let gauge = Gauge::new("config_applied_timestamp_seconds", "Unix timestamp of the application of the configuration").unwrap();
+		REGISTRY.register(Box::new(gauge.clone())).unwrap();
+		gauge
+	};
+
 	/// Gauge Vector for per-network metrics.
 	///
 	/// Tracks the number of active monitors for each network, with the network name as a label.
@@ -151,6 +175,200 @@ lazy_static! {
 		gauge
 	};
 
+	/// Counter Vector for monitor matches.
+	///
+	/// Tracks the number of matches that passed the trigger conditions, labeled by monitor
+	/// name and network slug. Daily aggregates are also kept by the `stats` module.
+	pub static ref MONITOR_MATCHES_TOTAL: CounterVec = {
+		let counter = CounterVec::new(
+			Opts::new("monitor_matches_total", "Total number of monitor matches"),
+			&["monitor", "network"]
+		).unwrap();
+		REGISTRY.register(Box::new(counter.clone())).unwrap();
+		counter
+	};
+
+	// Gauge of the outcome of the last canary check of a monitor (1 passed, 0 failed)
+	pub static ref CANARY_UP: GaugeVec = {
+		let gauge = GaugeVec::new(
+			Opts::new("canary_up", "Whether the last canary check of a monitor passed"),
+			&["monitor"]
+		).unwrap();
+		REGISTRY.register(Box::new(gauge.clone())).unwrap();
+		gauge
+	};
+
+	// Counter of the canary checks by outcome
+	pub static ref CANARY_CHECKS_TOTAL: CounterVec = {
+		let counter = CounterVec::new(
+			Opts::new("canary_checks_total", "Total number of canary checks"),
+			&["monitor", "result"]
+		).unwrap();
+		REGISTRY.register(Box::new(counter.clone())).unwrap();
+		counter
+	};
+
+	// Gauge of the time of the last passed canary check of a monitor
+	pub static ref CANARY_LAST_SUCCESS_TIMESTAMP: GaugeVec = {
+		let gauge = GaugeVec::new(
+			Opts::new("canary_last_success_timestamp_seconds", "Unix timestamp of the last passed canary check"),
+			&["monitor"]
+		).unwrap();
+		REGISTRY.register(Box::new(gauge.clone())).unwrap();
+		gauge
+	};
+
+	// Gauge of the signatures of a contract differing from its configured spec
+	pub static ref ABI_DRIFT_SIGNATURES: GaugeVec = {
+		let gauge = GaugeVec::new(
+			Opts::new("abi_drift_signatures", "Functions and events of a deployed contract differing from its configured spec"),
+			&["monitor", "network", "address"]
+		).unwrap();
+		REGISTRY.register(Box::new(gauge.clone())).unwrap();
+		gauge
+	};
+
+	// Counter of the matches of monitors in observe mode, not notified
+	pub static ref MONITOR_OBSERVED_MATCHES_TOTAL: CounterVec = {
+		let counter = CounterVec::new(
+			Opts::new("monitor_observed_matches_total", "Total number of matches of monitors in observe mode"),
+			&["monitor", "network"]
+		).unwrap();
+		REGISTRY.register(Box::new(counter.clone())).unwrap();
+		counter
+	};
+
+	// Counter of the matches of sampled monitors left out of the notifications
+	pub static ref MONITOR_SAMPLED_OUT_MATCHES_TOTAL: CounterVec = {
+		let counter = CounterVec::new(
+			Opts::new("monitor_sampled_out_matches_total", "Total number of matches of sampled monitors not notified"),
+			&["monitor", "network"]
+		).unwrap();
+		REGISTRY.register(Box::new(counter.clone())).unwrap();
+		counter
+	};
+
+	// Counter of the matched transactions of tracing monitors, by trace status
+	pub static ref TRACED_TRANSACTIONS_TOTAL: CounterVec = {
+		let counter = CounterVec::new(
+			Opts::new("traced_transactions_total", "Total number of matched transactions considered for tracing"),
+			&["network", "status"]
+		).unwrap();
+		REGISTRY.register(Box::new(counter.clone())).unwrap();
+		counter
+	};
+
+	// Histogram of the latency of the notifications, by stage
+	pub static ref NOTIFICATION_LATENCY_SECONDS: HistogramVec = {
+		let histogram = HistogramVec::new(
+			HistogramOpts::new("notification_latency_seconds", "Latency of the notifications from the block timestamp to their dispatch, by stage")
+				.buckets(vec![0.1, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0, 120.0, 300.0, 900.0]),
+			&["network", "stage"]
+		).unwrap();
+		REGISTRY.register(Box::new(histogram.clone())).unwrap();
+		histogram
+	};
+
+	// Counter of the delivery reports of triggers, by result
+	pub static ref TRIGGER_DELIVERY_REPORTS_TOTAL: CounterVec = {
+		let counter = CounterVec::new(
+			Opts::new("trigger_delivery_reports_total", "Total number of delivery reports of triggers"),
+			&["trigger", "result"]
+		).unwrap();
+		REGISTRY.register(Box::new(counter.clone())).unwrap();
+		counter
+	};
+
+	/// Counter Vector for watcher restarts.
+	///
+	/// Tracks the number of block processing runs cancelled by the watchdog because they
+	/// exceeded the stall timeout, labeled by network slug.
+	pub static ref WATCHER_RESTARTS_TOTAL: CounterVec = {
+		let counter = CounterVec::new(
+			Opts::new("watcher_restarts_total", "Total number of stalled block watcher runs restarted by the watchdog"),
+			&["network"]
+		).unwrap();
+		REGISTRY.register(Box::new(counter.clone())).unwrap();
+		counter
+	};
+
+	/// Counter Vector for panics.
+	///
+	/// Tracks the panics of the service, labeled by the slug of the network whose watcher
+	/// panicked, and by an empty network for panics outside of the network watchers.
+	pub static ref PANICS_TOTAL: CounterVec = {
+		let counter = CounterVec::new(
+			Opts::new("panics_total", "Total number of panics"),
+			&["network"]
+		).unwrap();
+		REGISTRY.register(Box::new(counter.clone())).unwrap();
+		counter
+	};
+
+	/// Gauge Vector for the tasks of the networks.
+	///
+	/// Tracks the running tasks of each network by kind, so that a network monopolizing the
+	/// runtime can be identified.
+	pub static ref NETWORK_TASKS: GaugeVec = {
+		let gauge = GaugeVec::new(
+			Opts::new("network_tasks", "Number of running tasks of a network"),
+			&["network", "kind"]
+		).unwrap();
+		REGISTRY.register(Box::new(gauge.clone())).unwrap();
+		gauge
+	};
+
+	/// Gauge Vector for the blocks queued in the processing pipelines.
+	///
+	/// Tracks the blocks of the current processing run of each network that are not yet
+	/// handed over to the triggers.
+	pub static ref NETWORK_QUEUED_BLOCKS: GaugeVec = {
+		let gauge = GaugeVec::new(
+			Opts::new("network_queued_blocks", "Number of blocks of a network waiting in its processing pipeline"),
+			&["network"]
+		).unwrap();
+		REGISTRY.register(Box::new(gauge.clone())).unwrap();
+		gauge
+	};
+
+	/// Gauge Vector for the suspended network watchers.
+	///
+	/// Set to 1 while the runs of a network are suspended by its restart policy.
+	pub static ref NETWORK_WATCHER_SUSPENDED: GaugeVec = {
+		let gauge = GaugeVec::new(
+			Opts::new("network_watcher_suspended", "Whether the block processing runs of a network are suspended after repeated failures"),
+			&["network"]
+		).unwrap();
+		REGISTRY.register(Box::new(gauge.clone())).unwrap();
+		gauge
+	};
+
+	/// Counter Vector for skipped blocks.
+	///
+	/// Tracks the number of blocks added to the skip list after repeated failures,
+	/// labeled by network slug.
+	pub static ref BLOCKS_SKIPPED_TOTAL: CounterVec = {
+		let counter = CounterVec::new(
+			Opts::new("blocks_skipped_total", "Total number of blocks skipped after repeated processing failures"),
+			&["network"]
+		).unwrap();
+		REGISTRY.register(Box::new(counter.clone())).unwrap();
+		counter
+	};
+
+	/// Counter Vector for oversized blocks.
+	///
+	/// Tracks the number of blocks exceeding the transaction count of the network's block
+	/// guardrails, labeled by network slug.
+	pub static ref OVERSIZED_BLOCKS_TOTAL: CounterVec = {
+		let counter = CounterVec::new(
+			Opts::new("oversized_blocks_total", "Total number of oversized blocks processed in chunks"),
+			&["network"]
+		).unwrap();
+		REGISTRY.register(Box::new(counter.clone())).unwrap();
+		counter
+	};
+
 	// ============================================================
 	// RPC Operational Metrics
 	// ============================================================
@@ -167,6 +385,19 @@ lazy_static! {
 		counter
 	};
 
+	/// Counter for the estimated cost of RPC requests.
+	///
+	/// Tracks the estimated cost of the RPC requests made to the networks configuring an
+	/// `rpc_cost`, labeled by network and method.
+	pub static ref RPC_ESTIMATED_COST_TOTAL: CounterVec = {
+		let counter = CounterVec::new(
+			Opts::new("rpc_estimated_cost_total", "Estimated cost of RPC requests"),
+			&["network", "method"]
+		).unwrap();
+		REGISTRY.register(Box::new(counter.clone())).unwrap();
+		counter
+	};
+
 	/// Counter for RPC request errors.
 	///
 	/// Tracks the total number of failed RPC requests, labeled by network, HTTP status code, and error type.
@@ -216,6 +447,32 @@ lazy_static! {
 		counter
 	};
 
+	/// Counter for the time spent waiting for the rate limit of a provider.
+	///
+	/// Tracks, per network, how long requests waited for their turn at the provider shared
+	/// with other networks (see `rpc_urls[].provider`).
+	pub static ref RPC_PROVIDER_WAIT_SECONDS_TOTAL: CounterVec = {
+		let counter = CounterVec::new(
+			Opts::new("rpc_provider_wait_seconds_total", "Total time requests waited for the rate limit of their RPC provider"),
+			&["provider", "network"]
+		).unwrap();
+		REGISTRY.register(Box::new(counter.clone())).unwrap();
+		counter
+	};
+
+	/// Counter for the backoffs of a provider.
+	///
+	/// Tracks the rate limit responses that paused the requests of all the networks sharing
+	/// the provider.
+	pub static ref RPC_PROVIDER_BACKOFFS_TOTAL: CounterVec = {
+		let counter = CounterVec::new(
+			Opts::new("rpc_provider_backoffs_total", "Total number of rate limit backoffs of an RPC provider"),
+			&["provider"]
+		).unwrap();
+		REGISTRY.register(Box::new(counter.clone())).unwrap();
+		counter
+	};
+
 	/// Counter for RPC responses where the JSON-RPC `result` field is null.
 	///
 	/// `result: null` is a legitimate answer for some methods (e.g. `eth_getBlockByNumber`
@@ -246,6 +503,134 @@ lazy_static! {
 		REGISTRY.register(Box::new(counter.clone())).unwrap();
 		counter
 	};
+
+	/// Gauge Vector for block hash divergence between RPC endpoints.
+	///
+	/// Tracks the number of endpoints whose block hash differs from the majority at the
+	/// last consistency check, labeled by network. `0` means all endpoints agree.
+	pub static ref RPC_BLOCK_HASH_DIVERGENCE: GaugeVec = {
+		let gauge = GaugeVec::new(
+			Opts::new("rpc_block_hash_divergence", "Number of RPC endpoints whose block hash differs from the majority"),
+			&["network"]
+		).unwrap();
+		REGISTRY.register(Box::new(gauge.clone())).unwrap();
+		gauge
+	};
+
+	/// Gauge Vector for RPC endpoint lag.
+	///
+	/// Tracks the number of blocks each endpoint lags behind the most advanced endpoint
+	/// of the same network at the last consistency check, labeled by network and endpoint.
+	pub static ref RPC_ENDPOINT_BLOCK_LAG: GaugeVec = {
+		let gauge = GaugeVec::new(
+			Opts::new("rpc_endpoint_block_lag", "Number of blocks an RPC endpoint lags behind the most advanced endpoint"),
+			&["network", "endpoint"]
+		).unwrap();
+		REGISTRY.register(Box::new(gauge.clone())).unwrap();
+		gauge
+	};
+
+	/// Gauge Vector for RPC endpoint capabilities.
+	///
+	/// Tracks whether each endpoint offers an optional capability (`debug_trace`, `trace`,
+	/// `batch`) as detected at startup, labeled by network, endpoint and capability.
+	pub static ref RPC_ENDPOINT_CAPABILITY: GaugeVec = {
+		let gauge = GaugeVec::new(
+			Opts::new("rpc_endpoint_capability", "Whether an RPC endpoint offers an optional capability"),
+			&["network", "endpoint", "capability"]
+		).unwrap();
+		REGISTRY.register(Box::new(gauge.clone())).unwrap();
+		gauge
+	};
+
+	// ============================================================
+	// Window Store Metrics
+	// ============================================================
+
+	/// Counter for window store operations.
+	///
+	/// Tracks the operations on the windowed state store, labeled by backend and operation.
+	pub static ref WINDOW_STORE_OPERATIONS_TOTAL: CounterVec = {
+		let counter = CounterVec::new(
+			Opts::new("window_store_operations_total", "Total number of window store operations"),
+			&["backend", "operation"]
+		).unwrap();
+		REGISTRY.register(Box::new(counter.clone())).unwrap();
+		counter
+	};
+
+	/// Counter for failed window store operations.
+	///
+	/// Tracks the operations on the windowed state store that failed, labeled by backend and operation.
+	pub static ref WINDOW_STORE_ERRORS_TOTAL: CounterVec = {
+		let counter = CounterVec::new(
+			Opts::new("window_store_errors_total", "Total number of failed window store operations"),
+			&["backend", "operation"]
+		).unwrap();
+		REGISTRY.register(Box::new(counter.clone())).unwrap();
+		counter
+	};
+
+	/// Gauge Vector for window store keys.
+	///
+	/// Tracks the number of keys held by the windowed state store, labeled by backend.
+	/// Only reported by the in-memory backend.
+	pub static ref WINDOW_STORE_KEYS: GaugeVec = {
+		let gauge = GaugeVec::new(
+			Opts::new("window_store_keys", "Number of keys held by the window store"),
+			&["backend"]
+		).unwrap();
+		REGISTRY.register(Box::new(gauge.clone())).unwrap();
+		gauge
+	};
+
+	/// Counter Vector for contract call cache lookups.
+	///
+	/// Tracks the lookups of the cache of contract reads, labeled by network and result
+	/// (`hit` or `miss`).
+	pub static ref CONTRACT_CALL_CACHE_REQUESTS_TOTAL: CounterVec = {
+		let counter = CounterVec::new(
+			Opts::new("contract_call_cache_requests_total", "Total number of lookups of the contract call cache"),
+			&["network", "result"]
+		).unwrap();
+		REGISTRY.register(Box::new(counter.clone())).unwrap();
+		counter
+	};
+
+	/// Gauge for contract call cache entries.
+	///
+	/// Tracks the number of results held by the cache of contract reads.
+	pub static ref CONTRACT_CALL_CACHE_ENTRIES: Gauge = {
+		let gauge = Gauge::new("contract_call_cache_entries", "Number of results held by the contract call cache").unwrap();
+		REGISTRY.register(Box::new(gauge.clone())).unwrap();
+		gauge
+	};
+
+	/// Counter for retries.
+	///
+	/// Tracks the retries of failed operations (RPC and webhook requests, emails, storage
+	/// commands), by component.
+	pub static ref RETRIES_TOTAL: CounterVec = {
+		let counter = CounterVec::new(
+			Opts::new("retries_total", "Total number of retries of failed operations"),
+			&["component"]
+		).unwrap();
+		REGISTRY.register(Box::new(counter.clone())).unwrap();
+		counter
+	};
+
+	/// Counter for operations given up.
+	///
+	/// Tracks the failed operations that were not retried any further, because they reached
+	/// their maximum number of retries or the retry budget of their component was exhausted.
+	pub static ref RETRIES_EXHAUSTED_TOTAL: CounterVec = {
+		let counter = CounterVec::new(
+			Opts::new("retries_exhausted_total", "Total number of failed operations given up after retrying"),
+			&["component", "reason"]
+		).unwrap();
+		REGISTRY.register(Box::new(counter.clone())).unwrap();
+		counter
+	};
 }
 
 /// Gather all metrics and encode into the provided format.
@@ -327,13 +712,14 @@ pub fn update_monitoring_metrics(
 	// Count unique contracts across all monitors
 	let mut unique_contracts = std::collections::HashSet::new();
 	for monitor in monitors.values() {
-		for address in &monitor.addresses {
+		for network in &monitor.networks {
+			// Verify the network exists in our network repository
+			if !networks.contains_key(network) {
+				continue;
+			}
 			// Create a unique identifier for each contract (network + address)
-			for network in &monitor.networks {
-				// Verify the network exists in our network repository
-				if networks.contains_key(network) {
-					unique_contracts.insert(format!("{}:{}", network, address.address));
-				}
+			for address in monitor.addresses_for_network(network) {
+				unique_contracts.insert(format!("{}:{}", network, address.address));
 			}
 		}
 	}
@@ -371,6 +757,99 @@ pub fn update_monitoring_metrics(
 	}
 }
 
+// ============================================================
+// Match Metrics Helper Functions
+// ============================================================
+
+/// Records a match of a monitor in observe mode.
+///
+/// # Arguments
+/// * `monitor` - The name of the monitor
+/// * `network` - The network slug
+pub fn record_observed_match(monitor: &str, network: &str) {
+	MONITOR_OBSERVED_MATCHES_TOTAL
+		.with_label_values(&[monitor, network])
+		.inc();
+}
+
+/// Records a match of a sampled monitor left out of the notifications.
+///
+/// # Arguments
+/// * `monitor` - The name of the monitor
+/// * `network` - The network slug
+pub fn record_sampled_out_match(monitor: &str, network: &str) {
+	MONITOR_SAMPLED_OUT_MATCHES_TOTAL
+		.with_label_values(&[monitor, network])
+		.inc();
+}
+
+/// Records the trace status of a matched transaction.
+///
+/// # Arguments
+/// * `network` - The network slug
+/// * `status` - The trace status (`traced`, `sampled_out`, `over_budget`, etc.)
+pub fn record_traced_transaction(network: &str, status: &str) {
+	TRACED_TRANSACTIONS_TOTAL
+		.with_label_values(&[network, status])
+		.inc();
+}
+
+/// Records the latency of a stage of a notification.
+///
+/// # Arguments
+/// * `network` - The network slug
+/// * `stage` - The stage (`block_to_receipt`, `receipt_to_detection`, etc.)
+/// * `latency_secs` - The latency of the stage in seconds
+pub fn record_notification_latency(network: &str, stage: &str, latency_secs: f64) {
+	NOTIFICATION_LATENCY_SECONDS
+		.with_label_values(&[network, stage])
+		.observe(latency_secs);
+}
+
+/// Records a delivery report of a trigger.
+///
+/// # Arguments
+/// * `trigger` - The trigger slug
+/// * `sent` - Whether the report was accepted by its URL
+pub fn record_delivery_report(trigger: &str, sent: bool) {
+	let result = if sent { "sent" } else { "failed" };
+	TRIGGER_DELIVERY_REPORTS_TOTAL
+		.with_label_values(&[trigger, result])
+		.inc();
+}
+
+/// Records the outcome of a canary check.
+///
+/// # Arguments
+/// * `monitor` - The name of the monitor
+/// * `passed` - Whether the check passed
+pub fn record_canary_check(monitor: &str, passed: bool) {
+	CANARY_UP
+		.with_label_values(&[monitor])
+		.set(if passed { 1.0 } else { 0.0 });
+	CANARY_CHECKS_TOTAL
+		.with_label_values(&[monitor, if passed { "passed" } else { "failed" }])
+		.inc();
+	if passed {
+		CANARY_LAST_SUCCESS_TIMESTAMP
+			.with_label_values(&[monitor])
+			.set(chrono::Utc::now().timestamp() as f64);
+	}
+}
+
+/// Records the outcome of an ABI drift check of a contract.
+///
+/// # Arguments
+/// * `monitor` - The name of the monitor
+/// * `network` - The network slug
+/// * `address` - The contract address
+/// * `signatures` - The number of functions and events differing from the configured spec
+pub fn record_abi_drift(monitor: &str, network: &str, address: &str, signatures: usize) {
+	ABI_DRIFT_SIGNATURES
+		.with_label_values(&[monitor, network, address])
+		.set(signatures as f64);
+}
+
 // ============================================================
 // RPC Metrics Helper Functions
 // ============================================================
@@ -384,6 +863,7 @@ pub fn record_rpc_request(network: &str, method: &str) {
 	RPC_REQUESTS_TOTAL
 		.with_label_values(&[network, method])
 		.inc();
+	usage::RPC_USAGE.record(network, method);
 }
 
 /// Records an RPC request error.
@@ -431,6 +911,28 @@ pub fn record_rate_limit(network: &str, endpoint: &str) {
 		.inc();
 }
 
+/// Records the time a request waited for the rate limit of its provider.
+///
+/// # Arguments
+/// * `provider` - The provider name
+/// * `network` - The network slug
+/// * `seconds` - Time waited
+pub fn record_provider_wait(provider: &str, network: &str, seconds: f64) {
+	RPC_PROVIDER_WAIT_SECONDS_TOTAL
+		.with_label_values(&[provider, network])
+		.inc_by(seconds);
+}
+
+/// Records a rate limit backoff of a provider.
+///
+/// # Arguments
+/// * `provider` - The provider name
+pub fn record_provider_backoff(provider: &str) {
+	RPC_PROVIDER_BACKOFFS_TOTAL
+		.with_label_values(&[provider])
+		.inc();
+}
+
 /// Records a JSON-RPC response where the `result` field was null.
 ///
 /// # Arguments
@@ -457,6 +959,125 @@ pub fn record_jsonrpc_passthrough(network: &str, code: &str) {
 		.inc();
 }
 
+/// Sets the number of RPC endpoints whose block hash diverges from the majority.
+///
+/// # Arguments
+/// * `network` - The network slug
+/// * `divergent_endpoints` - Number of endpoints that disagree with the majority
+pub fn set_block_hash_divergence(network: &str, divergent_endpoints: usize) {
+	RPC_BLOCK_HASH_DIVERGENCE
+		.with_label_values(&[network])
+		.set(divergent_endpoints as f64);
+}
+
+/// Sets the number of blocks an RPC endpoint lags behind the most advanced endpoint.
+///
+/// # Arguments
+/// * `network` - The network slug
+/// * `endpoint` - The endpoint host
+/// * `lag` - Number of blocks behind
+pub fn set_endpoint_block_lag(network: &str, endpoint: &str, lag: u64) {
+	RPC_ENDPOINT_BLOCK_LAG
+		.with_label_values(&[network, endpoint])
+		.set(lag as f64);
+}
+
+/// Sets whether an RPC endpoint offers an optional capability.
+///
+/// # Arguments
+/// * `network` - The network slug
+/// * `endpoint` - The endpoint host
+/// * `capability` - The capability name
+/// * `supported` - Whether the capability is offered
+pub fn set_endpoint_capability(network: &str, endpoint: &str, capability: &str, supported: bool) {
+	RPC_ENDPOINT_CAPABILITY
+		.with_label_values(&[network, endpoint, capability])
+		.set(if supported { 1.0 } else { 0.0 });
+}
+
+// ============================================================
+// Window Store Metrics Helper Functions
+// ============================================================
+
+/// Records a window store operation.
+///
+/// # Arguments
+/// * `backend` - The backend of the store (e.g., "memory", "redis")
+/// * `operation` - The operation (e.g., "push", "range")
+pub fn record_window_store_operation(backend: &str, operation: &str) {
+	WINDOW_STORE_OPERATIONS_TOTAL
+		.with_label_values(&[backend, operation])
+		.inc();
+}
+
+/// Records a failed window store operation.
+///
+/// # Arguments
+/// * `backend` - The backend of the store
+/// * `operation` - The operation that failed
+pub fn record_window_store_error(backend: &str, operation: &str) {
+	WINDOW_STORE_ERRORS_TOTAL
+		.with_label_values(&[backend, operation])
+		.inc();
+}
+
+/// Sets the number of keys held by the window store.
+///
+/// # Arguments
+/// * `backend` - The backend of the store
+/// * `keys` - Number of keys
+pub fn set_window_store_keys(backend: &str, keys: usize) {
+	WINDOW_STORE_KEYS
+		.with_label_values(&[backend])
+		.set(keys as f64);
+}
+
+// ============================================================
+// Contract Call Cache Metrics Helper Functions
+// ============================================================
+
+/// Records a lookup of the contract call cache.
+///
+/// # Arguments
+/// * `network` - The network slug
+/// * `hit` - Whether the result was cached
+pub fn record_contract_call_cache_lookup(network: &str, hit: bool) {
+	CONTRACT_CALL_CACHE_REQUESTS_TOTAL
+		.with_label_values(&[network, if hit { "hit" } else { "miss" }])
+		.inc();
+}
+
+/// Sets the number of results held by the contract call cache.
+///
+/// # Arguments
+/// * `entries` - Number of results
+pub fn set_contract_call_cache_entries(entries: usize) {
+	CONTRACT_CALL_CACHE_ENTRIES.set(entries as f64);
+}
+
+// ============================================================
+// Retry Metrics Helper Functions
+// ============================================================
+
+/// Records a retry of a failed operation.
+///
+/// # Arguments
+/// * `component` - Component of the operation (e.g. `rpc`, `webhook`, `email`)
+pub fn record_retry(component: &str) {
+	RETRIES_TOTAL.with_label_values(&[component]).inc();
+}
+
+/// Records a failed operation given up.
+///
+/// # Arguments
+/// * `component` - Component of the operation
+/// * `reason` - Why it was not retried (`max_retries`, `budget`)
+pub fn record_retry_exhausted(component: &str, reason: &str) {
+	RETRIES_EXHAUSTED_TOTAL
+		.with_label_values(&[component, reason])
+		.inc();
+}
+
 /// Initializes RPC metrics for a network so they appear in Prometheus output with 0 values.
 ///
 /// This should be called when a transport client is created for a network.
@@ -548,6 +1169,19 @@ mod tests {
 		RPC_RATE_LIMITS_TOTAL.reset();
 		RPC_NULL_RESULTS_TOTAL.reset();
 		RPC_JSONRPC_PASSTHROUGH_TOTAL.reset();
+		RPC_BLOCK_HASH_DIVERGENCE.reset();
+		WATCHER_RESTARTS_TOTAL.reset();
+		CONFIG_VERSION_INFO.reset();
+		PANICS_TOTAL.reset();
+		NETWORK_TASKS.reset();
+		NETWORK_QUEUED_BLOCKS.reset();
+		NETWORK_WATCHER_SUSPENDED.reset();
+		BLOCKS_SKIPPED_TOTAL.reset();
+		OVERSIZED_BLOCKS_TOTAL.reset();
+		RPC_ENDPOINT_BLOCK_LAG.reset();
+		TRIGGER_DELIVERY_REPORTS_TOTAL.reset();
+		TRACED_TRANSACTIONS_TOTAL.reset();
+		NOTIFICATION_LATENCY_SECONDS.reset();
 	}
 
 	// Helper function to create a test network
@@ -1094,6 +1728,20 @@ mod tests {
 			.get_metric_with_label_values(&["ethereum", "https://rpc2.example.com"])
 			.unwrap();
 		assert_eq!(rpc2_rate_limits.get(), 1.0);
+
+		// Test consistency check gauges
+		set_block_hash_divergence("ethereum", 1);
+		set_endpoint_block_lag("ethereum", "rpc1.example.com", 3);
+
+		let divergence = RPC_BLOCK_HASH_DIVERGENCE
+			.get_metric_with_label_values(&["ethereum"])
+			.unwrap();
+		assert_eq!(divergence.get(), 1.0);
+
+		let lag = RPC_ENDPOINT_BLOCK_LAG
+			.get_metric_with_label_values(&["ethereum", "rpc1.example.com"])
+			.unwrap();
+		assert_eq!(lag.get(), 3.0);
 	}
 
 	#[test]
@@ -1,10 +1,15 @@
 //! Metrics server module
 //!
 //! This module provides an HTTP server to expose Prometheus metrics for scraping.
+//! Requests are authenticated with the API tokens when any is configured (see [`auth`]).
+//!
+//! [`auth`]: crate::utils::metrics::auth
 
-use actix_web::middleware::{Compress, DefaultHeaders, NormalizePath};
+use actix_web::middleware::{from_fn, Compress, DefaultHeaders, NormalizePath};
 use actix_web::{web, App, HttpResponse, HttpServer, Responder};
-use std::sync::Arc;
+use chrono::{Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, sync::Arc};
 use tokio::sync::Mutex;
 use tracing::{error, info};
 
@@ -13,7 +18,12 @@ use crate::{
 		MonitorRepository, MonitorService, NetworkRepository, NetworkService, TriggerRepository,
 		TriggerService,
 	},
-	utils::metrics::{gather_metrics, update_monitoring_metrics, update_system_metrics},
+	utils::metrics::{
+		auth::{authorize, ApiTokens},
+		gather_metrics,
+		stats::{DailyMatchCount, MATCH_STATS},
+		update_monitoring_metrics, update_system_metrics,
+	},
 };
 
 // Type aliases to simplify complex types in function signatures
@@ -83,40 +93,70 @@ async fn metrics_handler(
 	}
 }
 
+/// Query parameters of the stats endpoint
+#[derive(Debug, Default, Deserialize)]
+pub struct StatsQuery {
+	/// Number of days to include, counting today (all retained days if omitted)
+	pub days: Option<u32>,
+	/// Only include this monitor
+	pub monitor: Option<String>,
+	/// Only include this network
+	pub network: Option<String>,
+}
+
+/// Response of the stats endpoint
+#[derive(Debug, Serialize)]
+pub struct StatsResponse {
+	/// Daily match counts per monitor and network, most recent day first
+	pub daily: Vec<DailyMatchCount>,
+	/// Total number of matches per monitor over the returned days
+	pub totals: BTreeMap<String, u64>,
+}
+
+/// Stats endpoint handler
+///
+/// Returns the daily match aggregates, optionally filtered by period, monitor and network.
+async fn stats_handler(query: web::Query<StatsQuery>) -> impl Responder {
+	let since = query
+		.days
+		.filter(|days| *days > 0)
+		.map(|days| Utc::now().date_naive() - Duration::days(i64::from(days) - 1));
+	let daily = MATCH_STATS.query(since, query.monitor.as_deref(), query.network.as_deref());
+
+	let mut totals = BTreeMap::new();
+	for count in &daily {
+		*totals.entry(count.monitor.clone()).or_insert(0) += count.matches;
+	}
+
+	HttpResponse::Ok().json(StatsResponse { daily, totals })
+}
+
 // Create metrics server
 pub fn create_metrics_server(
 	bind_address: String,
 	monitor_service: MonitorServiceArc,
 	network_service: NetworkServiceArc,
 	trigger_service: TriggerServiceArc,
+	api_tokens: ApiTokens,
 ) -> std::io::Result<actix_web::dev::Server> {
-	let actual_bind_address = if std::env::var("IN_DOCKER").unwrap_or_default() == "true" {
-		if let Some(port) = bind_address.split(':').nth(1) {
-			format!("0.0.0.0:{}", port)
-		} else {
-			"0.0.0.0:8081".to_string()
-		}
-	} else {
-		bind_address.clone()
-	};
-
-	info!(
-		"Starting metrics server on {} (actual bind: {})",
-		bind_address, actual_bind_address
-	);
+	info!("Starting metrics server on {}", bind_address);
 
 	Ok(HttpServer::new(move || {
 		App::new()
+			// Registered first so it runs after the path normalization
+			.wrap(from_fn(authorize))
 			.wrap(Compress::default())
 			.wrap(NormalizePath::trim())
 			.wrap(DefaultHeaders::new())
+			.app_data(web::Data::new(api_tokens.clone()))
 			.app_data(web::Data::new(monitor_service.clone()))
 			.app_data(web::Data::new(network_service.clone()))
 			.app_data(web::Data::new(trigger_service.clone()))
 			.route("/metrics", web::get().to(metrics_handler))
+			.route("/stats", web::get().to(stats_handler))
 	})
 	.workers(2)
-	.bind(actual_bind_address)?
+	.bind(bind_address)?
 	.shutdown_timeout(5)
 	.run())
 }
@@ -297,6 +337,36 @@ mod tests {
 		assert!(body_str.contains("# HELP"));
 	}
 
+	#[actix_web::test]
+	async fn test_stats_handler() {
+		let today = Utc::now().date_naive();
+		MATCH_STATS.record("stats_handler_monitor", "ethereum_mainnet", today);
+		MATCH_STATS.record("stats_handler_monitor", "ethereum_mainnet", today);
+		MATCH_STATS.record(
+			"stats_handler_monitor",
+			"ethereum_mainnet",
+			today - Duration::days(10),
+		);
+
+		let app =
+			test::init_service(App::new().route("/stats", web::get().to(stats_handler))).await;
+
+		let req = test::TestRequest::get()
+			.uri("/stats?days=7&monitor=stats_handler_monitor")
+			.to_request();
+		let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+
+		assert_eq!(body["daily"].as_array().unwrap().len(), 1);
+		assert_eq!(body["daily"][0]["matches"], 2);
+		assert_eq!(body["totals"]["stats_handler_monitor"], 2);
+
+		let req = test::TestRequest::get()
+			.uri("/stats?monitor=stats_handler_monitor")
+			.to_request();
+		let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+		assert_eq!(body["totals"]["stats_handler_monitor"], 3);
+	}
+
 	#[tokio::test]
 	async fn test_create_metrics_server() {
 		// Create test services
@@ -316,6 +386,7 @@ mod tests {
 			monitor_service,
 			network_service,
 			trigger_service,
+			ApiTokens::default(),
 		);
 
 		// Assert server creation is successful
@@ -351,50 +422,4 @@ mod tests {
 		// Gracefully shut down the server
 		server_task.abort();
 	}
-
-	#[tokio::test]
-	async fn test_docker_bind_address_handling() {
-		// Save original environment state
-		let original_docker_env = std::env::var("IN_DOCKER").ok();
-
-		// Set IN_DOCKER environment variable
-		std::env::set_var("IN_DOCKER", "true");
-
-		// Mock the HttpServer::bind function to avoid actual network binding
-		// We'll just test the address transformation logic
-		let bind_address = "localhost:8081".to_string();
-		let actual_bind_address = if std::env::var("IN_DOCKER").unwrap_or_default() == "true" {
-			if let Some(port) = bind_address.split(':').nth(1) {
-				format!("0.0.0.0:{}", port)
-			} else {
-				"0.0.0.0:8081".to_string()
-			}
-		} else {
-			bind_address.clone()
-		};
-
-		// Verify the address transformation logic
-		assert_eq!(actual_bind_address, "0.0.0.0:8081");
-
-		// Test with no port specified
-		let bind_address = "localhost".to_string();
-		let actual_bind_address = if std::env::var("IN_DOCKER").unwrap_or_default() == "true" {
-			if let Some(port) = bind_address.split(':').nth(1) {
-				format!("0.0.0.0:{}", port)
-			} else {
-				"0.0.0.0:8081".to_string()
-			}
-		} else {
-			bind_address.clone()
-		};
-
-		// Verify the address transformation logic
-		assert_eq!(actual_bind_address, "0.0.0.0:8081");
-
-		// Restore original environment
-		match original_docker_env {
-			Some(val) => std::env::set_var("IN_DOCKER", val),
-			None => std::env::remove_var("IN_DOCKER"),
-		}
-	}
 }
@@ -0,0 +1,365 @@
+//! Historical match statistics.
+//!
+//! Keeps daily aggregates of the number of matches per monitor and network, persisted as
+//! a JSON file so that alert volume trends survive restarts and do not depend on external
+//! log retention. The aggregates are served by the `/stats` endpoint of the metrics server
+//! and every match also increments the `monitor_matches_total` Prometheus counter.
+//!
+//! The file is rewritten periodically and on shutdown, and only if matches were recorded
+//! since the previous write.
+
+use chrono::{Duration, NaiveDate, Utc};
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use std::{
+	collections::BTreeMap,
+	path::PathBuf,
+	sync::{
+		atomic::{AtomicBool, Ordering},
+		Mutex, RwLock, TryLockError,
+	},
+	time::Duration as StdDuration,
+};
+
+use crate::utils::metrics::MONITOR_MATCHES_TOTAL;
+
+/// Number of days of aggregates kept on disk
+pub const RETENTION_DAYS: i64 = 90;
+
+/// Daily counts keyed by date (`YYYY-MM-DD`), monitor name and network slug
+type DailyCounts = BTreeMap<String, BTreeMap<String, BTreeMap<String, u64>>>;
+
+lazy_static! {
+	/// Process-wide store of the match statistics.
+	pub static ref MATCH_STATS: MatchStats = MatchStats::default();
+}
+
+/// Number of matches of a monitor on a network during one day
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DailyMatchCount {
+	/// Day of the matches (UTC, `YYYY-MM-DD`)
+	pub date: String,
+	/// Name of the monitor
+	pub monitor: String,
+	/// Slug of the network
+	pub network: String,
+	/// Number of matches
+	pub matches: u64,
+}
+
+/// Daily match aggregates with optional file persistence
+#[derive(Default)]
+pub struct MatchStats {
+	storage_path: RwLock<Option<PathBuf>>,
+	counts: Mutex<DailyCounts>,
+	/// Whether matches were recorded since the last write
+	dirty: AtomicBool,
+	/// Serializes writes so that an older snapshot never overwrites a newer one
+	persist_lock: tokio::sync::Mutex<()>,
+}
+
+impl MatchStats {
+	/// Sets the file where the aggregates are persisted and loads its content
+	///
+	/// # Arguments
+	/// * `path` - Path of the JSON file holding the aggregates
+	pub fn configure_storage(&self, path: PathBuf) {
+		let loaded: Option<DailyCounts> = std::fs::read_to_string(&path)
+			.ok()
+			.and_then(|content| serde_json::from_str(&content).ok());
+		if let Some(loaded) = loaded {
+			let mut counts = self.lock_counts();
+			for (date, monitors) in loaded {
+				for (monitor, networks) in monitors {
+					for (network, matches) in networks {
+						*counts
+							.entry(date.clone())
+							.or_default()
+							.entry(monitor.clone())
+							.or_default()
+							.entry(network)
+							.or_default() += matches;
+					}
+				}
+			}
+		}
+		if let Ok(mut storage_path) = self.storage_path.write() {
+			*storage_path = Some(path);
+		}
+	}
+
+	/// Records a match of a monitor on a network for the given day
+	///
+	/// # Arguments
+	/// * `monitor` - Name of the monitor
+	/// * `network` - Slug of the network
+	/// * `date` - Day of the match
+	pub fn record(&self, monitor: &str, network: &str, date: NaiveDate) {
+		MONITOR_MATCHES_TOTAL
+			.with_label_values(&[monitor, network])
+			.inc();
+		*self
+			.lock_counts()
+			.entry(date.to_string())
+			.or_default()
+			.entry(monitor.to_string())
+			.or_default()
+			.entry(network.to_string())
+			.or_default() += 1;
+		self.dirty.store(true, Ordering::SeqCst);
+	}
+
+	/// Returns the daily aggregates, most recent day first
+	///
+	/// # Arguments
+	/// * `since` - First day to include, all days if `None`
+	/// * `monitor` - Only include this monitor, if set
+	/// * `network` - Only include this network, if set
+	pub fn query(
+		&self,
+		since: Option<NaiveDate>,
+		monitor: Option<&str>,
+		network: Option<&str>,
+	) -> Vec<DailyMatchCount> {
+		let since = since.map(|since| since.to_string());
+		let counts = self.lock_counts();
+
+		let mut result = Vec::new();
+		for (date, monitors) in counts.iter().rev() {
+			if since.as_ref().is_some_and(|since| date < since) {
+				continue;
+			}
+			for (name, networks) in monitors {
+				if monitor.is_some_and(|monitor| monitor != name) {
+					continue;
+				}
+				for (slug, matches) in networks {
+					if network.is_some_and(|network| network != slug) {
+						continue;
+					}
+					result.push(DailyMatchCount {
+						date: date.clone(),
+						monitor: name.clone(),
+						network: slug.clone(),
+						matches: *matches,
+					});
+				}
+			}
+		}
+		result
+	}
+
+	/// Writes the aggregates to the configured file if matches were recorded since the last
+	/// write, dropping days past the retention
+	pub async fn persist(&self) {
+		let Some(path) = self
+			.storage_path
+			.read()
+			.ok()
+			.and_then(|storage_path| storage_path.clone())
+		else {
+			return;
+		};
+
+		let _guard = self.persist_lock.lock().await;
+		if !self.dirty.swap(false, Ordering::SeqCst) {
+			return;
+		}
+		let cutoff = (Utc::now().date_naive() - Duration::days(RETENTION_DAYS)).to_string();
+		let snapshot = {
+			let mut counts = self.lock_counts();
+			counts.retain(|date, _| *date >= cutoff);
+			counts.clone()
+		};
+
+		let result = async {
+			if let Some(parent) = path.parent() {
+				tokio::fs::create_dir_all(parent).await?;
+			}
+			tokio::fs::write(&path, serde_json::to_string(&snapshot)?).await?;
+			Ok::<(), anyhow::Error>(())
+		}
+		.await;
+
+		if let Err(e) = result {
+			self.dirty.store(true, Ordering::SeqCst);
+			tracing::error!(
+				"Failed to persist match statistics to {}: {}",
+				path.display(),
+				e
+			);
+		}
+	}
+
+	/// Writes the aggregates to the configured file without awaiting, if matches were
+	/// recorded since the last write
+	///
+	/// Used by the panic hook: the write is skipped rather than waiting for a lock, which
+	/// the panicking thread may hold.
+	pub fn persist_blocking(&self) {
+		let Some(path) = self
+			.storage_path
+			.try_read()
+			.ok()
+			.and_then(|storage_path| storage_path.clone())
+		else {
+			return;
+		};
+
+		let Ok(_guard) = self.persist_lock.try_lock() else {
+			tracing::error!("Match statistics are being persisted, skipping the write");
+			return;
+		};
+		let snapshot = match self.counts.try_lock() {
+			Ok(counts) => counts.clone(),
+			Err(TryLockError::Poisoned(poisoned)) => poisoned.into_inner().clone(),
+			Err(TryLockError::WouldBlock) => {
+				tracing::error!("Match statistics are locked, skipping the write");
+				return;
+			}
+		};
+		if !self.dirty.swap(false, Ordering::SeqCst) {
+			return;
+		}
+
+		let result = (|| {
+			if let Some(parent) = path.parent() {
+				std::fs::create_dir_all(parent)?;
+			}
+			std::fs::write(&path, serde_json::to_string(&snapshot)?)?;
+			Ok::<(), anyhow::Error>(())
+		})();
+
+		if let Err(e) = result {
+			tracing::error!(
+				"Failed to persist match statistics to {}: {}",
+				path.display(),
+				e
+			);
+		}
+	}
+
+	fn lock_counts(&self) -> std::sync::MutexGuard<'_, DailyCounts> {
+		match self.counts.lock() {
+			Ok(counts) => counts,
+			Err(poisoned) => poisoned.into_inner(),
+		}
+	}
+}
+
+/// Periodically persists the process-wide match statistics
+///
+/// # Arguments
+/// * `interval` - Time between two writes
+pub fn start_match_stats_snapshots(interval: StdDuration) -> tokio::task::JoinHandle<()> {
+	tokio::spawn(async move {
+		let mut ticker = tokio::time::interval(interval);
+		ticker.tick().await;
+		loop {
+			ticker.tick().await;
+			MATCH_STATS.persist().await;
+		}
+	})
+}
+
+/// Records a match in the process-wide statistics for the current day
+///
+/// # Arguments
+/// * `monitor` - Name of the monitor
+/// * `network` - Slug of the network
+pub fn record_match(monitor: &str, network: &str) {
+	MATCH_STATS.record(monitor, network, Utc::now().date_naive());
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use tempfile::TempDir;
+
+	fn day(value: &str) -> NaiveDate {
+		NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+	}
+
+	#[test]
+	fn test_record_and_query() {
+		let stats = MatchStats::default();
+		stats.record("Large Transfer", "ethereum_mainnet", day("2026-01-01"));
+		stats.record("Large Transfer", "ethereum_mainnet", day("2026-01-01"));
+		stats.record("Large Transfer", "polygon_mainnet", day("2026-01-02"));
+		stats.record("Ownership", "ethereum_mainnet", day("2026-01-03"));
+
+		let all = stats.query(None, None, None);
+		assert_eq!(all.len(), 3);
+		// Most recent day first
+		assert_eq!(all[0].date, "2026-01-03");
+		assert_eq!(all[2].matches, 2);
+
+		let recent = stats.query(Some(day("2026-01-02")), Some("Large Transfer"), None);
+		assert_eq!(
+			recent,
+			vec![DailyMatchCount {
+				date: "2026-01-02".to_string(),
+				monitor: "Large Transfer".to_string(),
+				network: "polygon_mainnet".to_string(),
+				matches: 1,
+			}]
+		);
+
+		let ethereum = stats.query(None, None, Some("ethereum_mainnet"));
+		assert_eq!(ethereum.len(), 2);
+	}
+
+	#[tokio::test]
+	async fn test_persist_and_reload() {
+		let temp_dir = TempDir::new().unwrap();
+		let path = temp_dir.path().join("stats").join("match_stats.json");
+		let today = Utc::now().date_naive();
+
+		let stats = MatchStats::default();
+		stats.configure_storage(path.clone());
+		stats.record("Monitor", "ethereum_mainnet", today);
+		// Days past the retention are dropped when persisting
+		stats.record(
+			"Monitor",
+			"ethereum_mainnet",
+			today - Duration::days(RETENTION_DAYS + 1),
+		);
+		stats.persist().await;
+
+		let reloaded = MatchStats::default();
+		reloaded.configure_storage(path.clone());
+		let counts = reloaded.query(None, None, None);
+		assert_eq!(counts.len(), 1);
+		assert_eq!(counts[0].date, today.to_string());
+		assert_eq!(counts[0].matches, 1);
+
+		// The file is only rewritten after new matches
+		std::fs::remove_file(&path).unwrap();
+		stats.persist().await;
+		assert!(!path.exists());
+		stats.record("Monitor", "ethereum_mainnet", today);
+		stats.persist().await;
+		assert!(path.exists());
+	}
+
+	#[test]
+	fn test_persist_blocking() {
+		let temp_dir = TempDir::new().unwrap();
+		let path = temp_dir.path().join("match_stats.json");
+
+		let stats = MatchStats::default();
+		stats.configure_storage(path.clone());
+		stats.record("Monitor", "ethereum_mainnet", Utc::now().date_naive());
+		{
+			// The write is skipped while the counts are locked
+			let _counts = stats.lock_counts();
+			stats.persist_blocking();
+		}
+		assert!(!path.exists());
+
+		stats.persist_blocking();
+		let reloaded = MatchStats::default();
+		reloaded.configure_storage(path);
+		assert_eq!(reloaded.query(None, None, None).len(), 1);
+	}
+}
@@ -0,0 +1,218 @@
+//! RPC usage accounting.
+//!
+//! Every RPC call is counted per network and method by the `rpc_requests_total` metric. When
+//! a network configures the cost of its calls (`rpc_cost`), the estimated spend is also added
+//! to the `rpc_estimated_cost_total` metric, to budget the provider usage driven by the
+//! service. A summary of the calls made since the previous one is logged periodically.
+
+use lazy_static::lazy_static;
+use std::{
+	collections::{BTreeMap, HashMap},
+	sync::{Arc, Mutex, RwLock},
+	time::Duration,
+};
+
+use crate::{models::RpcCostConfig, utils::metrics::RPC_ESTIMATED_COST_TOTAL};
+
+/// Default time between two usage summaries
+pub const DEFAULT_SUMMARY_INTERVAL: Duration = Duration::from_secs(3600);
+
+lazy_static! {
+	/// Process-wide RPC usage accounting.
+	pub static ref RPC_USAGE: RpcUsage = RpcUsage::default();
+}
+
+/// Calls made to the endpoints of a network since the previous summary
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NetworkUsage {
+	/// Number of calls, keyed by method
+	pub calls: BTreeMap<String, u64>,
+	/// Estimated cost of the calls, if the network configures its costs
+	pub cost: Option<f64>,
+	/// Unit of the cost
+	pub unit: Option<String>,
+}
+
+impl NetworkUsage {
+	/// Returns the total number of calls
+	pub fn total_calls(&self) -> u64 {
+		self.calls.values().sum()
+	}
+}
+
+/// Counts of RPC calls and their estimated cost
+#[derive(Default)]
+pub struct RpcUsage {
+	costs: RwLock<HashMap<String, Arc<RpcCostConfig>>>,
+	/// Calls since the previous summary, keyed by network and method
+	calls: Mutex<BTreeMap<String, BTreeMap<String, u64>>>,
+}
+
+impl RpcUsage {
+	/// Sets the cost of the calls of a network
+	///
+	/// # Arguments
+	/// * `network` - The network slug
+	/// * `cost` - The cost of the calls
+	pub fn set_costs(&self, network: &str, cost: RpcCostConfig) {
+		let cost = Arc::new(cost);
+		match self.costs.write() {
+			Ok(mut costs) => costs.insert(network.to_string(), cost),
+			Err(poisoned) => poisoned.into_inner().insert(network.to_string(), cost),
+		};
+	}
+
+	/// Records a call, adding its cost to the `rpc_estimated_cost_total` metric
+	///
+	/// # Arguments
+	/// * `network` - The network slug
+	/// * `method` - The RPC method name
+	pub fn record(&self, network: &str, method: &str) {
+		*self
+			.lock_calls()
+			.entry(network.to_string())
+			.or_default()
+			.entry(method.to_string())
+			.or_default() += 1;
+		if let Some(cost) = self.cost_config(network) {
+			RPC_ESTIMATED_COST_TOTAL
+				.with_label_values(&[network, method])
+				.inc_by(cost.cost_of(method));
+		}
+	}
+
+	/// Returns the usage of each network since the previous call, and starts a new period
+	pub fn take_summary(&self) -> BTreeMap<String, NetworkUsage> {
+		let calls = std::mem::take(&mut *self.lock_calls());
+		calls
+			.into_iter()
+			.map(|(network, calls)| {
+				let cost_config = self.cost_config(&network);
+				let usage = NetworkUsage {
+					cost: cost_config.as_ref().map(|cost| {
+						calls
+							.iter()
+							.map(|(method, count)| cost.cost_of(method) * *count as f64)
+							.sum()
+					}),
+					unit: cost_config.and_then(|cost| cost.unit.clone()),
+					calls,
+				};
+				(network, usage)
+			})
+			.collect()
+	}
+
+	fn cost_config(&self, network: &str) -> Option<Arc<RpcCostConfig>> {
+		match self.costs.read() {
+			Ok(costs) => costs.get(network).cloned(),
+			Err(poisoned) => poisoned.into_inner().get(network).cloned(),
+		}
+	}
+
+	fn lock_calls(&self) -> std::sync::MutexGuard<'_, BTreeMap<String, BTreeMap<String, u64>>> {
+		match self.calls.lock() {
+			Ok(calls) => calls,
+			Err(poisoned) => poisoned.into_inner(),
+		}
+	}
+}
+
+/// Formats the usage of a network for the logs
+fn format_usage(network: &str, usage: &NetworkUsage) -> String {
+	let calls = usage
+		.calls
+		.iter()
+		.map(|(method, count)| format!("{}: {}", method, count))
+		.collect::<Vec<_>>()
+		.join(", ");
+	let mut line = format!(
+		"RPC usage of {}: {} calls ({})",
+		network,
+		usage.total_calls(),
+		calls
+	);
+	if let Some(cost) = usage.cost {
+		line.push_str(&format!(", estimated cost {:.2}", cost));
+		if let Some(unit) = &usage.unit {
+			line.push_str(&format!(" {}", unit));
+		}
+	}
+	line
+}
+
+/// Periodically logs the RPC usage of each network since the previous summary
+///
+/// # Arguments
+/// * `interval` - Time between two summaries
+pub fn start_usage_summary(interval: Duration) -> tokio::task::JoinHandle<()> {
+	tokio::spawn(async move {
+		let mut ticker = tokio::time::interval(interval);
+		ticker.tick().await;
+		loop {
+			ticker.tick().await;
+			for (network, usage) in RPC_USAGE.take_summary() {
+				tracing::info!("{}", format_usage(&network, &usage));
+			}
+		}
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_summary_with_costs() {
+		let usage = RpcUsage::default();
+		usage.set_costs(
+			"usage_priced",
+			RpcCostConfig {
+				default: 10.0,
+				methods: BTreeMap::from([("eth_getLogs".to_string(), 75.0)]),
+				unit: Some("CU".to_string()),
+			},
+		);
+		usage.record("usage_priced", "eth_getLogs");
+		usage.record("usage_priced", "eth_getLogs");
+		usage.record("usage_priced", "eth_blockNumber");
+		usage.record("usage_free", "getLatestLedger");
+
+		let summary = usage.take_summary();
+		let priced = &summary["usage_priced"];
+		assert_eq!(priced.total_calls(), 3);
+		assert_eq!(priced.calls["eth_getLogs"], 2);
+		assert_eq!(priced.cost, Some(160.0));
+		assert_eq!(
+			format_usage("usage_priced", priced),
+			"RPC usage of usage_priced: 3 calls (eth_blockNumber: 1, eth_getLogs: 2), \
+			 estimated cost 160.00 CU"
+		);
+		assert_eq!(summary["usage_free"].cost, None);
+
+		// A summary starts a new period
+		assert!(usage.take_summary().is_empty());
+	}
+
+	#[test]
+	fn test_record_estimated_cost_metric() {
+		let usage = RpcUsage::default();
+		usage.set_costs(
+			"usage_metric",
+			RpcCostConfig {
+				default: 2.5,
+				..Default::default()
+			},
+		);
+		let before = RPC_ESTIMATED_COST_TOTAL
+			.with_label_values(&["usage_metric", "eth_call"])
+			.get();
+		usage.record("usage_metric", "eth_call");
+		assert_eq!(
+			RPC_ESTIMATED_COST_TOTAL
+				.with_label_values(&["usage_metric", "eth_call"])
+				.get() - before,
+			2.5
+		);
+	}
+}
@@ -3,26 +3,38 @@
 //! This module provides various utility functions and types that are used across
 //! the application. Currently includes:
 //!
+//! - alert_import: Conversion of third-party alert definitions into monitors and triggers
 //! - constants: Constants for the application
+//! - control: Runtime control state and control socket
+//! - crash: Panic handling with a flush of the buffered state
 //! - cron_utils: Utilities for working with cron schedules and time intervals
 //! - logging: Logging utilities
 //! - macros: Macros for common functionality
 //! - metrics: Metrics utilities
 //! - monitor: Monitor utilities
 //! - parsing: Parsing utilities
+//! - retry: Retries with exponential backoff, jitter and budget
+//! - state_archive: Export and import of the monitor state
+//! - systemd: Readiness and watchdog notifications to the service manager
 //! - tests: Test utilities
 //! - http: HTTP client utilities (i.e. creation retryable HTTP clients)
 
 mod cron_utils;
 
+pub mod alert_import;
 pub mod client_storage;
 pub mod constants;
+pub mod control;
+pub mod crash;
 pub mod http;
 pub mod logging;
 pub mod macros;
 pub mod metrics;
 pub mod monitor;
 pub mod parsing;
+pub mod retry;
+pub mod state_archive;
+pub mod systemd;
 pub mod tests;
 
 pub use client_storage::ClientStorage;
@@ -31,3 +43,4 @@ pub use cron_utils::*;
 pub use http::*;
 pub use macros::*;
 pub use parsing::*;
+pub use retry::{count_retries, RetryBudget, RetryBudgets, RetryPolicy};
@@ -0,0 +1,565 @@
+//! ABI drift detection of monitored contracts.
+//!
+//! Monitors decode contract interactions with the contract specs of their configuration. After
+//! an upgrade, the deployed contract may no longer expose the configured functions and events,
+//! and the monitor silently stops matching. A monitor with an `abi_drift` section periodically
+//! compares the configured spec of each address with the interface of the deployed contract:
+//! the verified ABI published on Etherscan for EVM networks (of the implementation, for
+//! EIP-1967 proxies), and the contract spec embedded in the contract code for Stellar networks.
+//! Only the configured functions and events are compared: those missing from the deployed
+//! contract or whose signature changed are logged, exposed by the `abi_drift_signatures`
+//! metric, and notified to the triggers of the `abi_drift` section.
+
+use std::{
+	collections::{BTreeSet, HashMap},
+	sync::Arc,
+	time::Duration,
+};
+use tokio::task::JoinHandle;
+
+use crate::{
+	models::{
+		BlockChainType, ContractSpec, EVMContractSpec, Monitor, Network,
+		StellarFormattedContractSpec, Trigger,
+	},
+	services::{
+		blockchain::{BlockChainClient, ClientPoolTrait, EvmClientTrait},
+		notification::NotificationService,
+	},
+	utils::metrics::record_abi_drift,
+};
+
+/// Default endpoint of the Etherscan API, serving all the chains it supports
+pub const DEFAULT_ETHERSCAN_API_URL: &str = "https://api.etherscan.io/v2/api";
+
+/// Storage slot holding the implementation of an EIP-1967 proxy
+const EIP1967_IMPLEMENTATION_SLOT: &str =
+	"0x360894a13ba1a3210667c828492db98dca3e2076cc3735a920a3ca505d382bbc";
+
+/// Template variables of the notifications of a drift
+pub const DRIFT_VARIABLES: &[&str] = &[
+	"monitor.name",
+	"drift.network",
+	"drift.address",
+	"drift.missing",
+	"drift.changed",
+];
+
+/// Configured functions and events that differ in the deployed contract
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AbiDrift {
+	/// Configured signatures without a function or event of the same name in the deployed
+	/// contract
+	pub missing: Vec<String>,
+	/// Configured signatures whose function or event has another signature in the deployed
+	/// contract, as `configured -> deployed`
+	pub changed: Vec<String>,
+}
+
+impl AbiDrift {
+	/// Returns true if the configured functions and events are all deployed
+	pub fn is_empty(&self) -> bool {
+		self.missing.is_empty() && self.changed.is_empty()
+	}
+
+	/// Returns the number of differing signatures
+	pub fn len(&self) -> usize {
+		self.missing.len() + self.changed.len()
+	}
+}
+
+/// Drift of a contract of a monitor
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContractDrift {
+	/// Slug of the network of the contract
+	pub network: String,
+	/// Address of the contract
+	pub address: String,
+	/// Differences with the configured spec
+	pub drift: AbiDrift,
+}
+
+/// Access to the verified ABIs of the Etherscan API
+#[derive(Debug, Clone, PartialEq)]
+pub struct EtherscanConfig {
+	/// Endpoint of the API
+	pub url: String,
+	/// API key
+	pub api_key: String,
+}
+
+/// Returns the signatures of the functions and events of a contract spec
+fn spec_signatures(spec: &ContractSpec) -> BTreeSet<String> {
+	match spec {
+		ContractSpec::EVM(abi) => abi
+			.functions()
+			.map(|function| function.signature())
+			.chain(abi.events().map(|event| event.signature()))
+			.collect(),
+		ContractSpec::Stellar(spec) => {
+			let formatted = StellarFormattedContractSpec::from(spec.clone());
+			formatted
+				.functions
+				.into_iter()
+				.map(|function| function.signature)
+				.chain(formatted.events.into_iter().map(|event| event.signature))
+				.collect()
+		}
+		ContractSpec::Midnight | ContractSpec::Solana(_) => BTreeSet::new(),
+	}
+}
+
+/// Compares a configured contract spec with the interface of the deployed contract
+///
+/// Functions and events of the deployed contract absent from the configured spec are not a
+/// drift, a spec usually describing only the part of the interface a monitor uses.
+///
+/// # Arguments
+/// * `configured` - The contract spec of the monitor configuration
+/// * `deployed` - The interface of the deployed contract
+///
+/// # Returns
+/// The configured signatures missing from the deployed contract or changed in it
+pub fn compare_specs(configured: &ContractSpec, deployed: &ContractSpec) -> AbiDrift {
+	let configured = spec_signatures(configured);
+	let deployed = spec_signatures(deployed);
+	let name = |signature: &str| signature.split('(').next().unwrap_or_default().to_string();
+
+	let mut drift = AbiDrift::default();
+	for signature in configured.difference(&deployed) {
+		let replacements: Vec<&str> = deployed
+			.iter()
+			.filter(|deployed| name(deployed) == name(signature))
+			.map(String::as_str)
+			.collect();
+		if replacements.is_empty() {
+			drift.missing.push(signature.clone());
+		} else {
+			drift
+				.changed
+				.push(format!("{} -> {}", signature, replacements.join(" | ")));
+		}
+	}
+	drift
+}
+
+/// Returns the implementation address held in the EIP-1967 implementation slot
+///
+/// # Arguments
+/// * `slot` - Hex-encoded value of the slot
+///
+/// # Returns
+/// The implementation, or `None` if the slot is empty (the contract is not a proxy)
+fn slot_address(slot: &str) -> Option<String> {
+	let value = slot.trim_start_matches("0x");
+	// The address is held in the lowest 20 bytes of the slot
+	if value.len() < 40 || value.chars().all(|c| c == '0') {
+		return None;
+	}
+	Some(format!("0x{}", &value[value.len() - 40..]))
+}
+
+/// Returns the implementation of a contract if it is an EIP-1967 proxy
+///
+/// # Arguments
+/// * `client` - The client of the network of the contract
+/// * `address` - The address of the contract
+///
+/// # Returns
+/// The implementation, or `None` if the contract is not a proxy
+pub async fn eip1967_implementation<C: EvmClientTrait>(
+	client: &C,
+	address: &str,
+) -> Result<Option<String>, anyhow::Error> {
+	let slot = client
+		.get_storage_at(address, EIP1967_IMPLEMENTATION_SLOT)
+		.await?;
+	Ok(slot_address(&slot))
+}
+
+/// Fetches the verified ABI of a contract from the Etherscan API
+///
+/// # Arguments
+/// * `http` - The HTTP client to use
+/// * `etherscan` - The Etherscan API access
+/// * `chain_id` - The chain ID of the network of the contract
+/// * `address` - The address of the contract
+///
+/// # Errors
+/// Returns an error if the request fails or if the contract is not verified
+pub async fn fetch_verified_abi(
+	http: &reqwest::Client,
+	etherscan: &EtherscanConfig,
+	chain_id: u64,
+	address: &str,
+) -> Result<ContractSpec, anyhow::Error> {
+	let response: serde_json::Value = http
+		.get(&etherscan.url)
+		.query(&[
+			("chainid", chain_id.to_string().as_str()),
+			("module", "contract"),
+			("action", "getabi"),
+			("address", address),
+			("apikey", etherscan.api_key.as_str()),
+		])
+		.send()
+		.await?
+		.error_for_status()?
+		.json()
+		.await?;
+
+	let result = response["result"].as_str().unwrap_or_default();
+	if response["status"].as_str() != Some("1") {
+		return Err(anyhow::anyhow!(
+			"Etherscan returned no ABI for {}: {}",
+			address,
+			result
+		));
+	}
+	let abi: alloy::json_abi::JsonAbi = serde_json::from_str(result)
+		.map_err(|e| anyhow::anyhow!("Failed to parse the ABI of {}: {}", address, e))?;
+	Ok(ContractSpec::EVM(EVMContractSpec::from(abi)))
+}
+
+/// Fetches the interface of a deployed contract
+async fn deployed_spec<CP: ClientPoolTrait>(
+	client_pool: &CP,
+	http: &reqwest::Client,
+	etherscan: Option<&EtherscanConfig>,
+	network: &Network,
+	address: &str,
+) -> Result<ContractSpec, anyhow::Error> {
+	match network.network_type {
+		BlockChainType::EVM => {
+			let etherscan = etherscan.ok_or_else(|| {
+				anyhow::anyhow!("ETHERSCAN_API_KEY is required to fetch verified ABIs")
+			})?;
+			let chain_id = network
+				.chain_id
+				.ok_or_else(|| anyhow::anyhow!("Network {} has no chain_id", network.slug))?;
+			// The verified ABI of a proxy describes the proxy, not the contract behind it
+			let client = client_pool.get_evm_client(network).await?;
+			let implementation = eip1967_implementation(&*client, address).await?;
+			fetch_verified_abi(
+				http,
+				etherscan,
+				chain_id,
+				implementation.as_deref().unwrap_or(address),
+			)
+			.await
+		}
+		BlockChainType::Stellar => {
+			let client = client_pool.get_stellar_client(network).await?;
+			client.get_contract_spec(address).await
+		}
+		_ => Err(anyhow::anyhow!(
+			"ABI drift checks are not supported on {} networks",
+			network.network_type
+		)),
+	}
+}
+
+/// Compares the configured contract specs of a monitor with the deployed contracts
+///
+/// Addresses without a configured contract spec are skipped, and contracts whose interface
+/// cannot be fetched are logged and skipped.
+///
+/// # Arguments
+/// * `client_pool` - The client pool to use
+/// * `http` - The HTTP client of the Etherscan API
+/// * `etherscan` - The Etherscan API access, required for EVM networks
+/// * `networks` - The configured networks, keyed by slug
+/// * `monitor` - The monitor to check
+///
+/// # Returns
+/// The outcome of each compared contract
+pub async fn check_abi_drift<CP: ClientPoolTrait>(
+	client_pool: &CP,
+	http: &reqwest::Client,
+	etherscan: Option<&EtherscanConfig>,
+	networks: &HashMap<String, Network>,
+	monitor: &Monitor,
+) -> Vec<ContractDrift> {
+	let mut drifts = Vec::new();
+	for network in monitor
+		.networks
+		.iter()
+		.filter_map(|slug| networks.get(slug))
+	{
+		for address in monitor.addresses_for_network(&network.slug) {
+			let Some(configured) = &address.contract_spec else {
+				continue;
+			};
+			let deployed = match deployed_spec(
+				client_pool,
+				http,
+				etherscan,
+				network,
+				&address.address,
+			)
+			.await
+			{
+				Ok(spec) => spec,
+				Err(e) => {
+					tracing::warn!(
+						monitor = %monitor.name,
+						"Failed to fetch the interface of {} on {}: {}",
+						address.address,
+						network.slug,
+						e
+					);
+					continue;
+				}
+			};
+			let drift = compare_specs(configured, &deployed);
+			record_abi_drift(&monitor.name, &network.slug, &address.address, drift.len());
+			drifts.push(ContractDrift {
+				network: network.slug.clone(),
+				address: address.address.clone(),
+				drift,
+			});
+		}
+	}
+	drifts
+}
+
+/// Returns the template variables of the notification of a drift
+fn drift_variables(monitor: &Monitor, drift: &ContractDrift) -> HashMap<String, String> {
+	HashMap::from([
+		("monitor.name".to_string(), monitor.name.clone()),
+		("drift.network".to_string(), drift.network.clone()),
+		("drift.address".to_string(), drift.address.clone()),
+		("drift.missing".to_string(), drift.drift.missing.join(", ")),
+		("drift.changed".to_string(), drift.drift.changed.join(", ")),
+	])
+}
+
+/// Periodically checks the ABI drift of the monitors that enable it
+///
+/// A drift is notified to the triggers of the `abi_drift` section of the monitor when it is
+/// first detected and whenever it changes.
+///
+/// # Arguments
+/// * `client_pool` - The client pool to use
+/// * `notification_service` - The service sending the notifications
+/// * `triggers` - The configured triggers, keyed by name
+/// * `networks` - The configured networks, keyed by slug
+/// * `monitors` - The active monitors
+/// * `etherscan` - Access to the verified ABIs of the Etherscan API, if configured
+///
+/// # Returns
+/// The tasks running the checks, one per monitor
+pub fn start_abi_drift_checks<CP>(
+	client_pool: Arc<CP>,
+	notification_service: Arc<NotificationService>,
+	triggers: &HashMap<String, Trigger>,
+	networks: &HashMap<String, Network>,
+	monitors: &[Monitor],
+	etherscan: Option<EtherscanConfig>,
+) -> Vec<JoinHandle<()>>
+where
+	CP: ClientPoolTrait + Send + Sync + 'static,
+{
+	let http = reqwest::Client::new();
+	let mut handles = Vec::new();
+	for monitor in monitors {
+		let Some(config) = &monitor.abi_drift else {
+			continue;
+		};
+		let interval = Duration::from_secs(config.interval_secs);
+		let drift_triggers: Vec<Trigger> = config
+			.triggers
+			.iter()
+			.filter_map(|name| triggers.get(name).cloned())
+			.collect();
+		let monitor = monitor.clone();
+		let networks = networks.clone();
+		let client_pool = client_pool.clone();
+		let notification_service = notification_service.clone();
+		let etherscan = etherscan.clone();
+		let http = http.clone();
+		handles.push(tokio::spawn(async move {
+			let mut notified: HashMap<(String, String), AbiDrift> = HashMap::new();
+			let mut ticker = tokio::time::interval(interval);
+			loop {
+				ticker.tick().await;
+				let drifts = check_abi_drift(
+					&*client_pool,
+					&http,
+					etherscan.as_ref(),
+					&networks,
+					&monitor,
+				)
+				.await;
+				for drift in drifts {
+					let key = (drift.network.clone(), drift.address.clone());
+					if drift.drift.is_empty() {
+						notified.remove(&key);
+						continue;
+					}
+					tracing::warn!(
+						monitor = %monitor.name,
+						"Contract {} on {} no longer matches its configured spec (missing: [{}], changed: [{}])",
+						drift.address,
+						drift.network,
+						drift.drift.missing.join(", "),
+						drift.drift.changed.join(", ")
+					);
+					if notified.get(&key) == Some(&drift.drift) {
+						continue;
+					}
+					let variables = drift_variables(&monitor, &drift);
+					for trigger in &drift_triggers {
+						let trigger = trigger.with_parameters(&monitor);
+						let mut variables = variables.clone();
+						variables.extend(trigger.parameter_variables(&monitor));
+						if let Err(e) = notification_service.notify(&trigger, &variables).await {
+							tracing::error!(
+								monitor = %monitor.name,
+								"Failed to notify the ABI drift to trigger {}: {}",
+								trigger.name,
+								e
+							);
+						}
+					}
+					notified.insert(key, drift.drift);
+				}
+			}
+		}));
+	}
+	handles
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use serde_json::json;
+
+	fn evm_spec(abi: serde_json::Value) -> ContractSpec {
+		ContractSpec::EVM(EVMContractSpec::from(abi))
+	}
+
+	fn transfer_abi() -> serde_json::Value {
+		json!([
+			{
+				"type": "function",
+				"name": "transfer",
+				"inputs": [
+					{"name": "to", "type": "address"},
+					{"name": "value", "type": "uint256"}
+				],
+				"outputs": [{"name": "", "type": "bool"}],
+				"stateMutability": "nonpayable"
+			},
+			{
+				"type": "event",
+				"name": "Transfer",
+				"inputs": [
+					{"name": "from", "type": "address", "indexed": true},
+					{"name": "to", "type": "address", "indexed": true},
+					{"name": "value", "type": "uint256", "indexed": false}
+				],
+				"anonymous": false
+			}
+		])
+	}
+
+	#[test]
+	fn test_compare_specs() {
+		let configured = evm_spec(transfer_abi());
+		assert!(compare_specs(&configured, &configured).is_empty());
+
+		let mut upgraded = transfer_abi();
+		upgraded[0]["inputs"][1]["type"] = json!("uint128");
+		let drift = compare_specs(&configured, &evm_spec(upgraded));
+		assert!(drift.missing.is_empty());
+		assert_eq!(
+			drift.changed,
+			vec!["transfer(address,uint256) -> transfer(address,uint128)"]
+		);
+		assert_eq!(drift.len(), 1);
+
+		// Functions of the deployed contract absent from the configured spec are not a drift
+		let mut extended = transfer_abi();
+		extended.as_array_mut().unwrap().push(json!({
+			"type": "function",
+			"name": "pause",
+			"inputs": [],
+			"outputs": [],
+			"stateMutability": "nonpayable"
+		}));
+		assert!(compare_specs(&configured, &evm_spec(extended)).is_empty());
+
+		let mut removed = transfer_abi();
+		removed.as_array_mut().unwrap().remove(1);
+		let drift = compare_specs(&configured, &evm_spec(removed));
+		assert_eq!(drift.missing, vec!["Transfer(address,address,uint256)"]);
+		assert!(drift.changed.is_empty());
+	}
+
+	#[test]
+	fn test_slot_address() {
+		assert_eq!(
+			slot_address("0x000000000000000000000000a0b86991c6218b36c1d19d4a2e9eb0ce3606eb48"),
+			Some("0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".to_string())
+		);
+		assert_eq!(
+			slot_address("0x0000000000000000000000000000000000000000000000000000000000000000"),
+			None
+		);
+		assert_eq!(slot_address("0x"), None);
+	}
+
+	#[tokio::test]
+	async fn test_fetch_verified_abi() {
+		let mut server = mockito::Server::new_async().await;
+		let address = "0x0000000000000000000000000000000000000001";
+		let verified = server
+			.mock("GET", "/")
+			.match_query(mockito::Matcher::AllOf(vec![
+				mockito::Matcher::UrlEncoded("chainid".into(), "1".into()),
+				mockito::Matcher::UrlEncoded("action".into(), "getabi".into()),
+				mockito::Matcher::UrlEncoded("address".into(), address.into()),
+				mockito::Matcher::UrlEncoded("apikey".into(), "key".into()),
+			]))
+			.with_body(
+				json!({
+					"status": "1",
+					"message": "OK",
+					"result": transfer_abi().to_string()
+				})
+				.to_string(),
+			)
+			.create_async()
+			.await;
+		let etherscan = EtherscanConfig {
+			url: server.url(),
+			api_key: "key".to_string(),
+		};
+		let http = reqwest::Client::new();
+
+		let spec = fetch_verified_abi(&http, &etherscan, 1, address)
+			.await
+			.unwrap();
+		assert!(compare_specs(&evm_spec(transfer_abi()), &spec).is_empty());
+		verified.assert_async().await;
+
+		server
+			.mock("GET", "/")
+			.match_query(mockito::Matcher::UrlEncoded("chainid".into(), "5".into()))
+			.with_body(
+				json!({
+					"status": "0",
+					"message": "NOTOK",
+					"result": "Contract source code not verified"
+				})
+				.to_string(),
+			)
+			.create_async()
+			.await;
+		let err = fetch_verified_abi(&http, &etherscan, 5, address)
+			.await
+			.unwrap_err();
+		assert!(err.to_string().contains("not verified"));
+	}
+}
@@ -0,0 +1,336 @@
+//! Activation of monitors on contract deployment.
+//!
+//! A monitor with an `activate_on_deployment` section can be registered before the contract it
+//! watches is deployed. Its dependency is checked periodically, and by the block handler for
+//! the blocks of the dependency network past the last check, until code is found at the
+//! contract address. Every check records the latest block it covers, so that once the contract
+//! is found the monitor applies from the first block after the last check that found no code:
+//! the blocks processed between the deployment and its detection are not missed.
+
+use lazy_static::lazy_static;
+use std::{
+	collections::HashMap,
+	sync::{Arc, RwLock},
+	time::Duration,
+};
+use tokio::task::JoinHandle;
+
+use crate::{
+	models::{BlockChainType, DeploymentDependency, Monitor, Network},
+	services::blockchain::{BlockChainClient, ClientPoolTrait},
+};
+
+lazy_static! {
+	/// Process-wide record of the deployment checks of the monitors.
+	pub static ref DEPLOYMENTS: DeploymentTracker = DeploymentTracker::default();
+}
+
+/// Deployment checks of a monitor
+#[derive(Debug, Clone, Copy, Default)]
+struct DeploymentState {
+	/// Latest block of the dependency network checked without code at the address
+	checked_until: Option<u64>,
+	/// First block of the dependency network the monitor applies to, once deployed
+	deployed_at: Option<u64>,
+}
+
+/// Deployment checks of the monitors waiting for a contract, keyed by monitor name
+#[derive(Default)]
+pub struct DeploymentTracker {
+	states: RwLock<HashMap<String, DeploymentState>>,
+}
+
+impl DeploymentTracker {
+	/// Records a check of the contract dependency of a monitor
+	///
+	/// A check finding code activates the monitor from the block following the latest block
+	/// checked without code, or from the genesis if the first check found code.
+	///
+	/// # Arguments
+	/// * `monitor` - The monitor name
+	/// * `latest_block` - Latest block of the dependency network when the check started
+	/// * `deployed` - Whether code was found at the contract address
+	pub fn record_check(&self, monitor: &str, latest_block: u64, deployed: bool) {
+		let mut states = match self.states.write() {
+			Ok(states) => states,
+			Err(poisoned) => poisoned.into_inner(),
+		};
+		let state = states.entry(monitor.to_string()).or_default();
+		if state.deployed_at.is_some() {
+			return;
+		}
+		if deployed {
+			state.deployed_at = Some(state.checked_until.map_or(0, |block| block + 1));
+		} else {
+			state.checked_until = Some(state.checked_until.unwrap_or(0).max(latest_block));
+		}
+	}
+
+	/// Returns the first block of the dependency network the monitor applies to, once its
+	/// contract is deployed
+	///
+	/// # Arguments
+	/// * `monitor` - The monitor name
+	pub fn deployed_at(&self, monitor: &str) -> Option<u64> {
+		self.state(monitor).deployed_at
+	}
+
+	/// Returns true if the monitor has no contract dependency, or if it applies to the block
+	///
+	/// Block numbers are only compared on the network of the dependency; on its other
+	/// networks, the monitor applies to every block processed once the contract is deployed.
+	///
+	/// # Arguments
+	/// * `monitor` - The monitor
+	/// * `network_slug` - Network of the block
+	/// * `block_number` - Number of the block
+	pub fn is_active(&self, monitor: &Monitor, network_slug: &str, block_number: u64) -> bool {
+		let Some(dependency) = &monitor.activate_on_deployment else {
+			return true;
+		};
+		self.deployed_at(&monitor.name).is_some_and(|deployed_at| {
+			dependency.network != network_slug || block_number >= deployed_at
+		})
+	}
+
+	/// Returns true if the dependency of the monitor must be checked before filtering a
+	/// block of its network: the contract is not known deployed and the block is past the
+	/// latest check
+	fn needs_check(&self, monitor: &str, block_number: u64) -> bool {
+		let state = self.state(monitor);
+		state.deployed_at.is_none()
+			&& state
+				.checked_until
+				.is_none_or(|checked_until| block_number > checked_until)
+	}
+
+	fn state(&self, monitor: &str) -> DeploymentState {
+		let states = match self.states.read() {
+			Ok(states) => states,
+			Err(poisoned) => poisoned.into_inner(),
+		};
+		states.get(monitor).copied().unwrap_or_default()
+	}
+}
+
+/// Checks whether the contract of a deployment dependency has code
+///
+/// # Arguments
+/// * `client_pool` - The client pool to use
+/// * `network` - The network of the contract
+/// * `dependency` - The deployment dependency
+///
+/// # Returns
+/// The latest block of the network when the check started, and whether code is found at
+/// the contract address
+///
+/// # Errors
+/// Returns an error if the network does not support the check or if the request fails
+pub async fn is_deployed<CP: ClientPoolTrait>(
+	client_pool: &CP,
+	network: &Network,
+	dependency: &DeploymentDependency,
+) -> Result<(u64, bool), anyhow::Error> {
+	match network.network_type {
+		BlockChainType::EVM => {
+			let client = client_pool.get_evm_client(network).await?;
+			let latest_block = client.get_latest_block_number().await?;
+			Ok((
+				latest_block,
+				client.has_contract_code(&dependency.address).await?,
+			))
+		}
+		BlockChainType::Stellar => {
+			let client = client_pool.get_stellar_client(network).await?;
+			let latest_block = client.get_latest_block_number().await?;
+			Ok((
+				latest_block,
+				client.has_contract_code(&dependency.address).await?,
+			))
+		}
+		_ => Err(anyhow::anyhow!(
+			"Deployment checks are not supported on {} networks",
+			network.network_type
+		)),
+	}
+}
+
+/// Checks the deployment dependency of a monitor and records the check
+///
+/// # Arguments
+/// * `client_pool` - The client pool to use
+/// * `network` - The network of the contract
+/// * `monitor_name` - The monitor waiting for the contract
+/// * `dependency` - The deployment dependency of the monitor
+///
+/// # Returns
+/// True if the contract is deployed
+///
+/// # Errors
+/// Returns an error if the network does not support the check or if the request fails
+pub async fn check_deployment<CP: ClientPoolTrait>(
+	client_pool: &CP,
+	network: &Network,
+	monitor_name: &str,
+	dependency: &DeploymentDependency,
+) -> Result<bool, anyhow::Error> {
+	let (latest_block, deployed) = is_deployed(client_pool, network, dependency).await?;
+	DEPLOYMENTS.record_check(monitor_name, latest_block, deployed);
+	if deployed {
+		tracing::info!(
+			monitor = %monitor_name,
+			"{} is deployed on {}, monitor activated from block {}",
+			dependency.address,
+			network.slug,
+			DEPLOYMENTS.deployed_at(monitor_name).unwrap_or(0)
+		);
+	}
+	Ok(deployed)
+}
+
+/// Checks the dependencies on a network that are not known deployed at a block
+///
+/// Called before filtering a block, so that a contract deployed since the latest periodic
+/// check activates its monitors from the block following that check. Failed checks are
+/// logged, the monitors staying inactive for the block.
+///
+/// # Arguments
+/// * `client_pool` - The client pool to use
+/// * `network` - The network of the block
+/// * `monitors` - The active monitors
+/// * `block_number` - The block about to be filtered
+pub async fn check_deployments_at<CP: ClientPoolTrait>(
+	client_pool: &CP,
+	network: &Network,
+	monitors: &[Monitor],
+	block_number: u64,
+) {
+	for monitor in monitors {
+		let Some(dependency) = &monitor.activate_on_deployment else {
+			continue;
+		};
+		if dependency.network != network.slug
+			|| !DEPLOYMENTS.needs_check(&monitor.name, block_number)
+		{
+			continue;
+		}
+		if let Err(e) = check_deployment(client_pool, network, &monitor.name, dependency).await {
+			tracing::warn!(
+				monitor = %monitor.name,
+				"Failed to check the deployment of {}: {}",
+				dependency.address,
+				e
+			);
+		}
+	}
+}
+
+/// Periodically checks the contract dependencies of the monitors until they are deployed
+///
+/// # Arguments
+/// * `client_pool` - The client pool to use
+/// * `networks` - The configured networks, keyed by slug
+/// * `monitors` - The active monitors
+///
+/// # Returns
+/// The tasks running the checks, one per monitor waiting for a deployment
+pub fn start_deployment_watchers<CP>(
+	client_pool: Arc<CP>,
+	networks: &HashMap<String, Network>,
+	monitors: &[Monitor],
+) -> Vec<JoinHandle<()>>
+where
+	CP: ClientPoolTrait + Send + Sync + 'static,
+{
+	let mut handles = Vec::new();
+	for monitor in monitors {
+		let Some(dependency) = monitor.activate_on_deployment.clone() else {
+			continue;
+		};
+		let Some(network) = networks.get(&dependency.network).cloned() else {
+			tracing::error!(
+				"Network '{}' of the deployment dependency of monitor '{}' not found",
+				dependency.network,
+				monitor.name
+			);
+			continue;
+		};
+		tracing::info!(
+			monitor = %monitor.name,
+			"Monitor inactive until {} is deployed on {}",
+			dependency.address,
+			network.slug
+		);
+
+		let interval = Duration::from_secs(dependency.interval_secs);
+		let monitor_name = monitor.name.clone();
+		let client_pool = client_pool.clone();
+		handles.push(tokio::spawn(async move {
+			let mut ticker = tokio::time::interval(interval);
+			loop {
+				ticker.tick().await;
+				if DEPLOYMENTS.deployed_at(&monitor_name).is_some() {
+					break;
+				}
+				match check_deployment(&*client_pool, &network, &monitor_name, &dependency).await {
+					Ok(true) => break,
+					Ok(false) => tracing::debug!(
+						monitor = %monitor_name,
+						"{} is not deployed on {} yet",
+						dependency.address,
+						network.slug
+					),
+					Err(e) => tracing::warn!(
+						monitor = %monitor_name,
+						"Failed to check the deployment of {}: {}",
+						dependency.address,
+						e
+					),
+				}
+			}
+		}));
+	}
+	handles
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::utils::tests::builders::evm::monitor::MonitorBuilder;
+
+	#[test]
+	fn test_is_active() {
+		let tracker = DeploymentTracker::default();
+		let independent = MonitorBuilder::new().name("independent").build();
+		let dependent = MonitorBuilder::new()
+			.name("dependent")
+			.activate_on_deployment(
+				"ethereum_mainnet",
+				"0x0000000000000000000000000000000000000001",
+			)
+			.build();
+
+		assert!(tracker.is_active(&independent, "ethereum_mainnet", 0));
+		assert!(!tracker.is_active(&dependent, "ethereum_mainnet", 100));
+		assert!(tracker.needs_check("dependent", 100));
+
+		// No code up to block 120, the blocks up to it need no new check
+		tracker.record_check("dependent", 120, false);
+		assert!(!tracker.needs_check("dependent", 110));
+		assert!(tracker.needs_check("dependent", 121));
+
+		// Code found at block 130: the monitor applies from the block following the last
+		// check without code
+		tracker.record_check("dependent", 130, true);
+		assert_eq!(tracker.deployed_at("dependent"), Some(121));
+		assert!(!tracker.is_active(&dependent, "ethereum_mainnet", 120));
+		assert!(tracker.is_active(&dependent, "ethereum_mainnet", 121));
+		assert!(!tracker.needs_check("dependent", 200));
+		// Block numbers of other networks are not compared
+		assert!(tracker.is_active(&dependent, "base_mainnet", 1));
+
+		// A contract found on the first check applies to every block
+		tracker.record_check("deployed", 500, true);
+		assert_eq!(tracker.deployed_at("deployed"), Some(0));
+	}
+}
@@ -0,0 +1,329 @@
+//! Canary checks of monitors.
+//!
+//! A monitor with a `canary` section is periodically checked against a block it is known to
+//! match: the block is fetched and filtered again, the trigger conditions are evaluated and
+//! the notifications are sent to the triggers of the canary. The triggers of the monitor and
+//! of the owners of its addresses are never notified of canary matches, and a canary without
+//! triggers is a dry run that prepares the notifications without sending them. A check fails
+//! when the block does not produce a match or when a notification cannot be sent, proving
+//! whether the alerting path works end-to-end. The outcome of every check is exposed by the `canary_up`,
+//! `canary_checks_total` and `canary_last_success_timestamp_seconds` metrics, so that failures
+//! are alerted on through a path independent of the one being checked.
+
+use async_trait::async_trait;
+use std::{
+	collections::HashMap,
+	sync::{Arc, Mutex},
+	time::Duration,
+};
+use tokio::task::JoinHandle;
+
+use crate::{
+	bootstrap::{get_contract_specs, run_trigger_filters},
+	models::{Monitor, MonitorMatch, Network, ScriptLanguage},
+	services::{
+		blockchain::ClientPoolTrait,
+		filter::{handle_match, FilterServiceTrait},
+		trigger::{TriggerError, TriggerExecutionServiceTrait},
+	},
+	utils::{
+		metrics::record_canary_check,
+		monitor::{
+			execution::{filter_network_block, ExecutionResult},
+			MonitorExecutionError,
+		},
+	},
+};
+
+/// Trigger execution service keeping the errors of the triggers it executes
+struct RecordingTriggerService<'a, T> {
+	inner: &'a T,
+	/// Whether the notifications are prepared without being sent
+	dry_run: bool,
+	errors: Mutex<Vec<String>>,
+}
+
+#[async_trait]
+impl<T: TriggerExecutionServiceTrait + Send + Sync> TriggerExecutionServiceTrait
+	for RecordingTriggerService<'_, T>
+{
+	async fn execute(
+		&self,
+		trigger_slugs: &[String],
+		variables: HashMap<String, String>,
+		monitor_match: &MonitorMatch,
+		trigger_scripts: &HashMap<String, (ScriptLanguage, String)>,
+	) -> Result<(), TriggerError> {
+		if self.dry_run {
+			return Ok(());
+		}
+		let result = self
+			.inner
+			.execute(trigger_slugs, variables, monitor_match, trigger_scripts)
+			.await;
+		if let Err(e) = &result {
+			match self.errors.lock() {
+				Ok(mut errors) => errors.push(e.to_string()),
+				Err(poisoned) => poisoned.into_inner().push(e.to_string()),
+			}
+		}
+		result
+	}
+
+	async fn load_scripts(
+		&self,
+		monitors: &[Monitor],
+	) -> Result<HashMap<String, (ScriptLanguage, String)>, TriggerError> {
+		self.inner.load_scripts(monitors).await
+	}
+}
+
+/// Runs the canary check of a monitor
+///
+/// # Arguments
+/// * `client_pool` - The client pool to use
+/// * `filter_service` - The filter service to use
+/// * `trigger_service` - The trigger execution service sending the notifications
+/// * `trigger_scripts` - The trigger condition scripts of the monitors
+/// * `network` - The network of the canary block
+/// * `monitor` - The monitor to check
+///
+/// # Returns
+/// The number of matches notified
+///
+/// # Errors
+/// Returns an error if the monitor has no canary, if the block could not be filtered, did not
+/// produce a match, or if a notification could not be sent to the triggers of the canary
+pub async fn run_canary<CP, FS, T>(
+	client_pool: &Arc<CP>,
+	filter_service: &FS,
+	trigger_service: &T,
+	trigger_scripts: &HashMap<String, (ScriptLanguage, String)>,
+	network: &Network,
+	monitor: &Monitor,
+) -> ExecutionResult<usize>
+where
+	CP: ClientPoolTrait + Send + Sync + 'static,
+	FS: FilterServiceTrait + Send + Sync + 'static,
+	T: TriggerExecutionServiceTrait + Send + Sync,
+{
+	let canary = monitor.canary.as_ref().ok_or_else(|| {
+		MonitorExecutionError::not_found(
+			format!("Monitor '{}' has no canary", monitor.name),
+			None,
+			None,
+		)
+	})?;
+
+	// Canary matches are only sent to the triggers of the canary, not to the triggers of the
+	// monitor or of the owners of its addresses
+	let mut canary_monitor = monitor.for_network(&network.slug);
+	canary_monitor.triggers = canary.triggers.clone();
+	for owner in &mut canary_monitor.owners {
+		owner.triggers.clear();
+	}
+	let monitors = vec![canary_monitor];
+	let contract_specs =
+		get_contract_specs(client_pool, &[(network.clone(), monitors.clone())]).await;
+	let matches = filter_network_block(
+		&**client_pool,
+		filter_service,
+		network,
+		Some(canary.block),
+		&monitors,
+		&contract_specs,
+	)
+	.await?;
+	let matches = run_trigger_filters(&matches, &network.slug, trigger_scripts).await;
+	if matches.is_empty() {
+		return Err(MonitorExecutionError::execution_error(
+			format!(
+				"Canary block {} of {} produced no match",
+				canary.block, network.slug
+			),
+			None,
+			None,
+		));
+	}
+
+	let recording = RecordingTriggerService {
+		inner: trigger_service,
+		dry_run: canary.triggers.is_empty(),
+		errors: Mutex::new(Vec::new()),
+	};
+	for monitor_match in &matches {
+		handle_match(monitor_match.clone(), &recording, trigger_scripts)
+			.await
+			.map_err(|e| {
+				MonitorExecutionError::execution_error(
+					format!("Failed to handle canary match: {}", e),
+					None,
+					None,
+				)
+			})?;
+	}
+	let errors = recording
+		.errors
+		.into_inner()
+		.unwrap_or_else(|poisoned| poisoned.into_inner());
+	if !errors.is_empty() {
+		return Err(MonitorExecutionError::execution_error(
+			format!("Failed to send canary notifications: {}", errors.join(", ")),
+			None,
+			None,
+		));
+	}
+
+	Ok(matches.len())
+}
+
+/// Periodically runs the canary checks of the monitors that have one
+///
+/// # Arguments
+/// * `client_pool` - The client pool to use
+/// * `filter_service` - The filter service to use
+/// * `trigger_service` - The trigger execution service sending the notifications
+/// * `trigger_scripts` - The trigger condition scripts of the monitors
+/// * `networks` - The configured networks, keyed by slug
+/// * `monitors` - The active monitors
+///
+/// # Returns
+/// The tasks running the checks, one per canary
+pub fn start_canaries<CP, FS, T>(
+	client_pool: Arc<CP>,
+	filter_service: Arc<FS>,
+	trigger_service: Arc<T>,
+	trigger_scripts: HashMap<String, (ScriptLanguage, String)>,
+	networks: &HashMap<String, Network>,
+	monitors: &[Monitor],
+) -> Vec<JoinHandle<()>>
+where
+	CP: ClientPoolTrait + Send + Sync + 'static,
+	FS: FilterServiceTrait + Send + Sync + 'static,
+	T: TriggerExecutionServiceTrait + Send + Sync + 'static,
+{
+	let mut handles = Vec::new();
+	for monitor in monitors {
+		let Some(canary) = &monitor.canary else {
+			continue;
+		};
+		let Some(network) = networks.get(&canary.network).cloned() else {
+			tracing::error!(
+				"Network '{}' of the canary of monitor '{}' not found",
+				canary.network,
+				monitor.name
+			);
+			continue;
+		};
+
+		let interval = Duration::from_secs(canary.interval_secs);
+		let monitor = monitor.clone();
+		let client_pool = client_pool.clone();
+		let filter_service = filter_service.clone();
+		let trigger_service = trigger_service.clone();
+		let trigger_scripts = trigger_scripts.clone();
+		handles.push(tokio::spawn(async move {
+			let mut ticker = tokio::time::interval(interval);
+			loop {
+				ticker.tick().await;
+				let result = run_canary(
+					&client_pool,
+					&*filter_service,
+					&*trigger_service,
+					&trigger_scripts,
+					&network,
+					&monitor,
+				)
+				.await;
+				match &result {
+					Ok(notified) => tracing::info!(
+						monitor = %monitor.name,
+						"Canary check passed, {} matches notified",
+						notified
+					),
+					Err(e) => tracing::error!(
+						monitor = %monitor.name,
+						"Canary check failed: {}",
+						e
+					),
+				}
+				record_canary_check(&monitor.name, result.is_ok());
+			}
+		}));
+	}
+	handles
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		models::{EVMMonitorMatch, MatchConditions},
+		utils::tests::builders::evm::{monitor::MonitorBuilder, transaction::TransactionBuilder},
+	};
+
+	struct FailingTriggerService;
+
+	#[async_trait]
+	impl TriggerExecutionServiceTrait for FailingTriggerService {
+		async fn execute(
+			&self,
+			_trigger_slugs: &[String],
+			_variables: HashMap<String, String>,
+			_monitor_match: &MonitorMatch,
+			_trigger_scripts: &HashMap<String, (ScriptLanguage, String)>,
+		) -> Result<(), TriggerError> {
+			Err(TriggerError::execution_error_without_log(
+				"webhook unreachable",
+				None,
+				None,
+			))
+		}
+
+		async fn load_scripts(
+			&self,
+			_monitors: &[Monitor],
+		) -> Result<HashMap<String, (ScriptLanguage, String)>, TriggerError> {
+			Ok(HashMap::new())
+		}
+	}
+
+	#[tokio::test]
+	async fn test_recording_trigger_service_keeps_errors() {
+		let recording = RecordingTriggerService {
+			inner: &FailingTriggerService,
+			dry_run: false,
+			errors: Mutex::new(Vec::new()),
+		};
+		let monitor_match = MonitorMatch::EVM(Box::new(EVMMonitorMatch {
+			monitor: MonitorBuilder::new().name("canary").build(),
+			transaction: TransactionBuilder::new().build(),
+			receipt: None,
+			logs: None,
+			network_slug: "ethereum_mainnet".to_string(),
+			matched_on: MatchConditions::default(),
+			matched_on_audit: Vec::new(),
+			matched_on_args: None,
+			block_timestamp: None,
+			trace: None,
+		}));
+
+		assert!(recording
+			.execute(&[], HashMap::new(), &monitor_match, &HashMap::new())
+			.await
+			.is_err());
+		assert_eq!(recording.errors.lock().unwrap().len(), 1);
+
+		// A dry run does not send the notifications
+		let dry_run = RecordingTriggerService {
+			inner: &FailingTriggerService,
+			dry_run: true,
+			errors: Mutex::new(Vec::new()),
+		};
+		assert!(dry_run
+			.execute(&[], HashMap::new(), &monitor_match, &HashMap::new())
+			.await
+			.is_ok());
+		assert!(dry_run.errors.lock().unwrap().is_empty());
+	}
+}
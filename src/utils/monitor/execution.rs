@@ -3,7 +3,7 @@
 //! This module provides functionality to execute monitors against specific block numbers on blockchain networks.
 use crate::{
 	bootstrap::{get_contract_specs, has_active_monitors},
-	models::{BlockChainType, ScriptLanguage},
+	models::{BlockChainType, ContractSpec, Monitor, MonitorMatch, Network, ScriptLanguage},
 	repositories::{
 		MonitorRepositoryTrait, MonitorService, NetworkRepositoryTrait, NetworkService,
 		TriggerRepositoryTrait,
@@ -81,11 +81,18 @@ pub async fn execute_monitor<
 	config: MonitorExecutionConfig<M, N, TR, CP, FS>,
 ) -> ExecutionResult<String> {
 	tracing::debug!("Loading monitor configuration");
+	// References are validated against the loaded networks and triggers
+	let network_service = config.network_service.lock().await.clone();
+	let trigger_service = config.trigger_execution_service.trigger_service().clone();
 	let monitor = config
 		.monitor_service
 		.lock()
 		.await
-		.load_from_path(Some(Path::new(&config.path)), None, None)
+		.load_from_path(
+			Some(Path::new(&config.path)),
+			Some(network_service),
+			Some(trigger_service),
+		)
 		.await
 		.map_err(|e| MonitorExecutionError::execution_error(e.to_string(), None, None))?;
 
@@ -131,6 +138,9 @@ pub async fn execute_monitor<
 			network_slug = %network.slug,
 			"Processing network"
 		);
+		let monitor = monitor.for_network(&network.slug);
+		// The block is fetched with the RPC endpoints the monitor pins, if any
+		let network = monitor.rpc_network(&network).unwrap_or(network);
 
 		let contract_specs = get_contract_specs(
 			&config.client_pool,
@@ -138,267 +148,266 @@ pub async fn execute_monitor<
 		)
 		.await;
 
-		let matches = match network.network_type {
-			BlockChainType::EVM => {
-				let client = config
-					.client_pool
-					.get_evm_client(&network)
-					.await
-					.map_err(|e| {
-						MonitorExecutionError::execution_error(
-							format!("Failed to get EVM client: {}", e),
-							None,
-							None,
-						)
-					})?;
+		let matches = filter_network_block(
+			&*config.client_pool,
+			&*config.filter_service,
+			&network,
+			config.block_number,
+			std::slice::from_ref(&monitor),
+			&contract_specs,
+		)
+		.await?;
 
-				let block_number = match config.block_number {
-					Some(block_number) => {
-						tracing::debug!(block = %block_number, "Using specified block number");
-						block_number
-					}
-					None => {
-						let latest = client.get_latest_block_number().await.map_err(|e| {
-							MonitorExecutionError::execution_error(e.to_string(), None, None)
-						})?;
-						tracing::debug!(block = %latest, "Using latest block number");
-						latest
-					}
-				};
+		tracing::debug!(matches_count = matches.len(), "Found matches for network");
+		all_matches.extend(matches);
+	}
 
-				tracing::debug!(block = %block_number, "Fetching block");
-				let blocks = client.get_blocks(block_number, None).await.map_err(|e| {
-					MonitorExecutionError::execution_error(
-						format!("Failed to get block {}: {}", block_number, e),
-						None,
-						None,
-					)
-				})?;
+	// Send notifications for each match
+	for match_result in all_matches.clone() {
+		let result = handle_match(
+			match_result,
+			&*config.trigger_execution_service,
+			&config.active_monitors_trigger_scripts,
+		)
+		.await;
+		match result {
+			Ok(_result) => info!("Successfully sent notifications for match"),
+			Err(e) => {
+				tracing::error!("Error sending notifications: {}", e);
+				continue;
+			}
+		};
+	}
 
-				let block = blocks.first().ok_or_else(|| {
-					MonitorExecutionError::not_found(
-						format!("Block {} not found", block_number),
-						None,
-						None,
-					)
-				})?;
+	tracing::debug!(total_matches = all_matches.len(), "Serializing results");
+	let json_matches = serde_json::to_string(&all_matches).map_err(|e| {
+		MonitorExecutionError::execution_error(
+			format!("Failed to serialize matches: {}", e),
+			None,
+			None,
+		)
+	})?;
 
-				tracing::debug!(block = %block_number, "Filtering block");
-				config
-					.filter_service
-					.filter_block(
-						&*client,
-						&network,
-						block,
-						std::slice::from_ref(&monitor),
-						Some(&contract_specs),
-					)
-					.await
-					.map_err(|e| {
-						MonitorExecutionError::execution_error(
-							format!("Failed to filter block: {}", e),
-							None,
-							None,
-						)
-					})?
-			}
-			BlockChainType::Stellar => {
-				let client = config
-					.client_pool
-					.get_stellar_client(&network)
-					.await
-					.map_err(|e| {
-						MonitorExecutionError::execution_error(
-							format!("Failed to get Stellar client: {}", e),
-							None,
-							None,
-						)
-					})?;
+	tracing::debug!("Monitor execution completed successfully");
+	Ok(json_matches)
+}
 
-				// If block number is not provided, get the latest block number
-				let block_number = match config.block_number {
-					Some(block_number) => block_number,
-					None => client.get_latest_block_number().await.map_err(|e| {
+/// Fetches a block of a network and filters it with the given monitors
+///
+/// Notifications are not sent for the matches.
+///
+/// # Arguments
+///
+/// * `client_pool` - The client pool to use
+/// * `filter_service` - The filter service to use
+/// * `network` - The network to fetch the block from
+/// * `block_number` - The block to filter, the latest block if `None`
+/// * `monitors` - The monitors to filter the block with, already resolved for the network
+/// * `contract_specs` - The contract specs of the monitors
+///
+/// # Returns
+///
+/// * `ExecutionResult<Vec<MonitorMatch>>` - The matches found in the block
+pub async fn filter_network_block<
+	CP: ClientPoolTrait + Send + Sync + 'static,
+	FS: FilterServiceTrait + Send + Sync + 'static,
+>(
+	client_pool: &CP,
+	filter_service: &FS,
+	network: &Network,
+	block_number: Option<u64>,
+	monitors: &[Monitor],
+	contract_specs: &[(String, ContractSpec)],
+) -> ExecutionResult<Vec<MonitorMatch>> {
+	let matches = match network.network_type {
+		BlockChainType::EVM => {
+			let client = client_pool.get_evm_client(network).await.map_err(|e| {
+				MonitorExecutionError::execution_error(
+					format!("Failed to get EVM client: {}", e),
+					None,
+					None,
+				)
+			})?;
+
+			let block_number = match block_number {
+				Some(block_number) => {
+					tracing::debug!(block = %block_number, "Using specified block number");
+					block_number
+				}
+				None => {
+					let latest = client.get_latest_block_number().await.map_err(|e| {
 						MonitorExecutionError::execution_error(e.to_string(), None, None)
-					})?,
-				};
+					})?;
+					tracing::debug!(block = %latest, "Using latest block number");
+					latest
+				}
+			};
+
+			tracing::debug!(block = %block_number, "Fetching block");
+			let blocks = client.get_blocks(block_number, None).await.map_err(|e| {
+				MonitorExecutionError::execution_error(
+					format!("Failed to get block {}: {}", block_number, e),
+					None,
+					None,
+				)
+			})?;
 
-				let blocks = client.get_blocks(block_number, None).await.map_err(|e| {
+			let block = blocks.first().ok_or_else(|| {
+				MonitorExecutionError::not_found(
+					format!("Block {} not found", block_number),
+					None,
+					None,
+				)
+			})?;
+
+			tracing::debug!(block = %block_number, "Filtering block");
+			filter_service
+				.filter_block(&*client, network, block, monitors, Some(contract_specs))
+				.await
+				.map_err(|e| {
 					MonitorExecutionError::execution_error(
-						format!("Failed to get block {}: {}", block_number, e),
+						format!("Failed to filter block: {}", e),
 						None,
 						None,
 					)
-				})?;
+				})?
+		}
+		BlockChainType::Stellar => {
+			let client = client_pool.get_stellar_client(network).await.map_err(|e| {
+				MonitorExecutionError::execution_error(
+					format!("Failed to get Stellar client: {}", e),
+					None,
+					None,
+				)
+			})?;
 
-				let block = blocks.first().ok_or_else(|| {
-					MonitorExecutionError::not_found(
-						format!("Block {} not found", block_number),
-						None,
-						None,
-					)
-				})?;
+			// If block number is not provided, get the latest block number
+			let block_number = match block_number {
+				Some(block_number) => block_number,
+				None => client.get_latest_block_number().await.map_err(|e| {
+					MonitorExecutionError::execution_error(e.to_string(), None, None)
+				})?,
+			};
 
-				config
-					.filter_service
-					.filter_block(
-						&*client,
-						&network,
-						block,
-						std::slice::from_ref(&monitor),
-						Some(&contract_specs),
-					)
-					.await
-					.map_err(|e| {
-						MonitorExecutionError::execution_error(
-							format!("Failed to filter block: {}", e),
-							None,
-							None,
-						)
-					})?
-			}
-			BlockChainType::Midnight => {
-				let client = config
-					.client_pool
-					.get_midnight_client(&network)
-					.await
-					.map_err(|e| {
-						MonitorExecutionError::execution_error(
-							format!("Failed to get Midnight client: {}", e),
-							None,
-							None,
-						)
-					})?;
+			let blocks = client.get_blocks(block_number, None).await.map_err(|e| {
+				MonitorExecutionError::execution_error(
+					format!("Failed to get block {}: {}", block_number, e),
+					None,
+					None,
+				)
+			})?;
 
-				// If block number is not provided, get the latest block number
-				let block_number = match config.block_number {
-					Some(block_number) => block_number,
-					None => client.get_latest_block_number().await.map_err(|e| {
-						MonitorExecutionError::execution_error(e.to_string(), None, None)
-					})?,
-				};
+			let block = blocks.first().ok_or_else(|| {
+				MonitorExecutionError::not_found(
+					format!("Block {} not found", block_number),
+					None,
+					None,
+				)
+			})?;
 
-				let blocks = client.get_blocks(block_number, None).await.map_err(|e| {
+			filter_service
+				.filter_block(&*client, network, block, monitors, Some(contract_specs))
+				.await
+				.map_err(|e| {
 					MonitorExecutionError::execution_error(
-						format!("Failed to get block {}: {}", block_number, e),
+						format!("Failed to filter block: {}", e),
 						None,
 						None,
 					)
-				})?;
-
-				let block = blocks.first().ok_or_else(|| {
-					MonitorExecutionError::not_found(
-						format!("Block {} not found", block_number),
+				})?
+		}
+		BlockChainType::Midnight => {
+			let client = client_pool
+				.get_midnight_client(network)
+				.await
+				.map_err(|e| {
+					MonitorExecutionError::execution_error(
+						format!("Failed to get Midnight client: {}", e),
 						None,
 						None,
 					)
 				})?;
 
-				config
-					.filter_service
-					.filter_block(
-						&*client,
-						&network,
-						block,
-						std::slice::from_ref(&monitor),
-						Some(&contract_specs),
-					)
-					.await
-					.map_err(|e| {
-						MonitorExecutionError::execution_error(
-							format!("Failed to filter block: {}", e),
-							None,
-							None,
-						)
-					})?
-			}
-			BlockChainType::Solana => {
-				let client = config
-					.client_pool
-					.get_solana_client(&network)
-					.await
-					.map_err(|e| {
-						MonitorExecutionError::execution_error(
-							format!("Failed to get Solana client: {}", e),
-							None,
-							None,
-						)
-					})?;
+			// If block number is not provided, get the latest block number
+			let block_number = match block_number {
+				Some(block_number) => block_number,
+				None => client.get_latest_block_number().await.map_err(|e| {
+					MonitorExecutionError::execution_error(e.to_string(), None, None)
+				})?,
+			};
 
-				// If block number is not provided, get the latest slot number
-				let slot_number = match config.block_number {
-					Some(slot_number) => slot_number,
-					None => client.get_latest_block_number().await.map_err(|e| {
-						MonitorExecutionError::execution_error(e.to_string(), None, None)
-					})?,
-				};
+			let blocks = client.get_blocks(block_number, None).await.map_err(|e| {
+				MonitorExecutionError::execution_error(
+					format!("Failed to get block {}: {}", block_number, e),
+					None,
+					None,
+				)
+			})?;
 
-				let blocks = client.get_blocks(slot_number, None).await.map_err(|e| {
-					MonitorExecutionError::execution_error(
-						format!("Failed to get slot {}: {}", slot_number, e),
-						None,
-						None,
-					)
-				})?;
+			let block = blocks.first().ok_or_else(|| {
+				MonitorExecutionError::not_found(
+					format!("Block {} not found", block_number),
+					None,
+					None,
+				)
+			})?;
 
-				let block = blocks.first().ok_or_else(|| {
-					MonitorExecutionError::not_found(
-						format!("Slot {} not found", slot_number),
+			filter_service
+				.filter_block(&*client, network, block, monitors, Some(contract_specs))
+				.await
+				.map_err(|e| {
+					MonitorExecutionError::execution_error(
+						format!("Failed to filter block: {}", e),
 						None,
 						None,
 					)
-				})?;
+				})?
+		}
+		BlockChainType::Solana => {
+			let client = client_pool.get_solana_client(network).await.map_err(|e| {
+				MonitorExecutionError::execution_error(
+					format!("Failed to get Solana client: {}", e),
+					None,
+					None,
+				)
+			})?;
 
-				config
-					.filter_service
-					.filter_block(
-						&*client,
-						&network,
-						block,
-						std::slice::from_ref(&monitor),
-						Some(&contract_specs),
-					)
-					.await
-					.map_err(|e| {
-						MonitorExecutionError::execution_error(
-							format!("Failed to filter block: {}", e),
-							None,
-							None,
-						)
-					})?
-			}
-		};
+			// If block number is not provided, get the latest slot number
+			let slot_number = match block_number {
+				Some(slot_number) => slot_number,
+				None => client.get_latest_block_number().await.map_err(|e| {
+					MonitorExecutionError::execution_error(e.to_string(), None, None)
+				})?,
+			};
 
-		tracing::debug!(matches_count = matches.len(), "Found matches for network");
-		all_matches.extend(matches);
-	}
+			let blocks = client.get_blocks(slot_number, None).await.map_err(|e| {
+				MonitorExecutionError::execution_error(
+					format!("Failed to get slot {}: {}", slot_number, e),
+					None,
+					None,
+				)
+			})?;
 
-	// Send notifications for each match
-	for match_result in all_matches.clone() {
-		let result = handle_match(
-			match_result,
-			&*config.trigger_execution_service,
-			&config.active_monitors_trigger_scripts,
-		)
-		.await;
-		match result {
-			Ok(_result) => info!("Successfully sent notifications for match"),
-			Err(e) => {
-				tracing::error!("Error sending notifications: {}", e);
-				continue;
-			}
-		};
-	}
+			let block = blocks.first().ok_or_else(|| {
+				MonitorExecutionError::not_found(
+					format!("Slot {} not found", slot_number),
+					None,
+					None,
+				)
+			})?;
 
-	tracing::debug!(total_matches = all_matches.len(), "Serializing results");
-	let json_matches = serde_json::to_string(&all_matches).map_err(|e| {
-		MonitorExecutionError::execution_error(
-			format!("Failed to serialize matches: {}", e),
-			None,
-			None,
-		)
-	})?;
+			filter_service
+				.filter_block(&*client, network, block, monitors, Some(contract_specs))
+				.await
+				.map_err(|e| {
+					MonitorExecutionError::execution_error(
+						format!("Failed to filter block: {}", e),
+						None,
+						None,
+					)
+				})?
+		}
+	};
 
-	tracing::debug!("Monitor execution completed successfully");
-	Ok(json_matches)
+	Ok(matches)
 }
@@ -0,0 +1,279 @@
+//! Expected match assertions
+//!
+//! Verifies monitor configurations against a regression suite: a JSON file listing, for
+//! blocks of a network, the monitors expected to match them. Every evaluated monitor that is
+//! not listed for a block is expected NOT to match it, so the suite also covers negative
+//! cases. Blocks are only filtered, no notification is sent.
+//!
+//! ```json
+//! [
+//!   { "network": "ethereum_mainnet", "block": 19000000, "monitors": ["Large Transfer"] },
+//!   { "network": "ethereum_mainnet", "block": 19000001, "monitors": [] }
+//! ]
+//! ```
+use crate::{
+	bootstrap::get_contract_specs,
+	models::{Monitor, MonitorMatch, Network},
+	services::{blockchain::ClientPoolTrait, filter::FilterServiceTrait},
+	utils::monitor::{
+		execution::{filter_network_block, ExecutionResult},
+		MonitorExecutionError,
+	},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+	collections::{BTreeSet, HashMap},
+	fmt::Write,
+	path::Path,
+	sync::Arc,
+};
+
+/// Monitors expected to match a block
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ExpectedMatch {
+	/// Slug of the network of the block
+	pub network: String,
+	/// Block (or slot) number
+	pub block: u64,
+	/// Names of the monitors expected to match the block
+	#[serde(default)]
+	pub monitors: Vec<String>,
+}
+
+/// Outcome of an expected match assertion
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ExpectationResult {
+	/// Slug of the network of the block
+	pub network: String,
+	/// Block (or slot) number
+	pub block: u64,
+	/// Monitors expected to match the block that did not
+	pub missing: Vec<String>,
+	/// Monitors that matched the block without being expected to
+	pub unexpected: Vec<String>,
+}
+
+impl ExpectationResult {
+	/// Returns whether the block matched exactly the expected monitors
+	pub fn is_success(&self) -> bool {
+		self.missing.is_empty() && self.unexpected.is_empty()
+	}
+}
+
+/// Loads expected match assertions from a JSON file
+///
+/// # Arguments
+///
+/// * `path` - Path of the JSON file, an array of [`ExpectedMatch`]
+///
+/// # Returns
+///
+/// * `ExecutionResult<Vec<ExpectedMatch>>` - The assertions
+pub async fn load_expectations(path: &Path) -> ExecutionResult<Vec<ExpectedMatch>> {
+	let content = tokio::fs::read_to_string(path).await.map_err(|e| {
+		MonitorExecutionError::not_found(
+			format!("Failed to read expectations file {}", path.display()),
+			Some(e.into()),
+			None,
+		)
+	})?;
+	serde_json::from_str(&content).map_err(|e| {
+		MonitorExecutionError::execution_error(
+			format!("Failed to parse expectations file {}", path.display()),
+			Some(e.into()),
+			None,
+		)
+	})
+}
+
+/// Compares the monitors that matched a block with the expected ones
+///
+/// # Arguments
+///
+/// * `expected` - The assertion of the block
+/// * `matched` - Names of the monitors that matched the block
+///
+/// # Returns
+///
+/// * `ExpectationResult` - The missing and unexpected monitors, sorted by name
+pub fn compare_matches(expected: &ExpectedMatch, matched: &[String]) -> ExpectationResult {
+	let expected_monitors: BTreeSet<&String> = expected.monitors.iter().collect();
+	let matched_monitors: BTreeSet<&String> = matched.iter().collect();
+
+	ExpectationResult {
+		network: expected.network.clone(),
+		block: expected.block,
+		missing: expected_monitors
+			.difference(&matched_monitors)
+			.map(|name| name.to_string())
+			.collect(),
+		unexpected: matched_monitors
+			.difference(&expected_monitors)
+			.map(|name| name.to_string())
+			.collect(),
+	}
+}
+
+/// Formats the failed assertions as a diff
+///
+/// Monitors expected to match but that did not are prefixed with `-`, monitors that matched
+/// without being expected to with `+`.
+///
+/// # Arguments
+///
+/// * `results` - Outcomes of the assertions
+///
+/// # Returns
+///
+/// * `String` - The diff, empty if every assertion succeeded
+pub fn format_diff(results: &[ExpectationResult]) -> String {
+	let mut diff = String::new();
+	for result in results.iter().filter(|result| !result.is_success()) {
+		let _ = writeln!(diff, "{} block {}:", result.network, result.block);
+		for name in &result.missing {
+			let _ = writeln!(diff, "  - {} (expected to match)", name);
+		}
+		for name in &result.unexpected {
+			let _ = writeln!(diff, "  + {} (not expected to match)", name);
+		}
+	}
+	diff
+}
+
+/// Filters the blocks of the assertions and compares their matches with the expected ones
+///
+/// # Arguments
+///
+/// * `expectations` - The assertions to verify
+/// * `monitors` - The monitors to evaluate, only the ones configured on a network are
+///   evaluated against its blocks
+/// * `networks` - The configured networks, keyed by slug
+/// * `client_pool` - The client pool to use
+/// * `filter_service` - The filter service to use
+///
+/// # Returns
+///
+/// * `ExecutionResult<Vec<ExpectationResult>>` - The outcome of each assertion, in order
+pub async fn verify_expectations<
+	CP: ClientPoolTrait + Send + Sync + 'static,
+	FS: FilterServiceTrait + Send + Sync + 'static,
+>(
+	expectations: &[ExpectedMatch],
+	monitors: &[Monitor],
+	networks: &HashMap<String, Network>,
+	client_pool: &Arc<CP>,
+	filter_service: &FS,
+) -> ExecutionResult<Vec<ExpectationResult>> {
+	let mut results = Vec::with_capacity(expectations.len());
+
+	for expected in expectations {
+		let network = networks.get(&expected.network).ok_or_else(|| {
+			MonitorExecutionError::not_found(
+				format!("Network '{}' not found", expected.network),
+				None,
+				None,
+			)
+		})?;
+		let network_monitors: Vec<Monitor> = monitors
+			.iter()
+			.filter(|monitor| monitor.networks.contains(&network.slug))
+			.map(|monitor| monitor.for_network(&network.slug))
+			.collect();
+
+		let contract_specs =
+			get_contract_specs(client_pool, &[(network.clone(), network_monitors.clone())]).await;
+		let matches = filter_network_block(
+			&**client_pool,
+			filter_service,
+			network,
+			Some(expected.block),
+			&network_monitors,
+			&contract_specs,
+		)
+		.await?;
+
+		let matched: Vec<String> = matches
+			.iter()
+			.map(|monitor_match| monitor_match.monitor().name.clone())
+			.collect();
+		results.push(compare_matches(expected, &matched));
+	}
+
+	Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn expected(block: u64, monitors: &[&str]) -> ExpectedMatch {
+		ExpectedMatch {
+			network: "ethereum_mainnet".to_string(),
+			block,
+			monitors: monitors.iter().map(|name| name.to_string()).collect(),
+		}
+	}
+
+	#[test]
+	fn test_compare_matches() {
+		let result = compare_matches(
+			&expected(1, &["Large Transfer", "Ownership Change"]),
+			&["Ownership Change".to_string(), "Whale Alert".to_string()],
+		);
+
+		assert!(!result.is_success());
+		assert_eq!(result.missing, vec!["Large Transfer".to_string()]);
+		assert_eq!(result.unexpected, vec!["Whale Alert".to_string()]);
+	}
+
+	#[test]
+	fn test_compare_matches_without_expected_monitors() {
+		assert!(compare_matches(&expected(1, &[]), &[]).is_success());
+		assert!(!compare_matches(&expected(1, &[]), &["Whale Alert".to_string()]).is_success());
+	}
+
+	#[test]
+	fn test_format_diff() {
+		let results = vec![
+			compare_matches(
+				&expected(1, &["Large Transfer"]),
+				&["Large Transfer".to_string()],
+			),
+			compare_matches(
+				&expected(2, &["Large Transfer"]),
+				&["Whale Alert".to_string()],
+			),
+		];
+
+		assert_eq!(
+			format_diff(&results),
+			"ethereum_mainnet block 2:\n  - Large Transfer (expected to match)\n  + Whale Alert (not expected to match)\n"
+		);
+		assert!(format_diff(&results[..1]).is_empty());
+	}
+
+	#[tokio::test]
+	async fn test_load_expectations() {
+		let temp_dir = tempfile::tempdir().unwrap();
+		let path = temp_dir.path().join("expectations.json");
+		std::fs::write(
+			&path,
+			r#"[{"network": "ethereum_mainnet", "block": 1, "monitors": ["Large Transfer"]},
+			{"network": "ethereum_mainnet", "block": 2}]"#,
+		)
+		.unwrap();
+
+		let expectations = load_expectations(&path).await.unwrap();
+		assert_eq!(
+			expectations,
+			vec![expected(1, &["Large Transfer"]), expected(2, &[])]
+		);
+
+		std::fs::write(&path, r#"[{"network": "ethereum_mainnet", "blocks": 1}]"#).unwrap();
+		assert!(load_expectations(&path).await.is_err());
+		assert!(load_expectations(&temp_dir.path().join("missing.json"))
+			.await
+			.is_err());
+	}
+}
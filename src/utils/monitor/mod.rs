@@ -4,7 +4,15 @@
 //!
 //! - execution: Monitor execution logic against a specific block
 //! - error: Error types for monitor execution
+//! - expectations: Expected match assertions verifying monitors against blocks
+//! - canary: Periodic end-to-end checks of monitors against blocks they are known to match
+//! - activation: Activation of monitors once the contract they depend on is deployed
+//! - abi_drift: Detection of deployed contracts no longer matching their configured specs
 
 mod error;
 pub use error::MonitorExecutionError;
+pub mod abi_drift;
+pub mod activation;
+pub mod canary;
 pub mod execution;
+pub mod expectations;
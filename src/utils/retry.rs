@@ -0,0 +1,460 @@
+//! Retries with exponential backoff, jitter and budget.
+//!
+//! Every retried operation of the service goes through a [`RetryPolicy`] built from a
+//! [`RetryConfig`], whether it is an HTTP request (RPC endpoints and webhook notifiers, through
+//! [`create_retryable_http_client`](crate::utils::create_retryable_http_client)), an SMTP
+//! delivery or a command of a storage backend. Only errors classified as retryable
+//! ([`ClassifiedError`]) are retried.
+//!
+//! The retries of each component (e.g. `rpc`, `webhook`) are limited by a budget of
+//! `RETRY_BUDGET_PER_MINUTE` retries, so that an outage of a dependency is not amplified by the
+//! retries of every caller. The budgets are created from the runtime settings and kept in the
+//! [`RetryBudgets`] of the service whose clients share them, e.g. the client pool for the RPC
+//! requests, whose budgets are kept per network so that the retries of a failing network do not
+//! starve the others. The retries are counted by the `retries_total` metric, and the operations
+//! given up by the `retries_exhausted_total` metric. [`count_retries`] also counts the retries
+//! made while running a future, e.g. for the delivery reports of a notification.
+
+use rand::Rng;
+use reqwest_retry::RetryDecision;
+use std::{
+	cell::Cell,
+	collections::HashMap,
+	future::Future,
+	sync::{Arc, Mutex},
+	time::{Duration, Instant, SystemTime},
+};
+
+use crate::utils::{
+	logging::error::ClassifiedError,
+	metrics::{record_retry, record_retry_exhausted},
+	JitterSetting, RetryConfig,
+};
+
+tokio::task_local! {
+	/// Retries made by the future run by [`count_retries`]
+	static COUNTED_RETRIES: Cell<u32>;
+}
+
+/// Runs a future and counts the retries of the operations it makes
+///
+/// # Arguments
+/// * `future` - The future, whose retried operations run in the same task
+///
+/// # Returns
+/// The output of the future and its number of retries
+pub async fn count_retries<F: Future>(future: F) -> (F::Output, u32) {
+	COUNTED_RETRIES
+		.scope(Cell::new(0), async move {
+			let output = future.await;
+			(output, COUNTED_RETRIES.with(Cell::get))
+		})
+		.await
+}
+
+/// Budget of the retries of a component, refilled continuously
+#[derive(Debug)]
+pub struct RetryBudget {
+	/// Maximum number of retries per minute, `0` for no limit
+	per_minute: u32,
+	state: Mutex<(f64, Instant)>,
+}
+
+impl RetryBudget {
+	/// Creates a new budget
+	///
+	/// # Arguments
+	/// * `per_minute` - Maximum number of retries per minute, `0` for no limit
+	pub fn new(per_minute: u32) -> Self {
+		Self {
+			per_minute,
+			state: Mutex::new((f64::from(per_minute), Instant::now())),
+		}
+	}
+
+	/// Withdraws a retry from the budget
+	///
+	/// # Returns
+	/// False if the budget is exhausted
+	pub fn try_withdraw(&self) -> bool {
+		if self.per_minute == 0 {
+			return true;
+		}
+		let mut state = match self.state.lock() {
+			Ok(guard) => guard,
+			Err(poisoned) => poisoned.into_inner(),
+		};
+		let (available, refilled_at) = &mut *state;
+		let capacity = f64::from(self.per_minute);
+		*available =
+			(*available + refilled_at.elapsed().as_secs_f64() * capacity / 60.0).min(capacity);
+		*refilled_at = Instant::now();
+		if *available < 1.0 {
+			return false;
+		}
+		*available -= 1.0;
+		true
+	}
+}
+
+/// Retry budgets of the components of a service, by network
+///
+/// The default budgets have no limit.
+#[derive(Debug, Default)]
+pub struct RetryBudgets {
+	/// Maximum number of retries per minute of each budget, `0` for no limit
+	per_minute: u32,
+	budgets: Mutex<HashMap<(String, Option<String>), Arc<RetryBudget>>>,
+}
+
+impl RetryBudgets {
+	/// Creates new budgets
+	///
+	/// # Arguments
+	/// * `per_minute` - Maximum number of retries per minute of each budget, `0` for no limit
+	pub fn new(per_minute: u32) -> Self {
+		Self {
+			per_minute,
+			budgets: Mutex::new(HashMap::new()),
+		}
+	}
+
+	/// Returns the retry budget of a component, or of a component on a network
+	///
+	/// # Arguments
+	/// * `component` - Name of the component (e.g. `rpc`, `webhook`)
+	/// * `network` - Slug of the network, for the budgets kept per network
+	pub fn get(&self, component: &str, network: Option<&str>) -> Arc<RetryBudget> {
+		let mut budgets = match self.budgets.lock() {
+			Ok(guard) => guard,
+			Err(poisoned) => poisoned.into_inner(),
+		};
+		Arc::clone(
+			budgets
+				.entry((component.to_string(), network.map(str::to_string)))
+				.or_insert_with(|| Arc::new(RetryBudget::new(self.per_minute))),
+		)
+	}
+}
+
+/// Retry policy of a component
+#[derive(Clone, Debug)]
+pub struct RetryPolicy {
+	component: String,
+	config: RetryConfig,
+	budget: Arc<RetryBudget>,
+}
+
+impl RetryPolicy {
+	/// Creates the policy of a component, whose retries are not limited by a budget
+	///
+	/// # Arguments
+	/// * `component` - Name of the component, used as metric label (e.g. `window_store`)
+	/// * `config` - Retry configuration
+	pub fn new(component: &str, config: &RetryConfig) -> Self {
+		Self::with_budget(component, config, Arc::new(RetryBudget::new(0)))
+	}
+
+	/// Creates the policy of a component, sharing its budget in the budgets of a service
+	///
+	/// # Arguments
+	/// * `component` - Name of the component, used as metric label (e.g. `webhook`)
+	/// * `config` - Retry configuration
+	/// * `budgets` - The retry budgets of the service
+	pub fn shared(component: &str, config: &RetryConfig, budgets: &RetryBudgets) -> Self {
+		Self::with_budget(component, config, budgets.get(component, None))
+	}
+
+	/// Creates the policy of a component on a network, sharing the budget of the component on
+	/// that network in the budgets of a service
+	///
+	/// # Arguments
+	/// * `component` - Name of the component, used as metric label (e.g. `rpc`)
+	/// * `network` - Slug of the network
+	/// * `config` - Retry configuration
+	/// * `budgets` - The retry budgets of the service
+	pub fn for_network(
+		component: &str,
+		network: &str,
+		config: &RetryConfig,
+		budgets: &RetryBudgets,
+	) -> Self {
+		Self::with_budget(component, config, budgets.get(component, Some(network)))
+	}
+
+	/// Creates the policy of a component with its own budget
+	///
+	/// # Arguments
+	/// * `component` - Name of the component, used as metric label
+	/// * `config` - Retry configuration
+	/// * `budget` - Budget of the retries
+	pub fn with_budget(component: &str, config: &RetryConfig, budget: Arc<RetryBudget>) -> Self {
+		Self {
+			component: component.to_string(),
+			config: config.clone(),
+			budget,
+		}
+	}
+
+	/// Returns the delay before a retry
+	///
+	/// The delay grows exponentially from `initial_backoff` by `base_for_backoff`, up to
+	/// `max_backoff`. With full jitter, it is drawn uniformly between `initial_backoff` and
+	/// this delay.
+	///
+	/// # Arguments
+	/// * `n_past_retries` - Number of retries already made
+	pub fn backoff(&self, n_past_retries: u32) -> Duration {
+		let factor = f64::from(self.config.base_for_backoff.max(1)).powi(n_past_retries as i32);
+		let max_backoff = self.config.max_backoff.max(self.config.initial_backoff);
+		let delay = self
+			.config
+			.initial_backoff
+			.mul_f64(factor.min(u32::MAX as f64))
+			.min(max_backoff);
+		match self.config.jitter {
+			JitterSetting::None => delay,
+			JitterSetting::Full => {
+				let min = self.config.initial_backoff.min(delay);
+				min + (delay - min).mul_f64(random_fraction())
+			}
+		}
+	}
+
+	/// Decides whether to retry after a retryable failure, and records the decision
+	///
+	/// # Arguments
+	/// * `n_past_retries` - Number of retries already made
+	///
+	/// # Returns
+	/// The delay before the retry, or `None` to give up
+	pub fn next_retry(&self, n_past_retries: u32) -> Option<Duration> {
+		if n_past_retries >= self.config.max_retries {
+			record_retry_exhausted(&self.component, "max_retries");
+			return None;
+		}
+		if !self.budget.try_withdraw() {
+			tracing::warn!(
+				component = %self.component,
+				"Retry budget exhausted, not retrying"
+			);
+			record_retry_exhausted(&self.component, "budget");
+			return None;
+		}
+		record_retry(&self.component);
+		let _ = COUNTED_RETRIES.try_with(|retries| retries.set(retries.get() + 1));
+		Some(self.backoff(n_past_retries))
+	}
+
+	/// Runs an operation, retrying it while it fails with a retryable error
+	///
+	/// # Arguments
+	/// * `operation` - The operation, called once per attempt
+	///
+	/// # Returns
+	/// The result of the first successful attempt, or the error of the last attempt
+	pub async fn retry<T, E, F, Fut>(&self, mut operation: F) -> Result<T, E>
+	where
+		E: ClassifiedError,
+		F: FnMut() -> Fut,
+		Fut: Future<Output = Result<T, E>>,
+	{
+		let mut n_past_retries = 0;
+		loop {
+			let error = match operation().await {
+				Ok(value) => return Ok(value),
+				Err(error) => error,
+			};
+			if !error.is_retryable() {
+				return Err(error);
+			}
+			match self.next_retry(n_past_retries) {
+				Some(delay) => {
+					tokio::time::sleep(delay).await;
+					n_past_retries += 1;
+				}
+				None => return Err(error),
+			}
+		}
+	}
+}
+
+impl reqwest_retry::RetryPolicy for RetryPolicy {
+	fn should_retry(&self, _request_start_time: SystemTime, n_past_retries: u32) -> RetryDecision {
+		match self.next_retry(n_past_retries) {
+			Some(delay) => RetryDecision::Retry {
+				execute_after: SystemTime::now() + delay,
+			},
+			None => RetryDecision::DoNotRetry,
+		}
+	}
+}
+
+/// Returns a random number in `[0, 1)`
+fn random_fraction() -> f64 {
+	rand::rng().random::<f64>()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::utils::logging::error::ErrorClass;
+	use std::sync::atomic::{AtomicU32, Ordering};
+
+	#[derive(Debug)]
+	struct TestError(ErrorClass);
+
+	impl ClassifiedError for TestError {
+		fn classification(&self) -> ErrorClass {
+			self.0
+		}
+	}
+
+	fn config(max_retries: u32, jitter: JitterSetting) -> RetryConfig {
+		RetryConfig {
+			max_retries,
+			base_for_backoff: 2,
+			initial_backoff: Duration::from_millis(1),
+			max_backoff: Duration::from_millis(4),
+			jitter,
+		}
+	}
+
+	#[test]
+	fn test_backoff() {
+		let policy = RetryPolicy::with_budget(
+			"test",
+			&config(5, JitterSetting::None),
+			Arc::new(RetryBudget::new(0)),
+		);
+		let delays: Vec<_> = (0..4).map(|retry| policy.backoff(retry)).collect();
+		assert_eq!(
+			delays,
+			[1, 2, 4, 4].map(Duration::from_millis).to_vec(),
+			"exponential, capped at max_backoff"
+		);
+
+		let jittered = RetryPolicy::with_budget(
+			"test",
+			&config(5, JitterSetting::Full),
+			Arc::new(RetryBudget::new(0)),
+		);
+		for retry in 0..4 {
+			let delay = jittered.backoff(retry);
+			assert!(delay >= Duration::from_millis(1) && delay <= Duration::from_millis(4));
+		}
+	}
+
+	#[tokio::test]
+	async fn test_retry_classified_errors() {
+		let policy = RetryPolicy::with_budget(
+			"test",
+			&config(3, JitterSetting::Full),
+			Arc::new(RetryBudget::new(0)),
+		);
+
+		let attempts = AtomicU32::new(0);
+		let result: Result<(), _> = policy
+			.retry(|| async {
+				attempts.fetch_add(1, Ordering::SeqCst);
+				Err(TestError(ErrorClass::Transient))
+			})
+			.await;
+		assert!(result.is_err());
+		assert_eq!(attempts.load(Ordering::SeqCst), 4, "1 attempt + 3 retries");
+
+		// Permanent errors are not retried
+		let attempts = AtomicU32::new(0);
+		let result: Result<(), _> = policy
+			.retry(|| async {
+				attempts.fetch_add(1, Ordering::SeqCst);
+				Err(TestError(ErrorClass::Permanent))
+			})
+			.await;
+		assert!(result.is_err());
+		assert_eq!(attempts.load(Ordering::SeqCst), 1);
+
+		let attempts = AtomicU32::new(0);
+		let result = policy
+			.retry(|| async {
+				match attempts.fetch_add(1, Ordering::SeqCst) {
+					0 => Err(TestError(ErrorClass::RateLimited)),
+					_ => Ok("sent"),
+				}
+			})
+			.await;
+		assert_eq!(result.unwrap(), "sent");
+	}
+
+	#[tokio::test]
+	async fn test_retry_budget() {
+		let budget = Arc::new(RetryBudget::new(2));
+		let policy = RetryPolicy::with_budget("test", &config(10, JitterSetting::None), budget);
+
+		let attempts = AtomicU32::new(0);
+		let result: Result<(), _> = policy
+			.retry(|| async {
+				attempts.fetch_add(1, Ordering::SeqCst);
+				Err(TestError(ErrorClass::Transient))
+			})
+			.await;
+		assert!(result.is_err());
+		assert_eq!(attempts.load(Ordering::SeqCst), 3, "budget of 2 retries");
+	}
+
+	#[test]
+	fn test_retry_budget_per_network() {
+		let budgets = RetryBudgets::default();
+		let network_budget = budgets.get("test_budgets", Some("ethereum_mainnet"));
+		assert!(Arc::ptr_eq(
+			&network_budget,
+			&budgets.get("test_budgets", Some("ethereum_mainnet"))
+		));
+		assert!(!Arc::ptr_eq(
+			&network_budget,
+			&budgets.get("test_budgets", Some("polygon_mainnet"))
+		));
+		assert!(!Arc::ptr_eq(
+			&network_budget,
+			&budgets.get("test_budgets", None)
+		));
+		// Services do not share their budgets
+		assert!(!Arc::ptr_eq(
+			&network_budget,
+			&RetryBudgets::default().get("test_budgets", Some("ethereum_mainnet"))
+		));
+
+		// Every budget gets the limit of the service
+		let budgets = RetryBudgets::new(1);
+		assert!(budgets.get("test_budgets", None).try_withdraw());
+		assert!(!budgets.get("test_budgets", None).try_withdraw());
+		assert!(budgets
+			.get("test_budgets", Some("ethereum_mainnet"))
+			.try_withdraw());
+	}
+
+	#[tokio::test]
+	async fn test_count_retries() {
+		let policy = RetryPolicy::with_budget(
+			"test",
+			&config(3, JitterSetting::None),
+			Arc::new(RetryBudget::new(0)),
+		);
+		let attempts = AtomicU32::new(0);
+		let (result, retries) = count_retries(policy.retry(|| async {
+			match attempts.fetch_add(1, Ordering::SeqCst) {
+				0 | 1 => Err(TestError(ErrorClass::Transient)),
+				_ => Ok(()),
+			}
+		}))
+		.await;
+		assert!(result.is_ok());
+		assert_eq!(retries, 2);
+
+		// Retries outside of a counted future are not recorded
+		let result: Result<(), _> = policy
+			.retry(|| async { Err(TestError(ErrorClass::Transient)) })
+			.await;
+		assert!(result.is_err());
+	}
+}
@@ -0,0 +1,398 @@
+//! Monitor state archives.
+//!
+//! Moving a deployment to a new host must not lose its progress nor re-alert on blocks it
+//! already processed. [`export_state`] bundles the state kept in the data directory into a
+//! single versioned JSON archive, and [`import_state`] restores it on the new host:
+//! - the last processed block of every network
+//! - the missed blocks awaiting recovery and the skip lists of poison blocks
+//! - the aggregation state: match statistics, window store snapshot, first-time
+//!   interactions and the nonces of monitored senders
+//!
+//! Every archive carries a SHA-256 checksum of its content. When `STATE_SIGNING_KEY` is set,
+//! archives are also signed with HMAC-SHA256, and only archives with a valid signature are
+//! imported.
+
+use anyhow::Context;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+	collections::BTreeMap,
+	path::{Component, Path},
+};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Format identifier of the archives
+pub const STATE_ARCHIVE_FORMAT: &str = "openzeppelin-monitor-state";
+
+/// Version of the archives written by this release
+pub const STATE_ARCHIVE_VERSION: u32 = 1;
+
+/// Suffixes of the per-network state files
+const NETWORK_STATE_SUFFIXES: &[&str] = &[
+	"_last_block.txt",
+	"_missed_blocks.json",
+	"_missed_blocks.txt",
+	"_skipped_blocks.json",
+];
+
+/// Names of the process-wide state files
+const STATE_FILES: &[&str] = &["match_stats.json", "window_store.json"];
+
+/// Directories whose files are all state
+const STATE_DIRS: &[&str] = &["interactions", "nonces"];
+
+/// Versioned archive of the state of a deployment
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StateArchive {
+	/// Format identifier, always [`STATE_ARCHIVE_FORMAT`]
+	pub format: String,
+	/// Version of the archive format
+	pub version: u32,
+	/// Unix timestamp (milliseconds) of the export
+	pub created_at: i64,
+	/// Base64 content of the state files, keyed by path relative to the data directory
+	pub files: BTreeMap<String, String>,
+	/// Hex SHA-256 digest of the content
+	pub checksum: String,
+	/// Hex HMAC-SHA256 of the content, if exported with a signing key
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub signature: Option<String>,
+}
+
+impl StateArchive {
+	/// Returns the bytes covered by the checksum and the signature
+	fn signed_content(&self) -> Vec<u8> {
+		let mut content = format!("{}\n{}\n{}\n", self.format, self.version, self.created_at);
+		for (path, data) in &self.files {
+			content.push_str(path);
+			content.push('\n');
+			content.push_str(data);
+			content.push('\n');
+		}
+		content.into_bytes()
+	}
+
+	fn compute_checksum(&self) -> String {
+		hex::encode(Sha256::digest(self.signed_content()))
+	}
+
+	fn mac(&self, signing_key: &str) -> anyhow::Result<HmacSha256> {
+		if signing_key.is_empty() {
+			anyhow::bail!("The state signing key cannot be empty");
+		}
+		let mut mac = HmacSha256::new_from_slice(signing_key.as_bytes())
+			.map_err(|e| anyhow::anyhow!("Invalid state signing key: {}", e))?;
+		mac.update(&self.signed_content());
+		Ok(mac)
+	}
+
+	/// Checks the format, version, checksum and signature of the archive
+	///
+	/// # Arguments
+	/// * `signing_key` - Key the archive must be signed with, if any
+	///
+	/// # Errors
+	/// Returns an error if the archive is not a supported state archive, was altered, or is
+	/// not signed with `signing_key`
+	pub fn verify(&self, signing_key: Option<&str>) -> anyhow::Result<()> {
+		if self.format != STATE_ARCHIVE_FORMAT {
+			anyhow::bail!("Not a monitor state archive (format '{}')", self.format);
+		}
+		if self.version == 0 || self.version > STATE_ARCHIVE_VERSION {
+			anyhow::bail!(
+				"Unsupported state archive version {} (supported: up to {})",
+				self.version,
+				STATE_ARCHIVE_VERSION
+			);
+		}
+		if self.checksum != self.compute_checksum() {
+			anyhow::bail!("State archive checksum mismatch, the archive was altered");
+		}
+		if let Some(signing_key) = signing_key {
+			let signature = self
+				.signature
+				.as_ref()
+				.context("State archive is not signed")?;
+			let signature = hex::decode(signature).context("Invalid state archive signature")?;
+			self.mac(signing_key)?
+				.verify_slice(&signature)
+				.map_err(|_| anyhow::anyhow!("Invalid state archive signature"))?;
+		}
+		Ok(())
+	}
+}
+
+/// Returns whether a file of the data directory is part of the state
+fn is_state_file(name: &str) -> bool {
+	STATE_FILES.contains(&name)
+		|| NETWORK_STATE_SUFFIXES
+			.iter()
+			.any(|suffix| name.len() > suffix.len() && name.ends_with(suffix))
+}
+
+/// Bundles the state of the data directory into an archive
+///
+/// # Arguments
+/// * `data_dir` - The data directory
+/// * `signing_key` - Key to sign the archive with, if any
+///
+/// # Errors
+/// Returns an error if a state file cannot be read
+pub async fn export_state(
+	data_dir: &Path,
+	signing_key: Option<&str>,
+) -> anyhow::Result<StateArchive> {
+	let mut files = BTreeMap::new();
+	let mut entries = tokio::fs::read_dir(data_dir)
+		.await
+		.with_context(|| format!("Failed to read data directory {}", data_dir.display()))?;
+	while let Some(entry) = entries.next_entry().await? {
+		let name = entry.file_name().to_string_lossy().to_string();
+		let file_type = entry.file_type().await?;
+		if file_type.is_file() && is_state_file(&name) {
+			let content = tokio::fs::read(entry.path()).await?;
+			files.insert(name, STANDARD.encode(content));
+		} else if file_type.is_dir() && STATE_DIRS.contains(&name.as_str()) {
+			let mut dir_entries = tokio::fs::read_dir(entry.path()).await?;
+			while let Some(dir_entry) = dir_entries.next_entry().await? {
+				if dir_entry.file_type().await?.is_file() {
+					let content = tokio::fs::read(dir_entry.path()).await?;
+					files.insert(
+						format!("{}/{}", name, dir_entry.file_name().to_string_lossy()),
+						STANDARD.encode(content),
+					);
+				}
+			}
+		}
+	}
+
+	let mut archive = StateArchive {
+		format: STATE_ARCHIVE_FORMAT.to_string(),
+		version: STATE_ARCHIVE_VERSION,
+		created_at: Utc::now().timestamp_millis(),
+		files,
+		checksum: String::new(),
+		signature: None,
+	};
+	archive.checksum = archive.compute_checksum();
+	if let Some(signing_key) = signing_key {
+		archive.signature = Some(hex::encode(
+			archive.mac(signing_key)?.finalize().into_bytes(),
+		));
+	}
+	Ok(archive)
+}
+
+/// Restores the state of an archive into the data directory, overwriting existing files
+///
+/// # Arguments
+/// * `archive` - The archive
+/// * `data_dir` - The data directory
+/// * `signing_key` - Key the archive must be signed with, if any
+///
+/// # Returns
+/// The number of restored files
+///
+/// # Errors
+/// Returns an error if the archive fails verification, holds a path outside the data
+/// directory, or a file cannot be written
+pub async fn import_state(
+	archive: &StateArchive,
+	data_dir: &Path,
+	signing_key: Option<&str>,
+) -> anyhow::Result<usize> {
+	archive.verify(signing_key)?;
+
+	// Decode every file before writing any, so that a corrupt archive leaves no partial state
+	let mut files = Vec::with_capacity(archive.files.len());
+	for (path, data) in &archive.files {
+		let relative = Path::new(path);
+		if !relative
+			.components()
+			.all(|component| matches!(component, Component::Normal(_)))
+		{
+			anyhow::bail!("Invalid path '{}' in state archive", path);
+		}
+		let content = STANDARD
+			.decode(data)
+			.with_context(|| format!("Invalid content of '{}' in state archive", path))?;
+		files.push((data_dir.join(relative), content));
+	}
+
+	for (path, content) in &files {
+		if let Some(parent) = path.parent() {
+			tokio::fs::create_dir_all(parent).await?;
+		}
+		tokio::fs::write(path, content)
+			.await
+			.with_context(|| format!("Failed to write {}", path.display()))?;
+	}
+	Ok(files.len())
+}
+
+/// Writes the archive of the state of the data directory to a file
+///
+/// # Arguments
+/// * `data_dir` - The data directory
+/// * `path` - Path of the archive
+/// * `signing_key` - Key to sign the archive with, if any
+///
+/// # Returns
+/// The number of exported files
+///
+/// # Errors
+/// Returns an error if the state cannot be read or the archive written
+pub async fn export_state_to_file(
+	data_dir: &Path,
+	path: &Path,
+	signing_key: Option<&str>,
+) -> anyhow::Result<usize> {
+	let archive = export_state(data_dir, signing_key).await?;
+	tokio::fs::write(path, serde_json::to_vec_pretty(&archive)?)
+		.await
+		.with_context(|| format!("Failed to write state archive {}", path.display()))?;
+	Ok(archive.files.len())
+}
+
+/// Restores the state archived in a file into the data directory
+///
+/// # Arguments
+/// * `path` - Path of the archive
+/// * `data_dir` - The data directory
+/// * `signing_key` - Key the archive must be signed with, if any
+///
+/// # Returns
+/// The number of restored files
+///
+/// # Errors
+/// Returns an error if the archive cannot be read, fails verification or cannot be restored
+pub async fn import_state_from_file(
+	path: &Path,
+	data_dir: &Path,
+	signing_key: Option<&str>,
+) -> anyhow::Result<usize> {
+	let content = tokio::fs::read(path)
+		.await
+		.with_context(|| format!("Failed to read state archive {}", path.display()))?;
+	let archive: StateArchive =
+		serde_json::from_slice(&content).context("Failed to parse state archive")?;
+	import_state(&archive, data_dir, signing_key).await
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	async fn write_state(data_dir: &Path) {
+		tokio::fs::write(data_dir.join("ethereum_mainnet_last_block.txt"), "100")
+			.await
+			.unwrap();
+		tokio::fs::write(data_dir.join("ethereum_mainnet_skipped_blocks.json"), "[]")
+			.await
+			.unwrap();
+		tokio::fs::write(data_dir.join("match_stats.json"), "{}")
+			.await
+			.unwrap();
+		tokio::fs::create_dir_all(data_dir.join("interactions"))
+			.await
+			.unwrap();
+		tokio::fs::write(
+			data_dir.join("interactions").join("ethereum_mainnet.json"),
+			"{}",
+		)
+		.await
+		.unwrap();
+		tokio::fs::create_dir_all(data_dir.join("nonces"))
+			.await
+			.unwrap();
+		tokio::fs::write(data_dir.join("nonces").join("hot_wallets.json"), "{}")
+			.await
+			.unwrap();
+		// Not part of the state
+		tokio::fs::write(data_dir.join("ethereum_mainnet_blocks_1.json"), "[]")
+			.await
+			.unwrap();
+		tokio::fs::write(data_dir.join("match_journal.jsonl"), "")
+			.await
+			.unwrap();
+	}
+
+	#[tokio::test]
+	async fn test_export_import_round_trip() {
+		let source = tempfile::tempdir().unwrap();
+		let target = tempfile::tempdir().unwrap();
+		write_state(source.path()).await;
+
+		let archive_path = source.path().join("state.json");
+		let exported = export_state_to_file(source.path(), &archive_path, Some("secret"))
+			.await
+			.unwrap();
+		assert_eq!(exported, 5);
+
+		let imported = import_state_from_file(&archive_path, target.path(), Some("secret"))
+			.await
+			.unwrap();
+		assert_eq!(imported, 5);
+		assert_eq!(
+			tokio::fs::read_to_string(target.path().join("ethereum_mainnet_last_block.txt"))
+				.await
+				.unwrap(),
+			"100"
+		);
+		assert!(target
+			.path()
+			.join("interactions")
+			.join("ethereum_mainnet.json")
+			.exists());
+		assert!(target
+			.path()
+			.join("nonces")
+			.join("hot_wallets.json")
+			.exists());
+		assert!(!target.path().join("match_journal.jsonl").exists());
+	}
+
+	#[tokio::test]
+	async fn test_verify_rejects_altered_archives() {
+		let source = tempfile::tempdir().unwrap();
+		write_state(source.path()).await;
+
+		let archive = export_state(source.path(), Some("secret")).await.unwrap();
+		assert!(archive.verify(Some("secret")).is_ok());
+		assert!(archive.verify(None).is_ok());
+		assert!(archive.verify(Some("other")).is_err());
+
+		let mut altered = archive.clone();
+		altered.files.insert(
+			"ethereum_mainnet_last_block.txt".to_string(),
+			STANDARD.encode("1"),
+		);
+		assert!(altered.verify(None).is_err());
+
+		let mut unsigned = archive.clone();
+		unsigned.signature = None;
+		assert!(unsigned.verify(None).is_ok());
+		assert!(unsigned.verify(Some("secret")).is_err());
+
+		let mut future = archive;
+		future.version = STATE_ARCHIVE_VERSION + 1;
+		assert!(future.verify(None).is_err());
+	}
+
+	#[tokio::test]
+	async fn test_import_rejects_paths_outside_data_dir() {
+		let target = tempfile::tempdir().unwrap();
+		let mut archive = export_state(target.path(), None).await.unwrap();
+		archive
+			.files
+			.insert("../escape.txt".to_string(), STANDARD.encode("x"));
+		archive.checksum = archive.compute_checksum();
+
+		assert!(import_state(&archive, target.path(), None).await.is_err());
+		assert!(!target.path().join("..").join("escape.txt").exists());
+	}
+}
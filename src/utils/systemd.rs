@@ -0,0 +1,160 @@
+//! Service manager notifications.
+//!
+//! Implements the `sd_notify` protocol, so that the service can run as a systemd unit with
+//! `Type=notify`: the service reports `READY=1` once its watchers are started, `RELOADING=1`
+//! and `STOPPING=1` on reload and shutdown, and when the unit sets `WatchdogSec`, pings the
+//! systemd watchdog with `WATCHDOG=1`. The pings are withheld while a network watcher is
+//! stalled beyond the reach of the internal watchdog, so that systemd restarts a hung service
+//! rather than only a dead one.
+//!
+//! Without the `NOTIFY_SOCKET` environment variable set by systemd, and on non-Unix
+//! platforms, notifications are no-ops.
+
+use std::{io, time::Duration};
+use tokio::task::JoinHandle;
+
+use crate::services::blockwatcher::stalled_watchers;
+
+/// Sends a notification to the service manager
+///
+/// # Arguments
+/// * `state` - Newline-separated assignments, e.g. `READY=1`
+///
+/// # Returns
+/// `true` if the notification was sent, `false` if the service is not run by a service
+/// manager supporting notifications
+///
+/// # Errors
+/// Returns an error if the notification socket could not be reached
+pub fn notify(state: &str) -> io::Result<bool> {
+	match std::env::var("NOTIFY_SOCKET") {
+		Ok(socket) if !socket.is_empty() => send_notification(&socket, state).map(|_| true),
+		_ => Ok(false),
+	}
+}
+
+/// Notifies the service manager, logging failures instead of returning them
+///
+/// # Arguments
+/// * `state` - Newline-separated assignments, e.g. `READY=1`
+pub fn notify_or_log(state: &str) {
+	if let Err(e) = notify(state) {
+		tracing::warn!("Failed to notify the service manager ({}): {}", state, e);
+	}
+}
+
+/// Interval at which the service manager expects watchdog pings
+///
+/// # Returns
+/// The watchdog timeout requested through `WATCHDOG_USEC`, if it applies to this process
+pub fn watchdog_timeout() -> Option<Duration> {
+	watchdog_timeout_from(
+		std::env::var("WATCHDOG_USEC").ok().as_deref(),
+		std::env::var("WATCHDOG_PID").ok().as_deref(),
+		std::process::id(),
+	)
+}
+
+/// Starts pinging the watchdog of the service manager, if it requested pings
+///
+/// Pings are sent at half the watchdog timeout, and withheld while a network watcher is
+/// stalled (see [`stalled_watchers`]).
+///
+/// # Returns
+/// The handle of the ping task, or `None` if the service manager has no watchdog
+pub fn start_watchdog_pings() -> Option<JoinHandle<()>> {
+	let timeout = watchdog_timeout()?;
+	tracing::info!("Pinging the systemd watchdog every {:?}", timeout / 2);
+	Some(tokio::spawn(async move {
+		let mut interval = tokio::time::interval(timeout / 2);
+		loop {
+			interval.tick().await;
+			let stalled = stalled_watchers();
+			if stalled.is_empty() {
+				notify_or_log("WATCHDOG=1");
+			} else {
+				tracing::error!(
+					networks = ?stalled,
+					"Withholding the systemd watchdog ping, watchers are stalled: {}",
+					stalled.join(", ")
+				);
+				notify_or_log(&format!("STATUS=Stalled watchers: {}", stalled.join(", ")));
+			}
+		}
+	}))
+}
+
+fn watchdog_timeout_from(usec: Option<&str>, pid: Option<&str>, own_pid: u32) -> Option<Duration> {
+	// The watchdog applies to another process, e.g. the parent shell of a wrapper script
+	if let Some(pid) = pid {
+		if pid.trim().parse::<u32>().ok()? != own_pid {
+			return None;
+		}
+	}
+	let usec = usec?.trim().parse::<u64>().ok()?;
+	(usec > 0).then(|| Duration::from_micros(usec))
+}
+
+#[cfg(unix)]
+fn send_notification(socket: &str, state: &str) -> io::Result<()> {
+	use std::os::unix::net::{SocketAddr, UnixDatagram};
+
+	let address = match socket.strip_prefix('@') {
+		#[cfg(target_os = "linux")]
+		Some(name) => {
+			use std::os::linux::net::SocketAddrExt;
+			SocketAddr::from_abstract_name(name.as_bytes())?
+		}
+		#[cfg(not(target_os = "linux"))]
+		Some(_) => {
+			return Err(io::Error::new(
+				io::ErrorKind::Unsupported,
+				"abstract notification sockets are only supported on Linux",
+			));
+		}
+		None => SocketAddr::from_pathname(socket)?,
+	};
+	let datagram = UnixDatagram::unbound()?;
+	datagram.send_to_addr(state.as_bytes(), &address)?;
+	Ok(())
+}
+
+#[cfg(not(unix))]
+fn send_notification(_socket: &str, _state: &str) -> io::Result<()> {
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_watchdog_timeout_from() {
+		assert_eq!(
+			watchdog_timeout_from(Some("30000000"), None, 42),
+			Some(Duration::from_secs(30))
+		);
+		assert_eq!(
+			watchdog_timeout_from(Some("30000000"), Some("42"), 42),
+			Some(Duration::from_secs(30))
+		);
+		// The watchdog is meant for another process
+		assert_eq!(watchdog_timeout_from(Some("30000000"), Some("7"), 42), None);
+		assert_eq!(watchdog_timeout_from(Some("0"), None, 42), None);
+		assert_eq!(watchdog_timeout_from(None, None, 42), None);
+	}
+
+	#[cfg(unix)]
+	#[test]
+	fn test_send_notification() {
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join("notify.sock");
+		let receiver = std::os::unix::net::UnixDatagram::bind(&path).unwrap();
+
+		send_notification(path.to_str().unwrap(), "READY=1").unwrap();
+
+		let mut buffer = [0u8; 64];
+		let size = receiver.recv(&mut buffer).unwrap();
+		assert_eq!(&buffer[..size], b"READY=1");
+	}
+}
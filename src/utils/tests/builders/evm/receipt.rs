@@ -11,6 +11,7 @@ pub struct ReceiptBuilder {
 	transaction_hash: Option<B256>,
 	status: Option<bool>,
 	gas_used: Option<U256>,
+	effective_gas_price: Option<U256>,
 	logs: Option<Vec<EVMReceiptLog>>,
 	from: Option<Address>,
 	to: Option<Address>,
@@ -42,6 +43,12 @@ impl ReceiptBuilder {
 		self
 	}
 
+	/// Sets the effective gas price of the transaction.
+	pub fn effective_gas_price(mut self, effective_gas_price: U256) -> Self {
+		self.effective_gas_price = Some(effective_gas_price);
+		self
+	}
+
 	/// Sets the transaction index in the block.
 	pub fn transaction_index(mut self, transaction_index: usize) -> Self {
 		self.transaction_index = Some(Index::from(transaction_index));
@@ -110,6 +117,7 @@ impl ReceiptBuilder {
 			transaction_hash: self.transaction_hash.unwrap_or_default(),
 			status: Some(status_u64),
 			gas_used: self.gas_used,
+			effective_gas_price: self.effective_gas_price,
 			logs: self.logs.unwrap_or_default(),
 			from: self.from.unwrap_or_default(),
 			to: self.to,
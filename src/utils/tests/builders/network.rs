@@ -3,7 +3,9 @@
 //! - `NetworkBuilder`: Builder for creating test Network instances
 
 use crate::models::{
-	BlockChainType, BlockRecoveryConfig, MaxPastBlocks, Network, RpcUrl, SecretString, SecretValue,
+	BlockChainType, BlockGuardrails, BlockRecoveryConfig, GetLogsLimits, MaxPastBlocks, Network,
+	PoisonBlockPolicy, RestartPolicy, RpcConsistencyConfig, RpcCostConfig, RpcProvider, RpcUrl,
+	SecretString, SecretValue, TraceBudget, WatchdogConfig,
 };
 
 /// Builder for creating test Network instances
@@ -19,7 +21,16 @@ pub struct NetworkBuilder {
 	confirmation_blocks: u64,
 	cron_schedule: String,
 	max_past_blocks: Option<MaxPastBlocks>,
+	start_block: Option<u64>,
 	recovery_config: Option<BlockRecoveryConfig>,
+	rpc_consistency_check: Option<RpcConsistencyConfig>,
+	watchdog: Option<WatchdogConfig>,
+	poison_block_policy: Option<PoisonBlockPolicy>,
+	rpc_cost: Option<RpcCostConfig>,
+	get_logs_limits: Option<GetLogsLimits>,
+	block_guardrails: Option<BlockGuardrails>,
+	restart_policy: Option<RestartPolicy>,
+	trace_budget: Option<TraceBudget>,
 }
 
 impl Default for NetworkBuilder {
@@ -36,7 +47,16 @@ impl Default for NetworkBuilder {
 			confirmation_blocks: 1,
 			cron_schedule: "0 */5 * * * *".to_string(),
 			max_past_blocks: Some(MaxPastBlocks::Limited(10)),
+			start_block: None,
 			recovery_config: None,
+			rpc_consistency_check: None,
+			watchdog: None,
+			poison_block_policy: None,
+			rpc_cost: None,
+			get_logs_limits: None,
+			block_guardrails: None,
+			restart_policy: None,
+			trace_budget: None,
 		}
 	}
 }
@@ -81,6 +101,7 @@ impl NetworkBuilder {
 			type_: "rpc".to_string(),
 			url: SecretValue::Plain(SecretString::new(url.to_string())),
 			weight: 100,
+			provider: None,
 		}];
 		self
 	}
@@ -92,6 +113,7 @@ impl NetworkBuilder {
 				type_: "rpc".to_string(),
 				url: SecretValue::Plain(SecretString::new(url.to_string())),
 				weight: 100,
+				provider: None,
 			})
 			.collect();
 		self
@@ -104,6 +126,7 @@ impl NetworkBuilder {
 				type_: "ws_rpc".to_string(),
 				url: SecretValue::Plain(SecretString::new(url.to_string())),
 				weight: 100,
+				provider: None,
 			})
 			.collect();
 		self
@@ -114,6 +137,7 @@ impl NetworkBuilder {
 			type_: type_.to_string(),
 			url: SecretValue::Plain(SecretString::new(url.to_string())),
 			weight,
+			provider: None,
 		});
 		self
 	}
@@ -123,10 +147,21 @@ impl NetworkBuilder {
 			type_: type_.to_string(),
 			url,
 			weight,
+			provider: None,
 		});
 		self
 	}
 
+	pub fn rpc_provider(mut self, name: &str, max_requests_per_second: Option<u32>) -> Self {
+		for rpc_url in self.rpc_urls.iter_mut() {
+			rpc_url.provider = Some(RpcProvider {
+				name: name.to_string(),
+				max_requests_per_second,
+			});
+		}
+		self
+	}
+
 	pub fn clear_rpc_urls(mut self) -> Self {
 		self.rpc_urls.clear();
 		self
@@ -157,11 +192,56 @@ impl NetworkBuilder {
 		self
 	}
 
+	pub fn start_block(mut self, block: u64) -> Self {
+		self.start_block = Some(block);
+		self
+	}
+
 	pub fn recovery_config(mut self, config: BlockRecoveryConfig) -> Self {
 		self.recovery_config = Some(config);
 		self
 	}
 
+	pub fn rpc_consistency_check(mut self, config: RpcConsistencyConfig) -> Self {
+		self.rpc_consistency_check = Some(config);
+		self
+	}
+
+	pub fn watchdog(mut self, config: WatchdogConfig) -> Self {
+		self.watchdog = Some(config);
+		self
+	}
+
+	pub fn poison_block_policy(mut self, policy: PoisonBlockPolicy) -> Self {
+		self.poison_block_policy = Some(policy);
+		self
+	}
+
+	pub fn rpc_cost(mut self, rpc_cost: RpcCostConfig) -> Self {
+		self.rpc_cost = Some(rpc_cost);
+		self
+	}
+
+	pub fn get_logs_limits(mut self, limits: GetLogsLimits) -> Self {
+		self.get_logs_limits = Some(limits);
+		self
+	}
+
+	pub fn block_guardrails(mut self, guardrails: BlockGuardrails) -> Self {
+		self.block_guardrails = Some(guardrails);
+		self
+	}
+
+	pub fn restart_policy(mut self, policy: RestartPolicy) -> Self {
+		self.restart_policy = Some(policy);
+		self
+	}
+
+	pub fn trace_budget(mut self, budget: TraceBudget) -> Self {
+		self.trace_budget = Some(budget);
+		self
+	}
+
 	pub fn build(self) -> Network {
 		Network {
 			name: self.name,
@@ -175,7 +255,16 @@ impl NetworkBuilder {
 			confirmation_blocks: self.confirmation_blocks,
 			cron_schedule: self.cron_schedule,
 			max_past_blocks: self.max_past_blocks,
+			start_block: self.start_block,
 			recovery_config: self.recovery_config,
+			rpc_consistency_check: self.rpc_consistency_check,
+			watchdog: self.watchdog,
+			poison_block_policy: self.poison_block_policy,
+			rpc_cost: self.rpc_cost,
+			get_logs_limits: self.get_logs_limits,
+			block_guardrails: self.block_guardrails,
+			restart_policy: self.restart_policy,
+			trace_budget: self.trace_budget,
 		}
 	}
 }
@@ -2,10 +2,13 @@
 //!
 //! - `MonitorBuilder`: Builder for creating test Monitor instances
 
+use std::collections::BTreeMap;
+
 use crate::models::{
-	AddressWithSpec, ChainConfiguration, ContractSpec, EventCondition, FunctionCondition,
-	MatchConditions, Monitor, ScriptLanguage, StellarMonitorConfig, TransactionCondition,
-	TransactionStatus, TriggerConditions,
+	AbiDriftConfig, AddressOwner, AddressWithSpec, BatchConfig, CanaryConfig, ChainConfiguration,
+	ContractSpec, DeploymentDependency, EventCondition, FunctionCondition, MatchConditions,
+	Monitor, MonitorSeverity, PostProcessingScript, RpcUrl, SamplingConfig, ScriptLanguage,
+	StellarMonitorConfig, TransactionCondition, TransactionStatus, TriggerConditions,
 };
 
 /// Builder for creating test Monitor instances
@@ -18,6 +21,19 @@ pub struct MonitorBuilder {
 	trigger_conditions: Vec<TriggerConditions>,
 	triggers: Vec<String>,
 	chain_configurations: Vec<ChainConfiguration>,
+	network_addresses: BTreeMap<String, Vec<AddressWithSpec>>,
+	start_block: Option<u64>,
+	severity: MonitorSeverity,
+	observe: bool,
+	canary: Option<CanaryConfig>,
+	activate_on_deployment: Option<DeploymentDependency>,
+	abi_drift: Option<AbiDriftConfig>,
+	sampling: Option<SamplingConfig>,
+	batch: Option<BatchConfig>,
+	trigger_params: BTreeMap<String, BTreeMap<String, String>>,
+	owners: Vec<AddressOwner>,
+	post_processing: Option<PostProcessingScript>,
+	rpc_overrides: BTreeMap<String, Vec<RpcUrl>>,
 }
 
 impl Default for MonitorBuilder {
@@ -41,6 +57,19 @@ impl Default for MonitorBuilder {
 				stellar: Some(StellarMonitorConfig::default()),
 				..Default::default()
 			}],
+			network_addresses: BTreeMap::new(),
+			start_block: None,
+			severity: MonitorSeverity::default(),
+			observe: false,
+			canary: None,
+			activate_on_deployment: None,
+			abi_drift: None,
+			sampling: None,
+			batch: None,
+			trigger_params: BTreeMap::new(),
+			owners: Vec::new(),
+			post_processing: None,
+			rpc_overrides: BTreeMap::new(),
 		}
 	}
 }
@@ -146,6 +175,7 @@ impl MonitorBuilder {
 			timeout_ms,
 			arguments,
 			language,
+			expression: None,
 		});
 		self
 	}
@@ -160,6 +190,95 @@ impl MonitorBuilder {
 		self
 	}
 
+	pub fn severity(mut self, severity: MonitorSeverity) -> Self {
+		self.severity = severity;
+		self
+	}
+
+	pub fn observe(mut self, observe: bool) -> Self {
+		self.observe = observe;
+		self
+	}
+
+	pub fn canary(mut self, network: &str, block: u64, interval_secs: u64) -> Self {
+		self.canary = Some(CanaryConfig {
+			network: network.to_string(),
+			block,
+			interval_secs,
+			triggers: Vec::new(),
+		});
+		self
+	}
+
+	pub fn activate_on_deployment(mut self, network: &str, address: &str) -> Self {
+		self.activate_on_deployment = Some(DeploymentDependency {
+			network: network.to_string(),
+			address: address.to_string(),
+			interval_secs: DeploymentDependency::DEFAULT_INTERVAL_SECS,
+		});
+		self
+	}
+
+	pub fn abi_drift(mut self, interval_secs: u64, triggers: Vec<String>) -> Self {
+		self.abi_drift = Some(AbiDriftConfig {
+			interval_secs,
+			triggers,
+		});
+		self
+	}
+
+	pub fn sampling(mut self, sampling: SamplingConfig) -> Self {
+		self.sampling = Some(sampling);
+		self
+	}
+
+	pub fn batch(mut self, aggregate_arg: Option<&str>) -> Self {
+		self.batch = Some(BatchConfig {
+			aggregate_arg: aggregate_arg.map(|arg| arg.to_string()),
+		});
+		self
+	}
+
+	pub fn trigger_param(mut self, trigger: &str, name: &str, value: &str) -> Self {
+		self.trigger_params
+			.entry(trigger.to_string())
+			.or_default()
+			.insert(name.to_string(), value.to_string());
+		self
+	}
+
+	pub fn owner(mut self, name: &str, addresses: Vec<&str>, mention: Option<&str>) -> Self {
+		self.owners.push(AddressOwner {
+			name: name.to_string(),
+			addresses: addresses.into_iter().map(|a| a.to_string()).collect(),
+			mention: mention.map(|m| m.to_string()),
+			triggers: Vec::new(),
+		});
+		self
+	}
+
+	pub fn owner_triggers(mut self, triggers: Vec<String>) -> Self {
+		if let Some(owner) = self.owners.last_mut() {
+			owner.triggers = triggers;
+		}
+		self
+	}
+
+	pub fn post_processing(
+		mut self,
+		script_path: &str,
+		language: ScriptLanguage,
+		arguments: Option<Vec<String>>,
+	) -> Self {
+		self.post_processing = Some(PostProcessingScript {
+			script_path: script_path.to_string(),
+			arguments,
+			language,
+			timeout_ms: 1000,
+		});
+		self
+	}
+
 	pub fn build(self) -> Monitor {
 		Monitor {
 			name: self.name,
@@ -170,6 +289,19 @@ impl MonitorBuilder {
 			trigger_conditions: self.trigger_conditions,
 			triggers: self.triggers,
 			chain_configurations: self.chain_configurations,
+			network_addresses: self.network_addresses,
+			start_block: self.start_block,
+			severity: self.severity,
+			observe: self.observe,
+			canary: self.canary,
+			activate_on_deployment: self.activate_on_deployment,
+			abi_drift: self.abi_drift,
+			sampling: self.sampling,
+			batch: self.batch,
+			trigger_params: self.trigger_params,
+			owners: self.owners,
+			post_processing: self.post_processing,
+			rpc_overrides: self.rpc_overrides,
 		}
 	}
 }
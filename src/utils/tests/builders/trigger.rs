@@ -4,18 +4,22 @@
 
 use crate::{
 	models::{
-		NotificationMessage, ScriptLanguage, SecretString, SecretValue, Trigger, TriggerType,
-		TriggerTypeConfig, WebhookPayloadMode,
+		BlockChainType, ChainMessage, ChainMessages, DeliveryReportConfig, NotificationMessage,
+		ScriptLanguage, SecretString, SecretValue, Trigger, TriggerType, TriggerTypeConfig,
+		WebhookPayloadMode,
 	},
-	utils::RetryConfig,
+	utils::{HttpClientConfig, RetryConfig},
 };
 use email_address::EmailAddress;
+use std::collections::BTreeMap;
 
 /// Builder for creating test Trigger instances
 pub struct TriggerBuilder {
 	name: String,
 	trigger_type: TriggerType,
 	config: TriggerTypeConfig,
+	parameters: BTreeMap<String, String>,
+	delivery_report: Option<DeliveryReportConfig>,
 }
 
 impl Default for TriggerBuilder {
@@ -33,10 +37,14 @@ impl Default for TriggerBuilder {
 				message: NotificationMessage {
 					title: "Alert".to_string(),
 					body: "Test message".to_string(),
+					chains: ChainMessages::default(),
 				},
 				payload_mode: WebhookPayloadMode::default(),
 				retry_policy: RetryConfig::default(),
+				http_client: HttpClientConfig::default(),
 			},
+			parameters: BTreeMap::new(),
+			delivery_report: None,
 		}
 	}
 }
@@ -66,9 +74,11 @@ impl TriggerBuilder {
 			message: NotificationMessage {
 				title: "Alert".to_string(),
 				body: "Test message".to_string(),
+				chains: ChainMessages::default(),
 			},
 			payload_mode: WebhookPayloadMode::default(),
 			retry_policy: RetryConfig::default(),
+			http_client: HttpClientConfig::default(),
 		};
 		self
 	}
@@ -80,8 +90,10 @@ impl TriggerBuilder {
 			message: NotificationMessage {
 				title: "Alert".to_string(),
 				body: "Test message".to_string(),
+				chains: ChainMessages::default(),
 			},
 			retry_policy: RetryConfig::default(),
+			http_client: HttpClientConfig::default(),
 		};
 		self
 	}
@@ -93,8 +105,10 @@ impl TriggerBuilder {
 			message: NotificationMessage {
 				title: "Alert".to_string(),
 				body: "Test message".to_string(),
+				chains: ChainMessages::default(),
 			},
 			retry_policy: RetryConfig::default(),
+			http_client: HttpClientConfig::default(),
 		};
 		self
 	}
@@ -108,8 +122,10 @@ impl TriggerBuilder {
 			message: NotificationMessage {
 				title: "Test title".to_string(),
 				body: "Test message".to_string(),
+				chains: ChainMessages::default(),
 			},
 			retry_policy: RetryConfig::default(),
+			http_client: HttpClientConfig::default(),
 		};
 		self
 	}
@@ -161,6 +177,36 @@ impl TriggerBuilder {
 		self
 	}
 
+	pub fn chain_message(mut self, chain: BlockChainType, title: Option<&str>, body: &str) -> Self {
+		if let Some(message) = self.config.get_message_mut() {
+			let variant = Some(ChainMessage {
+				title: title.map(|title| title.to_string()),
+				body: body.to_string(),
+			});
+			match chain {
+				BlockChainType::EVM => message.chains.evm = variant,
+				BlockChainType::Stellar => message.chains.stellar = variant,
+				BlockChainType::Midnight => message.chains.midnight = variant,
+				BlockChainType::Solana => message.chains.solana = variant,
+			}
+		}
+		self
+	}
+
+	pub fn parameter(mut self, name: &str, default: &str) -> Self {
+		self.parameters
+			.insert(name.to_string(), default.to_string());
+		self
+	}
+
+	pub fn delivery_report(mut self, url: &str, failures_only: bool) -> Self {
+		self.delivery_report = Some(DeliveryReportConfig {
+			url: SecretValue::Plain(SecretString::new(url.to_string())),
+			failures_only,
+		});
+		self
+	}
+
 	pub fn trigger_type(mut self, trigger_type: TriggerType) -> Self {
 		self.trigger_type = trigger_type;
 		self
@@ -183,6 +229,7 @@ impl TriggerBuilder {
 			message: NotificationMessage {
 				title: "Test Subject".to_string(),
 				body: "Test Body".to_string(),
+				chains: ChainMessages::default(),
 			},
 			sender: EmailAddress::new_unchecked(sender),
 			recipients: recipients
@@ -250,6 +297,17 @@ impl TriggerBuilder {
 		self
 	}
 
+	pub fn http_client(mut self, config: HttpClientConfig) -> Self {
+		match &mut self.config {
+			TriggerTypeConfig::Slack { http_client, .. }
+			| TriggerTypeConfig::Discord { http_client, .. }
+			| TriggerTypeConfig::Webhook { http_client, .. }
+			| TriggerTypeConfig::Telegram { http_client, .. } => *http_client = config,
+			_ => {}
+		}
+		self
+	}
+
 	pub fn url(mut self, url: SecretValue) -> Self {
 		self.config = match self.config {
 			TriggerTypeConfig::Webhook {
@@ -260,6 +318,7 @@ impl TriggerBuilder {
 				message,
 				payload_mode,
 				retry_policy,
+				http_client,
 			} => TriggerTypeConfig::Webhook {
 				url,
 				method,
@@ -268,24 +327,29 @@ impl TriggerBuilder {
 				message,
 				payload_mode,
 				retry_policy,
+				http_client,
 			},
 			TriggerTypeConfig::Discord {
 				discord_url: _,
 				message,
 				retry_policy,
+				http_client,
 			} => TriggerTypeConfig::Discord {
 				discord_url: url,
 				message,
 				retry_policy,
+				http_client,
 			},
 			TriggerTypeConfig::Slack {
 				slack_url: _,
 				message,
 				retry_policy,
+				http_client,
 			} => TriggerTypeConfig::Slack {
 				slack_url: url,
 				message,
 				retry_policy,
+				http_client,
 			},
 			config => config,
 		};
@@ -297,6 +361,8 @@ impl TriggerBuilder {
 			name: self.name,
 			trigger_type: self.trigger_type,
 			config: self.config,
+			parameters: self.parameters,
+			delivery_report: self.delivery_report,
 		}
 	}
 }
@@ -335,9 +401,11 @@ mod tests {
 				message: NotificationMessage {
 					title: "Alert".to_string(),
 					body: "Test message".to_string(),
+					chains: ChainMessages::default(),
 				},
 				payload_mode: WebhookPayloadMode::default(),
 				retry_policy: RetryConfig::default(),
+				http_client: HttpClientConfig::default(),
 			})
 			.build();
 
@@ -402,6 +470,7 @@ mod tests {
 				message,
 				payload_mode,
 				retry_policy: _,
+				http_client: _,
 			} => {
 				assert_eq!(url.as_ref().to_string(), "https://webhook.example.com");
 				assert_eq!(method, Some("POST".to_string()));
@@ -432,6 +501,7 @@ mod tests {
 				slack_url,
 				message,
 				retry_policy: _,
+				http_client: _,
 			} => {
 				assert_eq!(slack_url.as_ref().to_string(), "https://slack.webhook.com");
 				assert_eq!(message.title, "Alert");
@@ -455,6 +525,7 @@ mod tests {
 				discord_url,
 				message,
 				retry_policy: _,
+				http_client: _,
 			} => {
 				assert_eq!(
 					discord_url.as_ref().to_string(),
@@ -5,13 +5,13 @@ use std::sync::Arc;
 
 use crate::{
 	services::notification::NotificationClientPool,
-	utils::{create_retryable_http_client, RetryConfig},
+	utils::{create_retryable_http_client, RetryConfig, RetryPolicy},
 };
 
 /// Creates a default HTTP client with retry capabilities for testing purposes.
 pub fn create_test_http_client() -> Arc<ClientWithMiddleware> {
 	let retryable_client = create_retryable_http_client::<DefaultRetryableStrategy>(
-		&RetryConfig::default(),
+		RetryPolicy::new("test", &RetryConfig::default()),
 		Client::new(),
 		None,
 	);
@@ -10,9 +10,37 @@ use alloy::{
 use mockall::predicate;
 use mockito::Server;
 use openzeppelin_monitor::{
-	models::{BlockType, EVMBlock, EVMReceiptLog, EVMTransactionReceipt},
-	services::blockchain::{BlockChainClient, EvmClient, EvmClientTrait},
+	models::{BlockType, EVMBlock, EVMReceiptLog, EVMTransactionReceipt, GetLogsLimits},
+	services::blockchain::{
+		BlockChainClient, ContractCallCache, EvmClient, EvmClientTrait, TransportError,
+	},
 };
+use serde_json::{json, Value};
+use std::sync::{Arc, Mutex};
+
+/// Returns the block range of an `eth_getLogs` query
+fn logs_query_range(params: &Option<Vec<Value>>) -> (u64, u64) {
+	let filter = &params.as_ref().unwrap()[0];
+	let block = |field: &str| {
+		u64::from_str_radix(filter[field].as_str().unwrap().trim_start_matches("0x"), 16).unwrap()
+	};
+	(block("fromBlock"), block("toBlock"))
+}
+
+/// Returns a log of each block of a range
+fn logs_response(from: u64, to: u64) -> Value {
+	let logs: Vec<Value> = (from..=to)
+		.map(|block| {
+			json!({
+				"address": "0x0000000000000000000000000000000000000000",
+				"topics": [],
+				"data": "0x",
+				"blockNumber": format!("0x{:x}", block),
+			})
+		})
+		.collect();
+	json!({ "jsonrpc": "2.0", "id": 1, "result": logs })
+}
 
 #[tokio::test]
 async fn test_get_transaction_receipt() {
@@ -79,6 +107,172 @@ async fn test_get_logs_for_blocks() {
 	assert_eq!(result.unwrap().len(), 1);
 }
 
+#[tokio::test]
+async fn test_get_logs_for_blocks_max_block_range() {
+	let queries = Arc::new(Mutex::new(Vec::new()));
+	let recorded = queries.clone();
+	let mut mock_transport = MockEVMTransportClient::new();
+	mock_transport
+		.expect_send_raw_request()
+		.withf(|method, _| method == "eth_getLogs")
+		.returning(move |_, params| {
+			let (from, to) = logs_query_range(&params);
+			recorded.lock().unwrap().push((from, to));
+			Ok(logs_response(from, to))
+		});
+
+	let client = EvmClient::new_with_transport(mock_transport).with_logs_limits(GetLogsLimits {
+		max_block_range: Some(2),
+		..Default::default()
+	});
+	let logs = client.get_logs_for_blocks(1, 5, None).await.unwrap();
+
+	assert_eq!(*queries.lock().unwrap(), vec![(1, 2), (3, 4), (5, 5)]);
+	let blocks: Vec<_> = logs.iter().map(|log| log.block_number).collect();
+	assert_eq!(
+		blocks,
+		(1..=5).map(|n| Some(U64::from(n))).collect::<Vec<_>>()
+	);
+}
+
+#[tokio::test]
+async fn test_get_logs_for_blocks_splits_on_limit_error() {
+	let queries = Arc::new(Mutex::new(Vec::new()));
+	let recorded = queries.clone();
+	let mut mock_transport = MockEVMTransportClient::new();
+	mock_transport
+		.expect_send_raw_request()
+		.returning(move |_, params| {
+			let (from, to) = logs_query_range(&params);
+			recorded.lock().unwrap().push((from, to));
+			if to - from >= 2 {
+				return Err(TransportError::rpc_error(
+					-32005,
+					"query returned more than 10000 results",
+					"http://localhost:8545",
+					None,
+					None,
+				));
+			}
+			Ok(logs_response(from, to))
+		});
+
+	let client = EvmClient::new_with_transport(mock_transport);
+	let logs = client.get_logs_for_blocks(1, 6, None).await.unwrap();
+
+	assert_eq!(
+		*queries.lock().unwrap(),
+		vec![(1, 6), (1, 3), (1, 2), (3, 3), (4, 6), (4, 5), (6, 6)]
+	);
+	let blocks: Vec<_> = logs.iter().map(|log| log.block_number).collect();
+	assert_eq!(
+		blocks,
+		(1..=6).map(|n| Some(U64::from(n))).collect::<Vec<_>>()
+	);
+}
+
+#[tokio::test]
+async fn test_get_logs_for_blocks_custom_limit_error() {
+	let mock_transport = || {
+		let mut mock_transport = MockEVMTransportClient::new();
+		mock_transport
+			.expect_send_raw_request()
+			.returning(move |_, params| {
+				let (from, to) = logs_query_range(&params);
+				if from != to {
+					return Err(TransportError::rpc_error(
+						-32000,
+						"Exceeds the node's query cap",
+						"http://localhost:8545",
+						None,
+						None,
+					));
+				}
+				Ok(logs_response(from, to))
+			});
+		mock_transport
+	};
+
+	// Not recognized as a limit error unless configured
+	let client = EvmClient::new_with_transport(mock_transport());
+	assert!(client.get_logs_for_blocks(1, 2, None).await.is_err());
+
+	let client = EvmClient::new_with_transport(mock_transport()).with_logs_limits(GetLogsLimits {
+		error_patterns: vec!["query cap".to_string()],
+		..Default::default()
+	});
+	assert_eq!(
+		client.get_logs_for_blocks(1, 2, None).await.unwrap().len(),
+		2
+	);
+}
+
+#[tokio::test]
+async fn test_has_contract_code() {
+	let client = |code: &'static str| {
+		let mut mock_transport = MockEVMTransportClient::new();
+		mock_transport
+			.expect_send_raw_request()
+			.withf(|method, params| {
+				method == "eth_getCode"
+					&& params.as_ref().unwrap()[0] == "0x0000000000000000000000000000000000000001"
+			})
+			.returning(move |_, _| Ok(json!({ "jsonrpc": "2.0", "id": 1, "result": code })));
+		EvmClient::new_with_transport(mock_transport)
+	};
+
+	let address = "0x0000000000000000000000000000000000000001";
+	assert!(client("0x6080").has_contract_code(address).await.unwrap());
+	assert!(!client("0x").has_contract_code(address).await.unwrap());
+}
+
+#[tokio::test]
+async fn test_call_cached() {
+	let address = "0x0000000000000000000000000000000000000001";
+	let decimals = "0x313ce567";
+	let mut mock_transport = MockEVMTransportClient::new();
+	mock_transport
+		.expect_send_raw_request()
+		.withf(move |method, params| {
+			let params = params.as_ref().unwrap();
+			method == "eth_call" && params[0]["to"] == address && params[0]["data"] == decimals
+		})
+		.times(1)
+		.returning(|_, _| {
+			Ok(json!({ "jsonrpc": "2.0", "id": 1, "result": "0x0000000000000000000000000000000000000000000000000000000000000006" }))
+		});
+	let client = EvmClient::new_with_transport(mock_transport)
+		.with_call_cache(Arc::new(ContractCallCache::default()));
+
+	// The second read is served from the cache
+	for _ in 0..2 {
+		let result = client.call(address, decimals).await.unwrap();
+		assert!(result.ends_with("06"));
+	}
+}
+
+#[tokio::test]
+async fn test_trace_transaction() {
+	let hash = "0x0000000000000000000000000000000000000000000000000000000000000001";
+	let mut mock_transport = MockEVMTransportClient::new();
+	mock_transport
+		.expect_send_raw_request()
+		.withf(move |method, params| {
+			let params = params.as_ref().unwrap();
+			method == "debug_traceTransaction"
+				&& params[0] == hash
+				&& params[1]["tracer"] == "callTracer"
+		})
+		.times(1)
+		.returning(|_, _| {
+			Ok(json!({ "jsonrpc": "2.0", "id": 1, "result": { "type": "CALL", "calls": [] } }))
+		});
+	let client = EvmClient::new_with_transport(mock_transport);
+
+	let trace = client.trace_transaction(hash).await.unwrap();
+	assert_eq!(trace["type"], "CALL");
+}
+
 #[tokio::test]
 async fn test_get_latest_block_number() {
 	let mut mock = MockEvmClientTrait::<MockEVMTransportClient>::new();
@@ -187,6 +187,36 @@ async fn test_different_evm_networks_get_different_clients() {
 	mock_2.assert();
 }
 
+#[tokio::test]
+async fn test_overridden_rpc_urls_get_different_clients() {
+	let pool = ClientPool::new();
+	let mut mock_server = mockito::Server::new_async().await;
+	let mut mock_server_2 = mockito::Server::new_async().await;
+
+	let mock = create_evm_valid_server_mock_network_response(&mut mock_server);
+	let mock_2 = create_evm_valid_server_mock_network_response(&mut mock_server_2);
+
+	// Same network, with the endpoints of a monitor override
+	let network = create_evm_test_network_with_urls(vec![&mock_server.url()]);
+	let mut overridden = network.clone();
+	overridden.rpc_urls = create_evm_test_network_with_urls(vec![&mock_server_2.url()]).rpc_urls;
+
+	let client1 = pool.get_evm_client(&network).await.unwrap();
+	let client2 = pool.get_evm_client(&overridden).await.unwrap();
+	let client3 = pool.get_evm_client(&network).await.unwrap();
+
+	assert_eq!(
+		pool.get_client_count::<EvmClient<EVMTransportClient>>(BlockChainType::EVM)
+			.await,
+		2
+	);
+	assert!(!Arc::ptr_eq(&client1, &client2));
+	assert!(Arc::ptr_eq(&client1, &client3));
+
+	mock.assert();
+	mock_2.assert();
+}
+
 #[tokio::test]
 async fn test_different_stellar_networks_get_different_clients() {
 	let pool = ClientPool::new();
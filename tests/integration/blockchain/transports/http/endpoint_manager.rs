@@ -2,12 +2,17 @@ use mockito::Server;
 use reqwest_middleware::{ClientBuilder, ClientWithMiddleware};
 use reqwest_retry::{policies::ExponentialBackoff, RetryTransientMiddleware};
 use serde_json::{json, Value};
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 use tokio::sync::RwLock;
 
-use openzeppelin_monitor::services::blockchain::{
-	BlockchainTransport, HttpEndpointManager, TransportError, ROTATE_ON_ERROR_CODES,
+use openzeppelin_monitor::services::{
+	blockchain::{
+		BlockchainTransport, HttpEndpointManager, ProviderLimiter, TransportError,
+		ROTATE_ON_ERROR_CODES,
+	},
+	blockwatcher::{EndpointCapabilities, EndpointCapabilityRegistry},
 };
+use openzeppelin_monitor::utils::{RetryBudget, RetryConfig, RetryPolicy};
 
 use crate::integration::mocks::{AlwaysFailsToUpdateClientTransport, MockTransport};
 
@@ -896,6 +901,80 @@ async fn test_jsonrpc_error_no_fallback() {
 	mock.assert();
 }
 
+/// Errors caused by the request itself (e.g. an `eth_getLogs` range too large) are returned
+/// at once, without trying the fallbacks that would reject the request the same way.
+#[tokio::test]
+async fn test_request_errors_do_not_rotate() {
+	let mut primary_server = Server::new_async().await;
+	let mut fallback_server = Server::new_async().await;
+
+	let jsonrpc_mock = primary_server
+		.mock("POST", "/")
+		.match_body(mockito::Matcher::PartialJson(json!({"method": "eth_getLogs"})))
+		.with_status(200)
+		.with_header("content-type", "application/json")
+		.with_body(
+			r#"{"id":1,"jsonrpc":"2.0","error":{"message":"Query returned more than 10000 results","code":-32005}}"#,
+		)
+		.expect(1)
+		.create_async()
+		.await;
+	let fallback_mock = fallback_server
+		.mock("POST", "/")
+		.expect(0)
+		.create_async()
+		.await;
+
+	let manager = HttpEndpointManager::new(
+		get_mock_client_builder(),
+		primary_server.url().as_ref(),
+		vec![fallback_server.url()],
+		TEST_NETWORK_SLUG.to_string(),
+		&[],
+	)
+	.with_request_errors("eth_getLogs", vec!["query returned more than".to_string()]);
+	let transport = MockTransport::new();
+
+	let err = manager
+		.send_raw_request(&transport, "eth_getLogs", Some(json!([{}])))
+		.await
+		.unwrap_err();
+	assert!(matches!(err, TransportError::RpcError { code: -32005, .. }));
+	jsonrpc_mock.assert();
+
+	fallback_mock.assert();
+	assert_eq!(&*manager.active_url.read().await, &primary_server.url());
+
+	// HTTP errors with a rotating status code are returned at once as well
+	let mut http_server = Server::new_async().await;
+	let http_mock = http_server
+		.mock("POST", "/")
+		.with_status(400)
+		.with_body("Log response size exceeded. Query returned more than 10000 results")
+		.expect(1)
+		.create_async()
+		.await;
+	let manager = HttpEndpointManager::new(
+		get_mock_client_builder(),
+		http_server.url().as_ref(),
+		vec![fallback_server.url()],
+		TEST_NETWORK_SLUG.to_string(),
+		&[],
+	)
+	.with_request_errors("eth_getLogs", vec!["Query Returned More Than".to_string()]);
+
+	match manager
+		.send_raw_request(&transport, "eth_getLogs", Some(json!([{}])))
+		.await
+		.unwrap_err()
+	{
+		TransportError::Http { status_code, .. } => assert_eq!(status_code, 400),
+		other => panic!("Expected Http error, got {:?}", other),
+	}
+	http_mock.assert();
+	fallback_mock.assert();
+}
+
 /// JSON-RPC error codes in the per-transport skip-list (e.g. Solana's skipped-slot codes)
 /// must be passed through to the caller without rotating, so per-client handlers can
 /// distinguish "legitimate chain state" from "broken endpoint".
@@ -1131,3 +1210,191 @@ async fn test_malformed_envelope_all_endpoints_fail_does_not_loop() {
 	mock_a.assert();
 	mock_b.assert();
 }
+
+#[tokio::test]
+async fn test_rate_limit_pauses_provider_across_networks() {
+	let mut shared_server = Server::new_async().await;
+	let mut fallback_server = Server::new_async().await;
+	let mut free_server = Server::new_async().await;
+	let other_server = Server::new_async().await;
+
+	let shared_mock = shared_server
+		.mock("POST", "/")
+		.with_status(429)
+		.with_header("retry-after", "30")
+		.with_body("Rate limited")
+		.expect(1)
+		.create_async()
+		.await;
+	let fallback_mock = fallback_server
+		.mock("POST", "/")
+		.with_status(200)
+		.with_header("content-type", "application/json")
+		.with_body(r#"{"jsonrpc": "2.0", "result": "success", "id": 1}"#)
+		.create_async()
+		.await;
+	let free_mock = free_server
+		.mock("GET", "/")
+		.with_status(200)
+		.create_async()
+		.await;
+
+	// Both networks use an endpoint of the same provider
+	let provider = Arc::new(ProviderLimiter::new("shared", None));
+	let first_network = HttpEndpointManager::new(
+		get_mock_client_builder(),
+		shared_server.url().as_ref(),
+		vec![fallback_server.url()],
+		"first-network".to_string(),
+		&[],
+	)
+	.with_providers(HashMap::from([(
+		shared_server.url(),
+		Arc::clone(&provider),
+	)]));
+	let second_network = HttpEndpointManager::new(
+		get_mock_client_builder(),
+		other_server.url().as_ref(),
+		vec![shared_server.url(), free_server.url()],
+		"second-network".to_string(),
+		&[],
+	)
+	.with_providers(HashMap::from([(
+		shared_server.url(),
+		Arc::clone(&provider),
+	)]));
+	let transport = MockTransport::new();
+
+	let result = first_network
+		.send_raw_request(&transport, "test_method", Some(json!(["param1"])))
+		.await
+		.unwrap();
+	assert_eq!(result["result"], "success");
+	assert!(provider.is_backing_off());
+
+	// The other network rotates away from the paused provider
+	let new_url = second_network.try_rotate_url(&transport).await.unwrap();
+	assert_eq!(new_url, free_server.url());
+
+	shared_mock.assert();
+	fallback_mock.assert();
+	free_mock.assert();
+}
+
+#[tokio::test]
+async fn test_trace_requests_are_sent_to_a_capable_endpoint() {
+	let mut primary_server = Server::new_async().await;
+	let mut tracing_server = Server::new_async().await;
+
+	let primary_mock = primary_server
+		.mock("POST", "/")
+		.with_status(200)
+		.with_header("content-type", "application/json")
+		.with_body(r#"{"jsonrpc": "2.0", "result": "0x1", "id": 1}"#)
+		.expect(1)
+		.create_async()
+		.await;
+	let connect_mock = tracing_server
+		.mock("GET", "/")
+		.with_status(200)
+		.create_async()
+		.await;
+	let trace_mock = tracing_server
+		.mock("POST", "/")
+		.with_status(200)
+		.with_header("content-type", "application/json")
+		.with_body(r#"{"jsonrpc": "2.0", "result": {"type": "CALL"}, "id": 1}"#)
+		.expect(2)
+		.create_async()
+		.await;
+
+	let network_slug = "trace-routing-network";
+	let capabilities = EndpointCapabilityRegistry::default();
+	capabilities.record(
+		network_slug,
+		&primary_server.url(),
+		EndpointCapabilities::default(),
+	);
+	capabilities.record(
+		network_slug,
+		&tracing_server.url(),
+		EndpointCapabilities {
+			debug_trace: true,
+			..Default::default()
+		},
+	);
+	let manager = HttpEndpointManager::new(
+		get_mock_client_builder(),
+		primary_server.url().as_ref(),
+		vec![tracing_server.url()],
+		network_slug.to_string(),
+		&[],
+	)
+	.with_capabilities(Arc::new(capabilities));
+	let transport = MockTransport::new();
+
+	// Requests served by every endpoint stay on the active one
+	manager
+		.send_raw_request(&transport, "eth_blockNumber", None::<Value>)
+		.await
+		.unwrap();
+	assert_eq!(&*manager.active_url.read().await, &primary_server.url());
+
+	// Traces move to the endpoint serving them, and stay there
+	for _ in 0..2 {
+		let result = manager
+			.send_raw_request(
+				&transport,
+				"debug_traceTransaction",
+				Some(json!(["0x01", { "tracer": "callTracer" }])),
+			)
+			.await
+			.unwrap();
+		assert_eq!(result["result"]["type"], "CALL");
+	}
+	assert_eq!(&*manager.active_url.read().await, &tracing_server.url());
+
+	primary_mock.assert();
+	connect_mock.assert();
+	trace_mock.assert();
+}
+
+#[tokio::test]
+async fn test_rotation_bounded_by_retry_policy() {
+	let mut primary_server = Server::new_async().await;
+	let fallback_server = Server::new_async().await;
+
+	let primary_mock = primary_server
+		.mock("POST", "/")
+		.with_status(503)
+		.with_body("Service Unavailable")
+		.expect(1)
+		.create_async()
+		.await;
+
+	// The policy allows no retry, the fallback is not tried
+	let manager = HttpEndpointManager::new(
+		get_mock_client_builder(),
+		primary_server.url().as_ref(),
+		vec![fallback_server.url()],
+		TEST_NETWORK_SLUG.to_string(),
+		&[],
+	)
+	.with_retry_policy(RetryPolicy::with_budget(
+		"rpc",
+		&RetryConfig {
+			max_retries: 0,
+			..Default::default()
+		},
+		Arc::new(RetryBudget::new(0)),
+	));
+	let transport = MockTransport::new();
+
+	let result = manager
+		.send_raw_request(&transport, "test_method", Some(json!(["param1"])))
+		.await;
+	assert!(result.is_err());
+	assert_eq!(&*manager.active_url.read().await, &primary_server.url());
+
+	primary_mock.assert();
+}
@@ -1,6 +1,9 @@
 use mockito::Server;
 use openzeppelin_monitor::{
-	services::blockchain::{BlockchainTransport, HttpTransportClient, RotatingTransport},
+	services::blockchain::{
+		BlockchainTransport, HttpTransportClient, RotatingTransport, RpcCache, RpcCacheMode,
+		TransportContext,
+	},
 	utils::RetryConfig,
 };
 use reqwest_middleware::ClientBuilder;
@@ -279,3 +282,69 @@ async fn test_update_endpoint_manager_client() {
 	initial_request_mock.assert();
 	updated_mock.assert();
 }
+
+#[tokio::test]
+async fn test_send_raw_request_record_and_replay() {
+	let mut server = Server::new_async().await;
+	let network_mock = create_http_valid_server_mock_network_response(&mut server);
+	let request_mock = server
+		.mock("POST", "/")
+		.match_body(r#"{"id":1,"jsonrpc":"2.0","method":"testMethod","params":{"key":"value"}}"#)
+		.with_header("content-type", "application/json")
+		.with_status(200)
+		.with_body(r#"{"jsonrpc":"2.0","result":{"data":"recorded"},"id":1}"#)
+		.expect(1)
+		.create();
+
+	let cache_dir = tempfile::tempdir().unwrap();
+	let network = create_evm_test_network_with_urls(vec![&server.url()]);
+	let recorder = HttpTransportClient::new_with_cache(
+		&network,
+		None,
+		&[],
+		Some(RpcCache::new(
+			RpcCacheMode::Record,
+			cache_dir.path().to_path_buf(),
+		)),
+		&TransportContext::default(),
+	)
+	.await
+	.unwrap();
+
+	let recorded = recorder
+		.send_raw_request("testMethod", Some(json!({"key": "value"})))
+		.await
+		.unwrap();
+	assert_eq!(recorded["result"]["data"], "recorded");
+	network_mock.assert();
+	request_mock.assert();
+
+	// Replay does not reach the server, even to test the connection
+	drop(server);
+	let replayer = HttpTransportClient::new_with_cache(
+		&network,
+		None,
+		&[],
+		Some(RpcCache::new(
+			RpcCacheMode::Replay,
+			cache_dir.path().to_path_buf(),
+		)),
+		&TransportContext::default(),
+	)
+	.await
+	.unwrap();
+
+	let replayed = replayer
+		.send_raw_request("testMethod", Some(json!({"key": "value"})))
+		.await
+		.unwrap();
+	assert_eq!(replayed, recorded);
+
+	let missing = replayer
+		.send_raw_request("testMethod", Some(json!({"key": "other"})))
+		.await;
+	assert!(missing
+		.unwrap_err()
+		.to_string()
+		.contains("No recorded response for testMethod"));
+}
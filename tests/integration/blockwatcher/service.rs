@@ -9,9 +9,12 @@ use crate::integration::mocks::{
 };
 use openzeppelin_monitor::{
 	models::{BlockChainType, BlockType, MaxPastBlocks, Network, ProcessedBlock},
-	services::blockwatcher::{
-		process_new_blocks, BlockCheckResult, BlockTracker, BlockTrackerTrait, BlockWatcherError,
-		BlockWatcherService, NetworkBlockWatcher,
+	services::{
+		blockwatcher::{
+			process_new_blocks, BlockCheckResult, BlockFailures, BlockTracker, BlockTrackerTrait,
+			BlockWatcherError, BlockWatcherService, NetworkBlockWatcher,
+		},
+		trigger::LatencyTracker,
 	},
 	utils::get_cron_interval_ms,
 };
@@ -278,6 +281,8 @@ async fn test_normal_block_range() {
 		block_handler,
 		trigger_handler,
 		block_tracker_arc,
+		Arc::new(BlockFailures::default()),
+		Arc::new(LatencyTracker::default()),
 	)
 	.await;
 
@@ -325,6 +330,8 @@ async fn test_fresh_start_processing() {
 		block_handler,
 		trigger_handler,
 		Arc::new(block_tracker),
+		Arc::new(BlockFailures::default()),
+		Arc::new(LatencyTracker::default()),
 	)
 	.await;
 
@@ -371,6 +378,8 @@ async fn test_no_new_blocks() {
 		block_handler,
 		trigger_handler,
 		Arc::new(block_tracker),
+		Arc::new(BlockFailures::default()),
+		Arc::new(LatencyTracker::default()),
 	)
 	.await;
 
@@ -450,6 +459,8 @@ async fn test_concurrent_processing() {
 		block_handler,
 		trigger_handler,
 		Arc::new(block_tracker),
+		Arc::new(BlockFailures::default()),
+		Arc::new(LatencyTracker::default()),
 	)
 	.await;
 
@@ -550,6 +561,8 @@ async fn test_ordered_trigger_handling() {
 		block_handler,
 		trigger_handler,
 		Arc::new(block_tracker),
+		Arc::new(BlockFailures::default()),
+		Arc::new(LatencyTracker::default()),
 	)
 	.await;
 
@@ -618,6 +631,8 @@ async fn test_block_storage_enabled() {
 		block_handler,
 		trigger_handler,
 		Arc::new(block_tracker),
+		Arc::new(BlockFailures::default()),
+		Arc::new(LatencyTracker::default()),
 	)
 	.await;
 
@@ -671,6 +686,8 @@ async fn test_max_past_blocks_limit() {
 		block_handler,
 		trigger_handler,
 		Arc::new(block_tracker),
+		Arc::new(BlockFailures::default()),
+		Arc::new(LatencyTracker::default()),
 	)
 	.await;
 
@@ -749,6 +766,8 @@ async fn test_max_past_blocks_limit_recommended() {
 		block_handler,
 		trigger_handler,
 		Arc::new(block_tracker),
+		Arc::new(BlockFailures::default()),
+		Arc::new(LatencyTracker::default()),
 	)
 	.await;
 
@@ -802,6 +821,8 @@ async fn test_confirmation_blocks() {
 		block_handler,
 		trigger_handler,
 		Arc::new(block_tracker),
+		Arc::new(BlockFailures::default()),
+		Arc::new(LatencyTracker::default()),
 	)
 	.await;
 
@@ -850,6 +871,8 @@ async fn test_process_new_blocks_storage_error() {
 		block_handler,
 		trigger_handler,
 		Arc::new(MockBlockTracker::default()),
+		Arc::new(BlockFailures::default()),
+		Arc::new(LatencyTracker::default()),
 	)
 	.await;
 
@@ -898,6 +921,8 @@ async fn test_process_new_blocks_network_errors() {
 		block_handler,
 		trigger_handler,
 		Arc::new(MockBlockTracker::default()),
+		Arc::new(BlockFailures::default()),
+		Arc::new(LatencyTracker::default()),
 	)
 	.await;
 
@@ -949,6 +974,8 @@ async fn test_process_new_blocks_get_blocks_error() {
 		block_handler,
 		trigger_handler,
 		Arc::new(MockBlockTracker::default()),
+		Arc::new(BlockFailures::default()),
+		Arc::new(LatencyTracker::default()),
 	)
 	.await;
 
@@ -1032,6 +1059,8 @@ async fn test_process_new_blocks_storage_save_error() {
 		block_handler,
 		trigger_handler,
 		Arc::new(block_tracker),
+		Arc::new(BlockFailures::default()),
+		Arc::new(LatencyTracker::default()),
 	)
 	.await;
 
@@ -1110,6 +1139,8 @@ async fn test_process_new_blocks_save_last_processed_error() {
 		block_handler,
 		trigger_handler,
 		Arc::new(block_tracker),
+		Arc::new(BlockFailures::default()),
+		Arc::new(LatencyTracker::default()),
 	)
 	.await;
 
@@ -1191,6 +1222,8 @@ async fn test_process_new_blocks_storage_delete_error() {
 		block_handler,
 		trigger_handler,
 		Arc::new(block_tracker),
+		Arc::new(BlockFailures::default()),
+		Arc::new(LatencyTracker::default()),
 	)
 	.await;
 
@@ -1428,6 +1461,8 @@ async fn test_process_new_blocks_get_blocks_error_fresh_start() {
 		block_handler,
 		trigger_handler,
 		Arc::new(MockBlockTracker::default()),
+		Arc::new(BlockFailures::default()),
+		Arc::new(LatencyTracker::default()),
 	)
 	.await;
 
@@ -2241,6 +2276,8 @@ async fn test_missed_block_detection_and_saving() {
 		block_handler,
 		trigger_handler,
 		Arc::new(block_tracker),
+		Arc::new(BlockFailures::default()),
+		Arc::new(LatencyTracker::default()),
 	)
 	.await;
 
@@ -2331,6 +2368,8 @@ async fn test_missed_block_save_error() {
 		block_handler,
 		trigger_handler,
 		Arc::new(block_tracker),
+		Arc::new(BlockFailures::default()),
+		Arc::new(LatencyTracker::default()),
 	)
 	.await;
 
@@ -2424,6 +2463,8 @@ async fn test_missed_block_detection_store_disabled() {
 		block_handler,
 		trigger_handler,
 		Arc::new(block_tracker),
+		Arc::new(BlockFailures::default()),
+		Arc::new(LatencyTracker::default()),
 	)
 	.await;
 
@@ -2701,6 +2742,8 @@ async fn test_duplicate_block_detection() {
 		block_handler,
 		trigger_handler,
 		Arc::new(block_tracker),
+		Arc::new(BlockFailures::default()),
+		Arc::new(LatencyTracker::default()),
 	)
 	.await;
 
@@ -2787,6 +2830,8 @@ async fn test_missed_blocks_with_store_blocks_none() {
 		block_handler,
 		trigger_handler,
 		Arc::new(block_tracker),
+		Arc::new(BlockFailures::default()),
+		Arc::new(LatencyTracker::default()),
 	)
 	.await;
 
@@ -2857,6 +2902,8 @@ async fn test_empty_blocks_from_rpc_despite_having_new_blocks() {
 		block_handler,
 		trigger_handler,
 		Arc::new(block_tracker),
+		Arc::new(BlockFailures::default()),
+		Arc::new(LatencyTracker::default()),
 	)
 	.await;
 
@@ -2962,6 +3009,8 @@ async fn test_out_of_order_in_cleanup_phase() {
 		block_handler,
 		trigger_handler,
 		Arc::new(block_tracker),
+		Arc::new(BlockFailures::default()),
+		Arc::new(LatencyTracker::default()),
 	)
 	.await;
 
@@ -3071,6 +3120,8 @@ async fn test_duplicate_detection_main_loop() {
 		block_handler,
 		trigger_handler,
 		Arc::new(block_tracker),
+		Arc::new(BlockFailures::default()),
+		Arc::new(LatencyTracker::default()),
 	)
 	.await;
 
@@ -3229,6 +3280,8 @@ async fn test_out_of_order_detection_main_loop() {
 		block_handler,
 		trigger_handler,
 		Arc::new(block_tracker),
+		Arc::new(BlockFailures::default()),
+		Arc::new(LatencyTracker::default()),
 	)
 	.await;
 
@@ -3343,6 +3396,8 @@ async fn test_duplicate_detection_in_cleanup_phase() {
 		block_handler,
 		trigger_handler,
 		Arc::new(block_tracker),
+		Arc::new(BlockFailures::default()),
+		Arc::new(LatencyTracker::default()),
 	)
 	.await;
 
@@ -16,23 +16,31 @@ use openzeppelin_monitor::{
 		process_block,
 	},
 	models::{
-		AddressWithSpec, BlockChainType, ContractSpec, EVMContractSpec, EVMMonitorMatch,
-		EVMTransactionReceipt, MatchConditions, Monitor, MonitorMatch, ProcessedBlock,
-		ScriptLanguage, SecretString, SecretValue, StellarBlock, StellarContractSpec,
-		StellarFormattedContractSpec, StellarMonitorMatch, TransactionType, Trigger,
-		TriggerConditions,
+		AddressWithSpec, BlockChainType, BlockGuardrails, BlockType, ContractSpec, EVMContractSpec,
+		EVMMonitorMatch, EVMTransactionReceipt, MatchConditions, Monitor, MonitorMatch,
+		ProcessedBlock, ScriptLanguage, SecretString, SecretValue, StellarBlock,
+		StellarContractSpec, StellarFormattedContractSpec, StellarMonitorMatch, TransactionType,
+		Trigger, TriggerConditions, DEFAULT_CONFIG_DIR,
 	},
 	services::{
-		filter::{stellar_helpers::are_same_address, FilterService},
+		blockwatcher::BlockFailures,
+		filter::{
+			state::InMemoryWindowStore, stellar_helpers::are_same_address, BlockStreamExt,
+			FilterContext, FilterPipeline, FilterService,
+		},
 		notification::NotificationService,
-		trigger::{TriggerExecutionService, TriggerExecutionServiceTrait},
+		trigger::{
+			LatencyTracker, NotificationOrdering, PendingNotifications, PriorityDispatchQueue,
+			TriggerExecutionService, TriggerExecutionServiceTrait,
+		},
 	},
 	utils::{
+		metrics::OVERSIZED_BLOCKS_TOTAL,
 		tests::{
 			evm::{monitor::MonitorBuilder, transaction::TransactionBuilder},
 			trigger::TriggerBuilder,
 		},
-		RetryConfig,
+		RetryBudgets, RetryConfig,
 	},
 };
 use std::str::FromStr;
@@ -40,8 +48,9 @@ use stellar_xdr::curr::{
 	ScSpecEntry, ScSpecFunctionInputV0, ScSpecFunctionV0, ScSpecTypeDef, ScSymbol, StringM,
 };
 
+use futures::StreamExt;
 use serde_json::json;
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::HashMap, path::Path, sync::Arc};
 use tokio::sync::watch;
 
 fn create_test_monitor(
@@ -75,7 +84,10 @@ fn create_test_monitor_match(chain: BlockChainType) -> MonitorMatch {
 			receipt: Some(EVMTransactionReceipt::default()),
 			logs: Some(vec![]),
 			matched_on: MatchConditions::default(),
+			matched_on_audit: Vec::new(),
 			matched_on_args: None,
+			block_timestamp: None,
+			trace: None,
 		})),
 		BlockChainType::Stellar => MonitorMatch::Stellar(Box::new(StellarMonitorMatch {
 			monitor: create_test_monitor("test", vec!["stellar_mainnet"], false, vec![]),
@@ -87,6 +99,7 @@ fn create_test_monitor_match(chain: BlockChainType) -> MonitorMatch {
 			ledger: StellarBlock::default(),
 			matched_on: MatchConditions::default(),
 			matched_on_args: None,
+			missing_data: Vec::new(),
 		})),
 		_ => panic!("Unsupported chain"),
 	}
@@ -135,9 +148,11 @@ async fn test_initialize_services() {
 		MockNetworkRepository,
 		MockTriggerRepository,
 	>(
+		Path::new(DEFAULT_CONFIG_DIR),
 		Some(mock_monitor_service),
 		Some(mock_network_service),
 		Some(mock_trigger_service),
+		RetryBudgets::default(),
 	)
 	.await
 	.expect("Failed to initialize services");
@@ -200,6 +215,8 @@ async fn test_create_block_handler_evm() {
 		monitors,
 		client_pool,
 		contract_specs,
+		Arc::new(BlockFailures::default()),
+		Arc::new(LatencyTracker::default()),
 	);
 
 	let result = block_handler(block, network).await;
@@ -233,6 +250,13 @@ async fn test_create_trigger_handler() {
 		shutdown_tx,
 		Arc::new(trigger_execution_service),
 		HashMap::new(),
+		NotificationOrdering::None,
+		false,
+		Arc::new(LatencyTracker::default()),
+		Arc::new(PriorityDispatchQueue::new(0)),
+		Arc::new(InMemoryWindowStore::new()),
+		Arc::new(PendingNotifications::default()),
+		FilterContext::default(),
 	);
 
 	assert!(Arc::strong_count(&trigger_handler) == 1);
@@ -261,6 +285,13 @@ async fn test_create_trigger_handler_empty_matches() {
 		shutdown_tx,
 		Arc::new(trigger_execution_service),
 		HashMap::new(),
+		NotificationOrdering::None,
+		false,
+		Arc::new(LatencyTracker::default()),
+		Arc::new(PriorityDispatchQueue::new(0)),
+		Arc::new(InMemoryWindowStore::new()),
+		Arc::new(PendingNotifications::default()),
+		FilterContext::default(),
 	);
 
 	assert!(Arc::strong_count(&trigger_handler) == 1);
@@ -353,6 +384,8 @@ async fn test_create_block_handler_stellar() {
 		monitors,
 		Arc::new(handle_block_client_pool),
 		contract_specs,
+		Arc::new(BlockFailures::default()),
+		Arc::new(LatencyTracker::default()),
 	);
 	let result = block_handler(block, network).await;
 
@@ -394,6 +427,8 @@ async fn test_create_block_handler_evm_client_error() {
 		monitors,
 		client_pool,
 		contract_specs,
+		Arc::new(BlockFailures::default()),
+		Arc::new(LatencyTracker::default()),
 	);
 	let result = block_handler(block, network).await;
 
@@ -433,6 +468,8 @@ async fn test_create_block_handler_stellar_client_error() {
 		monitors,
 		client_pool,
 		contract_specs,
+		Arc::new(BlockFailures::default()),
+		Arc::new(LatencyTracker::default()),
 	);
 
 	let result = block_handler(block, network).await;
@@ -483,6 +520,13 @@ print(True)  # Always return true for test
 		shutdown_tx,
 		Arc::new(trigger_execution_service),
 		trigger_scripts,
+		NotificationOrdering::None,
+		false,
+		Arc::new(LatencyTracker::default()),
+		Arc::new(PriorityDispatchQueue::new(0)),
+		Arc::new(InMemoryWindowStore::new()),
+		Arc::new(PendingNotifications::default()),
+		FilterContext::default(),
 	);
 
 	assert!(Arc::strong_count(&trigger_handler) == 1);
@@ -494,6 +538,7 @@ print(True)  # Always return true for test
 		language: ScriptLanguage::Python,
 		timeout_ms: 1000,
 		arguments: None,
+		expression: None,
 	}];
 
 	let processed_block = ProcessedBlock {
@@ -506,7 +551,10 @@ print(True)  # Always return true for test
 			logs: Some(vec![]),
 			network_slug: "ethereum_mainnet".to_string(),
 			matched_on: MatchConditions::default(),
+			matched_on_audit: Vec::new(),
 			matched_on_args: None,
+			block_timestamp: None,
+			trace: None,
 		}))],
 	};
 
@@ -549,6 +597,7 @@ async fn test_process_block() {
 		&monitors,
 		None,
 		&filter_service,
+		&BlockFailures::default(),
 		&mut shutdown_rx,
 	)
 	.await;
@@ -563,6 +612,88 @@ async fn test_process_block() {
 	);
 }
 
+#[tokio::test]
+async fn test_process_oversized_block_in_chunks() {
+	let mut mock_client = MockEvmClientTrait::<MockEVMTransportClient>::new();
+	let mut network = create_test_network("Ethereum", "ethereum_oversized", BlockChainType::EVM);
+	network.block_guardrails = Some(BlockGuardrails {
+		max_block_transactions: 4,
+		chunk_size: 2,
+	});
+	let mut block = create_test_block(BlockChainType::EVM, 100);
+	if let BlockType::EVM(evm_block) = &mut block {
+		evm_block.0.transactions = (0..5).map(|_| TransactionBuilder::new().build()).collect();
+	}
+	let monitors = vec![create_test_monitor(
+		"test",
+		vec!["ethereum_oversized"],
+		false,
+		vec![],
+	)];
+	let filter_service = FilterService::new();
+
+	#[allow(unused_variables)]
+	let (shutdown_tx, mut shutdown_rx) = watch::channel(false);
+
+	// The logs of the block are fetched once for its 3 chunks
+	mock_client
+		.expect_get_logs_for_blocks()
+		.times(1)
+		.returning(|_, _, _| Ok(vec![]));
+
+	let result = process_block(
+		&mock_client,
+		&network,
+		&block,
+		&monitors,
+		None,
+		&filter_service,
+		&BlockFailures::default(),
+		&mut shutdown_rx,
+	)
+	.await;
+
+	assert!(result.is_some());
+	let oversized = OVERSIZED_BLOCKS_TOTAL
+		.get_metric_with_label_values(&["ethereum_oversized"])
+		.unwrap();
+	assert_eq!(oversized.get(), 1.0);
+}
+
+#[tokio::test]
+async fn test_filter_pipeline_stream() {
+	let mut mock_client = MockEvmClientTrait::<MockEVMTransportClient>::new();
+	let network = create_test_network("Ethereum", "ethereum_mainnet", BlockChainType::EVM);
+	let monitors = vec![create_test_monitor(
+		"test",
+		vec!["ethereum_mainnet"],
+		false,
+		vec![],
+	)];
+
+	mock_client
+		.expect_get_logs_for_blocks()
+		.times(3)
+		.returning(|_, _, _| Ok(vec![]));
+
+	let pipeline =
+		FilterPipeline::new(Arc::new(mock_client), network, monitors).with_concurrency(2);
+	let blocks = futures::stream::iter(
+		(100..103).map(|number| create_test_block(BlockChainType::EVM, number)),
+	);
+	let processed: Vec<_> = blocks.filter_matches(pipeline).collect().await;
+
+	let block_numbers: Vec<_> = processed
+		.into_iter()
+		.map(|result| {
+			let processed_block = result.unwrap();
+			assert_eq!(processed_block.network_slug, "ethereum_mainnet");
+			processed_block.block_number
+		})
+		.collect();
+	assert_eq!(block_numbers, vec![100, 101, 102]);
+}
+
 #[tokio::test]
 #[ignore]
 /// Skipping as this test is flaky and fails intermittently
@@ -593,6 +724,7 @@ async fn test_process_block_with_shutdown() {
 		&monitors,
 		None,
 		&filter_service,
+		&BlockFailures::default(),
 		&mut shutdown_rx,
 	)
 	.await;
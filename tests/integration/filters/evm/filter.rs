@@ -3,20 +3,27 @@
 //! Tests the monitoring functionality for EVM-compatible blockchains,
 //! including event and transaction filtering.
 
-use alloy::primitives::Uint;
+use alloy::primitives::{Address, Uint, U256};
 use serde_json::json;
-use std::collections::HashMap;
+use std::{collections::HashMap, str::FromStr};
 
 use openzeppelin_monitor::{
 	models::{
-		BlockType, ContractSpec, EVMReceiptLog, EVMTransactionReceipt, EventCondition,
-		FunctionCondition, Monitor, MonitorMatch, TransactionCondition, TransactionStatus,
+		BlockType, ChainConfiguration, ContractSpec, EVMMonitorConfig, EVMMonitorMatch,
+		EVMReceiptLog, EVMTransactionReceipt, EventCondition, FunctionCondition, MatchConditions,
+		Monitor, MonitorMatch, PostProcessingScript, ScriptLanguage, TransactionCondition,
+		TransactionStatus,
 	},
 	services::{
 		blockchain::{EvmClient, TransportError},
 		filter::{handle_match, FilterError, FilterService},
 	},
-	utils::tests::evm::{receipt::ReceiptBuilder, transaction::TransactionBuilder},
+	utils::{
+		normalize_string,
+		tests::evm::{
+			monitor::MonitorBuilder, receipt::ReceiptBuilder, transaction::TransactionBuilder,
+		},
+	},
 };
 
 use crate::integration::{
@@ -605,6 +612,71 @@ async fn test_handle_match() -> Result<(), Box<FilterError>> {
 	Ok(())
 }
 
+#[tokio::test]
+async fn test_handle_match_with_post_processing() -> Result<(), Box<FilterError>> {
+	let test_data = TestDataBuilder::new("evm").build();
+	let filter_service = FilterService::new();
+	let mock_transport = setup_mock_transport(test_data.clone());
+	let client = EvmClient::new_with_transport(mock_transport);
+
+	let mut monitor = test_data.monitor;
+	monitor.post_processing = Some(PostProcessingScript {
+		script_path: "enrich.sh".to_string(),
+		arguments: None,
+		language: ScriptLanguage::Bash,
+		timeout_ms: 1000,
+	});
+	let trigger_scripts = HashMap::from([(
+		format!("{}|enrich.sh", normalize_string(&monitor.name)),
+		(
+			ScriptLanguage::Bash,
+			"cat > /dev/null; echo 'looking up owner'; \
+			 echo '{\"owner\": \"treasury\", \"risk\": {\"score\": 7}}'"
+				.to_string(),
+		),
+	)]);
+
+	let mut trigger_execution_service =
+		setup_trigger_execution_service("tests/integration/fixtures/evm/triggers/trigger.json")
+			.await;
+	trigger_execution_service
+		.expect_execute()
+		.withf(
+			|_trigger_name, variables, _monitor_match, _trigger_scripts| {
+				variables.get("enrichment.owner") == Some(&"treasury".to_string())
+					&& variables.get("enrichment.risk.score") == Some(&"7".to_string())
+					&& variables.get("monitor.name") == Some(&"Mint USDC Token".to_string())
+			},
+		)
+		.once()
+		.returning(|_, _, _, _| Ok(()));
+
+	let contract_with_spec: (String, ContractSpec) = (
+		"0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".to_string(),
+		test_data.contract_spec.unwrap(),
+	);
+	let matches = filter_service
+		.filter_block(
+			&client,
+			&test_data.network,
+			&test_data.blocks[0],
+			&[monitor],
+			Some(&[contract_with_spec]),
+		)
+		.await?;
+	assert_eq!(matches.len(), 1);
+
+	let result = handle_match(
+		matches[0].clone(),
+		&trigger_execution_service,
+		&trigger_scripts,
+	)
+	.await;
+	assert!(result.is_ok());
+
+	Ok(())
+}
+
 #[tokio::test]
 async fn test_handle_match_with_no_args() -> Result<(), Box<FilterError>> {
 	// Load test data using common utility
@@ -695,6 +767,66 @@ async fn test_handle_match_with_no_args() -> Result<(), Box<FilterError>> {
 	Ok(())
 }
 
+#[tokio::test]
+async fn test_handle_match_with_funds_flow() -> Result<(), Box<FilterError>> {
+	let treasury = Address::from_str("0x00000000000000000000000000000000000000aa").unwrap();
+	let attacker = Address::from_str("0x00000000000000000000000000000000000000bb").unwrap();
+	let usdc = Address::from_str("0x00000000000000000000000000000000000000cc").unwrap();
+
+	let monitor = MonitorBuilder::new()
+		.name("Treasury outflows")
+		.triggers(vec!["example_trigger_slack".to_string()])
+		.chain_configurations(vec![ChainConfiguration {
+			evm: Some(EVMMonitorConfig {
+				funds_flow: true,
+				..Default::default()
+			}),
+			..Default::default()
+		}])
+		.build();
+	let receipt = ReceiptBuilder::new()
+		.contract_address(usdc)
+		.from(treasury)
+		.to(attacker)
+		.value(U256::from(1_200_000u64))
+		.build();
+	let transaction = TransactionBuilder::new().from(attacker).to(usdc).build();
+
+	let monitor_match = MonitorMatch::EVM(Box::new(EVMMonitorMatch {
+		monitor,
+		transaction,
+		receipt: Some(receipt.clone()),
+		logs: Some(receipt.logs.clone()),
+		network_slug: "ethereum_mainnet".to_string(),
+		matched_on: MatchConditions::default(),
+		matched_on_audit: Vec::new(),
+		matched_on_args: None,
+		block_timestamp: None,
+		trace: None,
+	}));
+
+	let mut trigger_execution_service =
+		setup_trigger_execution_service("tests/integration/fixtures/evm/triggers/trigger.json")
+			.await;
+	trigger_execution_service
+		.expect_execute()
+		.withf(|_trigger_name, variables, _monitor_match, _trigger_scripts| {
+			variables.get(
+				"funds_flow.0x00000000000000000000000000000000000000aa.0x00000000000000000000000000000000000000cc",
+			) == Some(&"-1200000".to_string())
+				&& variables.get(
+					"funds_flow.0x00000000000000000000000000000000000000bb.0x00000000000000000000000000000000000000cc",
+				) == Some(&"1200000".to_string())
+		})
+		.once()
+		.returning(|_, _, _, _| Ok(()));
+
+	let result = handle_match(monitor_match, &trigger_execution_service, &HashMap::new()).await;
+	assert!(result.is_ok(), "Handle match should succeed");
+
+	Ok(())
+}
+
 #[tokio::test]
 async fn test_handle_match_with_key_collision() -> Result<(), Box<FilterError>> {
 	// Load test data using common utility
@@ -756,6 +888,7 @@ async fn test_handle_match_with_key_collision() -> Result<(), Box<FilterError>>
 			events: vec![],
 			transactions: vec![],
 		},
+		matched_on_audit: Vec::new(),
 		matched_on_args: Some(EVMMatchArguments {
 			functions: Some(vec![EVMMatchParamsMap {
 				signature: "dangerousFunc(bytes32 signature, uint256 value)".to_string(),
@@ -777,6 +910,8 @@ async fn test_handle_match_with_key_collision() -> Result<(), Box<FilterError>>
 			}]),
 			events: None,
 		}),
+		block_timestamp: None,
+		trace: None,
 	};
 
 	let match_wrapper = MonitorMatch::EVM(Box::new(evm_match));
@@ -885,6 +1020,7 @@ async fn test_handle_match_with_duplicate_event_signatures() -> Result<(), Box<F
 			],
 			transactions: vec![],
 		},
+		matched_on_audit: Vec::new(),
 		matched_on_args: Some(EVMMatchArguments {
 			functions: None,
 			events: Some(vec![
@@ -938,6 +1074,8 @@ async fn test_handle_match_with_duplicate_event_signatures() -> Result<(), Box<F
 				},
 			]),
 		}),
+		block_timestamp: None,
+		trace: None,
 	};
 
 	let match_wrapper = MonitorMatch::EVM(Box::new(evm_match));
@@ -35,8 +35,17 @@ fn create_test_network() -> Network {
 		confirmation_blocks: 1,
 		cron_schedule: "*/10 * * * * *".to_string(),
 		max_past_blocks: Some(MaxPastBlocks::Limited(50)),
+		start_block: None,
 		store_blocks: Some(true),
 		recovery_config: None,
+		rpc_consistency_check: None,
+		watchdog: None,
+		poison_block_policy: None,
+		rpc_cost: None,
+		get_logs_limits: None,
+		block_guardrails: None,
+		restart_policy: None,
+		trace_budget: None,
 	}
 }
 
@@ -57,6 +66,19 @@ fn create_test_monitor() -> Monitor {
 		trigger_conditions: vec![],
 		triggers: vec![],
 		chain_configurations: vec![],
+		start_block: None,
+		network_addresses: Default::default(),
+		severity: Default::default(),
+		observe: false,
+		canary: None,
+		activate_on_deployment: None,
+		abi_drift: None,
+		sampling: None,
+		batch: None,
+		trigger_params: Default::default(),
+		owners: Vec::new(),
+		post_processing: None,
+		rpc_overrides: Default::default(),
 	}
 }
 
@@ -955,6 +977,19 @@ async fn test_filter_matches_address_in_inner_instructions() -> Result<(), Box<F
 		trigger_conditions: vec![],
 		triggers: vec![],
 		chain_configurations: vec![],
+		start_block: None,
+		network_addresses: Default::default(),
+		severity: Default::default(),
+		observe: false,
+		canary: None,
+		activate_on_deployment: None,
+		abi_drift: None,
+		sampling: None,
+		batch: None,
+		trigger_params: Default::default(),
+		owners: Vec::new(),
+		post_processing: None,
+		rpc_overrides: Default::default(),
 	};
 
 	// Transaction where top-level instruction is Squads V4,
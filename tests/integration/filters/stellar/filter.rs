@@ -593,6 +593,106 @@ async fn test_monitor_transactions_with_no_expressions() -> Result<(), Box<Filte
 	Ok(())
 }
 
+#[tokio::test]
+async fn test_monitor_transactions_without_events() -> Result<(), Box<FilterError>> {
+	let test_data = TestDataBuilder::new("stellar").build();
+	let filter_service = FilterService::new();
+
+	let monitor = make_monitor_with_transactions(test_data.monitor, false);
+
+	let transactions: Vec<StellarTransactionInfo> =
+		read_and_parse_json("tests/integration/fixtures/stellar/transactions.json");
+
+	let mut mock_client = MockStellarClientTrait::<MockStellarTransportClient>::new();
+	let decoded_transactions: Vec<StellarTransaction> = transactions
+		.iter()
+		.map(|tx| StellarTransaction::from(tx.clone()))
+		.collect();
+
+	mock_client
+		.expect_get_transactions()
+		.times(1)
+		.returning(move |_, _| Ok(decoded_transactions.clone()));
+
+	// The events are unavailable, the ledger is filtered without them
+	mock_client
+		.expect_get_events()
+		.times(1)
+		.returning(|_, _| Err(anyhow::anyhow!("getEvents unavailable")));
+
+	mock_client
+		.expect_get_contract_spec()
+		.returning(move |_| Ok(test_data.contract_spec.clone().unwrap()));
+
+	let matches = filter_service
+		.filter_block(
+			&mock_client,
+			&test_data.network,
+			&test_data.blocks[0],
+			&[monitor],
+			None,
+		)
+		.await?;
+
+	assert!(
+		!matches.is_empty(),
+		"Should have found matching transactions"
+	);
+	match &matches[0] {
+		MonitorMatch::Stellar(stellar_match) => {
+			assert_eq!(stellar_match.matched_on.transactions.len(), 1);
+			assert_eq!(stellar_match.missing_data, vec!["events".to_string()]);
+		}
+		_ => {
+			panic!("Expected Stellar match");
+		}
+	}
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_monitor_events_fails_without_events() -> Result<(), Box<FilterError>> {
+	let test_data = TestDataBuilder::new("stellar").build();
+	let filter_service = FilterService::new();
+
+	let monitor = make_monitor_with_events(test_data.monitor, false);
+
+	let transactions: Vec<StellarTransactionInfo> =
+		read_and_parse_json("tests/integration/fixtures/stellar/transactions.json");
+
+	let mut mock_client = MockStellarClientTrait::<MockStellarTransportClient>::new();
+	let decoded_transactions: Vec<StellarTransaction> = transactions
+		.iter()
+		.map(|tx| StellarTransaction::from(tx.clone()))
+		.collect();
+
+	mock_client
+		.expect_get_transactions()
+		.times(1)
+		.returning(move |_, _| Ok(decoded_transactions.clone()));
+
+	// The events are unavailable, the ledger fails rather than losing its event matches
+	mock_client
+		.expect_get_events()
+		.times(1)
+		.returning(|_, _| Err(anyhow::anyhow!("getEvents unavailable")));
+
+	let result = filter_service
+		.filter_block(
+			&mock_client,
+			&test_data.network,
+			&test_data.blocks[0],
+			&[monitor],
+			None,
+		)
+		.await;
+
+	assert!(matches!(result, Err(FilterError::NetworkError(_))));
+
+	Ok(())
+}
+
 #[tokio::test]
 async fn test_monitor_with_multiple_conditions() -> Result<(), Box<FilterError>> {
 	let test_data = TestDataBuilder::new("stellar").build();
@@ -1033,6 +1133,7 @@ async fn test_handle_match_with_key_collision() -> Result<(), Box<FilterError>>
 			}]),
 			events: None,
 		}),
+		missing_data: Vec::new(),
 	};
 
 	let match_wrapper = MonitorMatch::Stellar(Box::new(stellar_match));
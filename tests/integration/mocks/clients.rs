@@ -27,7 +27,10 @@ use openzeppelin_monitor::{
 			MidnightClientTrait, MidnightSubstrateClientTrait, SignatureInfo, SolanaClientTrait,
 			StellarClientTrait,
 		},
-		filter::{EVMBlockFilter, MidnightBlockFilter, SolanaBlockFilter, StellarBlockFilter},
+		filter::{
+			EVMBlockFilter, FilterContext, MidnightBlockFilter, SolanaBlockFilter,
+			StellarBlockFilter,
+		},
 	},
 };
 
@@ -67,6 +70,15 @@ mock! {
 			to_block: u64,
 			addresses: Option<Vec<String>>,
 		) -> Result<Vec<EVMReceiptLog>,  anyhow::Error>;
+
+		async fn call(&self, address: &str, calldata: &str) -> Result<String, anyhow::Error>;
+
+		async fn get_storage_at(&self, address: &str, slot: &str) -> Result<String, anyhow::Error>;
+
+		async fn trace_transaction(
+			&self,
+			transaction_hash: &str,
+		) -> Result<serde_json::Value, anyhow::Error>;
 	}
 
 	impl<T: Send + Sync + Clone + 'static> Clone for EvmClientTrait<T> {
@@ -226,7 +238,7 @@ impl<T: Send + Sync + Clone + 'static> BlockFilterFactory<MockMidnightClientTrai
 	for MockMidnightClientTrait<T>
 {
 	type Filter = MidnightBlockFilter<MockMidnightClientTrait<T>>;
-	fn filter() -> Self::Filter {
+	fn filter(_context: &FilterContext) -> Self::Filter {
 		MidnightBlockFilter {
 			_client: PhantomData,
 		}
@@ -237,7 +249,7 @@ impl<T: Send + Sync + Clone + 'static> BlockFilterFactory<MockStellarClientTrait
 	for MockStellarClientTrait<T>
 {
 	type Filter = StellarBlockFilter<MockStellarClientTrait<T>>;
-	fn filter() -> Self::Filter {
+	fn filter(_context: &FilterContext) -> Self::Filter {
 		StellarBlockFilter {
 			_client: PhantomData,
 		}
@@ -248,9 +260,10 @@ impl<T: Send + Sync + Clone + 'static> BlockFilterFactory<MockEvmClientTrait<T>>
 	for MockEvmClientTrait<T>
 {
 	type Filter = EVMBlockFilter<MockEvmClientTrait<T>>;
-	fn filter() -> Self::Filter {
+	fn filter(context: &FilterContext) -> Self::Filter {
 		EVMBlockFilter {
 			_client: PhantomData,
+			context: context.clone(),
 		}
 	}
 }
@@ -259,7 +272,7 @@ impl<T: Send + Sync + Clone + 'static> BlockFilterFactory<MockSolanaClientTrait<
 	for MockSolanaClientTrait<T>
 {
 	type Filter = SolanaBlockFilter<MockSolanaClientTrait<T>>;
-	fn filter() -> Self::Filter {
+	fn filter(_context: &FilterContext) -> Self::Filter {
 		SolanaBlockFilter {
 			_client: PhantomData,
 		}
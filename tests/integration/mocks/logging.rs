@@ -1,16 +1,13 @@
 //! Sample integration test for file logging.
 //!
-//! Environment variables used:
-//! - LOG_MODE: "stdout" (default) or "file"
-//! - LOG_LEVEL: log level ("trace", "debug", "info", "warn", "error"); default is "info"
-//! - LOG_DATA_DIR: when using file mode, the path of the log file (default "logs/monitor.log")
-//!   Refer to `src/logging/mod.rs` for more details.
+//! Logging is configured with a [`LoggingConfig`] writing to a temporary directory.
+//! Refer to `src/utils/logging/mod.rs` for more details.
 use chrono::Utc;
 use openzeppelin_monitor::utils::logging::{
-	compute_rolled_file_path, setup_logging, space_based_rolling,
+	compute_rolled_file_path, setup_logging, space_based_rolling, LoggingConfig,
 };
 use std::{
-	env, fs,
+	fs,
 	fs::{create_dir_all, remove_dir_all},
 	io::Write,
 	path::Path,
@@ -19,16 +16,6 @@ use std::{
 };
 use tempfile::TempDir;
 
-use lazy_static::lazy_static;
-
-// Global lazy_static that initializes logging only once.
-lazy_static! {
-	// This will call setup_logging() the first time INIT_LOGGING is dereferenced.
-	static ref INIT_LOGGING: () = {
-		let _ = setup_logging();
-	};
-}
-
 pub fn compute_final_log_path(base_file_path: &str, date_str: &str, max_size: u64) -> String {
 	let time_based_path = compute_rolled_file_path(base_file_path, date_str, 1);
 	space_based_rolling(&time_based_path, base_file_path, date_str, max_size)
@@ -42,12 +29,6 @@ fn test_setup_logging_file_mode_creates_log_file() {
 	let temp_dir = TempDir::new().expect("Failed to create temp dir");
 	let temp_log_dir = temp_dir.path().to_str().unwrap();
 
-	// Unset env var to ensure default values are used and not to interfere with the test.
-	env::remove_var("LOG_MAX_SIZE");
-	env::set_var("LOG_MODE", "file");
-	env::set_var("LOG_LEVEL", "debug");
-	env::set_var("LOG_DATA_DIR", format!("{}/", temp_log_dir));
-
 	// Clean up any previous logs and create the log directory.
 	let _ = remove_dir_all(temp_log_dir);
 
@@ -55,8 +36,13 @@ fn test_setup_logging_file_mode_creates_log_file() {
 
 	create_dir_all(temp_log_dir).expect("Failed to create log directory");
 
-	// Force the lazy_static to initialize logging.
-	*INIT_LOGGING;
+	// Logging is initialized once per process, so only this test sets it up.
+	let _ = setup_logging(&LoggingConfig {
+		to_file: true,
+		level: "debug".to_string(),
+		data_dir: format!("{}/", temp_log_dir),
+		..Default::default()
+	});
 
 	// Sleep for the logger to flush.
 	thread::sleep(Duration::from_millis(200));
@@ -86,11 +72,6 @@ fn test_log_file_rolls_when_existing() {
 	let temp_dir = TempDir::new().expect("Failed to create temp dir");
 	let temp_log_dir = temp_dir.path();
 
-	// Setup environment variables for file logging.
-	env::set_var("LOG_MODE", "file");
-	env::set_var("LOG_LEVEL", "debug");
-	env::set_var("LOG_DATA_DIR", temp_log_dir.to_str().unwrap());
-
 	// Clean up any previous logs in the temporary directory.
 	let _ = fs::remove_dir_all(temp_log_dir);
 	fs::create_dir_all(temp_log_dir).expect("Failed to create log directory");
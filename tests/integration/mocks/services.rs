@@ -9,7 +9,7 @@ use openzeppelin_monitor::{
 		blockchain::BlockFilterFactory,
 		blockwatcher::{
 			BlockCheckResult, BlockStorage, BlockTrackerTrait, JobSchedulerTrait, MissedBlockEntry,
-			MissedBlockStatus,
+			MissedBlockStatus, SkippedBlockEntry,
 		},
 		filter::{FilterError, FilterServiceTrait},
 		notification::NotificationService,
@@ -68,12 +68,17 @@ mock! {
 		async fn save_missed_blocks(&self, network_slug: &str, blocks: &[u64]) -> Result<(), anyhow::Error>;
 		async fn save_last_processed_block(&self, network_slug: &str, block_number: u64) -> Result<(), anyhow::Error>;
 		async fn get_last_processed_block(&self, network_slug: &str) -> Result<Option<u64>, anyhow::Error>;
+		async fn get_cold_start_target(&self, network_slug: &str) -> Result<Option<u64>, anyhow::Error>;
+		async fn save_cold_start_target(&self, network_slug: &str, block: Option<u64>) -> Result<(), anyhow::Error>;
 		async fn save_blocks(&self, network_slug: &str, blocks: &[BlockType]) -> Result<(), anyhow::Error>;
 		async fn delete_blocks(&self, network_slug: &str) -> Result<(), anyhow::Error>;
 		async fn get_missed_blocks(&self, network_id: &str, max_block_age: u64, current_block: u64, max_retries: u32) -> Result<Vec<MissedBlockEntry>, anyhow::Error>;
 		async fn update_missed_block_status(&self, network_id: &str, block_number: u64, status: MissedBlockStatus, error: Option<String>) -> Result<(), anyhow::Error>;
 		async fn remove_recovered_blocks(&self, network_id: &str, block_numbers: &[u64]) -> Result<(), anyhow::Error>;
 		async fn prune_old_missed_blocks(&self, network_id: &str, max_block_age: u64, current_block: u64) -> Result<usize, anyhow::Error>;
+		async fn save_skipped_block(&self, network_id: &str, entry: SkippedBlockEntry) -> Result<(), anyhow::Error>;
+		async fn get_skipped_blocks(&self, network_id: &str) -> Result<Vec<SkippedBlockEntry>, anyhow::Error>;
+		async fn remove_skipped_blocks(&self, network_id: &str, block_numbers: &[u64]) -> Result<(), anyhow::Error>;
 	}
 
 	impl Clone for BlockStorage {
@@ -161,6 +161,19 @@ fn create_solana_test_monitor() -> Monitor {
 		trigger_conditions: vec![],
 		triggers: vec![],
 		chain_configurations: vec![],
+		start_block: None,
+		network_addresses: Default::default(),
+		severity: Default::default(),
+		observe: false,
+		canary: None,
+		activate_on_deployment: None,
+		abi_drift: None,
+		sampling: None,
+		batch: None,
+		trigger_params: Default::default(),
+		owners: Vec::new(),
+		post_processing: None,
+		rpc_overrides: Default::default(),
 	}
 }
 
@@ -991,6 +1004,7 @@ async fn test_execute_monitor_evm_with_trigger_scripts() {
 		language: ScriptLanguage::Python,
 		timeout_ms: 10000,
 		arguments: None,
+		expression: None,
 	}];
 	mocked_monitors.insert("monitor".to_string(), test_data.monitor.clone());
 	let mock_monitor_service = setup_monitor_service(mocked_monitors);
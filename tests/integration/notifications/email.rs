@@ -6,8 +6,8 @@ use std::collections::HashMap;
 
 use openzeppelin_monitor::{
 	models::{
-		EVMMonitorMatch, MatchConditions, Monitor, MonitorMatch, NotificationMessage, SecretString,
-		SecretValue, TriggerType, TriggerTypeConfig,
+		ChainMessages, EVMMonitorMatch, MatchConditions, Monitor, MonitorMatch,
+		NotificationMessage, SecretString, SecretValue, TriggerType, TriggerTypeConfig,
 	},
 	services::notification::{
 		EmailContent, EmailNotifier, NotificationError, NotificationService, SmtpConfig,
@@ -42,7 +42,10 @@ fn create_test_evm_match(monitor: Monitor) -> MonitorMatch {
 		logs: Some(create_test_evm_logs()),
 		network_slug: "ethereum_mainnet".to_string(),
 		matched_on: MatchConditions::default(),
+		matched_on_audit: Vec::new(),
 		matched_on_args: None,
+		block_timestamp: None,
+		trace: None,
 	}))
 }
 
@@ -135,6 +138,7 @@ async fn test_notification_service_email_execution_failure() {
 		message: NotificationMessage {
 			title: "Email Test Alert".to_string(),
 			body: "Test email message with value ${value}".to_string(),
+			chains: ChainMessages::default(),
 		},
 		sender: "sender@example.com".parse().unwrap(),
 		recipients: vec!["recipient@example.com".parse().unwrap()],
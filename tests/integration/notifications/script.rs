@@ -29,7 +29,10 @@ fn create_test_evm_match(monitor: Monitor) -> MonitorMatch {
 		logs: Some(create_test_evm_logs()),
 		network_slug: "ethereum_mainnet".to_string(),
 		matched_on: MatchConditions::default(),
+		matched_on_audit: Vec::new(),
 		matched_on_args: None,
+		block_timestamp: None,
+		trace: None,
 	}))
 }
 
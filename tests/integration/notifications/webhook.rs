@@ -39,7 +39,10 @@ fn create_test_evm_match(monitor: Monitor) -> MonitorMatch {
 		logs: Some(create_test_evm_logs()),
 		network_slug: "ethereum_mainnet".to_string(),
 		matched_on: MatchConditions::default(),
+		matched_on_audit: Vec::new(),
 		matched_on_args: None,
+		block_timestamp: None,
+		trace: None,
 	}))
 }
 fn create_test_payload() -> serde_json::Value {
@@ -316,6 +319,44 @@ async fn test_notification_service_webhook_raw_payload_mode() {
 	mock.assert();
 }
 
+#[tokio::test]
+async fn test_notification_service_webhook_normalized_payload_mode() {
+	let notification_service = NotificationService::new();
+	let mut server = Server::new_async().await;
+
+	// The normalized match has a chain-agnostic envelope
+	let mock = server
+		.mock("POST", "/")
+		.with_status(200)
+		.with_header("content-type", "application/json")
+		.match_body(Matcher::PartialJson(serde_json::json!({
+			"schema_version": 2,
+			"chain": "evm",
+			"monitor": {
+				"name": "test_monitor",
+				"severity": "medium"
+			}
+		})))
+		.create_async()
+		.await;
+
+	let trigger = TriggerBuilder::new()
+		.name("test_normalized_webhook")
+		.webhook(&server.url())
+		.webhook_payload_mode(WebhookPayloadMode::Normalized)
+		.message("", "")
+		.build();
+
+	let monitor_match = create_test_evm_match(create_test_monitor("test_monitor"));
+
+	let result = notification_service
+		.execute(&trigger, &HashMap::new(), &monitor_match, &HashMap::new())
+		.await;
+
+	assert!(result.is_ok());
+	mock.assert();
+}
+
 #[tokio::test]
 async fn test_notification_service_webhook_template_mode_default() {
 	let notification_service = NotificationService::new();
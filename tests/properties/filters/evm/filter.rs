@@ -16,7 +16,7 @@ use openzeppelin_monitor::{
 			evm_helpers::{
 				are_same_address, are_same_signature, normalize_address, normalize_signature,
 			},
-			EVMBlockFilter,
+			EVMBlockFilter, FilterContext,
 		},
 	},
 	utils::tests::evm::{monitor::MonitorBuilder, receipt::ReceiptBuilder},
@@ -445,6 +445,7 @@ proptest! {
 
 		let filter = EVMBlockFilter::<EvmClient<EVMTransportClient>> {
 			_client: PhantomData,
+			context: FilterContext::default(),
 		};
 		let result = filter.evaluate_expression(&expr, &params).unwrap();
 
@@ -480,6 +481,7 @@ proptest! {
 
 		let filter = EVMBlockFilter::<EvmClient<EVMTransportClient>> {
 			_client: PhantomData,
+			context: FilterContext::default(),
 		};
 		let result = filter.evaluate_expression(&expr, &params).unwrap();
 
@@ -522,6 +524,7 @@ proptest! {
 
 		let filter = EVMBlockFilter::<EvmClient<EVMTransportClient>> {
 			_client: PhantomData,
+			context: FilterContext::default(),
 		};
 
 		let result = filter.evaluate_expression(&expr, &params).unwrap();
@@ -563,6 +566,7 @@ proptest! {
 
 		let filter = EVMBlockFilter::<EvmClient<EVMTransportClient>> {
 			_client: PhantomData,
+			context: FilterContext::default(),
 		};
 		let result = filter.evaluate_expression(&expr, &params).unwrap();
 
@@ -602,6 +606,7 @@ proptest! {
 
 		let filter = EVMBlockFilter::<EvmClient<EVMTransportClient>> {
 			_client: PhantomData,
+			context: FilterContext::default(),
 		};
 		let result = filter.evaluate_expression(&expr, &params).unwrap();
 
@@ -660,6 +665,7 @@ proptest! {
 
 		let filter = EVMBlockFilter::<EvmClient<EVMTransportClient>> {
 			_client: PhantomData,
+			context: FilterContext::default(),
 		};
 		let result = filter.evaluate_expression(&expr, &params).unwrap();
 		let lhs_as_u128 = lhs_value_str.parse::<u128>().unwrap_or_default();
@@ -701,6 +707,7 @@ proptest! {
 
 			let filter = EVMBlockFilter::<EvmClient<EVMTransportClient>> {
 					_client: PhantomData,
+					context: FilterContext::default(),
 			};
 			let result = filter.evaluate_expression(&expr, &params).unwrap();
 
@@ -732,6 +739,7 @@ proptest! {
 
 			let filter = EVMBlockFilter::<EvmClient<EVMTransportClient>> {
 					_client: PhantomData,
+					context: FilterContext::default(),
 			};
 			let result = filter.evaluate_expression(&expr, &params).unwrap();
 			// Normalize the target for comparison
@@ -780,6 +788,7 @@ proptest! {
 
 			let filter = EVMBlockFilter::<EvmClient<EVMTransportClient>> {
 					_client: PhantomData,
+					context: FilterContext::default(),
 			};
 			let result = filter.evaluate_expression(&expr, &params).unwrap();
 
@@ -817,6 +826,7 @@ proptest! {
 
 		let filter = EVMBlockFilter::<EvmClient<EVMTransportClient>> {
 			_client: PhantomData,
+			context: FilterContext::default(),
 		};
 		let result = filter.evaluate_expression(&expr, &params).unwrap();
 
@@ -857,6 +867,7 @@ proptest! {
 
 		let filter = EVMBlockFilter::<EvmClient<EVMTransportClient>> {
 			_client: PhantomData,
+			context: FilterContext::default(),
 		};
 		let result = filter.evaluate_expression(&expr, &params).unwrap();
 
@@ -884,6 +895,7 @@ proptest! {
 
 		let filter = EVMBlockFilter::<EvmClient<EVMTransportClient>> {
 			_client: PhantomData,
+			context: FilterContext::default(),
 		};
 		let result = filter.evaluate_expression(&expr, &params).unwrap();
 
@@ -936,6 +948,7 @@ proptest! {
 
 		let filter = EVMBlockFilter::<EvmClient<EVMTransportClient>> {
 			_client: PhantomData,
+			context: FilterContext::default(),
 		};
 		let result = filter.evaluate_expression(&expr, &params).unwrap();
 
@@ -973,6 +986,7 @@ proptest! {
 
 		let filter = EVMBlockFilter::<EvmClient<EVMTransportClient>> {
 			_client: PhantomData,
+			context: FilterContext::default(),
 		};
 
 		// Test various invalid expression scenarios
@@ -1001,6 +1015,7 @@ proptest! {
 	) {
 		let filter = EVMBlockFilter::<EvmClient<EVMTransportClient>> {
 			_client: PhantomData,
+			context: FilterContext::default(),
 		};
 
 		// Test transaction matching across different status types
@@ -1047,6 +1062,7 @@ proptest! {
 	) {
 		let filter = EVMBlockFilter::<EvmClient<EVMTransportClient>> {
 			_client: PhantomData,
+			context: FilterContext::default(),
 		};
 		let mut matched_transactions = Vec::new();
 
@@ -1077,6 +1093,7 @@ proptest! {
 	) {
 		let filter = EVMBlockFilter::<EvmClient<EVMTransportClient>> {
 			_client: PhantomData,
+			context: FilterContext::default(),
 		};
 		let mut matched_functions = Vec::new();
 		let mut matched_args = EVMMatchArguments {
@@ -1144,6 +1161,7 @@ proptest! {
 	) {
 		let filter = EVMBlockFilter::<EvmClient<EVMTransportClient>> {
 			_client: PhantomData,
+			context: FilterContext::default(),
 		};
 		let mut matched_events = Vec::new();
 		let mut matched_args = EVMMatchArguments {
@@ -1185,6 +1203,7 @@ proptest! {
 	) {
 		let filter = EVMBlockFilter::<EvmClient<EVMTransportClient>> {
 			_client: PhantomData,
+			context: FilterContext::default(),
 		};
 
 		// Decode the event
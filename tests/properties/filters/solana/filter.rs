@@ -49,6 +49,19 @@ fn create_solana_monitor(
 			solana: Some(SolanaMonitorConfig::default()),
 			..Default::default()
 		}],
+		start_block: None,
+		network_addresses: Default::default(),
+		severity: Default::default(),
+		observe: false,
+		canary: None,
+		activate_on_deployment: None,
+		abi_drift: None,
+		sampling: None,
+		batch: None,
+		trigger_params: Default::default(),
+		owners: Vec::new(),
+		post_processing: None,
+		rpc_overrides: Default::default(),
 	}
 }
 
@@ -2,7 +2,7 @@ use crate::properties::strategies::trigger_strategy;
 
 use openzeppelin_monitor::{
 	models::{
-		ConfigLoader, NotificationMessage, SecretString, SecretValue, TriggerType,
+		ChainMessages, ConfigLoader, NotificationMessage, SecretString, SecretValue, TriggerType,
 		TriggerTypeConfig, WebhookPayloadMode,
 	},
 	repositories::{TriggerRepository, TriggerRepositoryTrait},
@@ -93,7 +93,7 @@ proptest! {
 			// Test invalid cases
 			match &trigger.trigger_type {
 				TriggerType::Slack => {
-					if let TriggerTypeConfig::Slack { slack_url: _, message: _, retry_policy: _ } = &trigger.config {
+					if let TriggerTypeConfig::Slack { slack_url: _, message: _, retry_policy: _, http_client: _ } = &trigger.config {
 						invalid_trigger = trigger.clone();
 						if let TriggerTypeConfig::Slack { slack_url, .. } = &mut invalid_trigger.config {
 							*slack_url = SecretValue::Plain(SecretString::new("not-a-url".to_string())); // Invalid URL format
@@ -107,6 +107,7 @@ proptest! {
 							*m = NotificationMessage {
 								title: "".to_string(),
 								body: "test".to_string(),
+								chains: ChainMessages::default(),
 							};
 						}
 						prop_assert!(invalid_trigger.validate().is_err());
@@ -117,6 +118,7 @@ proptest! {
 							*m = NotificationMessage {
 								title: "Alert".to_string(),
 								body: "".to_string(),
+								chains: ChainMessages::default(),
 							};
 						}
 						prop_assert!(invalid_trigger.validate().is_err());
@@ -144,13 +146,14 @@ proptest! {
 							*m = NotificationMessage {
 								title: "   ".to_string(),
 								body: "".to_string(),
+								chains: ChainMessages::default(),
 							};
 						}
 						prop_assert!(invalid_trigger.validate().is_err());
 					}
 				}
 				TriggerType::Webhook => {
-					if let TriggerTypeConfig::Webhook { url: _, method: _, headers: _, secret: _, message: _, payload_mode: _, retry_policy: _ } = &trigger.config {
+					if let TriggerTypeConfig::Webhook { url: _, method: _, headers: _, secret: _, message: _, payload_mode: _, retry_policy: _, http_client: _ } = &trigger.config {
 						// Test invalid method
 						invalid_trigger = trigger.clone();
 						if let TriggerTypeConfig::Webhook { method: m, .. } = &mut invalid_trigger.config {
@@ -172,6 +175,7 @@ proptest! {
 							*m = NotificationMessage {
 								title: "".to_string(),
 								body: "test".to_string(),
+								chains: ChainMessages::default(),
 							};
 						}
 						prop_assert!(invalid_trigger.validate().is_err());
@@ -183,6 +187,7 @@ proptest! {
 							*m = NotificationMessage {
 								title: "Alert".to_string(),
 								body: "".to_string(),
+								chains: ChainMessages::default(),
 							};
 						}
 						prop_assert!(invalid_trigger.validate().is_err());
@@ -194,13 +199,14 @@ proptest! {
 							*m = NotificationMessage {
 								title: "".to_string(),
 								body: "".to_string(),
+								chains: ChainMessages::default(),
 							};
 						}
 						prop_assert!(invalid_trigger.validate().is_ok());
 					}
 				}
 				TriggerType::Discord => {
-					if let TriggerTypeConfig::Discord { discord_url: _, message: _, retry_policy: _ } = &trigger.config {
+					if let TriggerTypeConfig::Discord { discord_url: _, message: _, retry_policy: _, http_client: _ } = &trigger.config {
 						// Test invalid URL
 						invalid_trigger = trigger.clone();
 						if let TriggerTypeConfig::Discord { discord_url: u, .. } = &mut invalid_trigger.config {
@@ -214,6 +220,7 @@ proptest! {
 							*m = NotificationMessage {
 								title: "".to_string(),
 								body: "test".to_string(),
+								chains: ChainMessages::default(),
 							};
 						}
 						prop_assert!(invalid_trigger.validate().is_err());
@@ -224,13 +231,14 @@ proptest! {
 							*m = NotificationMessage {
 								title: "Alert".to_string(),
 								body: "".to_string(),
+								chains: ChainMessages::default(),
 							};
 						}
 						prop_assert!(invalid_trigger.validate().is_err());
 					}
 				}
 				TriggerType::Telegram => {
-					if let TriggerTypeConfig::Telegram { token: _, chat_id: _, disable_web_preview: _, message: _, retry_policy: _ } = &trigger.config {
+					if let TriggerTypeConfig::Telegram { token: _, chat_id: _, disable_web_preview: _, message: _, retry_policy: _, http_client: _ } = &trigger.config {
 						// Test invalid token
 						invalid_trigger = trigger.clone();
 						if let TriggerTypeConfig::Telegram { token: t, .. } = &mut invalid_trigger.config {
@@ -251,6 +259,7 @@ proptest! {
 							*m = NotificationMessage {
 								title: "".to_string(),
 								body: "test".to_string(),
+								chains: ChainMessages::default(),
 							};
 						}
 						prop_assert!(invalid_trigger.validate().is_err());
@@ -261,6 +270,7 @@ proptest! {
 							*m = NotificationMessage {
 								title: "Alert".to_string(),
 								body: "".to_string(),
+								chains: ChainMessages::default(),
 							};
 						}
 						prop_assert!(invalid_trigger.validate().is_err());
@@ -1,14 +1,14 @@
 use email_address::EmailAddress;
 use openzeppelin_monitor::{
 	models::{
-		AddressWithSpec, BlockChainType, EventCondition, FunctionCondition, MatchConditions,
-		MaxPastBlocks, Monitor, Network, NotificationMessage, RpcUrl, ScriptLanguage, SecretString,
-		SecretValue, TransactionCondition, TransactionStatus, Trigger, TriggerConditions,
-		TriggerType, TriggerTypeConfig, WebhookPayloadMode,
+		AddressWithSpec, BlockChainType, ChainMessages, EventCondition, FunctionCondition,
+		MatchConditions, MaxPastBlocks, Monitor, Network, NotificationMessage, RpcUrl,
+		ScriptLanguage, SecretString, SecretValue, TransactionCondition, TransactionStatus,
+		Trigger, TriggerConditions, TriggerType, TriggerTypeConfig, WebhookPayloadMode,
 	},
 	utils::{
 		tests::{evm::monitor::MonitorBuilder, network::NetworkBuilder, trigger::TriggerBuilder},
-		RetryConfig,
+		HttpClientConfig, RetryConfig,
 	},
 };
 use proptest::{option, prelude::*};
@@ -81,7 +81,11 @@ pub fn notification_message_strategy() -> impl Strategy<Value = NotificationMess
 		"[a-zA-Z0-9_]{1,50}".prop_map(|s| s.to_string()),
 		"[a-zA-Z0-9_]{1,100}".prop_map(|s| s.to_string()),
 	)
-		.prop_map(|(title, body)| NotificationMessage { title, body })
+		.prop_map(|(title, body)| NotificationMessage {
+			title,
+			body,
+			chains: ChainMessages::default(),
+		})
 }
 
 pub fn trigger_strategy() -> impl Strategy<Value = Trigger> {
@@ -98,6 +102,7 @@ pub fn trigger_strategy() -> impl Strategy<Value = Trigger> {
 					slack_url: SecretValue::Plain(SecretString::new(slack_url)),
 					message,
 					retry_policy: RetryConfig::default(),
+					http_client: HttpClientConfig::default(),
 				})
 		)
 			.prop_map(|(name, trigger_type, config)| TriggerBuilder::new()
@@ -165,6 +170,7 @@ pub fn trigger_strategy() -> impl Strategy<Value = Trigger> {
 						message,
 						payload_mode: WebhookPayloadMode::default(),
 						retry_policy: RetryConfig::default(),
+						http_client: HttpClientConfig::default(),
 					}
 				})
 		)
@@ -186,6 +192,7 @@ pub fn rpc_url_strategy() -> impl Strategy<Value = RpcUrl> {
 			type_,
 			url: SecretValue::Plain(SecretString::new(url)),
 			weight,
+			provider: None,
 		})
 }
 
@@ -317,6 +324,7 @@ pub fn trigger_conditions_strategy() -> impl Strategy<Value = Vec<TriggerConditi
 				arguments: Some(arguments.split(',').map(|s| s.to_string()).collect()),
 				language,
 				timeout_ms,
+				expression: None,
 			}]
 		})
 }